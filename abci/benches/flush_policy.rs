@@ -0,0 +1,100 @@
+//! Throughput of `CheckTx` requests under the flush policies of the ABCI
+//! server.
+//!
+//! Like CometBFT's mempool connection, the client pipelines batches of
+//! `CheckTx` requests, each followed by a `Flush` request, and reads the
+//! responses on a separate thread.
+//!
+//! Run with `cargo bench -p tendermint-abci --bench flush_policy`.
+
+use std::{
+    io::{BufReader, BufWriter, Read, Write},
+    net::TcpStream,
+    thread,
+    time::{Duration, Instant},
+};
+
+use prost::Message;
+use tendermint_abci::{Application, FlushPolicy, ServerBuilder};
+use tendermint_proto::v0_38::abci::{request, Request, RequestCheckTx, RequestFlush, Response};
+
+const BATCH_SIZE: usize = 100;
+
+#[derive(Clone)]
+struct App;
+
+impl Application for App {}
+
+fn encode(value: request::Value) -> Vec<u8> {
+    Request { value: Some(value) }.encode_length_delimited_to_vec()
+}
+
+fn read_response(reader: &mut impl Read) -> Response {
+    let mut len = 0_usize;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0_u8];
+        reader.read_exact(&mut byte).unwrap();
+        len |= usize::from(byte[0] & 0x7F) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+    }
+    let mut buf = vec![0_u8; len];
+    reader.read_exact(&mut buf).unwrap();
+    Response::decode(buf.as_slice()).unwrap()
+}
+
+// Returns the number of `CheckTx` requests handled per second.
+fn run(flush_policy: FlushPolicy, batches: usize) -> f64 {
+    let server = ServerBuilder::default()
+        .flush_policy(flush_policy)
+        .bind("127.0.0.1:0", App)
+        .unwrap();
+    let server_addr = server.local_addr();
+    let _ = thread::spawn(move || server.listen());
+    let stream = TcpStream::connect(server_addr).unwrap();
+    stream.set_nodelay(true).unwrap();
+
+    let check_tx = encode(request::Value::CheckTx(RequestCheckTx {
+        tx: vec![0x42; 250].into(),
+        r#type: 0,
+    }));
+    let flush = encode(request::Value::Flush(RequestFlush {}));
+
+    let start = Instant::now();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let reader_hdl = thread::spawn(move || {
+        for _ in 0..batches * (BATCH_SIZE + 1) {
+            read_response(&mut reader);
+        }
+    });
+    let mut writer = BufWriter::new(stream);
+    for _ in 0..batches {
+        for _ in 0..BATCH_SIZE {
+            writer.write_all(&check_tx).unwrap();
+        }
+        writer.write_all(&flush).unwrap();
+        writer.flush().unwrap();
+    }
+    reader_hdl.join().unwrap();
+
+    (batches * BATCH_SIZE) as f64 / start.elapsed().as_secs_f64()
+}
+
+fn main() {
+    // Only run a few batches when built as a test.
+    let batches = if std::env::args().any(|arg| arg == "--bench") {
+        2_000
+    } else {
+        10
+    };
+
+    for (name, flush_policy) in [
+        ("per response", FlushPolicy::PerResponse),
+        ("on flush request", FlushPolicy::OnFlushRequest),
+        ("timed (1ms)", FlushPolicy::Timed(Duration::from_millis(1))),
+    ] {
+        let throughput = run(flush_policy, batches);
+        println!("{name:<20} {throughput:>12.0} CheckTx/s");
+    }
+}
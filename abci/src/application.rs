@@ -4,6 +4,7 @@
 pub mod echo;
 #[cfg(feature = "kvstore-app")]
 pub mod kvstore;
+pub mod query;
 
 use tendermint_proto::v0_38::abci::{
     request::Value, response, response_process_proposal, response_verify_vote_extension, Request,
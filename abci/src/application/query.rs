@@ -0,0 +1,248 @@
+//! Routing of ABCI `Query` requests to handlers by path.
+
+use std::{fmt, sync::Arc};
+
+use bytes::Bytes;
+use prost::Message;
+use tendermint_proto::v0_38::{
+    abci::{RequestQuery, ResponseQuery},
+    crypto::ProofOps,
+};
+
+/// The response code returned for queries on a path no handler is registered
+/// for, or whose data cannot be decoded.
+pub const CODE_UNKNOWN_REQUEST: u32 = 1;
+
+type Handler = dyn Fn(Query<'_>) -> ResponseQuery + Send + Sync;
+
+/// Routes ABCI `Query` requests to handlers registered by path prefix.
+///
+/// A handler registered for a prefix handles the queries on the prefix
+/// itself and on any path below it, e.g. a handler for `/store` handles
+/// `/store` and `/store/bank/key`, but not `/storage`. When several prefixes
+/// match a path, the longest one wins. Leading slashes are ignored.
+///
+/// ## Example
+///
+/// ```rust
+/// use tendermint_abci::QueryRouter;
+/// use tendermint_proto::v0_38::abci::RequestQuery;
+///
+/// let router = QueryRouter::new()
+///     .route("/store", |query| query.respond(query.data().to_vec()))
+///     .route("/p2p/filter/id", |query| {
+///         query.reject(1, format!("peer {} is banned", query.subpath()))
+///     });
+///
+/// let response = router.query(RequestQuery {
+///     path: "/store/key".to_string(),
+///     data: "key".into(),
+///     ..Default::default()
+/// });
+/// assert_eq!(response.code, 0);
+/// assert_eq!(response.value, "key");
+/// ```
+#[derive(Clone, Default)]
+pub struct QueryRouter {
+    routes: Vec<(String, Arc<Handler>)>,
+}
+
+impl fmt::Debug for QueryRouter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryRouter")
+            .field(
+                "routes",
+                &self.routes.iter().map(|(p, _)| p).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl QueryRouter {
+    /// Create a router without any routes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handle the queries on the given path prefix with the given handler.
+    ///
+    /// Leading and trailing slashes of the prefix are ignored. A handler
+    /// registered earlier for the same prefix is replaced.
+    pub fn route<F>(mut self, prefix: &str, handler: F) -> Self
+    where
+        F: Fn(Query<'_>) -> ResponseQuery + Send + Sync + 'static,
+    {
+        let prefix = normalize(prefix).trim_end_matches('/').to_string();
+        self.routes.retain(|(p, _)| *p != prefix);
+        self.routes.push((prefix, Arc::new(handler)));
+        self
+    }
+
+    /// Handle the queries on the given path prefix with the given handler,
+    /// which receives the query data decoded as a Protobuf message.
+    ///
+    /// Queries whose data cannot be decoded are rejected with
+    /// [`CODE_UNKNOWN_REQUEST`] without calling the handler.
+    pub fn route_typed<T, F>(self, prefix: &str, handler: F) -> Self
+    where
+        T: Message + Default,
+        F: Fn(Query<'_>, T) -> ResponseQuery + Send + Sync + 'static,
+    {
+        self.route(prefix, move |query| match query.decode::<T>() {
+            Ok(request) => handler(query, request),
+            Err(e) => query.reject(
+                CODE_UNKNOWN_REQUEST,
+                format!("failed to decode query data: {e}"),
+            ),
+        })
+    }
+
+    /// Handle the given query with the handler registered for the longest
+    /// prefix of its path.
+    ///
+    /// Queries on paths without a handler are rejected with
+    /// [`CODE_UNKNOWN_REQUEST`].
+    pub fn query(&self, request: RequestQuery) -> ResponseQuery {
+        let path = normalize(&request.path);
+        let route = self
+            .routes
+            .iter()
+            .filter_map(|(prefix, handler)| {
+                subpath(path, prefix).map(|subpath| (prefix, subpath, handler))
+            })
+            .max_by_key(|(prefix, _, _)| prefix.len());
+
+        match route {
+            Some((prefix, subpath, handler)) => handler(Query {
+                request: &request,
+                prefix,
+                subpath,
+            }),
+            None => Query {
+                request: &request,
+                prefix: "",
+                subpath: path,
+            }
+            .reject(
+                CODE_UNKNOWN_REQUEST,
+                format!("unknown query path: {}", request.path),
+            ),
+        }
+    }
+}
+
+fn normalize(path: &str) -> &str {
+    path.trim_start_matches('/')
+}
+
+/// The part of the path below the prefix, if the path is the prefix itself or
+/// a path below it.
+fn subpath<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    if prefix.is_empty() {
+        return Some(path);
+    }
+    let rest = path.strip_prefix(prefix)?;
+    if rest.is_empty() {
+        Some(rest)
+    } else {
+        rest.strip_prefix('/')
+    }
+}
+
+/// A query being handled by a [`QueryRouter`].
+#[derive(Clone, Copy, Debug)]
+pub struct Query<'a> {
+    request: &'a RequestQuery,
+    prefix: &'a str,
+    subpath: &'a str,
+}
+
+impl<'a> Query<'a> {
+    /// The original request.
+    pub fn request(&self) -> &'a RequestQuery {
+        self.request
+    }
+
+    /// The full path of the query.
+    pub fn path(&self) -> &'a str {
+        &self.request.path
+    }
+
+    /// The prefix of the route handling the query, without leading slashes.
+    pub fn prefix(&self) -> &'a str {
+        self.prefix
+    }
+
+    /// The part of the path below the prefix of the route handling the
+    /// query, e.g. `bank/key` for a query on `/store/bank/key` handled by a
+    /// route for `/store`.
+    pub fn subpath(&self) -> &'a str {
+        self.subpath
+    }
+
+    /// The non-empty segments of the part of the path below the prefix.
+    pub fn segments(&self) -> impl Iterator<Item = &'a str> {
+        self.subpath.split('/').filter(|s| !s.is_empty())
+    }
+
+    /// The raw query data.
+    pub fn data(&self) -> &'a [u8] {
+        &self.request.data
+    }
+
+    /// Decode the query data as a Protobuf message.
+    pub fn decode<T>(&self) -> Result<T, prost::DecodeError>
+    where
+        T: Message + Default,
+    {
+        T::decode(self.request.data.clone())
+    }
+
+    /// The height at which the query is to be performed, where 0 means the
+    /// latest height.
+    pub fn height(&self) -> i64 {
+        self.request.height
+    }
+
+    /// Whether a proof of the result was requested.
+    pub fn prove(&self) -> bool {
+        self.request.prove
+    }
+
+    /// A successful response carrying the given value, for the key given by
+    /// the query data at the height of the query.
+    pub fn respond(&self, value: impl Into<Bytes>) -> ResponseQuery {
+        ResponseQuery {
+            key: self.request.data.clone(),
+            value: value.into(),
+            height: self.request.height,
+            ..Default::default()
+        }
+    }
+
+    /// Like [`Query::respond`], but with a proof of the value attached if one
+    /// was requested.
+    ///
+    /// The proof is only computed when it is needed.
+    pub fn respond_with_proof<F>(&self, value: impl Into<Bytes>, proof: F) -> ResponseQuery
+    where
+        F: FnOnce() -> ProofOps,
+    {
+        let mut response = self.respond(value);
+        if self.prove() {
+            response.proof_ops = Some(proof());
+        }
+        response
+    }
+
+    /// A response rejecting the query with the given code and log message.
+    pub fn reject(&self, code: u32, log: impl Into<String>) -> ResponseQuery {
+        ResponseQuery {
+            code,
+            log: log.into(),
+            key: self.request.data.clone(),
+            height: self.request.height,
+            ..Default::default()
+        }
+    }
+}
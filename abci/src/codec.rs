@@ -35,6 +35,8 @@ pub struct Codec<S, I, O> {
     // Fixed-length read window
     read_window: Vec<u8>,
     write_buf: BytesMut,
+    // Number of buffered bytes from which the write buffer is written out
+    write_buf_size: usize,
     _incoming: PhantomData<I>,
     _outgoing: PhantomData<O>,
 }
@@ -52,10 +54,24 @@ where
             read_buf: BytesMut::new(),
             read_window: vec![0_u8; read_buf_size],
             write_buf: BytesMut::new(),
+            write_buf_size: 0,
             _incoming: Default::default(),
             _outgoing: Default::default(),
         }
     }
+
+    /// The underlying stream.
+    pub fn stream(&self) -> &S {
+        &self.stream
+    }
+
+    /// Buffer up to the given number of bytes of outgoing messages sent with
+    /// [`Codec::buffer`] before writing them out to the stream.
+    pub fn with_write_buf_size(mut self, write_buf_size: usize) -> Self {
+        self.write_buf_size = write_buf_size;
+        self.write_buf.reserve(write_buf_size);
+        self
+    }
 }
 
 // Iterating over a codec produces instances of `Result<I>`.
@@ -99,6 +115,26 @@ where
     /// Send a message using this codec.
     pub fn send(&mut self, message: O) -> Result<(), Error> {
         encode_length_delimited(message, &mut self.write_buf)?;
+        self.flush()
+    }
+
+    /// Buffer a message, only writing out the buffered messages once they
+    /// reach the size of the write buffer.
+    pub fn buffer(&mut self, message: O) -> Result<(), Error> {
+        encode_length_delimited(message, &mut self.write_buf)?;
+        if self.write_buf.len() >= self.write_buf_size {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Whether messages are buffered, waiting to be written out.
+    pub fn has_buffered(&self) -> bool {
+        !self.write_buf.is_empty()
+    }
+
+    /// Write out the buffered messages and flush the stream.
+    pub fn flush(&mut self) -> Result<(), Error> {
         while !self.write_buf.is_empty() {
             let bytes_written = self
                 .stream
@@ -39,6 +39,15 @@ define_error! {
         ChannelRecv
             [ DisplayError<std::sync::mpsc::RecvError> ]
             | _ | { "channel recv error" },
+
+        AlreadyStarted
+            | _ | { "server already started" },
+
+        ShutdownTimeout
+            { timeout: std::time::Duration }
+            | e | {
+                format_args!("server did not shut down within {:?}", e.timeout)
+            },
     }
 }
 
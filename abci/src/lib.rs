@@ -15,8 +15,11 @@ mod server;
 pub use application::echo::EchoApp;
 #[cfg(feature = "kvstore-app")]
 pub use application::kvstore::{KeyValueStoreApp, KeyValueStoreDriver};
-pub use application::Application;
+pub use application::{
+    query::{Query, QueryRouter, CODE_UNKNOWN_REQUEST},
+    Application,
+};
 #[cfg(feature = "client")]
 pub use client::{Client, ClientBuilder};
 pub use error::Error;
-pub use server::{Server, ServerBuilder};
+pub use server::{FlushPolicy, Server, ServerBuilder};
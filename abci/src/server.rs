@@ -1,21 +1,71 @@
 //! ABCI application server interface.
 
+#[cfg(feature = "service")]
+use std::sync::{Arc, Mutex};
 use std::{
+    io::ErrorKind,
     net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::atomic::{AtomicBool, Ordering},
     thread,
+    time::{Duration, Instant},
 };
 
+#[cfg(feature = "service")]
+use tendermint::service::{Health, Service};
+
+use tendermint_proto::v0_38::abci::request::Value;
 use tracing::{error, info};
 
-use crate::{application::RequestDispatcher, codec::ServerCodec, error::Error, Application};
+use crate::{
+    application::RequestDispatcher,
+    codec::ServerCodec,
+    error::{Error, ErrorDetail},
+    Application,
+};
 
 /// The size of the read buffer for each incoming connection to the ABCI
 /// server (1MB).
 pub const DEFAULT_SERVER_READ_BUF_SIZE: usize = 1024 * 1024;
 
+/// The size of the write buffer for each incoming connection to the ABCI
+/// server (64KB), when responses are not flushed one by one.
+pub const DEFAULT_SERVER_WRITE_BUF_SIZE: usize = 64 * 1024;
+
+/// When the responses of an ABCI server are flushed to its connections.
+///
+/// Whatever the policy, buffered responses are written out once they fill
+/// the write buffer, and flushed along with the response to a `Flush`
+/// request.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// Flush every response as soon as it is produced.
+    ///
+    /// This is the default, which suits clients waiting for the response to
+    /// every request before sending the next one, such as the client of this
+    /// crate.
+    #[default]
+    PerResponse,
+
+    /// Only flush the responses along with the response to a `Flush`
+    /// request.
+    ///
+    /// This is what the Go implementation does, and suits CometBFT, which
+    /// pipelines requests and sends `Flush` requests to receive their
+    /// responses. Clients sending no `Flush` requests will not receive their
+    /// responses until the write buffer is full.
+    OnFlushRequest,
+
+    /// Flush the responses once the given time has elapsed since the
+    /// first of them was buffered, or along with the response to a `Flush`
+    /// request, whichever comes first.
+    Timed(Duration),
+}
+
 /// Allows us to configure and construct an ABCI server.
 pub struct ServerBuilder {
     read_buf_size: usize,
+    write_buf_size: usize,
+    flush_policy: FlushPolicy,
 }
 
 impl ServerBuilder {
@@ -25,7 +75,29 @@ impl ServerBuilder {
     /// incoming data from the client. This needs to be tuned for your
     /// application.
     pub fn new(read_buf_size: usize) -> Self {
-        Self { read_buf_size }
+        Self {
+            read_buf_size,
+            write_buf_size: DEFAULT_SERVER_WRITE_BUF_SIZE,
+            flush_policy: FlushPolicy::default(),
+        }
+    }
+
+    /// Set the size of the buffer holding the responses to write to each
+    /// connection, which is only used if the flush policy is not
+    /// [`FlushPolicy::PerResponse`].
+    pub fn write_buf_size(mut self, write_buf_size: usize) -> Self {
+        self.write_buf_size = write_buf_size;
+        self
+    }
+
+    /// Set when responses are flushed to connections.
+    ///
+    /// Buffering responses lets applications handling many requests, such as
+    /// `CheckTx` requests under high load, write them out with fewer system
+    /// calls.
+    pub fn flush_policy(mut self, flush_policy: FlushPolicy) -> Self {
+        self.flush_policy = flush_policy;
+        self
     }
 
     /// Constructor for an ABCI server.
@@ -46,15 +118,17 @@ impl ServerBuilder {
             listener,
             local_addr,
             read_buf_size: self.read_buf_size,
+            write_buf_size: self.write_buf_size,
+            flush_policy: self.flush_policy,
+            #[cfg(feature = "service")]
+            service: ServiceState::default(),
         })
     }
 }
 
 impl Default for ServerBuilder {
     fn default() -> Self {
-        Self {
-            read_buf_size: DEFAULT_SERVER_READ_BUF_SIZE,
-        }
+        Self::new(DEFAULT_SERVER_READ_BUF_SIZE)
     }
 }
 
@@ -64,18 +138,35 @@ impl Default for ServerBuilder {
 /// application is cloned for access in each thread. It is up to the
 /// application developer to manage shared state across these different
 /// threads.
+///
+/// With the `service` feature, the server can also listen in the background
+/// as a [`Service`]. Shutting it down stops accepting connections, while the
+/// connections already accepted are served until their clients close them.
 pub struct Server<App> {
     app: App,
     listener: TcpListener,
     local_addr: String,
     read_buf_size: usize,
+    write_buf_size: usize,
+    flush_policy: FlushPolicy,
+    #[cfg(feature = "service")]
+    service: ServiceState,
 }
 
 impl<App: Application> Server<App> {
     /// Initiate a blocking listener for incoming connections.
     pub fn listen(self) -> Result<(), Error> {
+        self.accept_until(&AtomicBool::new(false))
+    }
+
+    // Accepts incoming connections until the given flag is set, which is
+    // checked on every incoming connection.
+    fn accept_until(&self, stopping: &AtomicBool) -> Result<(), Error> {
         loop {
             let (stream, addr) = self.listener.accept().map_err(Error::io)?;
+            if stopping.load(Ordering::SeqCst) {
+                return Ok(());
+            }
             let addr = addr.to_string();
             info!("Incoming connection from: {}", addr);
             self.spawn_client_handler(stream, addr);
@@ -89,17 +180,52 @@ impl<App: Application> Server<App> {
 
     fn spawn_client_handler(&self, stream: TcpStream, addr: String) {
         let app = self.app.clone();
-        let read_buf_size = self.read_buf_size;
-        let _ = thread::spawn(move || Self::handle_client(stream, addr, app, read_buf_size));
+        let codec = ServerCodec::new(stream, self.read_buf_size);
+        let codec = match self.flush_policy {
+            FlushPolicy::PerResponse => codec,
+            _ => codec.with_write_buf_size(self.write_buf_size),
+        };
+        let flush_policy = self.flush_policy;
+        let _ = thread::spawn(move || Self::handle_client(codec, addr, app, flush_policy));
     }
 
-    fn handle_client(stream: TcpStream, addr: String, app: App, read_buf_size: usize) {
-        let mut codec = ServerCodec::new(stream, read_buf_size);
+    fn handle_client(
+        mut codec: ServerCodec<TcpStream>,
+        addr: String,
+        app: App,
+        flush_policy: FlushPolicy,
+    ) {
         info!("Listening for incoming requests from {}", addr);
+        // When the first of the responses currently buffered was buffered.
+        let mut buffered_since: Option<Instant> = None;
         loop {
+            if let FlushPolicy::Timed(interval) = flush_policy {
+                // Wait for the next request no longer than until the
+                // buffered responses are due.
+                let timeout = match buffered_since {
+                    Some(since) => match interval.checked_sub(since.elapsed()) {
+                        Some(timeout) if !timeout.is_zero() => Some(timeout),
+                        _ => {
+                            if let Err(e) = codec.flush() {
+                                error!("Failed sending responses to client {}: {:?}", addr, e);
+                                return;
+                            }
+                            buffered_since = None;
+                            None
+                        },
+                    },
+                    None => None,
+                };
+                if let Err(e) = codec.stream().set_read_timeout(timeout) {
+                    error!("Failed to set read timeout for client {}: {:?}", addr, e);
+                    return;
+                }
+            }
+
             let request = match codec.next() {
                 Some(result) => match result {
                     Ok(r) => r,
+                    Err(e) if is_timeout(&e) => continue,
                     Err(e) => {
                         error!(
                             "Failed to read incoming request from client {}: {:?}",
@@ -113,11 +239,112 @@ impl<App: Application> Server<App> {
                     return;
                 },
             };
+            let is_flush = matches!(request.value, Some(Value::Flush(_)));
             let response = app.handle(request);
-            if let Err(e) = codec.send(response) {
+            let result = match flush_policy {
+                FlushPolicy::PerResponse => codec.send(response),
+                _ if is_flush => codec.send(response),
+                _ => codec.buffer(response),
+            };
+            if let Err(e) = result {
                 error!("Failed sending response to client {}: {:?}", addr, e);
                 return;
             }
+            buffered_since = if codec.has_buffered() {
+                buffered_since.or_else(|| Some(Instant::now()))
+            } else {
+                None
+            };
+        }
+    }
+}
+
+/// How often the end of the listener thread is checked while shutting down
+/// the server.
+#[cfg(feature = "service")]
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// The state of the listener thread of a server started as a service.
+#[cfg(feature = "service")]
+#[derive(Default)]
+struct ServiceState {
+    stopping: Arc<AtomicBool>,
+    // The error the listener thread stopped with, if any.
+    failure: Arc<Mutex<Option<String>>>,
+    thread: Option<thread::JoinHandle<()>>,
+    stopped: bool,
+}
+
+#[cfg(feature = "service")]
+impl<App: Application> Service for Server<App> {
+    type Error = Error;
+
+    fn start(&mut self) -> Result<(), Error> {
+        if self.service.thread.is_some() || self.service.stopped {
+            return Err(Error::already_started());
+        }
+        let server = Server {
+            app: self.app.clone(),
+            listener: self.listener.try_clone().map_err(Error::io)?,
+            local_addr: self.local_addr.clone(),
+            read_buf_size: self.read_buf_size,
+            write_buf_size: self.write_buf_size,
+            flush_policy: self.flush_policy,
+            service: ServiceState::default(),
+        };
+        let stopping = self.service.stopping.clone();
+        let failure = self.service.failure.clone();
+        self.service.thread = Some(thread::spawn(move || {
+            if let Err(e) = server.accept_until(&stopping) {
+                error!("ABCI server stopped listening: {}", e);
+                *failure.lock().unwrap() = Some(e.to_string());
+            }
+        }));
+        Ok(())
+    }
+
+    fn health(&self) -> Health {
+        if let Some(failure) = self.service.failure.lock().unwrap().clone() {
+            return Health::Failed(failure);
+        }
+        match &self.service.thread {
+            Some(thread) if !thread.is_finished() => Health::Running,
+            Some(_) => Health::Stopped,
+            None if self.service.stopped => Health::Stopped,
+            None => Health::NotStarted,
         }
     }
+
+    fn shutdown(&mut self, deadline: Duration) -> Result<(), Error> {
+        let thread = match &self.service.thread {
+            Some(thread) => thread,
+            None => return Ok(()),
+        };
+        self.service.stopping.store(true, Ordering::SeqCst);
+        // Wake the listener thread up from waiting for a connection.
+        let _ = TcpStream::connect(&self.local_addr);
+
+        let started = Instant::now();
+        while !thread.is_finished() {
+            let elapsed = started.elapsed();
+            if elapsed >= deadline {
+                return Err(Error::shutdown_timeout(deadline));
+            }
+            thread::sleep(SHUTDOWN_POLL_INTERVAL.min(deadline - elapsed));
+        }
+        if let Some(thread) = self.service.thread.take() {
+            let _ = thread.join();
+        }
+        self.service.stopped = true;
+        Ok(())
+    }
+}
+
+fn is_timeout(e: &Error) -> bool {
+    match e.detail() {
+        ErrorDetail::Io(e) => {
+            matches!(e.source.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+        },
+        _ => false,
+    }
 }
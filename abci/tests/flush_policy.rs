@@ -0,0 +1,134 @@
+//! Tests for the flush policies of the ABCI server.
+
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::TcpStream,
+    time::{Duration, Instant},
+};
+
+use prost::Message;
+use tendermint_abci::{Application, FlushPolicy, ServerBuilder};
+use tendermint_proto::v0_38::abci::{
+    request, response, Request, RequestEcho, RequestFlush, Response,
+};
+
+#[derive(Clone)]
+struct App;
+
+impl Application for App {}
+
+fn connect(flush_policy: FlushPolicy) -> TcpStream {
+    let server = ServerBuilder::default()
+        .flush_policy(flush_policy)
+        .bind("127.0.0.1:0", App)
+        .unwrap();
+    let server_addr = server.local_addr();
+    let _ = std::thread::spawn(move || server.listen());
+    TcpStream::connect(server_addr).unwrap()
+}
+
+fn echo(message: &str) -> Vec<u8> {
+    let request = Request {
+        value: Some(request::Value::Echo(RequestEcho {
+            message: message.to_string(),
+        })),
+    };
+    request.encode_length_delimited_to_vec()
+}
+
+fn flush() -> Vec<u8> {
+    let request = Request {
+        value: Some(request::Value::Flush(RequestFlush {})),
+    };
+    request.encode_length_delimited_to_vec()
+}
+
+// Read the given number of responses.
+fn read_responses(stream: &mut TcpStream, count: usize) -> Vec<response::Value> {
+    let mut buf = Vec::new();
+    let mut responses = Vec::new();
+    while responses.len() < count {
+        let mut chunk = [0_u8; 1024];
+        let read = stream.read(&mut chunk).unwrap();
+        assert_ne!(read, 0, "connection closed");
+        buf.extend_from_slice(&chunk[..read]);
+        while let Ok(response) = Response::decode_length_delimited(buf.as_slice()) {
+            let len = response.encoded_len();
+            buf.drain(..prost::length_delimiter_len(len) + len);
+            responses.push(response.value.unwrap());
+        }
+    }
+    responses
+}
+
+fn assert_nothing_to_read(stream: &mut TcpStream) {
+    stream
+        .set_read_timeout(Some(Duration::from_millis(200)))
+        .unwrap();
+    let err = stream.read(&mut [0_u8; 1024]).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        ErrorKind::WouldBlock | ErrorKind::TimedOut
+    ));
+    stream.set_read_timeout(None).unwrap();
+}
+
+#[test]
+fn responses_are_flushed_one_by_one_by_default() {
+    let mut stream = connect(FlushPolicy::default());
+
+    stream.write_all(&echo("one")).unwrap();
+    let responses = read_responses(&mut stream, 1);
+    assert!(matches!(&responses[0], response::Value::Echo(r) if r.message == "one"));
+}
+
+#[test]
+fn responses_are_flushed_on_flush_requests() {
+    let mut stream = connect(FlushPolicy::OnFlushRequest);
+
+    stream.write_all(&echo("one")).unwrap();
+    stream.write_all(&echo("two")).unwrap();
+    assert_nothing_to_read(&mut stream);
+
+    stream.write_all(&flush()).unwrap();
+    let responses = read_responses(&mut stream, 3);
+    assert!(matches!(&responses[0], response::Value::Echo(r) if r.message == "one"));
+    assert!(matches!(&responses[1], response::Value::Echo(r) if r.message == "two"));
+    assert!(matches!(responses[2], response::Value::Flush(_)));
+}
+
+#[test]
+fn responses_are_flushed_once_due() {
+    let interval = Duration::from_millis(300);
+    let mut stream = connect(FlushPolicy::Timed(interval));
+
+    let start = Instant::now();
+    stream.write_all(&echo("one")).unwrap();
+    stream.write_all(&echo("two")).unwrap();
+    let responses = read_responses(&mut stream, 2);
+    assert!(start.elapsed() >= interval);
+    assert!(matches!(&responses[1], response::Value::Echo(r) if r.message == "two"));
+
+    // Flush requests do not wait.
+    let start = Instant::now();
+    stream.write_all(&echo("three")).unwrap();
+    stream.write_all(&flush()).unwrap();
+    read_responses(&mut stream, 2);
+    assert!(start.elapsed() < interval);
+}
+
+#[test]
+fn full_write_buffers_are_written_out() {
+    let server = ServerBuilder::default()
+        .flush_policy(FlushPolicy::OnFlushRequest)
+        .write_buf_size(1)
+        .bind("127.0.0.1:0", App)
+        .unwrap();
+    let server_addr = server.local_addr();
+    let _ = std::thread::spawn(move || server.listen());
+    let mut stream = TcpStream::connect(server_addr).unwrap();
+
+    stream.write_all(&echo("one")).unwrap();
+    let responses = read_responses(&mut stream, 1);
+    assert!(matches!(&responses[0], response::Value::Echo(r) if r.message == "one"));
+}
@@ -0,0 +1,112 @@
+//! Tests for routing ABCI queries by path.
+
+use tendermint_abci::{QueryRouter, CODE_UNKNOWN_REQUEST};
+use tendermint_proto::v0_38::{
+    abci::RequestQuery,
+    crypto::{ProofOp, ProofOps},
+};
+
+fn request(path: &str, data: &[u8], prove: bool) -> RequestQuery {
+    RequestQuery {
+        path: path.to_string(),
+        data: data.to_vec().into(),
+        height: 10,
+        prove,
+    }
+}
+
+fn router() -> QueryRouter {
+    QueryRouter::new()
+        .route("/store", |query| {
+            query.respond_with_proof(format!("store:{}", query.subpath()), || ProofOps {
+                ops: vec![ProofOp {
+                    r#type: "ics23:iavl".to_string(),
+                    key: query.data().to_vec(),
+                    data: vec![],
+                }],
+            })
+        })
+        .route("/store/bank", |query| {
+            query.respond(query.segments().collect::<Vec<_>>().join(","))
+        })
+        .route_typed("/p2p/filter/id", |query, request: RequestQuery| {
+            query.respond(format!("{}:{}", query.subpath(), request.path))
+        })
+}
+
+#[test]
+fn routes_by_longest_prefix() {
+    let router = router();
+
+    let response = router.query(request("/store/acc/key", b"key", false));
+    assert_eq!(response.code, 0);
+    assert_eq!(response.value, "store:acc/key");
+    assert_eq!(response.key, "key");
+    assert_eq!(response.height, 10);
+
+    let response = router.query(request("store", b"", false));
+    assert_eq!(response.value, "store:");
+
+    let response = router.query(request("/store/bank/balances/addr", b"", false));
+    assert_eq!(response.value, "balances,addr");
+}
+
+#[test]
+fn ignores_trailing_slashes_of_prefixes() {
+    let router = QueryRouter::new()
+        .route("/custom/", |query| {
+            query.respond(format!("original:{}", query.subpath()))
+        })
+        .route("/custom", |query| {
+            query.respond(format!("replaced:{}", query.subpath()))
+        });
+
+    let response = router.query(request("/custom/foo", b"", false));
+    assert_eq!(response.code, 0);
+    assert_eq!(response.value, "replaced:foo");
+
+    let response = router.query(request("/custom", b"", false));
+    assert_eq!(response.value, "replaced:");
+}
+
+#[test]
+fn rejects_unknown_paths() {
+    let router = router();
+    for path in ["/storage", "/p2p", "", "/custom/foo"] {
+        let response = router.query(request(path, b"", false));
+        assert_eq!(response.code, CODE_UNKNOWN_REQUEST, "{path}");
+        assert!(response.log.contains("unknown query path"));
+    }
+}
+
+#[test]
+fn attaches_proofs_when_requested() {
+    let router = router();
+
+    let response = router.query(request("/store/acc", b"key", false));
+    assert!(response.proof_ops.is_none());
+
+    let response = router.query(request("/store/acc", b"key", true));
+    let proof = response.proof_ops.unwrap();
+    assert_eq!(proof.ops[0].key, b"key");
+}
+
+#[test]
+fn decodes_typed_requests() {
+    use prost::Message;
+
+    let router = router();
+    let data = RequestQuery {
+        path: "inner".to_string(),
+        ..Default::default()
+    }
+    .encode_to_vec();
+
+    let response = router.query(request("/p2p/filter/id/peer", &data, false));
+    assert_eq!(response.code, 0);
+    assert_eq!(response.value, "peer:inner");
+
+    let response = router.query(request("/p2p/filter/id/peer", b"\xff\xff", false));
+    assert_eq!(response.code, CODE_UNKNOWN_REQUEST);
+    assert!(response.log.contains("failed to decode"));
+}
@@ -0,0 +1,45 @@
+//! Integration tests for the ABCI server managed as a service.
+
+#[cfg(all(feature = "client", feature = "echo-app", feature = "service"))]
+mod service_integration {
+    use std::time::Duration;
+
+    use tendermint::service::{Health, Service};
+    use tendermint_abci::{ClientBuilder, EchoApp, ServerBuilder};
+    use tendermint_proto::v0_38::abci::RequestEcho;
+
+    #[test]
+    fn start_and_shutdown() {
+        let mut server = ServerBuilder::default()
+            .bind("127.0.0.1:0", EchoApp)
+            .unwrap();
+        let server_addr = server.local_addr();
+        assert_eq!(server.health(), Health::NotStarted);
+
+        server.start().unwrap();
+        assert!(server.health().is_running());
+        assert!(server.start().is_err());
+
+        let mut client = ClientBuilder::default().connect(&server_addr).unwrap();
+        let response = client
+            .echo(RequestEcho {
+                message: "Hello ABCI!".to_string(),
+            })
+            .unwrap();
+        assert_eq!(response.message, "Hello ABCI!");
+
+        server.shutdown(Duration::from_secs(5)).unwrap();
+        assert_eq!(server.health(), Health::Stopped);
+        // Shutting down a stopped server does nothing.
+        server.shutdown(Duration::from_secs(5)).unwrap();
+        assert!(server.start().is_err());
+
+        // The connection accepted before shutting down is still served.
+        let response = client
+            .echo(RequestEcho {
+                message: "Still there?".to_string(),
+            })
+            .unwrap();
+        assert_eq!(response.message, "Still there?");
+    }
+}
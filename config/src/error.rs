@@ -34,5 +34,33 @@ define_error! {
         Tendermint
             [ TendermintError ]
             |_| { format_args!("tendermint error") },
+
+        InvalidChecksum
+            { expected: String, actual: String }
+            | e | {
+                format_args!("invalid checksum: expected {}, got {}",
+                    e.expected, e.actual)
+            },
+
+        RecoveryRequired
+            { path: String }
+            | e | {
+                format_args!("refusing to sign until the signing state in {} is confirmed",
+                    e.path)
+            },
+
+        SignStateRegression
+            {
+                height: u64,
+                round: i32,
+                step: i8,
+                last_height: u64,
+                last_round: i32,
+                last_step: i8,
+            }
+            | e | {
+                format_args!("refusing to sign at {}/{}/{}, which conflicts with the last signed message at {}/{}/{}",
+                    e.height, e.round, e.step, e.last_height, e.last_round, e.last_step)
+            },
     }
 }
@@ -8,7 +8,7 @@ use tendermint::{node, private_key::PrivateKey, public_key::PublicKey};
 use crate::{error::Error, prelude::*};
 
 /// P2P node private keys
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct NodeKey {
     /// Private key
     pub priv_key: PrivateKey,
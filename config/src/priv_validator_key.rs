@@ -12,7 +12,7 @@ use tendermint::{
 use crate::{error::Error, prelude::*};
 
 /// Validator private key
-#[derive(Serialize, Deserialize)] // JSON custom serialization for priv_validator_key.json
+#[derive(Debug, Serialize, Deserialize)] // JSON custom serialization for priv_validator_key.json
 pub struct PrivValidatorKey {
     /// Address
     pub address: account::Id,
@@ -0,0 +1,288 @@
+//! Validator signing state
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tendermint::{
+    block,
+    crypto::{default::Sha256, Sha256 as _},
+    serializers::bytes::{base64string, hexstring},
+};
+
+use crate::{error::Error, prelude::*};
+
+/// The height, round and step of the last message signed by a validator,
+/// which it must not sign again for a conflicting message
+/// (`priv_validator_state.json`).
+///
+/// Files written by [`SignStateStore`] carry a checksum of the state, which
+/// is verified on load. Files without one, e.g. those written by Go nodes,
+/// are accepted as they are.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivValidatorState {
+    /// Height of the last signed message
+    pub height: block::Height,
+
+    /// Round of the last signed message
+    pub round: i32,
+
+    /// Step of the last signed message: 1 for a proposal, 2 for a prevote
+    /// and 3 for a precommit
+    pub step: i8,
+
+    /// Signature of the last signed message
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "base64string")]
+    pub signature: Vec<u8>,
+
+    /// Sign bytes of the last signed message
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "hexstring")]
+    pub signbytes: Vec<u8>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    checksum: Option<String>,
+}
+
+impl PrivValidatorState {
+    /// Parse `priv_validator_state.json`, verifying its checksum if it has
+    /// one
+    pub fn parse_json<T: AsRef<str>>(json_string: T) -> Result<Self, Error> {
+        let mut state =
+            serde_json::from_str::<Self>(json_string.as_ref()).map_err(Error::serde_json)?;
+
+        if let Some(expected) = state.checksum.take() {
+            let actual = state.checksum();
+            if expected != actual {
+                return Err(Error::invalid_checksum(expected, actual));
+            }
+        }
+
+        Ok(state)
+    }
+
+    /// Load `priv_validator_state.json` from a file
+    pub fn load_json_file<P>(path: &P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let json_string = fs::read_to_string(path)
+            .map_err(|e| Error::file_io(format!("{}", path.as_ref().display()), e))?;
+
+        Self::parse_json(json_string)
+    }
+
+    /// Serialize the state with its checksum
+    pub fn to_json(&self) -> String {
+        let state = Self {
+            checksum: Some(self.checksum()),
+            ..self.clone()
+        };
+        serde_json::to_string_pretty(&state).expect("the state serializes to JSON")
+    }
+
+    /// The uppercase hexadecimal SHA-256 digest of the state serialized
+    /// without a checksum.
+    fn checksum(&self) -> String {
+        let state = Self {
+            checksum: None,
+            ..self.clone()
+        };
+        let json = serde_json::to_vec(&state).expect("the state serializes to JSON");
+        Sha256::digest(json)
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect()
+    }
+
+    fn hrs(&self) -> (block::Height, i32, i8) {
+        (self.height, self.round, self.step)
+    }
+}
+
+/// Crash-safe storage of the [`PrivValidatorState`] of a validator, which
+/// refuses to sign messages conflicting with the last signed one.
+///
+/// The state is written to a temporary file which is synced and then renamed
+/// over the state file, so that the file holds either the previous or the new
+/// state after a crash.
+///
+/// If the state file exists but cannot be loaded, e.g. because it was
+/// truncated or its checksum does not match, the store enters a recovery
+/// mode in which it refuses to sign anything until an operator confirms the
+/// state to resume from with [`SignStateStore::confirm`].
+#[derive(Debug)]
+pub struct SignStateStore {
+    path: PathBuf,
+    state: PrivValidatorState,
+    corruption: Option<Error>,
+}
+
+impl SignStateStore {
+    /// Open the state file at the given path, creating it with an initial
+    /// state if it does not exist.
+    pub fn open<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_path_buf();
+        match fs::read_to_string(&path) {
+            Ok(json_string) => match PrivValidatorState::parse_json(json_string) {
+                Ok(state) => Ok(Self {
+                    path,
+                    state,
+                    corruption: None,
+                }),
+                Err(e) => Ok(Self {
+                    path,
+                    state: PrivValidatorState::default(),
+                    corruption: Some(e),
+                }),
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let store = Self {
+                    path,
+                    state: PrivValidatorState::default(),
+                    corruption: None,
+                };
+                store.persist(&store.state)?;
+                Ok(store)
+            },
+            Err(e) => Err(Error::file_io(format!("{}", path.display()), e)),
+        }
+    }
+
+    /// Path of the state file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The last signed state, unless the store is in recovery mode.
+    pub fn state(&self) -> Result<&PrivValidatorState, Error> {
+        self.ensure_not_recovering()?;
+        Ok(&self.state)
+    }
+
+    /// Why the state file could not be loaded, if the store is in recovery
+    /// mode.
+    pub fn corruption(&self) -> Option<&Error> {
+        self.corruption.as_ref()
+    }
+
+    pub fn is_recovering(&self) -> bool {
+        self.corruption.is_some()
+    }
+
+    /// Check that a message with the given sign bytes can be signed at the
+    /// given height, round and step.
+    ///
+    /// Returns the signature to reuse if the message was the last one
+    /// signed, and fails if signing it would conflict with the last signed
+    /// message.
+    pub fn check(
+        &self,
+        height: block::Height,
+        round: i32,
+        step: i8,
+        signbytes: &[u8],
+    ) -> Result<Option<&[u8]>, Error> {
+        self.ensure_not_recovering()?;
+
+        let last = &self.state;
+        let hrs = (height, round, step);
+        if hrs > last.hrs() {
+            return Ok(None);
+        }
+        if hrs == last.hrs() && signbytes == last.signbytes.as_slice() {
+            return Ok(Some(&last.signature));
+        }
+
+        Err(Error::sign_state_regression(
+            height.value(),
+            round,
+            step,
+            last.height.value(),
+            last.round,
+            last.step,
+        ))
+    }
+
+    /// Record that a message with the given sign bytes and signature was
+    /// signed at the given height, round and step.
+    ///
+    /// The signature must not be released before this succeeds.
+    pub fn record(
+        &mut self,
+        height: block::Height,
+        round: i32,
+        step: i8,
+        signbytes: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<(), Error> {
+        if self.check(height, round, step, &signbytes)?.is_some() {
+            return Ok(());
+        }
+
+        let state = PrivValidatorState {
+            height,
+            round,
+            step,
+            signature,
+            signbytes,
+            checksum: None,
+        };
+        self.persist(&state)?;
+        self.state = state;
+        Ok(())
+    }
+
+    /// Leave recovery mode, or overwrite the current state, with a state
+    /// confirmed by an operator, e.g. that of the last message the validator
+    /// is known to have signed.
+    pub fn confirm(&mut self, state: PrivValidatorState) -> Result<(), Error> {
+        self.persist(&state)?;
+        self.state = state;
+        self.corruption = None;
+        Ok(())
+    }
+
+    fn ensure_not_recovering(&self) -> Result<(), Error> {
+        match self.corruption {
+            Some(_) => Err(Error::recovery_required(format!("{}", self.path.display()))),
+            None => Ok(()),
+        }
+    }
+
+    fn persist(&self, state: &PrivValidatorState) -> Result<(), Error> {
+        write_atomically(&self.path, state.to_json().as_bytes())
+            .map_err(|e| Error::file_io(format!("{}", self.path.display()), e))
+    }
+}
+
+/// Replace the contents of the file at `path` by writing them to a
+/// temporary file next to it, which is renamed over it once synced.
+fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp_path, path)?;
+
+    // Sync the directory for the rename itself to be durable.
+    #[cfg(unix)]
+    {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        fs::File::open(dir)?.sync_all()?;
+    }
+
+    Ok(())
+}
@@ -6,7 +6,7 @@ use std::{fs, path::PathBuf, time::Duration};
 
 #[cfg(test)]
 use pretty_assertions::assert_eq;
-use tendermint::node;
+use tendermint::{node, secret::assert_redacted};
 use tendermint_config::*;
 
 /// Read a fixture file from the `support/config` directory
@@ -224,6 +224,26 @@ fn priv_validator_json_parser() {
     );
 }
 
+/// Private keys are redacted from the `Debug` output of the key files
+#[test]
+fn key_files_debug_output_is_redacted() {
+    let node_key = NodeKey::parse_json(read_fixture("node_key.json")).unwrap();
+    let priv_validator_key =
+        PrivValidatorKey::parse_json(read_fixture("priv_validator_key.json")).unwrap();
+
+    for (output, private_key) in [
+        (format!("{node_key:?}"), &node_key.priv_key),
+        (format!("{node_key:#?}"), &node_key.priv_key),
+        (
+            format!("{priv_validator_key:?}"),
+            &priv_validator_key.priv_key,
+        ),
+    ] {
+        let bytes = private_key.ed25519_signing_key().unwrap().as_bytes();
+        assert_redacted(&output, bytes);
+    }
+}
+
 /// Parse an example `config.toml` file to a `TendermintConfig` struct, then
 /// serialize it and parse again.
 #[test]
@@ -240,3 +260,89 @@ fn parsing_roundtrip() {
         written_config_toml
     );
 }
+
+/// A fresh path for a state file in the temporary directory
+fn temp_state_path(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("tendermint-config-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir.join("priv_validator_state.json")
+}
+
+/// Parse an example `priv_validator_state.json`, as written by Go nodes
+#[test]
+fn priv_validator_state_parser() {
+    let raw_state = read_fixture("priv_validator_state.json");
+    let state = PrivValidatorState::parse_json(raw_state).unwrap();
+    assert_eq!(state.height.value(), 12);
+    assert_eq!(state.round, 0);
+    assert_eq!(state.step, 3);
+    assert_eq!(state.signbytes, vec![0x73, 0x08, 0x02, 0x11, 0x02]);
+    assert_eq!(state.signature.len(), 64);
+
+    // The checksum is verified once added.
+    let parsed = PrivValidatorState::parse_json(state.to_json()).unwrap();
+    assert_eq!(parsed, state);
+    let tampered = state.to_json().replace("\"12\"", "\"11\"");
+    assert!(PrivValidatorState::parse_json(tampered).is_err());
+}
+
+/// Refuse to sign messages conflicting with the last signed one, across
+/// restarts
+#[test]
+fn sign_state_store_persists_last_signed() {
+    let path = temp_state_path("persists");
+    let height = 5_u32.into();
+
+    let mut store = SignStateStore::open(&path).unwrap();
+    assert_eq!(store.check(height, 0, 2, b"prevote").unwrap(), None);
+    store
+        .record(height, 0, 2, b"prevote".to_vec(), b"signature".to_vec())
+        .unwrap();
+    drop(store);
+
+    let store = SignStateStore::open(&path).unwrap();
+    assert_eq!(
+        store.check(height, 0, 2, b"prevote").unwrap(),
+        Some(&b"signature"[..])
+    );
+    assert!(store.check(height, 0, 2, b"other prevote").is_err());
+    assert!(store.check(height, 0, 1, b"proposal").is_err());
+    assert!(store.check(4_u32.into(), 3, 3, b"precommit").is_err());
+    assert_eq!(store.check(height, 0, 3, b"precommit").unwrap(), None);
+    assert!(!path.with_extension("json.tmp").exists());
+}
+
+/// Refuse to sign after detecting a corrupt state file, until an operator
+/// confirms the state to resume from
+#[test]
+fn sign_state_store_recovery() {
+    let path = temp_state_path("recovery");
+
+    let mut store = SignStateStore::open(&path).unwrap();
+    store
+        .record(7_u32.into(), 1, 3, b"precommit".to_vec(), b"sig".to_vec())
+        .unwrap();
+    let json = fs::read_to_string(&path).unwrap();
+    drop(store);
+
+    for corrupted in [
+        json[..json.len() / 2].to_owned(),
+        json.replace("\"7\"", "\"9\""),
+    ] {
+        fs::write(&path, corrupted).unwrap();
+
+        let mut store = SignStateStore::open(&path).unwrap();
+        assert!(store.is_recovering());
+        assert!(store.state().is_err());
+        assert!(store.check(100_u32.into(), 0, 1, b"proposal").is_err());
+
+        store
+            .confirm(PrivValidatorState::parse_json(&json).unwrap())
+            .unwrap();
+        assert!(!store.is_recovering());
+        assert_eq!(store.state().unwrap().height.value(), 7);
+        assert!(store.check(100_u32.into(), 0, 1, b"proposal").is_ok());
+    }
+}
@@ -1,36 +1,41 @@
 #![allow(unused)]
 
-use std::{convert::Infallible, str::FromStr, time::Duration};
+mod state;
+
+use std::{convert::Infallible, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 
 use clap::Parser;
 use color_eyre::{
-    eyre::{eyre, Result},
+    eyre::{eyre, Result, WrapErr},
     Report,
 };
 use futures::future::join_all;
-use tendermint::{crypto::default::Sha256, evidence::Evidence, Time};
+use tendermint::{evidence::Evidence, Time};
 use tendermint_light_client::{
     builder::LightClientBuilder,
     instance::Instance,
     light_client::Options,
-    store::memory::MemoryStore,
+    store::{memory::MemoryStore, LightStore},
     types::{Hash, Height, LightBlock, TrustThreshold},
 };
 use tendermint_light_client_detector::{
-    compare_new_header_with_witness, detect_divergence, gather_evidence_from_conflicting_headers,
-    CompareError, Error, ErrorDetail, Provider, Trace,
+    compare_new_header_with_witness, cross_check_witnesses, detect_divergence,
+    gather_evidence_from_conflicting_headers, CompareError, Disagreement, Error, ErrorDetail,
+    Provider, QuorumPolicy, Submission, Trace, Verdict,
 };
 use tendermint_rpc::{Client, HttpClient, HttpClientUrl, Url};
 use tracing::{debug, error, info, metadata::LevelFilter, warn};
 use tracing_subscriber::{util::SubscriberInitExt, EnvFilter};
 
-fn parse_trust_threshold(s: &str) -> Result<TrustThreshold> {
-    if let Some((l, r)) = s.split_once('/') {
-        TrustThreshold::new(l.parse()?, r.parse()?).map_err(Into::into)
-    } else {
-        Err(eyre!(
-            "invalid trust threshold: {s}, format must be X/Y where X and Y are integers"
-        ))
+use crate::state::{resolve_peers, resolve_trusted_state, Peers, StateDir, TrustedState};
+
+fn parse_quorum(s: &str) -> Result<QuorumPolicy> {
+    match s {
+        "all" => Ok(QuorumPolicy::All),
+        "majority" => Ok(QuorumPolicy::Majority),
+        _ => s.parse().map(QuorumPolicy::AtLeast).map_err(|_| {
+            eyre!("invalid quorum: {s}, must be 'all', 'majority' or a number of witnesses")
+        }),
     }
 }
 
@@ -72,28 +77,34 @@ struct Cli {
     #[clap(long)]
     chain_id: String,
 
-    /// Primary RPC address
-    #[clap(long)]
-    primary: HttpClientUrl,
+    /// Primary RPC address, required unless saved in the state directory
+    #[clap(long, requires = "witnesses")]
+    primary: Option<HttpClientUrl>,
 
-    /// Comma-separated list of witnesses RPC addresses
-    #[clap(long)]
-    witnesses: List<HttpClientUrl>,
+    /// Comma-separated list of witnesses RPC addresses, required unless saved in the state
+    /// directory
+    #[clap(long, requires = "primary")]
+    witnesses: Option<List<HttpClientUrl>>,
 
-    /// Height of trusted header
-    #[clap(long)]
-    trusted_height: Height,
+    /// Height of trusted header, required unless resuming from the state directory
+    #[clap(long, requires = "trusted_hash")]
+    trusted_height: Option<Height>,
+
+    /// Hash of trusted header, required unless resuming from the state directory
+    #[clap(long, requires = "trusted_height")]
+    trusted_hash: Option<Hash>,
 
-    /// Hash of trusted header
+    /// Directory in which to keep the trusted state and the peers, to resume from on the
+    /// next run without a trusted height and hash
     #[clap(long)]
-    trusted_hash: Hash,
+    state_dir: Option<PathBuf>,
 
     /// Height of the header to verify
     #[clap(long)]
     height: Option<Height>,
 
     /// Trust threshold
-    #[clap(long, value_parser = parse_trust_threshold, default_value_t = TrustThreshold::TWO_THIRDS)]
+    #[clap(long, default_value_t = TrustThreshold::TWO_THIRDS)]
     trust_threshold: TrustThreshold,
 
     /// Trusting period, in seconds (default: two weeks)
@@ -108,6 +119,18 @@ struct Cli {
     #[clap(long, default_value = "5")]
     max_block_lag: u64,
 
+    /// Time given to each witness to cross-check the primary's header, in seconds
+    #[clap(long, default_value = "60")]
+    witness_timeout: u64,
+
+    /// Number of witnesses which must agree with the primary: 'all', 'majority' or a number
+    #[clap(long, value_parser = parse_quorum, default_value = "1")]
+    quorum: QuorumPolicy,
+
+    /// Whether to submit the evidence of the attacks found to the primary and the witnesses
+    #[clap(long, default_value_t = true, action = clap::ArgAction::Set)]
+    submit_evidence: bool,
+
     /// Increase verbosity
     #[clap(flatten)]
     verbose: Verbosity,
@@ -129,17 +152,39 @@ async fn main() -> Result<()> {
         .finish()
         .init();
 
-    let options = Options {
-        trust_threshold: args.trust_threshold,
-        trusting_period: Duration::from_secs(args.trusting_period),
-        clock_drift: Duration::from_secs(args.max_clock_drift),
+    let options = Options::new(
+        args.trust_threshold,
+        Duration::from_secs(args.trusting_period),
+        Duration::from_secs(args.max_clock_drift),
+    )?;
+
+    let state_dir = args.state_dir.as_deref().map(StateDir::open).transpose()?;
+
+    let given_peers = match (args.primary, args.witnesses) {
+        (Some(primary), Some(witnesses)) => Some(Peers {
+            primary,
+            witnesses: witnesses.0,
+        }),
+        _ => None,
     };
+    let peers = resolve_peers(given_peers, state_dir.as_ref())?;
+
+    let (light_store, trusted_state) = resolve_trusted_state(
+        args.trusted_height.zip(args.trusted_hash),
+        state_dir.as_ref(),
+    )?;
+
+    args.quorum.validate(peers.witnesses.len())?;
+
+    if let Some(state_dir) = &state_dir {
+        state_dir.save_peers(&peers)?;
+    }
 
     let mut primary = make_provider(
         &args.chain_id,
-        args.primary,
-        args.trusted_height,
-        args.trusted_hash,
+        peers.primary,
+        light_store,
+        trusted_state,
         options,
     )
     .await?;
@@ -159,29 +204,36 @@ async fn main() -> Result<()> {
     info!("Verified to height {} on primary", primary_block.height());
     let primary_trace = primary.get_trace(primary_block.height());
 
-    let witnesses = join_all(args.witnesses.0.into_iter().map(|addr| {
+    let witnesses = join_all(peers.witnesses.into_iter().map(|addr| {
         make_provider(
             &args.chain_id,
             addr,
-            trusted_block.height(),
-            trusted_block.signed_header.header.hash(),
+            Box::new(MemoryStore::new()),
+            TrustedState::At(
+                trusted_block.height(),
+                trusted_block.signed_header.header.hash(),
+            ),
             options,
         )
     }))
     .await;
 
-    let mut witnesses = witnesses.into_iter().collect::<Result<Vec<_>>>()?;
+    let witnesses = witnesses.into_iter().collect::<Result<Vec<_>>>()?;
 
     let max_clock_drift = Duration::from_secs(args.max_clock_drift);
     let max_block_lag = Duration::from_secs(args.max_block_lag);
+    let witness_timeout = Duration::from_secs(args.witness_timeout);
     let now = Time::now();
 
     run_detector(
-        &mut primary,
-        witnesses.as_mut_slice(),
+        Arc::new(primary),
+        witnesses,
         primary_trace,
         max_clock_drift,
         max_block_lag,
+        witness_timeout,
+        args.quorum,
+        args.submit_evidence,
         now,
     )
     .await?;
@@ -189,81 +241,102 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run_detector(
-    primary: &mut Provider,
-    witnesses: &mut [Provider],
+    primary: Arc<Provider>,
+    witnesses: Vec<Provider>,
     primary_trace: Vec<LightBlock>,
     max_clock_drift: Duration,
     max_block_lag: Duration,
+    witness_timeout: Duration,
+    quorum: QuorumPolicy,
+    submit_evidence: bool,
     now: Time,
 ) -> Result<(), Report> {
-    if witnesses.is_empty() {
-        return Err(Error::no_witnesses().into());
-    }
-
     info!(
         "Running misbehavior detection against {} witnesses...",
         witnesses.len()
     );
 
-    let primary_trace = Trace::new(primary_trace)?;
-
-    let last_verified_block = primary_trace.last();
-    let last_verified_header = &last_verified_block.signed_header;
-
-    for witness in witnesses {
-        let divergence = detect_divergence::<Sha256>(
-            Some(primary),
-            witness,
-            primary_trace.clone().into_vec(),
-            max_clock_drift,
-            max_block_lag,
-        )
-        .await;
-
-        let evidence = match divergence {
-            Ok(Some(divergence)) => divergence.evidence,
-            Ok(None) => {
-                info!(
-                    "no divergence found between primary and witness {}",
-                    witness.peer_id()
-                );
+    let cross_check = cross_check_witnesses(
+        Some(primary.clone()),
+        witnesses,
+        primary_trace,
+        max_clock_drift,
+        max_block_lag,
+        witness_timeout,
+    )?;
+
+    for report in &cross_check.reports {
+        match &report.verdict {
+            Verdict::Agree => info!(
+                "no divergence found between primary and witness {}",
+                report.peer_id
+            ),
+            Verdict::Conflict(divergence) => warn!(
+                "witness {} has a header conflicting with the primary at height {}",
+                report.peer_id,
+                divergence.challenging_block.height()
+            ),
+            Verdict::Failed(e) => error!(
+                "failed to run attack detector against witness {}: {e}",
+                report.peer_id
+            ),
+            Verdict::Timeout => error!(
+                "witness {} did not complete the attack detection in time",
+                report.peer_id
+            ),
+        }
+    }
 
-                continue;
+    for (report, disagreement) in cross_check.classify() {
+        match disagreement {
+            Disagreement::FaultyWitness => {
+                warn!("witness {} is deemed faulty", report.peer_id)
             },
-            Err(e) => {
-                error!(
-                    "failed to run attack detector against witness {}: {e}",
-                    witness.peer_id()
-                );
+            Disagreement::PrimaryAttack => error!(
+                "witness {} proves that the light client is under attack",
+                report.peer_id
+            ),
+        }
+    }
 
-                continue;
-            },
-        };
-
-        // Report the evidence to the witness
-        witness
-            .report_evidence(Evidence::from(evidence.against_primary))
-            .await
-            .map_err(|e| eyre!("failed to report evidence to witness: {}", e))?;
-
-        if let Some(against_witness) = evidence.against_witness {
-            // Report the evidence to the primary
-            primary
-                .report_evidence(Evidence::from(against_witness))
-                .await
-                .map_err(|e| eyre!("failed to report evidence to primary: {}", e))?;
+    if submit_evidence {
+        cross_check
+            .submit_evidence(&primary, report_submission)
+            .await;
+    } else {
+        for (against, evidence) in cross_check.evidence(*primary.peer_id()) {
+            info!(
+                "not submitting the evidence against {against}: {}",
+                serde_json::to_string_pretty(evidence)?
+            );
         }
     }
 
+    cross_check.ensure_safe(quorum)?;
+
     Ok(())
 }
 
+fn report_submission(submission: Submission) {
+    match submission.outcome {
+        Ok(hash) => info!(
+            "submitted evidence against {} to {}, with hash {hash}",
+            submission.against, submission.peer_id
+        ),
+        Err(e) => error!(
+            "failed to submit evidence against {} to {}: {e}",
+            submission.against, submission.peer_id
+        ),
+    }
+}
+
 async fn make_provider(
     chain_id: &str,
     rpc_addr: HttpClientUrl,
-    trusted_height: Height,
-    trusted_hash: Hash,
+    light_store: Box<dyn LightStore>,
+    trusted_state: TrustedState,
     options: Options,
 ) -> Result<Provider> {
     use tendermint_rpc::client::CompatMode;
@@ -273,12 +346,18 @@ async fn make_provider(
         .build()?;
 
     let node_id = rpc_client.status().await?.node_info.id;
-    let light_store = Box::new(MemoryStore::new());
 
-    let instance =
-        LightClientBuilder::prod(node_id, rpc_client.clone(), light_store, options, None)
-            .trust_primary_at(trusted_height, trusted_hash)?
-            .build();
+    let builder = LightClientBuilder::prod(node_id, rpc_client.clone(), light_store, options, None);
+    let instance = match trusted_state {
+        TrustedState::At(trusted_height, trusted_hash) => {
+            builder.trust_primary_at(trusted_height, trusted_hash)?
+        },
+        TrustedState::Store => builder.trust_from_store().wrap_err(
+            "cannot resume from the trusted state in the state directory, \
+             set --trusted-height and --trusted-hash to re-initialize it",
+        )?,
+    }
+    .build();
 
     Ok(Provider::new(chain_id.to_string(), instance, rpc_client))
 }
@@ -0,0 +1,278 @@
+//! Persistence of the trusted state and the peers of the light client across
+//! runs, so that it can resume from where it left off rather than be
+//! re-initialized from a trusted height and hash.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use serde_json::{json, Value};
+use tendermint_light_client::{
+    store::{memory::MemoryStore, sqlite::SqliteStore, LightStore},
+    types::{Hash, Height},
+};
+use tendermint_rpc::{HttpClientUrl, Url};
+use tracing::info;
+
+const LIGHT_STORE_FILE: &str = "light_store.sqlite";
+const PEERS_FILE: &str = "peers.json";
+
+/// The primary and witnesses the light client connects to.
+#[derive(Clone, Debug)]
+pub struct Peers {
+    pub primary: HttpClientUrl,
+    pub witnesses: Vec<HttpClientUrl>,
+}
+
+/// Where the initial trusted state of a light client comes from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrustedState {
+    /// The block of the peer at the given height, which must have the given hash.
+    At(Height, Hash),
+    /// The highest trusted or verified block in the light store, kept by a previous run,
+    /// which must still be within the trusting period.
+    Store,
+}
+
+/// A directory holding the light store of the primary and the peers.
+#[derive(Debug)]
+pub struct StateDir {
+    path: PathBuf,
+}
+
+impl StateDir {
+    /// Open the given directory, creating it if needed.
+    pub fn open(path: &Path) -> Result<Self> {
+        fs::create_dir_all(path)
+            .wrap_err_with(|| format!("failed to create state directory {}", path.display()))?;
+
+        Ok(Self {
+            path: path.to_owned(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Open the light store of the primary, in which its trusted state is kept.
+    pub fn light_store(&self) -> Result<SqliteStore> {
+        SqliteStore::open(self.path.join(LIGHT_STORE_FILE)).map_err(Into::into)
+    }
+
+    /// Load the peers saved by a previous run, if any.
+    pub fn load_peers(&self) -> Result<Option<Peers>> {
+        let path = self.path.join(PEERS_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let bytes =
+            fs::read(&path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        let peers: Value = serde_json::from_slice(&bytes)?;
+
+        let parse = |url: &Value| -> Result<HttpClientUrl> {
+            let url = url
+                .as_str()
+                .ok_or_else(|| eyre!("invalid peer address in {}: {url}", path.display()))?;
+            Ok(url.parse()?)
+        };
+
+        let primary = parse(&peers["primary"])?;
+        let witnesses = peers["witnesses"]
+            .as_array()
+            .ok_or_else(|| eyre!("missing witnesses in {}", path.display()))?
+            .iter()
+            .map(parse)
+            .collect::<Result<_>>()?;
+
+        Ok(Some(Peers { primary, witnesses }))
+    }
+
+    /// Save the given peers, for the next run to resume with.
+    pub fn save_peers(&self, peers: &Peers) -> Result<()> {
+        let address = |url: &HttpClientUrl| Url::from(url.clone()).to_string();
+        let peers = json!({
+            "primary": address(&peers.primary),
+            "witnesses": peers.witnesses.iter().map(address).collect::<Vec<_>>(),
+        });
+
+        let path = self.path.join(PEERS_FILE);
+        fs::write(&path, serde_json::to_vec_pretty(&peers)?)
+            .wrap_err_with(|| format!("failed to write {}", path.display()))
+    }
+}
+
+/// The given peers, or else those saved in the state directory by a previous run.
+pub fn resolve_peers(given: Option<Peers>, state_dir: Option<&StateDir>) -> Result<Peers> {
+    match given {
+        Some(peers) => Ok(peers),
+        None => state_dir
+            .map(StateDir::load_peers)
+            .transpose()?
+            .flatten()
+            .ok_or_else(|| {
+                eyre!("--primary and --witnesses are required unless saved in --state-dir")
+            }),
+    }
+}
+
+/// The light store of the primary and the trusted state to initialize it from: the given
+/// trusted height and hash, or else the trusted state kept in the state directory by a
+/// previous run.
+pub fn resolve_trusted_state(
+    trusted: Option<(Height, Hash)>,
+    state_dir: Option<&StateDir>,
+) -> Result<(Box<dyn LightStore>, TrustedState)> {
+    match (trusted, state_dir) {
+        (Some((height, hash)), state_dir) => {
+            let light_store: Box<dyn LightStore> = match state_dir {
+                Some(state_dir) => Box::new(state_dir.light_store()?),
+                None => Box::new(MemoryStore::new()),
+            };
+            Ok((light_store, TrustedState::At(height, hash)))
+        },
+        (None, Some(state_dir)) => {
+            info!(
+                "Resuming from the trusted state in {}",
+                state_dir.path().display()
+            );
+            Ok((Box::new(state_dir.light_store()?), TrustedState::Store))
+        },
+        (None, None) => Err(eyre!(
+            "--trusted-height and --trusted-hash are required unless resuming from --state-dir"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint_light_client::types::{LightBlock, Status};
+    use tendermint_testgen::{Generator, LightChain};
+
+    use super::*;
+
+    fn url(addr: &str) -> HttpClientUrl {
+        addr.parse().unwrap()
+    }
+
+    fn peers() -> Peers {
+        Peers {
+            primary: url("http://127.0.0.1:26657"),
+            witnesses: vec![
+                url("http://127.0.0.1:36657"),
+                url("https://rpc.example.com/"),
+            ],
+        }
+    }
+
+    fn addresses(peers: &Peers) -> Vec<String> {
+        [&peers.primary]
+            .into_iter()
+            .chain(&peers.witnesses)
+            .map(|url| Url::from(url.clone()).to_string())
+            .collect()
+    }
+
+    fn light_block() -> LightBlock {
+        LightBlock::from(
+            LightChain::default_with_length(1).light_blocks[0]
+                .generate()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn peers_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_dir = StateDir::open(&dir.path().join("state")).unwrap();
+
+        state_dir.save_peers(&peers()).unwrap();
+        let loaded = StateDir::open(state_dir.path())
+            .unwrap()
+            .load_peers()
+            .unwrap()
+            .unwrap();
+        assert_eq!(addresses(&loaded), addresses(&peers()));
+    }
+
+    #[test]
+    fn missing_peers_are_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_dir = StateDir::open(dir.path()).unwrap();
+
+        assert!(state_dir.load_peers().unwrap().is_none());
+        assert!(resolve_peers(None, Some(&state_dir)).is_err());
+        assert!(resolve_peers(None, None).is_err());
+    }
+
+    #[test]
+    fn corrupt_peers_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_dir = StateDir::open(dir.path()).unwrap();
+
+        for corrupt in [
+            &b"{\"primary\": \"http://127.0.0.1:26657\""[..],
+            br#"{"primary": 26657, "witnesses": []}"#,
+            br#"{"primary": "http://127.0.0.1:26657"}"#,
+            br#"{"primary": "http://127.0.0.1:26657", "witnesses": ["not a url"]}"#,
+        ] {
+            fs::write(dir.path().join(PEERS_FILE), corrupt).unwrap();
+            assert!(
+                state_dir.load_peers().is_err(),
+                "{}",
+                String::from_utf8_lossy(corrupt)
+            );
+        }
+    }
+
+    #[test]
+    fn given_peers_and_trusted_state_take_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_dir = StateDir::open(dir.path()).unwrap();
+        state_dir.save_peers(&peers()).unwrap();
+
+        let given = Peers {
+            primary: url("http://127.0.0.1:46657"),
+            witnesses: vec![],
+        };
+        let resolved = resolve_peers(Some(given.clone()), Some(&state_dir)).unwrap();
+        assert_eq!(addresses(&resolved), addresses(&given));
+
+        let light_block = light_block();
+        let trusted = (
+            light_block.height(),
+            light_block.signed_header.header.hash(),
+        );
+        let (_, trusted_state) = resolve_trusted_state(Some(trusted), Some(&state_dir)).unwrap();
+        assert_eq!(trusted_state, TrustedState::At(trusted.0, trusted.1));
+    }
+
+    #[test]
+    fn resumes_without_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        let light_block = light_block();
+
+        // A previous run saved its peers and verified a block.
+        {
+            let state_dir = StateDir::open(dir.path()).unwrap();
+            state_dir.save_peers(&peers()).unwrap();
+            state_dir
+                .light_store()
+                .unwrap()
+                .insert(light_block.clone(), Status::Verified);
+        }
+
+        let state_dir = StateDir::open(dir.path()).unwrap();
+        let peers = resolve_peers(None, Some(&state_dir)).unwrap();
+        assert_eq!(addresses(&peers), addresses(&self::peers()));
+
+        let (light_store, trusted_state) = resolve_trusted_state(None, Some(&state_dir)).unwrap();
+        assert_eq!(trusted_state, TrustedState::Store);
+        assert_eq!(light_store.highest_trusted_or_verified(), Some(light_block));
+
+        assert!(resolve_trusted_state(None, None).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use tendermint::{crypto::Sha256, evidence::LightClientAttackEvidence, merkle::MerkleHash};
+use tendermint::evidence::LightClientAttackEvidence;
 use tendermint_light_client::verifier::types::LightBlock;
 use tracing::{error, error_span, warn};
 
@@ -7,11 +7,18 @@ use super::{
     provider::Provider, trace::Trace,
 };
 
+/// The evidence gathered from the conflicting headers of the primary and a witness, with
+/// [`gather_evidence_from_conflicting_headers`].
 #[derive(Clone, Debug)]
 pub struct GatheredEvidence {
+    /// The trace of the witness, from the last block it has in common with the primary to
+    /// its conflicting block.
     pub witness_trace: Trace,
 
+    /// The evidence against the primary, holding the witness as the source of truth.
     pub against_primary: LightClientAttackEvidence,
+    /// The evidence against the witness, holding the primary as the source of truth, if the
+    /// primary was available to gather it.
     pub against_witness: Option<LightClientAttackEvidence>,
 }
 
@@ -20,21 +27,18 @@ pub struct GatheredEvidence {
 ///
 /// If a primary provider is available, then we will also attempt to gather evidence against the
 /// witness by examining the witness's trace and holding the primary as the source of truth.
-pub async fn gather_evidence_from_conflicting_headers<H>(
+pub async fn gather_evidence_from_conflicting_headers(
     primary: Option<&Provider>,
     witness: &Provider,
     primary_trace: &Trace,
     challenging_block: &LightBlock,
-) -> Result<GatheredEvidence, Error>
-where
-    H: Sha256 + MerkleHash + Default,
-{
+) -> Result<GatheredEvidence, Error> {
     let _span =
         error_span!("gather_evidence_from_conflicting_headers", witness = %witness.peer_id())
             .entered();
 
     let (witness_trace, primary_block) =
-        examine_conflicting_header_against_trace::<H>(primary_trace, challenging_block, witness)
+        examine_conflicting_header_against_trace(primary_trace, challenging_block, witness)
             .map_err(|e| {
                 error!("Error validating witness's divergent header: {e}");
                 e
@@ -74,11 +78,12 @@ where
     // trace provided by the witness and holding the primary as the source of truth. Note: primary may not
     // respond but this is okay as we will halt anyway.
     let (primary_trace, witness_block) =
-        examine_conflicting_header_against_trace::<H>(&witness_trace, &primary_block, primary)
-            .map_err(|e| {
+        examine_conflicting_header_against_trace(&witness_trace, &primary_block, primary).map_err(
+            |e| {
                 error!("Error validating primary's divergent header: {e}");
                 e
-            })?;
+            },
+        )?;
 
     warn!("Gathering evidence against witness by primary...");
 
@@ -0,0 +1,619 @@
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel as crossbeam;
+use tracing::{debug, info, warn};
+
+use tendermint::{evidence::LightClientAttackEvidence, node};
+#[cfg(feature = "metrics")]
+use tendermint_light_client::metrics::Metrics;
+use tendermint_light_client::verifier::types::LightBlock;
+
+use super::{
+    detect::{detect_divergence, Divergence},
+    error::Error,
+    provider::Provider,
+};
+
+/// How many witnesses must agree with the primary for the header of the primary to be
+/// deemed safe by [`CrossCheck::ensure_safe`].
+///
+/// The policy only governs how many witnesses must agree: a header is never deemed safe if a
+/// witness has a conflicting one, whatever the policy.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QuorumPolicy {
+    /// All the witnesses must agree, without any of them timing out or failing.
+    All,
+    /// More than half of the witnesses must agree.
+    Majority,
+    /// At least the given number of witnesses must agree.
+    AtLeast(usize),
+}
+
+impl QuorumPolicy {
+    /// The number of witnesses which must agree out of the given number of witnesses.
+    pub fn required(&self, witnesses: usize) -> usize {
+        match self {
+            Self::All => witnesses,
+            Self::Majority => witnesses / 2 + 1,
+            Self::AtLeast(required) => *required,
+        }
+    }
+
+    /// Ensure that the policy can be met with the given number of witnesses, which is not the
+    /// case if it requires more witnesses to agree than there are.
+    pub fn validate(&self, witnesses: usize) -> Result<(), Error> {
+        let required = self.required(witnesses);
+        if required > witnesses {
+            return Err(Error::unreachable_quorum(required, witnesses));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for QuorumPolicy {
+    /// At least one witness must agree, as with the Go light client which drops the witnesses
+    /// that fail to respond.
+    fn default() -> Self {
+        Self::AtLeast(1)
+    }
+}
+
+/// The outcome of cross-checking the header of the primary with a witness.
+#[derive(Debug)]
+pub enum Verdict {
+    /// The witness has the same header as the primary.
+    Agree,
+    /// The witness has a conflicting header.
+    Conflict(Box<Divergence>),
+    /// The witness did not complete the cross-check in time.
+    Timeout,
+    /// The cross-check failed, e.g. because the witness did not respond or returned an
+    /// invalid header.
+    Failed(Box<Error>),
+}
+
+/// How a witness which does not agree with the primary is classified by
+/// [`CrossCheck::classify`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Disagreement {
+    /// The cross-check with the witness failed, e.g. because its header could not be verified.
+    /// The header of the primary can still be trusted if enough other witnesses agree with it.
+    FaultyWitness,
+    /// The witness has a conflicting header, verified from the trusted block the primary and
+    /// the witness have in common: this is a proven attack on the light client, and the header
+    /// of the primary must not be trusted.
+    PrimaryAttack,
+}
+
+/// The outcome of cross-checking the header of the primary with a witness, along with the
+/// witness itself.
+#[derive(Debug)]
+pub struct WitnessReport {
+    pub peer_id: node::Id,
+    pub verdict: Verdict,
+    /// The time the witness took to complete the cross-check, or the timeout if it did not.
+    pub elapsed: Duration,
+    /// The witness, unless it timed out, in which case it is left to complete the cross-check
+    /// in the background.
+    pub witness: Option<Provider>,
+}
+
+/// The outcome of cross-checking the header of the primary with its witnesses, as returned by
+/// [`cross_check_witnesses`].
+#[derive(Debug)]
+pub struct CrossCheck {
+    /// The reports of the witnesses, in the order they were given in.
+    pub reports: Vec<WitnessReport>,
+}
+
+impl CrossCheck {
+    /// The number of witnesses which agree with the primary.
+    pub fn agreeing(&self) -> usize {
+        self.reports
+            .iter()
+            .filter(|report| matches!(report.verdict, Verdict::Agree))
+            .count()
+    }
+
+    /// The divergences found between the primary and the witnesses.
+    pub fn divergences(&self) -> impl Iterator<Item = (&WitnessReport, &Divergence)> {
+        self.reports
+            .iter()
+            .filter_map(|report| match &report.verdict {
+                Verdict::Conflict(divergence) => Some((report, divergence.as_ref())),
+                _ => None,
+            })
+    }
+
+    /// The evidence gathered from the divergences found by the cross-check, along with the
+    /// node it is against: the given primary, or the diverging witness.
+    ///
+    /// The evidence is fully formed, and can be submitted to full nodes with
+    /// [`CrossCheck::submit_evidence`], or logged for it to be submitted out of band.
+    pub fn evidence(
+        &self,
+        primary: node::Id,
+    ) -> impl Iterator<Item = (node::Id, &LightClientAttackEvidence)> {
+        self.divergences().flat_map(move |(report, divergence)| {
+            let gathered = &divergence.evidence;
+            std::iter::once((primary, &gathered.against_primary)).chain(
+                gathered
+                    .against_witness
+                    .as_ref()
+                    .map(|against_witness| (report.peer_id, against_witness)),
+            )
+        })
+    }
+
+    /// Classify the witnesses which do not agree with the primary, except for those which
+    /// timed out.
+    ///
+    /// A witness with a conflicting header proves an attack on the light client, as its header
+    /// was verified from the trusted block it has in common with the primary, while a witness
+    /// for which the cross-check failed is deemed faulty.
+    pub fn classify(&self) -> impl Iterator<Item = (&WitnessReport, Disagreement)> {
+        self.reports
+            .iter()
+            .filter_map(|report| match report.verdict {
+                Verdict::Conflict(_) => Some((report, Disagreement::PrimaryAttack)),
+                Verdict::Failed(_) => Some((report, Disagreement::FaultyWitness)),
+                Verdict::Agree | Verdict::Timeout => None,
+            })
+    }
+
+    /// Record the time each witness took to complete the cross-check, or the timeout for those
+    /// which did not, with the given metrics of the light client.
+    #[cfg(feature = "metrics")]
+    pub fn observe(&self, metrics: &Metrics) {
+        for report in &self.reports {
+            metrics.observe_cross_check(report.elapsed);
+        }
+    }
+
+    /// Ensure that none of the witnesses has a header conflicting with the one of the primary,
+    /// and that enough witnesses agree with the primary as per the given policy, which must be
+    /// valid for the number of witnesses (see [`QuorumPolicy::validate`]).
+    pub fn ensure_safe(&self, policy: QuorumPolicy) -> Result<(), Error> {
+        if let Some((_, divergence)) = self.divergences().next() {
+            return Err(Error::divergence(
+                divergence.evidence.clone(),
+                divergence.challenging_block.clone(),
+            ));
+        }
+
+        policy.validate(self.reports.len())?;
+
+        let agreeing = self.agreeing();
+        let required = policy.required(self.reports.len());
+        if agreeing < required.max(1) {
+            return Err(Error::no_quorum(agreeing, required, self.reports.len()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Cross-check the last header of the primary trace with all the witnesses concurrently.
+///
+/// Each witness is given `timeout` to complete the cross-check, which includes
+/// [`detect_divergence`] waiting for witnesses lagging behind the primary, and gathering
+/// evidence in case of a conflict.
+///
+/// The header of the primary should then only be trusted if [`CrossCheck::ensure_safe`] holds.
+pub fn cross_check_witnesses(
+    primary: Option<Arc<Provider>>,
+    witnesses: Vec<Provider>,
+    primary_trace: Vec<LightBlock>,
+    max_clock_drift: Duration,
+    max_block_lag: Duration,
+    timeout: Duration,
+) -> Result<CrossCheck, Error> {
+    if witnesses.is_empty() {
+        return Err(Error::no_witnesses());
+    }
+
+    info!(
+        "Cross-checking the primary's header with {} witnesses...",
+        witnesses.len()
+    );
+
+    let peer_ids = witnesses
+        .iter()
+        .map(|witness| *witness.peer_id())
+        .collect::<Vec<_>>();
+
+    let started = Instant::now();
+    let (sender, receiver) = crossbeam::unbounded();
+    for (index, mut witness) in witnesses.into_iter().enumerate() {
+        let primary = primary.clone();
+        let primary_trace = primary_trace.clone();
+        let sender = sender.clone();
+
+        thread::spawn(move || {
+            let divergence = futures::executor::block_on(detect_divergence(
+                primary.as_deref(),
+                &mut witness,
+                primary_trace,
+                max_clock_drift,
+                max_block_lag,
+            ));
+
+            let verdict = match divergence {
+                Ok(None) => Verdict::Agree,
+                Ok(Some(divergence)) => Verdict::Conflict(Box::new(divergence)),
+                Err(e) => Verdict::Failed(Box::new(e)),
+            };
+
+            // The receiver is gone if the cross-check timed out.
+            sender
+                .send((index, verdict, started.elapsed(), witness))
+                .ok();
+        });
+    }
+    drop(sender);
+
+    let mut reports = peer_ids
+        .into_iter()
+        .map(|peer_id| WitnessReport {
+            peer_id,
+            verdict: Verdict::Timeout,
+            elapsed: timeout,
+            witness: None,
+        })
+        .collect::<Vec<_>>();
+
+    let deadline = started + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let (index, verdict, elapsed, witness) = match receiver.recv_timeout(remaining) {
+            Ok(completed) => completed,
+            // Either the timeout elapsed, or all the witnesses completed.
+            Err(_) => break,
+        };
+        debug!(witness = %witness.peer_id(), ?elapsed, "Cross-check completed");
+
+        reports[index].verdict = verdict;
+        reports[index].elapsed = elapsed;
+        reports[index].witness = Some(witness);
+    }
+
+    for report in &reports {
+        if let Verdict::Timeout = report.verdict {
+            warn!(witness = %report.peer_id, "Witness did not complete the cross-check in time");
+        }
+    }
+
+    Ok(CrossCheck { reports })
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint_light_client::{
+        components::{
+            io::{AtHeight, Io, IoError},
+            scheduler,
+        },
+        instance::Instance,
+        light_client::LightClient,
+        simulation::{ScriptedProvider, VirtualClock},
+        state::State,
+        store::{memory::MemoryStore, LightStore},
+        verifier::{options::Options, types::Status, ProdVerifier},
+    };
+    use tendermint_rpc::HttpClient;
+    use tendermint_testgen::{
+        helpers::get_time, light_block::LightBlock as TestgenLightBlock, Generator,
+    };
+
+    use super::*;
+    use crate::ErrorDetail;
+
+    const POLICIES: [QuorumPolicy; 3] = [
+        QuorumPolicy::All,
+        QuorumPolicy::Majority,
+        QuorumPolicy::AtLeast(1),
+    ];
+
+    fn light_block(height: u64, time_offset: Duration) -> LightBlock {
+        let time = (get_time(height).unwrap() + time_offset).unwrap();
+        LightBlock::from(
+            TestgenLightBlock::new_default_with_time_and_chain_id(
+                "test-chain".to_owned(),
+                time,
+                height,
+            )
+            .generate()
+            .unwrap(),
+        )
+    }
+
+    fn light_blocks() -> Vec<LightBlock> {
+        (1..=10).map(|h| light_block(h, Duration::ZERO)).collect()
+    }
+
+    fn peer_id(n: u8) -> node::Id {
+        node::Id::new([n; 20])
+    }
+
+    // A provider trusting the block at height 1 of the chain served by the given I/O.
+    fn provider(peer_id: node::Id, io: impl Io + 'static) -> Provider {
+        let mut trusted_block = light_block(1, Duration::ZERO);
+        trusted_block.provider = peer_id;
+        let mut light_store = MemoryStore::new();
+        light_store.insert(trusted_block, Status::Trusted);
+
+        let light_client = LightClient::new(
+            peer_id,
+            Options {
+                trust_threshold: Default::default(),
+                trusting_period: Duration::from_secs(60 * 60),
+                clock_drift: Duration::from_secs(10),
+            },
+            VirtualClock::new(get_time(11).unwrap()),
+            scheduler::basic_bisecting_schedule,
+            ProdVerifier::default(),
+            io,
+        );
+
+        Provider::new(
+            "test-chain".to_owned(),
+            Instance::new(light_client, State::new(light_store)),
+            HttpClient::new("http://127.0.0.1:26657").unwrap(),
+        )
+    }
+
+    fn primary() -> Provider {
+        provider(
+            peer_id(1),
+            ScriptedProvider::new(peer_id(1), light_blocks()),
+        )
+    }
+
+    // The trace of the primary, verifying the block at height 10 from the one at height 1.
+    fn primary_trace() -> Vec<LightBlock> {
+        [1, 10]
+            .into_iter()
+            .map(|height| LightBlock {
+                provider: peer_id(1),
+                ..light_block(height, Duration::ZERO)
+            })
+            .collect()
+    }
+
+    // The divergence between a primary and a witness serving a conflicting block at height 10,
+    // signed by the same validators.
+    fn divergence() -> Divergence {
+        let primary = primary();
+        let witness = ScriptedProvider::new(peer_id(2), light_blocks());
+        witness.equivocate(light_block(10, Duration::from_millis(500)));
+
+        futures::executor::block_on(detect_divergence(
+            Some(&primary),
+            &mut provider(peer_id(2), witness),
+            primary_trace(),
+            Duration::from_secs(10),
+            Duration::from_secs(10),
+        ))
+        .unwrap()
+        .expect("no divergence found")
+    }
+
+    fn cross_check(verdicts: Vec<Verdict>) -> CrossCheck {
+        let reports = verdicts
+            .into_iter()
+            .zip(1..)
+            .map(|(verdict, n)| WitnessReport {
+                peer_id: peer_id(n),
+                verdict,
+                elapsed: Duration::ZERO,
+                witness: None,
+            })
+            .collect();
+
+        CrossCheck { reports }
+    }
+
+    fn classified(cross_check: &CrossCheck) -> Vec<(node::Id, Disagreement)> {
+        cross_check
+            .classify()
+            .map(|(report, disagreement)| (report.peer_id, disagreement))
+            .collect()
+    }
+
+    fn failed() -> Verdict {
+        Verdict::Failed(Box::new(Error::bad_witness()))
+    }
+
+    fn is_no_quorum(result: Result<(), Error>) -> bool {
+        matches!(result.unwrap_err().detail(), ErrorDetail::NoQuorum(_))
+    }
+
+    #[test]
+    fn conflict_is_fatal_whatever_the_policy() {
+        let cross_check = cross_check(vec![
+            Verdict::Agree,
+            Verdict::Agree,
+            Verdict::Agree,
+            Verdict::Conflict(Box::new(divergence())),
+        ]);
+
+        assert_eq!(
+            classified(&cross_check),
+            [(peer_id(4), Disagreement::PrimaryAttack)]
+        );
+        for policy in POLICIES {
+            let e = cross_check.ensure_safe(policy).unwrap_err();
+            assert!(
+                matches!(e.detail(), ErrorDetail::Divergence(_)),
+                "{policy:?}: {e}"
+            );
+        }
+    }
+
+    #[test]
+    fn failed_witness_is_faulty() {
+        let cross_check = cross_check(vec![Verdict::Agree, failed(), Verdict::Agree]);
+
+        assert_eq!(
+            classified(&cross_check),
+            [(peer_id(2), Disagreement::FaultyWitness)]
+        );
+        assert!(is_no_quorum(cross_check.ensure_safe(QuorumPolicy::All)));
+        assert!(cross_check.ensure_safe(QuorumPolicy::Majority).is_ok());
+        assert!(cross_check.ensure_safe(QuorumPolicy::AtLeast(2)).is_ok());
+        assert!(is_no_quorum(
+            cross_check.ensure_safe(QuorumPolicy::AtLeast(3))
+        ));
+    }
+
+    #[test]
+    fn timed_out_witness_is_not_classified() {
+        let cross_check = cross_check(vec![Verdict::Timeout, Verdict::Agree, Verdict::Timeout]);
+
+        assert!(classified(&cross_check).is_empty());
+        assert!(is_no_quorum(cross_check.ensure_safe(QuorumPolicy::All)));
+        assert!(is_no_quorum(
+            cross_check.ensure_safe(QuorumPolicy::Majority)
+        ));
+        assert!(cross_check.ensure_safe(QuorumPolicy::AtLeast(1)).is_ok());
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn records_elapsed_time_of_witnesses() {
+        let mut cross_check = cross_check(vec![Verdict::Agree, failed(), Verdict::Timeout]);
+        for (report, millis) in cross_check.reports.iter_mut().zip([100, 200, 3000]) {
+            report.elapsed = Duration::from_millis(millis);
+        }
+
+        let metrics = Metrics::new().unwrap();
+        let registry = prometheus::Registry::new();
+        metrics.register(&registry).unwrap();
+        cross_check.observe(&metrics);
+
+        let families = registry.gather();
+        let histogram = families
+            .iter()
+            .find(|family| {
+                family.get_name() == "tendermint_light_client_cross_check_duration_seconds"
+            })
+            .unwrap()
+            .get_metric()[0]
+            .get_histogram();
+        assert_eq!(histogram.get_sample_count(), 3);
+        assert_eq!(histogram.get_sample_sum(), 3.3);
+    }
+
+    #[test]
+    fn some_witness_must_agree_whatever_the_policy() {
+        let cross_check = cross_check(vec![Verdict::Timeout, failed()]);
+
+        for policy in POLICIES.into_iter().chain([QuorumPolicy::AtLeast(0)]) {
+            assert!(is_no_quorum(cross_check.ensure_safe(policy)), "{policy:?}");
+        }
+    }
+
+    // An I/O taking the given time to serve each block.
+    #[derive(Debug)]
+    struct SlowIo(ScriptedProvider, Duration);
+
+    impl Io for SlowIo {
+        fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+            std::thread::sleep(self.1);
+            Io::fetch_light_block(&self.0, height)
+        }
+    }
+
+    #[test]
+    fn required_witnesses() {
+        for witnesses in 1..=4 {
+            assert_eq!(QuorumPolicy::All.required(witnesses), witnesses);
+            assert_eq!(QuorumPolicy::AtLeast(2).required(witnesses), 2);
+        }
+        let majorities: Vec<_> = (1..=5)
+            .map(|witnesses| QuorumPolicy::Majority.required(witnesses))
+            .collect();
+        assert_eq!(majorities, [1, 2, 2, 3, 3]);
+        assert_eq!(QuorumPolicy::default(), QuorumPolicy::AtLeast(1));
+    }
+
+    #[test]
+    fn unreachable_quorum_is_rejected() {
+        assert!(QuorumPolicy::AtLeast(2).validate(2).is_ok());
+        let e = QuorumPolicy::AtLeast(3).validate(2).unwrap_err();
+        assert!(matches!(
+            e.detail(),
+            ErrorDetail::UnreachableQuorum(e) if e.required == 3 && e.witnesses == 2
+        ));
+
+        let cross_check = cross_check(vec![Verdict::Agree, Verdict::Agree]);
+        let e = cross_check
+            .ensure_safe(QuorumPolicy::AtLeast(3))
+            .unwrap_err();
+        assert!(matches!(e.detail(), ErrorDetail::UnreachableQuorum(_)));
+    }
+
+    #[test]
+    fn cross_checks_witnesses_concurrently() {
+        let slow = SlowIo(
+            ScriptedProvider::new(peer_id(2), light_blocks()),
+            Duration::from_secs(10),
+        );
+        let equivocating = ScriptedProvider::new(peer_id(3), light_blocks());
+        equivocating.equivocate(light_block(10, Duration::from_millis(500)));
+        let withholding = ScriptedProvider::new(peer_id(4), light_blocks());
+        withholding.withhold(tendermint::block::Height::from(10_u32));
+        let agreeing = ScriptedProvider::new(peer_id(5), light_blocks());
+
+        let timeout = Duration::from_secs(1);
+        let started = Instant::now();
+        let cross_check = cross_check_witnesses(
+            Some(Arc::new(primary())),
+            vec![
+                provider(peer_id(2), slow),
+                provider(peer_id(3), equivocating),
+                provider(peer_id(4), withholding),
+                provider(peer_id(5), agreeing),
+            ],
+            primary_trace(),
+            Duration::from_secs(10),
+            Duration::from_secs(10),
+            timeout,
+        )
+        .unwrap();
+
+        // The slow witness does not hold up the others past the timeout.
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let reports = &cross_check.reports;
+        let peer_ids: Vec<_> = reports.iter().map(|report| report.peer_id).collect();
+        assert_eq!(peer_ids, [peer_id(2), peer_id(3), peer_id(4), peer_id(5)]);
+        assert!(matches!(reports[0].verdict, Verdict::Timeout));
+        assert_eq!(reports[0].elapsed, timeout);
+        assert!(reports[0].witness.is_none());
+        assert!(matches!(reports[1].verdict, Verdict::Conflict(_)));
+        assert!(matches!(reports[2].verdict, Verdict::Failed(_)));
+        assert!(matches!(reports[3].verdict, Verdict::Agree));
+        assert!(reports[1..].iter().all(|report| report.witness.is_some()));
+    }
+
+    #[test]
+    fn no_witnesses_is_an_error() {
+        let e = cross_check_witnesses(
+            None,
+            vec![],
+            primary_trace(),
+            Duration::from_secs(10),
+            Duration::from_secs(10),
+            Duration::from_secs(1),
+        )
+        .unwrap_err();
+
+        assert!(matches!(e.detail(), ErrorDetail::NoWitnesses(_)));
+    }
+}
@@ -2,7 +2,7 @@ use std::{thread, time::Duration};
 
 use tracing::{debug, warn};
 
-use tendermint::{block::signed_header::SignedHeader, crypto::Sha256, merkle::MerkleHash};
+use tendermint::block::signed_header::SignedHeader;
 use tendermint_light_client::light_client::TargetOrLatest;
 use tendermint_light_client::verifier::errors::ErrorExt;
 use tendermint_light_client::verifier::types::LightBlock;
@@ -34,16 +34,13 @@ pub struct Divergence {
 /// and then we need to find the point that the headers diverge and examine this for any evidence of
 /// an attack. We then attempt to find the bifurcation point and if successful construct the
 /// evidence of an attack to report to the witness.
-pub async fn detect_divergence<H>(
+pub async fn detect_divergence(
     primary: Option<&Provider>,
     witness: &mut Provider,
     primary_trace: Vec<LightBlock>,
     max_clock_drift: Duration,
     max_block_lag: Duration,
-) -> Result<Option<Divergence>, Error>
-where
-    H: Sha256 + MerkleHash + Default,
-{
+) -> Result<Option<Divergence>, Error> {
     let primary_trace = Trace::new(primary_trace)?;
 
     let last_verified_block = primary_trace.last();
@@ -51,7 +48,7 @@ where
 
     debug!(
         end_block_height = %last_verified_header.header.height,
-        end_block_hash = %last_verified_header.header.hash(),
+        end_block_hash = %witness.header_hash(&last_verified_header.header),
         length = primary_trace.len(),
         "Running detector against primary trace"
     );
@@ -81,7 +78,7 @@ where
             );
 
             // Gather the evidence to report from the conflicting headers
-            let evidence = gather_evidence_from_conflicting_headers::<H>(
+            let evidence = gather_evidence_from_conflicting_headers(
                 primary,
                 witness,
                 &primary_trace,
@@ -141,7 +138,9 @@ pub fn compare_new_header_with_witness(
 ) -> Result<(), CompareError> {
     let light_block = check_against_witness(new_header, witness, max_clock_drift, max_block_lag)?;
 
-    if light_block.signed_header.header.hash() != new_header.header.hash() {
+    if witness.header_hash(&light_block.signed_header.header)
+        != witness.header_hash(&new_header.header)
+    {
         return Err(CompareError::ConflictingHeaders(Box::new(light_block)));
     }
 
@@ -32,6 +32,31 @@ flex_error::define_error! {
         NoWitnesses
             |_| { "no witnesses provided" },
 
+        NoQuorum
+            {
+                agreeing: usize,
+                required: usize,
+                witnesses: usize,
+            }
+            |e| {
+                format_args!(
+                    "only {} out of {} witnesses agree with the primary, while {} are required",
+                    e.agreeing, e.witnesses, e.required
+                )
+            },
+
+        UnreachableQuorum
+            {
+                required: usize,
+                witnesses: usize,
+            }
+            |e| {
+                format_args!(
+                    "the quorum requires {} witnesses to agree with the primary, but there are only {}",
+                    e.required, e.witnesses
+                )
+            },
+
         BadWitness
             |_| { "bad witness" },
 
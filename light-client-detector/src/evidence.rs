@@ -10,6 +10,11 @@ use tendermint_light_client::verifier::types::LightBlock;
 
 /// Determines the type of attack and then forms the evidence filling out
 /// all the fields such that it is ready to be sent to a full node.
+///
+/// The `conflicted` block is the one the evidence is against, the `trusted` block is the
+/// one at the same height held as the source of truth, and the `common` block is the last
+/// block both chains agree on. The evidence is formed as by `newLightClientAttackEvidence`
+/// in the Go implementation, so that full nodes accept it.
 pub fn make_evidence(
     conflicted: LightBlock,
     trusted: LightBlock,
@@ -60,7 +65,8 @@ fn conflicting_header_is_invalid(conflicted: &Header, trusted: &Header) -> bool
 
 /// Find out what style of attack `LightClientAttackEvidence` was and then works out who
 /// the malicious validators were and returns them. This is used both for forming the `byzantine_validators`
-/// field and for validating that it is correct. Validators are ordered based on validator power.
+/// field and for validating that it is correct. Validators are ordered by decreasing voting power, then
+/// by address, as full nodes expect them to be.
 fn get_byzantine_validators(
     conflicted: &ConflictingBlock,
     common_validators: &validator::Set,
@@ -123,13 +129,13 @@ fn find_equivocating_validators(
         .iter()
         .enumerate()
     {
-        if sig_a.is_absent() {
+        if !sig_a.is_commit() {
             continue;
         }
 
-        let sig_b = &trusted.commit.signatures[i];
-        if sig_b.is_nil() {
-            continue;
+        match trusted.commit.signatures.get(i) {
+            Some(sig_b) if sig_b.is_commit() => {},
+            _ => continue,
         }
 
         let validator = sig_a
@@ -146,7 +152,107 @@ fn find_equivocating_validators(
 }
 
 fn cmp_voting_power_then_address(a: &validator::Info, b: &validator::Info) -> Ordering {
-    a.power
-        .cmp(&b.power)
+    b.power
+        .cmp(&a.power)
         .then_with(|| a.address.cmp(&b.address))
 }
+
+#[cfg(test)]
+mod tests {
+    use tendermint::account;
+    use tendermint_testgen::{
+        helpers::get_time, light_block::LightBlock as TestgenLightBlock, Commit, Generator, Header,
+        Validator, Vote,
+    };
+
+    use super::*;
+
+    // A block at the given height of the chain of the given validators, with a commit signed
+    // by the given voters only.
+    fn light_block(
+        validators: &[Validator],
+        height: u64,
+        time: u64,
+        voters: &[&str],
+    ) -> LightBlock {
+        let header = Header::new(validators)
+            .chain_id("test-chain")
+            .height(height)
+            .time(get_time(time).unwrap());
+        let votes = voters
+            .iter()
+            .map(|id| {
+                let validator = validators
+                    .iter()
+                    .find(|v| v.id.as_deref() == Some(*id))
+                    .unwrap();
+                Vote::new(validator.clone(), header.clone()).round(1)
+            })
+            .collect();
+        let commit = Commit::new_with_votes(header.clone(), 1, votes);
+        LightBlock::from(TestgenLightBlock::new(header, commit).generate().unwrap())
+    }
+
+    fn address(id: &str) -> account::Id {
+        Validator::new(id).generate().unwrap().address
+    }
+
+    fn byzantine_validators(evidence: &LightClientAttackEvidence) -> Vec<(account::Id, u64)> {
+        evidence
+            .byzantine_validators
+            .iter()
+            .map(|v| (v.address, v.power.value()))
+            .collect()
+    }
+
+    #[test]
+    fn equivocating_validators_signed_both_headers() {
+        let validators = [
+            Validator::new("a").voting_power(10),
+            Validator::new("b").voting_power(30),
+            Validator::new("c").voting_power(30),
+            Validator::new("d").voting_power(20),
+        ];
+        let common = light_block(&validators, 1, 1, &["a", "b", "c", "d"]);
+        let trusted = light_block(&validators, 3, 3, &["b", "c", "d"]);
+        // Only the time differs, so that the header is valid.
+        let conflicted = light_block(&validators, 3, 4, &["a", "b", "c", "d"]);
+
+        let evidence = make_evidence(conflicted, trusted, common);
+
+        // By decreasing voting power, then by address.
+        let mut expected = vec![(address("b"), 30), (address("c"), 30)];
+        expected.sort();
+        expected.push((address("d"), 20));
+        assert_eq!(byzantine_validators(&evidence), expected);
+        assert_eq!(evidence.common_height.value(), 3);
+    }
+
+    #[test]
+    fn lunatic_validators_ordered_by_common_voting_power() {
+        let common_validators = [
+            Validator::new("a").voting_power(10),
+            Validator::new("b").voting_power(20),
+            Validator::new("c").voting_power(30),
+        ];
+        // The conflicting header is signed in the reverse order of the common voting powers,
+        // and by a validator not in the common validator set.
+        let conflicting_validators = [
+            Validator::new("a").voting_power(30),
+            Validator::new("b").voting_power(20),
+            Validator::new("c").voting_power(10),
+            Validator::new("d").voting_power(40),
+        ];
+        let common = light_block(&common_validators, 1, 1, &["a", "b", "c"]);
+        let trusted = light_block(&common_validators, 3, 3, &["a", "b", "c"]);
+        let conflicted = light_block(&conflicting_validators, 3, 3, &["a", "b", "c", "d"]);
+
+        let evidence = make_evidence(conflicted, trusted, common);
+
+        assert_eq!(
+            byzantine_validators(&evidence),
+            [(address("c"), 30), (address("b"), 20), (address("a"), 10)]
+        );
+        assert_eq!(evidence.common_height.value(), 1);
+    }
+}
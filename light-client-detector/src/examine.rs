@@ -1,4 +1,3 @@
-use tendermint::{crypto::Sha256, merkle::MerkleHash};
 use tendermint_light_client::{
     state::State,
     store::{memory::MemoryStore, LightStore},
@@ -21,14 +20,11 @@ use super::{error::Error, provider::Provider, trace::Trace};
 //  2. The last block in the trace can not be of a lower height than the target block
 //     trace[len(trace)-1].Height >= targetBlock.Height
 //  3. The last block in the trace is conflicting with the target block
-pub fn examine_conflicting_header_against_trace<H>(
+pub fn examine_conflicting_header_against_trace(
     trace: &Trace,
     target_block: &LightBlock,
     source: &Provider,
-) -> Result<(Trace, LightBlock), Error>
-where
-    H: Sha256 + MerkleHash + Default,
-{
+) -> Result<(Trace, LightBlock), Error> {
     let trusted_block = trace.first();
 
     if target_block.height() < trusted_block.height() {
@@ -38,11 +34,10 @@ where
         ));
     };
 
-    let mut previously_verified_block =
-        check_trusted_block::<H>(source, trusted_block, target_block)?;
+    let mut previously_verified_block = check_trusted_block(source, trusted_block, target_block)?;
 
     for trace_block in trace.iter().skip(1) {
-        let result = examine_conflicting_header_against_trace_block::<H>(
+        let result = examine_conflicting_header_against_trace_block(
             source,
             trace_block,
             target_block,
@@ -73,14 +68,11 @@ pub enum ExaminationResult {
     Divergence(Trace, LightBlock),
 }
 
-fn check_trusted_block<H>(
+fn check_trusted_block(
     source: &Provider,
     trusted_block: &LightBlock,
     target_block: &LightBlock,
-) -> Result<LightBlock, Error>
-where
-    H: Sha256 + MerkleHash + Default,
-{
+) -> Result<LightBlock, Error> {
     // This case only happens in a forward lunatic attack. We treat the block with the
     // height directly after the targetBlock as the divergent block
     if trusted_block.height() > target_block.height() {
@@ -105,8 +97,8 @@ where
             .map_err(Error::light_client)?
     };
 
-    let source_block_hash = source_block.signed_header.header.hash_with::<H>();
-    let trace_block_hash = trusted_block.signed_header.header.hash_with::<H>();
+    let source_block_hash = source.header_hash(&source_block.signed_header.header);
+    let trace_block_hash = source.header_hash(&trusted_block.signed_header.header);
 
     // the first block in the trace MUST be the same to the light block that the source produces
     // else we cannot continue with verification.
@@ -122,15 +114,12 @@ where
 
 // check of primary is same as witness block at that height
 
-fn examine_conflicting_header_against_trace_block<H>(
+fn examine_conflicting_header_against_trace_block(
     source: &Provider,
     trace_block: &LightBlock,
     target_block: &LightBlock,
     prev_verified_block: LightBlock,
-) -> Result<ExaminationResult, Error>
-where
-    H: Sha256 + MerkleHash + Default,
-{
+) -> Result<ExaminationResult, Error> {
     // This case only happens in a forward lunatic attack. We treat the block with the
     // height directly after the targetBlock as the divergent block
     if trace_block.height() > target_block.height() {
@@ -166,8 +155,8 @@ where
             .map_err(Error::light_client)?
     };
 
-    let source_block_hash = source_block.signed_header.header.hash_with::<H>();
-    let trace_block_hash = trace_block.signed_header.header.hash_with::<H>();
+    let source_block_hash = source.header_hash(&source_block.signed_header.header);
+    let trace_block_hash = source.header_hash(&trace_block.signed_header.header);
 
     // we check that the source provider can verify a block at the same height of the
     // intermediate height
@@ -3,16 +3,26 @@
 //! See [`detect_divergence`] for the main entry point.
 
 mod conflict;
+mod cross_check;
 mod detect;
 mod error;
 mod evidence;
 mod examine;
 mod provider;
+mod submit;
 mod trace;
 
-pub use conflict::gather_evidence_from_conflicting_headers;
+#[doc(hidden)]
+pub mod simulation;
+
+pub use conflict::{gather_evidence_from_conflicting_headers, GatheredEvidence};
+pub use cross_check::{
+    cross_check_witnesses, CrossCheck, Disagreement, QuorumPolicy, Verdict, WitnessReport,
+};
 pub use detect::{compare_new_header_with_witness, detect_divergence, CompareError, Divergence};
 pub use error::{Error, ErrorDetail};
+pub use evidence::make_evidence;
 pub use provider::Provider;
+pub use submit::Submission;
 pub use tendermint::evidence::{Evidence, LightClientAttackEvidence};
 pub use trace::Trace;
@@ -1,3 +1,4 @@
+use tendermint::block::Header;
 use tendermint::block::Height;
 use tendermint::evidence::Evidence;
 use tendermint::hash::Hash;
@@ -34,6 +35,14 @@ impl Provider {
         self.instance.peer_id()
     }
 
+    /// The hash of the given header, as computed by the header hasher of the light client
+    /// (see [`LightClient::with_header_hasher`]).
+    ///
+    /// [`LightClient::with_header_hasher`]: tendermint_light_client::light_client::LightClient::with_header_hasher
+    pub fn header_hash(&self, header: &Header) -> Hash {
+        self.instance.light_client.header_hasher().hash(header)
+    }
+
     pub async fn report_evidence(&self, evidence: Evidence) -> Result<Hash, RpcError> {
         self.rpc_client
             .broadcast_evidence(evidence)
@@ -0,0 +1,229 @@
+//! A deterministic simulation harness, for reproducing attack scenarios on a
+//! light client in unit tests.
+//!
+//! A [`Simulation`] drives a light client step by step (see [`Step`]): it
+//! verifies the blocks of a primary as of the time of a [`VirtualClock`], and
+//! cross-checks the verified headers with witnesses with [`detect_divergence`],
+//! gathering the evidence of the attacks it finds. The primary and the
+//! witnesses are [`ScriptedProvider`]s, whose chains can be extended, and
+//! which can be made to withhold blocks or to equivocate, between the steps.
+//!
+//! Nothing runs in the background and no real time passes, so a scenario
+//! always unfolds the same way.
+//!
+//! This module is meant for tests only, and is not part of the stable API.
+
+use std::time::Duration;
+
+use tendermint_light_client::{
+    components::scheduler,
+    errors::Error as LightClientError,
+    instance::Instance,
+    light_client::LightClient,
+    simulation::{ScriptedProvider, VirtualClock},
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    verifier::{
+        options::Options,
+        types::{Height, LightBlock, PeerId, Status, Time},
+        ProdVerifier,
+    },
+};
+use tendermint_rpc::HttpClient;
+
+use crate::{
+    detect::{detect_divergence, Divergence},
+    error::Error,
+    provider::Provider,
+};
+
+/// The chain ID of the providers of a simulation.
+const CHAIN_ID: &str = "test-chain";
+
+/// The address of the RPC client of the providers of a simulation, which is
+/// never contacted as the evidence found is not submitted.
+const RPC_ADDRESS: &str = "http://127.0.0.1:26657";
+
+/// A step of a [`Simulation`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// Advance the clock by the given duration.
+    Advance(Duration),
+    /// Verify the block of the primary at the given height, and cross-check
+    /// it with the witnesses.
+    VerifyTo(Height),
+    /// Verify the latest block of the primary, and cross-check it with the
+    /// witnesses.
+    VerifyToHighest,
+}
+
+/// A witness with a block conflicting with the one verified by the light
+/// client.
+#[derive(Clone, Debug)]
+pub struct Conflict {
+    /// The witness
+    pub witness: PeerId,
+    /// The divergence found between the primary and the witness, with the
+    /// evidence gathered from it
+    pub divergence: Divergence,
+}
+
+/// The outcome of verifying a block of the primary and cross-checking it with
+/// the witnesses.
+#[derive(Debug)]
+pub struct Report {
+    /// The block verified by the light client
+    pub verified: LightBlock,
+    /// The witnesses with a conflicting block
+    pub conflicts: Vec<Conflict>,
+    /// The witnesses for which the cross-check failed, e.g. because they
+    /// failed to serve their block
+    pub failures: Vec<(PeerId, Error)>,
+}
+
+/// The outcome of a [`Step`].
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum Outcome {
+    /// The clock was advanced to the given time.
+    Advanced(Time),
+    /// A block of the primary was verified, and cross-checked with the
+    /// witnesses.
+    Verified(Report),
+    /// The light client failed to verify a block of the primary.
+    Failed(LightClientError),
+}
+
+/// Drives a light client verifying the blocks of a primary and
+/// cross-checking them with witnesses, one [`Step`] at a time.
+///
+/// The witnesses are not waited for when they lag behind the primary, as no
+/// real time passes, so a lagging witness fails the cross-check.
+#[derive(Debug)]
+pub struct Simulation {
+    options: Options,
+    trusted_block: LightBlock,
+    clock: VirtualClock,
+    primary: Provider,
+    witnesses: Vec<Provider>,
+}
+
+impl Simulation {
+    /// A simulation of a light client trusting the given block, verifying
+    /// the blocks of the given primary with the production verifier and the
+    /// bisecting scheduler, as of the time of the given clock.
+    pub fn new(
+        options: Options,
+        trusted_block: LightBlock,
+        clock: VirtualClock,
+        primary: ScriptedProvider,
+    ) -> Self {
+        let primary = provider(options, trusted_block.clone(), clock.clone(), primary);
+
+        Self {
+            options,
+            trusted_block,
+            clock,
+            primary,
+            witnesses: Vec::new(),
+        }
+    }
+
+    /// Cross-check the verified blocks with the given witness too, with a
+    /// light client of the same options trusting the same block.
+    pub fn with_witness(mut self, witness: ScriptedProvider) -> Self {
+        self.witnesses.push(provider(
+            self.options,
+            self.trusted_block.clone(),
+            self.clock.clone(),
+            witness,
+        ));
+        self
+    }
+
+    pub fn clock(&self) -> &VirtualClock {
+        &self.clock
+    }
+
+    pub fn primary(&self) -> &Provider {
+        &self.primary
+    }
+
+    /// Run the given step.
+    pub fn step(&mut self, step: Step) -> Outcome {
+        let verified = match step {
+            Step::Advance(duration) => return Outcome::Advanced(self.clock.advance(duration)),
+            Step::VerifyTo(height) => self.primary.verify_to_height(height),
+            Step::VerifyToHighest => self.primary.verify_to_highest(),
+        };
+
+        match verified {
+            Ok(verified) => Outcome::Verified(self.cross_check(verified)),
+            Err(e) => Outcome::Failed(e),
+        }
+    }
+
+    /// Run the given steps in order, and return their outcomes.
+    pub fn run(&mut self, steps: impl IntoIterator<Item = Step>) -> Vec<Outcome> {
+        steps.into_iter().map(|step| self.step(step)).collect()
+    }
+
+    fn cross_check(&mut self, verified: LightBlock) -> Report {
+        let primary_trace = self.primary.get_trace(verified.height());
+        let mut conflicts = Vec::new();
+        let mut failures = Vec::new();
+
+        for witness in &mut self.witnesses {
+            let divergence = futures::executor::block_on(detect_divergence(
+                Some(&self.primary),
+                witness,
+                primary_trace.clone(),
+                Duration::ZERO,
+                Duration::ZERO,
+            ));
+
+            match divergence {
+                Ok(None) => {},
+                Ok(Some(divergence)) => conflicts.push(Conflict {
+                    witness: *witness.peer_id(),
+                    divergence,
+                }),
+                Err(e) => failures.push((*witness.peer_id(), e)),
+            }
+        }
+
+        Report {
+            verified,
+            conflicts,
+            failures,
+        }
+    }
+}
+
+// A provider of the given scripted blocks, whose light client trusts the
+// given block.
+fn provider(
+    options: Options,
+    mut trusted_block: LightBlock,
+    clock: VirtualClock,
+    io: ScriptedProvider,
+) -> Provider {
+    trusted_block.provider = io.peer_id();
+    let mut light_store = MemoryStore::new();
+    light_store.insert(trusted_block, Status::Trusted);
+
+    let light_client = LightClient::new(
+        io.peer_id(),
+        options,
+        clock,
+        scheduler::basic_bisecting_schedule,
+        ProdVerifier::default(),
+        io,
+    );
+
+    Provider::new(
+        CHAIN_ID.to_owned(),
+        Instance::new(light_client, State::new(light_store)),
+        HttpClient::new(RPC_ADDRESS).unwrap(),
+    )
+}
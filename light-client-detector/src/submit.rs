@@ -0,0 +1,273 @@
+use tendermint::{
+    evidence::{Evidence, LightClientAttackEvidence},
+    node, Hash,
+};
+use tendermint_rpc::Error as RpcError;
+use tracing::{info, warn};
+
+use super::{cross_check::CrossCheck, provider::Provider};
+
+/// The outcome of submitting a piece of evidence to a node, as passed to the callback given
+/// to [`CrossCheck::submit_evidence`].
+#[derive(Debug)]
+pub struct Submission {
+    /// The node the evidence was submitted to.
+    pub peer_id: node::Id,
+    /// The node the evidence was gathered against, i.e. which provided the conflicting header.
+    pub against: node::Id,
+    /// The evidence of the attack.
+    pub evidence: LightClientAttackEvidence,
+    /// The hash of the evidence as returned by `/broadcast_evidence`, or the error which
+    /// prevented its submission.
+    pub outcome: Result<Hash, RpcError>,
+}
+
+impl CrossCheck {
+    /// Submit the evidence of each divergence found by the cross-check (see
+    /// [`CrossCheck::evidence`]) to the primary and to all the witnesses which completed it,
+    /// via `/broadcast_evidence`, and pass the outcome of each submission to `on_submission`.
+    ///
+    /// Both the evidence against the primary and, if it could be gathered, the evidence
+    /// against the diverging witness are submitted, since the correct nodes are the ones
+    /// which will act upon it, and which they are is not known.
+    ///
+    /// Returns the number of pieces of evidence which were successfully submitted.
+    pub async fn submit_evidence(
+        &self,
+        primary: &Provider,
+        mut on_submission: impl FnMut(Submission),
+    ) -> usize {
+        let nodes: Vec<&Provider> = std::iter::once(primary)
+            .chain(self.reports.iter().filter_map(|r| r.witness.as_ref()))
+            .collect();
+
+        let mut submitted = 0;
+        for (against, evidence) in self.evidence(*primary.peer_id()) {
+            for node in &nodes {
+                let outcome = node.report_evidence(Evidence::from(evidence.clone())).await;
+
+                match &outcome {
+                    Ok(hash) => {
+                        info!(node = %node.peer_id(), %against, %hash, "Submitted evidence");
+                        submitted += 1;
+                    },
+                    Err(e) => {
+                        warn!(node = %node.peer_id(), %against, "Failed to submit evidence: {e}");
+                    },
+                }
+
+                on_submission(Submission {
+                    peer_id: *node.peer_id(),
+                    against,
+                    evidence: evidence.clone(),
+                    outcome,
+                });
+            }
+        }
+
+        submitted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use tendermint_light_client::{
+        components::scheduler,
+        instance::Instance,
+        light_client::LightClient,
+        simulation::{ScriptedProvider, VirtualClock},
+        state::State,
+        store::{memory::MemoryStore, LightStore},
+        verifier::{
+            options::Options,
+            types::{LightBlock, Status},
+            ProdVerifier,
+        },
+    };
+    use tendermint_rpc::{
+        dialect::v0_37,
+        endpoint::evidence,
+        server::{Server, ServerHandle},
+        HttpClient, Method, ResponseError,
+    };
+    use tendermint_testgen::{
+        helpers::get_time, light_block::LightBlock as TestgenLightBlock, Generator,
+    };
+    use tokio::runtime::Runtime;
+
+    use super::*;
+    use crate::{
+        cross_check::{Verdict, WitnessReport},
+        simulation::{Outcome, Simulation, Step},
+        Divergence,
+    };
+
+    // The evidence received by a fake node, as JSON.
+    type Received = Arc<Mutex<Vec<serde_json::Value>>>;
+
+    fn light_block(height: u64, time_offset: Duration) -> LightBlock {
+        let time = (get_time(height).unwrap() + time_offset).unwrap();
+        LightBlock::from(
+            TestgenLightBlock::new_default_with_time_and_chain_id(
+                "test-chain".to_owned(),
+                time,
+                height,
+            )
+            .generate()
+            .unwrap(),
+        )
+    }
+
+    fn peer_id(n: u8) -> node::Id {
+        node::Id::new([n; 20])
+    }
+
+    // The divergence found by a witness with a primary equivocating on the block at height 10.
+    fn divergence() -> Divergence {
+        let light_blocks: Vec<_> = (1..=10).map(|h| light_block(h, Duration::ZERO)).collect();
+        let primary = ScriptedProvider::new(peer_id(1), light_blocks.clone());
+        primary.equivocate(light_block(10, Duration::from_millis(500)));
+        let witness = ScriptedProvider::new(peer_id(2), light_blocks);
+
+        let mut simulation = Simulation::new(
+            Options {
+                trust_threshold: Default::default(),
+                trusting_period: Duration::from_secs(60 * 60),
+                clock_drift: Duration::from_secs(10),
+            },
+            light_block(1, Duration::ZERO),
+            VirtualClock::new(get_time(11).unwrap()),
+            primary,
+        )
+        .with_witness(witness);
+
+        match simulation.step(Step::VerifyToHighest) {
+            Outcome::Verified(mut report) => report.conflicts.remove(0).divergence,
+            outcome => panic!("unexpected outcome: {outcome:?}"),
+        }
+    }
+
+    // A fake node recording the evidence it receives, which it rejects if told to.
+    fn serve(rt: &Runtime, reject: bool) -> (ServerHandle, Received) {
+        let received = Received::default();
+        let recorded = received.clone();
+        let server = Server::new().on_raw(Method::BroadcastEvidence.as_str(), move |params| {
+            recorded.lock().unwrap().push(params["evidence"].clone());
+            if reject {
+                return Err(ResponseError::server_error("invalid evidence"));
+            }
+            Ok(serde_json::to_value(evidence::Response {
+                hash: Hash::Sha256([1; 32]),
+            })
+            .unwrap())
+        });
+
+        let _guard = rt.enter();
+        (server.bind(([127, 0, 0, 1], 0).into()).unwrap(), received)
+    }
+
+    // A provider whose RPC client is connected to the given fake node.
+    fn provider(peer_id: node::Id, server: &ServerHandle) -> Provider {
+        let mut light_store = MemoryStore::new();
+        light_store.insert(light_block(1, Duration::ZERO), Status::Trusted);
+        let light_client = LightClient::new(
+            peer_id,
+            Options {
+                trust_threshold: Default::default(),
+                trusting_period: Duration::from_secs(60 * 60),
+                clock_drift: Duration::from_secs(10),
+            },
+            VirtualClock::new(get_time(11).unwrap()),
+            scheduler::basic_bisecting_schedule,
+            ProdVerifier::default(),
+            ScriptedProvider::new(peer_id, vec![]),
+        );
+
+        Provider::new(
+            "test-chain".to_owned(),
+            Instance::new(light_client, State::new(light_store)),
+            HttpClient::new(server.http_url().as_str()).unwrap(),
+        )
+    }
+
+    fn json(evidence: &LightClientAttackEvidence) -> serde_json::Value {
+        serde_json::to_value(v0_37::Evidence::from(Evidence::from(evidence.clone()))).unwrap()
+    }
+
+    #[test]
+    fn submits_evidence_to_primary_and_completed_witnesses() {
+        let rt = Runtime::new().unwrap();
+        let divergence = divergence();
+        let against_primary = divergence.evidence.against_primary.clone();
+        let against_witness = divergence.evidence.against_witness.clone().unwrap();
+
+        let (primary_server, primary_received) = serve(&rt, false);
+        let (diverging_server, diverging_received) = serve(&rt, false);
+        let (faulty_server, faulty_received) = serve(&rt, true);
+        let primary = provider(peer_id(1), &primary_server);
+
+        let report = |n, verdict, server| WitnessReport {
+            peer_id: peer_id(n),
+            verdict,
+            elapsed: Duration::ZERO,
+            witness: Some(provider(peer_id(n), server)),
+        };
+        let cross_check = CrossCheck {
+            reports: vec![
+                report(
+                    2,
+                    Verdict::Conflict(Box::new(divergence)),
+                    &diverging_server,
+                ),
+                report(3, Verdict::Agree, &faulty_server),
+                // A witness which timed out is left out.
+                WitnessReport {
+                    peer_id: peer_id(4),
+                    verdict: Verdict::Timeout,
+                    elapsed: Duration::ZERO,
+                    witness: None,
+                },
+            ],
+        };
+
+        let mut submissions = Vec::new();
+        let submitted = rt.block_on(
+            cross_check.submit_evidence(&primary, |submission| submissions.push(submission)),
+        );
+
+        // Both pieces of evidence are submitted to each of the three nodes, one of which
+        // rejects them.
+        assert_eq!(submitted, 4);
+        let submitted_to: Vec<_> = submissions
+            .iter()
+            .map(|s| (s.peer_id, s.against, s.outcome.is_ok()))
+            .collect();
+        assert_eq!(
+            submitted_to,
+            [
+                (peer_id(1), peer_id(1), true),
+                (peer_id(2), peer_id(1), true),
+                (peer_id(3), peer_id(1), false),
+                (peer_id(1), peer_id(2), true),
+                (peer_id(2), peer_id(2), true),
+                (peer_id(3), peer_id(2), false),
+            ]
+        );
+        assert_eq!(submissions[0].evidence, against_primary);
+        assert_eq!(submissions[3].evidence, against_witness);
+
+        let expected = [json(&against_primary), json(&against_witness)];
+        for received in [primary_received, diverging_received, faulty_received] {
+            assert_eq!(*received.lock().unwrap(), expected);
+        }
+
+        for server in [primary_server, diverging_server, faulty_server] {
+            rt.block_on(server.shutdown()).unwrap();
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use tendermint_light_client::{
+    components::io::{AtHeight, IoErrorDetail},
+    errors::ErrorDetail,
+    simulation::{ScriptedProvider, VirtualClock},
+    verifier::{
+        options::Options,
+        types::{Height, LightBlock, PeerId},
+    },
+};
+use tendermint_light_client_detector::{
+    simulation::{Outcome, Report, Simulation, Step},
+    ErrorDetail as DetectorErrorDetail,
+};
+use tendermint_testgen::{
+    helpers::get_time, light_block::LightBlock as TestgenLightBlock, Generator,
+};
+
+const TRUSTING_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+fn light_block(height: u64, time_offset: Duration) -> LightBlock {
+    let time = (get_time(height).unwrap() + time_offset).unwrap();
+    LightBlock::from(
+        TestgenLightBlock::new_default_with_time_and_chain_id(
+            "test-chain".to_owned(),
+            time,
+            height,
+        )
+        .generate()
+        .unwrap(),
+    )
+}
+
+fn light_blocks(heights: std::ops::RangeInclusive<u64>) -> Vec<LightBlock> {
+    heights.map(|h| light_block(h, Duration::ZERO)).collect()
+}
+
+fn peer_id(n: u8) -> PeerId {
+    PeerId::new([n; 20])
+}
+
+// A simulation of a light client trusting the block at height 1 of the primary,
+// as of a clock just after the block at height 10.
+fn simulation(primary: ScriptedProvider, witnesses: Vec<ScriptedProvider>) -> Simulation {
+    let trusted_block = light_block(1, Duration::ZERO);
+    let clock = VirtualClock::new(get_time(11).unwrap());
+    let options = Options {
+        trust_threshold: Default::default(),
+        trusting_period: TRUSTING_PERIOD,
+        clock_drift: Duration::from_secs(10),
+    };
+
+    witnesses.into_iter().fold(
+        Simulation::new(options, trusted_block, clock, primary),
+        Simulation::with_witness,
+    )
+}
+
+fn verified(outcome: Outcome) -> Report {
+    match outcome {
+        Outcome::Verified(report) => report,
+        outcome => panic!("unexpected outcome: {outcome:?}"),
+    }
+}
+
+#[test]
+fn equivocating_primary_is_caught_by_witness() {
+    let primary = ScriptedProvider::new(peer_id(1), light_blocks(1..=10));
+    let witness = ScriptedProvider::new(peer_id(2), light_blocks(1..=10));
+    // A block signed by the same validators, but for another header.
+    primary.equivocate(light_block(10, Duration::from_millis(500)));
+    let mut simulation = simulation(primary.clone(), vec![witness]);
+
+    let report = verified(simulation.step(Step::VerifyToHighest));
+
+    assert_eq!(report.verified.height().value(), 10);
+    assert_eq!(report.verified.provider, peer_id(1));
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].witness, peer_id(2));
+    let divergence = &report.conflicts[0].divergence;
+    assert_ne!(
+        divergence.challenging_block.signed_header.header.hash(),
+        report.verified.signed_header.header.hash()
+    );
+    // The evidence against the primary is the header it equivocated on.
+    assert_eq!(
+        divergence
+            .evidence
+            .against_primary
+            .conflicting_block
+            .signed_header,
+        report.verified.signed_header
+    );
+    assert_eq!(primary.fetches()[0], AtHeight::Highest);
+}
+
+#[test]
+fn withheld_block_is_verified_once_released() {
+    let primary = ScriptedProvider::new(peer_id(1), light_blocks(1..=10));
+    let witness = ScriptedProvider::new(peer_id(2), light_blocks(1..=10));
+    primary.withhold(Height::from(5_u32));
+    let mut simulation = simulation(primary.clone(), vec![witness]);
+
+    let e = match simulation.step(Step::VerifyTo(Height::from(5_u32))) {
+        Outcome::Failed(e) => e,
+        outcome => panic!("unexpected outcome: {outcome:?}"),
+    };
+    assert!(matches!(
+        e.detail(),
+        ErrorDetail::Io(e) if matches!(e.source, IoErrorDetail::RequestTimeout(_))
+    ));
+
+    primary.release(Height::from(5_u32));
+    let report = verified(simulation.step(Step::VerifyTo(Height::from(5_u32))));
+
+    assert_eq!(report.verified.height().value(), 5);
+    assert!(report.conflicts.is_empty());
+    assert!(report.failures.is_empty());
+}
+
+#[test]
+fn lagging_witness_is_reported_until_it_catches_up() {
+    let primary = ScriptedProvider::new(peer_id(1), light_blocks(1..=5));
+    let witness = ScriptedProvider::new(peer_id(2), light_blocks(1..=4));
+    let mut simulation = simulation(primary.clone(), vec![witness.clone()]);
+
+    let report = verified(simulation.step(Step::VerifyToHighest));
+    assert_eq!(report.verified.height().value(), 5);
+    assert_eq!(report.failures.len(), 1);
+    assert!(matches!(
+        report.failures[0].1.detail(),
+        DetectorErrorDetail::BadWitness(_)
+    ));
+
+    primary.extend(light_blocks(6..=10));
+    witness.extend(light_blocks(5..=10));
+    let report = verified(simulation.step(Step::VerifyToHighest));
+    assert_eq!(report.verified.height().value(), 10);
+    assert!(report.failures.is_empty());
+}
+
+#[test]
+fn trusted_state_expires_with_the_clock() {
+    let primary = ScriptedProvider::new(peer_id(1), light_blocks(1..=10));
+    let mut simulation = simulation(primary, vec![]);
+
+    let outcomes = simulation.run([
+        Step::Advance(TRUSTING_PERIOD),
+        Step::VerifyTo(Height::from(10_u32)),
+    ]);
+
+    assert!(matches!(
+        &outcomes[0],
+        Outcome::Advanced(now) if *now == (get_time(11).unwrap() + TRUSTING_PERIOD).unwrap()
+    ));
+    assert!(matches!(
+        &outcomes[1],
+        Outcome::Failed(e) if matches!(e.detail(), ErrorDetail::TrustedStateOutsideTrustingPeriod(_))
+    ));
+}
@@ -4,7 +4,7 @@ use core::time::Duration;
 
 use flex_error::define_error;
 use serde::{Deserialize, Serialize};
-use tendermint::{account::Id, Error as TendermintError};
+use tendermint::{account::Id, secret::Sensitive, Error as TendermintError};
 
 use crate::{
     operations::voting_power::VotingPowerTally,
@@ -66,7 +66,7 @@ define_error! {
             {
                 signature: Vec<u8>,
                 validator: Box<Validator>,
-                sign_bytes: Vec<u8>,
+                sign_bytes: Sensitive<Vec<u8>>,
             }
             | e | {
                 format_args!("failed to verify signature `{:?}` with validator `{:?}` on sign_bytes `{:?}`",
@@ -174,6 +174,24 @@ define_error! {
     }
 }
 
+define_error! {
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    OptionsError {
+        ZeroTrustingPeriod
+            | _ | { "the trusting period must not be zero" },
+
+        ClockDriftTooLarge
+            {
+                clock_drift: Duration,
+                trusting_period: Duration,
+            }
+            | e | {
+                format_args!("the clock drift ({0:?}) must be shorter than the trusting period ({1:?})",
+                    e.clock_drift, e.trusting_period)
+            },
+    }
+}
+
 /// Extension methods for `ErrorKind`
 pub trait ErrorExt {
     /// Whether this error means that the light block
@@ -1,3 +1,16 @@
+//! The stateless verification core of the Tendermint light client: the
+//! [`predicates`] checked when verifying a header against a trusted state, the
+//! verification of commits and the tallying of their voting power (see
+//! [`operations`]), and the [`Verifier`] combining them.
+//!
+//! This crate is `no_std` and does no I/O, so that the exact same verification
+//! code can be reused by the light client, by IBC light clients running in
+//! smart contracts, and in browsers. It builds for `wasm32-unknown-unknown`
+//! with `default-features = false`, optionally with the `rust-crypto` feature
+//! providing the [`ProdVerifier`], and does not depend on an async runtime, a
+//! database or a clock: the current time is passed to the [`Verifier`] by the
+//! caller.
+
 #![no_std]
 
 extern crate alloc;
@@ -5,3 +5,6 @@ pub use self::voting_power::*;
 
 pub mod commit_validator;
 pub use self::commit_validator::*;
+
+pub mod header_hasher;
+pub use self::header_hasher::*;
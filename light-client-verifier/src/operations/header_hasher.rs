@@ -0,0 +1,85 @@
+//! Provides an interface and default implementation for the `HeaderHasher` operation
+
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use core::{fmt, marker::PhantomData};
+
+use tendermint::{crypto::Sha256, merkle::MerkleHash};
+
+use crate::types::{Hash, Header};
+
+/// Computes the hash of a header, which the commit of its block signs.
+///
+/// Chains which add fields to the header, or hash it differently, can plug their
+/// own hasher into a verifier with [`PredicateVerifier::with_header_hasher`].
+///
+/// [`PredicateVerifier::with_header_hasher`]: crate::PredicateVerifier::with_header_hasher
+pub trait HeaderHasher: Send + Sync {
+    /// The hash of the given header.
+    fn hash(&self, header: &Header) -> Hash;
+}
+
+/// Lets a hasher be shared by the components which hash headers, e.g. the
+/// verifier and the I/O of a light client.
+#[cfg(target_has_atomic = "ptr")]
+impl<T: HeaderHasher + ?Sized> HeaderHasher for Arc<T> {
+    fn hash(&self, header: &Header) -> Hash {
+        (**self).hash(header)
+    }
+}
+
+/// Default implementation of a `HeaderHasher`, computing the canonical
+/// Tendermint hash of a header (see [`Header::hash_with`]), parameterized with
+/// the implementation of SHA256.
+pub struct ProvidedHeaderHasher<H> {
+    _sha256: PhantomData<H>,
+}
+
+// Safety: the only member is phantom data
+unsafe impl<H> Send for ProvidedHeaderHasher<H> {}
+unsafe impl<H> Sync for ProvidedHeaderHasher<H> {}
+
+// Implemented by hand, as deriving them would require the SHA256
+// implementation to implement them too.
+impl<H> Default for ProvidedHeaderHasher<H> {
+    fn default() -> Self {
+        Self {
+            _sha256: PhantomData,
+        }
+    }
+}
+
+impl<H> Clone for ProvidedHeaderHasher<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H> Copy for ProvidedHeaderHasher<H> {}
+
+impl<H> PartialEq for ProvidedHeaderHasher<H> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<H> Eq for ProvidedHeaderHasher<H> {}
+
+impl<H> fmt::Debug for ProvidedHeaderHasher<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProvidedHeaderHasher")
+    }
+}
+
+impl<H> HeaderHasher for ProvidedHeaderHasher<H>
+where
+    H: MerkleHash + Sha256 + Default,
+{
+    fn hash(&self, header: &Header) -> Hash {
+        header.hash_with::<H>()
+    }
+}
+
+/// Default implementation of a `HeaderHasher`.
+#[cfg(feature = "rust-crypto")]
+pub type ProdHeaderHasher = ProvidedHeaderHasher<tendermint::crypto::default::Sha256>;
@@ -14,7 +14,7 @@ use tendermint::{
 use crate::{
     errors::VerificationError,
     prelude::*,
-    types::{Commit, SignedHeader, TrustThreshold, ValidatorSet},
+    types::{Commit, SignedHeader, TrustThreshold, Validator, ValidatorSet},
 };
 
 /// Tally for the voting power computed by the `VotingPowerCalculator`
@@ -41,6 +41,16 @@ impl fmt::Display for VotingPowerTally {
 /// Computes the voting power in a commit against a validator set.
 ///
 /// This trait provides default implementation of some helper functions.
+///
+/// Chains whose validators do not weigh as much as their voting power can plug a
+/// [`VoteWeight`] into the [`ProvidedVotingPowerCalculator`], while those with a different
+/// threshold arithmetic can override [`check_enough_trust`] and [`check_signers_overlap`].
+/// A calculator is plugged into a verifier with
+/// [`PredicateVerifier::with_voting_power_calculator`].
+///
+/// [`check_enough_trust`]: VotingPowerCalculator::check_enough_trust
+/// [`check_signers_overlap`]: VotingPowerCalculator::check_signers_overlap
+/// [`PredicateVerifier::with_voting_power_calculator`]: crate::PredicateVerifier::with_voting_power_calculator
 pub trait VotingPowerCalculator: Send + Sync {
     /// Compute the total voting power in a validator set
     fn total_power_of(&self, validator_set: &ValidatorSet) -> u64 {
@@ -61,11 +71,7 @@ pub trait VotingPowerCalculator: Send + Sync {
         let voting_power =
             self.voting_power_in(untrusted_header, trusted_validators, trust_threshold)?;
 
-        if trust_threshold.is_enough_power(voting_power.tallied, voting_power.total) {
-            Ok(())
-        } else {
-            Err(VerificationError::not_enough_trust(voting_power))
-        }
+        ensure_enough_trust(voting_power, trust_threshold)
     }
 
     /// Check if there is 2/3rd overlap between an untrusted header and untrusted validator set
@@ -74,17 +80,13 @@ pub trait VotingPowerCalculator: Send + Sync {
         untrusted_header: &SignedHeader,
         untrusted_validators: &ValidatorSet,
     ) -> Result<(), VerificationError> {
-        let trust_threshold = TrustThreshold::TWO_THIRDS;
-        let voting_power =
-            self.voting_power_in(untrusted_header, untrusted_validators, trust_threshold)?;
+        let voting_power = self.voting_power_in(
+            untrusted_header,
+            untrusted_validators,
+            TrustThreshold::TWO_THIRDS,
+        )?;
 
-        if trust_threshold.is_enough_power(voting_power.tallied, voting_power.total) {
-            Ok(())
-        } else {
-            Err(VerificationError::insufficient_signers_overlap(
-                voting_power,
-            ))
-        }
+        ensure_signers_overlap(voting_power)
     }
 
     /// Compute the voting power in a header and its commit against a validator set.
@@ -99,31 +101,276 @@ pub trait VotingPowerCalculator: Send + Sync {
     ) -> Result<VotingPowerTally, VerificationError>;
 }
 
+fn ensure_enough_trust(
+    voting_power: VotingPowerTally,
+    trust_threshold: TrustThreshold,
+) -> Result<(), VerificationError> {
+    if trust_threshold.is_enough_power(voting_power.tallied, voting_power.total) {
+        Ok(())
+    } else {
+        Err(VerificationError::not_enough_trust(voting_power))
+    }
+}
+
+fn ensure_signers_overlap(voting_power: VotingPowerTally) -> Result<(), VerificationError> {
+    if TrustThreshold::TWO_THIRDS.is_enough_power(voting_power.tallied, voting_power.total) {
+        Ok(())
+    } else {
+        Err(VerificationError::insufficient_signers_overlap(
+            voting_power,
+        ))
+    }
+}
+
+impl<C: VotingPowerCalculator + ?Sized> VotingPowerCalculator for Box<C> {
+    fn total_power_of(&self, validator_set: &ValidatorSet) -> u64 {
+        (**self).total_power_of(validator_set)
+    }
+
+    fn check_enough_trust(
+        &self,
+        untrusted_header: &SignedHeader,
+        trusted_validators: &ValidatorSet,
+        trust_threshold: TrustThreshold,
+    ) -> Result<(), VerificationError> {
+        (**self).check_enough_trust(untrusted_header, trusted_validators, trust_threshold)
+    }
+
+    fn check_signers_overlap(
+        &self,
+        untrusted_header: &SignedHeader,
+        untrusted_validators: &ValidatorSet,
+    ) -> Result<(), VerificationError> {
+        (**self).check_signers_overlap(untrusted_header, untrusted_validators)
+    }
+
+    fn voting_power_in(
+        &self,
+        signed_header: &SignedHeader,
+        validator_set: &ValidatorSet,
+        trust_threshold: TrustThreshold,
+    ) -> Result<VotingPowerTally, VerificationError> {
+        (**self).voting_power_in(signed_header, validator_set, trust_threshold)
+    }
+}
+
+/// How much a validator weighs in the tallies of a [`ProvidedVotingPowerCalculator`].
+pub trait VoteWeight: Send + Sync {
+    /// The weight of the given validator.
+    fn weight_of(&self, validator: &Validator) -> u64;
+}
+
+/// Validators weigh as much as their voting power, as per the Tendermint rules.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ByVotingPower;
+
+impl VoteWeight for ByVotingPower {
+    fn weight_of(&self, validator: &Validator) -> u64 {
+        validator.power()
+    }
+}
+
+/// All the validators weigh the same whatever their voting power, e.g. on
+/// proof-of-authority chains.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EqualWeight;
+
+impl VoteWeight for EqualWeight {
+    fn weight_of(&self, _validator: &Validator) -> u64 {
+        1
+    }
+}
+
+/// A chain-specific strategy for verifying the signatures of a commit, for
+/// chains which do not (or not always) sign commits with one signature per
+/// validator, e.g. because they aggregate the signatures of the validators.
+///
+/// Plugged into a [`VotingPowerCalculator`] with
+/// [`VotingPowerCalculatorExt::with_commit_verification`].
+///
+/// Note that the commits are validated by the
+/// [`CommitValidator`](super::CommitValidator) of the verifier before their
+/// voting power is tallied. The default one requires a signature slot per
+/// validator, so chains whose commits are shaped differently need a custom
+/// `CommitValidator` as well.
+pub trait CommitVerificationStrategy: Send + Sync {
+    /// Verify the commit of the given header against the given validator set
+    /// and tally the voting power of the validators which signed it.
+    ///
+    /// Returns `None` for commits the strategy does not apply to, which are
+    /// verified as per the default Tendermint rules instead.
+    fn voting_power_in(
+        &self,
+        signed_header: &SignedHeader,
+        validator_set: &ValidatorSet,
+        trust_threshold: TrustThreshold,
+    ) -> Option<Result<VotingPowerTally, VerificationError>>;
+}
+
+/// Extends [`VotingPowerCalculator`]s with chain-specific commit
+/// verification strategies.
+pub trait VotingPowerCalculatorExt: VotingPowerCalculator + Sized {
+    /// Verify the commits the given strategy applies to with the strategy,
+    /// and all others with this calculator.
+    fn with_commit_verification<S>(self, strategy: S) -> WithCommitVerification<Self, S>
+    where
+        S: CommitVerificationStrategy,
+    {
+        WithCommitVerification {
+            calculator: self,
+            strategy,
+        }
+    }
+}
+
+impl<C: VotingPowerCalculator> VotingPowerCalculatorExt for C {}
+
+/// A `VotingPowerCalculator` combining a [`CommitVerificationStrategy`] with
+/// a fallback calculator.
+///
+/// Created by [`VotingPowerCalculatorExt::with_commit_verification`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WithCommitVerification<C, S> {
+    calculator: C,
+    strategy: S,
+}
+
+impl<C, S> WithCommitVerification<C, S> {
+    /// The calculator verifying the commits the strategy does not apply to.
+    pub fn calculator(&self) -> &C {
+        &self.calculator
+    }
+
+    /// The chain-specific commit verification strategy.
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+}
+
+impl<C, S> VotingPowerCalculator for WithCommitVerification<C, S>
+where
+    C: VotingPowerCalculator,
+    S: CommitVerificationStrategy,
+{
+    fn total_power_of(&self, validator_set: &ValidatorSet) -> u64 {
+        self.calculator.total_power_of(validator_set)
+    }
+
+    fn check_enough_trust(
+        &self,
+        untrusted_header: &SignedHeader,
+        trusted_validators: &ValidatorSet,
+        trust_threshold: TrustThreshold,
+    ) -> Result<(), VerificationError> {
+        match self
+            .strategy
+            .voting_power_in(untrusted_header, trusted_validators, trust_threshold)
+        {
+            Some(voting_power) => ensure_enough_trust(voting_power?, trust_threshold),
+            None => self.calculator.check_enough_trust(
+                untrusted_header,
+                trusted_validators,
+                trust_threshold,
+            ),
+        }
+    }
+
+    fn check_signers_overlap(
+        &self,
+        untrusted_header: &SignedHeader,
+        untrusted_validators: &ValidatorSet,
+    ) -> Result<(), VerificationError> {
+        match self.strategy.voting_power_in(
+            untrusted_header,
+            untrusted_validators,
+            TrustThreshold::TWO_THIRDS,
+        ) {
+            Some(voting_power) => ensure_signers_overlap(voting_power?),
+            None => self
+                .calculator
+                .check_signers_overlap(untrusted_header, untrusted_validators),
+        }
+    }
+
+    fn voting_power_in(
+        &self,
+        signed_header: &SignedHeader,
+        validator_set: &ValidatorSet,
+        trust_threshold: TrustThreshold,
+    ) -> Result<VotingPowerTally, VerificationError> {
+        match self
+            .strategy
+            .voting_power_in(signed_header, validator_set, trust_threshold)
+        {
+            Some(result) => result,
+            None => self
+                .calculator
+                .voting_power_in(signed_header, validator_set, trust_threshold),
+        }
+    }
+}
+
 /// Default implementation of a `VotingPowerCalculator`, parameterized with
-/// the signature verification trait.
+/// the signature verification trait, and with the weight of the validators,
+/// which is their voting power by default.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct ProvidedVotingPowerCalculator<V> {
+pub struct ProvidedVotingPowerCalculator<V, W = ByVotingPower> {
+    weight: W,
     _verifier: PhantomData<V>,
 }
 
-// Safety: the only member is phantom data
-unsafe impl<V> Send for ProvidedVotingPowerCalculator<V> {}
-unsafe impl<V> Sync for ProvidedVotingPowerCalculator<V> {}
+// Safety: the only other member is phantom data
+unsafe impl<V, W: Send> Send for ProvidedVotingPowerCalculator<V, W> {}
+unsafe impl<V, W: Sync> Sync for ProvidedVotingPowerCalculator<V, W> {}
 
-impl<V> Default for ProvidedVotingPowerCalculator<V> {
+impl<V, W: Default> Default for ProvidedVotingPowerCalculator<V, W> {
     fn default() -> Self {
         Self {
+            weight: W::default(),
             _verifier: PhantomData,
         }
     }
 }
 
+impl<V, W> ProvidedVotingPowerCalculator<V, W> {
+    /// Weigh the validators with the given weight rather than the current one.
+    pub fn with_weight<X: VoteWeight>(self, weight: X) -> ProvidedVotingPowerCalculator<V, X> {
+        ProvidedVotingPowerCalculator {
+            weight,
+            _verifier: PhantomData,
+        }
+    }
+
+    /// The weight of the validators.
+    pub fn weight(&self) -> &W {
+        &self.weight
+    }
+}
+
 /// Default implementation of a `VotingPowerCalculator`.
 #[cfg(feature = "rust-crypto")]
 pub type ProdVotingPowerCalculator =
     ProvidedVotingPowerCalculator<tendermint::crypto::default::signature::Verifier>;
 
-impl<V: signature::Verifier> VotingPowerCalculator for ProvidedVotingPowerCalculator<V> {
+/// A `VotingPowerCalculator` for chains whose validators all weigh the same.
+#[cfg(feature = "rust-crypto")]
+pub type EqualWeightVotingPowerCalculator =
+    ProvidedVotingPowerCalculator<tendermint::crypto::default::signature::Verifier, EqualWeight>;
+
+impl<V, W> VotingPowerCalculator for ProvidedVotingPowerCalculator<V, W>
+where
+    V: signature::Verifier,
+    W: VoteWeight,
+{
+    fn total_power_of(&self, validator_set: &ValidatorSet) -> u64 {
+        validator_set
+            .validators()
+            .iter()
+            .fold(0u64, |total, val_info| {
+                total + self.weight.weight_of(val_info)
+            })
+    }
+
     fn voting_power_in(
         &self,
         signed_header: &SignedHeader,
@@ -173,13 +420,13 @@ impl<V: signature::Verifier> VotingPowerCalculator for ProvidedVotingPowerCalcul
                 return Err(VerificationError::invalid_signature(
                     signed_vote.signature().as_bytes().to_vec(),
                     Box::new(validator),
-                    sign_bytes,
+                    sign_bytes.into(),
                 ));
             }
 
             // If the vote is neither absent nor nil, tally its power
             if signature.is_commit() {
-                tallied_voting_power += validator.power();
+                tallied_voting_power += self.weight.weight_of(&validator);
             } else {
                 // It's OK. We include stray signatures (~votes for nil)
                 // to measure validator availability.
@@ -243,10 +490,10 @@ fn non_absent_vote(
 // TODO: We plan to add Lightweight MBT for `voting_power_in` in the near future
 #[cfg(test)]
 mod tests {
-    use tendermint::trust_threshold::TrustThresholdFraction;
+    use tendermint::{secret::assert_redacted, trust_threshold::TrustThresholdFraction};
     use tendermint_testgen::{
-        light_block::generate_signed_header, Commit, Generator, Header,
-        LightBlock as TestgenLightBlock, ValidatorSet, Vote as TestgenVote,
+        light_block::generate_signed_header, validator::generate_validators, Commit, Generator,
+        Header, LightBlock as TestgenLightBlock, Validator, ValidatorSet, Vote as TestgenVote,
     };
 
     use super::*;
@@ -258,6 +505,175 @@ mod tests {
         trust_threshold: TrustThresholdFraction::ONE_THIRD,
     };
 
+    /// Accepts commits without any signatures, as if they carried an
+    /// aggregated signature of all the validators.
+    struct AggregatedCommits;
+
+    impl CommitVerificationStrategy for AggregatedCommits {
+        fn voting_power_in(
+            &self,
+            signed_header: &SignedHeader,
+            validator_set: &crate::types::ValidatorSet,
+            trust_threshold: TrustThreshold,
+        ) -> Option<Result<VotingPowerTally, VerificationError>> {
+            if !signed_header.commit.signatures.is_empty() {
+                return None;
+            }
+            let total = validator_set.total_voting_power().value();
+            Some(Ok(VotingPowerTally {
+                total,
+                tallied: total,
+                trust_threshold,
+            }))
+        }
+    }
+
+    #[test]
+    fn test_commit_verification_strategy() {
+        let vp_calculator =
+            ProdVotingPowerCalculator::default().with_commit_verification(AggregatedCommits);
+        let trust_threshold = TrustThreshold::default();
+
+        let mut light_block: LightBlock = TestgenLightBlock::new_default(10)
+            .generate()
+            .unwrap()
+            .into();
+
+        // Commits the strategy does not apply to are verified as usual.
+        let tally = vp_calculator
+            .voting_power_in(
+                &light_block.signed_header,
+                &light_block.validators,
+                trust_threshold,
+            )
+            .unwrap();
+        assert_eq!(tally.tallied, 100);
+
+        // Without the strategy, a commit without signatures carries no power.
+        light_block.signed_header.commit.signatures = vec![];
+        let tally = vp_calculator
+            .calculator()
+            .voting_power_in(
+                &light_block.signed_header,
+                &light_block.validators,
+                trust_threshold,
+            )
+            .unwrap();
+        assert_eq!(tally, EXPECTED_RESULT);
+
+        let tally = vp_calculator
+            .voting_power_in(
+                &light_block.signed_header,
+                &light_block.validators,
+                trust_threshold,
+            )
+            .unwrap();
+        assert_eq!(tally.tallied, 100);
+        assert!(vp_calculator
+            .check_signers_overlap(&light_block.signed_header, &light_block.validators)
+            .is_ok());
+    }
+
+    /// Tallies no voting power, but trusts every header anyway.
+    struct TrustingCalculator;
+
+    impl VotingPowerCalculator for TrustingCalculator {
+        fn check_enough_trust(
+            &self,
+            _untrusted_header: &SignedHeader,
+            _trusted_validators: &crate::types::ValidatorSet,
+            _trust_threshold: TrustThreshold,
+        ) -> Result<(), VerificationError> {
+            Ok(())
+        }
+
+        fn check_signers_overlap(
+            &self,
+            _untrusted_header: &SignedHeader,
+            _untrusted_validators: &crate::types::ValidatorSet,
+        ) -> Result<(), VerificationError> {
+            Ok(())
+        }
+
+        fn voting_power_in(
+            &self,
+            _signed_header: &SignedHeader,
+            _validator_set: &crate::types::ValidatorSet,
+            trust_threshold: TrustThreshold,
+        ) -> Result<VotingPowerTally, VerificationError> {
+            Ok(VotingPowerTally {
+                total: 100,
+                tallied: 0,
+                trust_threshold,
+            })
+        }
+    }
+
+    #[test]
+    fn test_commit_verification_strategy_forwards_checks() {
+        let vp_calculator = TrustingCalculator.with_commit_verification(AggregatedCommits);
+        let trust_threshold = TrustThreshold::default();
+
+        let light_block: LightBlock = TestgenLightBlock::new_default(10)
+            .generate()
+            .unwrap()
+            .into();
+
+        // The checks of the calculator apply to the commits the strategy does
+        // not apply to.
+        assert!(vp_calculator
+            .check_enough_trust(
+                &light_block.signed_header,
+                &light_block.validators,
+                trust_threshold
+            )
+            .is_ok());
+        assert!(vp_calculator
+            .check_signers_overlap(&light_block.signed_header, &light_block.validators)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_equal_weight() {
+        let vals = vec![
+            Validator::new("a").voting_power(10),
+            Validator::new("b").voting_power(10),
+            Validator::new("c").voting_power(10),
+            Validator::new("d").voting_power(100),
+        ];
+        let header = Header::new(&vals).time(tendermint::Time::from_unix_timestamp(1, 0).unwrap());
+        // All the validators but the one with the most voting power sign.
+        let votes = vals[..3]
+            .iter()
+            .map(|val| TestgenVote::new(val.clone(), header.clone()))
+            .collect();
+        let commit = Commit::new_with_votes(header.clone(), 1, votes);
+        let signed_header = generate_signed_header(&header, &commit).unwrap();
+        let valset =
+            crate::types::ValidatorSet::without_proposer(generate_validators(&vals).unwrap());
+
+        let vp_calculator = ProdVotingPowerCalculator::default();
+        assert!(vp_calculator
+            .check_signers_overlap(&signed_header, &valset)
+            .is_err());
+
+        let vp_calculator = EqualWeightVotingPowerCalculator::default();
+        let tally = vp_calculator
+            .voting_power_in(&signed_header, &valset, TrustThreshold::TWO_THIRDS)
+            .unwrap();
+        assert_eq!((tally.tallied, tally.total), (3, 4));
+        assert!(vp_calculator
+            .check_signers_overlap(&signed_header, &valset)
+            .is_ok());
+
+        // Calculators can be used as trait objects.
+        let vp_calculator: Box<dyn VotingPowerCalculator> =
+            Box::new(ProdVotingPowerCalculator::default().with_weight(EqualWeight));
+        assert!(vp_calculator
+            .check_signers_overlap(&signed_header, &valset)
+            .is_ok());
+    }
+
     #[test]
     fn test_empty_signatures() {
         let vp_calculator = ProdVotingPowerCalculator::default();
@@ -346,7 +762,10 @@ mod tests {
         );
 
         match result_err {
-            Err(VerificationError(VerificationErrorDetail::InvalidSignature(_), _)) => {},
+            Err(VerificationError(VerificationErrorDetail::InvalidSignature(e), _)) => {
+                // The sign bytes are redacted from the error message.
+                assert_redacted(&e.to_string(), e.sign_bytes.expose());
+            },
             _ => panic!("expected InvalidSignature error"),
         }
     }
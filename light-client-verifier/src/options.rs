@@ -7,7 +7,7 @@ use core::time::Duration;
 use derive_more::Display;
 use serde::{Deserialize, Serialize};
 
-use crate::types::TrustThreshold;
+use crate::{errors::OptionsError, types::TrustThreshold};
 
 /// Verification parameters
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Display, Serialize, Deserialize)]
@@ -28,3 +28,65 @@ pub struct Options {
     /// blockchain.
     pub clock_drift: Duration,
 }
+
+impl Options {
+    /// Verification parameters with the given trust threshold, trusting period
+    /// and clock drift, validated as per [`Options::validate`].
+    pub fn new(
+        trust_threshold: TrustThreshold,
+        trusting_period: Duration,
+        clock_drift: Duration,
+    ) -> Result<Self, OptionsError> {
+        let options = Self {
+            trust_threshold,
+            trusting_period,
+            clock_drift,
+        };
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// Check that the trusting period is not zero, and that the clock drift is
+    /// shorter than it: with a longer drift, a header from the future could
+    /// still be trusted once the trusting period of its trusted state elapsed.
+    pub fn validate(&self) -> Result<(), OptionsError> {
+        if self.trusting_period.is_zero() {
+            return Err(OptionsError::zero_trusting_period());
+        }
+        if self.clock_drift >= self.trusting_period {
+            return Err(OptionsError::clock_drift_too_large(
+                self.clock_drift,
+                self.trusting_period,
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::OptionsErrorDetail;
+
+    #[test]
+    fn validate_options() {
+        let options = |trusting_period, clock_drift| {
+            Options::new(
+                TrustThreshold::ONE_THIRD,
+                Duration::from_secs(trusting_period),
+                Duration::from_secs(clock_drift),
+            )
+        };
+
+        assert!(options(3600, 0).is_ok());
+        assert!(options(3600, 10).is_ok());
+        assert!(matches!(
+            options(0, 0).unwrap_err().detail(),
+            OptionsErrorDetail::ZeroTrustingPeriod(_)
+        ));
+        assert!(matches!(
+            options(3600, 3600).unwrap_err().detail(),
+            OptionsErrorDetail::ClockDriftTooLarge(_)
+        ));
+    }
+}
@@ -1,4 +1,50 @@
 //! Predicates for light block validation and verification.
+//!
+//! The [`VerificationPredicates`] trait provides a spec abiding implementation of each
+//! predicate checked by the [`PredicateVerifier`]. Chains with modified validation rules,
+//! e.g. a different commit threshold or header fields, can override the predicates which
+//! differ, and plug them into the verifier with [`PredicateVerifier::with_predicates`]:
+//!
+//! ```
+//! use tendermint::{crypto::default::Sha256, trust_threshold::TrustThreshold as _};
+//! use tendermint_light_client_verifier::{
+//!     errors::VerificationError,
+//!     operations::VotingPowerCalculator,
+//!     predicates::VerificationPredicates,
+//!     types::{SignedHeader, TrustThreshold, ValidatorSet},
+//!     ProdVerifier,
+//! };
+//!
+//! /// Predicates of a chain whose blocks are committed by 3/4 of the
+//! /// voting power, rather than 2/3.
+//! struct MyChainPredicates;
+//!
+//! impl VerificationPredicates for MyChainPredicates {
+//!     type Sha256 = Sha256;
+//!
+//!     fn has_sufficient_signers_overlap(
+//!         &self,
+//!         untrusted_sh: &SignedHeader,
+//!         untrusted_validators: &ValidatorSet,
+//!         calculator: &dyn VotingPowerCalculator,
+//!     ) -> Result<(), VerificationError> {
+//!         let threshold = TrustThreshold::new(3, 4).unwrap();
+//!         let voting_power =
+//!             calculator.voting_power_in(untrusted_sh, untrusted_validators, threshold)?;
+//!
+//!         if threshold.is_enough_power(voting_power.tallied, voting_power.total) {
+//!             Ok(())
+//!         } else {
+//!             Err(VerificationError::insufficient_signers_overlap(voting_power))
+//!         }
+//!     }
+//! }
+//!
+//! let verifier = ProdVerifier::default().with_predicates(MyChainPredicates);
+//! ```
+//!
+//! [`PredicateVerifier`]: crate::PredicateVerifier
+//! [`PredicateVerifier::with_predicates`]: crate::PredicateVerifier::with_predicates
 
 use core::time::Duration;
 
@@ -8,7 +54,7 @@ use tendermint::{
 
 use crate::{
     errors::VerificationError,
-    operations::{CommitValidator, VotingPowerCalculator},
+    operations::{CommitValidator, HeaderHasher, VotingPowerCalculator},
     prelude::*,
     types::{Header, SignedHeader, Time, TrustThreshold, ValidatorSet},
 };
@@ -35,13 +81,15 @@ pub trait VerificationPredicates: Send + Sync {
     type Sha256: MerkleHash + Sha256 + Default;
 
     /// Compare the provided validator_set_hash against the hash produced from hashing the validator
-    /// set.
+    /// set, which must be in canonical order.
     fn validator_sets_match(
         &self,
         validators: &ValidatorSet,
         header_validators_hash: Hash,
     ) -> Result<(), VerificationError> {
-        let validators_hash = validators.hash_with::<Self::Sha256>();
+        let validators_hash = validators
+            .try_hash_with::<Self::Sha256>()
+            .map_err(VerificationError::tendermint)?;
         if header_validators_hash == validators_hash {
             Ok(())
         } else {
@@ -52,13 +100,16 @@ pub trait VerificationPredicates: Send + Sync {
         }
     }
 
-    /// Check that the hash of the next validator set in the header match the actual one.
+    /// Check that the hash of the next validator set in the header match the actual one, which
+    /// must be in canonical order.
     fn next_validators_match(
         &self,
         next_validators: &ValidatorSet,
         header_next_validators_hash: Hash,
     ) -> Result<(), VerificationError> {
-        let next_validators_hash = next_validators.hash_with::<Self::Sha256>();
+        let next_validators_hash = next_validators
+            .try_hash_with::<Self::Sha256>()
+            .map_err(VerificationError::tendermint)?;
         if header_next_validators_hash == next_validators_hash {
             Ok(())
         } else {
@@ -69,13 +120,15 @@ pub trait VerificationPredicates: Send + Sync {
         }
     }
 
-    /// Check that the hash of the header in the commit matches the actual one.
+    /// Check that the hash of the header in the commit matches the actual one,
+    /// as computed by the given header hasher.
     fn header_matches_commit(
         &self,
         header: &Header,
         commit_hash: Hash,
+        header_hasher: &dyn HeaderHasher,
     ) -> Result<(), VerificationError> {
-        let header_hash = header.hash_with::<Self::Sha256>();
+        let header_hash = header_hasher.hash(header);
         if header_hash == commit_hash {
             Ok(())
         } else {
@@ -99,7 +152,7 @@ pub trait VerificationPredicates: Send + Sync {
         Ok(())
     }
 
-    /// Check that the trusted header is within the trusting period, adjusting for clock drift.
+    /// Check that the trusted header is within the trusting period.
     fn is_within_trust_period(
         &self,
         trusted_header_time: Time,
@@ -116,7 +169,8 @@ pub trait VerificationPredicates: Send + Sync {
         }
     }
 
-    /// Check that the untrusted header is from past.
+    /// Check that the untrusted header is from past, allowing for the local clock
+    /// to be behind the clocks of the validators by up to the given drift.
     fn is_header_from_past(
         &self,
         untrusted_header_time: Time,
@@ -240,7 +294,9 @@ mod tests {
 
     use crate::{
         errors::{VerificationError, VerificationErrorDetail},
-        operations::{ProdCommitValidator, ProdVotingPowerCalculator, VotingPowerTally},
+        operations::{
+            ProdCommitValidator, ProdHeaderHasher, ProdVotingPowerCalculator, VotingPowerTally,
+        },
         predicates::{ProdPredicates, VerificationPredicates},
         prelude::*,
         types::{LightBlock, TrustThreshold},
@@ -461,8 +517,11 @@ mod tests {
         let vp = ProdPredicates;
 
         // 1. ensure valid signed header verifies
-        let result_ok =
-            vp.header_matches_commit(&signed_header.header, signed_header.commit.block_id.hash);
+        let result_ok = vp.header_matches_commit(
+            &signed_header.header,
+            signed_header.commit.block_id.hash,
+            &ProdHeaderHasher::default(),
+        );
 
         assert!(result_ok.is_ok());
 
@@ -471,8 +530,11 @@ mod tests {
             "15F15EF50BDE2018F4B129A827F90C18222C757770C8295EB8EE7BF50E761BC0"
                 .parse()
                 .unwrap();
-        let result_err =
-            vp.header_matches_commit(&signed_header.header, signed_header.commit.block_id.hash);
+        let result_err = vp.header_matches_commit(
+            &signed_header.header,
+            signed_header.commit.block_id.hash,
+            &ProdHeaderHasher::default(),
+        );
 
         // 3. ensure it fails with: VerificationVerificationError::InvalidCommitValue
         let header_hash = signed_header.header.hash();
@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     errors::{ErrorExt, VerificationError, VerificationErrorDetail},
-    operations::{voting_power::VotingPowerTally, CommitValidator, VotingPowerCalculator},
+    operations::{
+        voting_power::VotingPowerTally, CommitValidator, HeaderHasher, VotingPowerCalculator,
+    },
     options::Options,
     predicates::VerificationPredicates,
     types::{Time, TrustedBlockState, UntrustedBlockState},
@@ -12,7 +14,7 @@ use crate::{
 
 #[cfg(feature = "rust-crypto")]
 use crate::{
-    operations::{ProdCommitValidator, ProdVotingPowerCalculator},
+    operations::{ProdCommitValidator, ProdHeaderHasher, ProdVotingPowerCalculator},
     predicates::ProdPredicates,
 };
 
@@ -94,27 +96,98 @@ macro_rules! ensure_verdict_success {
 /// Predicate verifier encapsulating components necessary to facilitate
 /// verification.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
-pub struct PredicateVerifier<P, C, V> {
+pub struct PredicateVerifier<P, C, V, H> {
     predicates: P,
     voting_power_calculator: C,
     commit_validator: V,
+    header_hasher: H,
 }
 
-impl<P, C, V> PredicateVerifier<P, C, V>
+impl<P, C, V, H> PredicateVerifier<P, C, V, H>
 where
     P: VerificationPredicates,
     C: VotingPowerCalculator,
     V: CommitValidator,
+    H: HeaderHasher,
 {
     /// Constructor.
-    pub fn new(predicates: P, voting_power_calculator: C, commit_validator: V) -> Self {
+    pub fn new(
+        predicates: P,
+        voting_power_calculator: C,
+        commit_validator: V,
+        header_hasher: H,
+    ) -> Self {
         Self {
             predicates,
             voting_power_calculator,
             commit_validator,
+            header_hasher,
         }
     }
 
+    /// The predicates checked by this verifier.
+    pub fn predicates(&self) -> &P {
+        &self.predicates
+    }
+
+    /// Replace the predicates checked by this verifier, e.g. with ones overriding
+    /// some of the [`VerificationPredicates`] for a chain with modified validation
+    /// rules, keeping its other components.
+    pub fn with_predicates<Q>(self, predicates: Q) -> PredicateVerifier<Q, C, V, H>
+    where
+        Q: VerificationPredicates,
+    {
+        PredicateVerifier::new(
+            predicates,
+            self.voting_power_calculator,
+            self.commit_validator,
+            self.header_hasher,
+        )
+    }
+
+    /// Replace the voting power calculator of this verifier, keeping its other components.
+    pub fn with_voting_power_calculator<D>(
+        self,
+        voting_power_calculator: D,
+    ) -> PredicateVerifier<P, D, V, H>
+    where
+        D: VotingPowerCalculator,
+    {
+        PredicateVerifier::new(
+            self.predicates,
+            voting_power_calculator,
+            self.commit_validator,
+            self.header_hasher,
+        )
+    }
+
+    /// Replace the commit validator of this verifier, keeping its other components.
+    pub fn with_commit_validator<W>(self, commit_validator: W) -> PredicateVerifier<P, C, W, H>
+    where
+        W: CommitValidator,
+    {
+        PredicateVerifier::new(
+            self.predicates,
+            self.voting_power_calculator,
+            commit_validator,
+            self.header_hasher,
+        )
+    }
+
+    /// Replace the header hasher of this verifier, keeping its other components, e.g.
+    /// for a chain whose headers have additional fields or are hashed differently.
+    pub fn with_header_hasher<I>(self, header_hasher: I) -> PredicateVerifier<P, C, V, I>
+    where
+        I: HeaderHasher,
+    {
+        PredicateVerifier::new(
+            self.predicates,
+            self.voting_power_calculator,
+            self.commit_validator,
+            header_hasher,
+        )
+    }
+
     /// Validates an `UntrustedBlockState`.
     pub fn verify_validator_sets(&self, untrusted: &UntrustedBlockState<'_>) -> Verdict {
         // Ensure the header validator hashes match the given validators
@@ -135,6 +208,7 @@ where
         verdict!(self.predicates.header_matches_commit(
             &untrusted.signed_header.header,
             untrusted.signed_header.commit.block_id.hash,
+            &self.header_hasher,
         ));
 
         // Additional implementation specific validation
@@ -245,11 +319,12 @@ where
     }
 }
 
-impl<P, C, V> Verifier for PredicateVerifier<P, C, V>
+impl<P, C, V, H> Verifier for PredicateVerifier<P, C, V, H>
 where
     P: VerificationPredicates,
     C: VotingPowerCalculator,
     V: CommitValidator,
+    H: HeaderHasher,
 {
     /// Validate the given light block state by performing the following checks ->
     ///
@@ -314,8 +389,12 @@ where
 
 #[cfg(feature = "rust-crypto")]
 /// The default production implementation of the [`PredicateVerifier`].
-pub type ProdVerifier =
-    PredicateVerifier<ProdPredicates, ProdVotingPowerCalculator, ProdCommitValidator>;
+pub type ProdVerifier = PredicateVerifier<
+    ProdPredicates,
+    ProdVotingPowerCalculator,
+    ProdCommitValidator,
+    ProdHeaderHasher,
+>;
 
 #[cfg(test)]
 mod tests {
@@ -386,4 +465,99 @@ mod tests {
             v => panic!("expected ChainIdMismatch error, got: {:?}", v),
         }
     }
+
+    #[test]
+    fn test_verification_with_overridden_predicate() {
+        use crate::{errors::VerificationError, predicates::VerificationPredicates};
+
+        /// Predicates accepting headers with a chain-id that differs from the trusted one,
+        /// e.g. for a chain whose chain-id changes on upgrades.
+        struct AnyChainId;
+
+        impl VerificationPredicates for AnyChainId {
+            type Sha256 = tendermint::crypto::default::Sha256;
+
+            fn is_matching_chain_id(
+                &self,
+                _untrusted_chain_id: &tendermint::chain::Id,
+                _trusted_chain_id: &tendermint::chain::Id,
+            ) -> Result<(), VerificationError> {
+                Ok(())
+            }
+        }
+
+        let now = Time::now();
+        let light_block_1: LightBlock = TestgenLightBlock::new_default_with_time_and_chain_id(
+            "chain-1".to_owned(),
+            now.sub(Duration::from_secs(20)).unwrap(),
+            1u64,
+        )
+        .generate()
+        .unwrap()
+        .into();
+        let light_block_2: LightBlock = TestgenLightBlock::new_default_with_time_and_chain_id(
+            "chain-2".to_owned(),
+            now.sub(Duration::from_secs(10)).unwrap(),
+            2u64,
+        )
+        .generate()
+        .unwrap()
+        .into();
+
+        let vp = ProdVerifier::default().with_predicates(AnyChainId);
+        let opt = Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60),
+            clock_drift: Default::default(),
+        };
+
+        let verdict = vp.verify_update_header(
+            light_block_2.as_untrusted_state(),
+            light_block_1.as_trusted_state(),
+            &opt,
+            Time::now(),
+        );
+
+        assert_eq!(verdict, Verdict::Success);
+    }
+
+    #[test]
+    fn test_verification_with_custom_header_hasher() {
+        use crate::{
+            operations::HeaderHasher,
+            types::{Hash, Header},
+        };
+
+        /// A hasher for a chain hashing its headers differently.
+        struct ConstantHasher;
+
+        impl HeaderHasher for ConstantHasher {
+            fn hash(&self, _header: &Header) -> Hash {
+                Hash::Sha256([1; 32])
+            }
+        }
+
+        let now = Time::now();
+        let light_block: LightBlock = TestgenLightBlock::new_default_with_time_and_chain_id(
+            "chain".to_owned(),
+            now.sub(Duration::from_secs(20)).unwrap(),
+            1u64,
+        )
+        .generate()
+        .unwrap()
+        .into();
+
+        let vp = ProdVerifier::default().with_header_hasher(ConstantHasher);
+
+        match vp.verify_validator_sets(&light_block.as_untrusted_state()) {
+            Verdict::Invalid(VerificationErrorDetail::InvalidCommitValue(e)) => {
+                assert_eq!(e.header_hash, Hash::Sha256([1; 32]));
+                assert_eq!(
+                    e.commit_hash,
+                    light_block.signed_header.commit.block_id.hash
+                );
+            },
+            v => panic!("expected InvalidCommitValue error, got: {:?}", v),
+        }
+    }
 }
@@ -0,0 +1,141 @@
+//! Checking the continuity of the app hashes of consecutive verified headers.
+//!
+//! The header at height `h + 1` carries the app hash the validators agreed on
+//! after executing the block at height `h`. Comparing it with the app hash
+//! the application committed at `h`, as reported by a replayed application or
+//! recorded in block results, reveals non-deterministic state transitions.
+
+use std::collections::BTreeMap;
+
+use tendermint::{block::Header, AppHash};
+use tendermint_rpc::endpoint::block_results;
+use tracing::warn;
+
+use crate::{components::io::IoError, verifier::types::Height};
+
+/// A source of the app hashes committed by an application.
+pub trait CommittedAppHashes {
+    /// The app hash committed by the application after executing the block
+    /// at the given height, or `None` if it is unknown.
+    fn committed_app_hash(&self, height: Height) -> Result<Option<AppHash>, IoError>;
+}
+
+impl<T> CommittedAppHashes for &T
+where
+    T: CommittedAppHashes + ?Sized,
+{
+    fn committed_app_hash(&self, height: Height) -> Result<Option<AppHash>, IoError> {
+        (**self).committed_app_hash(height)
+    }
+}
+
+/// App hashes recorded while replaying the blocks, indexed by height.
+impl CommittedAppHashes for BTreeMap<Height, AppHash> {
+    fn committed_app_hash(&self, height: Height) -> Result<Option<AppHash>, IoError> {
+        Ok(self.get(&height).cloned())
+    }
+}
+
+/// Recorded `/block_results` responses.
+///
+/// Only nodes running CometBFT 0.38 or later report the app hash in block
+/// results; the results of earlier nodes are considered to be unknown.
+impl CommittedAppHashes for [block_results::Response] {
+    fn committed_app_hash(&self, height: Height) -> Result<Option<AppHash>, IoError> {
+        Ok(self
+            .iter()
+            .find(|results| results.height == height)
+            .map(|results| results.app_hash.clone())
+            .filter(|app_hash| !app_hash.as_bytes().is_empty()))
+    }
+}
+
+/// A mismatch between the app hash committed by the application at a height
+/// and the one in the header at the next height.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AppHashMismatch {
+    /// The height of the block after executing which the app state was
+    /// committed
+    pub height: Height,
+    /// The app hash committed by the application
+    pub committed: AppHash,
+    /// The app hash in the header at the next height
+    pub expected: AppHash,
+}
+
+/// Checks that the app hashes committed by an application match those in the
+/// headers verified by the light client.
+#[derive(Clone, Debug)]
+pub struct AppHashChecker<S> {
+    committed: S,
+}
+
+impl<S> AppHashChecker<S>
+where
+    S: CommittedAppHashes,
+{
+    /// Check against the app hashes from the given source.
+    pub fn new(committed: S) -> Self {
+        Self { committed }
+    }
+
+    /// Check the given headers, sorted by height, returning the mismatches
+    /// found.
+    ///
+    /// Only pairs of headers at consecutive heights are checked, and heights
+    /// for which the committed app hash is unknown are skipped.
+    pub fn check(&self, headers: &[Header]) -> Result<Vec<AppHashMismatch>, IoError> {
+        let mut mismatches = Vec::new();
+        for pair in headers.windows(2) {
+            let (header, next) = (&pair[0], &pair[1]);
+            if next.height != header.height.increment() {
+                continue;
+            }
+            if let Some(mismatch) = self.check_next(header.height, &next.app_hash)? {
+                mismatches.push(mismatch);
+            }
+        }
+        Ok(mismatches)
+    }
+
+    fn check_next(
+        &self,
+        height: Height,
+        expected: &AppHash,
+    ) -> Result<Option<AppHashMismatch>, IoError> {
+        let committed = match self.committed.committed_app_hash(height)? {
+            Some(committed) if &committed != expected => committed,
+            _ => return Ok(None),
+        };
+        warn!(
+            "app hash committed at height {} is {}, but the header at the next height has {}",
+            height, committed, expected
+        );
+        Ok(Some(AppHashMismatch {
+            height,
+            committed,
+            expected: expected.clone(),
+        }))
+    }
+}
+
+#[cfg(feature = "rpc-client")]
+mod prod {
+    use tendermint_rpc::Client;
+
+    use super::*;
+    use crate::{components::io::ProdIo, utils::block_on};
+
+    /// Fetch the committed app hashes from the `/block_results` of the node.
+    impl CommittedAppHashes for ProdIo {
+        fn committed_app_hash(&self, height: Height) -> Result<Option<AppHash>, IoError> {
+            let client = self.rpc_client().clone();
+            let results = block_on(
+                self.timeout(),
+                async move { client.block_results(height).await },
+            )?
+            .map_err(IoError::rpc)?;
+            core::slice::from_ref(&results).committed_app_hash(height)
+        }
+    }
+}
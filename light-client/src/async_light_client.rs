@@ -0,0 +1,395 @@
+//! Light client fetching light blocks without blocking, for use within an
+//! async runtime such as Tokio.
+//!
+//! The [`LightClient`](crate::light_client::LightClient) relies on a
+//! synchronous [`Io`](crate::components::io::Io) component, which services
+//! running in an async runtime can only provide by blocking on futures. The
+//! [`AsyncLightClient`] instead awaits an [`AsyncIo`] component, while
+//! verifying light blocks exactly like the former: only fetching blocks is
+//! asynchronous, as the other components do not perform I/O.
+
+use core::fmt;
+use std::collections::BTreeMap;
+#[cfg(any(feature = "metrics", feature = "rust-crypto"))]
+use std::sync::Arc;
+
+use tracing::Instrument;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+#[cfg(feature = "rust-crypto")]
+use crate::verifier::operations::{HeaderHasher, ProdHeaderHasher};
+use crate::{
+    components::{
+        clock::{Clock, MonotonicClock},
+        io::{AsyncIo, AtHeight},
+        scheduler::Scheduler,
+    },
+    errors::Error,
+    light_client::{direction, ChainUpgrade, Core, Direction, ForwardStep, Options, Strictness},
+    state::State,
+    verifier::{
+        types::{Height, LightBlock, PeerId, Status},
+        Verifier,
+    },
+};
+
+/// A light client fetching light blocks from its primary node with an
+/// [`AsyncIo`] component.
+///
+/// See [`LightClient`](crate::light_client::LightClient) for the
+/// documentation of the verification methods, which this client provides as
+/// `async` functions.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// use tendermint_light_client::{
+///     async_light_client::AsyncLightClient,
+///     components::{clock::SystemClock, io::ProdIo, scheduler},
+///     verifier::ProdVerifier,
+/// };
+///
+/// let light_client = AsyncLightClient::new(
+///     peer_id,
+///     options,
+///     SystemClock,
+///     scheduler::basic_bisecting_schedule,
+///     ProdVerifier::default(),
+///     ProdIo::new(peer_id, rpc_client, Some(timeout)),
+/// );
+/// let light_block = light_client.verify_to_highest(&mut state).await?;
+/// ```
+pub struct AsyncLightClient {
+    /// The peer id of the peer this client is connected to
+    pub peer: PeerId,
+    /// Options for this light client
+    pub options: Options,
+    /// How far ahead of its trusted state this light client may skip
+    pub strictness: Strictness,
+
+    // Never goes backwards, see `MonotonicClock`.
+    clock: Box<dyn Clock>,
+    scheduler: Box<dyn Scheduler>,
+    verifier: Box<dyn Verifier>,
+    io: Box<dyn AsyncIo>,
+    upgrade: Option<ChainUpgrade>,
+    #[cfg(feature = "rust-crypto")]
+    header_hasher: Arc<dyn HeaderHasher>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl fmt::Debug for AsyncLightClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncLightClient")
+            .field("peer", &self.peer)
+            .field("options", &self.options)
+            .field("strictness", &self.strictness)
+            .finish()
+    }
+}
+
+impl AsyncLightClient {
+    /// Constructs a new light client
+    pub fn new(
+        peer: PeerId,
+        options: Options,
+        clock: impl Clock + 'static,
+        scheduler: impl Scheduler + 'static,
+        verifier: impl Verifier + 'static,
+        io: impl AsyncIo + 'static,
+    ) -> Self {
+        Self::from_boxed(
+            peer,
+            options,
+            Box::new(clock),
+            Box::new(scheduler),
+            Box::new(verifier),
+            Box::new(io),
+        )
+    }
+
+    /// Constructs a new light client from boxed components
+    pub fn from_boxed(
+        peer: PeerId,
+        options: Options,
+        clock: Box<dyn Clock>,
+        scheduler: Box<dyn Scheduler>,
+        verifier: Box<dyn Verifier>,
+        io: Box<dyn AsyncIo>,
+    ) -> Self {
+        Self {
+            peer,
+            options,
+            strictness: Strictness::default(),
+            clock: Box::new(MonotonicClock::from_boxed(clock)),
+            scheduler,
+            verifier,
+            io,
+            upgrade: None,
+            #[cfg(feature = "rust-crypto")]
+            header_hasher: Arc::new(ProdHeaderHasher::default()),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+
+    /// Set how far ahead of its trusted state the light client may skip.
+    pub fn with_strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Follow the given upgrade of the chain, rather than halting when the
+    /// primary starts serving blocks of the successor chain.
+    #[cfg(feature = "rust-crypto")]
+    pub fn with_chain_upgrade(mut self, upgrade: ChainUpgrade) -> Self {
+        self.upgrade = Some(upgrade);
+        self
+    }
+
+    /// Hash headers with the given hasher rather than the canonical one, when
+    /// linking adjacent headers and checking the anchor of a chain upgrade,
+    /// for a chain whose headers are hashed differently. Its verifier and I/O
+    /// should hash headers the same way, see
+    /// [`PredicateVerifier::with_header_hasher`] and
+    /// [`ProdIo::with_header_hasher`].
+    ///
+    /// [`PredicateVerifier::with_header_hasher`]: crate::verifier::PredicateVerifier::with_header_hasher
+    /// [`ProdIo::with_header_hasher`]: crate::components::io::ProdIo::with_header_hasher
+    #[cfg(feature = "rust-crypto")]
+    pub fn with_header_hasher(mut self, header_hasher: Arc<dyn HeaderHasher>) -> Self {
+        self.header_hasher = header_hasher;
+        self
+    }
+
+    /// The hasher of the headers of this light client.
+    #[cfg(feature = "rust-crypto")]
+    pub fn header_hasher(&self) -> &dyn HeaderHasher {
+        self.header_hasher.as_ref()
+    }
+
+    /// Record the metrics of the verifications performed by the light client.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    fn core(&self) -> Core<'_> {
+        Core {
+            options: &self.options,
+            strictness: self.strictness,
+            clock: self.clock.as_ref(),
+            scheduler: self.scheduler.as_ref(),
+            verifier: self.verifier.as_ref(),
+            upgrade: self.upgrade.as_ref(),
+            #[cfg(feature = "rust-crypto")]
+            header_hasher: self.header_hasher.as_ref(),
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.as_deref(),
+        }
+    }
+
+    /// Attempt to update the light client to the highest block of the primary node
+    /// (see [`LightClient::verify_to_highest`]).
+    ///
+    /// [`LightClient::verify_to_highest`]: crate::light_client::LightClient::verify_to_highest
+    pub async fn verify_to_highest(&self, state: &mut State) -> Result<LightBlock, Error> {
+        let target_block = self
+            .io
+            .fetch_light_block(AtHeight::Highest)
+            .await
+            .map_err(Error::io)?;
+
+        self.verify_to_target(target_block.height(), state).await
+    }
+
+    /// Update the light client to a block of the primary node at the given height
+    /// (see [`LightClient::verify_to_target`]).
+    ///
+    /// [`LightClient::verify_to_target`]: crate::light_client::LightClient::verify_to_target
+    pub async fn verify_to_target(
+        &self,
+        target_height: Height,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        let span = tracing::debug_span!("verify_to_target", target_height = %target_height);
+
+        async move {
+            let result = match direction(target_height, state)? {
+                Direction::Stored(light_block) => Ok(light_block),
+                Direction::Forward => self.verify_forward(target_height, state).await,
+                Direction::Backward => self.verify_backward(target_height, state).await,
+            };
+
+            self.core().observe_state(state);
+            result
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Update the light client to the blocks of the primary node at each of the
+    /// given heights, and return the result of the verification of each of them
+    /// (see [`LightClient::verify_heights`]).
+    ///
+    /// [`LightClient::verify_heights`]: crate::light_client::LightClient::verify_heights
+    pub async fn verify_heights(
+        &self,
+        heights: &[Height],
+        state: &mut State,
+    ) -> BTreeMap<Height, Result<LightBlock, Error>> {
+        let mut heights = heights.to_vec();
+        heights.sort();
+        heights.dedup();
+
+        let mut results = BTreeMap::new();
+        for height in heights {
+            results.insert(height, self.verify_to_target(height, state).await);
+        }
+        results
+    }
+
+    /// Verify a batch of light blocks supplied by the caller, without fetching any
+    /// block from the primary node (see [`LightClient::verify_batch`]).
+    ///
+    /// As this does not perform any I/O, it is not an `async` function.
+    ///
+    /// [`LightClient::verify_batch`]: crate::light_client::LightClient::verify_batch
+    pub fn verify_batch(
+        &self,
+        light_blocks: Vec<LightBlock>,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        self.core().verify_batch(light_blocks, state)
+    }
+
+    /// Perform forward verification with bisection.
+    async fn verify_forward(
+        &self,
+        target_height: Height,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        let mut current_height = target_height;
+        let mut depth = 0;
+
+        loop {
+            let (trusted_block, now) =
+                match self
+                    .core()
+                    .forward_step(target_height, &mut current_height, state)?
+                {
+                    ForwardStep::Done(trusted_block) => {
+                        self.core().observe_bisection_depth(depth);
+                        return Ok(trusted_block);
+                    },
+                    ForwardStep::Fetch { trusted_block, now } => (trusted_block, now),
+                };
+            depth += 1;
+
+            let (current_block, status) = self.get_or_fetch_block(current_height, state).await?;
+
+            let verified = self.core().verify_fetched(
+                target_height,
+                &current_block,
+                status,
+                &trusted_block,
+                now,
+                state,
+            );
+
+            current_height = match verified {
+                Ok(next_height) => next_height,
+                Err(e) => {
+                    // Carry on from the anchor of the successor chain.
+                    #[cfg(feature = "rust-crypto")]
+                    if let Some(upgrade) = self.core().pending_upgrade(&e, state) {
+                        let anchor = self
+                            .io
+                            .fetch_light_block(AtHeight::At(upgrade.trust_options.height))
+                            .await
+                            .map_err(Error::io)?;
+                        self.core().trust_upgrade_anchor(upgrade, anchor, state)?;
+                        current_height = target_height;
+                        continue;
+                    }
+                    return Err(e);
+                },
+            };
+        }
+    }
+
+    /// Stub for when the "rust-crypto" feature is disabled.
+    #[cfg(not(feature = "rust-crypto"))]
+    async fn verify_backward(
+        &self,
+        target_height: Height,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        Err(crate::light_client::backward_unavailable(
+            target_height,
+            state,
+        ))
+    }
+
+    /// Perform sequential backward verification, like the
+    /// [`LightClient`](crate::light_client::LightClient) does.
+    #[cfg(feature = "rust-crypto")]
+    async fn verify_backward(
+        &self,
+        target_height: Height,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        let root = self.core().backward_root(target_height, state)?;
+
+        // Compute a range of `Height`s from `trusted_height - 1` to `target_height`, inclusive.
+        let range = (target_height.value()..root.height().value()).rev();
+        let heights = range.map(|h| Height::try_from(h).unwrap());
+
+        let mut latest = root;
+
+        for height in heights {
+            let (current, _status) = self.get_or_fetch_block(height, state).await?;
+            crate::light_client::link_backward(
+                &latest,
+                &current,
+                self.header_hasher.as_ref(),
+                state,
+            )?;
+            latest = current;
+        }
+
+        // We reached the target height.
+        assert_eq!(latest.height(), target_height);
+
+        Ok(latest)
+    }
+
+    /// Look in the light store for a block at the given height which has not
+    /// previously failed verification, or fetch it from the primary node and
+    /// store it with the `Unverified` status
+    /// (see [`LightClient::get_or_fetch_block`]).
+    ///
+    /// [`LightClient::get_or_fetch_block`]: crate::light_client::LightClient::get_or_fetch_block
+    pub async fn get_or_fetch_block(
+        &self,
+        height: Height,
+        state: &mut State,
+    ) -> Result<(LightBlock, Status), Error> {
+        if let Some(block) = state.light_store.get_non_failed(height) {
+            return Ok(block);
+        }
+
+        let block = self
+            .io
+            .fetch_light_block(AtHeight::At(height))
+            .await
+            .map_err(Error::io)?;
+
+        state.light_store.insert(block.clone(), Status::Unverified);
+
+        Ok((block, Status::Unverified))
+    }
+}
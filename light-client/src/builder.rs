@@ -1,6 +1,9 @@
 //! DSL for building light clients and supervisor
 
 mod light_client;
-pub use light_client::LightClientBuilder;
+pub use light_client::{HasTrustedState, LightClientBuilder, NoTrustedState};
+
+mod trust_options;
+pub use trust_options::TrustOptions;
 
 pub mod error;
@@ -3,7 +3,13 @@
 use flex_error::define_error;
 use tendermint::{block::Height, Hash};
 
-use crate::{components::io::IoError, verifier::errors::VerificationError};
+use crate::{
+    components::io::IoError,
+    verifier::{
+        errors::{OptionsError, VerificationError},
+        types::PeerId,
+    },
+};
 
 define_error! {
     Error {
@@ -35,11 +41,45 @@ define_error! {
             [ VerificationError ]
             | _ | { "invalid light block" },
 
+        InvalidOptions
+            [ OptionsError ]
+            | _ | { "invalid verification options" },
+
         NoTrustedStateInStore
             | _ | { "no trusted state in store" },
 
         EmptyWitnessList
             | _ | { "empty witness list" },
 
+        NotEnoughSources
+            { count: usize }
+            | e | {
+                format_args!("at least two sources are needed to confirm trust options, got {0}",
+                    e.count)
+            },
+
+        ConflictingSources
+            {
+                height: Height,
+                peer: PeerId,
+                hash: Hash,
+                conflicting_peer: PeerId,
+                conflicting_hash: Hash,
+            }
+            | e | {
+                format_args!("conflicting headers at height {0}: {1} has {2}, but {3} has {4}",
+                    e.height, e.peer, e.hash, e.conflicting_peer, e.conflicting_hash)
+            },
+
+        TrustOptionsNotConfirmed
+            {
+                height: Height,
+                hash: Hash,
+            }
+            | e | {
+                format_args!("trust options not confirmed: height = {0}, hash = {1}",
+                    e.height, e.hash)
+            },
+
     }
 }
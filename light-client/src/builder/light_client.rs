@@ -1,29 +1,34 @@
 //! DSL for building a light client [`Instance`]
 
+use std::sync::Arc;
+
 use tendermint::{block::Height, crypto::Sha256, merkle::MerkleHash, Hash};
 
 #[cfg(feature = "rpc-client")]
 use {
-    crate::components::clock::SystemClock,
-    crate::components::io::ProdIo,
-    crate::components::scheduler,
-    crate::verifier::{predicates::ProdPredicates, ProdVerifier},
-    core::time::Duration,
-    tendermint_rpc as rpc,
+    crate::components::clock::SystemClock, crate::components::io::ProdIo,
+    crate::components::scheduler, crate::verifier::predicates::ProdPredicates,
+    core::time::Duration, tendermint_rpc as rpc,
 };
 
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+#[cfg(feature = "rust-crypto")]
+use crate::{light_client::ChainUpgrade, verifier::ProdVerifier};
+
 use crate::{
-    builder::error::Error,
+    builder::{error::Error, TrustOptions},
     components::{
         clock::Clock,
         io::{AtHeight, Io},
         scheduler::Scheduler,
     },
     instance::Instance,
-    light_client::LightClient,
+    light_client::{LightClient, Strictness},
     state::{State, VerificationTrace},
     store::LightStore,
     verifier::{
+        operations::{HeaderHasher, ProvidedHeaderHasher},
         options::Options,
         predicates::VerificationPredicates,
         types::{LightBlock, PeerId, Status},
@@ -48,6 +53,12 @@ pub struct LightClientBuilder<State, H: MerkleHash + Sha256 + Default> {
     scheduler: Box<dyn Scheduler>,
     predicates: Box<dyn VerificationPredicates<Sha256 = H>>,
     light_store: Box<dyn LightStore>,
+    strictness: Strictness,
+    header_hasher: Option<Arc<dyn HeaderHasher>>,
+    #[cfg(feature = "rust-crypto")]
+    upgrade: Option<ChainUpgrade>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
 
     #[allow(dead_code)]
     state: State,
@@ -68,9 +79,61 @@ where
             scheduler: self.scheduler,
             predicates: self.predicates,
             light_store: self.light_store,
+            strictness: self.strictness,
+            header_hasher: self.header_hasher,
+            #[cfg(feature = "rust-crypto")]
+            upgrade: self.upgrade,
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics,
             state,
         }
     }
+
+    /// Set how far ahead of its trusted state the light client may skip.
+    pub fn strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Set the scheduler picking the light blocks to verify on the way to a
+    /// target, e.g. one of the [`SchedulingStrategy`] provided.
+    ///
+    /// [`SchedulingStrategy`]: crate::components::scheduler::SchedulingStrategy
+    pub fn scheduler(mut self, scheduler: impl Scheduler + 'static) -> Self {
+        self.scheduler = Box::new(scheduler);
+        self
+    }
+
+    /// Hash headers with the given hasher rather than the canonical one, for
+    /// a chain whose headers are hashed differently: when checking the hash of
+    /// the trusted state, and in the light client (see
+    /// [`LightClient::with_header_hasher`]).
+    ///
+    /// The verifier and the I/O of the light client must hash headers the same
+    /// way, so they should be given to [`LightClientBuilder::custom`], e.g. a
+    /// [`PredicateVerifier`] and a [`ProdIo`] configured with the same hasher.
+    ///
+    /// [`PredicateVerifier`]: crate::verifier::PredicateVerifier::with_header_hasher
+    /// [`ProdIo`]: crate::components::io::ProdIo::with_header_hasher
+    pub fn header_hasher(mut self, header_hasher: Arc<dyn HeaderHasher>) -> Self {
+        self.header_hasher = Some(header_hasher);
+        self
+    }
+
+    /// Follow the given upgrade of the chain, rather than halting when the
+    /// primary starts serving blocks of the successor chain.
+    #[cfg(feature = "rust-crypto")]
+    pub fn chain_upgrade(mut self, upgrade: ChainUpgrade) -> Self {
+        self.upgrade = Some(upgrade);
+        self
+    }
+
+    /// Record the metrics of the verifications performed by the light client.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
 }
 
 #[cfg(feature = "rpc-client")]
@@ -123,10 +186,35 @@ where
             scheduler,
             options,
             predicates,
+            strictness: Strictness::default(),
+            header_hasher: None,
+            #[cfg(feature = "rust-crypto")]
+            upgrade: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
             state: NoTrustedState,
         }
     }
 
+    /// Check the given predicates, rather than the default ones, when validating the
+    /// trusted state and verifying light blocks, for chains with modified validation rules
+    /// which override some of the [`VerificationPredicates`].
+    ///
+    /// This replaces the verifier of the light client with a
+    /// [`PredicateVerifier`](crate::verifier::PredicateVerifier) checking the given
+    /// predicates, with the production voting power calculator and commit validator.
+    #[cfg(feature = "rust-crypto")]
+    pub fn verification_predicates<P>(mut self, predicates: P) -> Self
+    where
+        P: VerificationPredicates<Sha256 = H> + Clone + 'static,
+    {
+        let verifier = ProdVerifier::default().with_predicates(predicates.clone());
+
+        self.verifier = Box::new(verifier);
+        self.predicates = Box::new(predicates);
+        self
+    }
+
     /// Set the given light block as the initial trusted state.
     pub fn trust_light_block(
         mut self,
@@ -167,8 +255,7 @@ where
             ));
         }
 
-        let header_hash = trusted_state.signed_header.header.hash_with::<H>();
-
+        let header_hash = self.hash_header(&trusted_state);
         if header_hash != trusted_hash {
             return Err(Error::hash_mismatch(trusted_hash, header_hash));
         }
@@ -176,7 +263,59 @@ where
         self.trust_light_block(trusted_state)
     }
 
+    /// Set the block from the primary peer matching the given trust options
+    /// as the trusted state.
+    pub fn trust_options(
+        self,
+        trust_options: TrustOptions,
+    ) -> Result<LightClientBuilder<HasTrustedState, H>, Error> {
+        self.trust_primary_at(trust_options.height, trust_options.hash)
+    }
+
+    /// Set the block from the primary peer matching the trust options
+    /// confirmed by the given sources as the trusted state, hashing the
+    /// headers with the header hasher of the light client.
+    ///
+    /// See [`TrustOptions::from_sources_with_confirmation`].
+    pub fn trust_sources_with_confirmation<I, F>(
+        self,
+        height: AtHeight,
+        sources: &[I],
+        confirm: F,
+    ) -> Result<LightClientBuilder<HasTrustedState, H>, Error>
+    where
+        I: Io,
+        F: FnOnce(&TrustOptions) -> bool,
+    {
+        let trust_options = match &self.header_hasher {
+            Some(header_hasher) => TrustOptions::from_sources_with_confirmation(
+                height,
+                sources,
+                header_hasher.as_ref(),
+                confirm,
+            ),
+            None => TrustOptions::from_sources_with_confirmation(
+                height,
+                sources,
+                &ProvidedHeaderHasher::<H>::default(),
+                confirm,
+            ),
+        }?;
+
+        self.trust_options(trust_options)
+    }
+
+    fn hash_header(&self, light_block: &LightBlock) -> Hash {
+        let header = &light_block.signed_header.header;
+        match &self.header_hasher {
+            Some(header_hasher) => header_hasher.hash(header),
+            None => header.hash_with::<H>(),
+        }
+    }
+
     fn validate(&self, light_block: &LightBlock) -> Result<(), Error> {
+        self.options.validate().map_err(Error::invalid_options)?;
+
         let header = &light_block.signed_header.header;
         let now = self.clock.now();
 
@@ -225,7 +364,26 @@ where
             self.scheduler,
             self.verifier,
             self.io,
-        );
+        )
+        .with_strictness(self.strictness);
+
+        #[cfg(feature = "rust-crypto")]
+        let light_client = match self.header_hasher {
+            Some(header_hasher) => light_client.with_header_hasher(header_hasher),
+            None => light_client,
+        };
+
+        #[cfg(feature = "rust-crypto")]
+        let light_client = match self.upgrade {
+            Some(upgrade) => light_client.with_chain_upgrade(upgrade),
+            None => light_client,
+        };
+
+        #[cfg(feature = "metrics")]
+        let light_client = match self.metrics {
+            Some(metrics) => light_client.with_metrics(metrics),
+            None => light_client,
+        };
 
         Instance::new(light_client, state)
     }
@@ -0,0 +1,135 @@
+//! Trust options confirmed by several independent sources
+
+use tendermint::{block::Height, Hash};
+
+#[cfg(feature = "rpc-client")]
+use {
+    crate::components::io::ProdIo, crate::verifier::types::PeerId, core::time::Duration,
+    std::sync::Arc, tendermint_rpc as rpc,
+};
+
+use crate::{
+    builder::error::Error,
+    components::io::{AtHeight, Io},
+    verifier::{operations::HeaderHasher, types::LightBlock},
+};
+
+/// The height and hash of the header to initialize a light client from.
+///
+/// Initializing a light client from a header fetched from a single node lets
+/// that node lead it to a fork of its choosing. The trust options should
+/// instead come from a source the user has reasons to trust, such as a block
+/// explorer or the operators of the validators, or at least be confirmed by
+/// several independent nodes, as done by
+/// [`TrustOptions::from_sources_with_confirmation`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrustOptions {
+    /// The height of the trusted header
+    pub height: Height,
+    /// The hash of the trusted header
+    pub hash: Hash,
+}
+
+impl TrustOptions {
+    /// Trust the header with the given height and hash.
+    pub fn new(height: Height, hash: Hash) -> Self {
+        Self { height, hash }
+    }
+
+    /// Fetch the header at the given height from each of the given sources,
+    /// and check that they all agree on its hash, as computed by the given
+    /// header hasher, which must be the one of the light client to initialize
+    /// (see [`LightClientBuilder::trust_sources_with_confirmation`]).
+    ///
+    /// At least two sources are needed. With [`AtHeight::Highest`], the header
+    /// at the latest height of the first source is fetched from the others.
+    ///
+    /// The agreed trust options are passed to `confirm`, which can for
+    /// instance prompt the user to compare them with the ones published by a
+    /// trusted party, and only returned if it accepts them.
+    ///
+    /// [`LightClientBuilder::trust_sources_with_confirmation`]: crate::builder::LightClientBuilder::trust_sources_with_confirmation
+    pub fn from_sources_with_confirmation<I, F>(
+        height: AtHeight,
+        sources: &[I],
+        header_hasher: &dyn HeaderHasher,
+        confirm: F,
+    ) -> Result<Self, Error>
+    where
+        I: Io,
+        F: FnOnce(&TrustOptions) -> bool,
+    {
+        let (first, others) = match sources {
+            [first, others @ ..] if !others.is_empty() => (first, others),
+            _ => return Err(Error::not_enough_sources(sources.len())),
+        };
+
+        let expected_height = match height {
+            AtHeight::At(height) => Some(height),
+            AtHeight::Highest => None,
+        };
+        let reference = first.fetch_light_block(height).map_err(Error::io)?;
+        if let Some(expected_height) = expected_height {
+            check_height(expected_height, &reference)?;
+        }
+        let height = reference.height();
+        let hash = header_hasher.hash(&reference.signed_header.header);
+
+        for source in others {
+            let light_block = source
+                .fetch_light_block(AtHeight::At(height))
+                .map_err(Error::io)?;
+            check_height(height, &light_block)?;
+
+            let other_hash = header_hasher.hash(&light_block.signed_header.header);
+            if other_hash != hash {
+                return Err(Error::conflicting_sources(
+                    height,
+                    reference.provider,
+                    hash,
+                    light_block.provider,
+                    other_hash,
+                ));
+            }
+        }
+
+        let trust_options = Self::new(height, hash);
+        if !confirm(&trust_options) {
+            return Err(Error::trust_options_not_confirmed(height, hash));
+        }
+
+        Ok(trust_options)
+    }
+
+    /// Fetch the trust options from the RPC endpoints of the given nodes,
+    /// whose headers are hashed with the given header hasher.
+    ///
+    /// See [`TrustOptions::from_sources_with_confirmation`].
+    #[cfg(feature = "rpc-client")]
+    pub fn from_rpc_with_confirmation<F>(
+        height: AtHeight,
+        nodes: Vec<(PeerId, rpc::HttpClient)>,
+        timeout: Option<Duration>,
+        header_hasher: Arc<dyn HeaderHasher>,
+        confirm: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnOnce(&TrustOptions) -> bool,
+    {
+        let sources: Vec<_> = nodes
+            .into_iter()
+            .map(|(peer_id, rpc_client)| {
+                ProdIo::new(peer_id, rpc_client, timeout).with_header_hasher(header_hasher.clone())
+            })
+            .collect();
+
+        Self::from_sources_with_confirmation(height, &sources, header_hasher.as_ref(), confirm)
+    }
+}
+
+fn check_height(expected: Height, light_block: &LightBlock) -> Result<(), Error> {
+    if light_block.height() != expected {
+        return Err(Error::height_mismatch(expected, light_block.height()));
+    }
+    Ok(())
+}
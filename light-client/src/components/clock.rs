@@ -1,6 +1,6 @@
 //! Provides an interface and a default implementation of the `Clock` component
 
-use std::convert::TryInto;
+use std::{convert::TryInto, sync::Mutex};
 
 use time::OffsetDateTime;
 
@@ -39,3 +39,70 @@ impl Clock for FixedClock {
         self.now
     }
 }
+
+/// Wraps a clock so that the time it provides never goes backwards.
+///
+/// If the wrapped clock goes back in time, e.g. when the system clock is
+/// adjusted, this keeps providing the latest time it provided until the
+/// wrapped clock catches up with it. Otherwise, a trusted state found expired
+/// by a verification could be trusted again by the next one, and a header
+/// rejected as from the future could later be accepted as from the past.
+pub struct MonotonicClock {
+    clock: Box<dyn Clock>,
+    latest: Mutex<Option<Time>>,
+}
+
+impl MonotonicClock {
+    pub fn new(clock: impl Clock + 'static) -> Self {
+        Self::from_boxed(Box::new(clock))
+    }
+
+    pub fn from_boxed(clock: Box<dyn Clock>) -> Self {
+        Self {
+            clock,
+            latest: Mutex::new(None),
+        }
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> Time {
+        let now = self.clock.now();
+        let mut latest = self.latest.lock().unwrap();
+
+        match *latest {
+            Some(latest) if now < latest => {
+                tracing::warn!(%now, %latest, "the local clock went backwards");
+                latest
+            },
+            _ => {
+                *latest = Some(now);
+                now
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::simulation::VirtualClock;
+
+    #[test]
+    fn monotonic_clock_never_goes_backwards() {
+        let start = Time::unix_epoch();
+        let virtual_clock = VirtualClock::new(start);
+        let clock = MonotonicClock::new(virtual_clock.clone());
+
+        let later = virtual_clock.advance(Duration::from_secs(10));
+        assert_eq!(clock.now(), later);
+
+        virtual_clock.set(start);
+        assert_eq!(clock.now(), later);
+
+        let latest = virtual_clock.advance(Duration::from_secs(11));
+        assert_eq!(clock.now(), latest);
+    }
+}
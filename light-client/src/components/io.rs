@@ -2,12 +2,20 @@
 
 use std::time::Duration;
 
+use async_trait::async_trait;
 use flex_error::{define_error, TraceError};
 use tendermint_rpc as rpc;
 #[cfg(feature = "rpc-client")]
 use tendermint_rpc::Client;
 
+#[cfg(feature = "rust-crypto")]
+use tendermint::{account, validator, Hash};
+#[cfg(feature = "rust-crypto")]
+use tendermint_rpc::endpoint::{commit, validators};
+
 use crate::verifier::types::{Height, LightBlock};
+#[cfg(feature = "rust-crypto")]
+use crate::verifier::{operations::HeaderHasher, types::PeerId};
 
 #[cfg(feature = "tokio")]
 type TimeoutError = flex_error::DisplayOnly<tokio::time::error::Elapsed>;
@@ -16,6 +24,7 @@ type TimeoutError = flex_error::DisplayOnly<tokio::time::error::Elapsed>;
 type TimeoutError = flex_error::NoSource;
 
 /// Type for selecting either a specific height or the latest one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AtHeight {
     /// A specific height
     At(Height),
@@ -59,6 +68,13 @@ define_error! {
             [ tendermint::Error ]
             | _ | { "fetched validator set is invalid" },
 
+        InconsistentResponses
+            { reason: String }
+            | e | {
+                format_args!("responses from the node are inconsistent: {}",
+                    e.reason)
+            },
+
         Timeout
             { duration: Duration }
             [ TimeoutError ]
@@ -67,37 +83,60 @@ define_error! {
                     e.duration.as_millis())
             },
 
+        RequestTimeout
+            { duration: Duration }
+            | e | {
+                format_args!("request timed out after {} ms",
+                    e.duration.as_millis())
+            },
+
         Runtime
             [ TraceError<std::io::Error> ]
             | _ | { "failed to initialize runtime" },
 
+        ResponseTooLarge
+            {
+                method: String,
+                limit: u64,
+            }
+            | e | {
+                format_args!("response to {} is larger than the limit of {} bytes",
+                    e.method, e.limit)
+            },
+
     }
 }
 
 impl IoError {
     pub fn from_rpc(err: rpc::Error) -> Self {
+        match err.detail() {
+            rpc::error::ErrorDetail::ResponseTooLarge(e) => {
+                return Self::response_too_large(e.method.clone(), e.limit)
+            },
+            rpc::error::ErrorDetail::RequestTimeout(e) => return Self::request_timeout(e.duration),
+            _ => {},
+        }
         Self::from_height_too_high(&err).unwrap_or_else(|| Self::rpc(err))
     }
 
     pub fn from_height_too_high(err: &rpc::Error) -> Option<Self> {
         use regex::Regex;
 
-        let err_str = err.to_string();
+        let reason = match err.detail() {
+            rpc::error::ErrorDetail::HeightNotAvailable(e) => e.source.data()?,
+            _ => return None,
+        };
 
-        if err_str.contains("must be less than or equal to") {
-            let re = Regex::new(
-                r"height (\d+) must be less than or equal to the current blockchain height (\d+)",
-            )
-            .ok()?;
+        let re = Regex::new(
+            r"height (\d+) must be less than or equal to the current blockchain height (\d+)",
+        )
+        .ok()?;
 
-            let captures = re.captures(&err_str)?;
-            let height = Height::try_from(captures[1].parse::<i64>().ok()?).ok()?;
-            let latest_height = Height::try_from(captures[2].parse::<i64>().ok()?).ok()?;
+        let captures = re.captures(reason)?;
+        let height = Height::try_from(captures[1].parse::<i64>().ok()?).ok()?;
+        let latest_height = Height::try_from(captures[2].parse::<i64>().ok()?).ok()?;
 
-            Some(Self::height_too_high(height, latest_height))
-        } else {
-            None
-        }
+        Some(Self::height_too_high(height, latest_height))
     }
 }
 
@@ -106,9 +145,33 @@ impl IoErrorDetail {
     pub fn is_timeout(&self) -> Option<Duration> {
         match self {
             Self::Timeout(e) => Some(e.duration),
+            Self::RequestTimeout(e) => Some(e.duration),
             _ => None,
         }
     }
+
+    /// Whether this error may not occur again when retrying the same query,
+    /// i.e. whether it is a timeout or an error of the transport rather than
+    /// an error returned by the node or a problem with its responses.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Timeout(_) | Self::RequestTimeout(_) => true,
+            Self::Rpc(e) => {
+                use rpc::error::ErrorDetail;
+
+                matches!(
+                    e.source,
+                    ErrorDetail::Io(_)
+                        | ErrorDetail::Http(_)
+                        | ErrorDetail::Hyper(_)
+                        | ErrorDetail::Timeout(_)
+                        | ErrorDetail::RequestTimeout(_)
+                        | ErrorDetail::ConnectionStale(_)
+                )
+            },
+            _ => false,
+        }
+    }
 }
 
 /// Interface for fetching light blocks from a full node, typically via the RPC client.
@@ -126,49 +189,290 @@ where
     }
 }
 
+/// Interface for fetching light blocks from a full node without blocking, for
+/// light clients running within an async runtime (see
+/// [`AsyncLightClient`](crate::async_light_client::AsyncLightClient)).
+#[async_trait]
+pub trait AsyncIo: Send + Sync {
+    /// Fetch a light block at the given height from a peer
+    async fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError>;
+}
+
+/// Assemble a light block from the response of a node's `/commit` endpoint
+/// and the responses of its `/validators` endpoint at the height of the
+/// commit and at the next one.
+///
+/// The responses are cross-checked before being assembled: the commit must be
+/// for the header it comes with, as hashed by the given hasher, the validator
+/// sets must be complete and for
+/// the expected heights, their hashes must match the ones in the header, and
+/// the proposer of the block must be in the validator set.
+#[cfg(feature = "rust-crypto")]
+pub fn light_block_from_responses(
+    commit: commit::Response,
+    validators: validators::Response,
+    next_validators: validators::Response,
+    provider: PeerId,
+    header_hasher: &dyn HeaderHasher,
+) -> Result<LightBlock, IoError> {
+    let signed_header = commit.signed_header;
+    let header = &signed_header.header;
+    let header_hash = header_hasher.hash(header);
+
+    if signed_header.commit.height != header.height {
+        return Err(IoError::inconsistent_responses(format!(
+            "commit is for height {}, but the header is for height {}",
+            signed_header.commit.height, header.height
+        )));
+    }
+    if signed_header.commit.block_id.hash != header_hash {
+        return Err(IoError::inconsistent_responses(format!(
+            "commit is for block {}, but the header is of block {}",
+            signed_header.commit.block_id.hash, header_hash
+        )));
+    }
+
+    let validators = validator_set_from_response(
+        validators,
+        header.height,
+        header.validators_hash,
+        Some(header.proposer_address),
+    )?;
+    let next_validators = validator_set_from_response(
+        next_validators,
+        header.height.increment(),
+        header.next_validators_hash,
+        None,
+    )?;
+
+    Ok(LightBlock::new(
+        signed_header,
+        validators,
+        next_validators,
+        provider,
+    ))
+}
+
+#[cfg(feature = "rust-crypto")]
+fn validator_set_from_response(
+    response: validators::Response,
+    height: Height,
+    hash: Hash,
+    proposer_address: Option<account::Id>,
+) -> Result<validator::Set, IoError> {
+    if response.block_height != height {
+        return Err(IoError::inconsistent_responses(format!(
+            "validators are for height {}, expected height {}",
+            response.block_height, height
+        )));
+    }
+    if usize::try_from(response.total).ok() != Some(response.validators.len()) {
+        return Err(IoError::inconsistent_responses(format!(
+            "got {} of the {} validators at height {}",
+            response.validators.len(),
+            response.total,
+            height
+        )));
+    }
+
+    let proposer = match proposer_address {
+        Some(address) => Some(
+            response
+                .validators
+                .iter()
+                .find(|v| v.address == address)
+                .cloned()
+                .ok_or_else(|| {
+                    IoError::invalid_validator_set(tendermint::Error::proposer_not_found(address))
+                })?,
+        ),
+        None => None,
+    };
+    let validator_set = validator::Set::try_from_unordered(response.validators, proposer)
+        .map_err(IoError::invalid_validator_set)?;
+
+    if validator_set.hash() != hash {
+        return Err(IoError::inconsistent_responses(format!(
+            "hash of the validators at height {} is {}, but the header has {}",
+            height,
+            validator_set.hash(),
+            hash
+        )));
+    }
+
+    Ok(validator_set)
+}
+
 #[cfg(feature = "rpc-client")]
-pub use self::prod::ProdIo;
+pub use self::prod::{fetch_light_block, ProdIo, ProviderPolicy, ResponseSizeLimits};
 
 #[cfg(feature = "rpc-client")]
 mod prod {
-    use std::time::Duration;
+    use std::{fmt, num::NonZeroUsize, sync::Arc, time::Duration};
 
     use tendermint::{
         account::Id as TMAccountId, block::signed_header::SignedHeader as TMSignedHeader,
         validator::Set as TMValidatorSet,
     };
-    use tendermint_rpc::Paging;
+    use tendermint_rpc::{
+        client::{RateLimit, RateLimitedClient, RateLimiter},
+        Method, Paging,
+    };
+    use tokio::sync::Semaphore;
 
     use super::*;
-    use crate::{utils::block_on, verifier::types::PeerId};
+    use crate::{utils::block_on, verifier::operations::ProdHeaderHasher};
+
+    /// The maximum size in bytes of the responses of a node to the requests
+    /// of a [`ProdIo`] component (see [`ProdIo::with_response_size_limits`]).
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct ResponseSizeLimits {
+        /// Limit of the responses to `/commit`.
+        pub commit: u64,
+        /// Limit of the responses to `/validators`, which apply to each page
+        /// of the validator sets.
+        pub validators: u64,
+        /// Limit of the responses to `/header`.
+        pub header: u64,
+    }
+
+    impl Default for ResponseSizeLimits {
+        /// Limits leaving room for commits signed by 10,000 validators, and
+        /// for pages of 100 validators.
+        fn default() -> Self {
+            Self {
+                commit: 4 * 1024 * 1024,
+                validators: 1024 * 1024,
+                header: 64 * 1024,
+            }
+        }
+    }
+
+    /// How a [`ProdIo`] component fetches light blocks from its peer (see
+    /// [`ProdIo::with_policy`]).
+    ///
+    /// The default policy sets no limits, and does not retry failed fetches.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct ProviderPolicy {
+        /// How long to wait for each attempt at fetching a light block,
+        /// failing it with a [`Timeout`](IoErrorDetail::Timeout) error.
+        pub attempt_timeout: Option<Duration>,
+        /// The maximum number of light blocks fetched from the peer at once,
+        /// by the component and its clones.
+        pub max_concurrent_fetches: Option<NonZeroUsize>,
+        /// The limit on the rate of the requests to the peer, shared by the
+        /// component and its clones.
+        pub rate_limit: Option<RateLimit>,
+        /// The number of times a fetch failing with a transient error (see
+        /// [`IoErrorDetail::is_transient`]) is retried.
+        pub max_retries: u32,
+        /// The delay before the first retry of a fetch, which doubles with
+        /// every retry up to `max_backoff`.
+        pub initial_backoff: Duration,
+        /// The maximum delay between two attempts at a fetch.
+        pub max_backoff: Duration,
+    }
+
+    impl Default for ProviderPolicy {
+        fn default() -> Self {
+            Self {
+                attempt_timeout: None,
+                max_concurrent_fetches: None,
+                rate_limit: None,
+                max_retries: 0,
+                initial_backoff: rpc::client::DEFAULT_INITIAL_BACKOFF,
+                max_backoff: rpc::client::DEFAULT_MAX_BACKOFF,
+            }
+        }
+    }
+
+    impl ProviderPolicy {
+        /// The delay before the given retry, starting from 1.
+        fn backoff_for(&self, retry: u32) -> Duration {
+            self.initial_backoff
+                .saturating_mul(2_u32.saturating_pow(retry - 1))
+                .min(self.max_backoff)
+        }
+    }
 
     /// Production implementation of the Io component, which fetches
     /// light blocks from full nodes via RPC.
-    #[derive(Clone, Debug)]
+    #[derive(Clone)]
     pub struct ProdIo {
         peer_id: PeerId,
         rpc_client: rpc::HttpClient,
         timeout: Option<Duration>,
+        policy: ProviderPolicy,
+        fetches: Option<Arc<Semaphore>>,
+        limiter: Option<RateLimiter>,
+        header_hasher: Arc<dyn HeaderHasher>,
+    }
+
+    impl fmt::Debug for ProdIo {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("ProdIo")
+                .field("peer_id", &self.peer_id)
+                .field("rpc_client", &self.rpc_client)
+                .field("timeout", &self.timeout)
+                .field("policy", &self.policy)
+                .field("fetches", &self.fetches)
+                .field("limiter", &self.limiter)
+                .finish()
+        }
     }
 
     impl Io for ProdIo {
         fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
-            let signed_header = self.fetch_signed_header(height)?;
-            let height = signed_header.header.height;
-            let proposer_address = signed_header.header.proposer_address;
-
-            let validator_set = self.fetch_validator_set(height.into(), Some(proposer_address))?;
-            let next_validator_set = self.fetch_validator_set(height.increment().into(), None)?;
+            let io = self.clone();
+            block_on(
+                self.timeout,
+                async move { io.fetch_with_retries(height).await },
+            )?
+        }
+    }
 
-            let light_block = LightBlock::new(
-                signed_header,
-                validator_set,
-                next_validator_set,
-                self.peer_id,
-            );
+    #[async_trait]
+    impl AsyncIo for ProdIo {
+        async fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+            let fetch = self.fetch_with_retries(height);
+            match self.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, fetch)
+                    .await
+                    .map_err(|e| IoError::timeout(timeout, e))?,
+                None => fetch.await,
+            }
+        }
+    }
 
-            Ok(light_block)
+    /// Fetch the light block at the given height from a node with the given
+    /// RPC client, cross-checking the responses of the node as described in
+    /// [`light_block_from_responses`].
+    pub async fn fetch_light_block<C>(
+        client: &C,
+        height: AtHeight,
+        provider: PeerId,
+        header_hasher: &dyn HeaderHasher,
+    ) -> Result<LightBlock, IoError>
+    where
+        C: Client + Sync,
+    {
+        let commit = match height {
+            AtHeight::Highest => client.latest_commit().await,
+            AtHeight::At(height) => client.commit(height).await,
         }
+        .map_err(IoError::from_rpc)?;
+
+        let height = commit.signed_header.header.height;
+        let validators = client
+            .validators(height, Paging::All)
+            .await
+            .map_err(IoError::from_rpc)?;
+        let next_validators = client
+            .validators(height.increment(), Paging::All)
+            .await
+            .map_err(IoError::from_rpc)?;
+
+        light_block_from_responses(commit, validators, next_validators, provider, header_hasher)
     }
 
     impl ProdIo {
@@ -185,9 +489,49 @@ mod prod {
                 peer_id,
                 rpc_client,
                 timeout,
+                policy: ProviderPolicy::default(),
+                fetches: None,
+                limiter: None,
+                header_hasher: Arc::new(ProdHeaderHasher::default()),
             }
         }
 
+        /// Fetch light blocks from the peer according to the given policy.
+        ///
+        /// The timeout given to [`ProdIo::new`] still bounds each fetch as a
+        /// whole, retries included.
+        pub fn with_policy(mut self, policy: ProviderPolicy) -> Self {
+            self.fetches = policy
+                .max_concurrent_fetches
+                .map(|permits| Arc::new(Semaphore::new(permits.get())));
+            self.limiter = policy.rate_limit.map(RateLimiter::new);
+            self.policy = policy;
+            self
+        }
+
+        /// Reject the responses of the node larger than the given limits,
+        /// before decoding them.
+        ///
+        /// This protects light clients running with little memory from
+        /// malicious nodes sending them oversized commits, validator sets or
+        /// headers.
+        pub fn with_response_size_limits(mut self, limits: ResponseSizeLimits) -> Self {
+            self.rpc_client = self
+                .rpc_client
+                .with_max_response_size_for(Method::Commit, limits.commit)
+                .with_max_response_size_for(Method::Validators, limits.validators)
+                .with_max_response_size_for(Method::Header, limits.header);
+            self
+        }
+
+        /// Hash the headers served by the peer with the given hasher rather
+        /// than the canonical one, when checking that they match their commit,
+        /// for a chain whose headers are hashed differently.
+        pub fn with_header_hasher(mut self, header_hasher: Arc<dyn HeaderHasher>) -> Self {
+            self.header_hasher = header_hasher;
+            self
+        }
+
         pub fn peer_id(&self) -> PeerId {
             self.peer_id
         }
@@ -200,6 +544,65 @@ mod prod {
             self.timeout
         }
 
+        pub fn policy(&self) -> &ProviderPolicy {
+            &self.policy
+        }
+
+        async fn fetch_with_retries(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+            let mut retries = 0;
+            loop {
+                let e = match self.fetch_once(height).await {
+                    Ok(light_block) => return Ok(light_block),
+                    Err(e) => e,
+                };
+                if retries == self.policy.max_retries || !e.detail().is_transient() {
+                    return Err(e);
+                }
+
+                retries += 1;
+                let delay = self.policy.backoff_for(retries);
+                tracing::debug!(peer = %self.peer_id, "retrying fetch in {delay:?} after error: {e}");
+                tokio::time::sleep(delay).await;
+            }
+        }
+
+        async fn fetch_once(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+            let _permit = match &self.fetches {
+                Some(fetches) => Some(
+                    fetches
+                        .acquire()
+                        .await
+                        .expect("the semaphore is never closed"),
+                ),
+                None => None,
+            };
+
+            let fetch = async {
+                match &self.limiter {
+                    Some(limiter) => {
+                        let client = RateLimitedClient::new(self.rpc_client.clone())
+                            .global_limit(limiter.clone());
+                        fetch_light_block(&client, height, self.peer_id, &*self.header_hasher).await
+                    },
+                    None => {
+                        fetch_light_block(
+                            &self.rpc_client,
+                            height,
+                            self.peer_id,
+                            &*self.header_hasher,
+                        )
+                        .await
+                    },
+                }
+            };
+            match self.policy.attempt_timeout {
+                Some(timeout) => tokio::time::timeout(timeout, fetch)
+                    .await
+                    .map_err(|e| IoError::timeout(timeout, e))?,
+                None => fetch.await,
+            }
+        }
+
         pub fn fetch_signed_header(&self, height: AtHeight) -> Result<TMSignedHeader, IoError> {
             let client = self.rpc_client.clone();
             let res = block_on(self.timeout, async move {
@@ -231,7 +634,7 @@ mod prod {
             let response = block_on(self.timeout, async move {
                 client.validators(height, Paging::All).await
             })?
-            .map_err(IoError::rpc)?;
+            .map_err(IoError::from_rpc)?;
 
             let validator_set = match proposer_address {
                 Some(proposer_address) => {
@@ -1,10 +1,29 @@
-//! Provides an interface and default implementation of the `Scheduler` component
+//! Provides an interface and default implementations of the `Scheduler` component
+//!
+//! Three scheduling strategies are provided, which can be picked on the light
+//! client builder with [`SchedulingStrategy`]:
+//!
+//! - [`basic_bisecting_schedule`], the default, which bisects between the
+//!   trusted and the current heights when the latter cannot be verified;
+//! - [`sequential_schedule`], which verifies every block above the trusted one;
+//! - [`skipping_schedule`], which reuses the blocks already fetched to skip
+//!   back as little as possible.
+//!
+//! Any scheduler can be wrapped in an [`Instrumented`] one to find out how many
+//! intermediate light blocks it had the light client verify and fetch.
 
 use core::convert::TryInto;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
 use contracts::*;
 
-use crate::{store::LightStore, verifier::types::Height};
+use crate::{
+    store::LightStore,
+    verifier::types::{Height, Status},
+};
 
 /// The scheduler decides what block to verify next given the current and target heights.
 ///
@@ -75,6 +94,192 @@ pub fn basic_bisecting_schedule(
     }
 }
 
+/// Sequential scheduler which picks the block right above the highest trusted
+/// one, so that every block between the trusted and the target heights gets
+/// verified.
+///
+/// Only the target block is skipped to first, as the light client fetches it
+/// before asking for a schedule.
+///
+/// ## Precondition
+/// - The light store contains at least one verified block. [LCV-SCHEDULE-PRE.1]
+///
+/// ## Postcondition
+/// - The resulting height must be valid according to `valid_schedule`. [LCV-SCHEDULE-POST.1]
+#[requires(light_store.highest_trusted_or_verified().is_some())]
+#[ensures(valid_schedule(ret, target_height, current_height, light_store))]
+pub fn sequential_schedule(
+    light_store: &dyn LightStore,
+    current_height: Height,
+    target_height: Height,
+) -> Height {
+    let trusted_height = light_store
+        .highest_trusted_or_verified_before(target_height)
+        .map(|lb| lb.height())
+        .unwrap();
+
+    if trusted_height < target_height {
+        trusted_height.increment()
+    } else {
+        target_height
+    }
+}
+
+/// Aggressively skipping scheduler, which goes straight to the target height
+/// whenever it can, and otherwise skips back as little as possible.
+///
+/// When the block at the current height cannot be verified, the highest block
+/// below it which was already fetched but could not be verified yet is tried
+/// again, since more blocks may have been verified in the meantime, and no
+/// new block needs to be fetched. Only if there is none is the midpoint
+/// between the trusted and the current heights picked, like
+/// [`basic_bisecting_schedule`] does.
+///
+/// ## Precondition
+/// - The light store contains at least one verified block. [LCV-SCHEDULE-PRE.1]
+///
+/// ## Postcondition
+/// - The resulting height must be valid according to `valid_schedule`. [LCV-SCHEDULE-POST.1]
+#[requires(light_store.highest_trusted_or_verified().is_some())]
+#[ensures(valid_schedule(ret, target_height, current_height, light_store))]
+pub fn skipping_schedule(
+    light_store: &dyn LightStore,
+    current_height: Height,
+    target_height: Height,
+) -> Height {
+    let trusted_height = light_store
+        .highest_trusted_or_verified_before(target_height)
+        .map(|lb| lb.height())
+        .unwrap();
+
+    if trusted_height == current_height {
+        target_height
+    } else {
+        light_store
+            .all(Status::Unverified)
+            .map(|lb| lb.height())
+            .filter(|&height| trusted_height < height && height < current_height)
+            .max()
+            .unwrap_or_else(|| midpoint(trusted_height, current_height))
+    }
+}
+
+/// The scheduling strategies provided by this crate, to pick from when
+/// building a light client.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SchedulingStrategy {
+    /// See [`basic_bisecting_schedule`].
+    #[default]
+    Bisecting,
+    /// See [`sequential_schedule`].
+    Sequential,
+    /// See [`skipping_schedule`].
+    Skipping,
+}
+
+#[contract_trait]
+impl Scheduler for SchedulingStrategy {
+    fn schedule(
+        &self,
+        light_store: &dyn LightStore,
+        current_height: Height,
+        target_height: Height,
+    ) -> Height {
+        let schedule = match self {
+            Self::Bisecting => basic_bisecting_schedule,
+            Self::Sequential => sequential_schedule,
+            Self::Skipping => skipping_schedule,
+        };
+        schedule(light_store, current_height, target_height)
+    }
+}
+
+/// Counters of the light blocks scheduled by an [`Instrumented`] scheduler.
+#[derive(Debug, Default)]
+pub struct SchedulingStats {
+    intermediate: AtomicU64,
+    fetched: AtomicU64,
+}
+
+impl SchedulingStats {
+    /// The number of intermediate light blocks scheduled, i.e. of the
+    /// scheduled blocks below the target ones.
+    pub fn intermediate(&self) -> u64 {
+        self.intermediate.load(Ordering::Relaxed)
+    }
+
+    /// The number of intermediate light blocks scheduled which were not in
+    /// the light store yet, and thus had to be fetched from the primary.
+    pub fn fetched(&self) -> u64 {
+        self.fetched.load(Ordering::Relaxed)
+    }
+
+    /// Reset the counters to zero.
+    pub fn reset(&self) {
+        self.intermediate.store(0, Ordering::Relaxed);
+        self.fetched.store(0, Ordering::Relaxed);
+    }
+}
+
+/// A scheduler which keeps count of the intermediate light blocks scheduled
+/// by another one, to compare the scheduling strategies.
+///
+/// The counters are shared, so that they can be read after the scheduler has
+/// been handed over to the light client:
+///
+/// ```rust
+/// use tendermint_light_client::components::scheduler::{Instrumented, SchedulingStrategy};
+///
+/// let scheduler = Instrumented::new(SchedulingStrategy::Skipping);
+/// let stats = scheduler.stats();
+/// // Pass `scheduler` to `LightClientBuilder::scheduler`, and read `stats`
+/// // once the light client has verified some blocks.
+/// assert_eq!(stats.fetched(), 0);
+/// ```
+#[derive(Debug)]
+pub struct Instrumented<S> {
+    inner: S,
+    stats: Arc<SchedulingStats>,
+}
+
+impl<S: Scheduler> Instrumented<S> {
+    /// Keep count of the light blocks scheduled by the given scheduler.
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            stats: Arc::default(),
+        }
+    }
+
+    /// The counters of the light blocks scheduled so far.
+    pub fn stats(&self) -> Arc<SchedulingStats> {
+        self.stats.clone()
+    }
+}
+
+#[contract_trait]
+impl<S: Scheduler> Scheduler for Instrumented<S> {
+    fn schedule(
+        &self,
+        light_store: &dyn LightStore,
+        current_height: Height,
+        target_height: Height,
+    ) -> Height {
+        let height = self
+            .inner
+            .schedule(light_store, current_height, target_height);
+
+        if height != target_height {
+            self.stats.intermediate.fetch_add(1, Ordering::Relaxed);
+            if light_store.get_non_failed(height).is_none() {
+                self.stats.fetched.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        height
+    }
+}
+
 /// Checks whether the given `scheduled_height` is a valid schedule according to the
 /// following specification.
 ///
@@ -4,6 +4,7 @@ use std::{fmt::Debug, time::Duration};
 
 use crossbeam_channel as crossbeam;
 use flex_error::{define_error, DisplayError, TraceError};
+use tendermint::chain::Id as ChainId;
 
 // Re-export for backward compatibility
 pub use crate::verifier::errors::ErrorExt;
@@ -13,7 +14,7 @@ use crate::{
         errors::VerificationErrorDetail,
         operations::voting_power::VotingPowerTally,
         options::Options,
-        types::{Hash, Height, LightBlock, PeerId, Status},
+        types::{Hash, Height, LightBlock, PeerId, Status, Time},
     },
 };
 
@@ -23,6 +24,18 @@ type SledError = TraceError<sled::Error>;
 #[cfg(not(feature = "sled"))]
 type SledError = flex_error::NoSource;
 
+#[cfg(feature = "rusqlite")]
+type SqliteError = TraceError<rusqlite::Error>;
+
+#[cfg(not(feature = "rusqlite"))]
+type SqliteError = flex_error::NoSource;
+
+#[cfg(feature = "rocksdb")]
+type RocksDbError = TraceError<rocksdb::Error>;
+
+#[cfg(not(feature = "rocksdb"))]
+type RocksDbError = flex_error::NoSource;
+
 define_error! {
     #[derive(Debug)]
     Error {
@@ -95,6 +108,19 @@ define_error! {
                     e.target_height, e.trusted_height)
             },
 
+        EmptyBatch
+            | _ | { "empty batch of light blocks" },
+
+        IncompleteBatch
+            {
+                target_height: Height,
+                trusted_height: Height,
+            }
+            | e | {
+                format_args!("batch lacks the light blocks needed to verify the block at height {0} from the trusted state at height {1}",
+                    e.target_height, e.trusted_height)
+            },
+
         InvalidLightBlock
             [ DisplayError<VerificationErrorDetail> ]
             | _ | { "invalid light block" },
@@ -116,6 +142,50 @@ define_error! {
                     e.height)
             },
 
+        ChainIdMismatch
+            {
+                height: Height,
+                chain_id: ChainId,
+                expected: ChainId,
+            }
+            | e | {
+                format_args!("header at height {0} is for chain {1} instead of {2}",
+                    e.height, e.chain_id, e.expected)
+            },
+
+        UpgradeDetected
+            {
+                height: Height,
+                chain_id: ChainId,
+                previous: ChainId,
+            }
+            | e | {
+                format_args!("chain upgrade detected: header at height {0} is for chain {1}, the successor of {2}",
+                    e.height, e.chain_id, e.previous)
+            },
+
+        InvalidUpgradeAnchor
+            {
+                height: Height,
+                chain_id: ChainId,
+                hash: Hash,
+            }
+            | e | {
+                format_args!("light block does not match the trust anchor at height {0} of upgraded chain {1}, with hash {2}",
+                    e.height, e.chain_id, e.hash)
+            },
+
+        NonDecreasingHeaderTime
+            {
+                height: Height,
+                time: Time,
+                next_time: Time,
+            }
+            | e | {
+                format_args!("header at height {0} has time {1}, which is not before the time {2} of the next header",
+                    e.height, e.time, e.next_time)
+            },
+
         ChannelDisconnected
             | _ | { "internal channel disconnected" },
 
@@ -123,10 +193,53 @@ define_error! {
             [ SledError ]
             | _ | { "sled error" },
 
+        Sqlite
+            [ SqliteError ]
+            | _ | { "sqlite error" },
+
+        RocksDb
+            [ RocksDbError ]
+            | _ | { "rocksdb error" },
+
         SerdeCbor
             [ TraceError<serde_cbor::Error> ]
             | _ | { "serde cbor error" },
 
+        UnsupportedStoreVersion
+            {
+                version: u32,
+                supported: u32,
+            }
+            | e | {
+                format_args!("light store has schema version {0}, newer than the supported version {1}",
+                    e.version, e.supported)
+            },
+
+        SnapshotIo
+            { path: String }
+            [ TraceError<std::io::Error> ]
+            | e | {
+                format_args!("failed to read or write the light store snapshot at {0}",
+                    e.path)
+            },
+
+        UnsupportedSnapshotVersion
+            {
+                version: u32,
+                supported: u32,
+            }
+            | e | {
+                format_args!("light store snapshot has version {0}, newer than the supported version {1}",
+                    e.version, e.supported)
+            },
+
+        HeadMismatch
+            { height: Height }
+            | e | {
+                format_args!("header announced at height {0} does not match the verified one",
+                    e.height)
+            },
+
     }
 }
 
@@ -0,0 +1,130 @@
+//! A stream of the verified heads of the chain, as announced by the primary.
+//!
+//! Rather than polling [`AsyncLightClient::verify_to_highest`] in a loop,
+//! [`verified_heads`] verifies each header announced by the primary as soon as
+//! it is announced, e.g. in the `NewBlockHeader` events of a subscription (see
+//! [`new_block_headers`]), and yields the verified light blocks.
+
+use std::pin::Pin;
+
+use futures::{stream, Stream, StreamExt};
+use tendermint::block::Header;
+
+use crate::{
+    async_light_client::AsyncLightClient,
+    errors::Error,
+    state::State,
+    verifier::types::{Height, LightBlock},
+};
+
+/// Verify each of the given headers, as announced by the primary of the given
+/// light client, and yield the verified light block at its height.
+///
+/// A header following the last verified one is verified against it, while one
+/// announced after a gap, e.g. after events were missed, is verified by
+/// bisection like with [`AsyncLightClient::verify_to_target`]. Headers at or
+/// below the last verified height, e.g. replayed after a reconnection, are
+/// skipped.
+///
+/// The verified light block must carry the announced header, or the error
+/// [`HeadMismatch`] is yielded instead. The stream yields the errors of the
+/// verification and of the given headers, and keeps going, until the given
+/// headers run out.
+///
+/// [`HeadMismatch`]: crate::errors::ErrorDetail::HeadMismatch
+pub fn verified_heads<'a, S>(
+    light_client: &'a AsyncLightClient,
+    state: &'a mut State,
+    headers: S,
+) -> impl Stream<Item = Result<LightBlock, Error>> + 'a
+where
+    S: Stream<Item = Result<Header, Error>> + 'a,
+{
+    let heads = Heads {
+        light_client,
+        state,
+        headers: Box::pin(headers),
+        latest_height: None,
+    };
+
+    stream::unfold(heads, |mut heads| async move {
+        let head = heads.next().await?;
+        Some((head, heads))
+    })
+}
+
+struct Heads<'a, S> {
+    light_client: &'a AsyncLightClient,
+    state: &'a mut State,
+    headers: Pin<Box<S>>,
+    latest_height: Option<Height>,
+}
+
+impl<S> Heads<'_, S>
+where
+    S: Stream<Item = Result<Header, Error>>,
+{
+    async fn next(&mut self) -> Option<Result<LightBlock, Error>> {
+        loop {
+            let header = match self.headers.next().await? {
+                Ok(header) => header,
+                Err(e) => return Some(Err(e)),
+            };
+            if self.latest_height >= Some(header.height) {
+                continue;
+            }
+
+            let head = self
+                .light_client
+                .verify_to_target(header.height, self.state)
+                .await
+                .and_then(|light_block| {
+                    if light_block.signed_header.header == header {
+                        Ok(light_block)
+                    } else {
+                        Err(Error::head_mismatch(header.height))
+                    }
+                });
+            if head.is_ok() {
+                self.latest_height = Some(header.height);
+            }
+
+            return Some(head);
+        }
+    }
+}
+
+/// The headers of the new blocks in the given events, e.g. those of a
+/// subscription to the `NewBlockHeader` events of the primary:
+///
+/// ```ignore
+/// let events = rpc_client.subscribe(EventType::NewBlockHeader.into()).await?;
+/// let heads = verified_heads(&light_client, &mut state, new_block_headers(events));
+/// ```
+///
+/// The events which do not carry a header are skipped.
+#[cfg(feature = "rpc-client")]
+pub fn new_block_headers<S>(events: S) -> impl Stream<Item = Result<Header, Error>>
+where
+    S: Stream<Item = Result<tendermint_rpc::event::Event, tendermint_rpc::Error>>,
+{
+    use tendermint_rpc::event::EventData;
+
+    use crate::components::io::IoError;
+
+    events.filter_map(|event| async move {
+        match event {
+            Ok(event) => match event.data {
+                EventData::NewBlockHeader { header, .. } => Some(Ok(*header)),
+                EventData::NewBlock {
+                    block: Some(block), ..
+                }
+                | EventData::LegacyNewBlock {
+                    block: Some(block), ..
+                } => Some(Ok(block.header)),
+                _ => None,
+            },
+            Err(e) => Some(Err(Error::io(IoError::from_rpc(e)))),
+        }
+    })
+}
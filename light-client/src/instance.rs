@@ -1,5 +1,7 @@
 //! Supervisor and Handle implementation.
 
+use std::collections::BTreeMap;
+
 use tendermint::block::Height;
 
 use crate::{
@@ -43,6 +45,24 @@ impl Instance {
         self.state.light_store.update(lb, Status::Trusted);
     }
 
+    /// Verify the given light blocks, supplied by the caller rather than fetched from
+    /// the primary, and return the highest of them once verified
+    /// (see [`LightClient::verify_batch`]).
+    pub fn verify_batch(&mut self, light_blocks: Vec<LightBlock>) -> Result<LightBlock, Error> {
+        self.light_client
+            .verify_batch(light_blocks, &mut self.state)
+    }
+
+    /// Verify the blocks of the primary at each of the given heights, and return
+    /// the result of the verification of each of them
+    /// (see [`LightClient::verify_heights`]).
+    pub fn verify_heights(
+        &mut self,
+        heights: &[Height],
+    ) -> BTreeMap<Height, Result<LightBlock, Error>> {
+        self.light_client.verify_heights(heights, &mut self.state)
+    }
+
     /// Get or fetch the block at the given height
     pub fn get_or_fetch_block(&mut self, height: Height) -> Result<LightBlock, Error> {
         let (block, _) = self
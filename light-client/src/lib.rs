@@ -15,12 +15,24 @@
 
 //! See the `light_client` module for the main documentation.
 
+pub mod app_hash;
+pub mod async_light_client;
 pub mod builder;
 pub mod components;
 pub mod contracts;
 pub mod errors;
+pub mod heads;
 pub mod instance;
 pub mod light_client;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "rpc-client")]
+pub mod query;
+#[cfg(feature = "rpc-client")]
+pub mod quick;
+pub mod refresh;
+#[cfg(feature = "rpc-client")]
+pub mod sample;
 pub mod state;
 pub mod store;
 
@@ -31,5 +43,7 @@ pub use tendermint_light_client_verifier as verifier;
 // Re-export for backward compatibility
 pub use verifier::{operations, predicates, types};
 
+#[doc(hidden)]
+pub mod simulation;
 #[doc(hidden)]
 pub mod tests;
@@ -2,22 +2,93 @@
 //!
 //! [1]: https://github.com/informalsystems/tendermint-rs/blob/main/docs/spec/lightclient/verification/verification.md
 
-use core::fmt;
+use core::{fmt, num::NonZeroU64};
+use std::collections::BTreeMap;
+#[cfg(any(feature = "metrics", feature = "rust-crypto"))]
+use std::sync::Arc;
 
 use contracts::*;
 
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+#[cfg(feature = "rust-crypto")]
+use crate::verifier::operations::{HeaderHasher, ProdHeaderHasher};
 // Re-export for backward compatibility
 pub use crate::verifier::options::Options;
 use crate::{
-    components::{clock::Clock, io::*, scheduler::*},
+    builder::TrustOptions,
+    components::{
+        clock::{Clock, MonotonicClock},
+        io::*,
+        scheduler::*,
+    },
     contracts::*,
     errors::Error,
     state::State,
     verifier::{
-        types::{Height, LightBlock, PeerId, Status},
+        types::{Height, LightBlock, PeerId, Status, Time},
         Verdict, Verifier,
     },
 };
+use tendermint::chain::Id as ChainId;
+
+/// How far ahead of its trusted state the light client may skip when verifying
+/// a block.
+///
+/// Skipping verification trusts a block signed by more than the trust
+/// threshold of the validators of the trusted block, however distant. Custody
+/// deployments which cannot afford to trust any such commit can instead
+/// require the light client to go through intermediate checkpoints, each
+/// verified against the previous one.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Strictness {
+    /// Skip to the target block, bisecting only when the validator sets of
+    /// the trusted and target blocks do not overlap enough.
+    #[default]
+    Skipping,
+    /// Never skip more than the given number of heights ahead of the trusted
+    /// state. With an interval of one, every block is verified against the
+    /// previous one, checking that its validator set is the one announced by
+    /// the `next_validators_hash` of the previous block.
+    Checkpoints(NonZeroU64),
+}
+
+impl Strictness {
+    /// Verify every block against the previous one.
+    pub const SEQUENTIAL: Self = Self::Checkpoints(NonZeroU64::MIN);
+
+    /// The height to verify next, given the trusted height and the height
+    /// picked by the scheduler.
+    fn next_height(&self, trusted_height: Height, scheduled_height: Height) -> Height {
+        match self {
+            Self::Skipping => scheduled_height,
+            Self::Checkpoints(interval) => {
+                let checkpoint = trusted_height.value().saturating_add(interval.get());
+                if checkpoint < scheduled_height.value() {
+                    Height::try_from(checkpoint).unwrap_or(scheduled_height)
+                } else {
+                    scheduled_height
+                }
+            },
+        }
+    }
+}
+
+/// A coordinated upgrade of the chain to a successor chain id, which the light
+/// client follows from a new trust anchor on the successor chain instead of
+/// halting with [`ErrorDetail::UpgradeDetected`].
+///
+/// The successor chain is expected to carry on at heights above the ones of the
+/// original chain, the trust anchor being typically its first block.
+///
+/// [`ErrorDetail::UpgradeDetected`]: crate::errors::ErrorDetail::UpgradeDetected
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainUpgrade {
+    /// The chain id of the successor chain
+    pub chain_id: ChainId,
+    /// The trusted header of the successor chain
+    pub trust_options: TrustOptions,
+}
 
 /// The light client implements a read operation of a header from the blockchain,
 /// by communicating with full nodes. As full nodes may be faulty, it cannot trust
@@ -34,11 +105,19 @@ pub struct LightClient {
     pub peer: PeerId,
     /// Options for this light client
     pub options: Options,
+    /// How far ahead of its trusted state this light client may skip
+    pub strictness: Strictness,
 
+    // Never goes backwards, see `MonotonicClock`.
     clock: Box<dyn Clock>,
     scheduler: Box<dyn Scheduler>,
     verifier: Box<dyn Verifier>,
     io: Box<dyn Io>,
+    upgrade: Option<ChainUpgrade>,
+    #[cfg(feature = "rust-crypto")]
+    header_hasher: Arc<dyn HeaderHasher>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl fmt::Debug for LightClient {
@@ -46,6 +125,7 @@ impl fmt::Debug for LightClient {
         f.debug_struct("LightClient")
             .field("peer", &self.peer)
             .field("options", &self.options)
+            .field("strictness", &self.strictness)
             .finish()
     }
 }
@@ -63,10 +143,16 @@ impl LightClient {
         Self {
             peer,
             options,
-            clock: Box::new(clock),
+            strictness: Strictness::default(),
+            clock: Box::new(MonotonicClock::new(clock)),
             scheduler: Box::new(scheduler),
             verifier: Box::new(verifier),
             io: Box::new(io),
+            upgrade: None,
+            #[cfg(feature = "rust-crypto")]
+            header_hasher: Arc::new(ProdHeaderHasher::default()),
+            #[cfg(feature = "metrics")]
+            metrics: None,
         }
     }
 
@@ -82,10 +168,73 @@ impl LightClient {
         Self {
             peer,
             options,
-            clock,
+            strictness: Strictness::default(),
+            clock: Box::new(MonotonicClock::from_boxed(clock)),
             scheduler,
             verifier,
             io,
+            upgrade: None,
+            #[cfg(feature = "rust-crypto")]
+            header_hasher: Arc::new(ProdHeaderHasher::default()),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+
+    /// Set how far ahead of its trusted state the light client may skip.
+    pub fn with_strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Follow the given upgrade of the chain, rather than halting when the
+    /// primary starts serving blocks of the successor chain.
+    #[cfg(feature = "rust-crypto")]
+    pub fn with_chain_upgrade(mut self, upgrade: ChainUpgrade) -> Self {
+        self.upgrade = Some(upgrade);
+        self
+    }
+
+    /// Hash headers with the given hasher rather than the canonical one, when
+    /// linking adjacent headers and checking the anchor of a chain upgrade,
+    /// for a chain whose headers are hashed differently. Its verifier and I/O
+    /// should hash headers the same way, see
+    /// [`PredicateVerifier::with_header_hasher`] and
+    /// [`ProdIo::with_header_hasher`].
+    ///
+    /// [`PredicateVerifier::with_header_hasher`]: crate::verifier::PredicateVerifier::with_header_hasher
+    /// [`ProdIo::with_header_hasher`]: crate::components::io::ProdIo::with_header_hasher
+    #[cfg(feature = "rust-crypto")]
+    pub fn with_header_hasher(mut self, header_hasher: Arc<dyn HeaderHasher>) -> Self {
+        self.header_hasher = header_hasher;
+        self
+    }
+
+    /// The hasher of the headers of this light client.
+    #[cfg(feature = "rust-crypto")]
+    pub fn header_hasher(&self) -> &dyn HeaderHasher {
+        self.header_hasher.as_ref()
+    }
+
+    /// Record the metrics of the verifications performed by the light client.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    fn core(&self) -> Core<'_> {
+        Core {
+            options: &self.options,
+            strictness: self.strictness,
+            clock: self.clock.as_ref(),
+            scheduler: self.scheduler.as_ref(),
+            verifier: self.verifier.as_ref(),
+            upgrade: self.upgrade.as_ref(),
+            #[cfg(feature = "rust-crypto")]
+            header_hasher: self.header_hasher.as_ref(),
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.as_deref(),
         }
     }
 
@@ -143,26 +292,70 @@ impl LightClient {
         target_height: Height,
         state: &mut State,
     ) -> Result<LightBlock, Error> {
-        // Let's first look in the store to see whether
-        // we have already successfully verified this block.
-        if let Some(light_block) = state.light_store.get_trusted_or_verified(target_height) {
-            return Ok(light_block);
-        }
+        let _span =
+            tracing::debug_span!("verify_to_target", target_height = %target_height).entered();
 
-        // Get the highest trusted state
-        let highest = state
-            .light_store
-            .highest_trusted_or_verified_before(target_height)
-            .or_else(|| state.light_store.lowest_trusted_or_verified())
-            .ok_or_else(Error::no_initial_trusted_state)?;
+        let result = match direction(target_height, state)? {
+            Direction::Stored(light_block) => Ok(light_block),
+            Direction::Forward => self.verify_forward(target_height, state),
+            Direction::Backward => self.verify_backward(target_height, state),
+        };
 
-        if target_height >= highest.height() {
-            // Perform forward verification with bisection
-            self.verify_forward(target_height, state)
-        } else {
-            // Perform sequential backward verification
-            self.verify_backward(target_height, state)
-        }
+        self.core().observe_state(state);
+        result
+    }
+
+    /// Update the light client to the blocks of the primary node at each of the
+    /// given heights, and return the result of the verification of each of them.
+    ///
+    /// The heights are verified in ascending order, each with
+    /// [`LightClient::verify_to_target`], so that the blocks verified for a
+    /// height are the trusted state the next one is verified from, and that the
+    /// blocks fetched by bisection are fetched once for all heights. Failing to
+    /// verify a height does not prevent verifying the others.
+    pub fn verify_heights(
+        &self,
+        heights: &[Height],
+        state: &mut State,
+    ) -> BTreeMap<Height, Result<LightBlock, Error>> {
+        let mut heights = heights.to_vec();
+        heights.sort();
+        heights.dedup();
+
+        heights
+            .into_iter()
+            .map(|height| (height, self.verify_to_target(height, state)))
+            .collect()
+    }
+
+    /// Verify a batch of light blocks supplied by the caller, e.g. downloaded
+    /// out-of-band ahead of time, without fetching any block from the primary node.
+    ///
+    /// The blocks are verified in ascending order of height, each against the highest
+    /// trusted or verified block below it. The batch must thus include the intermediate
+    /// blocks bisection would have fetched to reach its highest block, if the validator
+    /// sets change too much in between, and the checkpoints required by the
+    /// [`Strictness`] of the light client. Blocks which pass verification are added to the
+    /// light store with the `Verified` status, and those which cannot be trusted yet with
+    /// the `Unverified` status.
+    ///
+    /// ## Postcondition
+    /// - The light store contains a trusted or verified light block at the height of the
+    ///   highest block of the batch, which is returned.
+    ///
+    /// ## Error conditions
+    /// - The batch is empty
+    /// - The light store does not contain a trusted light block below a block of the batch
+    /// - The trusted light block a block of the batch is verified against is outside of the
+    ///   trusting period [LCV-INV-TP.1]
+    /// - If verification of a light block fails
+    /// - If the highest block of the batch cannot be trusted from the other blocks
+    pub fn verify_batch(
+        &self,
+        light_blocks: Vec<LightBlock>,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        self.core().verify_batch(light_blocks, state)
     }
 
     /// Perform forward verification with bisection.
@@ -171,105 +364,64 @@ impl LightClient {
         target_height: Height,
         state: &mut State,
     ) -> Result<LightBlock, Error> {
+        let core = self.core();
         let mut current_height = target_height;
+        let mut depth = 0;
 
         loop {
-            let now = self.clock.now();
-
-            // Get the latest trusted state
-            let trusted_block = state
-                .light_store
-                .highest_trusted_or_verified_before(target_height)
-                .ok_or_else(Error::no_initial_trusted_state)?;
-
-            if target_height < trusted_block.height() {
-                return Err(Error::target_lower_than_trusted_state(
-                    target_height,
-                    trusted_block.height(),
-                ));
-            }
-
-            // Check invariant [LCV-INV-TP.1]
-            if !is_within_trust_period(&trusted_block, self.options.trusting_period, now) {
-                return Err(Error::trusted_state_outside_trusting_period(
-                    Box::new(trusted_block),
-                    self.options,
-                ));
-            }
-
-            // Log the current height as a dependency of the block at the target height
-            state.trace_block(target_height, current_height);
-
-            // If the trusted state is now at a height equal to the target height, we are done.
-            // [LCV-DIST-LIFE.1]
-            if target_height == trusted_block.height() {
-                return Ok(trusted_block);
-            }
+            let (trusted_block, now) =
+                match core.forward_step(target_height, &mut current_height, state)? {
+                    ForwardStep::Done(trusted_block) => {
+                        core.observe_bisection_depth(depth);
+                        return Ok(trusted_block);
+                    },
+                    ForwardStep::Fetch { trusted_block, now } => (trusted_block, now),
+                };
+            depth += 1;
 
             // Fetch the block at the current height from the light store if already present,
             // or from the primary peer otherwise.
             let (current_block, status) = self.get_or_fetch_block(current_height, state)?;
 
-            // Validate and verify the current block
-            let verdict = self.verifier.verify_update_header(
-                current_block.as_untrusted_state(),
-                trusted_block.as_trusted_state(),
-                &self.options,
+            let verified = core.verify_fetched(
+                target_height,
+                &current_block,
+                status,
+                &trusted_block,
                 now,
+                state,
             );
 
-            match verdict {
-                Verdict::Success => {
-                    // Verification succeeded, add the block to the light store with
-                    // the `Verified` status or higher if already trusted.
-                    let new_status = Status::most_trusted(Status::Verified, status);
-                    state.light_store.update(&current_block, new_status);
-
-                    // Log the trusted height as a dependency of the block at the current height
-                    state.trace_block(current_height, trusted_block.height());
+            current_height = match verified {
+                Ok(next_height) => next_height,
+                Err(e) => {
+                    // Carry on from the anchor of the successor chain.
+                    #[cfg(feature = "rust-crypto")]
+                    if let Some(upgrade) = core.pending_upgrade(&e, state) {
+                        let anchor = self
+                            .io
+                            .fetch_light_block(AtHeight::At(upgrade.trust_options.height))
+                            .map_err(Error::io)?;
+                        core.trust_upgrade_anchor(upgrade, anchor, state)?;
+                        current_height = target_height;
+                        continue;
+                    }
+                    return Err(e);
                 },
-                Verdict::Invalid(e) => {
-                    // Verification failed, add the block to the light store with `Failed` status,
-                    // and abort.
-                    state.light_store.update(&current_block, Status::Failed);
-
-                    return Err(Error::invalid_light_block(e));
-                },
-                Verdict::NotEnoughTrust(_) => {
-                    // The current block cannot be trusted because of a missing overlap in the
-                    // validator sets. Add the block to the light store with
-                    // the `Unverified` status. This will engage bisection in an
-                    // attempt to raise the height of the highest trusted state
-                    // until there is enough overlap.
-                    state.light_store.update(&current_block, Status::Unverified);
-                },
-            }
-
-            // Compute the next height to fetch and verify
-            current_height =
-                self.scheduler
-                    .schedule(state.light_store.as_ref(), current_height, target_height);
+            };
         }
     }
 
-    /// Stub for when "unstable" feature is disabled.
+    /// Stub for when the "rust-crypto" feature, which provides the hasher
+    /// linking the headers together, is disabled.
     #[doc(hidden)]
-    #[cfg(not(feature = "unstable"))]
+    #[cfg(not(feature = "rust-crypto"))]
     fn verify_backward(
         &self,
         target_height: Height,
         state: &mut State,
     ) -> Result<LightBlock, Error> {
-        let trusted_state = state
-            .light_store
-            .highest_trusted_or_verified_before(target_height)
-            .or_else(|| state.light_store.lowest_trusted_or_verified())
-            .ok_or_else(Error::no_initial_trusted_state)?;
-
-        Err(Error::target_lower_than_trusted_state(
-            target_height,
-            trusted_state.height(),
-        ))
+        Err(backward_unavailable(target_height, state))
     }
 
     /// Perform sequential backward verification.
@@ -277,7 +429,12 @@ impl LightClient {
     /// Backward verification is implemented by taking a sliding window
     /// of length two between the trusted state and the target block and
     /// checking whether the last_block_id hash of the higher block
-    /// matches the computed hash of the lower block.
+    /// matches the computed hash of the lower block, as well as that the
+    /// lower block is for the same chain and has an earlier time, like the
+    /// Go light client does.
+    ///
+    /// This lets applications prove historical state, e.g. the app hash of
+    /// an old block, after syncing forward.
     ///
     /// ## Performance
     /// The algorithm implemented is very inefficient in case the target
@@ -285,34 +442,18 @@ impl LightClient {
     /// For a trusted state at height `T`, and a target block at height `H`,
     /// it will fetch and check hashes of `T - H` blocks.
     ///
-    /// ## Stability
-    /// This feature is only available if the `unstable` flag of is enabled.
-    /// If the flag is disabled, then any attempt to verify a block whose
-    /// height is lower than the highest trusted state will result in a
-    /// `TargetLowerThanTrustedState` error.
-    #[cfg(feature = "unstable")]
+    /// ## Availability
+    /// This feature is only available if the `rust-crypto` feature, enabled
+    /// by default, is enabled. If it is disabled, then any attempt to verify
+    /// a block whose height is lower than the highest trusted state will
+    /// result in a `TargetLowerThanTrustedState` error.
+    #[cfg(feature = "rust-crypto")]
     fn verify_backward(
         &self,
         target_height: Height,
         state: &mut State,
     ) -> Result<LightBlock, Error> {
-        use tendermint::crypto::default::Sha256;
-
-        let root = state
-            .light_store
-            .highest_trusted_or_verified_before(target_height)
-            .or_else(|| state.light_store.lowest_trusted_or_verified())
-            .ok_or_else(Error::no_initial_trusted_state)?;
-
-        assert!(root.height() >= target_height);
-
-        // Check invariant [LCV-INV-TP.1]
-        if !is_within_trust_period(&root, self.options.trusting_period, self.clock.now()) {
-            return Err(Error::trusted_state_outside_trusting_period(
-                Box::new(root),
-                self.options,
-            ));
-        }
+        let root = self.core().backward_root(target_height, state)?;
 
         // Compute a range of `Height`s from `trusted_height - 1` to `target_height`, inclusive.
         let range = (target_height.value()..root.height().value()).rev();
@@ -322,30 +463,7 @@ impl LightClient {
 
         for height in heights {
             let (current, _status) = self.get_or_fetch_block(height, state)?;
-
-            let latest_last_block_id = latest
-                .signed_header
-                .header
-                .last_block_id
-                .ok_or_else(|| Error::missing_last_block_id(latest.height()))?;
-
-            let current_hash = current.signed_header.header.hash_with::<Sha256>();
-
-            if current_hash != latest_last_block_id.hash {
-                return Err(Error::invalid_adjacent_headers(
-                    current_hash,
-                    latest_last_block_id.hash,
-                ));
-            }
-
-            // `latest` and `current` are linked together by `last_block_id`,
-            // therefore it is not relevant which we verified first.
-            // For consistency, we say that `latest` was verifed using
-            // `current` so that the trace is always pointing down the chain.
-            state.light_store.insert(current.clone(), Status::Trusted);
-            state.light_store.insert(latest.clone(), Status::Trusted);
-            state.trace_block(latest.height(), current.height());
-
+            link_backward(&latest, &current, self.header_hasher.as_ref(), state)?;
             latest = current;
         }
 
@@ -421,3 +539,525 @@ pub enum TargetOrLatest {
     Latest(LightBlock),
     Target(LightBlock),
 }
+
+/// The components of a light client which do not perform I/O, borrowed from a
+/// [`LightClient`] or an [`AsyncLightClient`](crate::async_light_client::AsyncLightClient)
+/// to run the steps of verification they have in common.
+pub(crate) struct Core<'a> {
+    pub(crate) options: &'a Options,
+    pub(crate) strictness: Strictness,
+    pub(crate) clock: &'a dyn Clock,
+    pub(crate) scheduler: &'a dyn Scheduler,
+    pub(crate) verifier: &'a dyn Verifier,
+    pub(crate) upgrade: Option<&'a ChainUpgrade>,
+    #[cfg(feature = "rust-crypto")]
+    pub(crate) header_hasher: &'a dyn HeaderHasher,
+    #[cfg(feature = "metrics")]
+    pub(crate) metrics: Option<&'a Metrics>,
+}
+
+/// How to verify a light block at a target height.
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum Direction {
+    /// The block is already trusted or verified in the light store
+    Stored(LightBlock),
+    /// The block is above the highest trusted state
+    Forward,
+    /// The block is below the highest trusted state
+    Backward,
+}
+
+/// The outcome of a step of forward verification.
+pub(crate) enum ForwardStep {
+    /// The trusted state reached the target height
+    Done(LightBlock),
+    /// The block at the current height must be fetched and verified against
+    /// the given trusted block
+    Fetch {
+        trusted_block: LightBlock,
+        now: Time,
+    },
+}
+
+/// Decide how to verify the light block at the given height.
+pub(crate) fn direction(target_height: Height, state: &State) -> Result<Direction, Error> {
+    // Let's first look in the store to see whether
+    // we have already successfully verified this block.
+    if let Some(light_block) = state.light_store.get_trusted_or_verified(target_height) {
+        return Ok(Direction::Stored(light_block));
+    }
+
+    // Get the highest trusted state
+    let highest = state
+        .light_store
+        .highest_trusted_or_verified_before(target_height)
+        .or_else(|| state.light_store.lowest_trusted_or_verified())
+        .ok_or_else(Error::no_initial_trusted_state)?;
+
+    if target_height >= highest.height() {
+        Ok(Direction::Forward)
+    } else {
+        Ok(Direction::Backward)
+    }
+}
+
+/// The error of verifying a light block below the highest trusted state, when
+/// backward verification is not enabled.
+#[cfg(not(feature = "rust-crypto"))]
+pub(crate) fn backward_unavailable(target_height: Height, state: &State) -> Error {
+    match state
+        .light_store
+        .highest_trusted_or_verified_before(target_height)
+        .or_else(|| state.light_store.lowest_trusted_or_verified())
+    {
+        Some(trusted_state) => {
+            Error::target_lower_than_trusted_state(target_height, trusted_state.height())
+        },
+        None => Error::no_initial_trusted_state(),
+    }
+}
+
+/// Check that the `current` block is the one right below the `latest` one, as
+/// per the `last_block_id` of the latter, and trust both of them.
+#[cfg(feature = "rust-crypto")]
+pub(crate) fn link_backward(
+    latest: &LightBlock,
+    current: &LightBlock,
+    header_hasher: &dyn HeaderHasher,
+    state: &mut State,
+) -> Result<(), Error> {
+    let latest_last_block_id = latest
+        .signed_header
+        .header
+        .last_block_id
+        .ok_or_else(|| Error::missing_last_block_id(latest.height()))?;
+
+    let current_header = &current.signed_header.header;
+    let latest_header = &latest.signed_header.header;
+
+    if current_header.chain_id != latest_header.chain_id {
+        return Err(Error::chain_id_mismatch(
+            current.height(),
+            current_header.chain_id.clone(),
+            latest_header.chain_id.clone(),
+        ));
+    }
+
+    if !current_header.time.before(latest_header.time) {
+        return Err(Error::non_decreasing_header_time(
+            current.height(),
+            current_header.time,
+            latest_header.time,
+        ));
+    }
+
+    let current_hash = header_hasher.hash(current_header);
+
+    if current_hash != latest_last_block_id.hash {
+        return Err(Error::invalid_adjacent_headers(
+            current_hash,
+            latest_last_block_id.hash,
+        ));
+    }
+
+    // `latest` and `current` are linked together by `last_block_id`,
+    // therefore it is not relevant which we verified first.
+    // For consistency, we say that `latest` was verifed using
+    // `current` so that the trace is always pointing down the chain.
+    state.light_store.insert(current.clone(), Status::Trusted);
+    state.light_store.insert(latest.clone(), Status::Trusted);
+    state.trace_block(latest.height(), current.height());
+
+    Ok(())
+}
+
+impl Core<'_> {
+    /// Verify a batch of light blocks (see [`LightClient::verify_batch`]).
+    pub(crate) fn verify_batch(
+        &self,
+        mut light_blocks: Vec<LightBlock>,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        light_blocks.sort_by_key(|lb| lb.height());
+        let target_height = light_blocks
+            .last()
+            .map(|lb| lb.height())
+            .ok_or_else(Error::empty_batch)?;
+
+        let _span = tracing::debug_span!(
+            "verify_batch",
+            target_height = %target_height,
+            blocks = light_blocks.len(),
+        )
+        .entered();
+
+        let now = self.clock.now();
+
+        for light_block in light_blocks {
+            let height = light_block.height();
+            if state.light_store.get_trusted_or_verified(height).is_some() {
+                continue;
+            }
+
+            let trusted_block = match state.light_store.highest_trusted_or_verified_before(height) {
+                Some(trusted_block) => trusted_block,
+                None => {
+                    return Err(match state.light_store.lowest_trusted_or_verified() {
+                        Some(lowest) => {
+                            Error::target_lower_than_trusted_state(height, lowest.height())
+                        },
+                        None => Error::no_initial_trusted_state(),
+                    })
+                },
+            };
+
+            // Check invariant [LCV-INV-TP.1]
+            if !is_within_trust_period(&trusted_block, self.options.trusting_period, now) {
+                return Err(Error::trusted_state_outside_trusting_period(
+                    Box::new(trusted_block),
+                    *self.options,
+                ));
+            }
+
+            // Blocks beyond the next checkpoint cannot be trusted yet.
+            if self.strictness.next_height(trusted_block.height(), height) != height {
+                state.light_store.update(&light_block, Status::Unverified);
+                continue;
+            }
+
+            self.check_chain_id(&light_block, &trusted_block)?;
+
+            // Log the current height as a dependency of the block at the target height
+            state.trace_block(target_height, height);
+
+            let verdict = self.verifier.verify_update_header(
+                light_block.as_untrusted_state(),
+                trusted_block.as_trusted_state(),
+                self.options,
+                now,
+            );
+
+            self.trace_verdict(&verdict, height, trusted_block.height());
+            match verdict {
+                Verdict::Success => {
+                    state.light_store.update(&light_block, Status::Verified);
+                    state.trace_block(height, trusted_block.height());
+                },
+                Verdict::Invalid(e) => {
+                    state.light_store.update(&light_block, Status::Failed);
+                    return Err(Error::invalid_light_block(e));
+                },
+                Verdict::NotEnoughTrust(_) => {
+                    state.light_store.update(&light_block, Status::Unverified);
+                },
+            }
+        }
+
+        match state.light_store.get_trusted_or_verified(target_height) {
+            Some(light_block) => Ok(light_block),
+            None => {
+                let trusted_height = state
+                    .light_store
+                    .highest_trusted_or_verified_before(target_height)
+                    .map(|lb| lb.height())
+                    .ok_or_else(Error::no_initial_trusted_state)?;
+
+                Err(Error::incomplete_batch(target_height, trusted_height))
+            },
+        }
+    }
+
+    /// Move forward verification towards the target height: check the highest
+    /// trusted state below it, and lower the current height to the next
+    /// checkpoint if needed.
+    pub(crate) fn forward_step(
+        &self,
+        target_height: Height,
+        current_height: &mut Height,
+        state: &mut State,
+    ) -> Result<ForwardStep, Error> {
+        let now = self.clock.now();
+
+        // Get the latest trusted state
+        let trusted_block = state
+            .light_store
+            .highest_trusted_or_verified_before(target_height)
+            .ok_or_else(Error::no_initial_trusted_state)?;
+
+        if target_height < trusted_block.height() {
+            return Err(Error::target_lower_than_trusted_state(
+                target_height,
+                trusted_block.height(),
+            ));
+        }
+
+        // Check invariant [LCV-INV-TP.1]
+        if !is_within_trust_period(&trusted_block, self.options.trusting_period, now) {
+            return Err(Error::trusted_state_outside_trusting_period(
+                Box::new(trusted_block),
+                *self.options,
+            ));
+        }
+
+        // Stop at the next checkpoint if not allowed to skip to the current height.
+        *current_height = self
+            .strictness
+            .next_height(trusted_block.height(), *current_height);
+
+        // Log the current height as a dependency of the block at the target height
+        state.trace_block(target_height, *current_height);
+
+        // If the trusted state is now at a height equal to the target height, we are done.
+        // [LCV-DIST-LIFE.1]
+        if target_height == trusted_block.height() {
+            return Ok(ForwardStep::Done(trusted_block));
+        }
+
+        Ok(ForwardStep::Fetch { trusted_block, now })
+    }
+
+    /// Verify the block fetched during forward verification against the
+    /// trusted block, and return the next height to fetch and verify.
+    pub(crate) fn verify_fetched(
+        &self,
+        target_height: Height,
+        current_block: &LightBlock,
+        status: Status,
+        trusted_block: &LightBlock,
+        now: Time,
+        state: &mut State,
+    ) -> Result<Height, Error> {
+        let current_height = current_block.height();
+
+        // Tell an upgrade of the chain apart from an invalid block
+        self.check_chain_id(current_block, trusted_block)?;
+
+        // Validate and verify the current block
+        let verdict = self.verifier.verify_update_header(
+            current_block.as_untrusted_state(),
+            trusted_block.as_trusted_state(),
+            self.options,
+            now,
+        );
+
+        self.trace_verdict(&verdict, current_height, trusted_block.height());
+        match verdict {
+            Verdict::Success => {
+                // Verification succeeded, add the block to the light store with
+                // the `Verified` status or higher if already trusted.
+                let new_status = Status::most_trusted(Status::Verified, status);
+                state.light_store.update(current_block, new_status);
+
+                // Log the trusted height as a dependency of the block at the current height
+                state.trace_block(current_height, trusted_block.height());
+            },
+            Verdict::Invalid(e) => {
+                // Verification failed, add the block to the light store with `Failed` status,
+                // and abort.
+                state.light_store.update(current_block, Status::Failed);
+
+                return Err(Error::invalid_light_block(e));
+            },
+            Verdict::NotEnoughTrust(_) => {
+                // The current block cannot be trusted because of a missing overlap in the
+                // validator sets. Add the block to the light store with
+                // the `Unverified` status. This will engage bisection in an
+                // attempt to raise the height of the highest trusted state
+                // until there is enough overlap.
+                state.light_store.update(current_block, Status::Unverified);
+            },
+        }
+
+        // Compute the next height to fetch and verify
+        Ok(self
+            .scheduler
+            .schedule(state.light_store.as_ref(), current_height, target_height))
+    }
+
+    /// The highest trusted state backward verification starts from.
+    #[cfg(feature = "rust-crypto")]
+    pub(crate) fn backward_root(
+        &self,
+        target_height: Height,
+        state: &State,
+    ) -> Result<LightBlock, Error> {
+        let root = state
+            .light_store
+            .highest_trusted_or_verified_before(target_height)
+            .or_else(|| state.light_store.lowest_trusted_or_verified())
+            .ok_or_else(Error::no_initial_trusted_state)?;
+
+        assert!(root.height() >= target_height);
+
+        // Check invariant [LCV-INV-TP.1]
+        if !is_within_trust_period(&root, self.options.trusting_period, self.clock.now()) {
+            return Err(Error::trusted_state_outside_trusting_period(
+                Box::new(root),
+                *self.options,
+            ));
+        }
+
+        Ok(root)
+    }
+
+    /// Check that the given block is for the chain of the trusted block, telling
+    /// a coordinated upgrade to a successor chain apart from a block of an
+    /// unrelated chain.
+    fn check_chain_id(
+        &self,
+        light_block: &LightBlock,
+        trusted_block: &LightBlock,
+    ) -> Result<(), Error> {
+        let chain_id = &light_block.signed_header.header.chain_id;
+        let previous = &trusted_block.signed_header.header.chain_id;
+        if chain_id == previous {
+            return Ok(());
+        }
+
+        let is_successor = self
+            .upgrade
+            .is_some_and(|upgrade| &upgrade.chain_id == chain_id)
+            || is_revision_bump(previous, chain_id);
+
+        if is_successor {
+            Err(Error::upgrade_detected(
+                light_block.height(),
+                chain_id.clone(),
+                previous.clone(),
+            ))
+        } else {
+            Err(Error::chain_id_mismatch(
+                light_block.height(),
+                chain_id.clone(),
+                previous.clone(),
+            ))
+        }
+    }
+
+    /// The configured upgrade, if the given error is the detection of it and
+    /// the anchor of the successor chain is not trusted yet.
+    #[cfg(feature = "rust-crypto")]
+    pub(crate) fn pending_upgrade(&self, e: &Error, state: &State) -> Option<&ChainUpgrade> {
+        use crate::errors::ErrorDetail;
+
+        let upgrade = self.upgrade?;
+        match e.detail() {
+            ErrorDetail::UpgradeDetected(e) if e.chain_id == upgrade.chain_id => {},
+            _ => return None,
+        }
+
+        let anchor_trusted = state
+            .light_store
+            .get(upgrade.trust_options.height, Status::Trusted)
+            .is_some_and(|lb| lb.signed_header.header.chain_id == upgrade.chain_id);
+
+        (!anchor_trusted).then_some(upgrade)
+    }
+
+    /// Validate the anchor of the successor chain fetched from the primary
+    /// against the trust options of the upgrade, like the initial trusted state,
+    /// and trust it.
+    #[cfg(feature = "rust-crypto")]
+    pub(crate) fn trust_upgrade_anchor(
+        &self,
+        upgrade: &ChainUpgrade,
+        anchor: LightBlock,
+        state: &mut State,
+    ) -> Result<(), Error> {
+        use crate::verifier::predicates::{ProdPredicates, VerificationPredicates};
+
+        let TrustOptions { height, hash } = upgrade.trust_options;
+        let header = &anchor.signed_header.header;
+
+        if anchor.height() != height
+            || header.chain_id != upgrade.chain_id
+            || self.header_hasher.hash(header) != hash
+        {
+            return Err(Error::invalid_upgrade_anchor(
+                height,
+                upgrade.chain_id.clone(),
+                hash,
+            ));
+        }
+
+        let now = self.clock.now();
+        let predicates = ProdPredicates;
+        predicates
+            .is_within_trust_period(header.time, self.options.trusting_period, now)
+            .and_then(|_| {
+                predicates.is_header_from_past(header.time, self.options.clock_drift, now)
+            })
+            .and_then(|_| {
+                predicates.validator_sets_match(&anchor.validators, header.validators_hash)
+            })
+            .and_then(|_| {
+                predicates
+                    .next_validators_match(&anchor.next_validators, header.next_validators_hash)
+            })
+            .map_err(|e| Error::invalid_light_block(e.0))?;
+
+        tracing::info!(%height, chain_id = %upgrade.chain_id, "following chain upgrade");
+        state.light_store.insert(anchor, Status::Trusted);
+
+        Ok(())
+    }
+
+    /// Record the verdict of verifying the block at `height` against the trusted
+    /// block at `trusted_height`.
+    fn trace_verdict(&self, verdict: &Verdict, height: Height, trusted_height: Height) {
+        match verdict {
+            Verdict::Success => {
+                tracing::debug!(%height, %trusted_height, "verified light block")
+            },
+            Verdict::Invalid(e) => {
+                tracing::warn!(%height, %trusted_height, error = %e, "invalid light block")
+            },
+            Verdict::NotEnoughTrust(_) => {
+                tracing::debug!(%height, %trusted_height, "not enough trust in light block")
+            },
+        }
+
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics {
+            metrics.observe_verdict(verdict);
+        }
+    }
+
+    /// Record the number of blocks forward verification went through to reach
+    /// its target.
+    pub(crate) fn observe_bisection_depth(&self, depth: u64) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics {
+            metrics.observe_bisection_depth(depth);
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = depth;
+    }
+
+    /// Record the size of the light store and the age of the trusted state.
+    pub(crate) fn observe_state(&self, state: &State) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics {
+            metrics.observe_state(state, self.clock.now());
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = state;
+    }
+}
+
+/// Whether `chain_id` follows `previous` in the `{name}-{revision}` convention
+/// for the chain ids of coordinated upgrades, e.g. `cosmoshub-4` after
+/// `cosmoshub-3`.
+fn is_revision_bump(previous: &ChainId, chain_id: &ChainId) -> bool {
+    fn revision(chain_id: &ChainId) -> Option<(&str, u64)> {
+        let (name, revision) = chain_id.as_str().rsplit_once('-')?;
+        Some((name, revision.parse().ok()?))
+    }
+
+    match (revision(previous), revision(chain_id)) {
+        (Some((previous_name, previous_revision)), Some((name, revision))) => {
+            name == previous_name && revision > previous_revision
+        },
+        _ => false,
+    }
+}
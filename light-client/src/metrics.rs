@@ -0,0 +1,369 @@
+//! Prometheus metrics of a light client, for operating it in production.
+//!
+//! The [`Metrics`] are given to the light client with
+//! [`LightClientBuilder::metrics`](crate::builder::LightClientBuilder::metrics)
+//! (or the `with_metrics` method of the light clients), and registered with
+//! the registry exposed by the application with [`Metrics::register`].
+
+use std::time::Duration;
+
+use prometheus::{
+    exponential_buckets, Gauge, Histogram, HistogramOpts, IntCounterVec, IntGaugeVec, Opts,
+    Registry,
+};
+
+use crate::{
+    state::State,
+    store::LightStore,
+    verifier::{
+        types::{Status, Time},
+        Verdict,
+    },
+};
+
+const NAMESPACE: &str = "tendermint_light_client";
+
+/// The metrics of a light client, which can be shared by several light
+/// clients to aggregate theirs.
+#[derive(Clone, Debug)]
+pub struct Metrics {
+    verifications: IntCounterVec,
+    bisection_depth: Histogram,
+    cross_check_duration: Histogram,
+    store_blocks: IntGaugeVec,
+    trust_anchor_age: Gauge,
+}
+
+impl Metrics {
+    /// Create the metrics, to be registered with [`Metrics::register`].
+    pub fn new() -> prometheus::Result<Self> {
+        Ok(Self {
+            verifications: IntCounterVec::new(
+                Opts::new(
+                    "verifications_total",
+                    "Number of light blocks verified against a trusted one, by verdict",
+                )
+                .namespace(NAMESPACE),
+                &["verdict"],
+            )?,
+            bisection_depth: Histogram::with_opts(
+                HistogramOpts::new(
+                    "bisection_depth",
+                    "Number of light blocks verified to reach a target height",
+                )
+                .namespace(NAMESPACE)
+                .buckets(exponential_buckets(1.0, 2.0, 8)?),
+            )?,
+            cross_check_duration: Histogram::with_opts(
+                HistogramOpts::new(
+                    "cross_check_duration_seconds",
+                    "Time taken by a witness to cross-check a header of the primary",
+                )
+                .namespace(NAMESPACE),
+            )?,
+            store_blocks: IntGaugeVec::new(
+                Opts::new(
+                    "store_blocks",
+                    "Number of light blocks in the light store, by status",
+                )
+                .namespace(NAMESPACE),
+                &["status"],
+            )?,
+            trust_anchor_age: Gauge::with_opts(
+                Opts::new(
+                    "trust_anchor_age_seconds",
+                    "Age of the highest trusted or verified light block",
+                )
+                .namespace(NAMESPACE),
+            )?,
+        })
+    }
+
+    /// Register the metrics with the given registry.
+    pub fn register(&self, registry: &Registry) -> prometheus::Result<()> {
+        registry.register(Box::new(self.verifications.clone()))?;
+        registry.register(Box::new(self.bisection_depth.clone()))?;
+        registry.register(Box::new(self.cross_check_duration.clone()))?;
+        registry.register(Box::new(self.store_blocks.clone()))?;
+        registry.register(Box::new(self.trust_anchor_age.clone()))
+    }
+
+    /// Record the verdict of verifying a light block.
+    pub fn observe_verdict(&self, verdict: &Verdict) {
+        let verdict = match verdict {
+            Verdict::Success => "success",
+            Verdict::Invalid(_) => "invalid",
+            Verdict::NotEnoughTrust(_) => "not_enough_trust",
+        };
+        self.verifications.with_label_values(&[verdict]).inc();
+    }
+
+    /// Record the number of light blocks verified to reach a target height
+    /// with bisection.
+    pub fn observe_bisection_depth(&self, depth: u64) {
+        self.bisection_depth.observe(depth as f64);
+    }
+
+    /// Record the time taken by a witness to cross-check a header of the
+    /// primary, which the `tendermint-light-client-detector` crate does for
+    /// each of its witness reports with `CrossCheck::observe` when its
+    /// `metrics` feature is enabled.
+    pub fn observe_cross_check(&self, elapsed: Duration) {
+        self.cross_check_duration.observe(elapsed.as_secs_f64());
+    }
+
+    /// Record the age of the highest trusted or verified block of the light
+    /// store of the given state as of the given time.
+    ///
+    /// Light clients call this after each verification, while the size of
+    /// the light store is only recorded by [`Metrics::observe_store_size`].
+    pub fn observe_state(&self, state: &State, now: Time) {
+        if let Some(trust_anchor) = state.light_store.highest_trusted_or_verified() {
+            let age = now.duration_since(trust_anchor.time()).unwrap_or_default();
+            self.trust_anchor_age.set(age.as_secs_f64());
+        }
+    }
+
+    /// Record the number of light blocks in the given light store, by status.
+    ///
+    /// This reads every light block of the store, which stores backed by a
+    /// database decode, so it should be called sparingly, e.g. at an interval
+    /// with [`Metrics::spawn_store_sampler`] on the light store of an
+    /// [`Instance`](crate::instance::Instance).
+    pub fn observe_store_size<S: LightStore + ?Sized>(&self, light_store: &S) {
+        for status in Status::iter() {
+            let count = light_store.all(*status).count();
+            self.store_blocks
+                .with_label_values(&[status_label(*status)])
+                .set(count as i64);
+        }
+    }
+}
+
+fn status_label(status: Status) -> &'static str {
+    match status {
+        Status::Unverified => "unverified",
+        Status::Verified => "verified",
+        Status::Trusted => "trusted",
+        Status::Failed => "failed",
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod background {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::{
+        task::JoinHandle,
+        time::{interval, MissedTickBehavior},
+    };
+
+    use super::*;
+    use crate::instance::Instance;
+
+    impl Metrics {
+        /// Record the size of the light store of the given instance at the
+        /// given interval (see [`Metrics::observe_store_size`]), in a task of
+        /// the current Tokio runtime.
+        ///
+        /// The instance can be shared with the application using it, as the
+        /// store is only read while holding its lock.
+        ///
+        /// The task stops if the lock of the instance gets poisoned, and can
+        /// be stopped with [`JoinHandle::abort`] otherwise.
+        ///
+        /// ## Panics
+        /// If called outside of a Tokio runtime.
+        pub fn spawn_store_sampler(
+            self,
+            instance: Arc<Mutex<Instance>>,
+            every: Duration,
+        ) -> JoinHandle<()> {
+            tokio::spawn(async move {
+                let mut ticks = interval(every);
+                ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                loop {
+                    ticks.tick().await;
+                    match instance.lock() {
+                        Ok(instance) => {
+                            self.observe_store_size(instance.state.light_store.as_ref())
+                        },
+                        Err(_) => {
+                            tracing::warn!(
+                                "instance lock poisoned, no longer sampling its light store"
+                            );
+                            return;
+                        },
+                    }
+                }
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use tendermint_testgen::{light_block::default_peer_id, Generator, LightChain};
+
+    use super::*;
+    use crate::{
+        components::{
+            io::{AtHeight, Io},
+            scheduler,
+        },
+        light_client::LightClient,
+        store::{memory::MemoryStore, LightStore},
+        tests::{MockClock, MockIo},
+        verifier::{options::Options, types::LightBlock, ProdVerifier},
+    };
+
+    fn light_blocks(length: u64) -> Vec<LightBlock> {
+        LightChain::default_with_length(length)
+            .light_blocks
+            .into_iter()
+            .map(|lb| LightBlock::from(lb.generate().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn registers_with_namespace() {
+        let metrics = Metrics::new().unwrap();
+        let registry = Registry::new();
+        metrics.register(&registry).unwrap();
+
+        metrics.observe_verdict(&Verdict::Success);
+        metrics.observe_bisection_depth(3);
+        metrics.observe_cross_check(Duration::from_millis(250));
+
+        let names: Vec<_> = registry
+            .gather()
+            .iter()
+            .map(|family| family.get_name().to_owned())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "tendermint_light_client_bisection_depth",
+                "tendermint_light_client_cross_check_duration_seconds",
+                "tendermint_light_client_trust_anchor_age_seconds",
+                "tendermint_light_client_verifications_total",
+            ]
+        );
+    }
+
+    #[test]
+    fn records_verification_of_light_client() {
+        let io = MockIo::new(light_blocks(5));
+
+        let mut light_store = MemoryStore::new();
+        light_store.insert(
+            io.fetch_light_block(AtHeight::At(1_u32.into())).unwrap(),
+            Status::Trusted,
+        );
+        let mut state = State {
+            light_store: Box::new(light_store),
+            verification_trace: HashMap::new(),
+        };
+
+        let metrics = Arc::new(Metrics::new().unwrap());
+        let light_client = LightClient::new(
+            default_peer_id(),
+            Options {
+                trust_threshold: Default::default(),
+                trusting_period: Duration::from_secs(60),
+                clock_drift: Duration::from_secs(10),
+            },
+            MockClock {
+                now: tendermint_testgen::helpers::get_time(8).unwrap(),
+            },
+            scheduler::basic_bisecting_schedule,
+            ProdVerifier::default(),
+            io,
+        )
+        .with_metrics(metrics.clone());
+
+        light_client
+            .verify_to_target(5_u32.into(), &mut state)
+            .unwrap();
+
+        assert_eq!(
+            metrics.verifications.with_label_values(&["success"]).get(),
+            1
+        );
+        assert_eq!(metrics.bisection_depth.get_sample_count(), 1);
+        assert_eq!(metrics.bisection_depth.get_sample_sum(), 1.0);
+        assert_eq!(metrics.trust_anchor_age.get(), 3.0);
+
+        // The size of the store is only recorded on demand.
+        assert_eq!(
+            metrics.store_blocks.with_label_values(&["trusted"]).get(),
+            0
+        );
+        metrics.observe_store_size(state.light_store.as_ref());
+        assert_eq!(
+            metrics.store_blocks.with_label_values(&["trusted"]).get(),
+            1
+        );
+        assert_eq!(
+            metrics.store_blocks.with_label_values(&["verified"]).get(),
+            1
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn samples_store_size_of_instance_in_background() {
+        use std::sync::Mutex;
+
+        use crate::instance::Instance;
+
+        let io = MockIo::new(light_blocks(5));
+        let mut light_store = MemoryStore::new();
+        light_store.insert(
+            io.fetch_light_block(AtHeight::At(1_u32.into())).unwrap(),
+            Status::Trusted,
+        );
+        let light_client = LightClient::new(
+            default_peer_id(),
+            Options {
+                trust_threshold: Default::default(),
+                trusting_period: Duration::from_secs(60),
+                clock_drift: Duration::from_secs(10),
+            },
+            MockClock {
+                now: tendermint_testgen::helpers::get_time(8).unwrap(),
+            },
+            scheduler::basic_bisecting_schedule,
+            ProdVerifier::default(),
+            io,
+        );
+        let instance = Arc::new(Mutex::new(Instance::new(
+            light_client,
+            State::new(light_store),
+        )));
+
+        let metrics = Metrics::new().unwrap();
+        let handle = metrics
+            .clone()
+            .spawn_store_sampler(instance.clone(), Duration::from_millis(10));
+        while metrics.store_blocks.with_label_values(&["trusted"]).get() != 1 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // The sampler reads the store the light client verifies into.
+        {
+            let mut instance = instance.lock().unwrap();
+            let instance = &mut *instance;
+            instance
+                .light_client
+                .verify_to_target(5_u32.into(), &mut instance.state)
+                .unwrap();
+        }
+        while metrics.store_blocks.with_label_values(&["verified"]).get() != 1 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        handle.abort();
+    }
+}
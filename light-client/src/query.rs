@@ -0,0 +1,131 @@
+//! Queries of the state of an application, verified by a light client.
+//!
+//! [`verified_query`] queries the application with a proof of the result,
+//! verifies the header at the next height with a light client, and checks the
+//! proof against the app hash in that header, which commits to the state
+//! queried. The node answering the query thus needs not be trusted.
+
+use flex_error::define_error;
+use tendermint::{abci::Code, block::Header, merkle::proof::ProofRuntime};
+use tendermint_rpc::{self as rpc, Client};
+
+use crate::{
+    components::io::IoError, errors::Error as LightClientError, instance::Instance,
+    utils::block_on, verifier::types::Height,
+};
+
+define_error! {
+    #[derive(Debug)]
+    Error {
+        Io
+            [ IoError ]
+            | _ | { "failed to query the node" },
+
+        QueryFailed
+            {
+                code: Code,
+                codespace: String,
+                log: String,
+            }
+            | e | {
+                format_args!("the application failed the query with code {} in {}: {}",
+                    e.code.value(), e.codespace, e.log)
+            },
+
+        HeightMismatch
+            {
+                expected: Height,
+                found: Height,
+            }
+            | e | {
+                format_args!("queried the state at height {}, but the node answered at height {}",
+                    e.expected, e.found)
+            },
+
+        Verification
+            [ LightClientError ]
+            | _ | { "failed to verify the header committing to the queried state" },
+
+        InvalidProof
+            [ rpc::Error ]
+            | _ | { "the queried value is not proven by the verified app hash" },
+    }
+}
+
+/// A value of the state of an application, proven by the app hash of a
+/// header verified by a light client.
+#[derive(Clone, Debug)]
+pub struct VerifiedQuery {
+    /// The queried key, as reported by the application
+    pub key: Vec<u8>,
+    /// The value proven at the queried key
+    pub value: Vec<u8>,
+    /// The height at which the state was queried
+    pub height: Height,
+    /// The verified header at the next height, whose app hash commits to the
+    /// queried state
+    pub header: Header,
+}
+
+/// Query the application of the node behind the given client at the given
+/// path and height, and check the proof of the result against the app hash
+/// of the header at the next height, verified by the light client of the
+/// given instance.
+///
+/// The proof operations are decoded by the given runtime, such as
+/// [`ProofRuntime::with_ics23`] for the stores of Cosmos SDK applications.
+/// The keys proven by them are ordered from the outermost tree to the
+/// innermost one, for instance the name of a store followed by the queried
+/// key. Only values proven to exist can be verified.
+///
+/// The header at the next height must have been produced, so the latest state
+/// that can be queried is the one at the height before the latest block.
+///
+/// This blocks the calling thread until the query and the verification
+/// completed.
+pub fn verified_query(
+    instance: &mut Instance,
+    client: &rpc::HttpClient,
+    runtime: &ProofRuntime,
+    path: impl Into<String>,
+    data: impl Into<Vec<u8>>,
+    keys: &[&[u8]],
+    height: Height,
+) -> Result<VerifiedQuery, Error> {
+    let (client, path, data) = (client.clone(), path.into(), data.into());
+    let response = block_on(None, async move {
+        client
+            .abci_query(Some(path), data, Some(height), true)
+            .await
+    })
+    .map_err(Error::io)?
+    .map_err(|e| Error::io(IoError::from_rpc(e)))?;
+
+    if response.code.is_err() {
+        return Err(Error::query_failed(
+            response.code,
+            response.codespace,
+            response.log,
+        ));
+    }
+    if response.height != height {
+        return Err(Error::height_mismatch(height, response.height));
+    }
+
+    let light_block = instance
+        .light_client
+        .verify_to_target(height.increment(), &mut instance.state)
+        .map_err(Error::verification)?;
+    let header = light_block.signed_header.header;
+
+    response
+        .verify(runtime, &header.app_hash, keys)
+        .map_err(Error::invalid_proof)?;
+
+    Ok(VerifiedQuery {
+        key: response.key,
+        value: response.value,
+        height,
+        header,
+    })
+}
@@ -0,0 +1,221 @@
+//! A quick way of checking that a transaction was committed to a chain.
+//!
+//! [`verify_tx_inclusion`] goes through the whole stack: it fetches the
+//! transaction and its inclusion proof from a node, verifies the header of
+//! the block of the transaction with a light client initialized from the
+//! given trust options, and checks the proof against that header.
+
+use core::time::Duration;
+
+use flex_error::define_error;
+use tendermint::{
+    abci::types::ExecTxResult,
+    block::Header,
+    chain,
+    crypto::{default::Sha256, Sha256 as _},
+    Hash,
+};
+use tendermint_rpc::{self as rpc, Client};
+
+use crate::{
+    builder::{error::Error as BuilderError, LightClientBuilder, TrustOptions},
+    components::io::IoError,
+    errors::Error as LightClientError,
+    store::memory::MemoryStore,
+    utils::block_on,
+    verifier::{
+        options::Options,
+        types::{Height, TrustThreshold},
+    },
+};
+
+/// How long the light client trusts a validator set, shorter than the
+/// unbonding period of most chains.
+pub const DEFAULT_TRUSTING_PERIOD: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// How long to wait for each response of the node.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+define_error! {
+    #[derive(Debug)]
+    Error {
+        Io
+            [ IoError ]
+            | _ | { "failed to query the node" },
+
+        ChainIdMismatch
+            {
+                expected: chain::Id,
+                found: chain::Id,
+            }
+            | e | {
+                format_args!("expected chain {}, but the node is on chain {}",
+                    e.expected, e.found)
+            },
+
+        LightClient
+            [ BuilderError ]
+            | _ | { "failed to initialize the light client" },
+
+        Verification
+            [ LightClientError ]
+            | _ | { "failed to verify the header of the block of the transaction" },
+
+        NotIncluded
+            [ rpc::Error ]
+            | _ | { "the transaction is not proven to be included in the verified block" },
+
+        TxHashMismatch
+            {
+                expected: Hash,
+                found: Hash,
+            }
+            | e | {
+                format_args!("expected transaction {}, but the node returned transaction {}",
+                    e.expected, e.found)
+            },
+    }
+}
+
+/// Whether the application accepted a transaction included in a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The transaction was executed successfully.
+    Succeeded,
+    /// The application failed the transaction, which is still included in
+    /// the block.
+    Failed {
+        /// The error code returned by the application
+        code: u32,
+        /// The namespace of the error code
+        codespace: String,
+        /// The log of the execution of the transaction
+        log: String,
+    },
+}
+
+impl Verdict {
+    fn of(tx_result: &ExecTxResult) -> Self {
+        if tx_result.code.is_ok() {
+            Self::Succeeded
+        } else {
+            Self::Failed {
+                code: tx_result.code.value(),
+                codespace: tx_result.codespace.clone(),
+                log: tx_result.log.clone(),
+            }
+        }
+    }
+}
+
+/// A transaction proven to be included in a block whose header was verified
+/// by a light client.
+#[derive(Clone, Debug)]
+pub struct TxInclusion {
+    /// The hash of the transaction
+    pub hash: Hash,
+    /// The transaction
+    pub tx: Vec<u8>,
+    /// The height of the block including the transaction
+    pub height: Height,
+    /// The index of the transaction in the block
+    pub index: u32,
+    /// The verified header of the block including the transaction
+    pub header: Header,
+    /// The result of the execution of the transaction, as reported by the
+    /// node. Unlike the inclusion of the transaction, it is not proven.
+    pub tx_result: ExecTxResult,
+    /// Whether the application accepted the transaction
+    pub verdict: Verdict,
+}
+
+/// Check that the transaction with the given hash was committed to the chain
+/// with the given ID, as served by the node at the given RPC URL.
+///
+/// The header of the block of the transaction is verified by a light client
+/// initialized from the given trust options, with a trust threshold of 1/3
+/// and a trusting period of [`DEFAULT_TRUSTING_PERIOD`], and the inclusion
+/// proof of the transaction fetched from the node is checked against it.
+/// The transaction returned by the node must have the given hash.
+///
+/// This blocks the calling thread until the whole pipeline completed.
+pub fn verify_tx_inclusion<U>(
+    url: U,
+    trust_options: TrustOptions,
+    chain_id: &chain::Id,
+    tx_hash: Hash,
+) -> Result<TxInclusion, Error>
+where
+    U: TryInto<rpc::HttpClientUrl, Error = rpc::Error>,
+{
+    let client = rpc::HttpClient::builder(url.try_into().map_err(into_io)?)
+        .timeout(DEFAULT_TIMEOUT)
+        .build()
+        .map_err(into_io)?;
+
+    let status_client = client.clone();
+    let status = block_on(Some(DEFAULT_TIMEOUT), async move {
+        status_client.status().await
+    })
+    .map_err(Error::io)?
+    .map_err(into_io)?;
+    check_chain_id(chain_id, &status.node_info.network)?;
+
+    let tx_client = client.clone();
+    let response = block_on(Some(DEFAULT_TIMEOUT), async move {
+        tx_client.tx(tx_hash, true).await
+    })
+    .map_err(Error::io)?
+    .map_err(into_io)?;
+    let found = Hash::Sha256(Sha256::digest(&response.tx));
+    if found != tx_hash {
+        return Err(Error::tx_hash_mismatch(tx_hash, found));
+    }
+
+    let options = Options {
+        trust_threshold: TrustThreshold::ONE_THIRD,
+        trusting_period: DEFAULT_TRUSTING_PERIOD,
+        clock_drift: Duration::from_secs(5),
+    };
+    let mut light_client = LightClientBuilder::prod(
+        status.node_info.id,
+        client,
+        Box::new(MemoryStore::new()),
+        options,
+        Some(DEFAULT_TIMEOUT),
+    )
+    .trust_options(trust_options)
+    .map_err(Error::light_client)?
+    .build();
+    let light_block = light_client
+        .light_client
+        .verify_to_target(response.height, &mut light_client.state)
+        .map_err(Error::verification)?;
+    let header = light_block.signed_header.header;
+    check_chain_id(chain_id, &header.chain_id)?;
+
+    response
+        .verify::<Sha256>(&header)
+        .map_err(Error::not_included)?;
+
+    Ok(TxInclusion {
+        verdict: Verdict::of(&response.tx_result),
+        hash: tx_hash,
+        tx: response.tx,
+        height: response.height,
+        index: response.index,
+        header,
+        tx_result: response.tx_result,
+    })
+}
+
+fn into_io(e: rpc::Error) -> Error {
+    Error::io(IoError::from_rpc(e))
+}
+
+fn check_chain_id(expected: &chain::Id, found: &chain::Id) -> Result<(), Error> {
+    if expected != found {
+        return Err(Error::chain_id_mismatch(expected.clone(), found.clone()));
+    }
+    Ok(())
+}
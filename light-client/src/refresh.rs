@@ -0,0 +1,304 @@
+//! Refreshing of the trusted state of a light client before it expires.
+//!
+//! A light client can only verify new blocks from a trusted state within the
+//! trusting period, so one which is left idle for longer loses its trust
+//! anchor, and needs to be given a new one out of band. The [`Refresher`]
+//! verifies to the highest block of the primary once the trusted state has
+//! grown old enough, either when called (see [`Refresher::refresh`]) or
+//! periodically in the background (see [`Refresher::spawn`]), and warns as
+//! the expiry of the trusted state approaches if it cannot.
+
+use std::time::Duration;
+
+use crate::{
+    errors::Error,
+    instance::Instance,
+    verifier::types::{LightBlock, Time},
+};
+
+/// Keeps the trusted state of a light client within the trusting period by
+/// verifying to the highest block of the primary once the highest trusted or
+/// verified block is older than a fraction of the trusting period, two thirds
+/// by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Refresher {
+    trusting_period: Duration,
+    refresh_after: Duration,
+}
+
+impl Refresher {
+    /// Refresh the trusted state once it is older than two thirds of the
+    /// given trusting period, which is typically the one of the light client
+    /// being refreshed.
+    pub fn new(trusting_period: Duration) -> Self {
+        Self {
+            trusting_period,
+            refresh_after: trusting_period * 2 / 3,
+        }
+    }
+
+    /// Refresh the trusted state once it is older than the given age, which
+    /// should leave enough of the trusting period to retry a failed refresh.
+    pub fn refresh_after(mut self, refresh_after: Duration) -> Self {
+        self.refresh_after = refresh_after;
+        self
+    }
+
+    /// How long the highest trusted or verified block of the given instance
+    /// remains within the trusting period, as of the given time, or `None` if
+    /// there is no such block.
+    pub fn expires_in(&self, instance: &Instance, now: Time) -> Option<Duration> {
+        let trusted_block = instance.state.light_store.highest_trusted_or_verified()?;
+        Some(
+            self.trusting_period
+                .saturating_sub(age(&trusted_block, now)),
+        )
+    }
+
+    /// Verify the given instance to the highest block of its primary if its
+    /// trusted state is due for a refresh as of the given time, and return
+    /// the newly verified block, or `None` if the trusted state is recent
+    /// enough.
+    ///
+    /// Fails if the trusted state has already expired, or if the refresh
+    /// fails, in which case a warning is also logged.
+    pub fn refresh(&self, instance: &mut Instance, now: Time) -> Result<Option<LightBlock>, Error> {
+        let trusted_block = instance
+            .state
+            .light_store
+            .highest_trusted_or_verified()
+            .ok_or_else(Error::no_initial_trusted_state)?;
+
+        let age = age(&trusted_block, now);
+        if age < self.refresh_after {
+            return Ok(None);
+        }
+
+        let height = trusted_block.height();
+        let expires_in = self.trusting_period.saturating_sub(age);
+        if expires_in.is_zero() {
+            tracing::error!(%height, "trusted state expired, it needs to be reset");
+            return Err(Error::trusted_state_outside_trusting_period(
+                Box::new(trusted_block),
+                instance.light_client.options,
+            ));
+        }
+
+        tracing::debug!(%height, ?expires_in, "refreshing trusted state");
+        match instance.light_client.verify_to_highest(&mut instance.state) {
+            Ok(light_block) => {
+                tracing::info!(height = %light_block.height(), "refreshed trusted state");
+                Ok(Some(light_block))
+            },
+            Err(e) => {
+                tracing::warn!(%height, ?expires_in, "failed to refresh trusted state: {e}");
+                Err(e)
+            },
+        }
+    }
+}
+
+/// The age of the given block as of the given time, which is zero if it is
+/// from the future.
+fn age(light_block: &LightBlock, now: Time) -> Duration {
+    now.duration_since(light_block.time()).unwrap_or_default()
+}
+
+#[cfg(feature = "tokio")]
+mod background {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::{
+        task::{spawn_blocking, JoinHandle},
+        time::{interval, MissedTickBehavior},
+    };
+
+    use super::*;
+    use crate::components::clock::Clock;
+
+    impl Refresher {
+        /// Check whether the trusted state of the given instance is due for
+        /// a refresh at the given interval, as of the time given by the
+        /// clock, and refresh it if so, in a task of the current Tokio
+        /// runtime.
+        ///
+        /// The interval should be a small fraction of what remains of the
+        /// trusting period after [`Refresher::refresh_after`], so that a
+        /// failed refresh is retried several times before the trusted state
+        /// expires.
+        ///
+        /// The task stops if the trusted state expires or the lock of the
+        /// instance gets poisoned, and can be stopped with
+        /// [`JoinHandle::abort`] otherwise.
+        ///
+        /// ## Panics
+        /// If called outside of a Tokio runtime.
+        pub fn spawn(
+            self,
+            instance: Arc<Mutex<Instance>>,
+            clock: impl Clock + 'static,
+            every: Duration,
+        ) -> JoinHandle<()> {
+            let clock = Arc::new(clock);
+            tokio::spawn(async move {
+                let mut ticks = interval(every);
+                ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                loop {
+                    ticks.tick().await;
+
+                    // Verification blocks on the I/O of the light client.
+                    let (instance, clock) = (instance.clone(), clock.clone());
+                    let expired = spawn_blocking(move || match instance.lock() {
+                        Ok(mut instance) => {
+                            let expired =
+                                self.expires_in(&instance, clock.now()) == Some(Duration::ZERO);
+                            // Failures are logged by `refresh`.
+                            self.refresh(&mut instance, clock.now()).ok();
+                            expired
+                        },
+                        Err(_) => {
+                            tracing::warn!("instance lock poisoned, no longer refreshing it");
+                            true
+                        },
+                    })
+                    .await
+                    .unwrap_or(true);
+
+                    if expired {
+                        return;
+                    }
+                }
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use tendermint_testgen::{light_block::default_peer_id, Generator, LightChain};
+
+    use super::*;
+    use crate::{
+        components::{
+            io::{AtHeight, Io},
+            scheduler,
+        },
+        light_client::LightClient,
+        state::State,
+        store::{memory::MemoryStore, LightStore},
+        tests::{MockClock, MockIo},
+        verifier::{options::Options, types::Status, ProdVerifier},
+    };
+
+    const TRUSTING_PERIOD: Duration = Duration::from_secs(30);
+
+    fn at(secs: u64) -> Time {
+        tendermint_testgen::helpers::get_time(secs).unwrap()
+    }
+
+    // An instance trusting the first of 10 blocks produced a second apart.
+    fn instance() -> Instance {
+        let light_blocks = LightChain::default_with_length(10)
+            .light_blocks
+            .into_iter()
+            .map(|lb| LightBlock::from(lb.generate().unwrap()))
+            .collect();
+        let io = MockIo::new(light_blocks);
+
+        let mut light_store = MemoryStore::new();
+        light_store.insert(
+            io.fetch_light_block(AtHeight::At(1_u32.into())).unwrap(),
+            Status::Trusted,
+        );
+        let state = State {
+            light_store: Box::new(light_store),
+            verification_trace: HashMap::new(),
+        };
+
+        let light_client = LightClient::new(
+            default_peer_id(),
+            Options {
+                trust_threshold: Default::default(),
+                trusting_period: TRUSTING_PERIOD,
+                clock_drift: Duration::from_secs(10),
+            },
+            MockClock { now: at(25) },
+            scheduler::basic_bisecting_schedule,
+            ProdVerifier::default(),
+            io,
+        );
+
+        Instance::new(light_client, state)
+    }
+
+    #[test]
+    fn leaves_recent_trusted_state_alone() {
+        let mut instance = instance();
+
+        let refresher = Refresher::new(TRUSTING_PERIOD);
+        assert_eq!(refresher.refresh(&mut instance, at(11)).unwrap(), None);
+        assert_eq!(
+            refresher.expires_in(&instance, at(11)),
+            Some(Duration::from_secs(20))
+        );
+    }
+
+    #[test]
+    fn refreshes_old_trusted_state() {
+        let mut instance = instance();
+
+        let refresher = Refresher::new(TRUSTING_PERIOD);
+        let light_block = refresher.refresh(&mut instance, at(25)).unwrap().unwrap();
+        assert_eq!(light_block.height().value(), 10);
+        assert_eq!(
+            refresher.expires_in(&instance, at(25)),
+            Some(Duration::from_secs(15))
+        );
+    }
+
+    #[test]
+    fn fails_on_expired_trusted_state() {
+        let mut instance = instance();
+
+        Refresher::new(TRUSTING_PERIOD)
+            .refresh(&mut instance, at(40))
+            .unwrap_err();
+        assert_eq!(
+            instance
+                .state
+                .light_store
+                .highest_trusted_or_verified()
+                .map(|lb| lb.height().value()),
+            Some(1)
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn refreshes_in_background() {
+        use std::sync::{Arc, Mutex};
+
+        let instance = Arc::new(Mutex::new(instance()));
+
+        let handle = Refresher::new(TRUSTING_PERIOD).spawn(
+            instance.clone(),
+            MockClock { now: at(25) },
+            Duration::from_millis(10),
+        );
+        let verified_height = || {
+            instance
+                .lock()
+                .unwrap()
+                .state
+                .light_store
+                .highest_trusted_or_verified()
+                .map(|lb| lb.height().value())
+        };
+        while verified_height() != Some(10) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        handle.abort();
+    }
+}
@@ -0,0 +1,111 @@
+//! Samples of blocks verified by a light client, for analytics.
+//!
+//! [`verified_block_samples`] fetches the blocks of a range of heights,
+//! verifies their headers with a light client, and checks the transactions of
+//! each block against its verified header before sampling it. The node serving
+//! the blocks thus needs not be trusted.
+
+use flex_error::define_error;
+use tendermint::{
+    crypto::{default::Sha256, Sha256 as _},
+    merkle, Hash,
+};
+use tendermint_rpc::{
+    self as rpc,
+    sample::{BlockSample, Sampler},
+    Client,
+};
+
+use crate::{
+    components::io::IoError, errors::Error as LightClientError, instance::Instance,
+    utils::block_on, verifier::types::Height,
+};
+
+define_error! {
+    #[derive(Debug)]
+    Error {
+        Io
+            [ IoError ]
+            | _ | { "failed to fetch the block" },
+
+        Verification
+            [ LightClientError ]
+            | _ | { "failed to verify the header of the block" },
+
+        HeaderMismatch
+            {
+                height: Height,
+            }
+            | e | {
+                format_args!("the header of the block at height {} is not the verified one",
+                    e.height)
+            },
+
+        DataHashMismatch
+            {
+                height: Height,
+            }
+            | e | {
+                format_args!("the transactions of the block at height {} do not match its data hash",
+                    e.height)
+            },
+    }
+}
+
+/// Sample the blocks of the node behind the given client from height `min`
+/// to height `max` inclusive, fetching and verifying them one at a time as the
+/// returned iterator is advanced.
+///
+/// The header of each block is verified by the light client of the given
+/// instance, and its transactions are checked against the data hash of that
+/// header, so that the time, proposer and transaction count of the samples
+/// are those committed to by the chain. The size of a sample also counts the
+/// evidence and last commit of the block, which are not checked.
+///
+/// The iterator stops after the first error, and blocks the calling thread
+/// until each block is fetched and verified.
+pub fn verified_block_samples<'a>(
+    instance: &'a mut Instance,
+    client: &'a rpc::HttpClient,
+    min: Height,
+    max: Height,
+) -> impl Iterator<Item = Result<BlockSample, Error>> + 'a {
+    let mut sampler = Sampler::new();
+    let mut next = Some(min);
+    core::iter::from_fn(move || {
+        let height = next.filter(|height| *height <= max)?;
+        let sample =
+            verified_block(instance, client, height).map(|block| sampler.sample_block(&block));
+        next = sample.as_ref().ok().map(|_| height.increment());
+        Some(sample)
+    })
+}
+
+fn verified_block(
+    instance: &mut Instance,
+    client: &rpc::HttpClient,
+    height: Height,
+) -> Result<tendermint::Block, Error> {
+    let client = client.clone();
+    let block = block_on(None, async move { client.block(height).await })
+        .map_err(Error::io)?
+        .map_err(|e| Error::io(IoError::from_rpc(e)))?
+        .block;
+
+    let light_block = instance
+        .light_client
+        .verify_to_target(height, &mut instance.state)
+        .map_err(Error::verification)?;
+    if block.header != light_block.signed_header.header {
+        return Err(Error::header_mismatch(height));
+    }
+
+    // The leaves of the block data Merkle tree are transaction hashes.
+    let leaves: Vec<_> = block.data.iter().map(Sha256::digest).collect();
+    let data_hash = Hash::Sha256(merkle::simple_hash_from_byte_vectors::<Sha256>(&leaves));
+    if block.header.data_hash != Some(data_hash) {
+        return Err(Error::data_hash_mismatch(height));
+    }
+
+    Ok(block)
+}
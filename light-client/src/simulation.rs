@@ -0,0 +1,183 @@
+//! Deterministic building blocks for reproducing attack scenarios on a light
+//! client in unit tests.
+//!
+//! A [`VirtualClock`] only changes time when told to, and the chains of
+//! [`ScriptedProvider`]s can be extended, and made to withhold blocks or to
+//! equivocate, between the steps of a test. The `simulation` module of the
+//! `tendermint-light-client-detector` crate drives a light client and its
+//! witnesses with them, detecting the attacks as a real light client would.
+//!
+//! Nothing runs in the background and no real time passes, so a scenario
+//! always unfolds the same way.
+//!
+//! This module is meant for tests only, and is not part of the stable API.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+
+use crate::{
+    components::{
+        clock::Clock,
+        io::{AsyncIo, AtHeight, Io, IoError},
+    },
+    verifier::types::{Height, LightBlock, PeerId, Time},
+};
+
+/// A clock whose time only changes when told to.
+///
+/// Clones of a clock share its time, so that advancing the clock kept by a
+/// test also advances the one given to the light client.
+#[derive(Clone, Debug)]
+pub struct VirtualClock {
+    now: Arc<Mutex<Time>>,
+}
+
+impl VirtualClock {
+    /// A clock starting at the given time.
+    pub fn new(now: Time) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    /// Advance the clock by the given duration, and return the new time.
+    pub fn advance(&self, duration: Duration) -> Time {
+        let mut now = self.now.lock().unwrap();
+        *now = (*now + duration).expect("time overflow");
+        *now
+    }
+
+    /// Set the clock to the given time, which may be in its past.
+    pub fn set(&self, now: Time) {
+        *self.now.lock().unwrap() = now;
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Time {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Script {
+    light_blocks: BTreeMap<Height, LightBlock>,
+    latest_height: Option<Height>,
+    withheld: BTreeSet<Height>,
+    fetches: Vec<AtHeight>,
+}
+
+/// A provider of light blocks following a script.
+///
+/// The provider serves the light blocks it was given, up to its latest
+/// height: the blocks above it are not produced yet. Its chain can be
+/// extended with [`ScriptedProvider::extend`], and it can be made to
+/// withhold blocks with [`ScriptedProvider::withhold`], or to serve
+/// conflicting blocks with [`ScriptedProvider::equivocate`].
+///
+/// Clones of a provider share its script, so that a test can change the
+/// script of the provider given to a light client.
+#[derive(Clone, Debug)]
+pub struct ScriptedProvider {
+    peer_id: PeerId,
+    script: Arc<Mutex<Script>>,
+}
+
+impl ScriptedProvider {
+    /// A provider with the given peer ID, serving the given light blocks.
+    pub fn new(peer_id: PeerId, light_blocks: Vec<LightBlock>) -> Self {
+        let provider = Self {
+            peer_id,
+            script: Default::default(),
+        };
+        provider.extend(light_blocks);
+        provider
+    }
+
+    pub fn peer_id(&self) -> PeerId {
+        self.peer_id
+    }
+
+    /// Serve the given light blocks too, raising the latest height of the
+    /// provider to the highest of them.
+    pub fn extend(&self, light_blocks: impl IntoIterator<Item = LightBlock>) {
+        let mut script = self.script.lock().unwrap();
+        for light_block in light_blocks {
+            let height = light_block.height();
+            script.latest_height = script.latest_height.max(Some(height));
+            script.light_blocks.insert(height, light_block);
+        }
+    }
+
+    /// Set the latest height of the provider, above which it fails to serve
+    /// blocks as if they were not produced yet.
+    pub fn set_latest_height(&self, height: Height) {
+        self.script.lock().unwrap().latest_height = Some(height);
+    }
+
+    /// Fail to serve the block at the given height, as if the requests for
+    /// it timed out.
+    pub fn withhold(&self, height: Height) {
+        self.script.lock().unwrap().withheld.insert(height);
+    }
+
+    /// Serve the block at the given height again.
+    pub fn release(&self, height: Height) {
+        self.script.lock().unwrap().withheld.remove(&height);
+    }
+
+    /// Serve the given block instead of the one at its height, e.g. a block
+    /// conflicting with the one served by the other providers.
+    pub fn equivocate(&self, light_block: LightBlock) {
+        self.script
+            .lock()
+            .unwrap()
+            .light_blocks
+            .insert(light_block.height(), light_block);
+    }
+
+    /// The heights of the blocks requested from the provider so far, in the
+    /// order they were requested.
+    pub fn fetches(&self) -> Vec<AtHeight> {
+        self.script.lock().unwrap().fetches.clone()
+    }
+}
+
+impl Io for ScriptedProvider {
+    fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+        let mut script = self.script.lock().unwrap();
+        script.fetches.push(height);
+
+        let latest_height = script.latest_height.ok_or_else(IoError::invalid_height)?;
+        let height = match height {
+            AtHeight::Highest => latest_height,
+            AtHeight::At(height) if height > latest_height => {
+                return Err(IoError::height_too_high(height, latest_height))
+            },
+            AtHeight::At(height) => height,
+        };
+        if script.withheld.contains(&height) {
+            return Err(IoError::request_timeout(Duration::ZERO));
+        }
+
+        let mut light_block = script
+            .light_blocks
+            .get(&height)
+            .cloned()
+            .ok_or_else(IoError::invalid_height)?;
+        light_block.provider = self.peer_id;
+        Ok(light_block)
+    }
+}
+
+#[async_trait]
+impl AsyncIo for ScriptedProvider {
+    async fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+        Io::fetch_light_block(self, height)
+    }
+}
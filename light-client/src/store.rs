@@ -1,23 +1,42 @@
 //! Interface and implementations of the light block store.
 //!
-//! See the `memory` and `sled` modules for:
+//! See the `memory`, `sled`, `sqlite` and `rocksdb` modules for:
 //!
 //! - a transient, in-memory implementation for testing purposes
-//! - a persistent, on-disk, sled-backed implementation for production
+//! - persistent, on-disk implementations for production, backed by sled,
+//!   SQLite or RocksDB
+//!
+//! Light blocks can be moved from one store to another, e.g. from a sled
+//! database to a SQLite one, with [`copy`], or through a portable
+//! [`snapshot`] file with [`LightStore::export`] and [`LightStore::import`].
+//!
+//! Blocks which fell out of the trusting period can be removed from a store
+//! with a [`pruning::Pruner`].
 
-use std::fmt::Debug;
+use std::{fmt::Debug, path::Path};
 
 use crate::{
+    errors::Error,
     utils::std_ext,
     verifier::types::{Height, LightBlock, Status},
 };
 
 pub mod memory;
+pub mod pruning;
+pub mod snapshot;
 
 #[cfg(feature = "lightstore-sled")]
 #[cfg_attr(docsrs, doc(cfg(feature = "lightstore-sled")))]
 pub mod sled;
 
+#[cfg(feature = "lightstore-sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "lightstore-sqlite")))]
+pub mod sqlite;
+
+#[cfg(feature = "lightstore-rocksdb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "lightstore-rocksdb")))]
+pub mod rocksdb;
+
 /// Store for light blocks.
 ///
 /// The light store records light blocks received from peers, and their verification status.
@@ -104,4 +123,30 @@ pub trait LightStore: Debug + Send + Sync {
         self.get(height, Status::Trusted)
             .or_else(|| self.get(height, Status::Verified))
     }
+
+    /// Export the trusted and verified light blocks of this store to a snapshot
+    /// at the given path (see [`snapshot::export`]).
+    fn export(&self, path: &Path) -> Result<(), Error> {
+        snapshot::export(self, path)
+    }
+
+    /// Import the light blocks of the snapshot at the given path into this
+    /// store (see [`snapshot::import`]).
+    fn import(&mut self, path: &Path) -> Result<(), Error> {
+        snapshot::import(self, path)
+    }
+}
+
+/// Copy all the light blocks of the given store, with their status, into
+/// another store, e.g. to migrate a light client from one database to
+/// another.
+///
+/// Light blocks already in the destination store are kept, unless the source
+/// store has a light block at the same height with the same status.
+pub fn copy(from: &dyn LightStore, to: &mut dyn LightStore) {
+    for status in Status::iter() {
+        for light_block in from.all(*status) {
+            to.insert(light_block, *status);
+        }
+    }
 }
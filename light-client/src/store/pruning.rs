@@ -0,0 +1,280 @@
+//! Pruning of the light blocks which the light client no longer needs.
+//!
+//! A light block can only serve as a trusted state for verification while it
+//! is within the trusting period, so the verified and trusted blocks which
+//! fell out of it only take up room in the light store. The [`Pruner`] removes
+//! them, either when called (see [`Pruner::prune`]) or periodically from the
+//! light store of an [`Instance`](crate::instance::Instance) in the background
+//! (see [`Pruner::spawn`]).
+
+use std::time::Duration;
+
+use crate::{
+    contracts::is_within_trust_period,
+    store::LightStore,
+    verifier::types::{Height, Status, Time},
+};
+
+/// The statuses of the light blocks which are pruned.
+const PRUNED_STATUSES: [Status; 2] = [Status::Verified, Status::Trusted];
+
+/// Removes the verified and trusted light blocks older than the trusting
+/// period from a light store.
+///
+/// The highest trusted block, and the highest trusted or verified block, are
+/// always kept as anchors for verification, however old. Optionally, the
+/// number of verified and trusted blocks kept can be capped with
+/// [`Pruner::max_retained`], in which case the lowest blocks beyond the cap
+/// are removed even if they are within the trusting period.
+///
+/// Unverified and failed blocks are left untouched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pruner {
+    trusting_period: Duration,
+    max_retained: Option<usize>,
+}
+
+impl Pruner {
+    /// Remove the blocks older than the given trusting period, which is
+    /// typically the one of the light client using the store.
+    pub fn new(trusting_period: Duration) -> Self {
+        Self {
+            trusting_period,
+            max_retained: None,
+        }
+    }
+
+    /// Keep at most the given number of the highest blocks of each of the
+    /// verified and trusted statuses.
+    pub fn max_retained(mut self, max_retained: usize) -> Self {
+        self.max_retained = Some(max_retained);
+        self
+    }
+
+    /// Prune the given light store, as of the given time, and return the
+    /// number of blocks removed.
+    pub fn prune<S>(&self, light_store: &mut S, now: Time) -> usize
+    where
+        S: LightStore + ?Sized,
+    {
+        let anchors: Vec<Height> = [
+            light_store.highest(Status::Trusted),
+            light_store.highest_trusted_or_verified(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|lb| lb.height())
+        .collect();
+
+        let mut removed = 0;
+        for status in PRUNED_STATUSES {
+            let mut light_blocks: Vec<_> = light_store.all(status).collect();
+            light_blocks.sort_by_key(|lb| lb.height());
+
+            // The blocks below this index are beyond the retention count.
+            let retained_from = match self.max_retained {
+                Some(max_retained) => light_blocks.len().saturating_sub(max_retained),
+                None => 0,
+            };
+
+            for (i, light_block) in light_blocks.iter().enumerate() {
+                let height = light_block.height();
+                if anchors.contains(&height) {
+                    continue;
+                }
+                if i < retained_from
+                    || !is_within_trust_period(light_block, self.trusting_period, now)
+                {
+                    light_store.remove(height, status);
+                    removed += 1;
+                }
+            }
+        }
+
+        tracing::debug!(removed, "pruned light store");
+        removed
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod background {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::{
+        task::JoinHandle,
+        time::{interval, MissedTickBehavior},
+    };
+
+    use super::*;
+    use crate::{components::clock::Clock, instance::Instance};
+
+    impl Pruner {
+        /// Prune the light store of the given instance at the given interval,
+        /// as of the time given by the clock, in a task of the current Tokio
+        /// runtime.
+        ///
+        /// The instance can be shared with the application using it, e.g.
+        /// along with a [`Refresher`](crate::refresh::Refresher), as the
+        /// store is only pruned while holding its lock.
+        ///
+        /// The task stops if the lock of the instance gets poisoned, and can
+        /// be stopped with [`JoinHandle::abort`] otherwise.
+        ///
+        /// ## Panics
+        /// If called outside of a Tokio runtime.
+        pub fn spawn(
+            self,
+            instance: Arc<Mutex<Instance>>,
+            clock: impl Clock + 'static,
+            every: Duration,
+        ) -> JoinHandle<()> {
+            tokio::spawn(async move {
+                let mut ticks = interval(every);
+                ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                loop {
+                    ticks.tick().await;
+                    match instance.lock() {
+                        Ok(mut instance) => {
+                            self.prune(instance.state.light_store.as_mut(), clock.now());
+                        },
+                        Err(_) => {
+                            tracing::warn!("instance lock poisoned, no longer pruning it");
+                            return;
+                        },
+                    }
+                }
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint_testgen::{Generator, LightChain};
+
+    use super::*;
+    use crate::{store::memory::MemoryStore, verifier::types::LightBlock};
+
+    fn light_blocks() -> Vec<LightBlock> {
+        LightChain::default_with_length(10)
+            .light_blocks
+            .into_iter()
+            .map(|lb| LightBlock::from(lb.generate().unwrap()))
+            .collect()
+    }
+
+    // A store of 10 blocks produced a second apart, of which the first 5 are
+    // trusted and the others verified, along with the time a second after the
+    // last block.
+    fn store() -> (MemoryStore, Time) {
+        let blocks = light_blocks();
+        let now = (blocks[9].time() + Duration::from_secs(1)).unwrap();
+
+        let mut light_store = MemoryStore::new();
+        for (i, light_block) in blocks.into_iter().enumerate() {
+            let status = if i < 5 {
+                Status::Trusted
+            } else {
+                Status::Verified
+            };
+            light_store.insert(light_block, status);
+        }
+        (light_store, now)
+    }
+
+    fn heights(light_store: &MemoryStore, status: Status) -> Vec<u64> {
+        light_store
+            .all(status)
+            .map(|lb| lb.height().value())
+            .collect()
+    }
+
+    #[test]
+    fn removes_blocks_outside_of_trusting_period() {
+        let (mut light_store, now) = store();
+
+        let removed = Pruner::new(Duration::from_secs(5)).prune(&mut light_store, now);
+        assert_eq!(removed, 5);
+        // The highest trusted block is kept as an anchor.
+        assert_eq!(heights(&light_store, Status::Trusted), [5]);
+        assert_eq!(heights(&light_store, Status::Verified), [7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn keeps_anchors_however_old() {
+        let (mut light_store, now) = store();
+        let later = (now + Duration::from_secs(3600)).unwrap();
+
+        Pruner::new(Duration::from_secs(5)).prune(&mut light_store, later);
+        assert_eq!(heights(&light_store, Status::Trusted), [5]);
+        assert_eq!(heights(&light_store, Status::Verified), [10]);
+    }
+
+    #[test]
+    fn caps_number_of_retained_blocks() {
+        let (mut light_store, now) = store();
+
+        Pruner::new(Duration::from_secs(3600))
+            .max_retained(2)
+            .prune(&mut light_store, now);
+        assert_eq!(heights(&light_store, Status::Trusted), [4, 5]);
+        assert_eq!(heights(&light_store, Status::Verified), [9, 10]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn prunes_instance_in_background() {
+        use std::sync::{Arc, Mutex};
+
+        use tendermint_testgen::light_block::default_peer_id;
+
+        use crate::{
+            components::scheduler,
+            instance::Instance,
+            light_client::LightClient,
+            state::State,
+            tests::{MockClock, MockIo},
+            verifier::{options::Options, ProdVerifier},
+        };
+
+        let (light_store, now) = store();
+        let light_client = LightClient::new(
+            default_peer_id(),
+            Options {
+                trust_threshold: Default::default(),
+                trusting_period: Duration::from_secs(5),
+                clock_drift: Duration::from_secs(10),
+            },
+            MockClock { now },
+            scheduler::basic_bisecting_schedule,
+            ProdVerifier::default(),
+            MockIo::new(light_blocks()),
+        );
+        let instance = Arc::new(Mutex::new(Instance::new(
+            light_client,
+            State::new(light_store),
+        )));
+
+        let handle = Pruner::new(Duration::from_secs(5)).spawn(
+            instance.clone(),
+            MockClock { now },
+            Duration::from_millis(10),
+        );
+        let heights = |status| {
+            instance
+                .lock()
+                .unwrap()
+                .state
+                .light_store
+                .all(status)
+                .map(|lb| lb.height().value())
+                .collect::<Vec<_>>()
+        };
+        while heights(Status::Trusted) != [5] {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        handle.abort();
+
+        assert_eq!(heights(Status::Verified), [7, 8, 9, 10]);
+    }
+}
@@ -0,0 +1,243 @@
+//! Persistent store backed by an on-disk RocksDB database.
+
+pub mod schema;
+
+use std::{fmt, path::Path};
+
+use rocksdb::{ColumnFamily, Direction, IteratorMode, Options, WriteBatch, DB};
+
+use super::{LightStore, Status};
+use crate::{
+    errors::Error,
+    verifier::types::{Height, LightBlock},
+};
+
+const UNVERIFIED: &str = "unverified";
+const VERIFIED: &str = "verified";
+const TRUSTED: &str = "trusted";
+const FAILED: &str = "failed";
+
+const COLUMN_FAMILIES: [&str; 5] = [schema::META, UNVERIFIED, VERIFIED, TRUSTED, FAILED];
+
+/// Persistent store backed by an on-disk RocksDB database.
+///
+/// The light blocks of each status are kept in a column family of their own,
+/// keyed by height and encoded with CBOR like in the [`SledStore`].
+/// Databases created by older versions of this crate are migrated to the
+/// current [`schema::SCHEMA_VERSION`] when opened, while those created by
+/// newer versions are refused.
+///
+/// [`SledStore`]: crate::store::sled::SledStore
+pub struct RocksDbStore {
+    db: DB,
+}
+
+impl fmt::Debug for RocksDbStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RocksDbStore")
+            .field("path", &self.db.path())
+            .finish()
+    }
+}
+
+fn key_bytes(height: Height) -> [u8; 8] {
+    // Heights are stored in big-endian form for RocksDB's iterators, which
+    // follow the lexicographic order of the keys, to work properly.
+    height.value().to_be_bytes()
+}
+
+impl RocksDbStore {
+    /// Open a RocksDB database, creating it if needed, and create a new
+    /// persistent store from it.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+
+        let db = DB::open_cf(&opts, path, COLUMN_FAMILIES).map_err(Error::rocks_db)?;
+
+        Self::new(db)
+    }
+
+    /// Create a new persistent store from a RocksDB database that is already
+    /// open, creating the column families of the store it lacks.
+    pub fn new(mut db: DB) -> Result<Self, Error> {
+        for name in COLUMN_FAMILIES {
+            if db.cf_handle(name).is_none() {
+                db.create_cf(name, &Options::default())
+                    .map_err(Error::rocks_db)?;
+            }
+        }
+
+        schema::migrate(&db)?;
+
+        Ok(Self { db })
+    }
+
+    fn cf(&self, status: Status) -> &ColumnFamily {
+        let name = match status {
+            Status::Unverified => UNVERIFIED,
+            Status::Verified => VERIFIED,
+            Status::Trusted => TRUSTED,
+            Status::Failed => FAILED,
+        };
+        self.db
+            .cf_handle(name)
+            .expect("the column families of the store are open")
+    }
+
+    fn first(&self, status: Status, mode: IteratorMode<'_>) -> Option<LightBlock> {
+        let (_, bytes) = self.db.iterator_cf(self.cf(status), mode).next()?.ok()?;
+        serde_cbor::from_slice(&bytes).ok()
+    }
+
+    fn try_update(&self, light_block: &LightBlock, status: Status) -> Result<(), Error> {
+        let key = key_bytes(light_block.height());
+        let bytes = serde_cbor::to_vec(light_block).map_err(Error::serde_cbor)?;
+
+        let mut batch = WriteBatch::default();
+        for other in Status::iter() {
+            if status != *other {
+                batch.delete_cf(self.cf(*other), key);
+            }
+        }
+        batch.put_cf(self.cf(status), key, bytes);
+
+        self.db.write(batch).map_err(Error::rocks_db)
+    }
+
+    fn try_insert(&self, light_block: &LightBlock, status: Status) -> Result<(), Error> {
+        let bytes = serde_cbor::to_vec(light_block).map_err(Error::serde_cbor)?;
+
+        self.db
+            .put_cf(self.cf(status), key_bytes(light_block.height()), bytes)
+            .map_err(Error::rocks_db)
+    }
+}
+
+impl LightStore for RocksDbStore {
+    fn get(&self, height: Height, status: Status) -> Option<LightBlock> {
+        let bytes = self.db.get_cf(self.cf(status), key_bytes(height)).ok()??;
+        serde_cbor::from_slice(&bytes).ok()
+    }
+
+    fn update(&mut self, light_block: &LightBlock, status: Status) {
+        self.try_update(light_block, status).ok();
+    }
+
+    fn insert(&mut self, light_block: LightBlock, status: Status) {
+        self.try_insert(&light_block, status).ok();
+    }
+
+    fn remove(&mut self, height: Height, status: Status) {
+        self.db.delete_cf(self.cf(status), key_bytes(height)).ok();
+    }
+
+    fn highest(&self, status: Status) -> Option<LightBlock> {
+        self.first(status, IteratorMode::End)
+    }
+
+    fn highest_before(&self, height: Height, status: Status) -> Option<LightBlock> {
+        let key = key_bytes(height);
+        self.first(status, IteratorMode::From(&key, Direction::Reverse))
+    }
+
+    fn lowest(&self, status: Status) -> Option<LightBlock> {
+        self.first(status, IteratorMode::Start)
+    }
+
+    fn all(&self, status: Status) -> Box<dyn Iterator<Item = LightBlock>> {
+        let light_blocks: Vec<LightBlock> = self
+            .db
+            .iterator_cf(self.cf(status), IteratorMode::Start)
+            .flatten()
+            .flat_map(|(_, bytes)| serde_cbor::from_slice(&bytes))
+            .collect();
+        Box::new(light_blocks.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+    use tendermint_testgen::{Generator, LightChain};
+
+    use super::*;
+
+    #[test]
+    fn highest_returns_latest_block() {
+        with_blocks(10, |mut db, blocks| {
+            for block in blocks {
+                db.insert(block.clone(), Status::Verified);
+                assert_eq!(db.highest(Status::Verified), Some(block));
+            }
+        })
+    }
+
+    #[test]
+    fn highest_before_returns_correct_block() {
+        with_blocks(10, |mut db, blocks| {
+            for block in blocks {
+                db.insert(block.clone(), Status::Verified);
+                assert_eq!(
+                    db.highest_before(block.height(), Status::Verified).as_ref(),
+                    Some(&block)
+                );
+                assert_eq!(
+                    db.highest_before(block.height().increment(), Status::Verified)
+                        .as_ref(),
+                    Some(&block)
+                );
+            }
+        })
+    }
+
+    #[test]
+    fn lowest_returns_earliest_block() {
+        with_blocks(10, |mut db, blocks| {
+            let initial_block = blocks[0].clone();
+            db.insert(initial_block.clone(), Status::Verified);
+            assert_eq!(db.lowest(Status::Verified).as_ref(), Some(&initial_block));
+
+            for block in blocks.into_iter().skip(1) {
+                db.insert(block, Status::Verified);
+                assert_eq!(db.lowest(Status::Verified).as_ref(), Some(&initial_block));
+            }
+        })
+    }
+
+    #[test]
+    fn update_moves_block_to_new_status() {
+        with_blocks(3, |mut db, blocks| {
+            for block in &blocks {
+                db.insert(block.clone(), Status::Unverified);
+            }
+            db.update(&blocks[1], Status::Verified);
+
+            assert_eq!(db.get(blocks[1].height(), Status::Unverified), None);
+            assert_eq!(
+                db.get(blocks[1].height(), Status::Verified).as_ref(),
+                Some(&blocks[1])
+            );
+            assert_eq!(
+                db.all(Status::Unverified).collect::<Vec<_>>(),
+                [blocks[0].clone(), blocks[2].clone()]
+            );
+        })
+    }
+
+    fn with_blocks(height: u64, f: impl FnOnce(RocksDbStore, Vec<LightBlock>)) {
+        let tmp_dir = tempdir().unwrap();
+        let db = RocksDbStore::open(tmp_dir.path()).unwrap();
+
+        let chain = LightChain::default_with_length(height);
+        let blocks = chain
+            .light_blocks
+            .into_iter()
+            .map(|lb| lb.generate().unwrap())
+            .map(LightBlock::from)
+            .collect::<Vec<_>>();
+
+        f(db, blocks)
+    }
+}
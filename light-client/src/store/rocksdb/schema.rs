@@ -0,0 +1,108 @@
+//! Versioning of the on-disk layout of the [`RocksDbStore`], and migrations
+//! of the databases created by older versions of this crate.
+//!
+//! [`RocksDbStore`]: super::RocksDbStore
+
+use rocksdb::DB;
+
+use crate::errors::Error;
+
+/// The version of the layout of the databases created by this version of the
+/// crate.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The column family holding the metadata of the store.
+pub(super) const META: &str = "meta";
+const VERSION_KEY: &[u8] = b"schema_version";
+
+type Migration = fn(&DB) -> Result<(), Error>;
+
+/// The migration at index `i` upgrades a database from version `i` to
+/// version `i + 1`.
+///
+/// Migrations must be idempotent: a migration interrupted before the new
+/// version is recorded runs again when the database is next opened.
+const MIGRATIONS: &[Migration] = &[
+    // Version 0 is a new database, whose column families are created when it
+    // is opened.
+    |_| Ok(()),
+];
+
+/// The version of the layout of the given database, or `None` if it has not
+/// been used yet.
+pub fn version(db: &DB) -> Result<Option<u32>, Error> {
+    let meta = match db.cf_handle(META) {
+        Some(meta) => meta,
+        None => return Ok(None),
+    };
+    let version = db.get_cf(meta, VERSION_KEY).map_err(Error::rocks_db)?;
+    // An unreadable version is taken to be from the future, so that the
+    // database is left untouched.
+    Ok(version.map(|bytes| match bytes.as_slice().try_into() {
+        Ok(be_bytes) => u32::from_be_bytes(be_bytes),
+        Err(_) => u32::MAX,
+    }))
+}
+
+/// Upgrade the given database, which must be open with the `meta` column
+/// family, to the current version.
+///
+/// Fails without modifying the database if it was created by a newer version
+/// of the crate.
+pub fn migrate(db: &DB) -> Result<(), Error> {
+    let version = version(db)?.unwrap_or(0);
+
+    if version > SCHEMA_VERSION {
+        return Err(Error::unsupported_store_version(version, SCHEMA_VERSION));
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(db)?;
+        set_version(db, from as u32 + 1)?;
+    }
+
+    Ok(())
+}
+
+fn set_version(db: &DB, version: u32) -> Result<(), Error> {
+    let meta = db.cf_handle(META).expect("the meta column family is open");
+    db.put_cf(meta, VERSION_KEY, version.to_be_bytes())
+        .map_err(Error::rocks_db)?;
+    db.flush_cf(meta).map_err(Error::rocks_db)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{errors::ErrorDetail, store::rocksdb::RocksDbStore};
+
+    #[test]
+    fn migrations_lead_to_current_version() {
+        assert_eq!(MIGRATIONS.len(), SCHEMA_VERSION as usize);
+    }
+
+    #[test]
+    fn new_database_gets_current_version() {
+        let tmp_dir = tempdir().unwrap();
+        let store = RocksDbStore::open(tmp_dir.path()).unwrap();
+        assert_eq!(version(&store.db).unwrap(), Some(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn newer_database_is_refused() {
+        let tmp_dir = tempdir().unwrap();
+        let store = RocksDbStore::open(tmp_dir.path()).unwrap();
+        set_version(&store.db, SCHEMA_VERSION + 1).unwrap();
+
+        match migrate(&store.db).unwrap_err().detail() {
+            ErrorDetail::UnsupportedStoreVersion(e) => {
+                assert_eq!(e.version, SCHEMA_VERSION + 1);
+                assert_eq!(e.supported, SCHEMA_VERSION);
+            },
+            e => panic!("unexpected error: {e:?}"),
+        }
+        assert_eq!(version(&store.db).unwrap(), Some(SCHEMA_VERSION + 1));
+    }
+}
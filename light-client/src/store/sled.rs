@@ -1,12 +1,16 @@
 //! Persistent store backed by an on-disk `sled` database.
 
+pub mod schema;
 pub mod utils;
 use std::path::Path;
 
 use utils::HeightIndexedDb;
 
 use super::{LightStore, Status};
-use crate::verifier::types::{Height, LightBlock};
+use crate::{
+    errors::Error,
+    verifier::types::{Height, LightBlock},
+};
 
 const UNVERIFIED: &str = "unverified";
 const VERIFIED: &str = "verified";
@@ -14,6 +18,10 @@ const TRUSTED: &str = "trusted";
 const FAILED: &str = "failed";
 
 /// Persistent store backed by an on-disk `sled` database.
+///
+/// Databases created by older versions of this crate are migrated to the
+/// current [`schema::SCHEMA_VERSION`] when opened, while those created by
+/// newer versions are refused.
 #[derive(Debug, Clone)]
 pub struct SledStore {
     unverified_db: HeightIndexedDb<LightBlock>,
@@ -24,18 +32,21 @@ pub struct SledStore {
 
 impl SledStore {
     /// Open a sled database and create a new persistent store from it.
-    pub fn open(db: impl AsRef<Path>) -> Result<Self, sled::Error> {
-        Ok(Self::new(sled::open(db)?))
+    pub fn open(db: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::new(sled::open(db).map_err(Error::sled)?)
     }
 
     /// Create a new persistent store from a sled database that is already open.
-    pub fn new(db: sled::Db) -> Self {
-        Self {
-            unverified_db: HeightIndexedDb::new(db.open_tree(UNVERIFIED).unwrap()),
-            verified_db: HeightIndexedDb::new(db.open_tree(VERIFIED).unwrap()),
-            trusted_db: HeightIndexedDb::new(db.open_tree(TRUSTED).unwrap()),
-            failed_db: HeightIndexedDb::new(db.open_tree(FAILED).unwrap()),
-        }
+    pub fn new(db: sled::Db) -> Result<Self, Error> {
+        schema::migrate(&db)?;
+
+        let open_tree = |name| db.open_tree(name).map_err(Error::sled);
+        Ok(Self {
+            unverified_db: HeightIndexedDb::new(open_tree(UNVERIFIED)?),
+            verified_db: HeightIndexedDb::new(open_tree(VERIFIED)?),
+            trusted_db: HeightIndexedDb::new(open_tree(TRUSTED)?),
+            failed_db: HeightIndexedDb::new(open_tree(FAILED)?),
+        })
     }
 
     fn db(&self, status: Status) -> &HeightIndexedDb<LightBlock> {
@@ -95,7 +106,7 @@ impl LightStore for SledStore {
 #[cfg(test)]
 mod tests {
     use tempfile::tempdir;
-    use tendermint_testgen::{light_block::TmLightBlock as TGLightBlock, Generator, LightChain};
+    use tendermint_testgen::{Generator, LightChain};
 
     use super::*;
 
@@ -150,18 +161,9 @@ mod tests {
             .light_blocks
             .into_iter()
             .map(|lb| lb.generate().unwrap())
-            .map(testgen_to_lb)
+            .map(LightBlock::from)
             .collect::<Vec<_>>();
 
         f(db, blocks)
     }
-
-    fn testgen_to_lb(tm_lb: TGLightBlock) -> LightBlock {
-        LightBlock {
-            signed_header: tm_lb.signed_header,
-            validators: tm_lb.validators,
-            next_validators: tm_lb.next_validators,
-            provider: tm_lb.provider,
-        }
-    }
 }
@@ -0,0 +1,133 @@
+//! Versioning of the on-disk layout of the [`SledStore`], and migrations of
+//! the databases created by older versions of this crate.
+//!
+//! [`SledStore`]: super::SledStore
+
+use super::{FAILED, TRUSTED, UNVERIFIED, VERIFIED};
+use crate::errors::Error;
+
+/// The version of the layout of the databases created by this version of the
+/// crate.
+pub const SCHEMA_VERSION: u32 = 1;
+
+const META: &str = "meta";
+const VERSION_KEY: &[u8] = b"schema_version";
+
+type Migration = fn(&sled::Db) -> Result<(), Error>;
+
+/// The migration at index `i` upgrades a database from version `i` to
+/// version `i + 1`.
+///
+/// Migrations must be idempotent: a migration interrupted before the new
+/// version is recorded runs again when the database is next opened.
+const MIGRATIONS: &[Migration] = &[
+    // Version 0 is the layout of the databases created before versioning was
+    // introduced, which version 1 keeps as is.
+    |_| Ok(()),
+];
+
+/// The version of the layout of the given database, or `None` if it was
+/// created before versioning was introduced or has not been used yet.
+pub fn version(db: &sled::Db) -> Result<Option<u32>, Error> {
+    let meta = db.open_tree(META).map_err(Error::sled)?;
+    let version = meta.get(VERSION_KEY).map_err(Error::sled)?;
+    // An unreadable version is taken to be from the future, so that the
+    // database is left untouched.
+    Ok(version.map(|bytes| match bytes.as_ref().try_into() {
+        Ok(be_bytes) => u32::from_be_bytes(be_bytes),
+        Err(_) => u32::MAX,
+    }))
+}
+
+/// Upgrade the given database to the current version.
+///
+/// Fails without modifying the database if it was created by a newer version
+/// of the crate.
+pub fn migrate(db: &sled::Db) -> Result<(), Error> {
+    let version = match version(db)? {
+        Some(version) => version,
+        None if is_empty(db)? => return set_version(db, SCHEMA_VERSION),
+        None => 0,
+    };
+
+    if version > SCHEMA_VERSION {
+        return Err(Error::unsupported_store_version(version, SCHEMA_VERSION));
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(db)?;
+        set_version(db, from as u32 + 1)?;
+    }
+
+    Ok(())
+}
+
+fn set_version(db: &sled::Db, version: u32) -> Result<(), Error> {
+    let meta = db.open_tree(META).map_err(Error::sled)?;
+    meta.insert(VERSION_KEY, &version.to_be_bytes())
+        .map_err(Error::sled)?;
+    db.flush().map_err(Error::sled)?;
+    Ok(())
+}
+
+fn is_empty(db: &sled::Db) -> Result<bool, Error> {
+    for name in [UNVERIFIED, VERIFIED, TRUSTED, FAILED] {
+        if !db.open_tree(name).map_err(Error::sled)?.is_empty() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::errors::ErrorDetail;
+
+    #[test]
+    fn migrations_lead_to_current_version() {
+        assert_eq!(MIGRATIONS.len(), SCHEMA_VERSION as usize);
+    }
+
+    #[test]
+    fn new_database_gets_current_version() {
+        let db = sled::open(tempdir().unwrap()).unwrap();
+        migrate(&db).unwrap();
+        assert_eq!(version(&db).unwrap(), Some(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn unversioned_database_is_migrated() {
+        let db = sled::open(tempdir().unwrap()).unwrap();
+        let verified = db.open_tree(VERIFIED).unwrap();
+        verified.insert(1_u64.to_be_bytes(), b"block").unwrap();
+
+        migrate(&db).unwrap();
+        assert_eq!(version(&db).unwrap(), Some(SCHEMA_VERSION));
+        assert_eq!(
+            verified.get(1_u64.to_be_bytes()).unwrap().as_deref(),
+            Some(&b"block"[..])
+        );
+
+        // Migrating again is a no-op.
+        migrate(&db).unwrap();
+        assert_eq!(version(&db).unwrap(), Some(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn newer_database_is_refused() {
+        let db = sled::open(tempdir().unwrap()).unwrap();
+        set_version(&db, SCHEMA_VERSION + 1).unwrap();
+
+        match migrate(&db).unwrap_err().detail() {
+            ErrorDetail::UnsupportedStoreVersion(e) => {
+                assert_eq!(e.version, SCHEMA_VERSION + 1);
+                assert_eq!(e.supported, SCHEMA_VERSION);
+            },
+            e => panic!("unexpected error: {e:?}"),
+        }
+        assert_eq!(version(&db).unwrap(), Some(SCHEMA_VERSION + 1));
+    }
+}
@@ -0,0 +1,220 @@
+//! Portable snapshots of the trusted and verified light blocks of a store.
+//!
+//! A snapshot exported from the store of a running light client, e.g. by an
+//! operator, can be imported into the empty store of a freshly provisioned one,
+//! which can then resume from the trusted state of the snapshot (see
+//! [`LightClientBuilder::trust_from_store`]) rather than from trust options.
+//! As a snapshot is trusted as is, it must come from a trusted source.
+//!
+//! A snapshot is a CBOR-encoded file, independent of the database backing the
+//! store it was exported from.
+//!
+//! [`LightClientBuilder::trust_from_store`]: crate::builder::LightClientBuilder::trust_from_store
+
+use std::{ffi::OsString, fs, path::Path};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    errors::Error,
+    store::LightStore,
+    verifier::types::{LightBlock, Status},
+};
+
+/// The version of the format of the snapshots exported by this version of the
+/// crate.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// The statuses of the light blocks exported in a snapshot. The other ones are
+/// only relevant to the light client which fetched the blocks.
+const EXPORTED: &[Status] = &[Status::Trusted, Status::Verified];
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    light_blocks: Vec<(Status, LightBlock)>,
+}
+
+// Decoded first, to reject snapshots of a newer version whatever their format.
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+/// Export the trusted and verified light blocks of the given store, with their
+/// status, to a snapshot at the given path.
+///
+/// The snapshot is written to a temporary file next to the given path, named
+/// after it with a `.tmp` suffix, which is then renamed to it, so that an
+/// existing snapshot is only replaced by a complete one.
+pub fn export<S: LightStore + ?Sized>(store: &S, path: &Path) -> Result<(), Error> {
+    let snapshot = Snapshot {
+        version: SNAPSHOT_VERSION,
+        light_blocks: EXPORTED
+            .iter()
+            .flat_map(|status| store.all(*status).map(|lb| (*status, lb)))
+            .collect(),
+    };
+    let bytes = serde_cbor::to_vec(&snapshot).map_err(Error::serde_cbor)?;
+
+    let mut tmp_path = OsString::from(path);
+    tmp_path.push(".tmp");
+    fs::write(&tmp_path, bytes)
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .map_err(|e| snapshot_io(path, e))
+}
+
+/// Import the light blocks of the snapshot at the given path into the given
+/// store, with their status.
+///
+/// Light blocks already in the store are kept, unless the snapshot has a
+/// light block at the same height which replaces them as per
+/// [`LightStore::insert`]: the sled and SQLite stores, which hold a light block
+/// per height and status, only replace the one with the same status, while the
+/// [`MemoryStore`], which holds a single light block per height, replaces it
+/// whatever its status. The trusted light blocks are imported last, so that
+/// they take precedence over the verified ones at the same height.
+///
+/// Fails without modifying the store if the snapshot was exported by a newer
+/// version of the crate.
+///
+/// [`MemoryStore`]: crate::store::memory::MemoryStore
+pub fn import<S: LightStore + ?Sized>(store: &mut S, path: &Path) -> Result<(), Error> {
+    let bytes = fs::read(path).map_err(|e| snapshot_io(path, e))?;
+
+    let Version { version } = serde_cbor::from_slice(&bytes).map_err(Error::serde_cbor)?;
+    if version > SNAPSHOT_VERSION {
+        return Err(Error::unsupported_snapshot_version(
+            version,
+            SNAPSHOT_VERSION,
+        ));
+    }
+
+    let mut snapshot: Snapshot = serde_cbor::from_slice(&bytes).map_err(Error::serde_cbor)?;
+    snapshot
+        .light_blocks
+        .sort_by_key(|(status, _)| *status == Status::Trusted);
+    for (status, light_block) in snapshot.light_blocks {
+        store.insert(light_block, status);
+    }
+
+    Ok(())
+}
+
+fn snapshot_io(path: &Path, e: std::io::Error) -> Error {
+    Error::snapshot_io(path.display().to_string(), e)
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint_testgen::{Generator, LightChain};
+
+    use super::*;
+    use crate::{errors::ErrorDetail, store::memory::MemoryStore, verifier::types::Height};
+
+    fn light_blocks() -> Vec<LightBlock> {
+        LightChain::default_with_length(4)
+            .light_blocks
+            .iter()
+            .map(|lb| LightBlock::from(lb.generate().unwrap()))
+            .collect()
+    }
+
+    fn heights(store: &dyn LightStore, status: Status) -> Vec<u64> {
+        store.all(status).map(|lb| lb.height().value()).collect()
+    }
+
+    #[test]
+    fn export_import_roundtrip() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("snapshot.cbor");
+        let light_blocks = light_blocks();
+
+        let mut store = MemoryStore::new();
+        store.insert(light_blocks[0].clone(), Status::Trusted);
+        store.insert(light_blocks[1].clone(), Status::Verified);
+        store.insert(light_blocks[2].clone(), Status::Unverified);
+        store.insert(light_blocks[3].clone(), Status::Failed);
+        store.export(&path).unwrap();
+
+        let mut imported = MemoryStore::new();
+        imported.import(&path).unwrap();
+
+        assert_eq!(heights(&imported, Status::Trusted), [1]);
+        assert_eq!(heights(&imported, Status::Verified), [2]);
+        assert!(heights(&imported, Status::Unverified).is_empty());
+        assert!(heights(&imported, Status::Failed).is_empty());
+        assert_eq!(
+            imported.get(Height::from(2_u32), Status::Verified),
+            Some(light_blocks[1].clone())
+        );
+    }
+
+    #[test]
+    fn export_keeps_files_with_the_same_stem() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("snapshot.cbor");
+        let sibling = tmp_dir.path().join("snapshot.tmp");
+        fs::write(&sibling, b"not a snapshot").unwrap();
+
+        let mut store = MemoryStore::new();
+        store.insert(light_blocks()[0].clone(), Status::Trusted);
+        store.export(&path).unwrap();
+
+        assert_eq!(fs::read(&sibling).unwrap(), b"not a snapshot");
+        assert!(!tmp_dir.path().join("snapshot.cbor.tmp").exists());
+        MemoryStore::new().import(&path).unwrap();
+    }
+
+    #[test]
+    fn trusted_light_blocks_take_precedence() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("snapshot.cbor");
+        // As exported from a store holding a light block per height and status.
+        let light_block = light_blocks()[0].clone();
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            light_blocks: vec![
+                (Status::Trusted, light_block.clone()),
+                (Status::Verified, light_block),
+            ],
+        };
+        fs::write(&path, serde_cbor::to_vec(&snapshot).unwrap()).unwrap();
+
+        let mut store = MemoryStore::new();
+        store.import(&path).unwrap();
+
+        assert_eq!(heights(&store, Status::Trusted), [1]);
+        assert!(heights(&store, Status::Verified).is_empty());
+    }
+
+    #[test]
+    fn newer_snapshot_is_rejected() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("snapshot.cbor");
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION + 1,
+            light_blocks: vec![(Status::Trusted, light_blocks()[0].clone())],
+        };
+        fs::write(&path, serde_cbor::to_vec(&snapshot).unwrap()).unwrap();
+
+        let mut store = MemoryStore::new();
+        let e = store.import(&path).unwrap_err();
+
+        assert!(matches!(
+            e.detail(),
+            ErrorDetail::UnsupportedSnapshotVersion(e) if e.version == SNAPSHOT_VERSION + 1
+        ));
+        assert!(store.highest(Status::Trusted).is_none());
+    }
+
+    #[test]
+    fn missing_snapshot_is_an_error() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("snapshot.cbor");
+
+        let e = MemoryStore::new().import(&path).unwrap_err();
+
+        assert!(matches!(e.detail(), ErrorDetail::SnapshotIo(_)));
+    }
+}
@@ -0,0 +1,302 @@
+//! Persistent store backed by an on-disk SQLite database.
+
+pub mod schema;
+
+use std::{path::Path, sync::Mutex};
+
+use rusqlite::{params, Connection, OptionalExtension, Params};
+
+use super::{LightStore, Status};
+use crate::{
+    errors::Error,
+    verifier::types::{Height, LightBlock},
+};
+
+/// Persistent store backed by an on-disk SQLite database.
+///
+/// The light blocks are kept in a single `light_blocks` table, indexed by
+/// status and height, and encoded with CBOR like in the [`SledStore`].
+/// Databases created by older versions of this crate are migrated to the
+/// current [`schema::SCHEMA_VERSION`] when opened, while those created by
+/// newer versions are refused.
+///
+/// [`SledStore`]: crate::store::sled::SledStore
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::Unverified => "unverified",
+        Status::Verified => "verified",
+        Status::Trusted => "trusted",
+        Status::Failed => "failed",
+    }
+}
+
+fn height_value(height: Height) -> i64 {
+    // Heights are bounded by `i64::MAX`.
+    height.value() as i64
+}
+
+impl SqliteStore {
+    /// Open a SQLite database, creating it if needed, and create a new
+    /// persistent store from it.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::new(Connection::open(path).map_err(Error::sqlite)?)
+    }
+
+    /// Create a new persistent store from a SQLite database that is already
+    /// open.
+    pub fn new(mut conn: Connection) -> Result<Self, Error> {
+        schema::migrate(&mut conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn query<P: Params>(&self, sql: &str, params: P) -> Result<Vec<LightBlock>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(sql).map_err(Error::sqlite)?;
+        let rows = stmt
+            .query_map(params, |row| row.get::<_, Vec<u8>>(0))
+            .map_err(Error::sqlite)?;
+
+        let mut light_blocks = Vec::new();
+        for bytes in rows {
+            let bytes = bytes.map_err(Error::sqlite)?;
+            light_blocks.push(serde_cbor::from_slice(&bytes).map_err(Error::serde_cbor)?);
+        }
+        Ok(light_blocks)
+    }
+
+    fn query_one<P: Params>(&self, sql: &str, params: P) -> Option<LightBlock> {
+        let conn = self.conn.lock().unwrap();
+        let bytes = conn
+            .query_row(sql, params, |row| row.get::<_, Vec<u8>>(0))
+            .optional()
+            .ok()??;
+        serde_cbor::from_slice(&bytes).ok()
+    }
+
+    fn try_update(&self, light_block: &LightBlock, status: Status) -> Result<(), Error> {
+        let bytes = serde_cbor::to_vec(light_block).map_err(Error::serde_cbor)?;
+        let height = height_value(light_block.height());
+
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(Error::sqlite)?;
+        tx.execute(
+            "DELETE FROM light_blocks WHERE height = ?1 AND status <> ?2",
+            params![height, status_name(status)],
+        )
+        .map_err(Error::sqlite)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO light_blocks (status, height, block) VALUES (?1, ?2, ?3)",
+            params![status_name(status), height, bytes],
+        )
+        .map_err(Error::sqlite)?;
+        tx.commit().map_err(Error::sqlite)
+    }
+
+    fn try_insert(&self, light_block: &LightBlock, status: Status) -> Result<(), Error> {
+        let bytes = serde_cbor::to_vec(light_block).map_err(Error::serde_cbor)?;
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO light_blocks (status, height, block) VALUES (?1, ?2, ?3)",
+            params![
+                status_name(status),
+                height_value(light_block.height()),
+                bytes
+            ],
+        )
+        .map_err(Error::sqlite)?;
+        Ok(())
+    }
+}
+
+impl LightStore for SqliteStore {
+    fn get(&self, height: Height, status: Status) -> Option<LightBlock> {
+        self.query_one(
+            "SELECT block FROM light_blocks WHERE status = ?1 AND height = ?2",
+            params![status_name(status), height_value(height)],
+        )
+    }
+
+    fn update(&mut self, light_block: &LightBlock, status: Status) {
+        self.try_update(light_block, status).ok();
+    }
+
+    fn insert(&mut self, light_block: LightBlock, status: Status) {
+        self.try_insert(&light_block, status).ok();
+    }
+
+    fn remove(&mut self, height: Height, status: Status) {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM light_blocks WHERE status = ?1 AND height = ?2",
+            params![status_name(status), height_value(height)],
+        )
+        .ok();
+    }
+
+    fn highest(&self, status: Status) -> Option<LightBlock> {
+        self.query_one(
+            "SELECT block FROM light_blocks WHERE status = ?1 ORDER BY height DESC LIMIT 1",
+            params![status_name(status)],
+        )
+    }
+
+    fn highest_before(&self, height: Height, status: Status) -> Option<LightBlock> {
+        self.query_one(
+            "SELECT block FROM light_blocks WHERE status = ?1 AND height <= ?2 \
+             ORDER BY height DESC LIMIT 1",
+            params![status_name(status), height_value(height)],
+        )
+    }
+
+    fn lowest(&self, status: Status) -> Option<LightBlock> {
+        self.query_one(
+            "SELECT block FROM light_blocks WHERE status = ?1 ORDER BY height ASC LIMIT 1",
+            params![status_name(status)],
+        )
+    }
+
+    fn all(&self, status: Status) -> Box<dyn Iterator<Item = LightBlock>> {
+        let light_blocks = self
+            .query(
+                "SELECT block FROM light_blocks WHERE status = ?1 ORDER BY height ASC",
+                params![status_name(status)],
+            )
+            .unwrap_or_default();
+        Box::new(light_blocks.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+    use tendermint_testgen::{Generator, LightChain};
+
+    use super::*;
+
+    #[test]
+    fn highest_returns_latest_block() {
+        with_blocks(10, |mut db, blocks| {
+            for block in blocks {
+                db.insert(block.clone(), Status::Verified);
+                assert_eq!(db.highest(Status::Verified), Some(block));
+            }
+        })
+    }
+
+    #[test]
+    fn highest_before_returns_correct_block() {
+        with_blocks(10, |mut db, blocks| {
+            for block in blocks {
+                db.insert(block.clone(), Status::Verified);
+                assert_eq!(
+                    db.highest_before(block.height(), Status::Verified).as_ref(),
+                    Some(&block)
+                );
+                assert_eq!(
+                    db.highest_before(block.height().increment(), Status::Verified)
+                        .as_ref(),
+                    Some(&block)
+                );
+            }
+        })
+    }
+
+    #[test]
+    fn lowest_returns_earliest_block() {
+        with_blocks(10, |mut db, blocks| {
+            let initial_block = blocks[0].clone();
+            db.insert(initial_block.clone(), Status::Verified);
+            assert_eq!(db.lowest(Status::Verified).as_ref(), Some(&initial_block));
+
+            for block in blocks.into_iter().skip(1) {
+                db.insert(block, Status::Verified);
+                assert_eq!(db.lowest(Status::Verified).as_ref(), Some(&initial_block));
+            }
+        })
+    }
+
+    #[test]
+    fn update_moves_block_to_new_status() {
+        with_blocks(3, |mut db, blocks| {
+            for block in &blocks {
+                db.insert(block.clone(), Status::Unverified);
+            }
+            db.update(&blocks[1], Status::Verified);
+
+            assert_eq!(db.get(blocks[1].height(), Status::Unverified), None);
+            assert_eq!(
+                db.get(blocks[1].height(), Status::Verified).as_ref(),
+                Some(&blocks[1])
+            );
+            assert_eq!(
+                db.all(Status::Unverified).collect::<Vec<_>>(),
+                [blocks[0].clone(), blocks[2].clone()]
+            );
+
+            db.remove(blocks[1].height(), Status::Verified);
+            assert_eq!(db.highest(Status::Verified), None);
+        })
+    }
+
+    #[test]
+    fn blocks_persist_across_reopening() {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("light_store.sqlite");
+        let blocks = blocks(2);
+
+        let mut db = SqliteStore::open(&path).unwrap();
+        db.insert(blocks[0].clone(), Status::Trusted);
+        drop(db);
+
+        let db = SqliteStore::open(&path).unwrap();
+        assert_eq!(db.highest(Status::Trusted).as_ref(), Some(&blocks[0]));
+    }
+
+    #[cfg(feature = "lightstore-sled")]
+    #[test]
+    fn migrates_from_sled_store() {
+        use crate::store::{copy, sled::SledStore};
+
+        let blocks = blocks(4);
+        let mut sled_store = SledStore::open(tempdir().unwrap()).unwrap();
+        sled_store.insert(blocks[0].clone(), Status::Trusted);
+        sled_store.insert(blocks[1].clone(), Status::Verified);
+        sled_store.insert(blocks[2].clone(), Status::Unverified);
+        sled_store.insert(blocks[3].clone(), Status::Failed);
+
+        let mut db = SqliteStore::new(Connection::open_in_memory().unwrap()).unwrap();
+        copy(&sled_store, &mut db);
+
+        for status in Status::iter() {
+            assert_eq!(
+                db.all(*status).collect::<Vec<_>>(),
+                sled_store.all(*status).collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(db.highest_trusted_or_verified().as_ref(), Some(&blocks[1]));
+    }
+
+    fn with_blocks(height: u64, f: impl FnOnce(SqliteStore, Vec<LightBlock>)) {
+        let db = SqliteStore::new(Connection::open_in_memory().unwrap()).unwrap();
+        f(db, blocks(height))
+    }
+
+    fn blocks(height: u64) -> Vec<LightBlock> {
+        let chain = LightChain::default_with_length(height);
+        chain
+            .light_blocks
+            .into_iter()
+            .map(|lb| lb.generate().unwrap())
+            .map(LightBlock::from)
+            .collect::<Vec<_>>()
+    }
+}
@@ -0,0 +1,110 @@
+//! Versioning of the schema of the [`SqliteStore`], and migrations of the
+//! databases created by older versions of this crate.
+//!
+//! The version of the schema is recorded in the `user_version` of the
+//! database, which is zero for new databases.
+//!
+//! [`SqliteStore`]: super::SqliteStore
+
+use rusqlite::{Connection, Transaction};
+
+use crate::errors::Error;
+
+/// The version of the schema of the databases created by this version of the
+/// crate.
+pub const SCHEMA_VERSION: u32 = 1;
+
+type Migration = fn(&Transaction<'_>) -> Result<(), rusqlite::Error>;
+
+/// The migration at index `i` upgrades a database from version `i` to
+/// version `i + 1`.
+///
+/// Each migration runs in a transaction together with the recording of the
+/// new version.
+const MIGRATIONS: &[Migration] = &[
+    // Version 0 is a new database.
+    |tx| {
+        tx.execute_batch(
+            "CREATE TABLE light_blocks (
+                status TEXT NOT NULL,
+                height INTEGER NOT NULL,
+                block BLOB NOT NULL,
+                PRIMARY KEY (status, height)
+            ) WITHOUT ROWID;
+            CREATE INDEX light_blocks_height ON light_blocks (height);",
+        )
+    },
+];
+
+/// The version of the schema of the given database.
+pub fn version(conn: &Connection) -> Result<u32, Error> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))
+        .map_err(Error::sqlite)
+        // A version out of range is taken to be from the future, so that the
+        // database is left untouched.
+        .map(|version| u32::try_from(version).unwrap_or(u32::MAX))
+}
+
+/// Upgrade the given database to the current version.
+///
+/// Fails without modifying the database if it was created by a newer version
+/// of the crate.
+pub fn migrate(conn: &mut Connection) -> Result<(), Error> {
+    let version = version(conn)?;
+
+    if version > SCHEMA_VERSION {
+        return Err(Error::unsupported_store_version(version, SCHEMA_VERSION));
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let tx = conn.transaction().map_err(Error::sqlite)?;
+        migration(&tx).map_err(Error::sqlite)?;
+        set_version(&tx, from as u32 + 1)?;
+        tx.commit().map_err(Error::sqlite)?;
+    }
+
+    Ok(())
+}
+
+fn set_version(conn: &Connection, version: u32) -> Result<(), Error> {
+    // Pragmas do not take parameters.
+    conn.execute_batch(&format!("PRAGMA user_version = {version}"))
+        .map_err(Error::sqlite)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ErrorDetail;
+
+    #[test]
+    fn migrations_lead_to_current_version() {
+        assert_eq!(MIGRATIONS.len(), SCHEMA_VERSION as usize);
+    }
+
+    #[test]
+    fn new_database_gets_current_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        assert_eq!(version(&conn).unwrap(), SCHEMA_VERSION);
+
+        // Migrating again is a no-op.
+        migrate(&mut conn).unwrap();
+        assert_eq!(version(&conn).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn newer_database_is_refused() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        set_version(&conn, SCHEMA_VERSION + 1).unwrap();
+
+        match migrate(&mut conn).unwrap_err().detail() {
+            ErrorDetail::UnsupportedStoreVersion(e) => {
+                assert_eq!(e.version, SCHEMA_VERSION + 1);
+                assert_eq!(e.supported, SCHEMA_VERSION);
+            },
+            e => panic!("unexpected error: {e:?}"),
+        }
+        assert_eq!(version(&conn).unwrap(), SCHEMA_VERSION + 1);
+    }
+}
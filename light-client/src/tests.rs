@@ -12,7 +12,7 @@ use tendermint_rpc as rpc;
 use crate::{
     components::{
         clock::Clock,
-        io::{AtHeight, Io, IoError},
+        io::{AsyncIo, AtHeight, Io, IoError},
     },
     errors::Error,
     light_client::LightClient,
@@ -125,6 +125,13 @@ impl Io for MockIo {
     }
 }
 
+#[async_trait::async_trait]
+impl AsyncIo for MockIo {
+    async fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+        Io::fetch_light_block(self, height)
+    }
+}
+
 #[cfg(feature = "rust-crypto")]
 pub fn verify_single(
     trusted_block: LightBlock,
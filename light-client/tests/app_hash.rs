@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use tendermint::{block::Header, AppHash};
+use tendermint_light_client::{
+    app_hash::{AppHashChecker, AppHashMismatch},
+    verifier::types::Height,
+};
+use tendermint_rpc::endpoint::block_results;
+use tendermint_testgen::{light_block::TmLightBlock as TGLightBlock, Generator, LightChain};
+
+fn app_hash(byte: u8) -> AppHash {
+    AppHash::try_from(vec![byte; 32]).unwrap()
+}
+
+// Headers at heights 1 to 4, the header at height `h` carrying the app hash
+// committed at `h - 1`.
+fn headers() -> Vec<Header> {
+    LightChain::default_with_length(4)
+        .light_blocks
+        .iter()
+        .map(|lb| {
+            let tm_lb: TGLightBlock = lb.generate().unwrap();
+            let mut header = tm_lb.signed_header.header;
+            header.app_hash = app_hash(header.height.value() as u8 - 1);
+            header
+        })
+        .collect()
+}
+
+fn committed(heights: impl IntoIterator<Item = u64>) -> BTreeMap<Height, AppHash> {
+    heights
+        .into_iter()
+        .map(|h| (Height::try_from(h).unwrap(), app_hash(h as u8)))
+        .collect()
+}
+
+#[test]
+fn continuous_app_hashes_pass() {
+    let checker = AppHashChecker::new(committed(1..=4));
+    assert_eq!(checker.check(&headers()).unwrap(), vec![]);
+}
+
+#[test]
+fn diverging_app_hash_is_flagged() {
+    let mut committed = committed(1..=4);
+    committed.insert(Height::from(2_u32), app_hash(0xFF));
+
+    let mismatches = AppHashChecker::new(committed).check(&headers()).unwrap();
+
+    assert_eq!(
+        mismatches,
+        vec![AppHashMismatch {
+            height: Height::from(2_u32),
+            committed: app_hash(0xFF),
+            expected: app_hash(2),
+        }]
+    );
+}
+
+#[test]
+fn gaps_and_unknown_app_hashes_are_skipped() {
+    let mut headers = headers();
+    // Without the header at height 3, neither the app hash committed at
+    // height 2 nor the one committed at height 3 can be checked.
+    headers.remove(2);
+    let mut committed = committed([1]);
+    committed.insert(Height::from(2_u32), app_hash(0xFF));
+    committed.insert(Height::from(3_u32), app_hash(0xFF));
+
+    let mismatches = AppHashChecker::new(committed).check(&headers).unwrap();
+    assert_eq!(mismatches, vec![]);
+}
+
+#[test]
+fn block_results_without_app_hash_are_skipped() {
+    let results: Vec<block_results::Response> = (1..=3_u32)
+        .map(|h| block_results::Response {
+            height: Height::from(h),
+            txs_results: None,
+            finalize_block_events: vec![],
+            begin_block_events: None,
+            end_block_events: None,
+            validator_updates: vec![],
+            consensus_param_updates: None,
+            // Results from a node predating CometBFT 0.38 at height 2
+            app_hash: if h == 2 {
+                AppHash::default()
+            } else {
+                app_hash(0xFF)
+            },
+        })
+        .collect();
+
+    let mismatches = AppHashChecker::new(results.as_slice())
+        .check(&headers())
+        .unwrap();
+
+    let heights: Vec<_> = mismatches.iter().map(|m| m.height.value()).collect();
+    assert_eq!(heights, [1, 3]);
+}
@@ -0,0 +1,167 @@
+use std::{collections::HashMap, time::Duration};
+
+use tendermint_light_client::{
+    async_light_client::AsyncLightClient,
+    components::{
+        io::{AtHeight, Io},
+        scheduler,
+    },
+    light_client::{LightClient, Strictness},
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    tests::*,
+    verifier::{
+        options::Options,
+        types::{Height, LightBlock, Status},
+        ProdVerifier,
+    },
+};
+use tendermint_testgen::{light_block::default_peer_id, Generator, LightChain, Tester};
+
+// Link to JSON test files repo:
+// https://github.com/informalsystems/conformance-tests
+const TEST_FILES_PATH: &str = "./tests/support/";
+
+fn setup(tc: &LightClientTest<LightBlock>) -> (Options, MockClock, MockIo, State) {
+    let options = Options {
+        trust_threshold: tc.trust_options.trust_level,
+        trusting_period: tc.trust_options.period.into(),
+        clock_drift: Duration::from_secs(10),
+    };
+    let clock = MockClock { now: tc.now };
+    let io = MockIo::new(tc.primary.lite_blocks.clone());
+
+    let trusted_state = io
+        .fetch_light_block(AtHeight::At(tc.trust_options.height))
+        .expect("could not 'request' light block");
+    let mut light_store = MemoryStore::new();
+    light_store.insert(trusted_state, Status::Trusted);
+    let state = State {
+        light_store: Box::new(light_store),
+        verification_trace: HashMap::new(),
+    };
+
+    (options, clock, io, state)
+}
+
+// The async light client must reach the same outcome as the sync one, after
+// fetching the same blocks.
+fn forward_test(tc: LightClientTest<LightBlock>) {
+    let target_height = tc.height_to_verify;
+
+    let (options, clock, io, mut sync_state) = setup(&tc);
+    let light_client = LightClient::new(
+        default_peer_id(),
+        options,
+        clock,
+        scheduler::basic_bisecting_schedule,
+        ProdVerifier::default(),
+        io,
+    );
+    let expected = light_client.verify_to_target(target_height, &mut sync_state);
+
+    let (options, clock, io, mut state) = setup(&tc);
+    let light_client = AsyncLightClient::new(
+        default_peer_id(),
+        options,
+        clock,
+        scheduler::basic_bisecting_schedule,
+        ProdVerifier::default(),
+        io,
+    );
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let result = rt.block_on(light_client.verify_to_target(target_height, &mut state));
+
+    match (result, expected) {
+        (Ok(light_block), Ok(expected)) => {
+            assert_eq!(light_block, expected);
+            assert_eq!(
+                state.get_trace(target_height),
+                sync_state.get_trace(target_height)
+            );
+        },
+        (Err(e), Err(expected)) => {
+            assert_eq!(e.detail().to_string(), expected.detail().to_string())
+        },
+        (result, expected) => panic!("got {result:?}, expected {expected:?}"),
+    }
+    assert_eq!(
+        state.light_store.all(Status::Unverified).count(),
+        sync_state.light_store.all(Status::Unverified).count()
+    );
+}
+
+#[test]
+fn run_tests() {
+    let mut tester = Tester::new("async light client verification", TEST_FILES_PATH);
+    tester.add_test("forward verification with bisection", forward_test);
+    tester.run_foreach_in_dir("bisection/single_peer");
+    tester.finalize();
+}
+
+fn make(length: u64) -> (AsyncLightClient, State) {
+    let chain: Vec<LightBlock> = LightChain::default_with_length(length)
+        .light_blocks
+        .into_iter()
+        .map(|lb| LightBlock::from(lb.generate().unwrap()))
+        .collect();
+
+    let clock = MockClock {
+        now: tendermint_testgen::helpers::get_time(length + 1).unwrap(),
+    };
+    let options = Options {
+        trust_threshold: Default::default(),
+        trusting_period: Duration::from_secs(60 * 60 * 24 * 10),
+        clock_drift: Duration::from_secs(10),
+    };
+
+    let mut light_store = MemoryStore::new();
+    light_store.insert(chain[0].clone(), Status::Trusted);
+    let state = State {
+        light_store: Box::new(light_store),
+        verification_trace: HashMap::new(),
+    };
+
+    let light_client = AsyncLightClient::new(
+        default_peer_id(),
+        options,
+        clock,
+        scheduler::basic_bisecting_schedule,
+        ProdVerifier::default(),
+        MockIo::new(chain),
+    );
+
+    (light_client, state)
+}
+
+#[tokio::test]
+async fn verifies_to_highest_on_a_multi_threaded_runtime() {
+    let (light_client, mut state) = make(10);
+
+    let light_block = tokio::spawn(async move { light_client.verify_to_highest(&mut state).await })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(light_block.height().value(), 10);
+}
+
+#[tokio::test]
+async fn goes_through_checkpoints() {
+    let (light_client, mut state) = make(10);
+    let light_client = light_client.with_strictness(Strictness::Checkpoints(3.try_into().unwrap()));
+
+    let target_height = Height::try_from(10u64).unwrap();
+    light_client
+        .verify_to_target(target_height, &mut state)
+        .await
+        .unwrap();
+
+    let heights: Vec<u64> = state
+        .light_store
+        .all(Status::Verified)
+        .map(|lb| lb.height().value())
+        .collect();
+    assert_eq!(heights, [4, 7, 10]);
+}
@@ -1,4 +1,4 @@
-#![cfg(feature = "unstable")]
+#![cfg(feature = "rust-crypto")]
 
 use std::{collections::HashMap, time::Duration};
 
@@ -9,7 +9,7 @@ use tendermint_light_client::{
         io::{AtHeight, Io},
         scheduler,
     },
-    errors::Error,
+    errors::{Error, ErrorDetail},
     light_client::LightClient,
     state::State,
     store::{memory::MemoryStore, LightStore},
@@ -20,19 +20,7 @@ use tendermint_light_client::{
         ProdVerifier,
     },
 };
-use tendermint_testgen::{
-    light_block::{default_peer_id, TmLightBlock as TGLightBlock},
-    Generator, LightChain,
-};
-
-fn testgen_to_lb(tm_lb: TGLightBlock) -> LightBlock {
-    LightBlock {
-        signed_header: tm_lb.signed_header,
-        validators: tm_lb.validators,
-        next_validators: tm_lb.next_validators,
-        provider: tm_lb.provider,
-    }
-}
+use tendermint_testgen::{light_block::default_peer_id, Generator, LightChain};
 
 #[derive(Clone, Debug)]
 struct TestCase {
@@ -46,7 +34,7 @@ fn make(chain: LightChain, trusted_height: Height) -> (LightClient, State) {
     let primary = default_peer_id();
 
     let clock = MockClock {
-        /// Set the current time to be ahead of the latest block in the chain
+        // Set the current time to be ahead of the latest block in the chain
         now: tendermint_testgen::helpers::get_time(chain.light_blocks.len() as u64 + 1).unwrap(),
     };
 
@@ -60,7 +48,7 @@ fn make(chain: LightChain, trusted_height: Height) -> (LightClient, State) {
         .light_blocks
         .into_iter()
         .map(|lb| lb.generate().unwrap())
-        .map(testgen_to_lb)
+        .map(LightBlock::from)
         .collect();
 
     let io = MockIo::new(light_blocks);
@@ -256,3 +244,40 @@ proptest! {
         bad_test(tc)?;
     }
 }
+
+#[test]
+fn verifies_historical_block_after_syncing_forward() {
+    let chain = LightChain::default_with_length(20);
+    let (light_client, mut state) = make(chain, 10_u32.into());
+
+    let highest = light_client
+        .verify_to_target(20_u32.into(), &mut state)
+        .unwrap();
+    assert_eq!(highest.height(), 20_u32.into());
+
+    let historical = light_client
+        .verify_to_target(3_u32.into(), &mut state)
+        .unwrap();
+    assert_eq!(historical.height(), 3_u32.into());
+    assert!(state
+        .light_store
+        .get(3_u32.into(), Status::Trusted)
+        .is_some());
+}
+
+#[test]
+fn rejects_header_not_before_next_header() {
+    let mut chain = LightChain::default_with_length(10);
+    // Only the time of the block at height 7 is changed, not the
+    // `last_block_id` of the block above it.
+    let header = chain.block_mut(7).unwrap().header.as_mut().unwrap();
+    header.time = Some(Time::from_unix_timestamp(1610105021, 0).unwrap());
+
+    let (light_client, mut state) = make(chain, 10_u32.into());
+    let result = light_client.verify_to_target(5_u32.into(), &mut state);
+
+    match result.unwrap_err().detail() {
+        ErrorDetail::NonDecreasingHeaderTime(e) => assert_eq!(e.height, 7_u32.into()),
+        e => panic!("unexpected error: {e:?}"),
+    }
+}
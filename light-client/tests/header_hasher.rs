@@ -0,0 +1,166 @@
+//! A light client of a chain whose headers are not hashed canonically, fetching
+//! light blocks from a fake node.
+
+#![cfg(feature = "rpc-client")]
+
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use tendermint_light_client::{
+    builder::LightClientBuilder,
+    components::{
+        io::{AtHeight, Io, IoErrorDetail, ProdIo},
+        scheduler,
+    },
+    simulation::VirtualClock,
+    store::memory::MemoryStore,
+    verifier::{
+        operations::HeaderHasher,
+        options::Options,
+        predicates::ProdPredicates,
+        types::{Hash, Header, Height, LightBlock},
+        ProdVerifier,
+    },
+};
+use tendermint_rpc::{
+    endpoint::{commit, validators},
+    server::{Server, ServerHandle},
+    HttpClient, Method,
+};
+use tendermint_testgen::{
+    helpers::get_time, light_block::TmLightBlock as TGLightBlock, Generator, LightChain,
+};
+use tokio::runtime::Runtime;
+
+/// A chain leaving the evidence hash out of the hash of its headers.
+struct WithoutEvidenceHash;
+
+impl HeaderHasher for WithoutEvidenceHash {
+    fn hash(&self, header: &Header) -> Hash {
+        Header {
+            evidence_hash: None,
+            ..header.clone()
+        }
+        .hash()
+    }
+}
+
+// The blocks of such a chain: the commits sign the hash of the headers
+// without their evidence hash, and the headers link to the previous ones by
+// that hash, but their canonical hash covers the evidence hash.
+fn light_blocks() -> Vec<LightBlock> {
+    LightChain::default_with_length(3)
+        .light_blocks
+        .iter()
+        .map(|lb| {
+            let tm_lb: TGLightBlock = lb.generate().unwrap();
+            let mut signed_header = tm_lb.signed_header;
+            signed_header.header.evidence_hash = Some(Hash::Sha256([1; 32]));
+
+            LightBlock {
+                signed_header,
+                validators: tm_lb.validators,
+                next_validators: tm_lb.next_validators,
+                provider: tm_lb.provider,
+            }
+        })
+        .collect()
+}
+
+// Serve the given blocks on a fake node.
+fn serve(rt: &Runtime, light_blocks: &[LightBlock]) -> ServerHandle {
+    let latest = light_blocks.last().unwrap().clone();
+    let commits: BTreeMap<_, _> = light_blocks
+        .iter()
+        .map(|lb| {
+            let commit = commit::Response {
+                signed_header: lb.signed_header.clone(),
+                canonical: true,
+            };
+            (lb.height(), commit)
+        })
+        .collect();
+    let validators: BTreeMap<_, _> = light_blocks
+        .iter()
+        .map(|lb| (lb.height(), lb.validators.clone()))
+        .chain([(latest.height().increment(), latest.next_validators.clone())])
+        .collect();
+
+    let server = Server::new()
+        .on(Method::Commit, move |req: commit::Request| {
+            let height = req.height.unwrap_or(latest.height());
+            Ok(commits[&height].clone())
+        })
+        .on(Method::Validators, move |req: validators::Request| {
+            let height = req.height.unwrap();
+            let validators = validators[&height].validators();
+            Ok(validators::Response::new(
+                height,
+                validators.clone(),
+                validators.len() as i32,
+            ))
+        });
+
+    let _guard = rt.enter();
+    server.bind(([127, 0, 0, 1], 0).into()).unwrap()
+}
+
+fn prod_io(server: &ServerHandle, lb: &LightBlock) -> ProdIo {
+    let client = HttpClient::new(server.http_url().as_str()).unwrap();
+    ProdIo::new(lb.provider, client, Some(Duration::from_secs(5)))
+}
+
+#[test]
+fn light_client_with_custom_header_hasher() {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let light_blocks = light_blocks();
+    let server = serve(&rt, &light_blocks);
+    let header_hasher: Arc<dyn HeaderHasher> = Arc::new(WithoutEvidenceHash);
+    let hash = |height: usize| header_hasher.hash(&light_blocks[height - 1].signed_header.header);
+
+    // Canonically, the headers do not match their commits.
+    let err =
+        Io::fetch_light_block(&prod_io(&server, &light_blocks[0]), AtHeight::Highest).unwrap_err();
+    assert!(matches!(
+        err.detail(),
+        IoErrorDetail::InconsistentResponses(_)
+    ));
+
+    let mut instance = LightClientBuilder::custom(
+        light_blocks[0].provider,
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60 * 60),
+            clock_drift: Duration::from_secs(10),
+        },
+        Box::new(MemoryStore::new()),
+        Box::new(prod_io(&server, &light_blocks[0]).with_header_hasher(header_hasher.clone())),
+        Box::new(VirtualClock::new(get_time(4).unwrap())),
+        Box::new(ProdVerifier::default().with_header_hasher(header_hasher.clone())),
+        Box::new(scheduler::basic_bisecting_schedule),
+        Box::new(ProdPredicates),
+    )
+    .header_hasher(header_hasher.clone())
+    .trust_primary_at(Height::from(2_u32), hash(2))
+    .unwrap()
+    .build();
+
+    // Forward, verifying the commit of the header at height 3.
+    let verified = instance
+        .light_client
+        .verify_to_target(Height::from(3_u32), &mut instance.state)
+        .unwrap();
+    assert_eq!(verified.signed_header, light_blocks[2].signed_header);
+
+    // Backward, linking the header at height 1 to the one at height 2.
+    let verified = instance
+        .light_client
+        .verify_to_target(Height::from(1_u32), &mut instance.state)
+        .unwrap();
+    assert_eq!(hash(1), header_hasher.hash(&verified.signed_header.header));
+    assert_ne!(hash(1), verified.signed_header.header.hash());
+
+    rt.block_on(server.shutdown()).unwrap();
+}
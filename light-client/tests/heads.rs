@@ -0,0 +1,111 @@
+#![cfg(feature = "rust-crypto")]
+
+use std::{collections::HashMap, time::Duration};
+
+use futures::{stream, StreamExt};
+use tendermint_light_client::{
+    async_light_client::AsyncLightClient,
+    components::{io::AtHeight, scheduler},
+    errors::{Error, ErrorDetail},
+    heads::verified_heads,
+    light_client::Strictness,
+    simulation::{ScriptedProvider, VirtualClock},
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    verifier::{
+        options::Options,
+        types::{Header, LightBlock, Status},
+        ProdVerifier,
+    },
+};
+use tendermint_testgen::{
+    helpers::get_time,
+    light_block::{default_peer_id, LightBlock as TestgenLightBlock},
+    Generator,
+};
+
+fn light_block(height: u64) -> LightBlock {
+    LightBlock::from(
+        TestgenLightBlock::new_default_with_time_and_chain_id(
+            "test-chain".to_owned(),
+            get_time(height).unwrap(),
+            height,
+        )
+        .generate()
+        .unwrap(),
+    )
+}
+
+fn header(height: u64) -> Result<Header, Error> {
+    Ok(light_block(height).signed_header.header)
+}
+
+// A light client trusting the block at height 1 of a chain of 10 blocks, and
+// verifying every block against the previous one.
+fn make() -> (AsyncLightClient, State, ScriptedProvider) {
+    let primary = ScriptedProvider::new(default_peer_id(), (1..=10).map(light_block).collect());
+
+    let mut light_store = MemoryStore::new();
+    light_store.insert(light_block(1), Status::Trusted);
+    let state = State {
+        light_store: Box::new(light_store),
+        verification_trace: HashMap::new(),
+    };
+
+    let light_client = AsyncLightClient::new(
+        default_peer_id(),
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60 * 60),
+            clock_drift: Duration::from_secs(10),
+        },
+        VirtualClock::new(get_time(11).unwrap()),
+        scheduler::basic_bisecting_schedule,
+        ProdVerifier::default(),
+        primary.clone(),
+    )
+    .with_strictness(Strictness::SEQUENTIAL);
+
+    (light_client, state, primary)
+}
+
+#[tokio::test]
+async fn verifies_announced_heads() {
+    let (light_client, mut state, primary) = make();
+    let mut forged = light_block(8).signed_header.header;
+    forged.time = get_time(100).unwrap();
+    let headers = stream::iter([
+        header(2),
+        header(3),
+        // A gap, e.g. after missed events
+        header(6),
+        // Replayed after a reconnection
+        header(3),
+        Ok(forged),
+        header(9),
+    ]);
+
+    let heads: Vec<_> = verified_heads(&light_client, &mut state, headers)
+        .collect()
+        .await;
+
+    let verified: Vec<_> = heads
+        .iter()
+        .map(|head| head.as_ref().map(|lb| lb.height().value()).ok())
+        .collect();
+    assert_eq!(verified, [Some(2), Some(3), Some(6), None, Some(9)]);
+    assert!(matches!(
+        heads[3].as_ref().unwrap_err().detail(),
+        ErrorDetail::HeadMismatch(e) if e.height.value() == 8
+    ));
+
+    let fetched: Vec<_> = primary
+        .fetches()
+        .into_iter()
+        .map(|height| match height {
+            AtHeight::At(height) => height.value(),
+            AtHeight::Highest => panic!("unexpected fetch of the highest block"),
+        })
+        .collect();
+    assert_eq!(fetched, (2..=9).collect::<Vec<_>>());
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "rust-crypto")]
+
+use std::{collections::HashMap, time::Duration};
+
+use tendermint_light_client::{
+    components::{io::AtHeight, scheduler},
+    errors::ErrorDetail,
+    light_client::{LightClient, Strictness},
+    simulation::{ScriptedProvider, VirtualClock},
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    verifier::{
+        options::Options,
+        types::{Height, LightBlock, Status},
+        ProdVerifier,
+    },
+};
+use tendermint_testgen::{
+    helpers::get_time,
+    light_block::{default_peer_id, LightBlock as TestgenLightBlock},
+    Generator,
+};
+
+fn light_block(height: u64) -> LightBlock {
+    LightBlock::from(
+        TestgenLightBlock::new_default_with_time_and_chain_id(
+            "test-chain".to_owned(),
+            get_time(height).unwrap(),
+            height,
+        )
+        .generate()
+        .unwrap(),
+    )
+}
+
+// A light client trusting the block at height 1 of a chain of 10 blocks, and
+// verifying every block against the previous one.
+fn make() -> (LightClient, State, ScriptedProvider) {
+    let primary = ScriptedProvider::new(default_peer_id(), (1..=10).map(light_block).collect());
+
+    let mut light_store = MemoryStore::new();
+    light_store.insert(light_block(1), Status::Trusted);
+    let state = State {
+        light_store: Box::new(light_store),
+        verification_trace: HashMap::new(),
+    };
+
+    let light_client = LightClient::new(
+        default_peer_id(),
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60 * 60),
+            clock_drift: Duration::from_secs(10),
+        },
+        VirtualClock::new(get_time(11).unwrap()),
+        scheduler::basic_bisecting_schedule,
+        ProdVerifier::default(),
+        primary.clone(),
+    )
+    .with_strictness(Strictness::SEQUENTIAL);
+
+    (light_client, state, primary)
+}
+
+#[test]
+fn verifies_each_height_once() {
+    let (light_client, mut state, primary) = make();
+    let heights = [8_u32, 4, 12, 4].map(Height::from);
+
+    let results = light_client.verify_heights(&heights, &mut state);
+
+    let verified: Vec<_> = results
+        .iter()
+        .map(|(height, result)| (height.value(), result.is_ok()))
+        .collect();
+    assert_eq!(verified, [(4, true), (8, true), (12, false)]);
+    assert_eq!(
+        results[&Height::from(8_u32)]
+            .as_ref()
+            .unwrap()
+            .height()
+            .value(),
+        8
+    );
+    assert!(matches!(
+        results[&Height::from(12_u32)]
+            .as_ref()
+            .unwrap_err()
+            .detail(),
+        ErrorDetail::Io(_)
+    ));
+
+    // The blocks verified for a height are reused for the next ones.
+    let fetched: Vec<_> = primary
+        .fetches()
+        .into_iter()
+        .map(|height| match height {
+            AtHeight::At(height) => height.value(),
+            AtHeight::Highest => panic!("unexpected fetch of the highest block"),
+        })
+        .collect();
+    assert_eq!(fetched, (2..=11).collect::<Vec<_>>());
+}
@@ -0,0 +1,341 @@
+use std::time::Duration;
+
+use tendermint_light_client::{
+    components::io::{light_block_from_responses, IoError, IoErrorDetail},
+    verifier::{operations::ProdHeaderHasher, types::LightBlock},
+};
+use tendermint_rpc::endpoint::{commit, validators};
+use tendermint_testgen::{Generator, LightChain};
+
+fn light_block() -> LightBlock {
+    LightBlock::from(
+        LightChain::default_with_length(2).light_blocks[1]
+            .generate()
+            .unwrap(),
+    )
+}
+
+fn responses(lb: &LightBlock) -> (commit::Response, validators::Response, validators::Response) {
+    let commit = commit::Response {
+        signed_header: lb.signed_header.clone(),
+        canonical: true,
+    };
+    let validators = validators::Response::new(
+        lb.height(),
+        lb.validators.validators().clone(),
+        lb.validators.validators().len() as i32,
+    );
+    let next_validators = validators::Response::new(
+        lb.height().increment(),
+        lb.next_validators.validators().clone(),
+        lb.next_validators.validators().len() as i32,
+    );
+    (commit, validators, next_validators)
+}
+
+#[test]
+fn light_block_from_consistent_responses() {
+    let lb = light_block();
+    let (commit, validators, next_validators) = responses(&lb);
+
+    let assembled = light_block_from_responses(
+        commit,
+        validators,
+        next_validators,
+        lb.provider,
+        &ProdHeaderHasher::default(),
+    )
+    .unwrap();
+
+    assert_eq!(assembled.signed_header, lb.signed_header);
+    assert_eq!(assembled.validators.hash(), lb.validators.hash());
+    assert_eq!(assembled.next_validators.hash(), lb.next_validators.hash());
+    assert_eq!(
+        assembled.validators.proposer().as_ref().map(|p| p.address),
+        Some(lb.signed_header.header.proposer_address)
+    );
+}
+
+#[test]
+fn validators_for_another_height_are_rejected() {
+    let lb = light_block();
+    let (commit, validators, _) = responses(&lb);
+    let next_validators = validators.clone();
+
+    let err = light_block_from_responses(
+        commit,
+        validators,
+        next_validators,
+        lb.provider,
+        &ProdHeaderHasher::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err.detail(),
+        IoErrorDetail::InconsistentResponses(_)
+    ));
+}
+
+#[test]
+fn incomplete_validators_are_rejected() {
+    let lb = light_block();
+    let (commit, mut validators, next_validators) = responses(&lb);
+    validators.validators.pop();
+
+    let err = light_block_from_responses(
+        commit,
+        validators,
+        next_validators,
+        lb.provider,
+        &ProdHeaderHasher::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err.detail(),
+        IoErrorDetail::InconsistentResponses(_)
+    ));
+}
+
+#[test]
+fn commits_for_another_block_are_rejected() {
+    let lb = light_block();
+    let (mut commit, validators, next_validators) = responses(&lb);
+    commit.signed_header.header.consensus_hash = Default::default();
+
+    let err = light_block_from_responses(
+        commit,
+        validators,
+        next_validators,
+        lb.provider,
+        &ProdHeaderHasher::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err.detail(),
+        IoErrorDetail::InconsistentResponses(_)
+    ));
+}
+
+#[test]
+fn validators_not_matching_the_header_are_rejected() {
+    let lb = light_block();
+    let (mut commit, validators, next_validators) = responses(&lb);
+    commit.signed_header.header.next_validators_hash = Default::default();
+    commit.signed_header.commit.block_id.hash = commit.signed_header.header.hash();
+
+    let err = light_block_from_responses(
+        commit,
+        validators,
+        next_validators,
+        lb.provider,
+        &ProdHeaderHasher::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err.detail(),
+        IoErrorDetail::InconsistentResponses(_)
+    ));
+}
+
+#[test]
+fn oversized_responses_are_reported() {
+    let err = IoError::from_rpc(tendermint_rpc::Error::response_too_large(
+        "commit".to_string(),
+        1024,
+    ));
+    match err.detail() {
+        IoErrorDetail::ResponseTooLarge(e) => {
+            assert_eq!(e.method, "commit");
+            assert_eq!(e.limit, 1024);
+        },
+        _ => panic!("unexpected error: {err:?}"),
+    }
+}
+
+#[test]
+fn request_timeouts_are_reported() {
+    let err = IoError::from_rpc(tendermint_rpc::Error::request_timeout(
+        Duration::from_millis(100),
+    ));
+
+    assert_eq!(err.detail().is_timeout(), Some(Duration::from_millis(100)));
+    assert!(err.detail().is_transient());
+}
+
+#[test]
+fn errors_returned_by_the_node_are_not_transient() {
+    let err = IoError::from_rpc(tendermint_rpc::Error::response(
+        tendermint_rpc::response_error::ResponseError::invalid_params("unknown height"),
+    ));
+
+    assert!(!err.detail().is_transient());
+}
+
+#[test]
+fn malformed_responses_are_not_transient() {
+    let err = IoError::from_rpc(tendermint_rpc::Error::malformed_json());
+    assert!(!err.detail().is_transient());
+
+    let err = IoError::from_rpc(tendermint_rpc::Error::connection_stale(
+        Duration::from_secs(1),
+    ));
+    assert!(err.detail().is_transient());
+}
+
+#[cfg(feature = "rpc-client")]
+mod prod {
+    use std::{
+        num::NonZeroUsize,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use tendermint_light_client::components::io::{AsyncIo, AtHeight, Io, ProdIo, ProviderPolicy};
+    use tendermint_rpc::{
+        server::{Server, ServerHandle},
+        HttpClient, Method,
+    };
+    use tokio::runtime::Runtime;
+
+    use super::*;
+
+    // A runtime with several workers, for the handlers of the fake node to
+    // block in place.
+    fn runtime() -> Runtime {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(4)
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    // Serve the light block on a fake node, running the given hook on every
+    // request for its commit.
+    fn serve<F>(rt: &Runtime, lb: &LightBlock, on_commit: F) -> ServerHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let (commit, validators, next_validators) = responses(lb);
+        let height = lb.height();
+        let server = Server::new()
+            .on(Method::Commit, move |_: commit::Request| {
+                on_commit();
+                Ok(commit.clone())
+            })
+            .on(Method::Validators, move |req: validators::Request| {
+                if req.height == Some(height) {
+                    Ok(validators.clone())
+                } else {
+                    Ok(next_validators.clone())
+                }
+            });
+
+        let _guard = rt.enter();
+        server.bind(([127, 0, 0, 1], 0).into()).unwrap()
+    }
+
+    // Block the thread of a handler of the fake node, but not the node.
+    fn sleep(duration: Duration) {
+        tokio::task::block_in_place(|| std::thread::sleep(duration));
+    }
+
+    fn prod_io(server: &ServerHandle, lb: &LightBlock, policy: ProviderPolicy) -> ProdIo {
+        let client = HttpClient::new(server.http_url().as_str()).unwrap();
+        ProdIo::new(lb.provider, client, Some(Duration::from_secs(5))).with_policy(policy)
+    }
+
+    // A hook making the first request for the commit take a second.
+    fn slow_first_commit(requests: Arc<AtomicUsize>) -> impl Fn() + Send + Sync + 'static {
+        move || {
+            if requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                sleep(Duration::from_secs(1));
+            }
+        }
+    }
+
+    #[test]
+    fn slow_fetches_time_out() {
+        let rt = runtime();
+        let lb = light_block();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = serve(&rt, &lb, slow_first_commit(requests.clone()));
+
+        let io = prod_io(
+            &server,
+            &lb,
+            ProviderPolicy {
+                attempt_timeout: Some(Duration::from_millis(100)),
+                ..Default::default()
+            },
+        );
+        let err = Io::fetch_light_block(&io, AtHeight::Highest).unwrap_err();
+
+        assert_eq!(err.detail().is_timeout(), Some(Duration::from_millis(100)));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        rt.block_on(server.shutdown()).unwrap();
+    }
+
+    #[test]
+    fn timed_out_fetches_are_retried() {
+        let rt = runtime();
+        let lb = light_block();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = serve(&rt, &lb, slow_first_commit(requests.clone()));
+
+        let io = prod_io(
+            &server,
+            &lb,
+            ProviderPolicy {
+                attempt_timeout: Some(Duration::from_millis(100)),
+                max_retries: 1,
+                initial_backoff: Duration::from_millis(10),
+                ..Default::default()
+            },
+        );
+        let fetched = Io::fetch_light_block(&io, AtHeight::Highest).unwrap();
+
+        assert_eq!(fetched.signed_header, lb.signed_header);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        rt.block_on(server.shutdown()).unwrap();
+    }
+
+    #[test]
+    fn concurrent_fetches_are_limited() {
+        let rt = runtime();
+        let lb = light_block();
+        let (in_flight, max_in_flight) =
+            (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let server = {
+            let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+            serve(&rt, &lb, move || {
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                sleep(Duration::from_millis(50));
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+            })
+        };
+
+        let io = prod_io(
+            &server,
+            &lb,
+            ProviderPolicy {
+                max_concurrent_fetches: NonZeroUsize::new(1),
+                ..Default::default()
+            },
+        );
+        let (clone, other_clone) = (io.clone(), io.clone());
+        rt.block_on(async move {
+            let (a, b, c) = tokio::join!(
+                AsyncIo::fetch_light_block(&io, AtHeight::Highest),
+                AsyncIo::fetch_light_block(&clone, AtHeight::Highest),
+                AsyncIo::fetch_light_block(&other_clone, AtHeight::Highest),
+            );
+            a.and(b).and(c).unwrap();
+        });
+
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 1);
+        rt.block_on(server.shutdown()).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, time::Duration};
 
 use tendermint_light_client::{
+    builder::{error::ErrorDetail, LightClientBuilder},
     components::{
         io::{AtHeight, Io},
         scheduler,
@@ -12,11 +13,15 @@ use tendermint_light_client::{
     tests::*,
     verifier::{
         options::Options,
-        types::{LightBlock, Status},
+        predicates::ProdPredicates,
+        types::{Height, LightBlock, Status},
         ProdVerifier,
     },
 };
-use tendermint_testgen::{light_block::default_peer_id, Tester};
+use tendermint_testgen::{
+    light_block::{default_peer_id, LightBlock as TestgenLightBlock},
+    Generator, Tester,
+};
 
 // Link to JSON test files repo:
 // https://github.com/informalsystems/conformance-tests
@@ -116,3 +121,32 @@ fn run_tests() {
     tester.run_foreach_in_dir("bisection/single_peer");
     tester.finalize();
 }
+
+#[test]
+fn builder_rejects_invalid_options() {
+    let light_block = LightBlock::from(TestgenLightBlock::new_default(1).generate().unwrap());
+    // The clock drift must be shorter than the trusting period.
+    let options = Options {
+        trust_threshold: Default::default(),
+        trusting_period: Duration::from_secs(10),
+        clock_drift: Duration::from_secs(10),
+    };
+
+    let result = LightClientBuilder::custom(
+        default_peer_id(),
+        options,
+        Box::new(MemoryStore::new()),
+        Box::new(MockIo::new(vec![light_block.clone()])),
+        Box::new(MockClock {
+            now: light_block.time(),
+        }),
+        Box::new(ProdVerifier::default()),
+        Box::new(scheduler::basic_bisecting_schedule),
+        Box::new(ProdPredicates),
+    )
+    .trust_primary_at(Height::from(1_u32), light_block.signed_header.header.hash());
+    assert!(matches!(
+        result.map(|_| ()).unwrap_err().detail(),
+        ErrorDetail::InvalidOptions(_)
+    ));
+}
@@ -18,23 +18,11 @@ mod mbt {
         },
     };
     use tendermint_testgen::{
-        apalache::*,
-        jsonatr::*,
-        light_block::{default_peer_id, TmLightBlock},
-        validator::generate_validators,
+        apalache::*, jsonatr::*, light_block::default_peer_id, validator::generate_validators,
         Command, Generator, LightBlock as TestgenLightBlock, TestEnv, Tester, Validator, Vote,
     };
     use time::OffsetDateTime;
 
-    fn testgen_to_lb(tm_lb: TmLightBlock) -> LightBlock {
-        LightBlock {
-            signed_header: tm_lb.signed_header,
-            validators: tm_lb.validators,
-            next_validators: tm_lb.next_validators,
-            provider: tm_lb.provider,
-        }
-    }
-
     #[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
     pub enum LiteTestKind {
         SingleStep,
@@ -441,7 +429,7 @@ mod mbt {
             commit.header = Some(header);
 
             input.testgen_block.commit = Some(commit);
-            input.block = testgen_to_lb(input.testgen_block.generate().unwrap());
+            input.block = LightBlock::from(input.testgen_block.generate().unwrap());
 
             (String::from("vote signature"), LiteVerdict::Invalid)
         }
@@ -0,0 +1,134 @@
+use std::{collections::HashMap, time::Duration};
+
+use tendermint::validator;
+use tendermint_light_client::{
+    components::{
+        io::{AtHeight, Io, IoError},
+        scheduler,
+    },
+    errors::ErrorDetail,
+    light_client::LightClient,
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    tests::MockClock,
+    verifier::{
+        options::Options,
+        types::{Height, LightBlock, Status},
+        ProdVerifier,
+    },
+};
+use tendermint_testgen::{light_block::default_peer_id, Generator, LightChain};
+
+/// An I/O component for light clients which are not expected to fetch any block.
+struct NoIo;
+
+impl Io for NoIo {
+    fn fetch_light_block(&self, _height: AtHeight) -> Result<LightBlock, IoError> {
+        panic!("unexpected attempt to fetch a light block")
+    }
+}
+
+fn chain(length: u64) -> Vec<LightBlock> {
+    LightChain::default_with_length(length)
+        .light_blocks
+        .into_iter()
+        .map(|lb| LightBlock::from(lb.generate().unwrap()))
+        .collect()
+}
+
+fn make(chain: &[LightBlock], trusted_height: u64) -> (LightClient, State) {
+    let clock = MockClock {
+        now: tendermint_testgen::helpers::get_time(chain.len() as u64 + 1).unwrap(),
+    };
+
+    let options = Options {
+        trust_threshold: Default::default(),
+        trusting_period: Duration::from_secs(60 * 60 * 24 * 10),
+        clock_drift: Duration::from_secs(10),
+    };
+
+    let mut light_store = MemoryStore::new();
+    light_store.insert(chain[trusted_height as usize - 1].clone(), Status::Trusted);
+
+    let state = State {
+        light_store: Box::new(light_store),
+        verification_trace: HashMap::new(),
+    };
+
+    let light_client = LightClient::new(
+        default_peer_id(),
+        options,
+        clock,
+        scheduler::basic_bisecting_schedule,
+        ProdVerifier::default(),
+        NoIo,
+    );
+
+    (light_client, state)
+}
+
+#[test]
+fn verify_batch_without_io() {
+    let chain = chain(10);
+    let (light_client, mut state) = make(&chain, 1);
+
+    let batch = vec![chain[9].clone(), chain[4].clone()];
+    let verified = light_client.verify_batch(batch, &mut state).unwrap();
+    assert_eq!(verified.height(), Height::from(10_u32));
+
+    for height in [5_u32, 10] {
+        assert!(state
+            .light_store
+            .get(height.into(), Status::Verified)
+            .is_some());
+    }
+    assert!(state
+        .get_trace(Height::from(10_u32))
+        .iter()
+        .any(|lb| lb.height() == Height::from(5_u32)));
+}
+
+#[test]
+fn verify_empty_batch() {
+    let chain = chain(3);
+    let (light_client, mut state) = make(&chain, 1);
+
+    let e = light_client.verify_batch(vec![], &mut state).unwrap_err();
+    assert!(matches!(e.detail(), ErrorDetail::EmptyBatch(_)));
+}
+
+#[test]
+fn verify_batch_below_trusted_state() {
+    let chain = chain(5);
+    let (light_client, mut state) = make(&chain, 4);
+
+    let e = light_client
+        .verify_batch(vec![chain[1].clone()], &mut state)
+        .unwrap_err();
+    assert!(matches!(
+        e.detail(),
+        ErrorDetail::TargetLowerThanTrustedState(_)
+    ));
+}
+
+#[test]
+fn verify_batch_with_invalid_block() {
+    let chain = chain(5);
+    let (light_client, mut state) = make(&chain, 1);
+
+    let mut invalid = chain[4].clone();
+    invalid.validators = validator::Set::without_proposer(vec![]);
+
+    let e = light_client
+        .verify_batch(vec![chain[2].clone(), invalid], &mut state)
+        .unwrap_err();
+    assert!(matches!(e.detail(), ErrorDetail::InvalidLightBlock(_)));
+    assert!(state
+        .light_store
+        .get(Height::from(3_u32), Status::Verified)
+        .is_some());
+    assert!(state
+        .light_store
+        .get(Height::from(5_u32), Status::Failed)
+        .is_some());
+}
@@ -0,0 +1,206 @@
+#![cfg(feature = "rpc-client")]
+
+use std::time::Duration;
+
+use tendermint::{
+    abci::Code,
+    crypto::{default::Sha256, Sha256 as _},
+    merkle::proof::{ProofOp, ProofOperator, ProofOps, ProofRuntime},
+    Hash, Time,
+};
+use tendermint_light_client::{
+    builder::{LightClientBuilder, TrustOptions},
+    query::{verified_query, Error, ErrorDetail, VerifiedQuery},
+    store::memory::MemoryStore,
+    verifier::{
+        options::Options,
+        types::{Height, LightBlock},
+    },
+};
+use tendermint_rpc::{
+    endpoint::{abci_query, commit, validators},
+    server::{Server, ServerHandle},
+    HttpClient, Method, ResponseError,
+};
+use tendermint_testgen::{
+    light_block::default_peer_id, Commit, Generator, Header, LightBlock as TGLightBlockBuilder,
+    Validator,
+};
+use tokio::runtime::Runtime;
+
+const PROOF_OP: &str = "test:sha256";
+const KEY: &[u8] = b"name";
+const VALUE: &[u8] = b"satoshi";
+
+// Proves a single key of a tree whose root is the hash of the key and value.
+struct HashOp {
+    key: Vec<u8>,
+}
+
+impl ProofOperator for HashOp {
+    fn run(&self, args: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, tendermint::Error> {
+        Ok(vec![root(&self.key, &args[0]).to_vec()])
+    }
+
+    fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+fn decode_hash_op(op: &ProofOp) -> Result<Box<dyn ProofOperator>, tendermint::Error> {
+    Ok(Box::new(HashOp {
+        key: op.key.clone(),
+    }))
+}
+
+fn root(key: &[u8], value: &[u8]) -> [u8; 32] {
+    Sha256::digest([key, value].concat())
+}
+
+// A chain of three blocks, the last one committing to the state in which
+// `KEY` is set to `VALUE` after executing the second one.
+fn light_blocks() -> Vec<LightBlock> {
+    let validators = [
+        Validator::new("1").voting_power(50),
+        Validator::new("2").voting_power(50),
+    ];
+    let now = Time::now();
+    let first = Header::new(&validators)
+        .chain_id("test-chain")
+        .height(1)
+        .time((now - Duration::from_secs(60)).unwrap());
+    let second = first.next();
+    let third = second.next().app_hash(Hash::Sha256(root(KEY, VALUE)));
+
+    [first, second, third]
+        .into_iter()
+        .map(|header| {
+            let commit = Commit::new(header.clone(), 1);
+            LightBlock::from(TGLightBlockBuilder::new(header, commit).generate().unwrap())
+        })
+        .collect()
+}
+
+fn query_response(height: u32, value: &[u8]) -> abci_query::Response {
+    abci_query::Response {
+        response: abci_query::AbciQuery {
+            key: KEY.to_vec(),
+            value: value.to_vec(),
+            proof: Some(ProofOps {
+                ops: vec![ProofOp {
+                    field_type: PROOF_OP.to_string(),
+                    key: KEY.to_vec(),
+                    data: vec![],
+                }],
+            }),
+            height: height.into(),
+            ..Default::default()
+        },
+    }
+}
+
+// Serve the given light blocks and query response on a fake node.
+fn serve(rt: &Runtime, light_blocks: Vec<LightBlock>, query: abci_query::Response) -> ServerHandle {
+    let commits = light_blocks.clone();
+    let server = Server::new()
+        .on(Method::Commit, move |req: commit::Request| {
+            let lb = match req.height {
+                Some(height) => commits.iter().find(|lb| lb.height() == height),
+                None => commits.last(),
+            };
+            let lb = lb.ok_or_else(|| ResponseError::invalid_params("unknown height"))?;
+            Ok(commit::Response {
+                signed_header: lb.signed_header.clone(),
+                canonical: true,
+            })
+        })
+        .on(Method::Validators, move |req: validators::Request| {
+            let validators = light_blocks[0].validators.validators().clone();
+            let total = validators.len() as i32;
+            Ok(validators::Response::new(
+                req.height.unwrap(),
+                validators,
+                total,
+            ))
+        })
+        .with_response(Method::AbciQuery, query);
+
+    let _guard = rt.enter();
+    server.bind(([127, 0, 0, 1], 0).into()).unwrap()
+}
+
+fn query(response: abci_query::Response) -> Result<VerifiedQuery, Error> {
+    let rt = Runtime::new().unwrap();
+    let light_blocks = light_blocks();
+    let trust_options = TrustOptions::new(
+        light_blocks[0].height(),
+        light_blocks[0].signed_header.header.hash(),
+    );
+    let server = serve(&rt, light_blocks, response);
+
+    let client = HttpClient::new(server.http_url().as_str()).unwrap();
+    let mut instance = LightClientBuilder::prod(
+        default_peer_id(),
+        client.clone(),
+        Box::new(MemoryStore::new()),
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60 * 60),
+            clock_drift: Duration::from_secs(5),
+        },
+        Some(Duration::from_secs(5)),
+    )
+    .trust_options(trust_options)
+    .unwrap()
+    .build();
+
+    let res = verified_query(
+        &mut instance,
+        &client,
+        &ProofRuntime::new().with_decoder(PROOF_OP, decode_hash_op),
+        "/store/test/key",
+        KEY,
+        &[KEY],
+        Height::from(2_u32),
+    );
+    rt.block_on(server.shutdown()).unwrap();
+    res
+}
+
+#[test]
+fn proven_value_is_verified() {
+    let verified = query(query_response(2, VALUE)).unwrap();
+
+    assert_eq!(verified.key, KEY);
+    assert_eq!(verified.value, VALUE);
+    assert_eq!(verified.height, Height::from(2_u32));
+    assert_eq!(verified.header.height, Height::from(3_u32));
+}
+
+#[test]
+fn value_not_committed_to_is_rejected() {
+    let err = query(query_response(2, b"nakamoto")).unwrap_err();
+
+    assert!(matches!(err.detail(), ErrorDetail::InvalidProof(_)));
+}
+
+#[test]
+fn answer_at_another_height_is_rejected() {
+    let err = query(query_response(1, VALUE)).unwrap_err();
+
+    assert!(matches!(err.detail(), ErrorDetail::HeightMismatch(_)));
+}
+
+#[test]
+fn failed_query_is_reported() {
+    let mut response = query_response(2, VALUE);
+    response.response.code = Code::from(1);
+    response.response.log = "unknown store".to_string();
+
+    let err = query(response).unwrap_err();
+
+    assert!(matches!(
+        err.detail(),
+        ErrorDetail::QueryFailed(e) if e.log == "unknown store"
+    ));
+}
@@ -0,0 +1,200 @@
+#![cfg(feature = "rpc-client")]
+
+use std::time::Duration;
+
+use tendermint::{
+    abci::{types::ExecTxResult, Code},
+    block::Height,
+    chain,
+    crypto::{default::Sha256, Sha256 as _},
+    merkle::{self, simple_hash_from_byte_vectors},
+    tx, Hash, Time,
+};
+use tendermint_light_client::{
+    builder::TrustOptions,
+    quick::{verify_tx_inclusion, Error, ErrorDetail, TxInclusion, Verdict},
+    verifier::types::LightBlock,
+};
+use tendermint_rpc::{
+    endpoint::{commit, tx as tx_endpoint, validators},
+    server::{Server, ServerHandle},
+    Method, ResponseError,
+};
+use tendermint_testgen::{Commit, Generator, Header, LightBlock as TGLightBlockBuilder, Validator};
+use tokio::runtime::Runtime;
+
+const TX: &[u8] = b"name=satoshi";
+
+fn tx_hash() -> Hash {
+    Hash::Sha256(Sha256::digest(TX))
+}
+
+// A chain of two blocks, the second one including `TX`.
+fn light_blocks() -> Vec<LightBlock> {
+    let validators = [
+        Validator::new("1").voting_power(50),
+        Validator::new("2").voting_power(50),
+    ];
+    let now = Time::now();
+    let first = Header::new(&validators)
+        .chain_id("test-chain")
+        .height(1)
+        .time((now - Duration::from_secs(60)).unwrap());
+    let data_hash = simple_hash_from_byte_vectors::<Sha256>(&[Sha256::digest(TX)]);
+    let second = first.next().data_hash(Hash::Sha256(data_hash));
+
+    [first, second]
+        .into_iter()
+        .map(|header| {
+            let commit = Commit::new(header.clone(), 1);
+            LightBlock::from(TGLightBlockBuilder::new(header, commit).generate().unwrap())
+        })
+        .collect()
+}
+
+fn tx_response(height: u32, code: Code) -> tx_endpoint::Response {
+    let leaf = Sha256::digest(TX);
+    let leaf_hash = simple_hash_from_byte_vectors::<Sha256>(&[leaf]);
+    tx_endpoint::Response {
+        hash: tx_hash(),
+        height: height.into(),
+        index: 0,
+        tx_result: ExecTxResult {
+            code,
+            ..Default::default()
+        },
+        tx: TX.to_vec(),
+        proof: Some(tx::Proof {
+            root_hash: Hash::Sha256(leaf_hash),
+            data: TX.to_vec(),
+            proof: merkle::Proof {
+                total: 1,
+                index: 0,
+                leaf_hash: Hash::Sha256(leaf_hash),
+                aunts: vec![],
+            },
+        }),
+    }
+}
+
+// Serve the given light blocks and transaction on a fake node.
+fn serve(
+    rt: &Runtime,
+    network: &str,
+    light_blocks: Vec<LightBlock>,
+    tx: tx_endpoint::Response,
+) -> ServerHandle {
+    let fixture: serde_json::Value = serde_json::from_str(include_str!(
+        "../../rpc/tests/kvstore_fixtures/v0_37/incoming/status.json"
+    ))
+    .unwrap();
+    let mut status = fixture["result"].clone();
+    status["node_info"]["network"] = network.into();
+
+    let commits = light_blocks.clone();
+    let server = Server::new()
+        .on_raw("status", move |_| Ok(status.clone()))
+        .on(Method::Commit, move |req: commit::Request| {
+            let lb = match req.height {
+                Some(height) => commits.iter().find(|lb| lb.height() == height),
+                None => commits.last(),
+            };
+            let lb = lb.ok_or_else(|| ResponseError::invalid_params("unknown height"))?;
+            Ok(commit::Response {
+                signed_header: lb.signed_header.clone(),
+                canonical: true,
+            })
+        })
+        .on(Method::Validators, move |req: validators::Request| {
+            let validators = light_blocks[0].validators.validators().clone();
+            let total = validators.len() as i32;
+            Ok(validators::Response::new(
+                req.height.unwrap(),
+                validators,
+                total,
+            ))
+        })
+        .with_response(Method::Tx, tx);
+
+    let _guard = rt.enter();
+    server.bind(([127, 0, 0, 1], 0).into()).unwrap()
+}
+
+fn verify(network: &str, tx: tx_endpoint::Response, chain_id: &str) -> Result<TxInclusion, Error> {
+    verify_hash(network, tx, chain_id, tx_hash())
+}
+
+fn verify_hash(
+    network: &str,
+    tx: tx_endpoint::Response,
+    chain_id: &str,
+    tx_hash: Hash,
+) -> Result<TxInclusion, Error> {
+    let rt = Runtime::new().unwrap();
+    let light_blocks = light_blocks();
+    let trust_options = TrustOptions::new(
+        light_blocks[0].height(),
+        light_blocks[0].signed_header.header.hash(),
+    );
+    let server = serve(&rt, network, light_blocks, tx);
+
+    let res = verify_tx_inclusion(
+        server.http_url().as_str(),
+        trust_options,
+        &chain_id.parse::<chain::Id>().unwrap(),
+        tx_hash,
+    );
+    rt.block_on(server.shutdown()).unwrap();
+    res
+}
+
+#[test]
+fn included_tx_is_verified() {
+    let inclusion = verify("test-chain", tx_response(2, Code::Ok), "test-chain").unwrap();
+
+    assert_eq!(inclusion.hash, tx_hash());
+    assert_eq!(inclusion.tx, TX);
+    assert_eq!(inclusion.height, Height::from(2_u32));
+    assert_eq!(inclusion.header.height, inclusion.height);
+    assert_eq!(inclusion.verdict, Verdict::Succeeded);
+}
+
+#[test]
+fn failed_tx_is_reported() {
+    let inclusion = verify("test-chain", tx_response(2, Code::from(5)), "test-chain").unwrap();
+
+    assert!(matches!(inclusion.verdict, Verdict::Failed { code: 5, .. }));
+}
+
+#[test]
+fn node_on_another_chain_is_rejected() {
+    let err = verify("test-chain", tx_response(2, Code::Ok), "other-chain").unwrap_err();
+
+    assert!(matches!(err.detail(), ErrorDetail::ChainIdMismatch(_)));
+}
+
+#[test]
+fn tx_not_in_the_verified_block_is_rejected() {
+    // The block at height 1 includes no transaction.
+    let err = verify("test-chain", tx_response(1, Code::Ok), "test-chain").unwrap_err();
+
+    assert!(matches!(err.detail(), ErrorDetail::NotIncluded(_)));
+}
+
+#[test]
+fn another_tx_of_the_block_is_rejected() {
+    // The node proves the inclusion of `TX`, not of the requested transaction.
+    let requested = Hash::Sha256(Sha256::digest(b"name=vitalik"));
+    let err = verify_hash(
+        "test-chain",
+        tx_response(2, Code::Ok),
+        "test-chain",
+        requested,
+    )
+    .unwrap_err();
+
+    assert!(matches!(
+        err.detail(),
+        ErrorDetail::TxHashMismatch(e) if e.expected == requested && e.found == tx_hash()
+    ));
+}
@@ -0,0 +1,119 @@
+#![cfg(all(feature = "lightstore-sqlite", feature = "rust-crypto"))]
+
+use std::{path::Path, time::Duration};
+
+use tendermint::crypto::default::Sha256;
+use tendermint_light_client::{
+    builder::{error::ErrorDetail, LightClientBuilder, NoTrustedState},
+    components::scheduler,
+    instance::Instance,
+    store::sqlite::SqliteStore,
+    tests::{MockClock, MockIo},
+    verifier::{
+        options::Options,
+        predicates::ProdPredicates,
+        types::{Height, LightBlock},
+        ProdVerifier,
+    },
+};
+use tendermint_testgen::{light_block::default_peer_id, Generator, LightChain};
+
+const TRUSTING_PERIOD: Duration = Duration::from_secs(60);
+
+fn light_blocks() -> Vec<LightBlock> {
+    LightChain::default_with_length(10)
+        .light_blocks
+        .iter()
+        .map(|lb| LightBlock::from(lb.generate().unwrap()))
+        .collect()
+}
+
+// A builder for a light client keeping its state in the given database, as of
+// the given number of seconds after the first block.
+fn builder(path: &Path, secs: u64) -> LightClientBuilder<NoTrustedState, Sha256> {
+    LightClientBuilder::custom(
+        default_peer_id(),
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: TRUSTING_PERIOD,
+            clock_drift: Duration::from_secs(10),
+        },
+        Box::new(SqliteStore::open(path).unwrap()),
+        Box::new(MockIo::new(light_blocks())),
+        Box::new(MockClock {
+            now: tendermint_testgen::helpers::get_time(secs).unwrap(),
+        }),
+        Box::new(ProdVerifier::default()),
+        Box::new(scheduler::basic_bisecting_schedule),
+        Box::new(ProdPredicates),
+    )
+}
+
+// Initialize a light client at height 1 from trust options, and verify up to
+// height 8.
+fn first_run(path: &Path) {
+    let light_blocks = light_blocks();
+    let mut instance: Instance = builder(path, 10)
+        .trust_primary_at(
+            Height::from(1_u32),
+            light_blocks[0].signed_header.header.hash(),
+        )
+        .unwrap()
+        .build();
+
+    instance
+        .light_client
+        .verify_to_target(Height::from(8_u32), &mut instance.state)
+        .unwrap();
+}
+
+#[test]
+fn resumes_from_persisted_trusted_state() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let path = tmp_dir.path().join("light_store.sqlite");
+    first_run(&path);
+
+    let mut instance = builder(&path, 30).trust_from_store().unwrap().build();
+    assert_eq!(
+        instance.latest_trusted().map(|lb| lb.height().value()),
+        Some(8)
+    );
+
+    instance
+        .light_client
+        .verify_to_target(Height::from(10_u32), &mut instance.state)
+        .unwrap();
+    assert!(instance
+        .state
+        .light_store
+        .get_trusted_or_verified(Height::from(10_u32))
+        .is_some());
+}
+
+#[test]
+fn refuses_expired_persisted_trusted_state() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let path = tmp_dir.path().join("light_store.sqlite");
+    first_run(&path);
+
+    // The block at height 8 is older than the trusting period.
+    let e = builder(&path, 8 + TRUSTING_PERIOD.as_secs() + 1)
+        .trust_from_store()
+        .err()
+        .unwrap();
+    assert!(matches!(e.detail(), ErrorDetail::InvalidLightBlock(_)));
+
+    // It can still be re-initialized from trust options.
+    let light_blocks = light_blocks();
+    let instance = builder(&path, 8 + TRUSTING_PERIOD.as_secs() + 1)
+        .trust_primary_at(
+            Height::from(10_u32),
+            light_blocks[9].signed_header.header.hash(),
+        )
+        .unwrap()
+        .build();
+    assert_eq!(
+        instance.latest_trusted().map(|lb| lb.height().value()),
+        Some(10)
+    );
+}
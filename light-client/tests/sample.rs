@@ -0,0 +1,203 @@
+#![cfg(feature = "rpc-client")]
+
+use std::time::Duration;
+
+use tendermint::{
+    block::Block,
+    crypto::{default::Sha256, Sha256 as _},
+    merkle, Hash, Time,
+};
+use tendermint_light_client::{
+    builder::{LightClientBuilder, TrustOptions},
+    sample::{verified_block_samples, Error, ErrorDetail},
+    store::memory::MemoryStore,
+    verifier::{
+        options::Options,
+        types::{Height, LightBlock},
+    },
+};
+use tendermint_rpc::{
+    endpoint::{block, commit, validators},
+    sample::BlockSample,
+    server::{Server, ServerHandle},
+    HttpClient, Method, ResponseError,
+};
+use tendermint_testgen::{
+    light_block::default_peer_id, Commit, Generator, Header, LightBlock as TGLightBlockBuilder,
+    Validator,
+};
+use tokio::runtime::Runtime;
+
+fn txs(height: u64) -> Vec<Vec<u8>> {
+    (0..height - 1)
+        .map(|i| format!("key{i}=value{i}").into_bytes())
+        .collect()
+}
+
+fn data_hash(txs: &[Vec<u8>]) -> Hash {
+    let leaves: Vec<_> = txs.iter().map(Sha256::digest).collect();
+    Hash::Sha256(merkle::simple_hash_from_byte_vectors::<Sha256>(&leaves))
+}
+
+// A chain of three blocks, the block at each height carrying one transaction
+// less than its height.
+fn light_blocks() -> Vec<LightBlock> {
+    let validators = [
+        Validator::new("1").voting_power(50),
+        Validator::new("2").voting_power(50),
+    ];
+    let now = Time::now();
+    let first = Header::new(&validators)
+        .chain_id("test-chain")
+        .height(1)
+        .time((now - Duration::from_secs(60)).unwrap());
+    let second = first.next();
+    let third = second.next();
+
+    [first, second, third]
+        .into_iter()
+        .zip(1..)
+        .map(|(header, height)| {
+            let header = header.data_hash(data_hash(&txs(height)));
+            let commit = Commit::new(header.clone(), 1);
+            LightBlock::from(TGLightBlockBuilder::new(header, commit).generate().unwrap())
+        })
+        .collect()
+}
+
+// The blocks of the given light blocks, of which the one at height 2 is
+// tampered with by the given function.
+fn blocks(light_blocks: &[LightBlock], tamper: impl Fn(&mut Block)) -> Vec<Block> {
+    let mut last_commit = None;
+    light_blocks
+        .iter()
+        .map(|lb| {
+            let header = lb.signed_header.header.clone();
+            let mut block = Block::new(
+                header,
+                txs(lb.height().value()),
+                Default::default(),
+                last_commit.replace(lb.signed_header.commit.clone()),
+            )
+            .unwrap();
+            if lb.height().value() == 2 {
+                tamper(&mut block);
+            }
+            block
+        })
+        .collect()
+}
+
+// Serve the given light blocks and blocks on a fake node.
+fn serve(rt: &Runtime, light_blocks: Vec<LightBlock>, blocks: Vec<Block>) -> ServerHandle {
+    let commits = light_blocks.clone();
+    let server = Server::new()
+        .on(Method::Commit, move |req: commit::Request| {
+            let lb = match req.height {
+                Some(height) => commits.iter().find(|lb| lb.height() == height),
+                None => commits.last(),
+            };
+            let lb = lb.ok_or_else(|| ResponseError::invalid_params("unknown height"))?;
+            Ok(commit::Response {
+                signed_header: lb.signed_header.clone(),
+                canonical: true,
+            })
+        })
+        .on(Method::Validators, move |req: validators::Request| {
+            let validators = light_blocks[0].validators.validators().clone();
+            let total = validators.len() as i32;
+            Ok(validators::Response::new(
+                req.height.unwrap(),
+                validators,
+                total,
+            ))
+        })
+        .on(Method::Block, move |req: block::Request| {
+            let block = blocks
+                .iter()
+                .find(|block| Some(block.header.height) == req.height)
+                .ok_or_else(|| ResponseError::invalid_params("unknown height"))?;
+            Ok(block::Response {
+                block_id: Default::default(),
+                block: block.clone(),
+            })
+        });
+
+    let _guard = rt.enter();
+    server.bind(([127, 0, 0, 1], 0).into()).unwrap()
+}
+
+fn samples(tamper: impl Fn(&mut Block)) -> Vec<Result<BlockSample, Error>> {
+    let rt = Runtime::new().unwrap();
+    let light_blocks = light_blocks();
+    let trust_options = TrustOptions::new(
+        light_blocks[0].height(),
+        light_blocks[0].signed_header.header.hash(),
+    );
+    let blocks = blocks(&light_blocks, tamper);
+    let server = serve(&rt, light_blocks, blocks);
+
+    let client = HttpClient::new(server.http_url().as_str()).unwrap();
+    let mut instance = LightClientBuilder::prod(
+        default_peer_id(),
+        client.clone(),
+        Box::new(MemoryStore::new()),
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60 * 60),
+            clock_drift: Duration::from_secs(5),
+        },
+        Some(Duration::from_secs(5)),
+    )
+    .trust_options(trust_options)
+    .unwrap()
+    .build();
+
+    let samples = verified_block_samples(
+        &mut instance,
+        &client,
+        Height::from(1_u32),
+        Height::from(3_u32),
+    )
+    .collect();
+    rt.block_on(server.shutdown()).unwrap();
+    samples
+}
+
+#[test]
+fn verified_blocks_are_sampled() {
+    let samples: Vec<_> = samples(|_| {}).into_iter().map(Result::unwrap).collect();
+
+    let heights: Vec<_> = samples.iter().map(|s| s.height.value()).collect();
+    assert_eq!(heights, [1, 2, 3]);
+    let tx_counts: Vec<_> = samples.iter().map(|s| s.tx_count).collect();
+    assert_eq!(tx_counts, [Some(0), Some(1), Some(2)]);
+    assert_eq!(samples[0].interval, None);
+    assert!(samples[1].interval.is_some());
+}
+
+#[test]
+fn tampered_transactions_are_rejected() {
+    let samples = samples(|block| block.data.push(b"forged=tx".to_vec()));
+
+    // Sampling stops at the first error.
+    assert_eq!(samples.len(), 2);
+    assert!(samples[0].is_ok());
+    assert!(matches!(
+        samples[1].as_ref().unwrap_err().detail(),
+        ErrorDetail::DataHashMismatch(e) if e.height.value() == 2
+    ));
+}
+
+#[test]
+fn unverified_header_is_rejected() {
+    // The node reports an earlier time for the block.
+    let samples =
+        samples(|block| block.header.time = (block.header.time - Duration::from_secs(1)).unwrap());
+
+    assert_eq!(samples.len(), 2);
+    assert!(matches!(
+        samples[1].as_ref().unwrap_err().detail(),
+        ErrorDetail::HeaderMismatch(e) if e.height.value() == 2
+    ));
+}
@@ -0,0 +1,142 @@
+use std::{collections::HashMap, time::Duration};
+
+use tendermint_light_client::{
+    components::{
+        io::{AtHeight, Io},
+        scheduler::{Instrumented, SchedulingStrategy},
+    },
+    light_client::LightClient,
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    tests::{MockClock, MockIo},
+    verifier::{
+        operations::VotingPowerTally,
+        options::Options,
+        types::{Height, LightBlock, Status, Time, TrustedBlockState, UntrustedBlockState},
+        ProdVerifier, Verdict, Verifier,
+    },
+};
+use tendermint_testgen::{light_block::default_peer_id, Generator, LightChain};
+
+const CHAIN_LENGTH: u64 = 16;
+
+// The blocks of the chain all have the same validators, so this verifier
+// lacks trust in those more than `MAX_SKIP` blocks above the trusted one, to
+// make the light client bisect.
+const MAX_SKIP: u64 = 4;
+
+#[derive(Default)]
+struct ShortSkipsVerifier(ProdVerifier);
+
+impl Verifier for ShortSkipsVerifier {
+    fn verify_update_header(
+        &self,
+        untrusted: UntrustedBlockState<'_>,
+        trusted: TrustedBlockState<'_>,
+        options: &Options,
+        now: Time,
+    ) -> Verdict {
+        if untrusted.height().value() - trusted.height.value() > MAX_SKIP {
+            return Verdict::NotEnoughTrust(VotingPowerTally {
+                total: 3,
+                tallied: 1,
+                trust_threshold: options.trust_threshold,
+            });
+        }
+        self.0
+            .verify_update_header(untrusted, trusted, options, now)
+    }
+
+    fn verify_misbehaviour_header(
+        &self,
+        untrusted: UntrustedBlockState<'_>,
+        trusted: TrustedBlockState<'_>,
+        options: &Options,
+        now: Time,
+    ) -> Verdict {
+        self.0
+            .verify_misbehaviour_header(untrusted, trusted, options, now)
+    }
+}
+
+fn light_blocks() -> Vec<LightBlock> {
+    LightChain::default_with_length(CHAIN_LENGTH)
+        .light_blocks
+        .iter()
+        .map(|lb| LightBlock::from(lb.generate().unwrap()))
+        .collect()
+}
+
+// Verify the last block of the chain from the first one with the given
+// strategy, and return the heights of the verified blocks along with the
+// number of intermediate blocks scheduled and fetched.
+fn verify_with(strategy: SchedulingStrategy) -> (Vec<u64>, u64, u64) {
+    let io = MockIo::new(light_blocks());
+    let mut light_store = MemoryStore::new();
+    light_store.insert(
+        io.fetch_light_block(AtHeight::At(Height::from(1_u32)))
+            .unwrap(),
+        Status::Trusted,
+    );
+    let mut state = State {
+        light_store: Box::new(light_store),
+        verification_trace: HashMap::new(),
+    };
+
+    let scheduler = Instrumented::new(strategy);
+    let stats = scheduler.stats();
+
+    let light_client = LightClient::new(
+        default_peer_id(),
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60 * 60 * 24 * 10),
+            clock_drift: Duration::from_secs(10),
+        },
+        MockClock {
+            now: tendermint_testgen::helpers::get_time(CHAIN_LENGTH + 1).unwrap(),
+        },
+        scheduler,
+        ShortSkipsVerifier::default(),
+        io,
+    );
+
+    light_client
+        .verify_to_target(Height::from(CHAIN_LENGTH as u32), &mut state)
+        .unwrap();
+
+    let mut verified: Vec<_> = state
+        .light_store
+        .all(Status::Verified)
+        .map(|lb| lb.height().value())
+        .collect();
+    verified.sort_unstable();
+
+    (verified, stats.intermediate(), stats.fetched())
+}
+
+#[test]
+fn bisecting_schedule_picks_midpoints() {
+    let (verified, intermediate, fetched) = verify_with(SchedulingStrategy::Bisecting);
+
+    assert_eq!(verified, [5, 8, 12, 16]);
+    // 9, 5, 11, 8 and 12 were scheduled.
+    assert_eq!((intermediate, fetched), (5, 5));
+}
+
+#[test]
+fn sequential_schedule_verifies_every_block() {
+    let (verified, intermediate, fetched) = verify_with(SchedulingStrategy::Sequential);
+
+    assert_eq!(verified, (2..=CHAIN_LENGTH).collect::<Vec<_>>());
+    assert_eq!((intermediate, fetched), (14, 14));
+}
+
+#[test]
+fn skipping_schedule_reuses_fetched_blocks() {
+    let (verified, intermediate, fetched) = verify_with(SchedulingStrategy::Skipping);
+
+    assert_eq!(verified, [5, 9, 13, 16]);
+    // 9, 5, 9 again and 13 were scheduled.
+    assert_eq!((intermediate, fetched), (4, 3));
+}
@@ -0,0 +1,122 @@
+use std::{collections::HashMap, num::NonZeroU64, time::Duration};
+
+use tendermint_light_client::{
+    components::{io::AtHeight, io::Io, scheduler},
+    light_client::{LightClient, Strictness},
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    tests::{MockClock, MockIo},
+    verifier::{
+        options::Options,
+        types::{Height, LightBlock, Status},
+        ProdVerifier,
+    },
+};
+use tendermint_testgen::{light_block::default_peer_id, Generator, LightChain};
+
+const CHAIN_LENGTH: u64 = 10;
+
+fn light_blocks() -> Vec<LightBlock> {
+    LightChain::default_with_length(CHAIN_LENGTH)
+        .light_blocks
+        .iter()
+        .map(|lb| LightBlock::from(lb.generate().unwrap()))
+        .collect()
+}
+
+// A light client trusting the block at height 1.
+fn make(strictness: Strictness) -> (LightClient, State) {
+    let io = MockIo::new(light_blocks());
+    let mut light_store = MemoryStore::new();
+    light_store.insert(
+        io.fetch_light_block(AtHeight::At(Height::from(1_u32)))
+            .unwrap(),
+        Status::Trusted,
+    );
+    let state = State {
+        light_store: Box::new(light_store),
+        verification_trace: HashMap::new(),
+    };
+
+    let light_client = LightClient::new(
+        default_peer_id(),
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60 * 60 * 24 * 10),
+            clock_drift: Duration::from_secs(10),
+        },
+        MockClock {
+            now: tendermint_testgen::helpers::get_time(CHAIN_LENGTH + 1).unwrap(),
+        },
+        scheduler::basic_bisecting_schedule,
+        ProdVerifier::default(),
+        io,
+    )
+    .with_strictness(strictness);
+
+    (light_client, state)
+}
+
+fn verified_heights(state: &State) -> Vec<u64> {
+    let mut heights: Vec<_> = state
+        .light_store
+        .all(Status::Verified)
+        .map(|lb| lb.height().value())
+        .collect();
+    heights.sort_unstable();
+    heights
+}
+
+#[test]
+fn skipping_verification_goes_straight_to_the_target() {
+    let (light_client, mut state) = make(Strictness::Skipping);
+
+    light_client
+        .verify_to_target(Height::from(10_u32), &mut state)
+        .unwrap();
+
+    assert_eq!(verified_heights(&state), [10]);
+}
+
+#[test]
+fn checkpoints_are_verified_on_the_way() {
+    let (light_client, mut state) = make(Strictness::Checkpoints(NonZeroU64::new(3).unwrap()));
+
+    light_client
+        .verify_to_target(Height::from(10_u32), &mut state)
+        .unwrap();
+
+    assert_eq!(verified_heights(&state), [4, 7, 10]);
+}
+
+#[test]
+fn sequential_verification_verifies_every_block() {
+    let (light_client, mut state) = make(Strictness::SEQUENTIAL);
+
+    light_client
+        .verify_to_target(Height::from(10_u32), &mut state)
+        .unwrap();
+
+    assert_eq!(verified_heights(&state), (2..=10).collect::<Vec<_>>());
+}
+
+#[test]
+fn batches_must_include_the_checkpoints() {
+    let (light_client, mut state) = make(Strictness::Checkpoints(NonZeroU64::new(3).unwrap()));
+    let light_blocks = light_blocks();
+    let batch = |heights: &[usize]| -> Vec<LightBlock> {
+        heights
+            .iter()
+            .map(|h| light_blocks[h - 1].clone())
+            .collect()
+    };
+
+    light_client
+        .verify_batch(batch(&[5, 10]), &mut state)
+        .unwrap_err();
+
+    light_client
+        .verify_batch(batch(&[4, 7, 10]), &mut state)
+        .unwrap();
+    assert_eq!(verified_heights(&state), [4, 7, 10]);
+}
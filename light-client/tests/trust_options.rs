@@ -0,0 +1,195 @@
+use std::{sync::Arc, time::Duration};
+
+use tendermint::{crypto::default::Sha256, AppHash, Hash};
+use tendermint_light_client::{
+    builder::{error::ErrorDetail, LightClientBuilder, TrustOptions},
+    components::{io::AtHeight, scheduler},
+    store::memory::MemoryStore,
+    tests::{MockClock, MockIo},
+    verifier::{
+        operations::{HeaderHasher, ProvidedHeaderHasher},
+        options::Options,
+        predicates::ProdPredicates,
+        types::{Header, Height, LightBlock},
+        ProdVerifier,
+    },
+};
+use tendermint_testgen::{Generator, LightChain};
+
+fn light_blocks() -> Vec<LightBlock> {
+    LightChain::default_with_length(4)
+        .light_blocks
+        .iter()
+        .map(|lb| LightBlock::from(lb.generate().unwrap()))
+        .collect()
+}
+
+// A node serving a fork at height 3.
+fn forked_source() -> MockIo {
+    let mut light_blocks = light_blocks();
+    light_blocks[2].signed_header.header.app_hash = AppHash::try_from(vec![0xFF; 32]).unwrap();
+    MockIo::new(light_blocks)
+}
+
+#[test]
+fn agreed_trust_options_are_confirmed() {
+    let sources = vec![MockIo::new(light_blocks()), MockIo::new(light_blocks())];
+    let expected = light_blocks()[2].signed_header.header.hash();
+
+    let mut confirmed = None;
+    let trust_options = TrustOptions::from_sources_with_confirmation(
+        AtHeight::At(Height::from(3_u32)),
+        &sources,
+        &ProvidedHeaderHasher::<Sha256>::default(),
+        |trust_options| {
+            confirmed = Some(trust_options.clone());
+            true
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        trust_options,
+        TrustOptions::new(Height::from(3_u32), expected)
+    );
+    assert_eq!(confirmed, Some(trust_options));
+}
+
+#[test]
+fn highest_height_of_first_source_is_used() {
+    let sources = vec![MockIo::new(light_blocks()), MockIo::new(light_blocks())];
+
+    let trust_options = TrustOptions::from_sources_with_confirmation(
+        AtHeight::Highest,
+        &sources,
+        &ProvidedHeaderHasher::<Sha256>::default(),
+        |_| true,
+    )
+    .unwrap();
+
+    assert_eq!(trust_options.height, Height::from(4_u32));
+}
+
+#[test]
+fn conflicting_sources_are_rejected() {
+    let sources = vec![MockIo::new(light_blocks()), forked_source()];
+
+    let e = TrustOptions::from_sources_with_confirmation(
+        AtHeight::At(Height::from(3_u32)),
+        &sources,
+        &ProvidedHeaderHasher::<Sha256>::default(),
+        |_| panic!("conflicting trust options must not be confirmed"),
+    )
+    .unwrap_err();
+    assert!(matches!(e.detail(), ErrorDetail::ConflictingSources(e) if e.height.value() == 3));
+
+    // The sources agree on the other heights.
+    TrustOptions::from_sources_with_confirmation(
+        AtHeight::At(Height::from(2_u32)),
+        &sources,
+        &ProvidedHeaderHasher::<Sha256>::default(),
+        |_| true,
+    )
+    .unwrap();
+}
+
+#[test]
+fn unconfirmed_trust_options_are_rejected() {
+    let sources = vec![MockIo::new(light_blocks()), MockIo::new(light_blocks())];
+
+    let e = TrustOptions::from_sources_with_confirmation(
+        AtHeight::At(Height::from(3_u32)),
+        &sources,
+        &ProvidedHeaderHasher::<Sha256>::default(),
+        |_| false,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        e.detail(),
+        ErrorDetail::TrustOptionsNotConfirmed(_)
+    ));
+}
+
+#[test]
+fn single_source_is_not_enough() {
+    let sources = vec![MockIo::new(light_blocks())];
+
+    let e = TrustOptions::from_sources_with_confirmation(
+        AtHeight::At(Height::from(3_u32)),
+        &sources,
+        &ProvidedHeaderHasher::<Sha256>::default(),
+        |_| true,
+    )
+    .unwrap_err();
+    assert!(matches!(e.detail(), ErrorDetail::NotEnoughSources(e) if e.count == 1));
+}
+
+/// A chain leaving the evidence hash out of the hash of its headers.
+struct WithoutEvidenceHash;
+
+impl HeaderHasher for WithoutEvidenceHash {
+    fn hash(&self, header: &Header) -> Hash {
+        Header {
+            evidence_hash: None,
+            ..header.clone()
+        }
+        .hash()
+    }
+}
+
+#[test]
+fn builder_hashes_trust_options_with_its_header_hasher() {
+    let light_blocks: Vec<_> = light_blocks()
+        .into_iter()
+        .map(|mut lb| {
+            lb.signed_header.header.evidence_hash = Some(Hash::Sha256([1; 32]));
+            lb
+        })
+        .collect();
+    let sources = vec![
+        MockIo::new(light_blocks.clone()),
+        MockIo::new(light_blocks.clone()),
+    ];
+    let header_hasher = Arc::new(WithoutEvidenceHash);
+    let expected = header_hasher.hash(&light_blocks[2].signed_header.header);
+    assert_ne!(expected, light_blocks[2].signed_header.header.hash());
+
+    let mut confirmed = None;
+    let builder = LightClientBuilder::custom(
+        light_blocks[0].provider,
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60 * 60),
+            clock_drift: Duration::from_secs(10),
+        },
+        Box::new(MemoryStore::new()),
+        Box::new(MockIo::new(light_blocks.clone())),
+        Box::new(MockClock {
+            now: light_blocks[3].time(),
+        }),
+        Box::new(ProdVerifier::default().with_header_hasher(header_hasher.clone())),
+        Box::new(scheduler::basic_bisecting_schedule),
+        Box::new(ProdPredicates),
+    )
+    .header_hasher(header_hasher);
+
+    let instance = builder
+        .trust_sources_with_confirmation(
+            AtHeight::At(Height::from(3_u32)),
+            &sources,
+            |trust_options| {
+                confirmed = Some(trust_options.clone());
+                true
+            },
+        )
+        .unwrap()
+        .build();
+    assert_eq!(
+        confirmed,
+        Some(TrustOptions::new(Height::from(3_u32), expected))
+    );
+    assert_eq!(
+        instance.latest_trusted().map(|lb| lb.height().value()),
+        Some(3)
+    );
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "rust-crypto")]
+
+use std::{collections::HashMap, time::Duration};
+
+use tendermint_light_client::{
+    builder::TrustOptions,
+    components::{io::AtHeight, io::Io, scheduler},
+    errors::ErrorDetail,
+    light_client::{ChainUpgrade, LightClient},
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    tests::{MockClock, MockIo},
+    verifier::{
+        options::Options,
+        types::{Height, LightBlock, Status},
+        ProdVerifier,
+    },
+};
+use tendermint_testgen::{
+    helpers::get_time,
+    light_block::{default_peer_id, LightBlock as TestgenLightBlock},
+    Generator,
+};
+
+// The height of the first block of the successor chain.
+const UPGRADE_HEIGHT: u64 = 6;
+
+fn light_block(chain_id: &str, height: u64) -> LightBlock {
+    LightBlock::from(
+        TestgenLightBlock::new_default_with_time_and_chain_id(
+            chain_id.to_owned(),
+            get_time(height).unwrap(),
+            height,
+        )
+        .generate()
+        .unwrap(),
+    )
+}
+
+// A chain whose blocks from `UPGRADE_HEIGHT` on have the given chain id.
+fn light_blocks(successor: &str) -> Vec<LightBlock> {
+    (1..=10)
+        .map(|height| {
+            let chain_id = if height < UPGRADE_HEIGHT {
+                "chain-1"
+            } else {
+                successor
+            };
+            light_block(chain_id, height)
+        })
+        .collect()
+}
+
+// A light client trusting the block at height 1.
+fn make(light_blocks: Vec<LightBlock>) -> (LightClient, State) {
+    let io = MockIo::new(light_blocks);
+    let mut light_store = MemoryStore::new();
+    light_store.insert(
+        io.fetch_light_block(AtHeight::At(Height::from(1_u32)))
+            .unwrap(),
+        Status::Trusted,
+    );
+    let state = State {
+        light_store: Box::new(light_store),
+        verification_trace: HashMap::new(),
+    };
+
+    let light_client = LightClient::new(
+        default_peer_id(),
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60 * 60),
+            clock_drift: Duration::from_secs(10),
+        },
+        MockClock {
+            now: get_time(11).unwrap(),
+        },
+        scheduler::basic_bisecting_schedule,
+        ProdVerifier::default(),
+        io,
+    );
+
+    (light_client, state)
+}
+
+fn chain_upgrade(hash_of: &LightBlock) -> ChainUpgrade {
+    ChainUpgrade {
+        chain_id: "chain-2".parse().unwrap(),
+        trust_options: TrustOptions::new(
+            Height::from(UPGRADE_HEIGHT as u32),
+            hash_of.signed_header.header.hash(),
+        ),
+    }
+}
+
+#[test]
+fn unrelated_chain_id_is_a_mismatch() {
+    let (light_client, mut state) = make(light_blocks("other-chain"));
+
+    let e = light_client
+        .verify_to_target(Height::from(10_u32), &mut state)
+        .unwrap_err();
+
+    match e.detail() {
+        ErrorDetail::ChainIdMismatch(e) => {
+            assert_eq!(e.chain_id.as_str(), "other-chain");
+            assert_eq!(e.expected.as_str(), "chain-1");
+        },
+        e => panic!("unexpected error: {e:?}"),
+    }
+    // The block is not marked as failed, as it is not invalid.
+    assert!(state.light_store.all(Status::Failed).next().is_none());
+}
+
+#[test]
+fn revision_bump_is_an_upgrade() {
+    let (light_client, mut state) = make(light_blocks("chain-2"));
+
+    let e = light_client
+        .verify_to_target(Height::from(10_u32), &mut state)
+        .unwrap_err();
+
+    match e.detail() {
+        ErrorDetail::UpgradeDetected(e) => {
+            assert_eq!(e.height.value(), 10);
+            assert_eq!(e.chain_id.as_str(), "chain-2");
+            assert_eq!(e.previous.as_str(), "chain-1");
+        },
+        e => panic!("unexpected error: {e:?}"),
+    }
+}
+
+#[test]
+fn configured_upgrade_is_followed() {
+    let light_blocks = light_blocks("chain-2");
+    let upgrade = chain_upgrade(&light_blocks[UPGRADE_HEIGHT as usize - 1]);
+    let (light_client, mut state) = make(light_blocks);
+    let light_client = light_client.with_chain_upgrade(upgrade);
+
+    let verified = light_client
+        .verify_to_target(Height::from(10_u32), &mut state)
+        .unwrap();
+
+    assert_eq!(verified.signed_header.header.chain_id.as_str(), "chain-2");
+    let trusted: Vec<_> = state
+        .light_store
+        .all(Status::Trusted)
+        .map(|lb| lb.height().value())
+        .collect();
+    assert_eq!(trusted, [1, UPGRADE_HEIGHT]);
+}
+
+#[test]
+fn upgrade_anchor_must_match_trust_options() {
+    let light_blocks = light_blocks("chain-2");
+    // The hash of another block of the successor chain.
+    let upgrade = chain_upgrade(&light_blocks[7]);
+    let (light_client, mut state) = make(light_blocks);
+    let light_client = light_client.with_chain_upgrade(upgrade);
+
+    let e = light_client
+        .verify_to_target(Height::from(10_u32), &mut state)
+        .unwrap_err();
+
+    assert!(matches!(
+        e.detail(),
+        ErrorDetail::InvalidUpgradeAnchor(e) if e.height.value() == UPGRADE_HEIGHT
+    ));
+    assert!(state
+        .light_store
+        .get(Height::from(UPGRADE_HEIGHT as u32), Status::Trusted)
+        .is_none());
+}
@@ -2,14 +2,17 @@
 
 use std::{
     cmp,
+    collections::HashMap,
     convert::{TryFrom, TryInto},
+    fmt,
     io::{self, Read, Write},
     marker::{Send, Sync},
     slice,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
+    time::Instant,
 };
 
 use chacha20poly1305::{
@@ -19,6 +22,7 @@ use chacha20poly1305::{
 use merlin::Transcript;
 use rand_core::OsRng;
 use subtle::ConstantTimeEq;
+use tendermint::secret::Secret;
 use tendermint_proto::v0_38 as proto;
 use tendermint_std_ext::TryClone;
 use x25519_dalek::{EphemeralSecret, PublicKey as EphemeralPublic};
@@ -29,7 +33,7 @@ pub use self::{
     protocol::Version,
     public_key::PublicKey,
 };
-use crate::error::Error;
+use crate::{error::Error, transport::ConnectionStats};
 
 #[cfg(feature = "amino")]
 mod amino_types;
@@ -73,6 +77,39 @@ pub struct AwaitingAuthSig {
     local_signature: ed25519_consensus::Signature,
 }
 
+impl<S: fmt::Debug> fmt::Debug for Handshake<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handshake")
+            .field("protocol_version", &self.protocol_version)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl fmt::Debug for AwaitingEphKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwaitingEphKey")
+            .field("local_privkey", &Secret::new(&self.local_privkey))
+            .field(
+                "local_eph_privkey",
+                &self.local_eph_privkey.as_ref().map(Secret::new),
+            )
+            .finish()
+    }
+}
+
+impl fmt::Debug for AwaitingAuthSig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwaitingAuthSig")
+            .field("sc_mac", &Secret::new(&self.sc_mac))
+            .field("kdf", &self.kdf)
+            .field("recv_cipher", &Secret::new(&self.recv_cipher))
+            .field("send_cipher", &Secret::new(&self.send_cipher))
+            .field("local_signature", &self.local_signature)
+            .finish()
+    }
+}
+
 #[allow(clippy::use_self)]
 impl Handshake<AwaitingEphKey> {
     /// Initiate a handshake.
@@ -108,7 +145,9 @@ impl Handshake<AwaitingEphKey> {
         &mut self,
         remote_eph_pubkey: EphemeralPublic,
     ) -> Result<Handshake<AwaitingAuthSig>, Error> {
-        let Some(local_eph_privkey) = self.state.local_eph_privkey.take() else { return Err(Error::missing_secret()) };
+        let Some(local_eph_privkey) = self.state.local_eph_privkey.take() else {
+            return Err(Error::missing_secret());
+        };
         let local_eph_pubkey = EphemeralPublic::from(&local_eph_privkey);
 
         // Compute common shared secret.
@@ -228,6 +267,42 @@ macro_rules! checked_io {
     }};
 }
 
+// Counters of the traffic over a `SecretConnection`, shared by its halves.
+struct Traffic {
+    established_at: Instant,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+}
+
+impl Traffic {
+    fn new() -> Self {
+        Self {
+            established_at: Instant::now(),
+            bytes_sent: AtomicU64::new(0),
+            bytes_received: AtomicU64::new(0),
+        }
+    }
+
+    fn sent(&self, n: usize) -> usize {
+        self.bytes_sent.fetch_add(n as u64, Ordering::Relaxed);
+        n
+    }
+
+    fn received(&self, n: usize) -> usize {
+        self.bytes_received.fetch_add(n as u64, Ordering::Relaxed);
+        n
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        ConnectionStats {
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            streams: HashMap::new(),
+            age: self.established_at.elapsed(),
+        }
+    }
+}
+
 /// Encrypted connection between peers in a Tendermint network.
 ///
 /// ## Connection integrity and failures
@@ -251,6 +326,12 @@ macro_rules! checked_io {
 ///
 /// When reading data, data smaller than [`DATA_MAX_SIZE`] is read atomically.
 ///
+/// ## Statistics
+///
+/// The number of bytes of data sent and received are counted from the start of the handshake,
+/// excluding the framing and encryption overhead, and are shared by the halves of a split
+/// connection. See [`SecretConnection::stats`].
+///
 /// [RFC 8439]: https://www.rfc-editor.org/rfc/rfc8439.html
 pub struct SecretConnection<IoHandler> {
     io_handler: IoHandler,
@@ -259,6 +340,27 @@ pub struct SecretConnection<IoHandler> {
     send_state: SendState,
     recv_state: ReceiveState,
     terminate: Arc<AtomicBool>,
+    traffic: Arc<Traffic>,
+}
+
+impl<IoHandler> fmt::Debug for SecretConnection<IoHandler> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The cipher states are not written out, as they hold the session keys.
+        f.debug_struct("SecretConnection")
+            .field("protocol_version", &self.protocol_version)
+            .field("remote_pubkey", &self.remote_pubkey)
+            .field("stats", &self.stats())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<IoHandler> SecretConnection<IoHandler> {
+    /// Returns statistics about the traffic over the connection. As a secret connection is not
+    /// aware of the streams multiplexed over it, it does not keep track of them.
+    #[must_use]
+    pub fn stats(&self) -> ConnectionStats {
+        self.traffic.stats()
+    }
 }
 
 impl<IoHandler: Read + Write + Send + Sync> SecretConnection<IoHandler> {
@@ -275,12 +377,40 @@ impl<IoHandler: Read + Write + Send + Sync> SecretConnection<IoHandler> {
     /// * if sharing of the signature fails
     /// * if receiving the signature fails
     pub fn new(
+        io_handler: IoHandler,
+        local_privkey: ed25519_consensus::SigningKey,
+        protocol_version: Version,
+    ) -> Result<Self, Error> {
+        let local_pubkey = PublicKey::from(&local_privkey);
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "secret_connection_handshake",
+            local_peer_id = %local_pubkey.peer_id(),
+            ?protocol_version,
+        )
+        .entered();
+
+        let result = Self::handshake(io_handler, local_privkey, local_pubkey, protocol_version);
+
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(sc) => tracing::debug!(
+                remote_peer_id = %sc.remote_pubkey().peer_id(),
+                "secret connection established"
+            ),
+            Err(e) => tracing::warn!(error = %e, "secret connection handshake failed"),
+        }
+
+        result
+    }
+
+    fn handshake(
         mut io_handler: IoHandler,
         local_privkey: ed25519_consensus::SigningKey,
+        local_pubkey: PublicKey,
         protocol_version: Version,
     ) -> Result<Self, Error> {
         // Start a handshake process.
-        let local_pubkey = PublicKey::from(&local_privkey);
         let (mut h, local_eph_pubkey) = Handshake::new(local_privkey, protocol_version);
 
         // Write local ephemeral pubkey and receive one too.
@@ -304,6 +434,7 @@ impl<IoHandler: Read + Write + Send + Sync> SecretConnection<IoHandler> {
                 buffer: vec![],
             },
             terminate: Arc::new(AtomicBool::new(false)),
+            traffic: Arc::new(Traffic::new()),
         };
 
         // Share each other's pubkey & challenge signature.
@@ -349,12 +480,14 @@ where
                 remote_pubkey,
                 state: self.send_state,
                 terminate: self.terminate.clone(),
+                traffic: self.traffic.clone(),
             },
             Receiver {
                 io_handler: self.io_handler,
                 remote_pubkey,
                 state: self.recv_state,
                 terminate: self.terminate,
+                traffic: self.traffic,
             },
         ))
     }
@@ -365,6 +498,7 @@ impl<IoHandler: Read> Read for SecretConnection<IoHandler> {
         checked_io!(
             self.terminate,
             read_and_decrypt(&mut self.io_handler, &mut self.recv_state, data)
+                .map(|n| self.traffic.received(n))
         )
     }
 }
@@ -374,6 +508,7 @@ impl<IoHandler: Write> Write for SecretConnection<IoHandler> {
         checked_io!(
             self.terminate,
             encrypt_and_write(&mut self.io_handler, &mut self.send_state, data)
+                .map(|n| self.traffic.sent(n))
         )
     }
 
@@ -401,6 +536,16 @@ pub struct Sender<IoHandler> {
     remote_pubkey: PublicKey,
     state: SendState,
     terminate: Arc<AtomicBool>,
+    traffic: Arc<Traffic>,
+}
+
+impl<IoHandler> fmt::Debug for Sender<IoHandler> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("remote_pubkey", &self.remote_pubkey)
+            .field("stats", &self.stats())
+            .finish_non_exhaustive()
+    }
 }
 
 impl<IoHandler> Sender<IoHandler> {
@@ -408,6 +553,13 @@ impl<IoHandler> Sender<IoHandler> {
     pub const fn remote_pubkey(&self) -> PublicKey {
         self.remote_pubkey
     }
+
+    /// Returns statistics about the traffic over the connection, including the traffic of the
+    /// other half.
+    #[must_use]
+    pub fn stats(&self) -> ConnectionStats {
+        self.traffic.stats()
+    }
 }
 
 impl<IoHandler: Write> Write for Sender<IoHandler> {
@@ -415,6 +567,7 @@ impl<IoHandler: Write> Write for Sender<IoHandler> {
         checked_io!(
             self.terminate,
             encrypt_and_write(&mut self.io_handler, &mut self.state, buf)
+                .map(|n| self.traffic.sent(n))
         )
     }
 
@@ -429,6 +582,16 @@ pub struct Receiver<IoHandler> {
     remote_pubkey: PublicKey,
     state: ReceiveState,
     terminate: Arc<AtomicBool>,
+    traffic: Arc<Traffic>,
+}
+
+impl<IoHandler> fmt::Debug for Receiver<IoHandler> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("remote_pubkey", &self.remote_pubkey)
+            .field("stats", &self.stats())
+            .finish_non_exhaustive()
+    }
 }
 
 impl<IoHandler> Receiver<IoHandler> {
@@ -436,6 +599,13 @@ impl<IoHandler> Receiver<IoHandler> {
     pub const fn remote_pubkey(&self) -> PublicKey {
         self.remote_pubkey
     }
+
+    /// Returns statistics about the traffic over the connection, including the traffic of the
+    /// other half.
+    #[must_use]
+    pub fn stats(&self) -> ConnectionStats {
+        self.traffic.stats()
+    }
 }
 
 impl<IoHandler: Read> Read for Receiver<IoHandler> {
@@ -443,6 +613,7 @@ impl<IoHandler: Read> Read for Receiver<IoHandler> {
         checked_io!(
             self.terminate,
             read_and_decrypt(&mut self.io_handler, &mut self.state, buf)
+                .map(|n| self.traffic.received(n))
         )
     }
 }
@@ -1,5 +1,8 @@
+use std::fmt;
+
 use hkdf::Hkdf;
 use sha2::Sha256;
+use tendermint::secret::Secret;
 use zeroize::Zeroize;
 
 /// "Info" parameter to HKDF we use to personalize the derivation
@@ -48,6 +51,16 @@ impl Kdf {
     }
 }
 
+impl fmt::Debug for Kdf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Kdf")
+            .field("recv_secret", &Secret::new(&self.recv_secret))
+            .field("send_secret", &Secret::new(&self.send_secret))
+            .field("challenge", &Secret::new(&self.challenge))
+            .finish()
+    }
+}
+
 impl Drop for Kdf {
     fn drop(&mut self) {
         self.recv_secret.zeroize();
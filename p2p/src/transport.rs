@@ -1,7 +1,11 @@
 //! Abstractions that describe types which support the physical transport - i.e. connection
 //! management - used in the p2p stack.
 
-use std::net::{SocketAddr, ToSocketAddrs};
+use std::{
+    collections::HashMap,
+    net::{SocketAddr, ToSocketAddrs},
+    time::Duration,
+};
 
 use eyre::Result;
 use tendermint::{node, public_key::PublicKey};
@@ -37,12 +41,35 @@ where
 }
 
 /// Known list of typed streams.
-#[derive(Clone, Copy, Hash, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
 pub enum StreamId {
     /// Stream to exchange message concerning Peer Exchange.
     Pex,
 }
 
+/// Statistics about the traffic over a [`Connection`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    /// Number of bytes sent to the peer.
+    pub bytes_sent: u64,
+    /// Number of bytes received from the peer.
+    pub bytes_received: u64,
+    /// Statistics of the streams opened over the connection, for connections which keep track of
+    /// the messages exchanged over each stream.
+    pub streams: HashMap<StreamId, StreamStats>,
+    /// How long the connection has been established for.
+    pub age: Duration,
+}
+
+/// Statistics about the messages exchanged over a stream.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StreamStats {
+    /// Number of messages sent to the peer.
+    pub messages_sent: u64,
+    /// Number of messages received from the peer.
+    pub messages_received: u64,
+}
+
 /// Envelope to trace the original direction of an established connection.
 pub enum Direction<Conn> {
     /// A peer that connected to the local node.
@@ -51,6 +78,23 @@ pub enum Direction<Conn> {
     Outgoing(Conn),
 }
 
+/// Priority of a message sent over a multiplexed connection, deciding which of the messages
+/// waiting to be sent goes first.
+///
+/// For example, consensus votes are sent with [`SendPriority::Urgent`] to preempt the gossip of
+/// block parts, sent with [`SendPriority::Background`]. Connections must not starve the messages
+/// of lower priorities, but still send them once they have been held back for a while.
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum SendPriority {
+    /// Messages which must be sent as soon as possible.
+    Urgent,
+    /// Messages of the regular traffic.
+    #[default]
+    Normal,
+    /// Bulk transfers which can be delayed.
+    Background,
+}
+
 /// Trait that describes the send end of a stream.
 pub trait StreamSend {
     /// Sends the message to the peer over the open stream. `msg` should be a valid and properly
@@ -62,6 +106,20 @@ pub trait StreamSend {
     /// * If the stream is closed.
     /// * If the peer is gone
     fn send<B: AsRef<[u8]>>(msg: B) -> Result<()>;
+
+    /// Sends the message to the peer over the open stream with the given priority, for
+    /// multiplexed connections which schedule the messages of their streams by priority. Other
+    /// connections send the message as with [`StreamSend::send`].
+    ///
+    /// # Errors
+    ///
+    /// * If the underlying I/O operations fail.
+    /// * If the stream is closed.
+    /// * If the peer is gone
+    fn send_with_priority<B: AsRef<[u8]>>(msg: B, priority: SendPriority) -> Result<()> {
+        let _ = priority;
+        Self::send(msg)
+    }
 }
 
 /// Trait which describes the core concept of a connection between two peers established by
@@ -85,6 +143,11 @@ pub trait Connection: Send {
     fn close(&self) -> Result<()>;
     /// Returns the local address for the connection.
     fn local_addr(&self) -> SocketAddr;
+    /// Returns statistics about the traffic over the connection, or `None` if the connection
+    /// does not keep track of them.
+    fn stats(&self) -> Option<ConnectionStats> {
+        None
+    }
     /// Opens a new bi-bidirectional stream for the given [`StreamId`].
     ///
     /// # Errors
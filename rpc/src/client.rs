@@ -3,30 +3,174 @@
 mod compat;
 pub use compat::CompatMode;
 
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+mod broadcast_guard;
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+pub use broadcast_guard::{
+    cosmos_sdk_sequence_mismatch, BroadcastGuard, DuplicatePolicy, SequenceConflictDetector,
+    DEFAULT_DUPLICATE_WINDOW,
+};
+
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+mod failover;
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+pub use failover::{FailoverClient, DEFAULT_FAILOVER_COOLDOWN};
+
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+mod health_monitor;
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+pub use health_monitor::{
+    HealthIssue, HealthMonitor, HealthReport, HealthState, HealthTransition,
+    DEFAULT_HEALTH_POLL_INTERVAL, DEFAULT_MAX_BLOCK_AGE, DEFAULT_MIN_PEERS,
+};
+
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "metrics")]
+pub use metrics::{ClientMetrics, MeteredClient, DEFAULT_METRICS_NAMESPACE};
+
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+mod pipeline;
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+pub use pipeline::{
+    BlockPipeline, Checkpoint, FetchedBlock, PipelineItem, DEFAULT_CHECKPOINT_INTERVAL,
+    DEFAULT_PIPELINE_CONCURRENCY,
+};
+
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+mod rate_limit;
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+pub use rate_limit::{LimitPolicy, RateLimit, RateLimitedClient, RateLimiter};
+
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+mod retry;
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+pub use retry::{
+    RetryingClient, DEFAULT_INITIAL_BACKOFF, DEFAULT_MAX_BACKOFF, DEFAULT_MAX_RETRIES,
+};
+
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+mod search;
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+pub use search::SEARCH_PER_PAGE;
+
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
 mod subscription;
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
-pub use subscription::{Subscription, SubscriptionClient};
+pub use subscription::{
+    OverflowPolicy, Subscription, SubscriptionBuffer, SubscriptionClient,
+    DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY,
+};
 
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
 pub mod sync;
 
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+mod tx_commit;
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+pub use tx_commit::{broadcast_tx_and_wait, TxCommitOutcome, DEFAULT_TX_POLL_INTERVAL};
+
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+mod watchdog;
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+pub use watchdog::{Stall, StallWatchdog, WatchdogEvent, DEFAULT_STALL_WINDOW};
+
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
 mod transport;
 
-#[cfg(feature = "http-client")]
-pub use transport::http::{HttpClient, HttpClientUrl};
-#[cfg(feature = "websocket-client")]
+#[cfg(all(feature = "grpc", not(target_arch = "wasm32")))]
+pub use transport::grpc::{BroadcastTxResponse, GrpcClient};
+#[cfg(all(feature = "http-client", not(target_arch = "wasm32")))]
+pub use transport::http::{Batch, HttpClient, HttpClientUrl};
+#[cfg(all(feature = "http-client", target_arch = "wasm32"))]
+pub use transport::wasm::http::{HttpClient, HttpClientUrl};
+#[cfg(all(feature = "websocket-client", target_arch = "wasm32"))]
+pub use transport::wasm::websocket::{
+    DriverExit, WebSocketClient, WebSocketClientDriver, WebSocketClientUrl,
+};
+#[cfg(all(feature = "websocket-client", not(target_arch = "wasm32")))]
 pub use transport::websocket::{
-    WebSocketClient, WebSocketClientDriver, WebSocketClientUrl, WebSocketConfig,
+    DriverExit, WebSocketClient, WebSocketClientDriver, WebSocketClientUrl, WebSocketConfig,
+    WebSocketService,
 };
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+pub use transport::Authorization;
 
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
-pub use transport::mock::{MockClient, MockRequestMatcher, MockRequestMethodMatcher};
+pub use transport::mock::{
+    MockCall, MockClient, MockRequestMatcher, MockRequestMethodMatcher, MockRequestScript,
+};
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+pub use transport::proxy::{Proxy, ProxyProtocol};
+#[cfg(all(
+    any(feature = "http-client", feature = "websocket-client"),
+    not(target_arch = "wasm32")
+))]
+pub use transport::recording::{Interaction, Recorder, ReplayClient};
 
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+use alloc::collections::VecDeque;
 use core::fmt;
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+use core::ops::RangeInclusive;
 
 use async_trait::async_trait;
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+use futures::stream::{self, BoxStream, StreamExt};
 use serde::{de::DeserializeOwned, Serialize};
 use tendermint::{abci, block::Height, evidence::Evidence, Genesis, Hash};
 
@@ -35,6 +179,7 @@ use crate::{
     paging::Paging,
     prelude::*,
     query::Query,
+    request::RawRequest,
     Error, Order, SimpleRequest,
 };
 
@@ -98,6 +243,11 @@ pub trait Client {
         self.perform(header::Request::new(height.into())).await
     }
 
+    /// `/header`: get the latest block header.
+    async fn latest_header(&self) -> Result<header::Response, Error> {
+        self.perform(header::Request::default()).await
+    }
+
     /// `/header_by_hash`: get block by hash.
     async fn header_by_hash(
         &self,
@@ -121,6 +271,9 @@ pub trait Client {
     }
 
     /// `/block_search`: search for blocks by BeginBlock and EndBlock events.
+    ///
+    /// The `query` can also match on the `block.height` key, for example to
+    /// restrict the search to a range of heights.
     async fn block_search(
         &self,
         query: Query,
@@ -132,18 +285,117 @@ pub trait Client {
             .await
     }
 
+    /// `/block_search`: search for all the blocks matching the query,
+    /// walking through the pages of results as the returned stream is
+    /// consumed.
+    ///
+    /// [`SEARCH_PER_PAGE`] blocks are requested per page. Blocks matching the
+    /// query while walking through the pages are not guaranteed to be
+    /// returned, but no block is returned twice.
+    #[cfg(any(feature = "http-client", feature = "websocket-client"))]
+    fn block_search_all(
+        &self,
+        query: Query,
+        order: Order,
+    ) -> BoxStream<'_, Result<block::Response, Error>>
+    where
+        Self: Sync + Sized,
+    {
+        search::paginate(
+            move |page, per_page| {
+                let response = self.block_search(query.clone(), page, per_page, order.clone());
+                async move {
+                    let response = response.await?;
+                    Ok((response.blocks, response.total_count))
+                }
+            },
+            |block| block.block.header.height,
+        )
+    }
+
     /// `/blockchain`: get block headers for `min` <= `height` <= `max`.
     ///
     /// Block headers are returned in descending order (highest first).
     ///
-    /// Returns at most 20 items.
+    /// Returns at most 20 items, see [`block_metas_range`] for more.
+    ///
+    /// [`block_metas_range`]: Client::block_metas_range
     async fn blockchain<H>(&self, min: H, max: H) -> Result<blockchain::Response, Error>
     where
         H: Into<Height> + Send,
     {
-        // TODO(tarcieri): return errors for invalid params before making request?
-        self.perform(blockchain::Request::new(min.into(), max.into()))
-            .await
+        let (min, max) = (min.into(), max.into());
+        if min > max {
+            return Err(Error::invalid_params(format!(
+                "minimum height {min} is greater than maximum height {max}"
+            )));
+        }
+        self.perform(blockchain::Request::new(min, max)).await
+    }
+
+    /// Stream the metadata of the blocks of the given range of heights, in
+    /// ascending order.
+    ///
+    /// The blocks are fetched with requests to `/blockchain` for up to
+    /// [`MAX_BLOCK_METAS`](blockchain::MAX_BLOCK_METAS) blocks at a time, as
+    /// the returned stream is consumed. The stream ends with an error if the
+    /// node does not return the block at some height of the range, e.g. a
+    /// pruned block or one above the latest height, and stops at the first
+    /// error.
+    #[cfg(any(feature = "http-client", feature = "websocket-client"))]
+    fn block_metas_range<H>(
+        &self,
+        range: RangeInclusive<H>,
+    ) -> BoxStream<'_, Result<tendermint::block::Meta, Error>>
+    where
+        Self: Sync + Sized,
+        H: Into<Height>,
+    {
+        let (start, end) = range.into_inner();
+        let end: Height = end.into();
+        let state = (Some(start.into()), VecDeque::new(), None);
+        stream::unfold(
+            state,
+            move |(mut next, mut metas, mut missing)| async move {
+                if metas.is_empty() && missing.is_none() {
+                    let min = next.filter(|height| *height <= end)?;
+                    let max = Height::try_from(
+                        min.value()
+                            .saturating_add(u64::from(blockchain::MAX_BLOCK_METAS) - 1),
+                    )
+                    .map_or(end, |max| max.min(end));
+                    let mut chunk = match self.blockchain(min, max).await {
+                        Ok(response) => response.block_metas,
+                        Err(e) => return Some((Err(e), (None, metas, None))),
+                    };
+
+                    // The node returns the metas in descending order, without the
+                    // blocks it does not have.
+                    chunk.sort_by_key(|meta| meta.header.height);
+                    let mut expected = min;
+                    for meta in chunk {
+                        if meta.header.height != expected {
+                            break;
+                        }
+                        metas.push_back(meta);
+                        expected = expected.increment();
+                    }
+                    next = Some(expected);
+                    if expected <= max {
+                        missing = Some(expected);
+                    }
+                }
+                match metas.pop_front() {
+                    Some(meta) => Some((Ok(meta), (next, metas, missing))),
+                    // Report the missing block once the contiguous ones before it
+                    // were yielded.
+                    None => missing.map(|height| {
+                        (Err(Error::missing_block_meta(height)), (None, metas, None))
+                    }),
+                }
+            },
+        )
+        .boxed()
     }
 
     /// `/broadcast_tx_async`: broadcast a transaction, returning immediately.
@@ -172,6 +424,16 @@ pub trait Client {
         self.perform(broadcast::tx_commit::Request::new(tx)).await
     }
 
+    /// `/check_tx`: check a transaction against the mempool rules of the
+    /// application, returning the response from `CheckTx` without
+    /// broadcasting the transaction.
+    async fn check_tx<T>(&self, tx: T) -> Result<abci::response::CheckTx, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        self.perform(check_tx::Request::new(tx)).await
+    }
+
     /// `/commit`: get block commit at a given height.
     async fn commit<H>(&self, height: H) -> Result<commit::Response, Error>
     where
@@ -195,6 +457,12 @@ pub trait Client {
         self.perform(consensus_state::Request::new()).await
     }
 
+    /// `/dump_consensus_state`: get the full consensus state of the node and
+    /// of its peers
+    async fn dump_consensus_state(&self) -> Result<dump_consensus_state::Response, Error> {
+        self.perform(dump_consensus_state::Request::new()).await
+    }
+
     // TODO(thane): Simplify once validators endpoint removes pagination.
     /// `/validators`: get validators a given height.
     async fn validators<H>(&self, height: H, paging: Paging) -> Result<validators::Response, Error>
@@ -270,17 +538,71 @@ pub trait Client {
         Ok(self.perform(genesis::Request::default()).await?.genesis)
     }
 
+    /// `/genesis_chunked`: get the genesis file chunk by chunk.
+    ///
+    /// Nodes only serve large genesis files this way. The chunks are
+    /// requested one after the other and reassembled before the genesis
+    /// file is deserialized.
+    async fn genesis_chunked<AppState>(&self) -> Result<Genesis<AppState>, Error>
+    where
+        AppState: fmt::Debug + Serialize + DeserializeOwned + Send,
+    {
+        let mut data = Vec::new();
+        let mut chunk = 0;
+        loop {
+            let response = self.perform(genesis_chunked::Request::new(chunk)).await?;
+            if response.chunk != chunk {
+                return Err(Error::client_internal(format!(
+                    "expected genesis chunk {chunk}, but got chunk {}",
+                    response.chunk
+                )));
+            }
+            data.extend(response.data);
+            chunk += 1;
+            if chunk >= response.total {
+                break;
+            }
+        }
+        serde_json::from_slice(&data).map_err(Error::serde)
+    }
+
     /// `/net_info`: obtain information about P2P and other network connections.
     async fn net_info(&self) -> Result<net_info::Response, Error> {
         self.perform(net_info::Request).await
     }
 
+    /// `/unconfirmed_txs`: get up to `limit` of the transactions in the
+    /// mempool, or as many as the node returns by default.
+    async fn unconfirmed_txs(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<unconfirmed_txs::Response, Error> {
+        self.perform(unconfirmed_txs::Request::new(limit)).await
+    }
+
+    /// `/num_unconfirmed_txs`: get the number and total size of the
+    /// transactions in the mempool.
+    async fn num_unconfirmed_txs(&self) -> Result<num_unconfirmed_txs::Response, Error> {
+        self.perform(num_unconfirmed_txs::Request).await
+    }
+
     /// `/status`: get Tendermint status including node info, pubkey, latest
     /// block hash, app hash, block height and time.
     async fn status(&self) -> Result<status::Response, Error> {
         self.perform(status::Request).await
     }
 
+    /// Determine the compatibility mode to use with the node from the version
+    /// it reports on the `/status` endpoint.
+    ///
+    /// The response of this endpoint has the same format in all supported
+    /// versions of the protocol, so it can be queried before the compatibility
+    /// mode is settled upon.
+    async fn detect_compat_mode(&self) -> Result<CompatMode, Error> {
+        let status = self.status().await?;
+        CompatMode::from_version(status.node_info.version)
+    }
+
     /// `/broadcast_evidence`: broadcast an evidence.
     async fn broadcast_evidence(&self, e: Evidence) -> Result<evidence::Response, Error> {
         self.perform(evidence::Request::new(e)).await
@@ -300,10 +622,48 @@ pub trait Client {
         per_page: u8,
         order: Order,
     ) -> Result<tx_search::Response, Error> {
-        self.perform(tx_search::Request::new(query, prove, page, per_page, order))
+        self.tx_search_with(tx_search::Request::new(query, prove, page, per_page, order))
             .await
     }
 
+    /// `/tx_search`: search for transactions with their results, with all the
+    /// parameters of the given request, such as `match_events`.
+    async fn tx_search_with(
+        &self,
+        request: tx_search::Request,
+    ) -> Result<tx_search::Response, Error> {
+        self.perform(request).await
+    }
+
+    /// `/tx_search`: search for all the transactions matching the query,
+    /// walking through the pages of results as the returned stream is
+    /// consumed.
+    ///
+    /// [`SEARCH_PER_PAGE`] transactions are requested per page. Transactions
+    /// matching the query while walking through the pages are not
+    /// guaranteed to be returned, but no transaction is returned twice.
+    #[cfg(any(feature = "http-client", feature = "websocket-client"))]
+    fn tx_search_all(
+        &self,
+        query: Query,
+        prove: bool,
+        order: Order,
+    ) -> BoxStream<'_, Result<tx::Response, Error>>
+    where
+        Self: Sync + Sized,
+    {
+        search::paginate(
+            move |page, per_page| {
+                let response = self.tx_search(query.clone(), prove, page, per_page, order.clone());
+                async move {
+                    let response = response.await?;
+                    Ok((response.txs, response.total_count))
+                }
+            },
+            |tx| (tx.height, tx.index),
+        )
+    }
+
     #[cfg(any(feature = "http-client", feature = "websocket-client"))]
     /// Poll the `/health` endpoint until it returns a successful result or
     /// the given `timeout` has elapsed.
@@ -321,12 +681,37 @@ pub trait Client {
             }
 
             attempts_remaining -= 1;
+            #[cfg(not(target_arch = "wasm32"))]
             tokio::time::sleep(poll_interval).await;
+            #[cfg(target_arch = "wasm32")]
+            transport::wasm::sleep(poll_interval).await;
         }
 
         Ok(())
     }
 
+    /// Perform a request to a method which is not modelled by this crate,
+    /// such as a custom method of a forked node, returning the `result` of
+    /// the response as JSON.
+    ///
+    /// Typed requests to such methods can be performed with
+    /// [`Client::perform`] instead, by implementing [`SimpleRequest`] for a
+    /// type of your own with [`Method::Custom`] as its method and the name of
+    /// the method given by [`RequestMessage::method_name`].
+    ///
+    /// [`Method::Custom`]: crate::Method::Custom
+    /// [`RequestMessage::method_name`]: crate::request::RequestMessage::method_name
+    async fn perform_raw<M>(
+        &self,
+        method: M,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, Error>
+    where
+        M: Into<String> + Send,
+    {
+        self.perform(RawRequest::new(method, params)).await
+    }
+
     /// Perform a request against the RPC endpoint.
     ///
     /// This method is used by the default implementations of specific
@@ -335,3 +720,116 @@ pub trait Client {
     where
         R: SimpleRequest;
 }
+
+#[cfg(all(test, any(feature = "http-client", feature = "websocket-client")))]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{error::ErrorDetail, Response};
+
+    const BLOCKCHAIN: &str =
+        include_str!("../tests/kvstore_fixtures/v0_37/incoming/blockchain_from_1_to_10.json");
+
+    /// A node having the blocks from `base` to `latest`, and recording the
+    /// ranges of heights requested to `/blockchain`.
+    struct Node {
+        base: u64,
+        latest: u64,
+        requests: Mutex<Vec<(u64, u64)>>,
+    }
+
+    impl Node {
+        fn new(base: u64, latest: u64) -> Self {
+            Self {
+                base,
+                latest,
+                requests: Mutex::new(vec![]),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Client for Node {
+        async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+        where
+            R: SimpleRequest,
+        {
+            assert_eq!(request.method(), crate::Method::Blockchain);
+            let request: serde_json::Value = serde_json::from_str(&request.into_json()).unwrap();
+            let height = |name: &str| request["params"][name].as_str().unwrap().parse().unwrap();
+            let (min, max): (u64, u64) = (height("minHeight"), height("maxHeight"));
+            self.requests.lock().unwrap().push((min, max));
+
+            let mut response: serde_json::Value = serde_json::from_str(BLOCKCHAIN).unwrap();
+            let meta = response["result"]["block_metas"][0].clone();
+            let (min, max) = (min.max(self.base), max.min(self.latest).min(min + 19));
+            let metas: Vec<_> = (min..=max)
+                .rev()
+                .map(|height| {
+                    let mut meta = meta.clone();
+                    meta["header"]["height"] = height.to_string().into();
+                    if height == 1 {
+                        meta["header"]["last_block_id"] = serde_json::Value::Null;
+                    }
+                    meta
+                })
+                .collect();
+            response["result"]["block_metas"] = metas.into();
+            response["result"]["last_height"] = self.latest.to_string().into();
+            R::Response::from_string(response.to_string()).map(Into::into)
+        }
+    }
+
+    async fn collect(node: &Node, range: RangeInclusive<u32>) -> Vec<Result<u64, u64>> {
+        node.block_metas_range(range)
+            .map(|meta| match meta {
+                Ok(meta) => Ok(meta.header.height.value()),
+                Err(e) => match e.detail() {
+                    ErrorDetail::MissingBlockMeta(e) => Err(e.height.value()),
+                    _ => panic!("unexpected error: {e}"),
+                },
+            })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn block_metas_range_is_chunked() {
+        let node = Node::new(1, 100);
+        let heights = collect(&node, 5..=48).await;
+
+        assert_eq!(heights, (5..=48).map(Ok).collect::<Vec<_>>());
+        assert_eq!(
+            *node.requests.lock().unwrap(),
+            [(5, 24), (25, 44), (45, 48)]
+        );
+    }
+
+    #[tokio::test]
+    async fn block_metas_range_reports_missing_blocks() {
+        // Blocks above the latest height.
+        let node = Node::new(1, 25);
+        let heights = collect(&node, 1..=30).await;
+        let mut expected: Vec<_> = (1..=25).map(Ok).collect();
+        expected.push(Err(26));
+        assert_eq!(heights, expected);
+
+        // Pruned blocks.
+        let node = Node::new(3, 25);
+        assert_eq!(collect(&node, 1..=5).await, [Err(1)]);
+        assert_eq!(node.requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn block_metas_range_can_be_empty() {
+        let node = Node::new(1, 25);
+        #[allow(clippy::reversed_empty_ranges)]
+        let range = 5..=4;
+        assert!(collect(&node, range).await.is_empty());
+        assert!(node.requests.lock().unwrap().is_empty());
+
+        let err = node.blockchain(5u32, 4u32).await.unwrap_err();
+        assert!(matches!(err.detail(), ErrorDetail::InvalidParams(_)));
+    }
+}
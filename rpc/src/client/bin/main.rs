@@ -7,6 +7,7 @@ use structopt::StructOpt;
 use tendermint::Hash;
 use tendermint_rpc::{
     client::CompatMode,
+    endpoint,
     event::{self, Event, EventData},
     query::Query,
     Client, Error, HttpClient, Order, Paging, Scheme, Subscription, SubscriptionClient, Url,
@@ -17,7 +18,8 @@ use tracing::{debug, error, info, level_filters::LevelFilter, warn};
 
 /// CLI for performing simple interactions against a Tendermint node's RPC.
 ///
-/// Supports HTTP, HTTPS, WebSocket and secure WebSocket (wss://) URLs.
+/// Supports HTTP, HTTPS, WebSocket, secure WebSocket (wss://) and Unix domain
+/// socket (unix://) URLs.
 #[derive(Debug, StructOpt)]
 struct Opt {
     /// The URL of the Tendermint node's RPC endpoint.
@@ -125,6 +127,12 @@ enum ClientRequest {
         /// The transaction to broadcast.
         tx: String,
     },
+    /// Check a transaction against the mempool rules of the ABCI app,
+    /// without broadcasting it.
+    CheckTx {
+        /// The transaction to check.
+        tx: String,
+    },
     /// Get the commit for the given height.
     Commit { height: u32 },
     /// Get consensus parameters for a specific height.
@@ -134,12 +142,22 @@ enum ClientRequest {
     },
     /// Get the current consensus state.
     ConsensusState,
+    /// Dump the full consensus state of the node and of its peers.
+    DumpConsensusState,
     /// Get the node's genesis data.
     Genesis,
+    /// Get the node's genesis data in chunks, for large genesis files.
+    GenesisChunked,
+    /// Get the header of a block at a given height.
+    Header { height: u32 },
+    /// Get the header of a block by its hash.
+    HeaderByHash { hash: String },
     /// Get the node's health.
     Health,
     /// Request the latest block.
     LatestBlock,
+    /// Request the header of the latest block.
+    LatestHeader,
     /// Request the results for the latest block.
     LatestBlockResults,
     /// Get the consensus parameters for the latest block.
@@ -148,6 +166,8 @@ enum ClientRequest {
     LatestCommit,
     /// Obtain information about the P2P stack and other network connections.
     NetInfo,
+    /// Get the number and total size of the transactions in the mempool.
+    NumUnconfirmedTxs,
     /// Get Tendermint status (node info, public key, latest block hash, etc.).
     Status,
     /// Fetch a transaction by way of its hash.
@@ -172,6 +192,15 @@ enum ClientRequest {
         order: Order,
         #[structopt(long)]
         prove: bool,
+        /// Require the conditions of the query to be matched by a single event.
+        #[structopt(long)]
+        match_events: bool,
+    },
+    /// Get the transactions in the mempool.
+    UnconfirmedTxs {
+        /// The maximum number of transactions to retrieve.
+        #[structopt(long)]
+        limit: Option<u32>,
     },
     /// Get the validators at the given height.
     Validators {
@@ -211,7 +240,9 @@ async fn main() {
         },
     };
     let result = match opt.url.scheme() {
-        Scheme::Http | Scheme::Https => http_request(opt.url, proxy_url, opt.req).await,
+        Scheme::Http | Scheme::Https | Scheme::Unix => {
+            http_request(opt.url, proxy_url, opt.req).await
+        },
         Scheme::WebSocket | Scheme::SecureWebSocket => match opt.proxy_url {
             Some(_) => Err(Error::invalid_params(
                 "proxies are only supported for use with HTTP clients at present".to_string(),
@@ -385,6 +416,9 @@ where
             serde_json::to_string_pretty(&client.broadcast_tx_sync(tx).await?)
                 .map_err(Error::serde)?
         },
+        ClientRequest::CheckTx { tx } => {
+            serde_json::to_string_pretty(&client.check_tx(tx).await?).map_err(Error::serde)?
+        },
         ClientRequest::ConsensusParams { height } => {
             serde_json::to_string_pretty(&client.consensus_params(height).await?)
                 .map_err(Error::serde)?
@@ -392,6 +426,20 @@ where
         ClientRequest::Commit { height } => {
             serde_json::to_string_pretty(&client.commit(height).await?).map_err(Error::serde)?
         },
+        ClientRequest::Header { height } => {
+            serde_json::to_string_pretty(&client.header(height).await?).map_err(Error::serde)?
+        },
+        ClientRequest::HeaderByHash { hash } => serde_json::to_string_pretty(
+            &client
+                .header_by_hash(
+                    tendermint::Hash::from_str(&hash).map_err(|e| Error::parse(e.to_string()))?,
+                )
+                .await?,
+        )
+        .map_err(Error::serde)?,
+        ClientRequest::LatestHeader => {
+            serde_json::to_string_pretty(&client.latest_header().await?).map_err(Error::serde)?
+        },
         ClientRequest::LatestBlock => {
             serde_json::to_string_pretty(&client.latest_block().await?).map_err(Error::serde)?
         },
@@ -409,16 +457,28 @@ where
         ClientRequest::ConsensusState => {
             serde_json::to_string_pretty(&client.consensus_state().await?).map_err(Error::serde)?
         },
+        ClientRequest::DumpConsensusState => {
+            serde_json::to_string_pretty(&client.dump_consensus_state().await?)
+                .map_err(Error::serde)?
+        },
         ClientRequest::Genesis => {
             serde_json::to_string_pretty(&client.genesis::<serde_json::Value>().await?)
                 .map_err(Error::serde)?
         },
+        ClientRequest::GenesisChunked => {
+            serde_json::to_string_pretty(&client.genesis_chunked::<serde_json::Value>().await?)
+                .map_err(Error::serde)?
+        },
         ClientRequest::Health => {
             serde_json::to_string_pretty(&client.health().await?).map_err(Error::serde)?
         },
         ClientRequest::NetInfo => {
             serde_json::to_string_pretty(&client.net_info().await?).map_err(Error::serde)?
         },
+        ClientRequest::NumUnconfirmedTxs => {
+            serde_json::to_string_pretty(&client.num_unconfirmed_txs().await?)
+                .map_err(Error::serde)?
+        },
         ClientRequest::Status => {
             serde_json::to_string_pretty(&client.status().await?).map_err(Error::serde)?
         },
@@ -437,12 +497,20 @@ where
             per_page,
             order,
             prove,
+            match_events,
         } => serde_json::to_string_pretty(
             &client
-                .tx_search(query, prove, page, per_page, order)
+                .tx_search_with(
+                    endpoint::tx_search::Request::new(query, prove, page, per_page, order)
+                        .with_match_events(match_events),
+                )
                 .await?,
         )
         .map_err(Error::serde)?,
+        ClientRequest::UnconfirmedTxs { limit } => {
+            serde_json::to_string_pretty(&client.unconfirmed_txs(limit).await?)
+                .map_err(Error::serde)?
+        },
         ClientRequest::Validators {
             height,
             all,
@@ -541,9 +609,12 @@ async fn recv_events(mut subs: Subscription, max_events: Option<u32>) -> Result<
 
 fn print_event(event: Event) -> Result<(), Error> {
     let json = match &event.data {
-        EventData::LegacyNewBlock { .. } => {
+        EventData::LegacyNewBlock { .. }
+        | EventData::NewBlockHeader {
+            num_txs: Some(_), ..
+        } => {
             // Print the old field structure in case the event was received
-            // from a pre-0.38 node. This is the only instance where the
+            // from a pre-0.38 node. These are the only instances where the
             // structure of the dumped event data currently differs.
             let ser_event: event::v0_37::SerEvent = event.into();
             serde_json::to_string_pretty(&ser_event).map_err(Error::serde)?
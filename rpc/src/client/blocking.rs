@@ -0,0 +1,524 @@
+//! A synchronous facade over the asynchronous HTTP client, for applications
+//! which do not run an async runtime.
+
+use alloc::sync::Arc;
+use core::{fmt, ops::RangeInclusive, time::Duration};
+
+use futures::stream::{BoxStream, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use tendermint::{abci, block::Height, evidence::Evidence, Genesis, Hash};
+use tokio::runtime::{self, Runtime};
+
+use crate::{
+    client::{Client, CompatMode, HttpClientUrl},
+    endpoint::*,
+    paging::Paging,
+    prelude::*,
+    query::Query,
+    Error, Order, SimpleRequest,
+};
+
+/// A blocking Tendermint RPC HTTP/S client.
+///
+/// Every method of the asynchronous [`Client`] trait is provided here as a
+/// method blocking the calling thread until its result is available. The
+/// requests are made by an asynchronous [`HttpClient`], driven by a runtime
+/// owned by the client and shared by its clones.
+///
+/// The methods of this client must not be called from within an async
+/// runtime, as the runtime of the client cannot be driven from there. The
+/// last clone of the client must not be dropped from within an async runtime
+/// either.
+///
+/// ```rust,ignore
+/// use tendermint_rpc::client::blocking::HttpClient;
+///
+/// let client = HttpClient::new("http://127.0.0.1:26657").unwrap();
+/// let status = client.status().unwrap();
+/// println!("latest height: {}", status.sync_info.latest_block_height);
+/// ```
+///
+/// [`HttpClient`]: crate::HttpClient
+#[derive(Clone)]
+pub struct HttpClient {
+    inner: crate::HttpClient,
+    runtime: Arc<Runtime>,
+}
+
+impl fmt::Debug for HttpClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpClient")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An iterator over the items of a paginated or sampled result, fetching
+/// them as it is advanced.
+pub struct Iter<'a, T> {
+    runtime: &'a Runtime,
+    stream: BoxStream<'a, Result<T, Error>>,
+}
+
+impl<'a, T> fmt::Debug for Iter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter").finish_non_exhaustive()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+impl HttpClient {
+    /// Construct a new blocking Tendermint RPC HTTP/S client connecting to
+    /// the given URL.
+    ///
+    /// See [`crate::HttpClient::new`].
+    pub fn new<U>(url: U) -> Result<Self, Error>
+    where
+        U: TryInto<HttpClientUrl, Error = Error>,
+    {
+        Self::from_async(crate::HttpClient::new(url)?)
+    }
+
+    /// Construct a new blocking Tendermint RPC HTTP/S client connecting to
+    /// the given URL, but via the specified proxy's URL.
+    ///
+    /// See [`crate::HttpClient::new_with_proxy`].
+    pub fn new_with_proxy<U, P>(url: U, proxy_url: P) -> Result<Self, Error>
+    where
+        U: TryInto<HttpClientUrl, Error = Error>,
+        P: TryInto<HttpClientUrl, Error = Error>,
+    {
+        Self::from_async(crate::HttpClient::new_with_proxy(url, proxy_url)?)
+    }
+
+    /// Make the requests of the given asynchronous client blocking.
+    ///
+    /// Clients with more configuration options can be built with
+    /// [`crate::HttpClient::builder`] and made blocking this way.
+    pub fn from_async(inner: crate::HttpClient) -> Result<Self, Error> {
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(Error::io)?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// The asynchronous client making the requests.
+    pub fn inner(&self) -> &crate::HttpClient {
+        &self.inner
+    }
+
+    /// Set compatibility mode on the instantiated client.
+    ///
+    /// See [`crate::HttpClient::set_compat_mode`].
+    pub fn set_compat_mode(&mut self, compat: CompatMode) {
+        self.inner.set_compat_mode(compat);
+    }
+
+    /// See [`crate::HttpClient::negotiate_compat_mode`].
+    pub fn negotiate_compat_mode(&mut self) -> Result<CompatMode, Error> {
+        self.runtime.block_on(self.inner.negotiate_compat_mode())
+    }
+
+    /// Set or remove the timeout of the requests made by the instantiated
+    /// client.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_timeout(timeout);
+    }
+
+    /// A client sharing the connections and the runtime of this one, whose
+    /// requests time out after the given duration instead of the timeout of
+    /// this client.
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        Self {
+            inner: self.inner.with_timeout(timeout),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// A client sharing the connections and the runtime of this one, whose
+    /// requests carry the given HTTP header.
+    ///
+    /// See [`crate::HttpClient::with_header`].
+    pub fn with_header(&self, name: &str, value: &str) -> Result<Self, Error> {
+        Ok(Self {
+            inner: self.inner.with_header(name, value)?,
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    fn iter<'a, T>(&'a self, stream: BoxStream<'a, Result<T, Error>>) -> Iter<'a, T> {
+        Iter {
+            runtime: &self.runtime,
+            stream,
+        }
+    }
+
+    /// `/abci_info`: get information about the ABCI application.
+    pub fn abci_info(&self) -> Result<abci::response::Info, Error> {
+        self.runtime.block_on(self.inner.abci_info())
+    }
+
+    /// `/abci_query`: query the ABCI application
+    pub fn abci_query<V>(
+        &self,
+        path: Option<String>,
+        data: V,
+        height: Option<Height>,
+        prove: bool,
+    ) -> Result<abci_query::AbciQuery, Error>
+    where
+        V: Into<Vec<u8>> + Send,
+    {
+        self.runtime
+            .block_on(self.inner.abci_query(path, data, height, prove))
+    }
+
+    /// `/block`: get block at a given height.
+    pub fn block<H>(&self, height: H) -> Result<block::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        self.runtime.block_on(self.inner.block(height))
+    }
+
+    /// `/block_by_hash`: get block by hash.
+    pub fn block_by_hash(&self, hash: Hash) -> Result<block_by_hash::Response, Error> {
+        self.runtime.block_on(self.inner.block_by_hash(hash))
+    }
+
+    /// `/block`: get the latest block.
+    pub fn latest_block(&self) -> Result<block::Response, Error> {
+        self.runtime.block_on(self.inner.latest_block())
+    }
+
+    /// `/header`: get block header at a given height.
+    pub fn header<H>(&self, height: H) -> Result<header::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        self.runtime.block_on(self.inner.header(height))
+    }
+
+    /// `/header`: get the latest block header.
+    pub fn latest_header(&self) -> Result<header::Response, Error> {
+        self.runtime.block_on(self.inner.latest_header())
+    }
+
+    /// `/header_by_hash`: get block by hash.
+    pub fn header_by_hash(&self, hash: Hash) -> Result<header_by_hash::Response, Error> {
+        self.runtime.block_on(self.inner.header_by_hash(hash))
+    }
+
+    /// `/block_results`: get ABCI results for a block at a particular height.
+    pub fn block_results<H>(&self, height: H) -> Result<block_results::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        self.runtime.block_on(self.inner.block_results(height))
+    }
+
+    /// `/block_results`: get ABCI results for the latest block.
+    pub fn latest_block_results(&self) -> Result<block_results::Response, Error> {
+        self.runtime.block_on(self.inner.latest_block_results())
+    }
+
+    /// `/block_search`: search for blocks by BeginBlock and EndBlock events.
+    pub fn block_search(
+        &self,
+        query: Query,
+        page: u32,
+        per_page: u8,
+        order: Order,
+    ) -> Result<block_search::Response, Error> {
+        self.runtime
+            .block_on(self.inner.block_search(query, page, per_page, order))
+    }
+
+    /// `/block_search`: search for all the blocks matching the query,
+    /// walking through the pages of results as the returned iterator is
+    /// advanced.
+    ///
+    /// See [`Client::block_search_all`].
+    pub fn block_search_all(&self, query: Query, order: Order) -> Iter<'_, block::Response> {
+        self.iter(self.inner.block_search_all(query, order))
+    }
+
+    /// `/blockchain`: get block headers for `min` <= `height` <= `max`.
+    ///
+    /// Block headers are returned in descending order (highest first).
+    ///
+    /// Returns at most 20 items.
+    pub fn blockchain<H>(&self, min: H, max: H) -> Result<blockchain::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        self.runtime.block_on(self.inner.blockchain(min, max))
+    }
+
+    /// Iterate over the metadata of the blocks of the given range of heights,
+    /// in ascending order, fetching them as the returned iterator is advanced.
+    ///
+    /// See [`Client::block_metas_range`].
+    pub fn block_metas_range<H>(
+        &self,
+        range: RangeInclusive<H>,
+    ) -> Iter<'_, tendermint::block::Meta>
+    where
+        H: Into<Height>,
+    {
+        self.iter(self.inner.block_metas_range(range))
+    }
+
+    /// `/broadcast_tx_async`: broadcast a transaction, returning immediately.
+    pub fn broadcast_tx_async<T>(&self, tx: T) -> Result<broadcast::tx_async::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        self.runtime.block_on(self.inner.broadcast_tx_async(tx))
+    }
+
+    /// `/broadcast_tx_sync`: broadcast a transaction, returning the response
+    /// from `CheckTx`.
+    pub fn broadcast_tx_sync<T>(&self, tx: T) -> Result<broadcast::tx_sync::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        self.runtime.block_on(self.inner.broadcast_tx_sync(tx))
+    }
+
+    /// `/broadcast_tx_commit`: broadcast a transaction, returning the response
+    /// from `DeliverTx`.
+    pub fn broadcast_tx_commit<T>(&self, tx: T) -> Result<broadcast::tx_commit::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        self.runtime.block_on(self.inner.broadcast_tx_commit(tx))
+    }
+
+    /// `/check_tx`: check a transaction against the mempool rules of the
+    /// application, without broadcasting it.
+    pub fn check_tx<T>(&self, tx: T) -> Result<abci::response::CheckTx, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        self.runtime.block_on(self.inner.check_tx(tx))
+    }
+
+    /// `/commit`: get block commit at a given height.
+    pub fn commit<H>(&self, height: H) -> Result<commit::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        self.runtime.block_on(self.inner.commit(height))
+    }
+
+    /// `/consensus_params`: get current consensus parameters at the specified
+    /// height.
+    pub fn consensus_params<H>(&self, height: H) -> Result<consensus_params::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        self.runtime.block_on(self.inner.consensus_params(height))
+    }
+
+    /// `/consensus_state`: get current consensus state
+    pub fn consensus_state(&self) -> Result<consensus_state::Response, Error> {
+        self.runtime.block_on(self.inner.consensus_state())
+    }
+
+    /// `/dump_consensus_state`: get the full consensus state of the node and
+    /// of its peers
+    pub fn dump_consensus_state(&self) -> Result<dump_consensus_state::Response, Error> {
+        self.runtime.block_on(self.inner.dump_consensus_state())
+    }
+
+    /// `/validators`: get validators a given height.
+    pub fn validators<H>(&self, height: H, paging: Paging) -> Result<validators::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        self.runtime.block_on(self.inner.validators(height, paging))
+    }
+
+    /// `/consensus_params`: get the latest consensus parameters.
+    pub fn latest_consensus_params(&self) -> Result<consensus_params::Response, Error> {
+        self.runtime.block_on(self.inner.latest_consensus_params())
+    }
+
+    /// `/commit`: get the latest block commit
+    pub fn latest_commit(&self) -> Result<commit::Response, Error> {
+        self.runtime.block_on(self.inner.latest_commit())
+    }
+
+    /// `/health`: get node health.
+    pub fn health(&self) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.health())
+    }
+
+    /// `/genesis`: get genesis file.
+    pub fn genesis<AppState>(&self) -> Result<Genesis<AppState>, Error>
+    where
+        AppState: fmt::Debug + Serialize + DeserializeOwned + Send,
+    {
+        self.runtime.block_on(self.inner.genesis())
+    }
+
+    /// `/genesis_chunked`: get the genesis file chunk by chunk.
+    pub fn genesis_chunked<AppState>(&self) -> Result<Genesis<AppState>, Error>
+    where
+        AppState: fmt::Debug + Serialize + DeserializeOwned + Send,
+    {
+        self.runtime.block_on(self.inner.genesis_chunked())
+    }
+
+    /// `/net_info`: obtain information about P2P and other network connections.
+    pub fn net_info(&self) -> Result<net_info::Response, Error> {
+        self.runtime.block_on(self.inner.net_info())
+    }
+
+    /// `/unconfirmed_txs`: get up to `limit` of the transactions in the
+    /// mempool, or as many as the node returns by default.
+    pub fn unconfirmed_txs(&self, limit: Option<u32>) -> Result<unconfirmed_txs::Response, Error> {
+        self.runtime.block_on(self.inner.unconfirmed_txs(limit))
+    }
+
+    /// `/num_unconfirmed_txs`: get the number and total size of the
+    /// transactions in the mempool.
+    pub fn num_unconfirmed_txs(&self) -> Result<num_unconfirmed_txs::Response, Error> {
+        self.runtime.block_on(self.inner.num_unconfirmed_txs())
+    }
+
+    /// `/status`: get Tendermint status including node info, pubkey, latest
+    /// block hash, app hash, block height and time.
+    pub fn status(&self) -> Result<status::Response, Error> {
+        self.runtime.block_on(self.inner.status())
+    }
+
+    /// `/broadcast_evidence`: broadcast an evidence.
+    pub fn broadcast_evidence(&self, e: Evidence) -> Result<evidence::Response, Error> {
+        self.runtime.block_on(self.inner.broadcast_evidence(e))
+    }
+
+    /// `/tx`: find transaction by hash.
+    pub fn tx(&self, hash: Hash, prove: bool) -> Result<tx::Response, Error> {
+        self.runtime.block_on(self.inner.tx(hash, prove))
+    }
+
+    /// `/tx_search`: search for transactions with their results.
+    pub fn tx_search(
+        &self,
+        query: Query,
+        prove: bool,
+        page: u32,
+        per_page: u8,
+        order: Order,
+    ) -> Result<tx_search::Response, Error> {
+        self.runtime
+            .block_on(self.inner.tx_search(query, prove, page, per_page, order))
+    }
+
+    /// `/tx_search`: search for transactions with their results, with all the
+    /// parameters of the given request.
+    pub fn tx_search_with(
+        &self,
+        request: tx_search::Request,
+    ) -> Result<tx_search::Response, Error> {
+        self.runtime.block_on(self.inner.tx_search_with(request))
+    }
+
+    /// `/tx_search`: search for all the transactions matching the query,
+    /// walking through the pages of results as the returned iterator is
+    /// advanced.
+    ///
+    /// See [`Client::tx_search_all`].
+    pub fn tx_search_all(&self, query: Query, prove: bool, order: Order) -> Iter<'_, tx::Response> {
+        self.iter(self.inner.tx_search_all(query, prove, order))
+    }
+
+    /// Poll the `/health` endpoint until it returns a successful result or
+    /// the given `timeout` has elapsed.
+    pub fn wait_until_healthy<T>(&self, timeout: T) -> Result<(), Error>
+    where
+        T: Into<Duration> + Send,
+    {
+        self.runtime
+            .block_on(self.inner.wait_until_healthy(timeout))
+    }
+
+    /// Perform a request to a method which is not modelled by this crate.
+    pub fn perform_raw(
+        &self,
+        method: impl Into<String>,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, Error> {
+        self.runtime
+            .block_on(self.inner.perform_raw(method.into(), params))
+    }
+
+    /// Perform a request against the RPC endpoint.
+    pub fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest,
+    {
+        self.runtime.block_on(self.inner.perform(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::*;
+
+    const HEALTH_RESPONSE: &str = r#"{"jsonrpc":"2.0","id":"","result":{}}"#;
+
+    #[test]
+    fn requests_block_until_answered() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                HEALTH_RESPONSE.len(),
+                HEALTH_RESPONSE
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+
+        let client = HttpClient::new(url.as_str()).unwrap();
+        client.health().unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.contains(r#""method": "health""#));
+    }
+
+    #[test]
+    fn errors_are_returned() {
+        // Nothing listens on the port once the listener is dropped.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let client = HttpClient::new(url.as_str()).unwrap();
+        assert!(client.status().is_err());
+    }
+}
@@ -0,0 +1,266 @@
+//! Client-side protection against double broadcasting of transactions.
+
+use alloc::collections::BTreeMap;
+use core::fmt;
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use sha2::{Digest, Sha256};
+use tendermint::{abci::Code, Hash};
+use tracing::warn;
+
+use crate::{
+    client::Client,
+    endpoint::broadcast::{tx_async, tx_sync},
+    prelude::*,
+    Error,
+};
+
+/// The default window within which a repeated broadcast of the same
+/// transaction is considered a duplicate.
+pub const DEFAULT_DUPLICATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// What a [`BroadcastGuard`] does when asked to broadcast a transaction it has
+/// already broadcast within its window.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Log a warning and broadcast the transaction anyway.
+    Warn,
+    /// Refuse to broadcast the transaction, returning an error.
+    Reject,
+}
+
+/// A predicate deciding whether a `CheckTx` rejection indicates that the
+/// transaction's sequence number (nonce) conflicts with the account state.
+pub type SequenceConflictDetector = fn(code: Code, log: &str) -> bool;
+
+/// Detects the account sequence mismatch errors produced by the Cosmos SDK
+/// ante handler.
+///
+/// Only the log is matched: the code of `ErrWrongSequence` (32) is only
+/// meaningful in the `sdk` codespace, which the broadcast responses do not
+/// carry, and other codespaces use the same code for unrelated errors.
+pub fn cosmos_sdk_sequence_mismatch(_code: Code, log: &str) -> bool {
+    log.contains("account sequence mismatch")
+}
+
+/// Remembers the hashes of recently broadcast transactions, so that a
+/// transaction accidentally broadcast twice (e.g. by a wallet retrying after a
+/// timeout) can be caught before it reaches the node.
+///
+/// The guard can also detect `CheckTx` rejections caused by sequence (nonce)
+/// conflicts, which usually means the transaction has to be signed again
+/// with a fresh sequence number, and report them as
+/// [`Error::sequence_conflict`] errors.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// use tendermint_rpc::{client::BroadcastGuard, HttpClient};
+///
+/// let client = HttpClient::new("http://127.0.0.1:26657").unwrap();
+/// let guard = BroadcastGuard::default();
+///
+/// guard.broadcast_tx_sync(&client, tx.clone()).await?;
+/// // Fails with a duplicate broadcast error.
+/// guard.broadcast_tx_sync(&client, tx).await?;
+/// ```
+pub struct BroadcastGuard {
+    window: Duration,
+    policy: DuplicatePolicy,
+    detector: Option<SequenceConflictDetector>,
+    recent: Mutex<BTreeMap<Hash, Instant>>,
+}
+
+impl Default for BroadcastGuard {
+    fn default() -> Self {
+        Self::new(DEFAULT_DUPLICATE_WINDOW)
+    }
+}
+
+impl fmt::Debug for BroadcastGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BroadcastGuard")
+            .field("window", &self.window)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+impl BroadcastGuard {
+    /// Create a guard rejecting repeated broadcasts of a transaction within
+    /// the given window, and detecting Cosmos SDK sequence mismatches.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            policy: DuplicatePolicy::Reject,
+            detector: Some(cosmos_sdk_sequence_mismatch),
+            recent: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Set what to do when a duplicate broadcast is detected.
+    pub fn policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Use the given predicate to detect sequence conflicts in `CheckTx`
+    /// rejections, or disable the detection with `None`.
+    pub fn sequence_conflict_detector(
+        mut self,
+        detector: Option<SequenceConflictDetector>,
+    ) -> Self {
+        self.detector = detector;
+        self
+    }
+
+    /// Record the broadcast of the given transaction, returning its hash.
+    ///
+    /// Fails if the transaction has already been recorded within the window
+    /// and the policy is [`DuplicatePolicy::Reject`].
+    pub fn record(&self, tx: &[u8]) -> Result<Hash, Error> {
+        let hash = tx_hash(tx);
+        let now = Instant::now();
+        let mut recent = self.recent.lock().unwrap();
+        recent.retain(|_, at| now.duration_since(*at) < self.window);
+
+        if recent.contains_key(&hash) {
+            match self.policy {
+                DuplicatePolicy::Reject => return Err(Error::duplicate_broadcast(hash)),
+                DuplicatePolicy::Warn => {
+                    warn!("Transaction {} was already broadcast recently", hash)
+                },
+            }
+        }
+        recent.insert(hash, now);
+        Ok(hash)
+    }
+
+    /// Forget about the broadcast of the transaction with the given hash, so
+    /// that it can be broadcast again without being considered a duplicate.
+    pub fn forget(&self, hash: &Hash) {
+        self.recent.lock().unwrap().remove(hash);
+    }
+
+    /// Whether the given `CheckTx` outcome indicates a sequence conflict.
+    pub fn is_sequence_conflict(&self, code: Code, log: &str) -> bool {
+        match self.detector {
+            Some(detect) => code.is_err() && detect(code, log),
+            None => false,
+        }
+    }
+
+    /// `/broadcast_tx_async` the transaction through the given client, unless
+    /// it is a duplicate of a recent broadcast.
+    ///
+    /// If the broadcast request fails, the transaction is forgotten so that
+    /// it can be retried.
+    pub async fn broadcast_tx_async<C>(
+        &self,
+        client: &C,
+        tx: Vec<u8>,
+    ) -> Result<tx_async::Response, Error>
+    where
+        C: Client + Sync,
+    {
+        let hash = self.record(&tx)?;
+        let result = client.broadcast_tx_async(tx).await;
+        if result.is_err() {
+            self.forget(&hash);
+        }
+        result
+    }
+
+    /// `/broadcast_tx_sync` the transaction through the given client, unless
+    /// it is a duplicate of a recent broadcast.
+    ///
+    /// If the transaction is rejected by `CheckTx`, it is forgotten so that it
+    /// can be retried. A rejection identified as a sequence conflict is
+    /// returned as an [`Error::sequence_conflict`] error.
+    pub async fn broadcast_tx_sync<C>(
+        &self,
+        client: &C,
+        tx: Vec<u8>,
+    ) -> Result<tx_sync::Response, Error>
+    where
+        C: Client + Sync,
+    {
+        let hash = self.record(&tx)?;
+        let response = match client.broadcast_tx_sync(tx).await {
+            Ok(response) => response,
+            Err(e) => {
+                self.forget(&hash);
+                return Err(e);
+            },
+        };
+
+        if response.code.is_err() {
+            self.forget(&hash);
+            if self.is_sequence_conflict(response.code, &response.log) {
+                return Err(Error::sequence_conflict(hash, response.log));
+            }
+        }
+        Ok(response)
+    }
+}
+
+pub(crate) fn tx_hash(tx: &[u8]) -> Hash {
+    let digest = Sha256::digest(tx);
+    Hash::Sha256(digest.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorDetail;
+
+    #[test]
+    fn rejects_duplicates_within_window() {
+        let guard = BroadcastGuard::default();
+        let hash = guard.record(b"tx").unwrap();
+        match guard.record(b"tx").unwrap_err().detail() {
+            ErrorDetail::DuplicateBroadcast(e) => assert_eq!(e.hash, hash),
+            e => panic!("unexpected error: {e:?}"),
+        }
+        guard.record(b"other tx").unwrap();
+    }
+
+    #[test]
+    fn accepts_duplicates_after_window() {
+        let guard = BroadcastGuard::new(Duration::from_millis(0));
+        guard.record(b"tx").unwrap();
+        guard.record(b"tx").unwrap();
+    }
+
+    #[test]
+    fn warns_on_duplicates() {
+        let guard = BroadcastGuard::default().policy(DuplicatePolicy::Warn);
+        guard.record(b"tx").unwrap();
+        guard.record(b"tx").unwrap();
+    }
+
+    #[test]
+    fn forgets_transactions() {
+        let guard = BroadcastGuard::default();
+        let hash = guard.record(b"tx").unwrap();
+        guard.forget(&hash);
+        guard.record(b"tx").unwrap();
+    }
+
+    #[test]
+    fn detects_sequence_conflicts() {
+        let guard = BroadcastGuard::default();
+        let log = "account sequence mismatch, expected 5, got 4: incorrect account sequence";
+        assert!(guard.is_sequence_conflict(Code::from(32), log));
+        assert!(!guard.is_sequence_conflict(Code::from(5), "insufficient funds"));
+        // Code 32 of another codespace, e.g. a wasm contract error
+        assert!(!guard.is_sequence_conflict(Code::from(32), "execute wasm contract failed"));
+        assert!(!guard.is_sequence_conflict(Code::Ok, ""));
+
+        let guard = guard.sequence_conflict_detector(None);
+        assert!(!guard.is_sequence_conflict(Code::from(32), log));
+    }
+}
@@ -36,6 +36,11 @@ impl CompatMode {
     /// for all supported RPC dialects, so such a request can be performed
     /// before the required compatibility mode is settled upon.
     ///
+    /// Nodes running Tendermint 0.33 are served with the 0.34 protocol, which
+    /// encodes the responses of the endpoints supported by this crate in the
+    /// same way, with the exception of the evidence parameters in the
+    /// consensus parameters.
+    ///
     /// Note that this is not fail-proof: the version is reported for a particular
     /// client connection, so any other connections to the same URL might not
     /// be handled by the same server. In the future, the RPC protocol should
@@ -47,7 +52,7 @@ impl CompatMode {
             .map_err(|_| Error::invalid_tendermint_version(raw_version))?;
 
         match (version.major, version.minor) {
-            (0, 34) => Ok(CompatMode::V0_34),
+            (0, 33) | (0, 34) => Ok(CompatMode::V0_34),
             (0, 37) => Ok(CompatMode::V0_37),
             (0, 38) => Ok(CompatMode::V0_37),
             _ => Err(Error::unsupported_tendermint_version(version.to_string())),
@@ -77,6 +82,10 @@ mod tests {
 
     #[test]
     fn test_parse_version_for_compat_mode() {
+        assert_eq!(
+            CompatMode::from_version(parse_version("v0.33.9")).unwrap(),
+            CompatMode::V0_34
+        );
         assert_eq!(
             CompatMode::from_version(parse_version("v0.34.16")).unwrap(),
             CompatMode::V0_34
@@ -93,6 +102,8 @@ mod tests {
             CompatMode::from_version(parse_version("v0.38.0")).unwrap(),
             CompatMode::V0_37
         );
+        let res = CompatMode::from_version(parse_version("v0.32.0"));
+        assert!(res.is_err());
         let res = CompatMode::from_version(parse_version("v0.39.0"));
         assert!(res.is_err());
         let res = CompatMode::from_version(parse_version("v1.0.0"));
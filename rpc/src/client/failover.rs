@@ -0,0 +1,516 @@
+//! Failover between the nodes of an ordered list of endpoints.
+
+use core::{
+    fmt,
+    future::Future,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use tendermint::{block::Height, evidence::Evidence, Hash};
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::{
+    client::Client, endpoint, error::ErrorDetail, prelude::*, Error, Method, SimpleRequest,
+};
+
+/// The default duration for which an endpoint is skipped after failing.
+pub const DEFAULT_FAILOVER_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// A client spreading requests over an ordered list of clients connected to
+/// different nodes, failing over to the next one when a request fails
+/// because of the transport, e.g. on connection errors or timeouts.
+///
+/// Endpoints are preferred in the order they are given in. An endpoint whose
+/// request failed because of the transport, or which fails a
+/// [`FailoverClient::health_check`], is skipped for a cooldown period, unless
+/// all the endpoints are being skipped, in which case they are all tried
+/// again. Requests failing with an error returned by the node, e.g. because
+/// of invalid parameters, are not failed over.
+///
+/// A request which timed out may still have been handled by the node, so
+/// only reads are failed over on timeouts: broadcasts, subscriptions and
+/// custom requests are not sent to another node after a timeout, lest they
+/// are handled twice, and the timeout is returned instead.
+///
+/// With [`FailoverClient::round_robin`], reads are spread evenly over the
+/// healthy endpoints instead. Broadcasts always go to the preferred endpoint
+/// first.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// use tendermint_rpc::{client::FailoverClient, Client};
+///
+/// let client = FailoverClient::from_urls([
+///     "http://node-0:26657",
+///     "http://node-1:26657",
+/// ])?
+/// .round_robin(true);
+///
+/// client.health_check().await;
+/// let status = client.status().await?;
+/// ```
+pub struct FailoverClient<C> {
+    endpoints: Vec<C>,
+    // Until when each endpoint is skipped, if it is.
+    unhealthy_until: Mutex<Vec<Option<Instant>>>,
+    cooldown: Duration,
+    round_robin: bool,
+    next: AtomicUsize,
+}
+
+impl<C: fmt::Debug> fmt::Debug for FailoverClient<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FailoverClient")
+            .field("endpoints", &self.endpoints)
+            .field("cooldown", &self.cooldown)
+            .field("round_robin", &self.round_robin)
+            .finish()
+    }
+}
+
+#[cfg(feature = "http-client")]
+impl FailoverClient<crate::HttpClient> {
+    /// Create HTTP clients for the given node URLs, in order of preference,
+    /// and fail over between them.
+    ///
+    /// ## Panics
+    ///
+    /// If no URL is given.
+    pub fn from_urls<I, U>(urls: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = U>,
+        U: TryInto<crate::HttpClientUrl, Error = Error>,
+    {
+        let endpoints = urls
+            .into_iter()
+            .map(crate::HttpClient::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(endpoints))
+    }
+}
+
+impl<C> FailoverClient<C> {
+    /// Fail over between the given clients, in order of preference.
+    ///
+    /// ## Panics
+    ///
+    /// If no client is given.
+    pub fn new(endpoints: Vec<C>) -> Self {
+        assert!(
+            !endpoints.is_empty(),
+            "a failover client needs at least one endpoint"
+        );
+        Self {
+            unhealthy_until: Mutex::new(vec![None; endpoints.len()]),
+            endpoints,
+            cooldown: DEFAULT_FAILOVER_COOLDOWN,
+            round_robin: false,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Set the duration for which an endpoint is skipped after failing.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Spread reads evenly over the healthy endpoints rather than sending
+    /// them to the preferred one.
+    pub fn round_robin(mut self, round_robin: bool) -> Self {
+        self.round_robin = round_robin;
+        self
+    }
+
+    /// The clients of the endpoints, in order of preference.
+    pub fn endpoints(&self) -> &[C] {
+        &self.endpoints
+    }
+
+    /// Whether the endpoint at the given position is currently considered
+    /// healthy, i.e. it is not being skipped after failing.
+    pub fn is_healthy(&self, index: usize) -> bool {
+        let unhealthy_until = self.unhealthy_until.lock().unwrap();
+        !matches!(unhealthy_until[index], Some(until) if until > Instant::now())
+    }
+
+    fn mark_healthy(&self, index: usize) {
+        self.unhealthy_until.lock().unwrap()[index] = None;
+    }
+
+    fn mark_unhealthy(&self, index: usize) {
+        self.unhealthy_until.lock().unwrap()[index] = Some(Instant::now() + self.cooldown);
+    }
+
+    /// The positions of the endpoints to try a request for the given method
+    /// with, in order: the healthy ones, then the others as a last resort.
+    fn candidates(&self, method: Method) -> Vec<usize> {
+        let count = self.endpoints.len();
+        let start = if self.round_robin && !is_broadcast(method) {
+            self.next.fetch_add(1, Ordering::Relaxed) % count
+        } else {
+            0
+        };
+        let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = (0..count)
+            .map(|i| (start + i) % count)
+            .partition(|&index| self.is_healthy(index));
+        healthy.extend(unhealthy);
+        healthy
+    }
+
+    async fn failover<'a, T, F, Fut>(&'a self, method: Method, call: F) -> Result<T, Error>
+    where
+        F: Fn(&'a C) -> Fut + Send,
+        Fut: Future<Output = Result<T, Error>> + Send,
+    {
+        let mut last_error = None;
+        for index in self.candidates(method) {
+            match call(&self.endpoints[index]).await {
+                Ok(output) => {
+                    self.mark_healthy(index);
+                    return Ok(output);
+                },
+                Err(e) if is_timeout(&e) && !is_read(method) => {
+                    debug!("not failing over {method} from endpoint {index} after timeout: {e}");
+                    self.mark_unhealthy(index);
+                    return Err(e);
+                },
+                Err(e) if is_transport_error(&e) => {
+                    debug!("failing over {method} from endpoint {index} after error: {e}");
+                    self.mark_unhealthy(index);
+                    last_error = Some(e);
+                },
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.expect("a failover client has at least one endpoint"))
+    }
+}
+
+impl<C> FailoverClient<C>
+where
+    C: Client + Send + Sync,
+{
+    /// Check the health of all the endpoints by querying their `/status`.
+    ///
+    /// Endpoints whose node cannot be reached or is still catching up are
+    /// skipped for the cooldown period. Returns the number of healthy
+    /// endpoints.
+    pub async fn health_check(&self) -> usize {
+        let statuses =
+            futures::future::join_all(self.endpoints.iter().map(|client| client.status())).await;
+
+        let mut healthy = 0;
+        for (index, status) in statuses.into_iter().enumerate() {
+            match status {
+                Ok(status) if !status.sync_info.catching_up => {
+                    self.mark_healthy(index);
+                    healthy += 1;
+                },
+                Ok(_) => {
+                    debug!("endpoint {index} is catching up");
+                    self.mark_unhealthy(index);
+                },
+                Err(e) => {
+                    debug!("endpoint {index} failed its health check: {e}");
+                    self.mark_unhealthy(index);
+                },
+            }
+        }
+        healthy
+    }
+}
+
+fn is_broadcast(method: Method) -> bool {
+    matches!(
+        method,
+        Method::BroadcastTxAsync
+            | Method::BroadcastTxSync
+            | Method::BroadcastTxCommit
+            | Method::BroadcastEvidence
+    )
+}
+
+/// Whether requests for the given method only read the state of the node,
+/// so that they can safely be sent to another node if they time out.
+fn is_read(method: Method) -> bool {
+    !is_broadcast(method)
+        && !matches!(
+            method,
+            Method::Subscribe | Method::Unsubscribe | Method::Custom
+        )
+}
+
+/// Whether the error means that the request timed out, the node having
+/// possibly handled it nonetheless.
+fn is_timeout(e: &Error) -> bool {
+    matches!(
+        e.detail(),
+        ErrorDetail::ConnectionStale(_) | ErrorDetail::Timeout(_) | ErrorDetail::RequestTimeout(_)
+    )
+}
+
+fn is_transport_error(e: &Error) -> bool {
+    matches!(
+        e.detail(),
+        ErrorDetail::Io(_)
+            | ErrorDetail::Http(_)
+            | ErrorDetail::Hyper(_)
+            | ErrorDetail::WebSocket(_)
+            | ErrorDetail::ConnectionStale(_)
+            | ErrorDetail::Tungstenite(_)
+            | ErrorDetail::Timeout(_)
+            | ErrorDetail::RequestTimeout(_)
+    )
+}
+
+#[async_trait]
+impl<C> Client for FailoverClient<C>
+where
+    C: Client + Send + Sync,
+{
+    async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest,
+    {
+        // Requests are not `Clone`, but can be rebuilt from their JSON form
+        // for every endpoint.
+        let method = request.method();
+        let request = request.into_json();
+        self.failover(method, |client| {
+            let request = R::from_string(&request);
+            async move { client.perform(request?).await }
+        })
+        .await
+    }
+
+    // The methods whose requests depend on the compatibility mode of the
+    // wrapped clients are forwarded to them rather than going through
+    // `perform`.
+
+    async fn block_results<H>(&self, height: H) -> Result<endpoint::block_results::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        let height = height.into();
+        self.failover(Method::BlockResults, |client| client.block_results(height))
+            .await
+    }
+
+    async fn latest_block_results(&self) -> Result<endpoint::block_results::Response, Error> {
+        self.failover(Method::BlockResults, |client| client.latest_block_results())
+            .await
+    }
+
+    async fn header<H>(&self, height: H) -> Result<endpoint::header::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        let height = height.into();
+        self.failover(Method::Header, |client| client.header(height))
+            .await
+    }
+
+    async fn latest_header(&self) -> Result<endpoint::header::Response, Error> {
+        self.failover(Method::Header, |client| client.latest_header())
+            .await
+    }
+
+    async fn header_by_hash(
+        &self,
+        hash: Hash,
+    ) -> Result<endpoint::header_by_hash::Response, Error> {
+        self.failover(Method::HeaderByHash, |client| client.header_by_hash(hash))
+            .await
+    }
+
+    async fn broadcast_evidence(&self, e: Evidence) -> Result<endpoint::evidence::Response, Error> {
+        self.failover(Method::BroadcastEvidence, |client| {
+            client.broadcast_evidence(e.clone())
+        })
+        .await
+    }
+
+    async fn tx(&self, hash: Hash, prove: bool) -> Result<endpoint::tx::Response, Error> {
+        self.failover(Method::Tx, |client| client.tx(hash, prove))
+            .await
+    }
+
+    async fn tx_search_with(
+        &self,
+        request: endpoint::tx_search::Request,
+    ) -> Result<endpoint::tx_search::Response, Error> {
+        self.failover(Method::TxSearch, |client| {
+            client.tx_search_with(request.clone())
+        })
+        .await
+    }
+
+    async fn broadcast_tx_commit<T>(
+        &self,
+        tx: T,
+    ) -> Result<endpoint::broadcast::tx_commit::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        let tx = tx.into();
+        self.failover(Method::BroadcastTxCommit, |client| {
+            client.broadcast_tx_commit(tx.clone())
+        })
+        .await
+    }
+
+    async fn check_tx<T>(&self, tx: T) -> Result<endpoint::check_tx::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        let tx = tx.into();
+        self.failover(Method::CheckTx, |client| client.check_tx(tx.clone()))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicBool, AtomicU32};
+
+    use super::*;
+    use crate::Response;
+
+    const STATUS: &str = include_str!("../../tests/kvstore_fixtures/v0_37/incoming/status.json");
+
+    /// A client answering requests for `/status` with a fixture and all the
+    /// other requests with an error naming it, unless it is down.
+    struct Node {
+        name: &'static str,
+        down: AtomicBool,
+        requests: AtomicU32,
+    }
+
+    impl Node {
+        fn up(name: &'static str) -> Self {
+            Self {
+                name,
+                down: AtomicBool::new(false),
+                requests: AtomicU32::new(0),
+            }
+        }
+
+        fn down(name: &'static str) -> Self {
+            let node = Self::up(name);
+            node.down.store(true, Ordering::SeqCst);
+            node
+        }
+
+        fn requests(&self) -> u32 {
+            self.requests.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl Client for Node {
+        async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+        where
+            R: SimpleRequest,
+        {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            if self.down.load(Ordering::SeqCst) {
+                Err(Error::timeout(Duration::from_secs(1)))
+            } else if request.method() == Method::Status {
+                R::Response::from_string(STATUS).map(Into::into)
+            } else {
+                Err(Error::client_internal(self.name.to_string()))
+            }
+        }
+    }
+
+    fn answered_by(e: &Error) -> &str {
+        match e.detail() {
+            ErrorDetail::ClientInternal(e) => &e.reason,
+            _ => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn prefers_the_first_endpoint() {
+        let client = FailoverClient::new(vec![Node::up("a"), Node::up("b")]);
+        for _ in 0..3 {
+            assert_eq!(answered_by(&client.health().await.unwrap_err()), "a");
+        }
+        assert_eq!(client.endpoints()[1].requests(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fails_over_on_transport_errors() {
+        let client = FailoverClient::new(vec![Node::down("a"), Node::up("b")])
+            .cooldown(Duration::from_secs(10));
+        assert_eq!(answered_by(&client.health().await.unwrap_err()), "b");
+        assert!(!client.is_healthy(0));
+
+        // The failed endpoint is skipped during the cooldown period.
+        assert_eq!(answered_by(&client.health().await.unwrap_err()), "b");
+        assert_eq!(client.endpoints()[0].requests(), 1);
+
+        // And tried again once it is over.
+        client.endpoints()[0].down.store(false, Ordering::SeqCst);
+        tokio::time::advance(Duration::from_secs(11)).await;
+        assert_eq!(answered_by(&client.health().await.unwrap_err()), "a");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn returns_the_last_error_when_all_endpoints_fail() {
+        let client = FailoverClient::new(vec![Node::down("a"), Node::down("b")]);
+        let e = client.health().await.unwrap_err();
+        assert!(matches!(e.detail(), ErrorDetail::Timeout(_)));
+
+        // Endpoints being skipped are still tried as a last resort.
+        let _ = client.health().await;
+        assert_eq!(client.endpoints()[0].requests(), 2);
+        assert_eq!(client.endpoints()[1].requests(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn does_not_resend_broadcasts_after_timeouts() {
+        let client = FailoverClient::new(vec![Node::down("a"), Node::up("b")]);
+        let e = client.broadcast_tx_sync("tx").await.unwrap_err();
+        assert!(matches!(e.detail(), ErrorDetail::Timeout(_)));
+        assert_eq!(client.endpoints()[1].requests(), 0);
+
+        // The endpoint is skipped afterwards.
+        assert!(!client.is_healthy(0));
+        let e = client.broadcast_tx_sync("tx").await.unwrap_err();
+        assert_eq!(answered_by(&e), "b");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn spreads_reads_with_round_robin() {
+        let client = FailoverClient::new(vec![Node::up("a"), Node::up("b")]).round_robin(true);
+        let mut answers = Vec::new();
+        for _ in 0..4 {
+            answers.push(answered_by(&client.health().await.unwrap_err()).to_string());
+        }
+        assert_eq!(answers, ["a", "b", "a", "b"]);
+
+        // Broadcasts go to the preferred endpoint.
+        for _ in 0..2 {
+            let e = client.broadcast_tx_sync("tx").await.unwrap_err();
+            assert_eq!(answered_by(&e), "a");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn health_check_skips_unreachable_endpoints() {
+        let client = FailoverClient::new(vec![Node::down("a"), Node::up("b")]);
+        assert_eq!(client.health_check().await, 1);
+        assert!(!client.is_healthy(0));
+        assert!(client.is_healthy(1));
+
+        assert_eq!(answered_by(&client.health().await.unwrap_err()), "b");
+        assert_eq!(client.endpoints()[0].requests(), 1);
+    }
+}
@@ -0,0 +1,361 @@
+//! Monitoring of the health of a node, for alerting.
+
+use core::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use tendermint::{block::Height, Time};
+use tokio::time::{interval, Interval, MissedTickBehavior};
+use tracing::{debug, warn};
+
+use crate::{client::Client, prelude::*, Error};
+
+/// The default interval between two checks of the health of a node.
+pub const DEFAULT_HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// The default age of the latest block of a node beyond which it is
+/// considered to be lagging.
+pub const DEFAULT_MAX_BLOCK_AGE: Duration = Duration::from_secs(60);
+
+/// The default number of peers below which a node is considered to have too
+/// few of them.
+pub const DEFAULT_MIN_PEERS: u64 = 1;
+
+/// What a node reported during a check of its health.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    /// Whether the node is still catching up with the network
+    pub catching_up: bool,
+    /// The latest block height of the node
+    pub latest_height: Height,
+    /// How long ago the latest block of the node was produced, according to
+    /// the local clock
+    pub latest_block_age: Duration,
+    /// The number of peers of the node
+    pub peers: u64,
+}
+
+/// A reason for a node to be considered degraded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HealthIssue {
+    /// The node is still catching up with the network
+    CatchingUp,
+    /// The latest block of the node is older than the maximum age
+    Lagging,
+    /// The node has fewer peers than the minimum
+    FewPeers,
+}
+
+/// The health of a node.
+#[derive(Debug, Clone)]
+pub enum HealthState {
+    /// The node answered and no issue was found
+    Healthy,
+    /// The node answered, but with the given issues
+    Degraded(Vec<HealthIssue>),
+    /// The node could not be queried
+    Unreachable(Error),
+}
+
+impl HealthState {
+    /// Whether the node is healthy.
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy)
+    }
+
+    // Whether both states are the same, regardless of the errors which made
+    // the node unreachable.
+    fn same_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Healthy, Self::Healthy) => true,
+            (Self::Degraded(a), Self::Degraded(b)) => a == b,
+            (Self::Unreachable(_), Self::Unreachable(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A change in the health of a node, as reported by a [`HealthMonitor`].
+#[derive(Debug, Clone)]
+pub struct HealthTransition {
+    /// The previous state of the node, or `None` for the first check
+    pub from: Option<HealthState>,
+    /// The new state of the node
+    pub to: HealthState,
+    /// What the node reported during the check which led to the new state,
+    /// if it could be queried
+    pub report: Option<HealthReport>,
+}
+
+/// A monitor periodically checking the health of a node.
+///
+/// Every check queries the `/health`, `/status` and `/net_info` endpoints of
+/// the node, and the node is considered:
+///
+/// - [`Unreachable`](HealthState::Unreachable) if any of them fails,
+/// - [`Degraded`](HealthState::Degraded) if it is still catching up, if its
+///   latest block is older than the maximum age or if it has fewer peers than
+///   the minimum,
+/// - [`Healthy`](HealthState::Healthy) otherwise.
+///
+/// Only the changes of state are reported, which makes the monitor suitable
+/// for alerting.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// use core::time::Duration;
+/// use futures::StreamExt;
+/// use tendermint_rpc::{client::HealthMonitor, HttpClient};
+///
+/// let client = HttpClient::new("http://127.0.0.1:26657")?;
+/// let mut transitions = HealthMonitor::new(Duration::from_secs(10))
+///     .min_peers(3)
+///     .watch(&client);
+/// while let Some(transition) = transitions.next().await {
+///     if !transition.to.is_healthy() {
+///         alert(&transition).await;
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthMonitor {
+    interval: Duration,
+    max_block_age: Duration,
+    min_peers: u64,
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self::new(DEFAULT_HEALTH_POLL_INTERVAL)
+    }
+}
+
+struct State<'a, C> {
+    client: &'a C,
+    monitor: HealthMonitor,
+    ticks: Interval,
+    last: Option<HealthState>,
+}
+
+impl HealthMonitor {
+    /// Check the health of the node at the given interval.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            max_block_age: DEFAULT_MAX_BLOCK_AGE,
+            min_peers: DEFAULT_MIN_PEERS,
+        }
+    }
+
+    /// Consider the node to be lagging once its latest block is older than
+    /// the given age.
+    pub fn max_block_age(mut self, max_block_age: Duration) -> Self {
+        self.max_block_age = max_block_age;
+        self
+    }
+
+    /// Consider the node to have too few peers below the given number.
+    pub fn min_peers(mut self, min_peers: u64) -> Self {
+        self.min_peers = min_peers;
+        self
+    }
+
+    /// Check the health of the node once.
+    pub async fn check<C>(&self, client: &C) -> Result<HealthReport, Error>
+    where
+        C: Client + Sync,
+    {
+        client.health().await?;
+        let status = client.status().await?;
+        let net_info = client.net_info().await?;
+
+        // A latest block from the future, according to the local clock, is
+        // as fresh as it gets.
+        let latest_block_age = now()?
+            .duration_since(status.sync_info.latest_block_time)
+            .unwrap_or_default();
+        Ok(HealthReport {
+            catching_up: status.sync_info.catching_up,
+            latest_height: status.sync_info.latest_block_height,
+            latest_block_age,
+            peers: net_info.n_peers,
+        })
+    }
+
+    /// The state of a node which made the given report.
+    pub fn assess(&self, report: &HealthReport) -> HealthState {
+        let mut issues = Vec::new();
+        if report.catching_up {
+            issues.push(HealthIssue::CatchingUp);
+        }
+        if report.latest_block_age > self.max_block_age {
+            issues.push(HealthIssue::Lagging);
+        }
+        if report.peers < self.min_peers {
+            issues.push(HealthIssue::FewPeers);
+        }
+        if issues.is_empty() {
+            HealthState::Healthy
+        } else {
+            HealthState::Degraded(issues)
+        }
+    }
+
+    /// Watch the health of the node the given client is connected to.
+    ///
+    /// The returned stream yields the state of the node after the first
+    /// check, which happens right away, and then every change of state. It
+    /// never ends.
+    pub fn watch<'a, C>(&self, client: &'a C) -> BoxStream<'a, HealthTransition>
+    where
+        C: Client + Sync,
+    {
+        let mut ticks = interval(self.interval);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let state = State {
+            client,
+            monitor: *self,
+            ticks,
+            last: None,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                state.ticks.tick().await;
+
+                let (to, report) = match state.monitor.check(state.client).await {
+                    Ok(report) => (state.monitor.assess(&report), Some(report)),
+                    Err(e) => (HealthState::Unreachable(e), None),
+                };
+                if matches!(&state.last, Some(last) if last.same_as(&to)) {
+                    debug!("node health unchanged: {:?}", to);
+                    continue;
+                }
+
+                warn!("node health changed from {:?} to {:?}", state.last, to);
+                let from = state.last.replace(to.clone());
+                return Some((HealthTransition { from, to, report }, state));
+            }
+        })
+        .boxed()
+    }
+}
+
+fn now() -> Result<Time, Error> {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Error::client_internal(e.to_string()))?;
+    Time::from_unix_timestamp(since_epoch.as_secs() as i64, since_epoch.subsec_nanos())
+        .map_err(Error::tendermint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::{MockClient, MockRequestScript},
+        Method,
+    };
+
+    const STATUS: &str = include_str!("../../tests/kvstore_fixtures/v0_37/incoming/status.json");
+    const NET_INFO: &str =
+        include_str!("../../tests/kvstore_fixtures/v0_37/incoming/net_info.json");
+    const HEALTH: &str = r#"{"jsonrpc":"2.0","id":"","result":{}}"#;
+
+    fn status(catching_up: bool) -> String {
+        STATUS
+            .replace(
+                r#""latest_block_time": "2023-02-27T07:13:30.422625189Z""#,
+                &format!(r#""latest_block_time": "{}""#, now().unwrap()),
+            )
+            .replace(
+                r#""catching_up": false"#,
+                &format!(r#""catching_up": {catching_up}"#),
+            )
+    }
+
+    fn net_info(peers: u64) -> String {
+        NET_INFO.replace(r#""n_peers": "0""#, &format!(r#""n_peers": "{peers}""#))
+    }
+
+    fn report() -> HealthReport {
+        HealthReport {
+            catching_up: false,
+            latest_height: 10u32.into(),
+            latest_block_age: Duration::from_secs(1),
+            peers: 5,
+        }
+    }
+
+    #[test]
+    fn assesses_reports() {
+        let monitor = HealthMonitor::default().min_peers(3);
+        assert!(monitor.assess(&report()).is_healthy());
+
+        let lagging = HealthReport {
+            catching_up: true,
+            latest_block_age: Duration::from_secs(120),
+            ..report()
+        };
+        assert!(matches!(
+            monitor.assess(&lagging),
+            HealthState::Degraded(issues)
+                if issues == [HealthIssue::CatchingUp, HealthIssue::Lagging]
+        ));
+
+        let isolated = HealthReport {
+            peers: 2,
+            ..report()
+        };
+        assert!(matches!(
+            monitor.assess(&isolated),
+            HealthState::Degraded(issues) if issues == [HealthIssue::FewPeers]
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reports_state_transitions() {
+        let script = MockRequestScript::default()
+            .on(Method::Health, Ok(HEALTH.to_string()))
+            .once(Method::Status, Ok(status(true)))
+            .once(Method::Status, Ok(status(true)))
+            .once(Method::Status, Err(Error::client_internal("down".into())))
+            .on(Method::Status, Ok(status(false)))
+            .on(Method::NetInfo, Ok(net_info(2)));
+        let (client, driver) = MockClient::new(script);
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        let mut transitions = HealthMonitor::new(Duration::from_secs(1)).watch(&client);
+
+        let first = transitions.next().await.unwrap();
+        assert!(first.from.is_none());
+        assert!(matches!(
+            &first.to,
+            HealthState::Degraded(issues) if issues == &[HealthIssue::CatchingUp]
+        ));
+        let report = first.report.unwrap();
+        assert!(report.catching_up);
+        assert_eq!(report.latest_height.value(), 53);
+        assert_eq!(report.peers, 2);
+
+        // The second check finds the node in the same state, which is not
+        // reported, and the third one cannot reach it.
+        let second = transitions.next().await.unwrap();
+        assert!(matches!(second.from, Some(HealthState::Degraded(_))));
+        assert!(matches!(second.to, HealthState::Unreachable(_)));
+        assert!(second.report.is_none());
+        assert_eq!(client.matcher().call_count(Method::Status), 3);
+
+        let third = transitions.next().await.unwrap();
+        assert!(matches!(third.from, Some(HealthState::Unreachable(_))));
+        assert!(third.to.is_healthy());
+
+        drop(transitions);
+        client.close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,308 @@
+//! Prometheus metrics of the requests made to a node.
+
+use core::future::Future;
+
+use async_trait::async_trait;
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry};
+use tendermint::{block::Height, evidence::Evidence, Hash};
+
+use crate::{client::Client, endpoint, prelude::*, Error, Method, SimpleRequest};
+
+/// The namespace of the metrics created by [`ClientMetrics::new`].
+pub const DEFAULT_METRICS_NAMESPACE: &str = "tendermint_rpc_client";
+
+/// The metrics recorded by a [`MeteredClient`], labelled by RPC method:
+///
+/// * `<namespace>_requests_total`, the number of requests made;
+/// * `<namespace>_errors_total`, the number of requests which failed;
+/// * `<namespace>_request_duration_seconds`, a histogram of the time taken
+///   by requests, failed ones included.
+///
+/// Metrics are shared by their clones, so that one set of metrics can be
+/// registered once and record the requests of several clients.
+#[derive(Clone, Debug)]
+pub struct ClientMetrics {
+    requests: IntCounterVec,
+    errors: IntCounterVec,
+    duration: HistogramVec,
+}
+
+impl ClientMetrics {
+    /// Create metrics in the [`DEFAULT_METRICS_NAMESPACE`].
+    pub fn new() -> Self {
+        Self::with_namespace(DEFAULT_METRICS_NAMESPACE)
+            .expect("the default metrics namespace is valid")
+    }
+
+    /// Create metrics in the given namespace, which fails if it is not a
+    /// valid Prometheus metric name.
+    pub fn with_namespace(namespace: &str) -> Result<Self, prometheus::Error> {
+        let requests = IntCounterVec::new(
+            Opts::new("requests_total", "Number of RPC requests made").namespace(namespace),
+            &["method"],
+        )?;
+        let errors = IntCounterVec::new(
+            Opts::new("errors_total", "Number of RPC requests which failed").namespace(namespace),
+            &["method"],
+        )?;
+        let duration = HistogramVec::new(
+            HistogramOpts::new(
+                "request_duration_seconds",
+                "Time taken by RPC requests, in seconds",
+            )
+            .namespace(namespace),
+            &["method"],
+        )?;
+
+        Ok(Self {
+            requests,
+            errors,
+            duration,
+        })
+    }
+
+    /// Register the metrics with the given registry, e.g. the one a service
+    /// exposes to be scraped.
+    pub fn register(&self, registry: &Registry) -> Result<(), prometheus::Error> {
+        registry.register(Box::new(self.requests.clone()))?;
+        registry.register(Box::new(self.errors.clone()))?;
+        registry.register(Box::new(self.duration.clone()))
+    }
+
+    /// The number of requests made for the given method.
+    pub fn requests(&self, method: Method) -> u64 {
+        self.requests.with_label_values(&[method.as_str()]).get()
+    }
+
+    /// The number of requests which failed for the given method.
+    pub fn errors(&self, method: Method) -> u64 {
+        self.errors.with_label_values(&[method.as_str()]).get()
+    }
+
+    async fn observe<F, T>(&self, method: Method, request: F) -> Result<T, Error>
+    where
+        F: Future<Output = Result<T, Error>>,
+    {
+        let label = [method.as_str()];
+        self.requests.with_label_values(&label).inc();
+        let timer = self.duration.with_label_values(&label).start_timer();
+        let result = request.await;
+        timer.observe_duration();
+        if result.is_err() {
+            self.errors.with_label_values(&label).inc();
+        }
+        result
+    }
+}
+
+impl Default for ClientMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A client decorator recording [`ClientMetrics`] for the requests made
+/// through the wrapped client.
+///
+/// Requests that issue several calls, e.g. [`Client::validators`] fetching
+/// all pages, are recorded per call.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// use prometheus::Registry;
+/// use tendermint_rpc::{
+///     client::{ClientMetrics, MeteredClient},
+///     Client, HttpClient,
+/// };
+///
+/// let registry = Registry::new();
+/// let metrics = ClientMetrics::new();
+/// metrics.register(&registry)?;
+///
+/// let client = MeteredClient::new(HttpClient::new("http://127.0.0.1:26657")?, metrics);
+///
+/// let status = client.status().await?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct MeteredClient<C> {
+    inner: C,
+    metrics: ClientMetrics,
+}
+
+impl<C> MeteredClient<C> {
+    /// Wrap the given client, recording its requests in `metrics`.
+    pub fn new(inner: C, metrics: ClientMetrics) -> Self {
+        Self { inner, metrics }
+    }
+
+    pub fn metrics(&self) -> &ClientMetrics {
+        &self.metrics
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<C> Client for MeteredClient<C>
+where
+    C: Client + Send + Sync,
+{
+    async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest,
+    {
+        self.metrics
+            .observe(request.method(), self.inner.perform(request))
+            .await
+    }
+
+    // The methods whose requests depend on the compatibility mode of the
+    // wrapped client are forwarded to it rather than going through `perform`.
+
+    async fn block_results<H>(&self, height: H) -> Result<endpoint::block_results::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        self.metrics
+            .observe(Method::BlockResults, self.inner.block_results(height))
+            .await
+    }
+
+    async fn latest_block_results(&self) -> Result<endpoint::block_results::Response, Error> {
+        self.metrics
+            .observe(Method::BlockResults, self.inner.latest_block_results())
+            .await
+    }
+
+    async fn header<H>(&self, height: H) -> Result<endpoint::header::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        self.metrics
+            .observe(Method::Header, self.inner.header(height))
+            .await
+    }
+
+    async fn latest_header(&self) -> Result<endpoint::header::Response, Error> {
+        self.metrics
+            .observe(Method::Header, self.inner.latest_header())
+            .await
+    }
+
+    async fn header_by_hash(
+        &self,
+        hash: Hash,
+    ) -> Result<endpoint::header_by_hash::Response, Error> {
+        self.metrics
+            .observe(Method::HeaderByHash, self.inner.header_by_hash(hash))
+            .await
+    }
+
+    async fn broadcast_evidence(&self, e: Evidence) -> Result<endpoint::evidence::Response, Error> {
+        self.metrics
+            .observe(Method::BroadcastEvidence, self.inner.broadcast_evidence(e))
+            .await
+    }
+
+    async fn tx(&self, hash: Hash, prove: bool) -> Result<endpoint::tx::Response, Error> {
+        self.metrics
+            .observe(Method::Tx, self.inner.tx(hash, prove))
+            .await
+    }
+
+    async fn tx_search_with(
+        &self,
+        request: endpoint::tx_search::Request,
+    ) -> Result<endpoint::tx_search::Response, Error> {
+        self.metrics
+            .observe(Method::TxSearch, self.inner.tx_search_with(request))
+            .await
+    }
+
+    async fn broadcast_tx_commit<T>(
+        &self,
+        tx: T,
+    ) -> Result<endpoint::broadcast::tx_commit::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        self.metrics
+            .observe(
+                Method::BroadcastTxCommit,
+                self.inner.broadcast_tx_commit(tx),
+            )
+            .await
+    }
+
+    async fn check_tx<T>(&self, tx: T) -> Result<endpoint::check_tx::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        self.metrics
+            .observe(Method::CheckTx, self.inner.check_tx(tx))
+            .await
+    }
+}
+
+#[cfg(all(test, any(feature = "http-client", feature = "websocket-client")))]
+mod tests {
+    use super::*;
+    use crate::client::{MockClient, MockRequestMethodMatcher};
+
+    const HEALTH: &str = r#"{"jsonrpc":"2.0","id":"","result":{}}"#;
+
+    fn mock_client() -> MockClient<MockRequestMethodMatcher> {
+        let matcher =
+            MockRequestMethodMatcher::default().map(Method::Health, Ok(HEALTH.to_string()));
+        MockClient::new(matcher).0
+    }
+
+    #[tokio::test]
+    async fn records_requests_and_errors() {
+        let client = MeteredClient::new(mock_client(), ClientMetrics::new());
+
+        client.health().await.unwrap();
+        client.health().await.unwrap();
+        client.net_info().await.unwrap_err();
+
+        let metrics = client.metrics();
+        assert_eq!(metrics.requests(Method::Health), 2);
+        assert_eq!(metrics.errors(Method::Health), 0);
+        assert_eq!(metrics.requests(Method::NetInfo), 1);
+        assert_eq!(metrics.errors(Method::NetInfo), 1);
+        assert_eq!(metrics.requests(Method::Status), 0);
+    }
+
+    #[tokio::test]
+    async fn exports_to_registry() {
+        let registry = Registry::new();
+        let metrics = ClientMetrics::with_namespace("app_rpc").unwrap();
+        metrics.register(&registry).unwrap();
+
+        let client = MeteredClient::new(mock_client(), metrics.clone());
+        client.health().await.unwrap();
+
+        let families = registry.gather();
+        let names: Vec<_> = families.iter().map(|f| f.get_name()).collect();
+        assert!(names.contains(&"app_rpc_requests_total"));
+        assert!(names.contains(&"app_rpc_request_duration_seconds"));
+
+        let duration = families
+            .iter()
+            .find(|f| f.get_name() == "app_rpc_request_duration_seconds")
+            .unwrap();
+        let histogram = duration.get_metric()[0].get_histogram();
+        assert_eq!(histogram.get_sample_count(), 1);
+
+        // The same metrics cannot be registered twice.
+        assert!(metrics.register(&registry).is_err());
+    }
+}
@@ -0,0 +1,467 @@
+//! Concurrent fetching of ranges of blocks with their results, e.g. for
+//! indexers backfilling a chain.
+
+use alloc::collections::BTreeMap;
+use core::{cmp, time::Duration};
+
+use futures::{
+    future::{self, BoxFuture},
+    stream::{self, BoxStream, FuturesUnordered},
+    FutureExt, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use tendermint::block::Height;
+use tokio::time::sleep;
+use tracing::debug;
+
+use crate::{
+    client::Client,
+    endpoint::{block, block_results},
+    error::ErrorDetail,
+    prelude::*,
+    Error,
+};
+
+/// The default maximum number of heights fetched at once by a
+/// [`BlockPipeline`].
+pub const DEFAULT_PIPELINE_CONCURRENCY: usize = 8;
+
+/// The default number of blocks delivered by a [`BlockPipeline`] between two
+/// checkpoints.
+pub const DEFAULT_CHECKPOINT_INTERVAL: u64 = 1000;
+
+/// The delay before the first retry of a height the node throttled, doubled
+/// on every following retry.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// The longest delay before retrying a height the node throttled.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// How many times a height is retried after the node throttled it before
+/// giving up.
+const MAX_THROTTLED_RETRIES: u32 = 8;
+
+/// A block fetched by a [`BlockPipeline`], with its results.
+#[derive(Clone, Debug)]
+pub struct FetchedBlock {
+    /// The `/block` response
+    pub block: block::Response,
+    /// The `/block_results` response
+    pub results: block_results::Response,
+}
+
+impl FetchedBlock {
+    /// The height of the block.
+    pub fn height(&self) -> Height {
+        self.block.block.header.height
+    }
+}
+
+/// The point a [`BlockPipeline`] reached, from which it can be resumed.
+///
+/// All the blocks below `next_height` were delivered.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// The height of the next block to deliver
+    pub next_height: Height,
+}
+
+/// An item delivered by a [`BlockPipeline`].
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum PipelineItem {
+    /// The next block of the range
+    Block(FetchedBlock),
+
+    /// A checkpoint covering all the blocks delivered so far, to be persisted
+    /// once they are processed
+    Checkpoint(Checkpoint),
+}
+
+/// Fetches the blocks of a range of heights with their results, several
+/// heights at once, and delivers them in order.
+///
+/// The number of heights fetched at once adapts to the node: it is halved
+/// when the node throttles requests, i.e. when they fail with a
+/// [`RateLimited`] error, time out, or get an HTTP 429 or 503 response, and
+/// increased by one once as many heights as are fetched at once were fetched
+/// successfully, up to the configured concurrency. Throttled heights are
+/// retried with an exponential backoff. Any other error is delivered and ends
+/// the stream.
+///
+/// A [`Checkpoint`] is delivered every [`checkpoint_interval`] blocks and
+/// after the last block. The pipeline can be resumed from the last
+/// checkpoint persisted with [`resume_from`].
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// use futures::StreamExt;
+/// use tendermint_rpc::{
+///     client::{BlockPipeline, PipelineItem},
+///     HttpClient,
+/// };
+///
+/// let client = HttpClient::new("http://127.0.0.1:26657")?;
+/// let mut items = BlockPipeline::new(&client, 1u32, 1_000_000u32)
+///     .concurrency(16)
+///     .resume_from(load_checkpoint()?)
+///     .stream();
+/// while let Some(item) = items.next().await {
+///     match item? {
+///         PipelineItem::Block(block) => index(block).await?,
+///         PipelineItem::Checkpoint(checkpoint) => save_checkpoint(checkpoint)?,
+///     }
+/// }
+/// ```
+///
+/// [`RateLimited`]: crate::error::ErrorDetail::RateLimited
+/// [`checkpoint_interval`]: BlockPipeline::checkpoint_interval
+/// [`resume_from`]: BlockPipeline::resume_from
+#[derive(Debug)]
+pub struct BlockPipeline<'a, C> {
+    client: &'a C,
+    start: Height,
+    end: Height,
+    concurrency: usize,
+    checkpoint_interval: u64,
+}
+
+impl<'a, C> BlockPipeline<'a, C> {
+    /// Fetch the blocks from height `start` to height `end` inclusive with
+    /// the given client.
+    pub fn new<H: Into<Height>>(client: &'a C, start: H, end: H) -> Self {
+        Self {
+            client,
+            start: start.into(),
+            end: end.into(),
+            concurrency: DEFAULT_PIPELINE_CONCURRENCY,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+        }
+    }
+
+    /// Fetch up to `concurrency` heights at once.
+    ///
+    /// ## Panics
+    ///
+    /// If `concurrency` is zero.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        assert!(
+            concurrency > 0,
+            "at least one height must be fetched at once"
+        );
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Deliver a checkpoint every `interval` blocks.
+    ///
+    /// ## Panics
+    ///
+    /// If `interval` is zero.
+    pub fn checkpoint_interval(mut self, interval: u64) -> Self {
+        assert!(interval > 0, "a checkpoint interval must not be zero");
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    /// Skip the blocks delivered before the given checkpoint was.
+    pub fn resume_from(mut self, checkpoint: Checkpoint) -> Self {
+        self.start = cmp::max(self.start, checkpoint.next_height);
+        self
+    }
+}
+
+struct State<'a, C> {
+    client: &'a C,
+    end: u64,
+    max_concurrency: usize,
+    checkpoint_interval: u64,
+    // The current number of heights fetched at once.
+    concurrency: usize,
+    // The heights fetched successfully since the concurrency last changed.
+    successes: usize,
+    // The next height never requested.
+    next: u64,
+    // The heights to retry, with the delay to wait before doing so.
+    retries: BTreeMap<u64, Duration>,
+    // The number of times each height was throttled.
+    throttled: BTreeMap<u64, u32>,
+    in_flight: FuturesUnordered<BoxFuture<'a, (u64, Result<FetchedBlock, Error>)>>,
+    fetched: BTreeMap<u64, FetchedBlock>,
+    // The height of the next block to deliver.
+    deliver: u64,
+    checkpoint: Option<Checkpoint>,
+    failed: bool,
+}
+
+impl<'a, C: Client + Sync> State<'a, C> {
+    fn fetch(
+        &self,
+        height: u64,
+        delay: Duration,
+    ) -> BoxFuture<'a, (u64, Result<FetchedBlock, Error>)> {
+        let client = self.client;
+        async move {
+            if !delay.is_zero() {
+                sleep(delay).await;
+            }
+            let h = Height::try_from(height).expect("heights come from a range of heights");
+            let fetched = future::try_join(client.block(h), client.block_results(h))
+                .await
+                .map(|(block, results)| FetchedBlock { block, results });
+            (height, fetched)
+        }
+        .boxed()
+    }
+
+    /// Request heights until as many as the current concurrency allows are
+    /// in flight. Heights are not requested too far ahead of the next one to
+    /// deliver, to bound the number of blocks held.
+    fn dispatch(&mut self) {
+        let lookahead = self.deliver + 2 * self.max_concurrency as u64;
+        while self.in_flight.len() < self.concurrency {
+            let retry = self.retries.keys().next().copied();
+            let (height, delay) = match retry {
+                Some(height) => (height, self.retries.remove(&height).unwrap_or_default()),
+                None if self.next <= self.end && self.next < lookahead => {
+                    self.next += 1;
+                    (self.next - 1, Duration::ZERO)
+                },
+                None => break,
+            };
+            let fetch = self.fetch(height, delay);
+            self.in_flight.push(fetch);
+        }
+    }
+
+    fn on_fetched(
+        &mut self,
+        height: u64,
+        fetched: Result<FetchedBlock, Error>,
+    ) -> Result<(), Error> {
+        let e = match fetched {
+            Ok(fetched) => {
+                self.fetched.insert(height, fetched);
+                self.throttled.remove(&height);
+                self.successes += 1;
+                if self.successes >= self.concurrency && self.concurrency < self.max_concurrency {
+                    self.concurrency += 1;
+                    self.successes = 0;
+                }
+                return Ok(());
+            },
+            Err(e) => e,
+        };
+
+        let retry_after = throttled(&e).ok_or_else(|| e.clone())?;
+        let retries = self.throttled.entry(height).or_default();
+        *retries += 1;
+        if *retries > MAX_THROTTLED_RETRIES {
+            return Err(e);
+        }
+        let backoff = INITIAL_BACKOFF
+            .saturating_mul(2_u32.saturating_pow(*retries - 1))
+            .min(MAX_BACKOFF);
+        let delay = backoff.max(retry_after);
+
+        self.concurrency = cmp::max(self.concurrency / 2, 1);
+        self.successes = 0;
+        debug!(
+            "node throttled the request for height {height}, retrying in {delay:?} and fetching {} heights at once: {e}",
+            self.concurrency
+        );
+        self.retries.insert(height, delay);
+        Ok(())
+    }
+}
+
+/// How long to wait before retrying a request which failed with the given
+/// error, if the node throttled it.
+fn throttled(e: &Error) -> Option<Duration> {
+    match e.detail() {
+        ErrorDetail::RateLimited(e) => Some(e.retry_after),
+        ErrorDetail::Timeout(_) | ErrorDetail::RequestTimeout(_) => Some(Duration::ZERO),
+        ErrorDetail::InvalidResponse(e) if matches!(e.context.status, 429 | 503) => {
+            Some(Duration::ZERO)
+        },
+        _ => None,
+    }
+}
+
+impl<'a, C: Client + Sync> BlockPipeline<'a, C> {
+    /// Start fetching the blocks, delivering them as the returned stream is
+    /// consumed.
+    pub fn stream(self) -> BoxStream<'a, Result<PipelineItem, Error>> {
+        let state = State {
+            client: self.client,
+            end: self.end.value(),
+            max_concurrency: self.concurrency,
+            checkpoint_interval: self.checkpoint_interval,
+            concurrency: self.concurrency,
+            successes: 0,
+            next: self.start.value(),
+            retries: BTreeMap::new(),
+            throttled: BTreeMap::new(),
+            in_flight: FuturesUnordered::new(),
+            fetched: BTreeMap::new(),
+            deliver: self.start.value(),
+            checkpoint: None,
+            failed: false,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                if state.failed {
+                    return None;
+                }
+                if let Some(checkpoint) = state.checkpoint.take() {
+                    return Some((Ok(PipelineItem::Checkpoint(checkpoint)), state));
+                }
+                if let Some(fetched) = state.fetched.remove(&state.deliver) {
+                    state.deliver += 1;
+                    if state.deliver % state.checkpoint_interval == 0 || state.deliver > state.end {
+                        state.checkpoint = Some(Checkpoint {
+                            next_height: Height::try_from(state.deliver).ok()?,
+                        });
+                    }
+                    return Some((Ok(PipelineItem::Block(fetched)), state));
+                }
+                if state.deliver > state.end {
+                    return None;
+                }
+
+                state.dispatch();
+                let (height, fetched) = state.in_flight.next().await?;
+                if let Err(e) = state.on_fetched(height, fetched) {
+                    state.failed = true;
+                    return Some((Err(e), state));
+                }
+            }
+        })
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::{Method, Response, SimpleRequest};
+
+    const BLOCK: &str =
+        include_str!("../../tests/kvstore_fixtures/v0_38/incoming/block_at_height_10.json");
+    const BLOCK_RESULTS: &str =
+        include_str!("../../tests/kvstore_fixtures/v0_38/incoming/block_results_at_height_10.json");
+
+    /// A node answering with fixtures for the requested heights above 1, the
+    /// higher heights faster, and throttling the first requests for some heights.
+    #[derive(Default)]
+    struct Node {
+        throttled: Mutex<Vec<u64>>,
+        failing: Option<u64>,
+        requests: AtomicU32,
+    }
+
+    impl Node {
+        fn throttling(heights: &[u64]) -> Self {
+            Self {
+                throttled: Mutex::new(heights.to_vec()),
+                ..Self::default()
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Client for Node {
+        async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+        where
+            R: SimpleRequest,
+        {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            let height = request.height().unwrap().value();
+            sleep(Duration::from_millis(100 - height)).await;
+
+            let mut throttled = self.throttled.lock().unwrap();
+            if let Some(i) = throttled.iter().position(|h| *h == height) {
+                throttled.remove(i);
+                return Err(Error::rate_limited("block".into(), Duration::from_secs(1)));
+            }
+            if self.failing == Some(height) {
+                return Err(Error::client_internal("unavailable".into()));
+            }
+
+            let fixture = match request.method() {
+                Method::Block => BLOCK,
+                Method::BlockResults => BLOCK_RESULTS,
+                method => panic!("unexpected request: {method}"),
+            };
+            let json =
+                fixture.replacen(r#""height": "10""#, &format!(r#""height": "{height}""#), 1);
+            R::Response::from_string(json).map(Into::into)
+        }
+    }
+
+    async fn collect(pipeline: BlockPipeline<'_, Node>) -> Vec<Result<PipelineItem, Error>> {
+        pipeline.stream().collect().await
+    }
+
+    fn summary(items: &[Result<PipelineItem, Error>]) -> Vec<String> {
+        items
+            .iter()
+            .map(|item| match item {
+                Ok(PipelineItem::Block(b)) => {
+                    assert_eq!(b.height(), b.results.height);
+                    format!("b{}", b.height())
+                },
+                Ok(PipelineItem::Checkpoint(c)) => format!("c{}", c.next_height),
+                Err(_) => "err".to_string(),
+            })
+            .collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn delivers_blocks_in_order_with_checkpoints() {
+        let node = Node::default();
+        let items = collect(BlockPipeline::new(&node, 2u32, 8u32).checkpoint_interval(3)).await;
+        assert_eq!(
+            summary(&items),
+            ["b2", "c3", "b3", "b4", "b5", "c6", "b6", "b7", "b8", "c9"]
+        );
+        assert_eq!(node.requests.load(Ordering::SeqCst), 14);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn resumes_from_a_checkpoint() {
+        let node = Node::default();
+        let checkpoint = Checkpoint {
+            next_height: 5u32.into(),
+        };
+        let items = collect(BlockPipeline::new(&node, 2u32, 6u32).resume_from(checkpoint)).await;
+        assert_eq!(summary(&items), ["b5", "b6", "c7"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_throttled_heights_with_less_concurrency() {
+        let node = Node::throttling(&[3, 4, 4]);
+        let pipeline = BlockPipeline::new(&node, 2u32, 6u32).concurrency(4);
+        let items = collect(pipeline).await;
+        assert_eq!(summary(&items), ["b2", "b3", "b4", "b5", "b6", "c7"]);
+        // Three throttled requests, each with one of a pair made for nothing.
+        assert_eq!(node.requests.load(Ordering::SeqCst), 10 + 6);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stops_at_the_first_failure() {
+        let node = Node {
+            failing: Some(4),
+            ..Node::default()
+        };
+        let items = collect(BlockPipeline::new(&node, 2u32, 6u32).concurrency(1)).await;
+        assert_eq!(summary(&items), ["b2", "b3", "err"]);
+    }
+}
@@ -0,0 +1,553 @@
+//! Client-side rate limiting of the requests made to a node.
+
+use alloc::{collections::BTreeMap, sync::Arc};
+use core::{fmt, time::Duration};
+
+use async_trait::async_trait;
+use tendermint::{block::Height, evidence::Evidence, Hash};
+use tokio::{
+    sync::Mutex,
+    time::{sleep, Instant},
+};
+
+use crate::{
+    client::{Client, Subscription, SubscriptionClient},
+    endpoint,
+    prelude::*,
+    query::Query,
+    Error, Method, SimpleRequest,
+};
+
+/// A limit on the rate of requests.
+///
+/// Up to `burst` requests can be made at once, `requests` by default, after
+/// which requests are let through at an even pace of `requests` per `period`.
+/// This is the behavior of a token bucket holding up to `burst` tokens, and
+/// refilled with `requests` tokens per `period`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    requests: u32,
+    period: Duration,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Allow `requests` requests per `period`.
+    ///
+    /// ## Panics
+    ///
+    /// If `requests` is zero or `period` is zero.
+    pub fn new(requests: u32, period: Duration) -> Self {
+        assert!(requests > 0, "a rate limit must allow at least one request");
+        assert!(
+            !period.is_zero(),
+            "a rate limit must have a non-zero period"
+        );
+        Self {
+            requests,
+            period,
+            burst: requests,
+        }
+    }
+
+    /// Allow up to `burst` requests to be made at once.
+    ///
+    /// ## Panics
+    ///
+    /// If `burst` is zero.
+    pub fn burst(mut self, burst: u32) -> Self {
+        assert!(
+            burst > 0,
+            "a rate limit must allow bursts of at least one request"
+        );
+        self.burst = burst;
+        self
+    }
+
+    /// Allow `requests` requests per second.
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    /// Allow `requests` requests per minute.
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    pub fn requests(&self) -> u32 {
+        self.requests
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    pub fn burst_size(&self) -> u32 {
+        self.burst
+    }
+
+    /// The interval between two requests at the sustained rate.
+    fn interval(&self) -> Duration {
+        self.period / self.requests
+    }
+}
+
+/// What a [`RateLimitedClient`] does with a request exceeding its limits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LimitPolicy {
+    /// Hold the request until the limits allow it to be made.
+    #[default]
+    Queue,
+    /// Fail the request with a [`RateLimited`] error telling how long to wait
+    /// before retrying.
+    ///
+    /// [`RateLimited`]: crate::error::ErrorDetail::RateLimited
+    Reject,
+}
+
+/// Enforces a [`RateLimit`] using the generic cell rate algorithm.
+///
+/// The lock is held by waiting requests, and being FIFO, lets them through in
+/// the order they arrived.
+#[derive(Debug)]
+struct Limiter {
+    limit: RateLimit,
+    /// The theoretical arrival time of the next request, if any was made.
+    next: Mutex<Option<Instant>>,
+}
+
+impl Limiter {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            next: Mutex::new(None),
+        }
+    }
+
+    /// How long a request arriving at `now` has to wait.
+    fn delay(&self, next: Option<Instant>, now: Instant) -> Duration {
+        let tolerance = self.limit.interval() * (self.limit.burst - 1);
+        match next {
+            Some(next) => next
+                .saturating_duration_since(now)
+                .saturating_sub(tolerance),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// The theoretical arrival time following a request made at `at`.
+    fn advance(&self, next: Option<Instant>, at: Instant) -> Instant {
+        let start = match next {
+            Some(next) if next > at => next,
+            _ => at,
+        };
+        start + self.limit.interval()
+    }
+
+    async fn wait(&self) {
+        let mut next = self.next.lock().await;
+        let now = Instant::now();
+        let delay = self.delay(*next, now);
+        if !delay.is_zero() {
+            sleep(delay).await;
+        }
+        *next = Some(self.advance(*next, now + delay));
+    }
+}
+
+/// A handle on the quota of requests allowed by a [`RateLimit`], which can be
+/// shared between several [`RateLimitedClient`]s.
+///
+/// Clones of a limiter share the same quota. Giving clones of a limiter to
+/// the clients wrapping an HTTP and a WebSocket client to the same node
+/// enforces a single limit on the requests made through either.
+///
+/// ```rust,ignore
+/// use tendermint_rpc::client::{RateLimit, RateLimitedClient, RateLimiter};
+///
+/// let limiter = RateLimiter::new(RateLimit::per_second(10).burst(20));
+/// let http = RateLimitedClient::new(http_client).global_limit(limiter.clone());
+/// let ws = RateLimitedClient::new(ws_client).global_limit(limiter);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RateLimiter(Arc<Limiter>);
+
+impl RateLimiter {
+    /// Create a limiter enforcing the given limit.
+    pub fn new(limit: RateLimit) -> Self {
+        Self(Arc::new(Limiter::new(limit)))
+    }
+
+    /// The limit enforced by this limiter.
+    pub fn limit(&self) -> RateLimit {
+        self.0.limit
+    }
+
+    /// Wait until the limit allows a request to be made, and count it.
+    pub async fn wait(&self) {
+        self.0.wait().await
+    }
+}
+
+impl From<RateLimit> for RateLimiter {
+    fn from(limit: RateLimit) -> Self {
+        Self::new(limit)
+    }
+}
+
+/// A client decorator enforcing rate limits on the requests made through the
+/// wrapped client.
+///
+/// A global limit applies to all requests, and each method can additionally
+/// be given its own limit. A request is counted against both. Requests that
+/// issue several calls, e.g. [`Client::all_validators`], are limited per
+/// call.
+///
+/// By default, requests exceeding a limit are queued and made in the order
+/// they were issued once the limits allow it. With [`LimitPolicy::Reject`],
+/// they fail immediately instead.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// use tendermint_rpc::{
+///     client::{RateLimit, RateLimitedClient},
+///     Client, HttpClient, Method,
+/// };
+///
+/// let client = RateLimitedClient::new(HttpClient::new("http://127.0.0.1:26657")?)
+///     .global_limit(RateLimit::per_second(20))
+///     .method_limit(Method::TxSearch, RateLimit::per_second(2));
+///
+/// let status = client.status().await?;
+/// ```
+pub struct RateLimitedClient<C> {
+    inner: C,
+    global: Option<RateLimiter>,
+    methods: BTreeMap<Method, RateLimiter>,
+    policy: LimitPolicy,
+}
+
+impl<C: fmt::Debug> fmt::Debug for RateLimitedClient<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitedClient")
+            .field("inner", &self.inner)
+            .field("global", &self.global.as_ref().map(RateLimiter::limit))
+            .field(
+                "methods",
+                &self
+                    .methods
+                    .iter()
+                    .map(|(m, l)| (*m, l.limit()))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+impl<C> RateLimitedClient<C> {
+    /// Wrap the given client, without any limits.
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            global: None,
+            methods: BTreeMap::new(),
+            policy: LimitPolicy::default(),
+        }
+    }
+
+    /// Limit the rate of all requests, with the given limit or a limiter
+    /// shared with other clients.
+    pub fn global_limit(mut self, limit: impl Into<RateLimiter>) -> Self {
+        self.global = Some(limit.into());
+        self
+    }
+
+    /// Limit the rate of the requests for the given method, on top of the
+    /// global limit.
+    pub fn method_limit(mut self, method: Method, limit: impl Into<RateLimiter>) -> Self {
+        self.methods.insert(method, limit.into());
+        self
+    }
+
+    /// Set what to do with requests exceeding the limits.
+    pub fn policy(mut self, policy: LimitPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// Wait until a request for the given method can be made, or fail if the
+    /// request is to be rejected.
+    async fn acquire(&self, method: Method) -> Result<(), Error> {
+        let limiters = [self.methods.get(&method), self.global.as_ref()];
+        match self.policy {
+            LimitPolicy::Queue => {
+                for limiter in limiters.into_iter().flatten() {
+                    limiter.0.wait().await;
+                }
+                Ok(())
+            },
+            LimitPolicy::Reject => {
+                // Both limits are checked before either is committed, so that
+                // a rejected request does not use up any quota.
+                let mut guards = Vec::with_capacity(2);
+                for limiter in limiters.into_iter().flatten() {
+                    guards.push((&limiter.0, limiter.0.next.lock().await));
+                }
+                let now = Instant::now();
+                let retry_after = guards
+                    .iter()
+                    .map(|(limiter, next)| limiter.delay(**next, now))
+                    .max()
+                    .unwrap_or_default();
+                if !retry_after.is_zero() {
+                    return Err(Error::rate_limited(method.to_string(), retry_after));
+                }
+                for (limiter, next) in guards.iter_mut() {
+                    **next = Some(limiter.advance(**next, now));
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl<C> Client for RateLimitedClient<C>
+where
+    C: Client + Send + Sync,
+{
+    async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest,
+    {
+        self.acquire(request.method()).await?;
+        self.inner.perform(request).await
+    }
+
+    // The methods whose requests depend on the compatibility mode of the
+    // wrapped client are forwarded to it rather than going through `perform`.
+
+    async fn block_results<H>(&self, height: H) -> Result<endpoint::block_results::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        self.acquire(Method::BlockResults).await?;
+        self.inner.block_results(height).await
+    }
+
+    async fn latest_block_results(&self) -> Result<endpoint::block_results::Response, Error> {
+        self.acquire(Method::BlockResults).await?;
+        self.inner.latest_block_results().await
+    }
+
+    async fn header<H>(&self, height: H) -> Result<endpoint::header::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        self.acquire(Method::Header).await?;
+        self.inner.header(height).await
+    }
+
+    async fn latest_header(&self) -> Result<endpoint::header::Response, Error> {
+        self.acquire(Method::Header).await?;
+        self.inner.latest_header().await
+    }
+
+    async fn header_by_hash(
+        &self,
+        hash: Hash,
+    ) -> Result<endpoint::header_by_hash::Response, Error> {
+        self.acquire(Method::HeaderByHash).await?;
+        self.inner.header_by_hash(hash).await
+    }
+
+    async fn broadcast_evidence(&self, e: Evidence) -> Result<endpoint::evidence::Response, Error> {
+        self.acquire(Method::BroadcastEvidence).await?;
+        self.inner.broadcast_evidence(e).await
+    }
+
+    async fn tx(&self, hash: Hash, prove: bool) -> Result<endpoint::tx::Response, Error> {
+        self.acquire(Method::Tx).await?;
+        self.inner.tx(hash, prove).await
+    }
+
+    async fn tx_search_with(
+        &self,
+        request: endpoint::tx_search::Request,
+    ) -> Result<endpoint::tx_search::Response, Error> {
+        self.acquire(Method::TxSearch).await?;
+        self.inner.tx_search_with(request).await
+    }
+
+    async fn broadcast_tx_commit<T>(
+        &self,
+        tx: T,
+    ) -> Result<endpoint::broadcast::tx_commit::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        self.acquire(Method::BroadcastTxCommit).await?;
+        self.inner.broadcast_tx_commit(tx).await
+    }
+
+    async fn check_tx<T>(&self, tx: T) -> Result<endpoint::check_tx::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        self.acquire(Method::CheckTx).await?;
+        self.inner.check_tx(tx).await
+    }
+}
+
+#[async_trait]
+impl<C> SubscriptionClient for RateLimitedClient<C>
+where
+    C: SubscriptionClient + Send + Sync,
+{
+    async fn subscribe(&self, query: Query) -> Result<Subscription, Error> {
+        self.acquire(Method::Subscribe).await?;
+        self.inner.subscribe(query).await
+    }
+
+    async fn unsubscribe(&self, query: Query) -> Result<(), Error> {
+        self.acquire(Method::Unsubscribe).await?;
+        self.inner.unsubscribe(query).await
+    }
+
+    // Releasing subscriptions is not limited, not to hold back their cleanup.
+    async fn release(&self, subscription: Subscription) -> Result<(), Error> {
+        self.inner.release(subscription).await
+    }
+
+    fn close(self) -> Result<(), Error> {
+        self.inner.close()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::{MockClient, MockRequestMethodMatcher},
+        error::ErrorDetail,
+    };
+
+    const HEALTH: &str = r#"{"jsonrpc":"2.0","id":"","result":{}}"#;
+    const NET_INFO: &str = r#"{
+        "jsonrpc": "2.0",
+        "id": "",
+        "result": {
+            "listening": true,
+            "listeners": [],
+            "n_peers": "0",
+            "peers": []
+        }
+    }"#;
+
+    fn mock_client() -> MockClient<MockRequestMethodMatcher> {
+        let matcher = MockRequestMethodMatcher::default()
+            .map(Method::Health, Ok(HEALTH.to_string()))
+            .map(Method::NetInfo, Ok(NET_INFO.to_string()));
+        MockClient::new(matcher).0
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn queues_requests_over_the_limit() {
+        let client = RateLimitedClient::new(mock_client()).global_limit(RateLimit::per_second(2));
+
+        let start = Instant::now();
+        client.health().await.unwrap();
+        client.health().await.unwrap();
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        client.net_info().await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+        client.health().await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rejects_requests_over_the_limit() {
+        let client = RateLimitedClient::new(mock_client())
+            .method_limit(Method::Health, RateLimit::per_second(1))
+            .policy(LimitPolicy::Reject);
+
+        client.health().await.unwrap();
+        match client.health().await.unwrap_err().detail() {
+            ErrorDetail::RateLimited(e) => {
+                assert_eq!(e.method, "health");
+                assert_eq!(e.retry_after, Duration::from_secs(1));
+            },
+            e => panic!("unexpected error: {e:?}"),
+        }
+
+        // Other methods are not limited.
+        client.net_info().await.unwrap();
+        client.net_info().await.unwrap();
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        client.health().await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rejected_requests_use_no_quota() {
+        let client = RateLimitedClient::new(mock_client())
+            .global_limit(RateLimit::per_second(2))
+            .method_limit(Method::Health, RateLimit::per_second(1))
+            .policy(LimitPolicy::Reject);
+
+        client.health().await.unwrap();
+        client.health().await.unwrap_err();
+        client.net_info().await.unwrap();
+        client.net_info().await.unwrap_err();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bursts_are_limited() {
+        let client = RateLimitedClient::new(mock_client())
+            .global_limit(RateLimit::per_second(2).burst(4))
+            .policy(LimitPolicy::Reject);
+
+        for _ in 0..4 {
+            client.health().await.unwrap();
+        }
+        client.health().await.unwrap_err();
+
+        // The quota is refilled at the sustained rate.
+        tokio::time::advance(Duration::from_millis(500)).await;
+        client.health().await.unwrap();
+        client.health().await.unwrap_err();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn limiters_can_be_shared() {
+        let limiter = RateLimiter::new(RateLimit::per_second(2));
+        let (client, driver) = MockClient::new(MockRequestMethodMatcher::default());
+        tokio::spawn(driver.run());
+        let subscriptions = RateLimitedClient::new(client).global_limit(limiter.clone());
+        let client = RateLimitedClient::new(mock_client()).global_limit(limiter);
+
+        let start = Instant::now();
+        client.health().await.unwrap();
+        subscriptions
+            .subscribe(crate::query::EventType::NewBlock.into())
+            .await
+            .unwrap();
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // Both clients used up the quota.
+        client.health().await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,429 @@
+//! Automatic retries of the requests to a node failing with transient errors.
+
+use alloc::collections::BTreeSet;
+use core::{fmt, future::Future, time::Duration};
+
+use async_trait::async_trait;
+use tendermint::{block::Height, evidence::Evidence, Hash};
+use tokio::time::sleep;
+use tracing::debug;
+
+use crate::{
+    client::Client, endpoint, error::ErrorDetail, prelude::*, response_error::Code, Error, Method,
+    SimpleRequest,
+};
+
+/// The default number of times a failed request is retried.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// The default delay before the first retry of a request.
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// The default maximum delay between two attempts at a request.
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// A client decorator retrying the requests made through the wrapped client
+/// when they fail with a transient error.
+///
+/// Requests failing because of the transport, e.g. on connection errors or
+/// timeouts, are retried, as are those failing with a [`RateLimited`] error
+/// or with one of the JSON-RPC error codes set with
+/// [`RetryingClient::retry_on_code`], which are by default the codes of the
+/// errors raised for HTTP and WebSocket responses the client cannot process.
+///
+/// Retries are delayed with an exponential backoff, which is randomized so
+/// that clients failing at the same time do not retry in lockstep, and which
+/// is at least as long as requested by [`RateLimited`] errors.
+///
+/// As they are not idempotent, the requests broadcasting transactions and
+/// evidence are not retried unless explicitly allowed with
+/// [`RetryingClient::allow_retries`]. Neither are the requests to custom
+/// methods ([`Method::Custom`]), which may not be idempotent either.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// use tendermint_rpc::{client::RetryingClient, Client, HttpClient};
+///
+/// let client = RetryingClient::new(HttpClient::new("http://127.0.0.1:26657")?)
+///     .max_retries(5);
+///
+/// let status = client.status().await?;
+/// ```
+///
+/// [`RateLimited`]: crate::error::ErrorDetail::RateLimited
+pub struct RetryingClient<C> {
+    inner: C,
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    codes: BTreeSet<Code>,
+    never_retried: BTreeSet<Method>,
+}
+
+impl<C: fmt::Debug> fmt::Debug for RetryingClient<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryingClient")
+            .field("inner", &self.inner)
+            .field("max_retries", &self.max_retries)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("codes", &self.codes)
+            .field("never_retried", &self.never_retried)
+            .finish()
+    }
+}
+
+impl<C> RetryingClient<C> {
+    /// Wrap the given client, retrying failed requests up to
+    /// [`DEFAULT_MAX_RETRIES`] times.
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            codes: [Code::HttpError, Code::WebSocketError]
+                .into_iter()
+                .collect(),
+            never_retried: [
+                Method::BroadcastTxAsync,
+                Method::BroadcastTxSync,
+                Method::BroadcastTxCommit,
+                Method::BroadcastEvidence,
+                Method::Custom,
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    /// Set the number of times a failed request is retried.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the delay before the first retry of a request, which doubles with
+    /// every retry up to `max`.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Also retry the requests failing with the given JSON-RPC error code.
+    pub fn retry_on_code(mut self, code: Code) -> Self {
+        self.codes.insert(code);
+        self
+    }
+
+    /// Never retry the requests for the given method.
+    pub fn never_retry(mut self, method: Method) -> Self {
+        self.never_retried.insert(method);
+        self
+    }
+
+    /// Retry the requests for the given method, e.g. for one of the broadcast
+    /// methods which are not retried by default.
+    pub fn allow_retries(mut self, method: Method) -> Self {
+        self.never_retried.remove(&method);
+        self
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// How long to wait before retrying a request which failed with the
+    /// given error, or `None` if it is not to be retried.
+    fn retry_after(&self, e: &Error) -> Option<Duration> {
+        match e.detail() {
+            ErrorDetail::Io(_)
+            | ErrorDetail::Http(_)
+            | ErrorDetail::Hyper(_)
+            | ErrorDetail::WebSocket(_)
+            | ErrorDetail::ConnectionStale(_)
+            | ErrorDetail::Tungstenite(_)
+            | ErrorDetail::Timeout(_)
+            | ErrorDetail::RequestTimeout(_) => Some(Duration::ZERO),
+            ErrorDetail::RateLimited(e) => Some(e.retry_after),
+            _ => match e.response_error() {
+                Some(e) if self.codes.contains(&e.code()) => Some(Duration::ZERO),
+                _ => None,
+            },
+        }
+    }
+
+    /// The randomized delay before the given retry, starting from 1.
+    fn backoff_for(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2_u32.saturating_pow(retry - 1))
+            .min(self.max_backoff);
+
+        // Wait between half and all of the backoff.
+        let mut random = [0; 4];
+        let jitter = match getrandom::getrandom(&mut random) {
+            Ok(()) => f64::from(u32::from_le_bytes(random)) / f64::from(u32::MAX),
+            Err(_) => 1.0,
+        };
+        backoff / 2 + (backoff / 2).mul_f64(jitter)
+    }
+
+    async fn retry<T, F, Fut>(&self, method: Method, attempt: F) -> Result<T, Error>
+    where
+        F: Fn() -> Fut + Send,
+        Fut: Future<Output = Result<T, Error>> + Send,
+    {
+        let mut retries = 0;
+        loop {
+            let e = match attempt().await {
+                Ok(output) => return Ok(output),
+                Err(e) => e,
+            };
+
+            if retries == self.max_retries || self.never_retried.contains(&method) {
+                return Err(e);
+            }
+            let retry_after = match self.retry_after(&e) {
+                Some(retry_after) => retry_after,
+                None => return Err(e),
+            };
+
+            retries += 1;
+            let delay = self.backoff_for(retries).max(retry_after);
+            debug!("retrying {method} in {delay:?} after error: {e}");
+            sleep(delay).await;
+        }
+    }
+}
+
+#[async_trait]
+impl<C> Client for RetryingClient<C>
+where
+    C: Client + Send + Sync,
+{
+    async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest,
+    {
+        // Requests are not `Clone`, but can be rebuilt from their JSON form
+        // for every attempt.
+        let method = request.method();
+        let request = request.into_json();
+        self.retry(method, || {
+            let request = R::from_string(&request);
+            async move { self.inner.perform(request?).await }
+        })
+        .await
+    }
+
+    // The methods whose requests depend on the compatibility mode of the
+    // wrapped client are forwarded to it rather than going through `perform`.
+
+    async fn block_results<H>(&self, height: H) -> Result<endpoint::block_results::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        let height = height.into();
+        self.retry(Method::BlockResults, || self.inner.block_results(height))
+            .await
+    }
+
+    async fn latest_block_results(&self) -> Result<endpoint::block_results::Response, Error> {
+        self.retry(Method::BlockResults, || self.inner.latest_block_results())
+            .await
+    }
+
+    async fn header<H>(&self, height: H) -> Result<endpoint::header::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        let height = height.into();
+        self.retry(Method::Header, || self.inner.header(height))
+            .await
+    }
+
+    async fn latest_header(&self) -> Result<endpoint::header::Response, Error> {
+        self.retry(Method::Header, || self.inner.latest_header())
+            .await
+    }
+
+    async fn header_by_hash(
+        &self,
+        hash: Hash,
+    ) -> Result<endpoint::header_by_hash::Response, Error> {
+        self.retry(Method::HeaderByHash, || self.inner.header_by_hash(hash))
+            .await
+    }
+
+    async fn broadcast_evidence(&self, e: Evidence) -> Result<endpoint::evidence::Response, Error> {
+        self.retry(Method::BroadcastEvidence, || {
+            self.inner.broadcast_evidence(e.clone())
+        })
+        .await
+    }
+
+    async fn tx(&self, hash: Hash, prove: bool) -> Result<endpoint::tx::Response, Error> {
+        self.retry(Method::Tx, || self.inner.tx(hash, prove)).await
+    }
+
+    async fn tx_search_with(
+        &self,
+        request: endpoint::tx_search::Request,
+    ) -> Result<endpoint::tx_search::Response, Error> {
+        self.retry(Method::TxSearch, || {
+            self.inner.tx_search_with(request.clone())
+        })
+        .await
+    }
+
+    async fn broadcast_tx_commit<T>(
+        &self,
+        tx: T,
+    ) -> Result<endpoint::broadcast::tx_commit::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        let tx = tx.into();
+        self.retry(Method::BroadcastTxCommit, || {
+            self.inner.broadcast_tx_commit(tx.clone())
+        })
+        .await
+    }
+
+    async fn check_tx<T>(&self, tx: T) -> Result<endpoint::check_tx::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        let tx = tx.into();
+        self.retry(Method::CheckTx, || self.inner.check_tx(tx.clone()))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+    use crate::{error::ErrorDetail, response_error::ResponseError};
+
+    /// A client failing the given number of times with the given error before
+    /// succeeding.
+    struct FlakyClient {
+        failures: u32,
+        error: fn() -> Error,
+        attempts: AtomicU32,
+    }
+
+    impl FlakyClient {
+        fn new(failures: u32, error: fn() -> Error) -> Self {
+            Self {
+                failures,
+                error,
+                attempts: AtomicU32::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Client for FlakyClient {
+        async fn perform<R>(&self, _request: R) -> Result<R::Output, Error>
+        where
+            R: SimpleRequest,
+        {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                Err((self.error)())
+            } else {
+                Err(Error::client_internal("succeeded".to_string()))
+            }
+        }
+    }
+
+    fn timeout() -> Error {
+        Error::timeout(Duration::from_secs(1))
+    }
+
+    fn succeeded(e: &Error) -> bool {
+        matches!(e.detail(), ErrorDetail::ClientInternal(e) if e.reason == "succeeded")
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_transient_errors() {
+        let client = RetryingClient::new(FlakyClient::new(3, timeout));
+        assert!(succeeded(&client.health().await.unwrap_err()));
+        assert_eq!(client.inner().attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_after_max_retries() {
+        let client = RetryingClient::new(FlakyClient::new(3, timeout)).max_retries(2);
+        let e = client.health().await.unwrap_err();
+        assert!(matches!(e.detail(), ErrorDetail::Timeout(_)));
+        assert_eq!(client.inner().attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn does_not_retry_other_errors() {
+        let client = RetryingClient::new(FlakyClient::new(1, || {
+            Error::response(ResponseError::invalid_params("bad height"))
+        }));
+        assert!(!succeeded(&client.health().await.unwrap_err()));
+        assert_eq!(client.inner().attempts.load(Ordering::SeqCst), 1);
+
+        let client = RetryingClient::new(FlakyClient::new(1, || {
+            Error::response(ResponseError::invalid_params("bad height"))
+        }))
+        .retry_on_code(Code::InvalidParams);
+        assert!(succeeded(&client.health().await.unwrap_err()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn does_not_retry_broadcasts() {
+        let client = RetryingClient::new(FlakyClient::new(1, timeout));
+        assert!(!succeeded(
+            &client.broadcast_tx_sync("tx").await.unwrap_err()
+        ));
+        assert_eq!(client.inner().attempts.load(Ordering::SeqCst), 1);
+
+        let client = RetryingClient::new(FlakyClient::new(1, timeout))
+            .allow_retries(Method::BroadcastTxSync);
+        assert!(succeeded(
+            &client.broadcast_tx_sync("tx").await.unwrap_err()
+        ));
+
+        let client = RetryingClient::new(FlakyClient::new(1, timeout)).never_retry(Method::Health);
+        assert!(!succeeded(&client.health().await.unwrap_err()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backs_off_exponentially() {
+        let client = RetryingClient::new(FlakyClient::new(3, timeout))
+            .backoff(Duration::from_secs(1), Duration::from_secs(3));
+
+        let start = tokio::time::Instant::now();
+        client.health().await.unwrap_err();
+        // Waiting between half and all of 1s, 2s and 3s.
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_secs(3), "{elapsed:?}");
+        assert!(elapsed <= Duration::from_secs(6), "{elapsed:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_as_long_as_rate_limited() {
+        let client = RetryingClient::new(FlakyClient::new(1, || {
+            Error::rate_limited("health".to_string(), Duration::from_secs(10))
+        }));
+
+        let start = tokio::time::Instant::now();
+        assert!(succeeded(&client.health().await.unwrap_err()));
+        assert!(start.elapsed() >= Duration::from_secs(10));
+    }
+}
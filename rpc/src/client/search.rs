@@ -0,0 +1,161 @@
+//! Streams walking through all the pages of search results.
+
+use alloc::collections::{BTreeSet, VecDeque};
+use core::future::Future;
+
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+
+use crate::{error::ErrorDetail, prelude::*, Error};
+
+/// The number of results requested per page, which is the most the node
+/// returns.
+pub const SEARCH_PER_PAGE: u8 = 100;
+
+struct State<F, T, K> {
+    fetch: F,
+    key: fn(&T) -> K,
+    next_page: Option<u32>,
+    page: VecDeque<T>,
+    seen: BTreeSet<K>,
+}
+
+/// Stream the results of a search, fetching pages with `fetch` as the
+/// results are consumed.
+///
+/// Results are identified with `key`, so that those moving to the next page
+/// as results are added while walking through the pages are not returned
+/// twice. Walking stops at the first page with fewer results than requested,
+/// or once past the total number of results as last reported by the node.
+pub(crate) fn paginate<'a, F, Fut, T, K>(
+    fetch: F,
+    key: fn(&T) -> K,
+) -> BoxStream<'a, Result<T, Error>>
+where
+    F: Fn(u32, u8) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<(Vec<T>, u32), Error>> + Send + 'a,
+    T: Send + 'a,
+    K: Ord + Send + 'a,
+{
+    let state = State {
+        fetch,
+        key,
+        next_page: Some(1),
+        page: VecDeque::new(),
+        seen: BTreeSet::new(),
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(result) = state.page.pop_front() {
+                return Some((Ok(result), state));
+            }
+
+            let page = state.next_page?;
+            match (state.fetch)(page, SEARCH_PER_PAGE).await {
+                Ok((results, total_count)) => {
+                    let walked = u64::from(page) * u64::from(SEARCH_PER_PAGE);
+                    let last = results.len() < usize::from(SEARCH_PER_PAGE)
+                        || walked >= u64::from(total_count);
+                    state.next_page = if last { None } else { Some(page + 1) };
+
+                    let (key, seen) = (state.key, &mut state.seen);
+                    state
+                        .page
+                        .extend(results.into_iter().filter(|r| seen.insert(key(r))));
+                },
+                // Results were removed since the previous page was fetched.
+                Err(e) if page > 1 && is_page_out_of_range(&e) => return None,
+                Err(e) => {
+                    state.next_page = None;
+                    return Some((Err(e), state));
+                },
+            }
+        }
+    })
+    .boxed()
+}
+
+fn is_page_out_of_range(e: &Error) -> bool {
+    matches!(e.detail(), ErrorDetail::PageOutOfRange(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::response_error::ResponseError;
+
+    type Page = futures::future::Ready<Result<(Vec<u32>, u32), Error>>;
+
+    /// Fetch pages of the given results, calling `between_pages` after each.
+    fn pages(
+        results: Vec<u32>,
+        between_pages: impl Fn(&mut Vec<u32>) + Send + Sync,
+    ) -> impl Fn(u32, u8) -> Page + Send {
+        let results = Mutex::new(results);
+        move |page, per_page| {
+            let mut results = results.lock().unwrap();
+            let total = results.len() as u32;
+            let start = (page - 1) as usize * per_page as usize;
+            let response = if start >= results.len() && page > 1 {
+                Err(Error::from_response(ResponseError::server_error(format!(
+                    "page should be within [1, {}] range, given {page}",
+                    page - 1,
+                ))))
+            } else {
+                let end = results.len().min(start + per_page as usize);
+                Ok((results[start..end].to_vec(), total))
+            };
+            between_pages(&mut results);
+            futures::future::ready(response)
+        }
+    }
+
+    #[tokio::test]
+    async fn walks_all_pages() {
+        let results = (0..250).collect::<Vec<_>>();
+        let stream = paginate(pages(results.clone(), |_| ()), |r| *r);
+        assert_eq!(stream.try_collect::<Vec<_>>().await.unwrap(), results);
+    }
+
+    #[tokio::test]
+    async fn skips_results_shifted_to_next_page() {
+        // Results are added at the front, as when searching in descending
+        // order while new results come in.
+        let results = (0..150).rev().collect::<Vec<_>>();
+        let stream = paginate(
+            pages(results.clone(), |results| results.insert(0, 1000)),
+            |r| *r,
+        );
+        let streamed = stream.try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(streamed, results);
+    }
+
+    #[tokio::test]
+    async fn stops_when_results_are_removed() {
+        let results = (0..150).collect::<Vec<_>>();
+        let stream = paginate(pages(results, |results| results.truncate(90)), |r| *r);
+        let streamed = stream.try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(streamed, (0..100).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn stops_early() {
+        let fetched = Mutex::new(0);
+        let stream = paginate(
+            |_, per_page| {
+                *fetched.lock().unwrap() += 1;
+                futures::future::ready(Ok(((0..u32::from(per_page)).collect(), 1000)))
+            },
+            |r| *r,
+        );
+        assert_eq!(stream.take(5).count().await, 5);
+        assert_eq!(*fetched.lock().unwrap(), 1);
+    }
+}
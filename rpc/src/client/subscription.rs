@@ -1,21 +1,21 @@
 //! Subscription- and subscription management-related functionality.
 
+use alloc::collections::VecDeque;
 use core::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
 
 use async_trait::async_trait;
 use futures::{
-    task::{Context, Poll},
+    task::{Context, Poll, Waker},
     Stream,
 };
 use pin_project::pin_project;
+use tokio::sync::Notify;
 
-use crate::{
-    client::sync::{ChannelRx, ChannelTx},
-    event::Event,
-    prelude::*,
-    query::Query,
-    Error,
-};
+use crate::{event::Event, prelude::*, query::Query, Error};
+
+/// The number of events buffered for each subscription by default.
+pub const DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY: usize = 1000;
 
 /// A client that exclusively provides [`Event`] subscription capabilities,
 /// without any other RPC method support.
@@ -35,13 +35,261 @@ pub trait SubscriptionClient {
     /// [`select_all`]: https://docs.rs/futures/*/futures/stream/fn.select_all.html
     async fn unsubscribe(&self, query: Query) -> Result<(), Error>;
 
+    /// Terminate the given [`Subscription`], leaving other subscriptions to
+    /// the same [`Query`] untouched.
+    ///
+    /// Subscriptions to the same query made with the same client share a
+    /// single subscription on the node, which is only unsubscribed from once
+    /// the last of them is released or dropped. The default implementation
+    /// unsubscribes from the query, terminating all subscriptions to it.
+    async fn release(&self, subscription: Subscription) -> Result<(), Error> {
+        self.unsubscribe(subscription.query().clone()).await
+    }
+
     /// Subscription clients will usually have long-running underlying
     /// transports that will need to be closed at some point.
     fn close(self) -> Result<(), Error>;
 }
 
-pub(crate) type SubscriptionTx = ChannelTx<Result<Event, Error>>;
-pub(crate) type SubscriptionRx = ChannelRx<Result<Event, Error>>;
+/// What to do with the events of a subscription whose buffer is full, the
+/// subscriber not keeping up with the events produced by the node.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait for the subscriber to catch up.
+    ///
+    /// No event is lost, but this holds back the driver of the client, and
+    /// so all of its other subscriptions and requests, until the buffer has
+    /// room for the event.
+    Block,
+    /// Drop the oldest buffered events to make room for the new ones, which
+    /// is the default.
+    ///
+    /// The gap is reported to the subscriber, before the next event it
+    /// receives, by a [`subscription_lagged`](Error::subscription_lagged)
+    /// error telling the number of dropped events. The subscription carries
+    /// on afterwards.
+    #[default]
+    DropOldest,
+    /// Terminate the subscription, which produces the buffered events and
+    /// then a [`subscription_overflow`](Error::subscription_overflow) error.
+    Error,
+}
+
+/// The buffer of the events received for a subscription, until they are
+/// consumed by the subscriber.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubscriptionBuffer {
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+impl SubscriptionBuffer {
+    /// A buffer holding up to the given number of events, dropping the
+    /// oldest ones once full.
+    ///
+    /// ## Panics
+    ///
+    /// If the capacity is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "subscription buffers must hold at least one event"
+        );
+        Self {
+            capacity,
+            policy: OverflowPolicy::default(),
+        }
+    }
+
+    /// Handle the events overflowing the buffer with the given policy.
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The maximum number of buffered events.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// How the events overflowing the buffer are handled.
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Create the channel of a subscription using this buffer.
+    pub(crate) fn channel(self) -> (SubscriptionTx, SubscriptionRx) {
+        let shared = Arc::new(Shared {
+            buffer: self,
+            state: Mutex::new(State {
+                events: VecDeque::new(),
+                dropped: 0,
+                failure: None,
+                terminated: false,
+                senders: 1,
+                closed: false,
+                rx_waker: None,
+            }),
+            space: Notify::new(),
+        });
+        (SubscriptionTx(shared.clone()), SubscriptionRx(shared))
+    }
+}
+
+impl Default for SubscriptionBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY)
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    buffer: SubscriptionBuffer,
+    state: Mutex<State>,
+    // Notified whenever the receiver consumed an event or went away.
+    space: Notify,
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    events: VecDeque<Result<Event, Error>>,
+    // The number of events dropped since the last one received.
+    dropped: u64,
+    // The error terminating the subscription once the events are consumed.
+    failure: Option<Error>,
+    // Whether the error terminating the subscription was received.
+    terminated: bool,
+    senders: usize,
+    // Whether the receiver was dropped.
+    closed: bool,
+    rx_waker: Option<Waker>,
+}
+
+impl State {
+    fn wake_rx(&mut self) {
+        if let Some(waker) = self.rx_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The sending end of the channel of a subscription, held by the router of
+/// the client driver.
+#[derive(Debug)]
+pub struct SubscriptionTx(Arc<Shared>);
+
+impl SubscriptionTx {
+    /// Buffer the given event or error, handling a full buffer according to
+    /// the overflow policy.
+    ///
+    /// Fails if the subscription is gone, or was terminated because of an
+    /// overflow.
+    pub async fn send(&self, ev: Result<Event, Error>) -> Result<(), Error> {
+        let SubscriptionBuffer { capacity, policy } = self.0.buffer;
+        loop {
+            {
+                let mut state = self.0.state();
+                if state.closed || state.failure.is_some() || state.terminated {
+                    return Err(Error::channel_send());
+                }
+                if state.events.len() < capacity {
+                    state.events.push_back(ev);
+                    state.wake_rx();
+                    return Ok(());
+                }
+                match policy {
+                    OverflowPolicy::Block => {},
+                    OverflowPolicy::DropOldest => {
+                        state.events.pop_front();
+                        state.dropped += 1;
+                        state.events.push_back(ev);
+                        state.wake_rx();
+                        return Ok(());
+                    },
+                    OverflowPolicy::Error => {
+                        let e = Error::subscription_overflow(capacity);
+                        state.failure = Some(e.clone());
+                        state.wake_rx();
+                        return Err(e);
+                    },
+                }
+            }
+            // A single permit is stored if the receiver consumed an event
+            // in the meantime, so no wakeup is missed.
+            self.0.space.notified().await;
+        }
+    }
+
+    /// Terminate the subscription with the given error, which is received
+    /// after the buffered events whatever the room left in the buffer.
+    pub fn terminate(&self, e: Error) {
+        let mut state = self.0.state();
+        if state.failure.is_none() {
+            state.failure = Some(e);
+            state.wake_rx();
+        }
+    }
+}
+
+impl Clone for SubscriptionTx {
+    fn clone(&self) -> Self {
+        self.0.state().senders += 1;
+        Self(self.0.clone())
+    }
+}
+
+impl Drop for SubscriptionTx {
+    fn drop(&mut self) {
+        let mut state = self.0.state();
+        state.senders -= 1;
+        if state.senders == 0 {
+            state.wake_rx();
+        }
+    }
+}
+
+/// The receiving end of the channel of a subscription.
+#[derive(Debug)]
+pub struct SubscriptionRx(Arc<Shared>);
+
+impl Stream for SubscriptionRx {
+    type Item = Result<Event, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.0.state();
+        if state.dropped > 0 {
+            let dropped = core::mem::take(&mut state.dropped);
+            return Poll::Ready(Some(Err(Error::subscription_lagged(dropped))));
+        }
+        if let Some(ev) = state.events.pop_front() {
+            drop(state);
+            self.0.space.notify_one();
+            return Poll::Ready(Some(ev));
+        }
+        if let Some(e) = state.failure.take() {
+            state.terminated = true;
+            return Poll::Ready(Some(Err(e)));
+        }
+        if state.terminated || state.senders == 0 {
+            return Poll::Ready(None);
+        }
+        state.rx_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for SubscriptionRx {
+    fn drop(&mut self) {
+        self.0.state().closed = true;
+        self.0.space.notify_one();
+    }
+}
 
 /// An interface that can be used to asynchronously receive [`Event`]s for a
 /// particular subscription.
@@ -102,3 +350,120 @@ impl Subscription {
         &self.query
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use futures::StreamExt;
+    use tokio::time::timeout;
+
+    use super::*;
+    use crate::{error::ErrorDetail, event::EventData};
+
+    fn event(n: u64) -> Result<Event, Error> {
+        Ok(Event {
+            query: "tm.event = 'Tx'".to_owned(),
+            data: EventData::GenericJsonEvent(n.into()),
+            events: None,
+        })
+    }
+
+    async fn recv_event(rx: &mut SubscriptionRx) -> u64 {
+        match rx.next().await.unwrap().unwrap().data {
+            EventData::GenericJsonEvent(n) => n.as_u64().unwrap(),
+            data => panic!("unexpected event data: {data:?}"),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn full_buffer_blocks_until_events_are_received() {
+        let buffer = SubscriptionBuffer::new(2).overflow_policy(OverflowPolicy::Block);
+        let (tx, mut rx) = buffer.channel();
+        tx.send(event(0)).await.unwrap();
+        tx.send(event(1)).await.unwrap();
+
+        let mut blocked = Box::pin(tx.send(event(2)));
+        assert!(timeout(Duration::from_secs(1), &mut blocked).await.is_err());
+
+        assert_eq!(recv_event(&mut rx).await, 0);
+        blocked.await.unwrap();
+        assert_eq!(recv_event(&mut rx).await, 1);
+        assert_eq!(recv_event(&mut rx).await, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dropped_subscription_unblocks_the_sender() {
+        let buffer = SubscriptionBuffer::new(1).overflow_policy(OverflowPolicy::Block);
+        let (tx, rx) = buffer.channel();
+        tx.send(event(0)).await.unwrap();
+
+        let mut blocked = Box::pin(tx.send(event(1)));
+        assert!(timeout(Duration::from_secs(1), &mut blocked).await.is_err());
+
+        drop(rx);
+        assert!(blocked.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn dropped_events_are_reported_as_a_gap() {
+        let (tx, mut rx) = SubscriptionBuffer::new(2).channel();
+        for n in 0..5 {
+            tx.send(event(n)).await.unwrap();
+        }
+
+        match rx.next().await.unwrap().unwrap_err().detail() {
+            ErrorDetail::SubscriptionLagged(e) => assert_eq!(e.dropped, 3),
+            e => panic!("unexpected error: {e}"),
+        }
+        assert_eq!(recv_event(&mut rx).await, 3);
+        assert_eq!(recv_event(&mut rx).await, 4);
+
+        // The subscription carries on after the gap.
+        tx.send(event(5)).await.unwrap();
+        assert_eq!(recv_event(&mut rx).await, 5);
+    }
+
+    #[tokio::test]
+    async fn overflow_terminates_the_subscription() {
+        let buffer = SubscriptionBuffer::new(2).overflow_policy(OverflowPolicy::Error);
+        let (tx, mut rx) = buffer.channel();
+        tx.send(event(0)).await.unwrap();
+        tx.send(event(1)).await.unwrap();
+        assert!(tx.send(event(2)).await.is_err());
+        assert!(tx.send(event(3)).await.is_err());
+
+        assert_eq!(recv_event(&mut rx).await, 0);
+        assert_eq!(recv_event(&mut rx).await, 1);
+        match rx.next().await.unwrap().unwrap_err().detail() {
+            ErrorDetail::SubscriptionOverflow(e) => assert_eq!(e.capacity, 2),
+            e => panic!("unexpected error: {e}"),
+        }
+        assert!(rx.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn termination_ignores_the_capacity() {
+        let (tx, mut rx) = SubscriptionBuffer::new(1).channel();
+        tx.send(event(0)).await.unwrap();
+        tx.terminate(Error::channel_send());
+
+        assert_eq!(recv_event(&mut rx).await, 0);
+        assert!(rx.next().await.unwrap().is_err());
+        assert!(rx.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn subscription_ends_with_its_senders() {
+        let (tx, mut rx) = SubscriptionBuffer::default().channel();
+        let tx2 = tx.clone();
+        tx.send(event(0)).await.unwrap();
+        drop(tx);
+        tx2.send(event(1)).await.unwrap();
+        drop(tx2);
+
+        assert_eq!(recv_event(&mut rx).await, 0);
+        assert_eq!(recv_event(&mut rx).await, 1);
+        assert!(rx.next().await.is_none());
+    }
+}
@@ -1,8 +1,8 @@
 //! Synchronization primitives specific to the Tendermint RPC client.
 //!
 //! At present, this wraps Tokio's synchronization primitives and provides some
-//! convenience methods. We also only implement unbounded channels at present.
-//! In future, if RPC consumers need it, we will implement bounded channels.
+//! convenience methods. We only implement unbounded channels here, the bounded
+//! buffers of subscriptions being implemented along with them.
 
 use core::pin::Pin;
 
@@ -1,7 +1,10 @@
 //! Tendermint RPC client implementations for different transports.
 
 mod auth;
+pub use auth::Authorization;
 pub mod mock;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod proxy;
 mod router;
 
 macro_rules! perform_with_compat {
@@ -14,7 +17,32 @@ macro_rules! perform_with_compat {
     }};
 }
 
-#[cfg(feature = "http-client")]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod recording;
+
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+use crate::request::RequestMessage;
+
+/// A span covering a request to a node, with its method and the height it is
+/// about as fields.
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+fn request_span<R: RequestMessage>(request: &R) -> tracing::Span {
+    tracing::debug_span!(
+        "rpc_request",
+        method = %request.method_name(),
+        height = request.height().map(|height| height.value()),
+    )
+}
+
+#[cfg(all(feature = "grpc", not(target_arch = "wasm32")))]
+pub mod grpc;
+#[cfg(all(feature = "http-client", not(target_arch = "wasm32")))]
 pub mod http;
-#[cfg(feature = "websocket-client")]
+#[cfg(all(feature = "http-client", not(target_arch = "wasm32")))]
+mod timeout;
+#[cfg(all(unix, feature = "http-client"))]
+mod unix;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
+#[cfg(all(feature = "websocket-client", not(target_arch = "wasm32")))]
 pub mod websocket;
@@ -1,33 +1,107 @@
 //! This module defines the `Authorization` type for
 //! authorizing a HTTP or WebSocket RPC client using
-//! HTTP Basic authentication.
+//! HTTP Basic authentication, bearer tokens or custom schemes.
 
-use alloc::string::{String, ToString};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 use core::fmt;
 
-use http::Uri;
+use http::{header::HeaderName, HeaderMap, HeaderValue, Uri};
 use subtle_encoding::base64;
 
-/// An HTTP authorization.
+use crate::Error;
+
+/// An HTTP authorization, sent to the RPC server via the `Authorization`
+/// HTTP header.
 ///
-/// Currently only HTTP Basic authentication is supported.
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// The credentials are redacted from the `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
 pub enum Authorization {
+    /// HTTP Basic authentication, with the Base64-encoded `user:password`
+    /// credentials.
     Basic(String),
+    /// Authentication with the given bearer token, e.g. as issued by the
+    /// OAuth 2.0 server guarding a reverse proxy.
+    Bearer(String),
+    /// The given value of the `Authorization` header, for other
+    /// authentication schemes.
+    Custom(String),
+}
+
+impl Authorization {
+    /// HTTP Basic authentication with the given user name and password.
+    pub fn basic(user: &str, password: &str) -> Self {
+        let bytes = base64::encode(format!("{user}:{password}"));
+        Self::Basic(String::from_utf8_lossy(&bytes).to_string())
+    }
+
+    /// Authentication with the given bearer token.
+    pub fn bearer(token: impl Into<String>) -> Self {
+        Self::Bearer(token.into())
+    }
+
+    /// The value of the `Authorization` header.
+    ///
+    /// Fails if the credentials contain characters not allowed in HTTP
+    /// headers, such as line breaks.
+    pub(crate) fn header_value(&self) -> Result<HeaderValue, Error> {
+        let mut value = HeaderValue::try_from(self.to_string())
+            .map_err(|_| Error::invalid_params("invalid authorization header".to_string()))?;
+        value.set_sensitive(true);
+        Ok(value)
+    }
 }
 
 impl fmt::Display for Authorization {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Basic(cred) => write!(f, "Basic {cred}"),
+            Self::Bearer(token) => write!(f, "Bearer {token}"),
+            Self::Custom(value) => write!(f, "{value}"),
         }
     }
 }
 
+impl fmt::Debug for Authorization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scheme = match self {
+            Self::Basic(_) => "Basic",
+            Self::Bearer(_) => "Bearer",
+            Self::Custom(_) => "Custom",
+        };
+        f.debug_tuple(scheme).field(&"<redacted>").finish()
+    }
+}
+
+/// Parse the given names and values of HTTP headers, e.g. to supply the API
+/// key of a hosted node provider.
+///
+/// The values are redacted from the `Debug` output, as they are likely to be
+/// credentials.
+pub(crate) fn header_map<N, V>(headers: &[(N, V)]) -> Result<HeaderMap, Error>
+where
+    N: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let (name, value) = (name.as_ref(), value.as_ref());
+        let invalid = || Error::invalid_params(format!("invalid HTTP header {name}"));
+        let name = HeaderName::try_from(name).map_err(|_| invalid())?;
+        let mut value = HeaderValue::try_from(value).map_err(|_| invalid())?;
+        value.set_sensitive(true);
+        map.append(name, value);
+    }
+    Ok(map)
+}
+
 /// Extract the authorization, if any, from the authority part of the given URI.
 ///
 /// This authorization can then be supplied to the RPC server via
 /// the `Authorization` HTTP header.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
 pub fn authorize(uri: &Uri) -> Option<Authorization> {
     let authority = uri.authority()?;
 
@@ -48,6 +122,22 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn parse_headers() {
+        let headers = header_map(&[
+            ("X-Api-Key", "s3cr3t"),
+            ("x-tenant", "a"),
+            ("x-tenant", "b"),
+        ])
+        .unwrap();
+        assert_eq!(headers["x-api-key"], "s3cr3t");
+        assert!(headers["x-api-key"].is_sensitive());
+        assert_eq!(headers.get_all("x-tenant").iter().count(), 2);
+
+        assert!(header_map(&[("X Api Key", "s3cr3t")]).is_err());
+        assert!(header_map(&[("X-Api-Key", "s3cr3t\n")]).is_err());
+    }
+
     #[test]
     fn extract_auth_absent() {
         let uri = Uri::from_str("http://example.com").unwrap();
@@ -67,4 +157,24 @@ mod tests {
         let base64 = "dG90bzp0YXRh".to_string();
         assert_eq!(authorize(&uri), Some(Authorization::Basic(base64)));
     }
+
+    #[test]
+    fn header_values() {
+        let basic = Authorization::basic("toto", "tata");
+        assert_eq!(basic.header_value().unwrap(), "Basic dG90bzp0YXRh");
+        let bearer = Authorization::bearer("s3cr3t");
+        assert_eq!(bearer.header_value().unwrap(), "Bearer s3cr3t");
+        let custom = Authorization::Custom("Token s3cr3t".to_string());
+        assert_eq!(custom.header_value().unwrap(), "Token s3cr3t");
+
+        assert!(Authorization::bearer("s3cr3t\r\nHost: evil")
+            .header_value()
+            .is_err());
+    }
+
+    #[test]
+    fn credentials_are_redacted() {
+        let debug = format!("{:?}", Authorization::bearer("s3cr3t"));
+        assert_eq!(debug, r#"Bearer("<redacted>")"#);
+    }
 }
@@ -0,0 +1,222 @@
+//! gRPC client for the BroadcastAPI of Tendermint nodes.
+
+use core::convert::{TryFrom, TryInto};
+
+use hyper::{client::HttpConnector, Uri};
+use tendermint::abci::{response::CheckTx, types::ExecTxResult};
+use tendermint_proto::v0_38::rpc::grpc::{
+    RequestBroadcastTx, RequestPing, ResponseBroadcastTx, ResponsePing,
+};
+use tonic::{body::BoxBody, client::Grpc, codec::ProstCodec, codegen::http::uri::PathAndQuery};
+
+use crate::{client::transport::http::HttpClientUrl, prelude::*, Error, Scheme, Url};
+
+const PING_PATH: &str = "/tendermint.rpc.grpc.BroadcastAPI/Ping";
+const BROADCAST_TX_PATH: &str = "/tendermint.rpc.grpc.BroadcastAPI/BroadcastTx";
+
+/// A client for the gRPC [BroadcastAPI] of Tendermint nodes, for users who
+/// prefer gRPC over JSON-RPC to broadcast transactions.
+///
+/// The API is only served by nodes with the `rpc.grpc_laddr` configuration
+/// option set, at that address. Connections are made over plaintext HTTP/2.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// use tendermint_rpc::GrpcClient;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = GrpcClient::new("http://127.0.0.1:26658").unwrap();
+///
+///     let response = client.broadcast_tx(b"key=value".to_vec()).await.unwrap();
+///
+///     println!("Transaction executed with code {:?}", response.tx_result.code);
+/// }
+/// ```
+///
+/// [BroadcastAPI]: https://github.com/cometbft/cometbft/blob/v0.37.x/proto/tendermint/rpc/grpc/types.proto
+#[derive(Debug, Clone)]
+pub struct GrpcClient {
+    inner: Grpc<hyper::Client<HttpConnector, BoxBody>>,
+}
+
+impl GrpcClient {
+    /// Construct a new gRPC client for the BroadcastAPI served at the given
+    /// `http://` URL.
+    ///
+    /// No connection is made until the first request.
+    pub fn new<U>(url: U) -> Result<Self, Error>
+    where
+        U: TryInto<HttpClientUrl, Error = Error>,
+    {
+        let url = url.try_into()?;
+        let scheme = Url::from(url.clone()).scheme();
+        if scheme != Scheme::Http {
+            return Err(Error::unsupported_scheme(scheme.to_string()));
+        }
+
+        let origin = Uri::try_from(url)?;
+        let client = hyper::Client::builder()
+            .http2_only(true)
+            .build(HttpConnector::new());
+
+        Ok(Self {
+            inner: Grpc::with_origin(client, origin),
+        })
+    }
+
+    /// Check that the BroadcastAPI is served.
+    pub async fn ping(&self) -> Result<(), Error> {
+        let _: ResponsePing = self.unary(PING_PATH, RequestPing {}).await?;
+        Ok(())
+    }
+
+    /// Broadcast the given transaction, and wait for it to be committed.
+    ///
+    /// This is the gRPC counterpart of [`Client::broadcast_tx_commit`], and
+    /// is subject to the same timeout on the side of the node.
+    ///
+    /// [`Client::broadcast_tx_commit`]: crate::Client::broadcast_tx_commit
+    pub async fn broadcast_tx<T>(&self, tx: T) -> Result<BroadcastTxResponse, Error>
+    where
+        T: Into<Vec<u8>>,
+    {
+        let request = RequestBroadcastTx { tx: tx.into() };
+        let response: ResponseBroadcastTx = self.unary(BROADCAST_TX_PATH, request).await?;
+        response.try_into()
+    }
+
+    async fn unary<Req, Res>(&self, path: &'static str, request: Req) -> Result<Res, Error>
+    where
+        Req: prost::Message + Send + Sync + 'static,
+        Res: prost::Message + Default + Send + Sync + 'static,
+    {
+        let mut grpc = self.inner.clone();
+        grpc.ready().await.map_err(Error::hyper)?;
+        let response = grpc
+            .unary(
+                tonic::Request::new(request),
+                PathAndQuery::from_static(path),
+                ProstCodec::default(),
+            )
+            .await
+            .map_err(Error::grpc)?;
+
+        Ok(response.into_inner())
+    }
+}
+
+/// The outcome of a transaction broadcast with [`GrpcClient::broadcast_tx`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BroadcastTxResponse {
+    /// The result of checking the transaction before adding it to the
+    /// mempool.
+    pub check_tx: CheckTx,
+
+    /// The result of executing the transaction.
+    pub tx_result: ExecTxResult,
+}
+
+// The `deliver_tx` field of the response of nodes before CometBFT 0.38 has
+// the same tag and encoding as `tx_result`, so that the messages of 0.38
+// decode the responses of all versions.
+impl TryFrom<ResponseBroadcastTx> for BroadcastTxResponse {
+    type Error = Error;
+
+    fn try_from(response: ResponseBroadcastTx) -> Result<Self, Error> {
+        let check_tx = response
+            .check_tx
+            .ok_or_else(tendermint::Error::missing_data)
+            .and_then(CheckTx::try_from)
+            .map_err(Error::tendermint)?;
+        let tx_result = response
+            .tx_result
+            .ok_or_else(tendermint::Error::missing_data)
+            .and_then(ExecTxResult::try_from)
+            .map_err(Error::tendermint)?;
+
+        Ok(Self {
+            check_tx,
+            tx_result,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use bytes::{BufMut, Bytes, BytesMut};
+    use hyper::{
+        header::{HeaderMap, HeaderValue},
+        service::{make_service_fn, service_fn},
+        Body, Request, Response, Server,
+    };
+    use prost::Message;
+    use tendermint_proto::v0_38::abci::{ExecTxResult as RawExecTxResult, ResponseCheckTx};
+
+    use super::*;
+
+    /// Frame a gRPC message.
+    fn frame(message: impl Message) -> Bytes {
+        let mut buf = BytesMut::new();
+        buf.put_u8(0);
+        buf.put_u32(message.encoded_len() as u32);
+        message.encode(&mut buf).unwrap();
+        buf.freeze()
+    }
+
+    /// Respond to `BroadcastTx` requests with the transaction as the data of
+    /// the result of executing it.
+    async fn serve(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+        assert_eq!(request.uri().path(), BROADCAST_TX_PATH);
+        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+        let request = RequestBroadcastTx::decode(&body[5..]).unwrap();
+
+        let response = ResponseBroadcastTx {
+            check_tx: Some(ResponseCheckTx {
+                gas_wanted: 10,
+                ..Default::default()
+            }),
+            tx_result: Some(RawExecTxResult {
+                data: request.tx.into(),
+                ..Default::default()
+            }),
+        };
+
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move {
+            sender.send_data(frame(response)).await.unwrap();
+            let mut trailers = HeaderMap::new();
+            trailers.insert("grpc-status", HeaderValue::from_static("0"));
+            sender.send_trailers(trailers).await.unwrap();
+        });
+
+        Ok(Response::builder()
+            .header("content-type", "application/grpc")
+            .body(body)
+            .unwrap())
+    }
+
+    #[tokio::test]
+    async fn broadcast_tx() {
+        let make_service = make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(serve)) });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into())
+            .http2_only(true)
+            .serve(make_service);
+        let url = format!("http://{}", server.local_addr());
+        tokio::spawn(server);
+
+        let client = GrpcClient::new(url.as_str()).unwrap();
+        let response = client.broadcast_tx(b"key=value".to_vec()).await.unwrap();
+        assert_eq!(response.check_tx.gas_wanted, 10);
+        assert_eq!(response.tx_result.data.as_ref(), b"key=value");
+    }
+
+    #[test]
+    fn unsupported_schemes() {
+        assert!(GrpcClient::new("https://127.0.0.1:26658").is_err());
+        assert!(GrpcClient::new("unix:///tmp/grpc.sock").is_err());
+    }
+}
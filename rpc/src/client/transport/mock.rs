@@ -1,13 +1,18 @@
 //! Mock client implementation for use in testing.
 
 use alloc::collections::BTreeMap as HashMap;
+use core::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use std::sync::Mutex;
 
 use async_trait::async_trait;
 
 use crate::dialect::{v0_37, Dialect};
 use crate::{
     client::{
-        subscription::SubscriptionTx,
+        subscription::{SubscriptionBuffer, SubscriptionTx},
         sync::{unbounded, ChannelRx, ChannelTx},
         transport::router::SubscriptionRouter,
         Client,
@@ -59,6 +64,7 @@ use crate::{
 pub struct MockClient<M: MockRequestMatcher> {
     matcher: M,
     driver_tx: ChannelTx<DriverCommand>,
+    subscription_buffer: SubscriptionBuffer,
 }
 
 #[async_trait]
@@ -79,11 +85,29 @@ impl<M: MockRequestMatcher> MockClient<M> {
     pub fn new(matcher: M) -> (Self, MockClientDriver) {
         let (driver_tx, driver_rx) = unbounded();
         (
-            Self { matcher, driver_tx },
+            Self {
+                matcher,
+                driver_tx,
+                subscription_buffer: SubscriptionBuffer::default(),
+            },
             MockClientDriver::new(driver_rx),
         )
     }
 
+    /// Buffer the events of each subscription with the given buffer, e.g. to
+    /// exercise its overflow policy with events published faster than they
+    /// are consumed.
+    pub fn subscription_buffer(mut self, buffer: SubscriptionBuffer) -> Self {
+        self.subscription_buffer = buffer;
+        self
+    }
+
+    /// The request matcher of the client, e.g. to inspect the requests
+    /// recorded by a [`MockRequestScript`].
+    pub fn matcher(&self) -> &M {
+        &self.matcher
+    }
+
     /// Publishes the given event to all subscribers whose query exactly
     /// matches that of the event.
     pub fn publish(&self, ev: &Event) {
@@ -102,7 +126,7 @@ impl<M: MockRequestMatcher> MockClient<M> {
 impl<M: MockRequestMatcher> SubscriptionClient for MockClient<M> {
     async fn subscribe(&self, query: Query) -> Result<Subscription, Error> {
         let id = uuid_str();
-        let (subs_tx, subs_rx) = unbounded();
+        let (subs_tx, subs_rx) = self.subscription_buffer.channel();
         let (result_tx, mut result_rx) = unbounded();
         self.driver_tx.send(DriverCommand::Subscribe {
             id: id.clone(),
@@ -121,6 +145,15 @@ impl<M: MockRequestMatcher> SubscriptionClient for MockClient<M> {
         result_rx.recv().await.unwrap()
     }
 
+    async fn release(&self, subscription: Subscription) -> Result<(), Error> {
+        let (result_tx, mut result_rx) = unbounded();
+        self.driver_tx.send(DriverCommand::Release {
+            id: subscription.id().to_string(),
+            result_tx,
+        })?;
+        result_rx.recv().await.unwrap()
+    }
+
     fn close(self) -> Result<(), Error> {
         Ok(())
     }
@@ -138,6 +171,10 @@ pub enum DriverCommand {
         query: Query,
         result_tx: ChannelTx<Result<(), Error>>,
     },
+    Release {
+        id: String,
+        result_tx: ChannelTx<Result<(), Error>>,
+    },
     Publish(Box<Event>),
     Terminate,
 }
@@ -166,7 +203,8 @@ impl MockClientDriver {
                     DriverCommand::Unsubscribe { query, result_tx } => {
                         self.unsubscribe(query, result_tx);
                     }
-                    DriverCommand::Publish(event) => self.publish(*event),
+                    DriverCommand::Release { id, result_tx } => self.release(id, result_tx),
+                    DriverCommand::Publish(event) => self.publish(*event).await,
                     DriverCommand::Terminate => return Ok(()),
                 }
             }
@@ -189,8 +227,13 @@ impl MockClientDriver {
         result_tx.send(Ok(())).unwrap();
     }
 
-    fn publish(&mut self, event: Event) {
-        self.router.publish_event(event);
+    fn release(&mut self, id: String, result_tx: ChannelTx<Result<(), Error>>) {
+        self.router.remove(&id);
+        result_tx.send(Ok(())).unwrap();
+    }
+
+    async fn publish(&mut self, event: Event) {
+        self.router.publish_event(event).await;
     }
 }
 
@@ -240,6 +283,184 @@ impl MockRequestMethodMatcher {
     }
 }
 
+type ParamsPredicate = Box<dyn Fn(&serde_json::Value) -> bool + Send + Sync>;
+
+struct MockRule {
+    method: Method,
+    params: Option<ParamsPredicate>,
+    response: Result<String, Error>,
+    once: bool,
+    used: AtomicBool,
+}
+
+impl MockRule {
+    fn matches(&self, method: Method, params: &serde_json::Value) -> bool {
+        let params_match = match &self.params {
+            Some(matches) => matches(params),
+            None => true,
+        };
+        // A rule answering once is used up by the first request it matches.
+        self.method == method
+            && params_match
+            && !(self.once && self.used.swap(true, Ordering::SeqCst))
+    }
+}
+
+/// A request made to a [`MockClient`] scripted with a [`MockRequestScript`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockCall {
+    /// The method of the request.
+    pub method: Method,
+    /// The parameters of the request, as sent in the JSON-RPC request.
+    pub params: serde_json::Value,
+}
+
+/// A [`MockRequestMatcher`] responding to requests as scripted with rules,
+/// and recording the requests it gets so that tests can assert on them.
+///
+/// Rules are tried in the order they were added, and the response of the
+/// first rule matching the method and parameters of a request is returned.
+/// Requests no rule matches fail with a mismatched response error, and are
+/// recorded too.
+///
+/// ## Examples
+///
+/// ```rust
+/// use tendermint_rpc::{Client, Method, MockClient, MockRequestScript};
+///
+/// const HEALTH_RESPONSE: &str = r#"{"jsonrpc": "2.0", "id": "", "result": {}}"#;
+///
+/// tokio_test::block_on(async {
+///     let script = MockRequestScript::default()
+///         .once(
+///             Method::Health,
+///             Err(tendermint_rpc::Error::client_internal("unavailable".into())),
+///         )
+///         .on(Method::Health, Ok(HEALTH_RESPONSE.to_string()));
+///     let (client, driver) = MockClient::new(script);
+///     let driver_hdl = tokio::spawn(async move { driver.run().await });
+///
+///     assert!(client.health().await.is_err());
+///     assert!(client.health().await.is_ok());
+///     assert_eq!(client.matcher().call_count(Method::Health), 2);
+///
+///     client.close();
+///     driver_hdl.await.unwrap();
+/// });
+/// ```
+#[derive(Default)]
+pub struct MockRequestScript {
+    rules: Vec<MockRule>,
+    calls: Mutex<Vec<MockCall>>,
+}
+
+impl fmt::Debug for MockRequestScript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockRequestScript")
+            .field("rules", &self.rules.len())
+            .field("calls", &self.calls())
+            .finish()
+    }
+}
+
+impl MockRequestMatcher for MockRequestScript {
+    fn response_for<R, S>(&self, request: R) -> Option<Result<R::Response, Error>>
+    where
+        R: Request<S>,
+        S: Dialect,
+    {
+        let method = request.method();
+        let json: serde_json::Value = match serde_json::from_str(&request.into_json()) {
+            Ok(json) => json,
+            Err(e) => return Some(Err(Error::serde(e))),
+        };
+        let params = json.get("params").cloned().unwrap_or_default();
+
+        let rule = self.rules.iter().find(|rule| rule.matches(method, &params));
+        self.calls.lock().unwrap().push(MockCall { method, params });
+
+        rule.map(|rule| match &rule.response {
+            Ok(json) => R::Response::from_string(json),
+            Err(e) => Err(e.clone()),
+        })
+    }
+}
+
+impl MockRequestScript {
+    /// Respond to all the requests with the given method with `response`.
+    ///
+    /// Successful responses must be JSON-encoded.
+    pub fn on(self, method: Method, response: Result<String, Error>) -> Self {
+        self.rule(method, None, response, false)
+    }
+
+    /// Respond to the requests with the given method whose parameters match
+    /// the given predicate with `response`.
+    ///
+    /// The predicate is given the parameters of the requests as sent in the
+    /// JSON-RPC requests, e.g. `{"height": "10"}` for a `/block` request.
+    pub fn on_params<F>(self, method: Method, params: F, response: Result<String, Error>) -> Self
+    where
+        F: Fn(&serde_json::Value) -> bool + Send + Sync + 'static,
+    {
+        self.rule(method, Some(Box::new(params)), response, false)
+    }
+
+    /// Respond to the next request with the given method with `response`,
+    /// after which the rule no longer matches, e.g. to fail a request before
+    /// the following ones succeed.
+    pub fn once(self, method: Method, response: Result<String, Error>) -> Self {
+        self.rule(method, None, response, true)
+    }
+
+    fn rule(
+        mut self,
+        method: Method,
+        params: Option<ParamsPredicate>,
+        response: Result<String, Error>,
+        once: bool,
+    ) -> Self {
+        self.rules.push(MockRule {
+            method,
+            params,
+            response,
+            once,
+            used: AtomicBool::new(false),
+        });
+        self
+    }
+
+    /// The requests made so far, in the order they were made.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// The methods of the requests made so far, in the order they were made.
+    pub fn methods(&self) -> Vec<Method> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|c| c.method)
+            .collect()
+    }
+
+    /// The number of requests made so far with the given method.
+    pub fn call_count(&self, method: Method) -> usize {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| c.method == method)
+            .count()
+    }
+
+    /// Forget the requests made so far.
+    pub fn clear_calls(&self) {
+        self.calls.lock().unwrap().clear();
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::path::PathBuf;
@@ -249,7 +470,7 @@ mod test {
     use tokio::fs;
 
     use super::*;
-    use crate::query::EventType;
+    use crate::{client::CompatMode, endpoint, query::EventType, Order};
 
     async fn read_json_fixture(version: &str, name: &str) -> String {
         fs::read_to_string(
@@ -262,6 +483,198 @@ mod test {
         .unwrap()
     }
 
+    /// Serves the genesis file of the fixtures in chunks of the given size.
+    struct GenesisChunkMatcher {
+        genesis: Vec<u8>,
+        chunk_size: usize,
+    }
+
+    impl MockRequestMatcher for GenesisChunkMatcher {
+        fn response_for<R, S>(&self, request: R) -> Option<Result<R::Response, Error>>
+        where
+            R: Request<S>,
+            S: Dialect,
+        {
+            let request: serde_json::Value = serde_json::from_str(&request.into_json()).unwrap();
+            let chunk: usize = request["params"]["chunk"].as_str()?.parse().unwrap();
+            let chunks: Vec<_> = self.genesis.chunks(self.chunk_size).collect();
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "chunk": chunk.to_string(),
+                    "total": chunks.len().to_string(),
+                    "data": String::from_utf8(subtle_encoding::base64::encode(chunks[chunk])).unwrap(),
+                },
+            });
+            Some(R::Response::from_string(response.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn genesis_chunked() {
+        let fixture: serde_json::Value =
+            serde_json::from_str(&read_json_fixture("v0_37", "genesis").await).unwrap();
+        let genesis = fixture["result"]["genesis"].to_string().into_bytes();
+        let (client, driver) = MockClient::new(GenesisChunkMatcher {
+            genesis: genesis.clone(),
+            chunk_size: genesis.len() / 3 + 1,
+        });
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        let actual = client
+            .genesis_chunked::<Option<serde_json::Value>>()
+            .await
+            .unwrap();
+        let expected: tendermint::Genesis<Option<serde_json::Value>> =
+            serde_json::from_slice(&genesis).unwrap();
+        assert_eq!(
+            serde_json::to_value(actual).unwrap(),
+            serde_json::to_value(expected).unwrap()
+        );
+
+        client.close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn scripted_client() {
+        let block_fixture = read_json_fixture("v0_37", "block_at_height_10").await;
+        let script = MockRequestScript::default()
+            .once(Method::Block, Err(Error::client_internal("fail".into())))
+            .on_params(
+                Method::Block,
+                |params| params["height"] == "10",
+                Ok(block_fixture),
+            );
+        let (client, driver) = MockClient::new(script);
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        client.block(Height::from(10_u32)).await.unwrap_err();
+        let block = client.block(Height::from(10_u32)).await.unwrap().block;
+        assert_eq!(Height::from(10_u32), block.header.height);
+        // No rule matches, but the request is recorded.
+        client.block(Height::from(11_u32)).await.unwrap_err();
+        client.health().await.unwrap_err();
+
+        let script = client.matcher();
+        assert_eq!(
+            script.methods(),
+            [Method::Block, Method::Block, Method::Block, Method::Health]
+        );
+        assert_eq!(script.call_count(Method::Block), 3);
+        assert_eq!(
+            script.calls()[2].params,
+            serde_json::json!({"height": "11"})
+        );
+
+        script.clear_calls();
+        assert!(script.calls().is_empty());
+
+        client.close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn tx_search_parameters() {
+        let fixture = read_json_fixture("v0_37", "tx_search_no_prove").await;
+        let script = MockRequestScript::default().on(Method::TxSearch, Ok(fixture));
+        let (client, driver) = MockClient::new(script);
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        // Decorators pass the parameters through.
+        let client = crate::client::RetryingClient::new(client);
+        let query: Query = "tx.height > 1".parse().unwrap();
+        client
+            .tx_search(query.clone(), false, 1, 10, Order::Descending)
+            .await
+            .unwrap();
+        client
+            .tx_search_with(
+                endpoint::tx_search::Request::new(query, false, 1, 10, Order::Descending)
+                    .with_match_events(true),
+            )
+            .await
+            .unwrap();
+
+        let client = client.into_inner();
+        let calls = client.matcher().calls();
+        assert_eq!(calls[0].params["order_by"], "desc");
+        assert!(calls[0].params.get("match_events").is_none());
+        assert_eq!(calls[1].params["order_by"], "desc");
+        assert_eq!(calls[1].params["match_events"], true);
+
+        client.close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+
+    /// Responds to every request with its method and parameters.
+    struct EchoMatcher;
+
+    impl MockRequestMatcher for EchoMatcher {
+        fn response_for<R, S>(&self, request: R) -> Option<Result<R::Response, Error>>
+        where
+            R: Request<S>,
+            S: Dialect,
+        {
+            let request: serde_json::Value = serde_json::from_str(&request.into_json()).unwrap();
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "method": request["method"],
+                    "params": request["params"],
+                },
+            });
+            Some(R::Response::from_string(response.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn raw_requests() {
+        let (client, driver) = MockClient::new(EchoMatcher);
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+        let expected = serde_json::json!({
+            "method": "validator_stats",
+            "params": {"window": "100"},
+        });
+
+        let response = client
+            .perform_raw("validator_stats", serde_json::json!({"window": "100"}))
+            .await
+            .unwrap();
+        assert_eq!(response, expected);
+
+        // Decorators rebuilding requests from their JSON form keep the name
+        // of the method.
+        let retrying = crate::client::RetryingClient::new(client);
+        let response = retrying
+            .perform_raw("validator_stats", serde_json::json!({"window": "100"}))
+            .await
+            .unwrap();
+        assert_eq!(response, expected);
+
+        retrying.into_inner().close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn compat_mode_detection() {
+        for (version, expected) in [("v0_34", CompatMode::V0_34), ("v0_37", CompatMode::V0_37)] {
+            let matcher = MockRequestMethodMatcher::default().map(
+                Method::Status,
+                Ok(read_json_fixture(version, "status").await),
+            );
+            let (client, driver) = MockClient::new(matcher);
+            let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+            assert_eq!(client.detect_compat_mode().await.unwrap(), expected);
+
+            client.close();
+            driver_hdl.await.unwrap().unwrap();
+        }
+    }
+
     mod v0_34 {
         use super::*;
         use crate::event::v0_34::DeEvent;
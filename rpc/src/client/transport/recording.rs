@@ -0,0 +1,392 @@
+//! Recording of the requests made by a client, and their offline replay.
+
+use alloc::collections::VecDeque;
+use core::fmt;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tendermint::{block::Height, evidence::Evidence, Hash, Time};
+
+use crate::{
+    client::{Client, CompatMode},
+    dialect, endpoint,
+    prelude::*,
+    Error, Response, SimpleRequest,
+};
+
+/// A request to a node along with the response received for it, as recorded
+/// in an archive by a [`Recorder`].
+///
+/// Archives are [JSON Lines] files holding one interaction per line.
+///
+/// [JSON Lines]: https://jsonlines.org/
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Interaction {
+    /// The time at which the response was received.
+    #[serde(with = "tendermint::serializers::time")]
+    pub timestamp: Time,
+    /// The JSON-RPC request, or batch of requests.
+    pub request: Value,
+    /// The JSON-RPC response, or batch of responses.
+    ///
+    /// Responses which are not valid JSON are recorded as strings.
+    pub response: Value,
+}
+
+impl Interaction {
+    fn matches(&self, request: &Value) -> bool {
+        self.request.get("method") == request.get("method")
+            && self.request.get("params") == request.get("params")
+    }
+}
+
+/// Writes every request made by a client and the response received for it
+/// to an archive, which can be replayed with a [`ReplayClient`].
+///
+/// Recorders can be cloned to share an archive across several clients.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// use tendermint_rpc::{client::Recorder, HttpClient};
+///
+/// let client = HttpClient::builder("http://127.0.0.1:26657".parse().unwrap())
+///     .recorder(Recorder::create("session.jsonl").unwrap())
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct Recorder {
+    sink: Arc<Mutex<dyn Write + Send>>,
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder").finish_non_exhaustive()
+    }
+}
+
+impl Recorder {
+    /// Record the interactions to the given writer.
+    pub fn new<W>(writer: W) -> Self
+    where
+        W: Write + Send + 'static,
+    {
+        Self {
+            sink: Arc::new(Mutex::new(writer)),
+        }
+    }
+
+    /// Record the interactions to the file at the given path, which is
+    /// truncated if it exists.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Ok(Self::new(File::create(path).map_err(Error::io)?))
+    }
+
+    /// Record the response to the given request, both serialized as JSON.
+    ///
+    /// Each interaction is flushed as soon as it is recorded, so that the
+    /// archive is usable even if the process is interrupted.
+    pub(crate) fn record(&self, request: &str, response: &str) -> Result<(), Error> {
+        let interaction = Interaction {
+            timestamp: now()?,
+            request: serde_json::from_str(request).map_err(Error::serde)?,
+            response: serde_json::from_str(response)
+                .unwrap_or_else(|_| Value::String(response.to_string())),
+        };
+        let mut line = serde_json::to_vec(&interaction).map_err(Error::serde)?;
+        line.push(b'\n');
+
+        let mut sink = self.sink.lock().unwrap();
+        sink.write_all(&line).map_err(Error::io)?;
+        sink.flush().map_err(Error::io)
+    }
+}
+
+fn now() -> Result<Time, Error> {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Error::client_internal(e.to_string()))?;
+    Time::from_unix_timestamp(since_epoch.as_secs() as i64, since_epoch.subsec_nanos())
+        .map_err(Error::tendermint)
+}
+
+/// A client answering requests with the responses recorded in an archive
+/// by a [`Recorder`], without connecting to a node.
+///
+/// Each request is answered with the first recorded response to a request
+/// of the same method with the same parameters which has not been replayed
+/// yet, regardless of the order in which the requests were recorded.
+/// Requests for which no such response is left fail with
+/// [`Error::mismatch_response`].
+///
+/// The compatibility mode must match the one the archive was recorded with.
+#[derive(Debug)]
+pub struct ReplayClient {
+    interactions: Mutex<VecDeque<Interaction>>,
+    compat: CompatMode,
+}
+
+impl ReplayClient {
+    /// Create a client replaying the given interactions.
+    pub fn new(interactions: impl IntoIterator<Item = Interaction>) -> Self {
+        Self {
+            interactions: Mutex::new(interactions.into_iter().collect()),
+            compat: Default::default(),
+        }
+    }
+
+    /// Create a client replaying the interactions of the archive at the
+    /// given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_reader(BufReader::new(File::open(path).map_err(Error::io)?))
+    }
+
+    /// Create a client replaying the interactions of the archive read from
+    /// the given reader.
+    pub fn from_reader<B: BufRead>(reader: B) -> Result<Self, Error> {
+        let mut interactions = Vec::new();
+        for line in reader.lines() {
+            let line = line.map_err(Error::io)?;
+            if !line.trim().is_empty() {
+                interactions.push(serde_json::from_str(&line).map_err(Error::serde)?);
+            }
+        }
+        Ok(Self::new(interactions))
+    }
+
+    /// Decode the replayed responses as per the given compatibility mode.
+    pub fn compat_mode(mut self, compat: CompatMode) -> Self {
+        self.compat = compat;
+        self
+    }
+
+    /// The interactions which have not been replayed yet.
+    pub fn remaining(&self) -> Vec<Interaction> {
+        self.interactions.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn replay<R, S>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest<S>,
+        S: dialect::Dialect,
+    {
+        let request: Value = serde_json::from_str(&request.into_json()).map_err(Error::serde)?;
+        let interaction = {
+            let mut interactions = self.interactions.lock().unwrap();
+            let index = interactions
+                .iter()
+                .position(|interaction| interaction.matches(&request))
+                .ok_or_else(Error::mismatch_response)?;
+            interactions.remove(index).unwrap()
+        };
+        let response = match interaction.response {
+            Value::String(s) => s,
+            other => other.to_string(),
+        };
+        R::Response::from_string(response).map(Into::into)
+    }
+
+    async fn perform_v0_34<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest<dialect::v0_34::Dialect>,
+    {
+        self.replay(request)
+    }
+}
+
+#[async_trait]
+impl Client for ReplayClient {
+    async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest,
+    {
+        self.replay(request)
+    }
+
+    async fn block_results<H>(&self, height: H) -> Result<endpoint::block_results::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        perform_with_compat!(self, endpoint::block_results::Request::new(height.into()))
+    }
+
+    async fn latest_block_results(&self) -> Result<endpoint::block_results::Response, Error> {
+        perform_with_compat!(self, endpoint::block_results::Request::default())
+    }
+
+    async fn header<H>(&self, height: H) -> Result<endpoint::header::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        let height = height.into();
+        match self.compat {
+            CompatMode::V0_37 => self.perform(endpoint::header::Request::new(height)).await,
+            CompatMode::V0_34 => {
+                let resp = self
+                    .perform_v0_34(endpoint::block::Request::new(height))
+                    .await?;
+                Ok(resp.into())
+            },
+        }
+    }
+
+    async fn latest_header(&self) -> Result<endpoint::header::Response, Error> {
+        match self.compat {
+            CompatMode::V0_37 => self.perform(endpoint::header::Request::default()).await,
+            CompatMode::V0_34 => {
+                let resp = self
+                    .perform_v0_34(endpoint::block::Request::default())
+                    .await?;
+                Ok(resp.into())
+            },
+        }
+    }
+
+    async fn header_by_hash(
+        &self,
+        hash: Hash,
+    ) -> Result<endpoint::header_by_hash::Response, Error> {
+        match self.compat {
+            CompatMode::V0_37 => {
+                self.perform(endpoint::header_by_hash::Request::new(hash))
+                    .await
+            },
+            CompatMode::V0_34 => {
+                let resp = self
+                    .perform_v0_34(endpoint::block_by_hash::Request::new(hash))
+                    .await?;
+                Ok(resp.into())
+            },
+        }
+    }
+
+    async fn broadcast_evidence(&self, e: Evidence) -> Result<endpoint::evidence::Response, Error> {
+        match self.compat {
+            CompatMode::V0_37 => self.perform(endpoint::evidence::Request::new(e)).await,
+            CompatMode::V0_34 => {
+                self.perform_v0_34(endpoint::evidence::Request::new(e))
+                    .await
+            },
+        }
+    }
+
+    async fn tx(&self, hash: Hash, prove: bool) -> Result<endpoint::tx::Response, Error> {
+        perform_with_compat!(self, endpoint::tx::Request::new(hash, prove))
+    }
+
+    async fn tx_search_with(
+        &self,
+        request: endpoint::tx_search::Request,
+    ) -> Result<endpoint::tx_search::Response, Error> {
+        perform_with_compat!(self, request)
+    }
+
+    async fn broadcast_tx_commit<T>(
+        &self,
+        tx: T,
+    ) -> Result<endpoint::broadcast::tx_commit::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        perform_with_compat!(self, endpoint::broadcast::tx_commit::Request::new(tx))
+    }
+
+    async fn check_tx<T>(&self, tx: T) -> Result<endpoint::check_tx::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        perform_with_compat!(self, endpoint::check_tx::Request::new(tx))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::request::Wrapper;
+
+    /// A writer that can be inspected while it is owned by a recorder.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn abci_info_response(id: &str, data: &str) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": {
+                "response": {
+                    "data": data,
+                    "last_block_height": "10",
+                    "last_block_app_hash": "AAAAAAAAAAA="
+                }
+            }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn records_and_replays() {
+        let buffer = SharedBuffer::default();
+        let recorder = Recorder::new(buffer.clone());
+        for data in ["first", "second"] {
+            let request = Wrapper::new(endpoint::abci_info::Request).into_json();
+            let id = serde_json::from_str::<Value>(&request).unwrap()["id"].clone();
+            recorder
+                .record(&request, &abci_info_response(id.as_str().unwrap(), data))
+                .unwrap();
+        }
+
+        let archive = buffer.0.lock().unwrap().clone();
+        let client = ReplayClient::from_reader(Cursor::new(archive)).unwrap();
+        assert_eq!(client.remaining().len(), 2);
+
+        // Identical requests are answered in the order they were recorded.
+        assert_eq!(client.abci_info().await.unwrap().data, "first");
+        assert_eq!(client.abci_info().await.unwrap().data, "second");
+        assert!(client.remaining().is_empty());
+
+        let err = client.abci_info().await.unwrap_err();
+        assert!(err.to_string().contains("no matching response"));
+    }
+
+    #[tokio::test]
+    async fn matches_requests_by_params() {
+        let request =
+            |height: u32| Wrapper::new(endpoint::commit::Request::new(height.into())).into_json();
+        let interaction = |height: u32| Interaction {
+            timestamp: Time::unix_epoch(),
+            request: serde_json::from_str(&request(height)).unwrap(),
+            response: Value::String("not a response".to_string()),
+        };
+        let client = ReplayClient::new([interaction(1), interaction(2)]);
+
+        assert!(client.commit(3_u32).await.is_err());
+        assert_eq!(client.remaining().len(), 2);
+
+        // The response is replayed and fails to parse.
+        assert!(client.commit(2_u32).await.is_err());
+        let remaining = client.remaining();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].request["params"]["height"], "1");
+    }
+}
@@ -29,9 +29,9 @@ impl SubscriptionRouter {
     /// Publishes the given error to all of the subscriptions to which the
     /// error is relevant, based on the given subscription id query.
     #[cfg_attr(not(feature = "websocket"), allow(dead_code))]
-    pub fn publish_error(&mut self, id: SubscriptionIdRef<'_>, err: Error) -> PublishResult {
+    pub async fn publish_error(&mut self, id: SubscriptionIdRef<'_>, err: Error) -> PublishResult {
         if let Some(query) = self.subscription_query(id).cloned() {
-            self.publish(query, Err(err))
+            self.publish(query, Err(err)).await
         } else {
             PublishResult::NoSubscribers
         }
@@ -52,24 +52,32 @@ impl SubscriptionRouter {
     /// Publishes the given event to all of the subscriptions to which the
     /// event is relevant, based on the associated query.
     #[cfg_attr(not(feature = "websocket"), allow(dead_code))]
-    pub fn publish_event(&mut self, ev: Event) -> PublishResult {
-        self.publish(ev.query.clone(), Ok(ev))
+    pub async fn publish_event(&mut self, ev: Event) -> PublishResult {
+        self.publish(ev.query.clone(), Ok(ev)).await
     }
 
     /// Publishes the given event/error to all of the subscriptions to which the
     /// event/error is relevant, based on the given query.
-    pub fn publish(&mut self, query: SubscriptionQuery, ev: Result<Event, Error>) -> PublishResult {
+    ///
+    /// Depending on the overflow policy of their buffers, this waits for the
+    /// subscribers to make room for the event/error.
+    pub async fn publish(
+        &mut self,
+        query: SubscriptionQuery,
+        ev: Result<Event, Error>,
+    ) -> PublishResult {
         let subs_for_query = match self.subscriptions.get_mut(&query) {
             Some(s) => s,
             None => return PublishResult::NoSubscribers,
         };
 
         // We assume here that any failure to publish an event is an indication
-        // that the receiver end of the channel has been dropped, which allows
-        // us to safely stop tracking the subscription.
+        // that the receiver end of the channel has been dropped, or that the
+        // subscription was terminated because of an overflow of its buffer,
+        // which allows us to safely stop tracking the subscription.
         let mut disconnected = HashSet::new();
         for (id, event_tx) in subs_for_query.iter_mut() {
-            if let Err(e) = event_tx.send(ev.clone()) {
+            if let Err(e) = event_tx.send(ev.clone()).await {
                 disconnected.insert(id.clone());
                 debug!(
                     "Automatically disconnecting subscription with ID {} for query \"{}\" due to failure to publish to it: {}",
@@ -111,10 +119,38 @@ impl SubscriptionRouter {
             .map(|subs_for_query| subs_for_query.len())
             .unwrap_or(0)
     }
+
+    /// Removes the subscription with the given ID, returning its query and
+    /// the number of subscriptions left for that query.
+    pub fn remove(&mut self, id: SubscriptionIdRef<'_>) -> Option<(SubscriptionQuery, usize)> {
+        let query = self.subscription_query(id)?.clone();
+        let subs_for_query = self.subscriptions.get_mut(&query)?;
+        subs_for_query.remove(id);
+        let left = subs_for_query.len();
+        if left == 0 {
+            self.subscriptions.remove(&query);
+        }
+        Some((query, left))
+    }
 }
 
 #[cfg(feature = "websocket-client")]
 impl SubscriptionRouter {
+    /// Terminates all of the subscriptions, whatever their query, with the
+    /// given error, which is received after their buffered events.
+    pub fn publish_error_to_all(&mut self, err: Error) {
+        for subs_for_query in self.subscriptions.values() {
+            for event_tx in subs_for_query.values() {
+                event_tx.terminate(err.clone());
+            }
+        }
+    }
+
+    /// Returns the number of active subscriptions.
+    pub fn num_subscriptions(&self) -> usize {
+        self.subscriptions.values().map(|subs| subs.len()).sum()
+    }
+
     /// Returns the number of active subscriptions for the given query.
     pub fn num_subscriptions_for_query(&self, query: impl ToString) -> usize {
         self.subscriptions
@@ -136,6 +172,7 @@ pub enum PublishResult {
 mod test {
     use std::path::PathBuf;
 
+    use futures::StreamExt;
     use tokio::{
         fs,
         time::{self, Duration},
@@ -143,7 +180,7 @@ mod test {
 
     use super::*;
     use crate::{
-        client::sync::{unbounded, ChannelRx},
+        client::subscription::{SubscriptionBuffer, SubscriptionRx},
         event::Event,
         utils::uuid_str,
     };
@@ -159,22 +196,19 @@ mod test {
         .unwrap()
     }
 
-    async fn must_recv<T>(ch: &mut ChannelRx<T>, timeout_ms: u64) -> T {
+    async fn must_recv(ch: &mut SubscriptionRx, timeout_ms: u64) -> Result<Event, Error> {
         let delay = time::sleep(Duration::from_millis(timeout_ms));
         tokio::select! {
             _ = delay, if !delay.is_elapsed() => panic!("timed out waiting for recv"),
-            Some(v) = ch.recv() => v,
+            Some(v) = ch.next() => v,
         }
     }
 
-    async fn must_not_recv<T>(ch: &mut ChannelRx<T>, timeout_ms: u64)
-    where
-        T: core::fmt::Debug,
-    {
+    async fn must_not_recv(ch: &mut SubscriptionRx, timeout_ms: u64) {
         let delay = time::sleep(Duration::from_millis(timeout_ms));
         tokio::select! {
             _ = delay, if !delay.is_elapsed() => (),
-            Some(v) = ch.recv() => panic!("got unexpected result from channel: {:?}", v),
+            Some(v) = ch.next() => panic!("got unexpected result from channel: {:?}", v),
         }
     }
 
@@ -196,9 +230,9 @@ mod test {
             let mut router = SubscriptionRouter::default();
 
             let (subs1_id, subs2_id, subs3_id) = (uuid_str(), uuid_str(), uuid_str());
-            let (subs1_event_tx, mut subs1_event_rx) = unbounded();
-            let (subs2_event_tx, mut subs2_event_rx) = unbounded();
-            let (subs3_event_tx, mut subs3_event_rx) = unbounded();
+            let (subs1_event_tx, mut subs1_event_rx) = SubscriptionBuffer::default().channel();
+            let (subs2_event_tx, mut subs2_event_rx) = SubscriptionBuffer::default().channel();
+            let (subs3_event_tx, mut subs3_event_rx) = SubscriptionBuffer::default().channel();
 
             // Two subscriptions with the same query
             router.add(subs1_id, "query1", subs1_event_tx);
@@ -208,7 +242,7 @@ mod test {
 
             let mut ev = read_event("subscribe_newblock_0").await;
             ev.query = "query1".into();
-            router.publish_event(ev.clone());
+            router.publish_event(ev.clone()).await;
 
             let subs1_ev = must_recv(&mut subs1_event_rx, 500).await.unwrap();
             let subs2_ev = must_recv(&mut subs2_event_rx, 500).await.unwrap();
@@ -217,7 +251,7 @@ mod test {
             assert_eq!(ev, subs2_ev);
 
             ev.query = "query2".into();
-            router.publish_event(ev.clone());
+            router.publish_event(ev.clone()).await;
 
             must_not_recv(&mut subs1_event_rx, 50).await;
             must_not_recv(&mut subs2_event_rx, 50).await;
@@ -244,9 +278,9 @@ mod test {
             let mut router = SubscriptionRouter::default();
 
             let (subs1_id, subs2_id, subs3_id) = (uuid_str(), uuid_str(), uuid_str());
-            let (subs1_event_tx, mut subs1_event_rx) = unbounded();
-            let (subs2_event_tx, mut subs2_event_rx) = unbounded();
-            let (subs3_event_tx, mut subs3_event_rx) = unbounded();
+            let (subs1_event_tx, mut subs1_event_rx) = SubscriptionBuffer::default().channel();
+            let (subs2_event_tx, mut subs2_event_rx) = SubscriptionBuffer::default().channel();
+            let (subs3_event_tx, mut subs3_event_rx) = SubscriptionBuffer::default().channel();
 
             // Two subscriptions with the same query
             router.add(subs1_id, "query1", subs1_event_tx);
@@ -256,7 +290,7 @@ mod test {
 
             let mut ev = read_event("subscribe_newblock_0").await;
             ev.query = "query1".into();
-            router.publish_event(ev.clone());
+            router.publish_event(ev.clone()).await;
 
             let subs1_ev = must_recv(&mut subs1_event_rx, 500).await.unwrap();
             let subs2_ev = must_recv(&mut subs2_event_rx, 500).await.unwrap();
@@ -265,7 +299,7 @@ mod test {
             assert_eq!(ev, subs2_ev);
 
             ev.query = "query2".into();
-            router.publish_event(ev.clone());
+            router.publish_event(ev.clone()).await;
 
             must_not_recv(&mut subs1_event_rx, 50).await;
             must_not_recv(&mut subs2_event_rx, 50).await;
@@ -0,0 +1,63 @@
+//! Connector wrapper bounding how long the HTTP transport takes to connect.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use std::io;
+
+use hyper::{service::Service, Uri};
+
+use crate::prelude::*;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A `hyper` connector failing connections which are not established within
+/// the given timeout, if any.
+#[derive(Debug, Clone)]
+pub struct TimeoutConnector<C> {
+    inner: C,
+    timeout: Option<Duration>,
+}
+
+impl<C> TimeoutConnector<C> {
+    pub fn new(inner: C, timeout: Option<Duration>) -> Self {
+        Self { inner, timeout }
+    }
+}
+
+impl<C> Service<Uri> for TimeoutConnector<C>
+where
+    C: Service<Uri>,
+    C::Error: Into<BoxError>,
+    C::Future: Send + 'static,
+{
+    type Response = C::Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let connecting = self.inner.call(uri);
+        let timeout = self.timeout;
+        Box::pin(async move {
+            let connected = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, connecting)
+                    .await
+                    .map_err(|_| {
+                        io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            format!("connection not established within {timeout:?}"),
+                        )
+                    })?,
+                None => connecting.await,
+            };
+            connected.map_err(Into::into)
+        })
+    }
+}
@@ -0,0 +1,83 @@
+//! Transports for clients running in web browsers, built on the `fetch` and
+//! `WebSocket` APIs.
+//!
+//! The JavaScript values these APIs deal with cannot be sent across threads,
+//! whereas the futures of the [`Client`](crate::Client) methods must be. The
+//! JavaScript side of each operation is therefore run as a task of its own on
+//! the event loop of the page (see [`spawn`]), which the clients wait for
+//! through a channel.
+
+use core::{future::Future, time::Duration};
+
+use js_sys::{Function, Promise};
+use tokio::sync::oneshot;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{prelude::*, Error};
+
+#[cfg(feature = "http-client")]
+pub mod http;
+#[cfg(feature = "websocket-client")]
+pub mod websocket;
+
+#[wasm_bindgen]
+extern "C" {
+    // Bound to the global object, so as to be available in web workers as
+    // well as in windows.
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &Function, timeout: i32) -> JsValue;
+}
+
+/// Run the given future as a task on the event loop of the page, returning a
+/// future of its output which can be sent across threads.
+pub(crate) fn spawn<F, T>(future: F) -> impl Future<Output = Result<T, Error>> + Send
+where
+    F: Future<Output = Result<T, Error>> + 'static,
+    T: Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    wasm_bindgen_futures::spawn_local(async move {
+        // The caller may have given up on the output in the meantime.
+        let _ = tx.send(future.await);
+    });
+    async move {
+        rx.await.map_err(|_| {
+            Error::client_internal("failed to hear back from the browser task".to_string())
+        })?
+    }
+}
+
+/// Wait for the given duration, with a timer of the browser.
+pub(crate) async fn sleep(duration: Duration) {
+    let millis = i32::try_from(duration.as_millis()).unwrap_or(i32::MAX);
+    let _ = spawn(async move {
+        let promise = Promise::new(&mut |resolve, _| {
+            set_timeout(&resolve, millis);
+        });
+        let _ = JsFuture::from(promise).await;
+        Ok(())
+    })
+    .await;
+}
+
+/// Wait for the given future for up to the given duration, failing with the
+/// given error otherwise.
+#[cfg(feature = "http-client")]
+pub(crate) async fn timeout<F, T>(duration: Duration, future: F, e: Error) -> Result<T, Error>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    tokio::select! {
+        res = future => res,
+        _ = sleep(duration) => Err(e),
+    }
+}
+
+/// The message of the given JavaScript error, e.g. as thrown by `fetch`.
+pub(crate) fn js_error_message(e: &JsValue) -> String {
+    e.dyn_ref::<js_sys::Error>()
+        .map(|e| String::from(e.message()))
+        .or_else(|| e.as_string())
+        .unwrap_or_else(|| format!("{e:?}"))
+}
@@ -0,0 +1,497 @@
+//! HTTP-based transport for Tendermint RPC clients running in web browsers,
+//! built on the `fetch` API.
+
+use core::{
+    convert::{TryFrom, TryInto},
+    future::Future,
+    str::FromStr,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use js_sys::Promise;
+use tendermint::{block::Height, evidence::Evidence, Hash};
+use tendermint_config::net;
+use tracing::Instrument;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Headers, Request, RequestInit, RequestMode};
+
+use super::{js_error_message, spawn, timeout};
+use crate::prelude::*;
+use crate::{
+    client::{
+        transport::{auth::header_map, request_span},
+        Authorization, Client, CompatMode,
+    },
+    dialect::{self, Dialect},
+    endpoint,
+    error::{ErrorDetail, ResponseContext},
+    Error, Response, Scheme, SimpleRequest, Url,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    // Bound to the global object, so as to be available in web workers as
+    // well as in windows.
+    #[wasm_bindgen(js_name = fetch)]
+    fn fetch_with_request(request: &Request) -> Promise;
+}
+
+/// A JSON-RPC/HTTP Tendermint RPC client for web browsers (implements
+/// [`crate::Client`]).
+///
+/// Requests are made with the `fetch` API of the browser, which handles the
+/// connections, TLS and proxies itself. The node must allow the origin of the
+/// page through its CORS settings (`cors_allowed_origins`).
+///
+/// Does not provide [`crate::event::Event`] subscription facilities (see
+/// [`crate::WebSocketClient`] for a client that does).
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// use tendermint_rpc::{Client, HttpClient};
+///
+/// wasm_bindgen_futures::spawn_local(async {
+///     let client = HttpClient::new("https://rpc.example.com").unwrap();
+///     let abci_info = client.abci_info().await.unwrap();
+///     web_sys::console::log_1(&format!("{abci_info:?}").into());
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    url: Url,
+    compat: CompatMode,
+    strict: bool,
+    headers: Vec<(String, String)>,
+    timeout: Option<Duration>,
+}
+
+/// The builder pattern constructor for [`HttpClient`].
+pub struct Builder {
+    url: HttpClientUrl,
+    compat: CompatMode,
+    strict: bool,
+    authorization: Option<Authorization>,
+    headers: Vec<(String, String)>,
+    timeout: Option<Duration>,
+}
+
+impl Builder {
+    /// Use the specified compatibility mode for the Tendermint RPC protocol.
+    ///
+    /// The default is the latest protocol version supported by this crate.
+    pub fn compat_mode(mut self, mode: CompatMode) -> Self {
+        self.compat = mode;
+        self
+    }
+
+    /// Reject responses to requests containing fields that are not known to
+    /// this crate.
+    ///
+    /// By default, unknown fields are ignored, so that the client can be used
+    /// with nodes running newer versions of Tendermint. Strict mode is meant
+    /// for conformance testing against a particular version.
+    pub fn strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Authorize the requests with the given `Authorization` header, e.g. to
+    /// reach nodes behind a reverse proxy requiring a bearer token.
+    ///
+    /// This takes precedence over the credentials of the URL of the node,
+    /// which are otherwise sent with HTTP Basic authentication.
+    pub fn authorization(mut self, authorization: Authorization) -> Self {
+        self.authorization = Some(authorization);
+        self
+    }
+
+    /// Send the given HTTP header with every request, e.g. the `X-Api-Key`
+    /// header required by hosted node providers.
+    ///
+    /// The node must allow the header through its CORS settings
+    /// (`cors_allowed_headers`), and browsers silently drop the headers they
+    /// do not let scripts set, such as `User-Agent`. The names and values of
+    /// the headers are validated when building the client.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Give up on requests whose response was not received within the given
+    /// duration, failing them with a `RequestTimeout` error.
+    ///
+    /// By default, requests are not timed out by the client.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Try to create a client with the options specified for this builder.
+    pub fn build(self) -> Result<HttpClient, Error> {
+        let url = self.url.0;
+        let authorization = self.authorization.or_else(|| {
+            url.username()
+                .map(|user| Authorization::basic(user, url.password().unwrap_or_default()))
+        });
+        let mut headers = vec![("Content-Type".to_string(), "application/json".to_string())];
+        if let Some(authorization) = authorization {
+            authorization.header_value()?;
+            headers.push(("Authorization".to_string(), authorization.to_string()));
+        }
+        header_map(&self.headers)?;
+        headers.extend(self.headers);
+
+        Ok(HttpClient {
+            url,
+            compat: self.compat,
+            strict: self.strict,
+            headers,
+            timeout: self.timeout,
+        })
+    }
+}
+
+impl HttpClient {
+    /// Construct a new Tendermint RPC HTTP/S client connecting to the given
+    /// URL.
+    pub fn new<U>(url: U) -> Result<Self, Error>
+    where
+        U: TryInto<HttpClientUrl, Error = Error>,
+    {
+        let url = url.try_into()?;
+        Self::builder(url).build()
+    }
+
+    /// Initiate a builder for a Tendermint RPC HTTP/S client connecting
+    /// to the given URL, so that more configuration options can be specified
+    /// with the builder.
+    pub fn builder(url: HttpClientUrl) -> Builder {
+        Builder {
+            url,
+            compat: Default::default(),
+            strict: false,
+            authorization: None,
+            headers: Vec::new(),
+            timeout: None,
+        }
+    }
+
+    /// Set compatibility mode on the instantiated client.
+    ///
+    /// As the HTTP client is stateless and does not support subscriptions,
+    /// the protocol version it uses can be changed at will, for example,
+    /// as a result of version discovery over the `/status` endpoint.
+    pub fn set_compat_mode(&mut self, compat: CompatMode) {
+        self.compat = compat;
+    }
+
+    /// Query the version of the node and switch to the compatibility mode
+    /// matching it, returning that mode.
+    ///
+    /// See [`Client::detect_compat_mode`].
+    pub async fn negotiate_compat_mode(&mut self) -> Result<CompatMode, Error> {
+        let compat = self.detect_compat_mode().await?;
+        self.set_compat_mode(compat);
+        Ok(compat)
+    }
+
+    /// Enable or disable strict decoding of responses on the instantiated
+    /// client (see [`Builder::strict_mode`]).
+    pub fn set_strict_mode(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Set or remove the timeout of the requests made by the instantiated
+    /// client (see [`Builder::timeout`]).
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// A copy of this client, whose requests time out after the given
+    /// duration instead of the timeout of this client.
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of this client, whose requests carry the given HTTP header,
+    /// replacing the values of this client for it (see [`Builder::header`]).
+    pub fn with_header(&self, name: &str, value: &str) -> Result<Self, Error> {
+        header_map(&[(name, value)])?;
+        let mut client = self.clone();
+        client
+            .headers
+            .retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        client.headers.push((name.to_string(), value.to_string()));
+        Ok(client)
+    }
+
+    /// Perform a request in the given RPC dialect, regardless of the
+    /// compatibility mode of the client.
+    ///
+    /// This lets forks of Tendermint whose RPC deviates from the conventions
+    /// of the supported protocol versions supply their own [`Dialect`].
+    pub async fn perform_with_dialect<R, S>(
+        &self,
+        request: R,
+        _dialect: S,
+    ) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest<S>,
+        S: Dialect,
+    {
+        let span = request_span(&request);
+        async move {
+            let method = request.method();
+            let request_body = request.into_json();
+            tracing::debug!("Outgoing request: {}", request_body);
+
+            let url = self.url.without_credentials();
+            let (status, response_body) = self
+                .bounded(spawn(fetch(url, self.headers.clone(), request_body)))
+                .await?;
+            tracing::debug!(status, "Incoming response: {}", response_body);
+
+            R::Response::from_string_with_mode(&response_body, self.strict)
+                .map(Into::into)
+                .map_err(|e| match e.detail() {
+                    ErrorDetail::Serde(_) | ErrorDetail::MalformedJson(_) => {
+                        let context = ResponseContext::new(
+                            method.to_string(),
+                            self.url.without_credentials(),
+                            status,
+                            &response_body,
+                        );
+                        Error::invalid_response(context, e)
+                    },
+                    _ => e,
+                })
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn perform_v0_34<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest<dialect::v0_34::Dialect>,
+    {
+        self.perform_with_dialect(request, dialect::v0_34::Dialect)
+            .await
+    }
+
+    /// Wait for the response to a request, for up to the timeout of the
+    /// client.
+    async fn bounded<T>(
+        &self,
+        response: impl Future<Output = Result<T, Error>>,
+    ) -> Result<T, Error> {
+        match self.timeout {
+            Some(duration) => timeout(duration, response, Error::request_timeout(duration)).await,
+            None => response.await,
+        }
+    }
+}
+
+/// Post the given JSON-RPC request to the given URL, returning the status and
+/// the body of the response.
+async fn fetch(
+    url: String,
+    headers: Vec<(String, String)>,
+    body: String,
+) -> Result<(u16, String), Error> {
+    let init = RequestInit::new();
+    init.set_method("POST");
+    init.set_mode(RequestMode::Cors);
+    init.set_body(&JsValue::from_str(&body));
+    let request_headers = Headers::new().map_err(fetch_error)?;
+    for (name, value) in &headers {
+        request_headers.append(name, value).map_err(fetch_error)?;
+    }
+    init.set_headers(&request_headers);
+    let request = Request::new_with_str_and_init(&url, &init).map_err(fetch_error)?;
+
+    let response: web_sys::Response = JsFuture::from(fetch_with_request(&request))
+        .await
+        .map_err(fetch_error)?
+        .unchecked_into();
+    let text = response.text().map_err(fetch_error)?;
+    let text = JsFuture::from(text).await.map_err(fetch_error)?;
+    Ok((response.status(), text.as_string().unwrap_or_default()))
+}
+
+fn fetch_error(e: JsValue) -> Error {
+    Error::fetch(js_error_message(&e))
+}
+
+#[async_trait]
+impl Client for HttpClient {
+    async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest,
+    {
+        self.perform_with_dialect(request, dialect::v0_37::Dialect)
+            .await
+    }
+
+    async fn block_results<H>(&self, height: H) -> Result<endpoint::block_results::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        perform_with_compat!(self, endpoint::block_results::Request::new(height.into()))
+    }
+
+    async fn latest_block_results(&self) -> Result<endpoint::block_results::Response, Error> {
+        perform_with_compat!(self, endpoint::block_results::Request::default())
+    }
+
+    async fn header<H>(&self, height: H) -> Result<endpoint::header::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        let height = height.into();
+        match self.compat {
+            CompatMode::V0_37 => self.perform(endpoint::header::Request::new(height)).await,
+            CompatMode::V0_34 => {
+                // Back-fill with a request to /block endpoint and
+                // taking just the header from the response.
+                let resp = self
+                    .perform_v0_34(endpoint::block::Request::new(height))
+                    .await?;
+                Ok(resp.into())
+            },
+        }
+    }
+
+    async fn latest_header(&self) -> Result<endpoint::header::Response, Error> {
+        match self.compat {
+            CompatMode::V0_37 => self.perform(endpoint::header::Request::default()).await,
+            CompatMode::V0_34 => {
+                // Back-fill with a request to /block endpoint and
+                // taking just the header from the response.
+                let resp = self
+                    .perform_v0_34(endpoint::block::Request::default())
+                    .await?;
+                Ok(resp.into())
+            },
+        }
+    }
+
+    async fn header_by_hash(
+        &self,
+        hash: Hash,
+    ) -> Result<endpoint::header_by_hash::Response, Error> {
+        match self.compat {
+            CompatMode::V0_37 => {
+                self.perform(endpoint::header_by_hash::Request::new(hash))
+                    .await
+            },
+            CompatMode::V0_34 => {
+                // Back-fill with a request to /block_by_hash endpoint and
+                // taking just the header from the response.
+                let resp = self
+                    .perform_v0_34(endpoint::block_by_hash::Request::new(hash))
+                    .await?;
+                Ok(resp.into())
+            },
+        }
+    }
+
+    /// `/broadcast_evidence`: broadcast an evidence.
+    async fn broadcast_evidence(&self, e: Evidence) -> Result<endpoint::evidence::Response, Error> {
+        match self.compat {
+            CompatMode::V0_37 => self.perform(endpoint::evidence::Request::new(e)).await,
+            CompatMode::V0_34 => {
+                self.perform_v0_34(endpoint::evidence::Request::new(e))
+                    .await
+            },
+        }
+    }
+
+    async fn tx(&self, hash: Hash, prove: bool) -> Result<endpoint::tx::Response, Error> {
+        perform_with_compat!(self, endpoint::tx::Request::new(hash, prove))
+    }
+
+    async fn tx_search_with(
+        &self,
+        request: endpoint::tx_search::Request,
+    ) -> Result<endpoint::tx_search::Response, Error> {
+        perform_with_compat!(self, request)
+    }
+
+    async fn broadcast_tx_commit<T>(
+        &self,
+        tx: T,
+    ) -> Result<endpoint::broadcast::tx_commit::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        perform_with_compat!(self, endpoint::broadcast::tx_commit::Request::new(tx))
+    }
+
+    async fn check_tx<T>(&self, tx: T) -> Result<endpoint::check_tx::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        perform_with_compat!(self, endpoint::check_tx::Request::new(tx))
+    }
+}
+
+/// A URL limited to use with HTTP clients.
+///
+/// Facilitates useful type conversions and inferences.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HttpClientUrl(Url);
+
+impl TryFrom<Url> for HttpClientUrl {
+    type Error = Error;
+
+    fn try_from(value: Url) -> Result<Self, Error> {
+        match value.scheme() {
+            Scheme::Http | Scheme::Https => Ok(Self(value)),
+            _ => Err(Error::invalid_url(value)),
+        }
+    }
+}
+
+impl FromStr for HttpClientUrl {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let url: Url = s.parse()?;
+        url.try_into()
+    }
+}
+
+impl TryFrom<&str> for HttpClientUrl {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<net::Address> for HttpClientUrl {
+    type Error = Error;
+
+    fn try_from(value: net::Address) -> Result<Self, Error> {
+        match value {
+            net::Address::Tcp {
+                peer_id: _,
+                host,
+                port,
+            } => format!("http://{host}:{port}").parse(),
+            net::Address::Unix { .. } => Err(Error::invalid_network_address()),
+        }
+    }
+}
+
+impl From<HttpClientUrl> for Url {
+    fn from(url: HttpClientUrl) -> Self {
+        url.0
+    }
+}
@@ -0,0 +1,1050 @@
+//! WebSocket-based clients for accessing Tendermint RPC functionality from
+//! web browsers, built on their `WebSocket` API.
+
+use alloc::{collections::BTreeMap as HashMap, fmt};
+use core::{
+    convert::{TryFrom, TryInto},
+    str::FromStr,
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tendermint::{block::Height, evidence::Evidence, Hash};
+use tendermint_config::net;
+use tokio::sync::watch;
+use tracing::{debug, error, Instrument};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{CloseEvent, Event as JsEvent, MessageEvent, WebSocket};
+
+use super::js_error_message;
+use crate::dialect::v0_34;
+use crate::{
+    client::{
+        subscription::{SubscriptionBuffer, SubscriptionTx},
+        sync::{unbounded, ChannelRx, ChannelTx},
+        transport::{
+            request_span,
+            router::{PublishResult, SubscriptionId, SubscriptionIdRef, SubscriptionRouter},
+        },
+        Client, CompatMode,
+    },
+    dialect::Dialect,
+    endpoint::{self, subscribe, unsubscribe},
+    error::Error,
+    event::{self, Event},
+    prelude::*,
+    query::Query,
+    request::Wrapper,
+    response,
+    utils::uuid_str,
+    Id, Request, Response, Scheme, SimpleRequest, Subscription, SubscriptionClient, Url,
+};
+
+/// The code of the close frame sent when a client closes the connection.
+const NORMAL_CLOSURE: u16 = 1000;
+
+/// Tendermint RPC client for web browsers that provides access to all RPC
+/// functionality (including [`Event`] subscription) over a WebSocket
+/// connection.
+///
+/// As with the native [`WebSocketClient`], this is a handle to its driver,
+/// which the caller is responsible for running, here as a task on the event
+/// loop of the page with `wasm_bindgen_futures::spawn_local`. See the example
+/// below.
+///
+/// The browser answers the PING messages of the node itself and does not let
+/// scripts send any, so that the driver has no keep-alive mechanism of its
+/// own. It terminates with an error once the browser reports the connection
+/// as broken.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// use futures::StreamExt;
+/// use tendermint_rpc::{query::EventType, SubscriptionClient, WebSocketClient};
+///
+/// wasm_bindgen_futures::spawn_local(async {
+///     let (client, driver) = WebSocketClient::new("wss://rpc.example.com/websocket")
+///         .await
+///         .unwrap();
+///     wasm_bindgen_futures::spawn_local(async move {
+///         let _ = driver.run().await;
+///     });
+///
+///     let mut blocks = client.subscribe(EventType::NewBlock.into()).await.unwrap();
+///     while let Some(Ok(event)) = blocks.next().await {
+///         web_sys::console::log_1(&format!("{event:?}").into());
+///     }
+/// });
+/// ```
+///
+/// [`WebSocketClient`]: https://docs.rs/tendermint-rpc/latest/tendermint_rpc/struct.WebSocketClient.html
+#[derive(Debug, Clone)]
+pub struct WebSocketClient {
+    cmd_tx: ChannelTx<DriverCommand>,
+    compat: CompatMode,
+    strict: bool,
+    subscription_buffer: SubscriptionBuffer,
+    status: DriverStatus,
+}
+
+/// The builder pattern constructor for [`WebSocketClient`].
+pub struct Builder {
+    url: WebSocketClientUrl,
+    compat: CompatMode,
+    strict: bool,
+    subscription_buffer: SubscriptionBuffer,
+}
+
+impl Builder {
+    /// Use the specified compatibility mode for the Tendermint RPC protocol.
+    ///
+    /// The default is the latest protocol version supported by this crate.
+    pub fn compat_mode(mut self, mode: CompatMode) -> Self {
+        self.compat = mode;
+        self
+    }
+
+    /// Reject responses to requests containing fields that are not known to
+    /// this crate.
+    ///
+    /// By default, unknown fields are ignored, so that the client can be used
+    /// with nodes running newer versions of Tendermint. Strict mode is meant
+    /// for conformance testing against a particular version.
+    pub fn strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Buffer the events of each subscription with the given buffer, whose
+    /// overflow policy decides what happens to the events of subscribers
+    /// which do not keep up.
+    ///
+    /// The default is a buffer of [`DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY`]
+    /// events, dropping the oldest ones once full.
+    ///
+    /// [`DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY`]: crate::client::DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY
+    pub fn subscription_buffer(mut self, buffer: SubscriptionBuffer) -> Self {
+        self.subscription_buffer = buffer;
+        self
+    }
+
+    /// Try to create a client with the options specified for this builder,
+    /// once the connection is open.
+    pub async fn build(self) -> Result<(WebSocketClient, WebSocketClientDriver), Error> {
+        let connection = Connection::open(&self.url.0).await?;
+        let (cmd_tx, cmd_rx) = unbounded();
+        let driver = WebSocketClientDriver::new(connection, cmd_rx, self.compat);
+
+        Ok((
+            WebSocketClient {
+                cmd_tx,
+                compat: self.compat,
+                strict: self.strict,
+                subscription_buffer: self.subscription_buffer,
+                status: driver.status(),
+            },
+            driver,
+        ))
+    }
+}
+
+impl WebSocketClient {
+    /// Construct a new WebSocket-based client connecting to the given
+    /// Tendermint node's RPC endpoint.
+    ///
+    /// Supports both `ws://` and `wss://` protocols.
+    pub async fn new<U>(url: U) -> Result<(Self, WebSocketClientDriver), Error>
+    where
+        U: TryInto<WebSocketClientUrl, Error = Error>,
+    {
+        let url = url.try_into()?;
+        Self::builder(url).build().await
+    }
+
+    /// Initiate a builder for a WebSocket-based client connecting to the given
+    /// Tendermint node's RPC endpoint.
+    ///
+    /// Supports both `ws://` and `wss://` protocols.
+    pub fn builder(url: WebSocketClientUrl) -> Builder {
+        Builder {
+            url,
+            compat: Default::default(),
+            strict: false,
+            subscription_buffer: SubscriptionBuffer::default(),
+        }
+    }
+
+    /// Whether the driver of this client has not terminated yet.
+    ///
+    /// Requests fail once the driver terminated, which
+    /// [`WebSocketClient::driver_exit`] tells the reason of.
+    pub fn is_healthy(&self) -> bool {
+        self.status.exit.borrow().is_none() && self.status.exit.has_changed().is_ok()
+    }
+
+    /// The number of subscriptions of the clients sharing the driver of this
+    /// one, which have not been terminated yet.
+    pub fn active_subscriptions(&self) -> usize {
+        self.status.subscriptions.load(Ordering::Relaxed)
+    }
+
+    /// Wait for the driver of this client to terminate, returning why it did.
+    ///
+    /// Closing the client (see [`SubscriptionClient::close`]) makes the
+    /// driver terminate once it received the responses to the pending
+    /// requests.
+    pub async fn driver_exit(&self) -> DriverExit {
+        let mut exit = self.status.exit.clone();
+        loop {
+            if let Some(exit) = exit.borrow_and_update().clone() {
+                return exit;
+            }
+            if exit.changed().await.is_err() {
+                // The driver may have terminated right before being dropped.
+                return exit.borrow().clone().unwrap_or(DriverExit::Dropped);
+            }
+        }
+    }
+
+    async fn perform_with_dialect<R, S>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest<S>,
+        S: Dialect,
+    {
+        let span = request_span(&request);
+        async move {
+            let wrapper = Wrapper::new(request);
+            let id = wrapper.id().to_string();
+            let wrapped_request = wrapper.into_json();
+            debug!("Outgoing request: {}", wrapped_request);
+
+            let (response_tx, mut response_rx) = unbounded();
+            self.cmd_tx
+                .send(DriverCommand::SimpleRequest(SimpleRequestCommand {
+                    id,
+                    wrapped_request,
+                    response_tx,
+                }))?;
+            let response = response_rx.recv().await.ok_or_else(driver_gone)??;
+            debug!("Incoming response: {}", response);
+
+            R::Response::from_string_with_mode(response, self.strict).map(Into::into)
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn perform_v0_34<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest<v0_34::Dialect>,
+    {
+        self.perform_with_dialect(request).await
+    }
+
+    async fn send_cmd_and_wait<F>(&self, cmd: F) -> Result<(), Error>
+    where
+        F: FnOnce(ChannelTx<Result<(), Error>>) -> DriverCommand,
+    {
+        let (response_tx, mut response_rx) = unbounded();
+        self.cmd_tx.send(cmd(response_tx))?;
+        response_rx.recv().await.ok_or_else(driver_gone)?
+    }
+}
+
+fn driver_gone() -> Error {
+    Error::client_internal("failed to hear back from WebSocket driver".to_string())
+}
+
+#[async_trait]
+impl Client for WebSocketClient {
+    async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest,
+    {
+        self.perform_with_dialect(request).await
+    }
+
+    async fn block_results<H>(&self, height: H) -> Result<endpoint::block_results::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        perform_with_compat!(self, endpoint::block_results::Request::new(height.into()))
+    }
+
+    async fn latest_block_results(&self) -> Result<endpoint::block_results::Response, Error> {
+        perform_with_compat!(self, endpoint::block_results::Request::default())
+    }
+
+    async fn header<H>(&self, height: H) -> Result<endpoint::header::Response, Error>
+    where
+        H: Into<Height> + Send,
+    {
+        let height = height.into();
+        match self.compat {
+            CompatMode::V0_37 => self.perform(endpoint::header::Request::new(height)).await,
+            CompatMode::V0_34 => {
+                // Back-fill with a request to /block endpoint and
+                // taking just the header from the response.
+                let resp = self
+                    .perform_v0_34(endpoint::block::Request::new(height))
+                    .await?;
+                Ok(resp.into())
+            },
+        }
+    }
+
+    async fn latest_header(&self) -> Result<endpoint::header::Response, Error> {
+        match self.compat {
+            CompatMode::V0_37 => self.perform(endpoint::header::Request::default()).await,
+            CompatMode::V0_34 => {
+                // Back-fill with a request to /block endpoint and
+                // taking just the header from the response.
+                let resp = self
+                    .perform_v0_34(endpoint::block::Request::default())
+                    .await?;
+                Ok(resp.into())
+            },
+        }
+    }
+
+    async fn header_by_hash(
+        &self,
+        hash: Hash,
+    ) -> Result<endpoint::header_by_hash::Response, Error> {
+        match self.compat {
+            CompatMode::V0_37 => {
+                self.perform(endpoint::header_by_hash::Request::new(hash))
+                    .await
+            },
+            CompatMode::V0_34 => {
+                // Back-fill with a request to /block_by_hash endpoint and
+                // taking just the header from the response.
+                let resp = self
+                    .perform_v0_34(endpoint::block_by_hash::Request::new(hash))
+                    .await?;
+                Ok(resp.into())
+            },
+        }
+    }
+
+    async fn tx(&self, hash: Hash, prove: bool) -> Result<endpoint::tx::Response, Error> {
+        perform_with_compat!(self, endpoint::tx::Request::new(hash, prove))
+    }
+
+    async fn tx_search_with(
+        &self,
+        request: endpoint::tx_search::Request,
+    ) -> Result<endpoint::tx_search::Response, Error> {
+        perform_with_compat!(self, request)
+    }
+
+    async fn broadcast_evidence(&self, e: Evidence) -> Result<endpoint::evidence::Response, Error> {
+        match self.compat {
+            CompatMode::V0_37 => self.perform(endpoint::evidence::Request::new(e)).await,
+            CompatMode::V0_34 => {
+                self.perform_v0_34(endpoint::evidence::Request::new(e))
+                    .await
+            },
+        }
+    }
+
+    async fn broadcast_tx_commit<T>(
+        &self,
+        tx: T,
+    ) -> Result<endpoint::broadcast::tx_commit::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        perform_with_compat!(self, endpoint::broadcast::tx_commit::Request::new(tx))
+    }
+
+    async fn check_tx<T>(&self, tx: T) -> Result<endpoint::check_tx::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        perform_with_compat!(self, endpoint::check_tx::Request::new(tx))
+    }
+}
+
+#[async_trait]
+impl SubscriptionClient for WebSocketClient {
+    async fn subscribe(&self, query: Query) -> Result<Subscription, Error> {
+        let (subscription_tx, subscription_rx) = self.subscription_buffer.channel();
+        // By default we use UUIDs to differentiate subscriptions
+        let id = uuid_str();
+        self.send_cmd_and_wait(|response_tx| {
+            DriverCommand::Subscribe(SubscribeCommand {
+                id: id.clone(),
+                query: query.to_string(),
+                subscription_tx,
+                response_tx,
+            })
+        })
+        .await?;
+        Ok(Subscription::new(id, query, subscription_rx))
+    }
+
+    async fn unsubscribe(&self, query: Query) -> Result<(), Error> {
+        self.send_cmd_and_wait(|response_tx| {
+            DriverCommand::Unsubscribe(UnsubscribeCommand {
+                query: query.to_string(),
+                response_tx,
+            })
+        })
+        .await
+    }
+
+    async fn release(&self, subscription: Subscription) -> Result<(), Error> {
+        self.send_cmd_and_wait(|response_tx| {
+            DriverCommand::Release(ReleaseCommand {
+                id: subscription.id().to_string(),
+                query: subscription.query().to_string(),
+                response_tx,
+            })
+        })
+        .await
+    }
+
+    fn close(self) -> Result<(), Error> {
+        self.cmd_tx.send(DriverCommand::Terminate)
+    }
+}
+
+/// Why a [`WebSocketClientDriver`] terminated.
+#[derive(Debug, Clone)]
+pub enum DriverExit {
+    /// The connection was closed as requested by a client, once the pending
+    /// requests were answered.
+    Closed,
+    /// The driver failed with the given error, e.g. as the connection broke.
+    Failed(Error),
+    /// The driver was dropped before terminating, e.g. as it was never run.
+    Dropped,
+}
+
+// The state of a driver shared with its clients.
+#[derive(Debug, Clone)]
+struct DriverStatus {
+    // The number of active subscriptions.
+    subscriptions: Arc<AtomicUsize>,
+    // Set once the driver terminated.
+    exit: watch::Receiver<Option<DriverExit>>,
+}
+
+/// A URL limited to use with WebSocket clients.
+///
+/// Facilitates useful type conversions and inferences.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WebSocketClientUrl(Url);
+
+impl TryFrom<Url> for WebSocketClientUrl {
+    type Error = Error;
+
+    fn try_from(value: Url) -> Result<Self, Error> {
+        match value.scheme() {
+            Scheme::WebSocket | Scheme::SecureWebSocket => Ok(Self(value)),
+            _ => Err(Error::invalid_params(format!(
+                "cannot use URL {value} with WebSocket clients"
+            ))),
+        }
+    }
+}
+
+impl FromStr for WebSocketClientUrl {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let url: Url = s.parse()?;
+        url.try_into()
+    }
+}
+
+impl fmt::Display for WebSocketClientUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl TryFrom<&str> for WebSocketClientUrl {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<net::Address> for WebSocketClientUrl {
+    type Error = Error;
+
+    fn try_from(value: net::Address) -> Result<Self, Error> {
+        match value {
+            net::Address::Tcp {
+                peer_id: _,
+                host,
+                port,
+            } => format!("ws://{host}:{port}/websocket").parse(),
+            net::Address::Unix { .. } => Err(Error::invalid_network_address()),
+        }
+    }
+}
+
+impl From<WebSocketClientUrl> for Url {
+    fn from(url: WebSocketClientUrl) -> Self {
+        url.0
+    }
+}
+
+// The different types of commands that can be sent from the WebSocketClient to
+// the driver.
+#[derive(Debug, Clone)]
+enum DriverCommand {
+    // Initiate a subscription request.
+    Subscribe(SubscribeCommand),
+    // Initiate an unsubscribe request.
+    Unsubscribe(UnsubscribeCommand),
+    // Terminate a single subscription.
+    Release(ReleaseCommand),
+    // For non-subscription-related requests.
+    SimpleRequest(SimpleRequestCommand),
+    Terminate,
+}
+
+#[derive(Debug, Clone)]
+struct SubscribeCommand {
+    // The desired ID for the outgoing JSON-RPC request.
+    id: String,
+    // The query for which we want to receive events.
+    query: String,
+    // Where to send subscription events.
+    subscription_tx: SubscriptionTx,
+    // Where to send the result of the subscription request.
+    response_tx: ChannelTx<Result<(), Error>>,
+}
+
+#[derive(Debug, Clone)]
+struct UnsubscribeCommand {
+    // The query from which to unsubscribe.
+    query: String,
+    // Where to send the result of the unsubscribe request.
+    response_tx: ChannelTx<Result<(), Error>>,
+}
+
+#[derive(Debug, Clone)]
+struct ReleaseCommand {
+    // The ID of the subscription to terminate.
+    id: String,
+    // The query of the subscription to terminate.
+    query: String,
+    // Where to send the result of the release, once the query is
+    // unsubscribed from if it was its last subscription.
+    response_tx: ChannelTx<Result<(), Error>>,
+}
+
+#[derive(Debug, Clone)]
+struct SimpleRequestCommand {
+    // The ID of the outgoing JSON-RPC request.
+    id: String,
+    // The wrapped and serialized JSON-RPC request.
+    wrapped_request: String,
+    // Where to send the result of the simple request.
+    response_tx: ChannelTx<Result<String, Error>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct GenericJsonResponse(serde_json::Value);
+
+impl Response for GenericJsonResponse {}
+
+// What the browser reports about the connection, through the event handlers
+// of the WebSocket.
+#[derive(Clone)]
+enum SocketEvent {
+    Open,
+    Text(String),
+    Error,
+    Closed(String),
+}
+
+// An open browser WebSocket, along with the event handlers forwarding its
+// events to the driver.
+struct Connection {
+    socket: WebSocket,
+    events: ChannelRx<SocketEvent>,
+    _on_open: Closure<dyn FnMut(JsEvent)>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_error: Closure<dyn FnMut(JsEvent)>,
+    _on_close: Closure<dyn FnMut(CloseEvent)>,
+}
+
+impl Connection {
+    async fn open(url: &Url) -> Result<Self, Error> {
+        let socket = WebSocket::new(&url.to_string()).map_err(|e| {
+            Error::web_socket(
+                format!("failed to connect to {url}: {}", js_error_message(&e)),
+                (),
+            )
+        })?;
+
+        let (events_tx, events) = unbounded();
+        // The driver may have terminated in the meantime.
+        let forward = move |tx: &ChannelTx<SocketEvent>, event| {
+            let _ = tx.send(event);
+        };
+        let tx = events_tx.clone();
+        let on_open = Closure::<dyn FnMut(JsEvent)>::new(move |_| forward(&tx, SocketEvent::Open));
+        let tx = events_tx.clone();
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |e: MessageEvent| {
+            // Binary messages are not part of the JSON-RPC protocol.
+            if let Some(text) = e.data().as_string() {
+                forward(&tx, SocketEvent::Text(text));
+            }
+        });
+        let tx = events_tx.clone();
+        let on_error =
+            Closure::<dyn FnMut(JsEvent)>::new(move |_| forward(&tx, SocketEvent::Error));
+        let tx = events_tx;
+        let on_close = Closure::<dyn FnMut(CloseEvent)>::new(move |e: CloseEvent| {
+            forward(
+                &tx,
+                SocketEvent::Closed(format!("code {}: {}", e.code(), e.reason())),
+            )
+        });
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        let mut connection = Self {
+            socket,
+            events,
+            _on_open: on_open,
+            _on_message: on_message,
+            _on_error: on_error,
+            _on_close: on_close,
+        };
+        match connection.events.recv().await {
+            Some(SocketEvent::Open) => Ok(connection),
+            Some(SocketEvent::Closed(reason)) => Err(Error::web_socket(
+                format!("failed to connect to {url}: connection closed with {reason}"),
+                (),
+            )),
+            _ => Err(Error::web_socket(format!("failed to connect to {url}"), ())),
+        }
+    }
+
+    fn send(&self, msg: &str) -> Result<(), Error> {
+        self.socket.send_with_str(msg).map_err(|e| {
+            Error::web_socket(
+                format!(
+                    "failed to write to WebSocket connection: {}",
+                    js_error_message(&e)
+                ),
+                (),
+            )
+        })
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // The handlers are dropped along with the connection, so must not be
+        // called by the browser anymore.
+        self.socket.set_onopen(None);
+        self.socket.set_onmessage(None);
+        self.socket.set_onerror(None);
+        self.socket.set_onclose(None);
+        let _ = self.socket.close();
+    }
+}
+
+/// Drives the WebSocket connection for a `WebSocketClient` instance.
+///
+/// This is the primary component responsible for transport-level interaction
+/// with the remote WebSocket endpoint. Its [`run`](Self::run) method must be
+/// spawned on the event loop of the page, with
+/// `wasm_bindgen_futures::spawn_local`.
+pub struct WebSocketClientDriver {
+    // The underlying browser WebSocket.
+    connection: Connection,
+    // Facilitates routing of events to their respective subscriptions.
+    router: SubscriptionRouter,
+    // How we receive incoming commands from the WebSocketClient.
+    cmd_rx: ChannelRx<DriverCommand>,
+    // Commands we've received but have not yet completed, indexed by their ID.
+    // A Terminate command is executed immediately.
+    pending_commands: HashMap<SubscriptionId, DriverCommand>,
+    // Subscribe commands waiting for the confirmation of a pending subscribe
+    // request for the same query, indexed by query.
+    waiting_subscriptions: HashMap<String, Vec<SubscribeCommand>>,
+    // The compatibility mode directing how to parse subscription events.
+    compat: CompatMode,
+    // Whether a client asked to close the connection, which the driver does
+    // once it completed the pending commands.
+    closing: bool,
+    // The number of active subscriptions, shared with the clients.
+    subscriptions: Arc<AtomicUsize>,
+    // Where to tell the clients why the driver terminated.
+    exit_tx: watch::Sender<Option<DriverExit>>,
+}
+
+impl WebSocketClientDriver {
+    fn new(connection: Connection, cmd_rx: ChannelRx<DriverCommand>, compat: CompatMode) -> Self {
+        Self {
+            connection,
+            router: SubscriptionRouter::default(),
+            cmd_rx,
+            pending_commands: HashMap::new(),
+            waiting_subscriptions: HashMap::new(),
+            compat,
+            closing: false,
+            subscriptions: Default::default(),
+            exit_tx: watch::channel(None).0,
+        }
+    }
+
+    fn status(&self) -> DriverStatus {
+        DriverStatus {
+            subscriptions: self.subscriptions.clone(),
+            exit: self.exit_tx.subscribe(),
+        }
+    }
+
+    /// Executes the WebSocket driver, which manages the underlying WebSocket
+    /// connection.
+    ///
+    /// When a client is closed, the driver stops accepting new commands, and
+    /// closes the connection once it received the responses to the pending
+    /// ones.
+    pub async fn run(mut self) -> Result<(), Error> {
+        let res = self.drive().await;
+        self.subscriptions.store(0, Ordering::Relaxed);
+        self.exit_tx.send_replace(Some(match &res {
+            Ok(()) => DriverExit::Closed,
+            Err(e) => DriverExit::Failed(e.clone()),
+        }));
+        res
+    }
+
+    async fn drive(&mut self) -> Result<(), Error> {
+        loop {
+            tokio::select! {
+                Some(event) = self.connection.events.recv() => match event {
+                    SocketEvent::Text(msg) => self.handle_text_msg(msg).await?,
+                    SocketEvent::Open => {},
+                    SocketEvent::Error => {
+                        let e = Error::web_socket(
+                            "failed to read from WebSocket connection".to_string(),
+                            (),
+                        );
+                        self.fail_all(e.clone());
+                        return Err(e);
+                    },
+                    SocketEvent::Closed(reason) => {
+                        let e = Error::web_socket(
+                            format!("WebSocket connection closed with {reason}"),
+                            (),
+                        );
+                        self.fail_all(e.clone());
+                        return Err(e);
+                    },
+                },
+                Some(cmd) = self.cmd_rx.recv() => match cmd {
+                    DriverCommand::Terminate => self.closing = true,
+                    cmd if self.closing => fail_command(
+                        cmd,
+                        Error::client_internal("WebSocket client is closing".to_string()),
+                    ),
+                    DriverCommand::Subscribe(subs_cmd) => self.subscribe(subs_cmd)?,
+                    DriverCommand::Unsubscribe(unsubs_cmd) => self.unsubscribe(unsubs_cmd)?,
+                    DriverCommand::Release(release_cmd) => self.release(release_cmd)?,
+                    DriverCommand::SimpleRequest(req_cmd) => self.simple_request(req_cmd)?,
+                },
+            }
+
+            // Subscriptions may also have been terminated by publishing to
+            // them.
+            self.count_subscriptions();
+            if self.closing
+                && self.pending_commands.is_empty()
+                && self.waiting_subscriptions.is_empty()
+            {
+                return self.close().await;
+            }
+        }
+    }
+
+    fn simple_request(&mut self, cmd: SimpleRequestCommand) -> Result<(), Error> {
+        if let Err(e) = self.connection.send(&cmd.wrapped_request) {
+            cmd.response_tx.send(Err(e.clone()))?;
+            return Err(e);
+        }
+        self.pending_commands
+            .insert(cmd.id.clone(), DriverCommand::SimpleRequest(cmd));
+        Ok(())
+    }
+
+    fn send_request<R>(&mut self, wrapper: Wrapper<R>) -> Result<(), Error>
+    where
+        R: Request,
+    {
+        self.connection
+            .send(&serde_json::to_string_pretty(&wrapper).unwrap())
+    }
+
+    fn subscribe(&mut self, cmd: SubscribeCommand) -> Result<(), Error> {
+        // If we already have an active subscription for the given query,
+        // there's no need to initiate another one. Just add this subscription
+        // to the router.
+        if self.router.num_subscriptions_for_query(cmd.query.clone()) > 0 {
+            let (id, query, subscription_tx, response_tx) =
+                (cmd.id, cmd.query, cmd.subscription_tx, cmd.response_tx);
+            self.router.add(id, query, subscription_tx);
+            self.count_subscriptions();
+            return response_tx.send(Ok(()));
+        }
+
+        // If a subscription request for the given query is already in
+        // flight, wait for its outcome instead of initiating another one.
+        if self.is_subscribing_to(&cmd.query) {
+            self.waiting_subscriptions
+                .entry(cmd.query.clone())
+                .or_default()
+                .push(cmd);
+            return Ok(());
+        }
+
+        // Otherwise, we need to initiate a subscription request.
+        let wrapper = Wrapper::new_with_id(
+            Id::Str(cmd.id.clone()),
+            subscribe::Request::new(cmd.query.clone()),
+        );
+        if let Err(e) = self.send_request(wrapper) {
+            cmd.response_tx.send(Err(e.clone()))?;
+            return Err(e);
+        }
+        self.pending_commands
+            .insert(cmd.id.clone(), DriverCommand::Subscribe(cmd));
+        Ok(())
+    }
+
+    fn unsubscribe(&mut self, cmd: UnsubscribeCommand) -> Result<(), Error> {
+        // Terminate all subscriptions for this query immediately. This
+        // prioritizes acknowledgement of the caller's wishes over networking
+        // problems.
+        let removed = self.router.remove_by_query(cmd.query.clone());
+        self.count_subscriptions();
+        if removed == 0 {
+            // If there were no subscriptions for this query, respond
+            // immediately.
+            cmd.response_tx.send(Ok(()))?;
+            return Ok(());
+        }
+        self.send_unsubscribe(cmd)
+    }
+
+    fn send_unsubscribe(&mut self, cmd: UnsubscribeCommand) -> Result<(), Error> {
+        // Unsubscribe requests can (and probably should) have distinct
+        // JSON-RPC IDs as compared to their subscription IDs.
+        let wrapper = Wrapper::new(unsubscribe::Request::new(cmd.query.clone()));
+        let req_id = wrapper.id().clone();
+        if let Err(e) = self.send_request(wrapper) {
+            cmd.response_tx.send(Err(e.clone()))?;
+            return Err(e);
+        }
+        self.pending_commands
+            .insert(req_id.to_string(), DriverCommand::Unsubscribe(cmd));
+        Ok(())
+    }
+
+    // Shares the number of active subscriptions with the clients, which must
+    // be done before responding to the commands changing it.
+    fn count_subscriptions(&self) {
+        self.subscriptions
+            .store(self.router.num_subscriptions(), Ordering::Relaxed);
+    }
+
+    fn is_subscribing_to(&self, query: &str) -> bool {
+        self.pending_commands
+            .values()
+            .any(|cmd| matches!(cmd, DriverCommand::Subscribe(subs_cmd) if subs_cmd.query == query))
+    }
+
+    fn release(&mut self, cmd: ReleaseCommand) -> Result<(), Error> {
+        // Only unsubscribe from the query once its last subscription is
+        // released. The subscription may also have been terminated already,
+        // e.g. by unsubscribing from its query.
+        let last = matches!(self.router.remove(&cmd.id), Some((_, 0)))
+            && !self.is_subscribing_to(&cmd.query);
+        self.count_subscriptions();
+        if !last {
+            cmd.response_tx.send(Ok(()))?;
+            return Ok(());
+        }
+
+        self.send_unsubscribe(UnsubscribeCommand {
+            query: cmd.query,
+            response_tx: cmd.response_tx,
+        })
+    }
+
+    async fn handle_text_msg(&mut self, msg: String) -> Result<(), Error> {
+        let parse_res = match self.compat {
+            CompatMode::V0_37 => event::v0_37::DeEvent::from_string(&msg).map(Into::into),
+            CompatMode::V0_34 => event::v0_34::DeEvent::from_string(&msg).map(Into::into),
+        };
+        if let Ok(ev) = parse_res {
+            debug!("JSON-RPC event: {}", msg);
+            self.publish_event(ev).await;
+            return Ok(());
+        }
+
+        let wrapper: response::Wrapper<GenericJsonResponse> = match serde_json::from_str(&msg) {
+            Ok(w) => w,
+            Err(e) => {
+                error!(
+                    "Failed to deserialize incoming message as a JSON-RPC message: {}",
+                    e
+                );
+
+                debug!("JSON-RPC message: {}", msg);
+
+                return Ok(());
+            },
+        };
+
+        debug!("Generic JSON-RPC message: {:?}", wrapper);
+
+        let id = wrapper.id().to_string();
+
+        let error = wrapper.into_error();
+        if let Some(e) = &error {
+            self.publish_error(&id, e.clone()).await;
+        }
+
+        if let Some(pending_cmd) = self.pending_commands.remove(&id) {
+            self.respond_to_pending_command(pending_cmd, msg, error)?;
+        };
+
+        // We ignore incoming messages whose ID we don't recognize (could be
+        // relating to a fire-and-forget unsubscribe request - see the
+        // unsubscribe_all_disconnected() method below).
+        Ok(())
+    }
+
+    async fn publish_error(&mut self, id: SubscriptionIdRef<'_>, err: Error) {
+        let res = self.router.publish_error(id, err).await;
+        self.unsubscribe_all_disconnected(res);
+    }
+
+    async fn publish_event(&mut self, ev: Event) {
+        let res = self.router.publish_event(ev).await;
+        self.unsubscribe_all_disconnected(res);
+    }
+
+    fn unsubscribe_all_disconnected(&mut self, res: PublishResult) {
+        if let PublishResult::AllDisconnected(query) = res {
+            debug!(
+                "All subscribers for query \"{}\" have disconnected. Unsubscribing from query...",
+                query
+            );
+
+            // If all subscribers have disconnected for this query, we need to
+            // unsubscribe from it. We issue a fire-and-forget unsubscribe
+            // message.
+            if let Err(e) = self.send_request(Wrapper::new(unsubscribe::Request::new(query))) {
+                error!("Failed to send unsubscribe request: {}", e);
+            }
+        }
+    }
+
+    fn respond_to_pending_command(
+        &mut self,
+        pending_cmd: DriverCommand,
+        response: String,
+        error: Option<Error>,
+    ) -> Result<(), Error> {
+        match pending_cmd {
+            DriverCommand::Subscribe(cmd) => {
+                // Subscriptions to the same query made in the meantime share
+                // the outcome of this request.
+                let waiting = self
+                    .waiting_subscriptions
+                    .remove(&cmd.query)
+                    .unwrap_or_default();
+                for cmd in core::iter::once(cmd).chain(waiting) {
+                    let (id, query, subscription_tx, response_tx) =
+                        (cmd.id, cmd.query, cmd.subscription_tx, cmd.response_tx);
+                    if let Some(e) = &error {
+                        // The caller may have given up on the subscription in
+                        // the meantime.
+                        let _ = response_tx.send(Err(e.clone()));
+                        continue;
+                    }
+                    self.router.add(id, query, subscription_tx);
+                    self.count_subscriptions();
+                    let _ = response_tx.send(Ok(()));
+                }
+                Ok(())
+            },
+            DriverCommand::Unsubscribe(cmd) => cmd.response_tx.send(Ok(())),
+            DriverCommand::SimpleRequest(cmd) => cmd.response_tx.send(Ok(response)),
+            _ => Ok(()),
+        }
+    }
+
+    // Returns the given error to all active subscriptions and pending
+    // commands, terminating them.
+    fn fail_all(&mut self, e: Error) {
+        self.router.publish_error_to_all(e.clone());
+        for (_, cmd) in core::mem::take(&mut self.pending_commands) {
+            fail_command(cmd, e.clone());
+        }
+        for cmd in core::mem::take(&mut self.waiting_subscriptions)
+            .into_values()
+            .flatten()
+        {
+            let _ = cmd.response_tx.send(Err(e.clone()));
+        }
+    }
+
+    async fn close(&mut self) -> Result<(), Error> {
+        self.connection
+            .socket
+            .close_with_code_and_reason(NORMAL_CLOSURE, "client closed WebSocket connection")
+            .map_err(|e| {
+                Error::web_socket(
+                    format!(
+                        "failed to close WebSocket connection: {}",
+                        js_error_message(&e)
+                    ),
+                    (),
+                )
+            })?;
+
+        // Wait for the closing handshake to complete.
+        while let Some(event) = self.connection.events.recv().await {
+            if matches!(event, SocketEvent::Closed(_) | SocketEvent::Error) {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Returns the given error to the issuer of the given command.
+fn fail_command(cmd: DriverCommand, e: Error) {
+    // The caller may have given up on the command in the meantime.
+    let _ = match cmd {
+        DriverCommand::Subscribe(cmd) => cmd.response_tx.send(Err(e)),
+        DriverCommand::Unsubscribe(cmd) => cmd.response_tx.send(Err(e)),
+        DriverCommand::SimpleRequest(cmd) => cmd.response_tx.send(Err(e)),
+        DriverCommand::Release(cmd) => cmd.response_tx.send(Err(e)),
+        DriverCommand::Terminate => Ok(()),
+    };
+}
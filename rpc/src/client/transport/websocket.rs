@@ -8,9 +8,12 @@ use core::{
 };
 
 use async_trait::async_trait;
+#[cfg(unix)]
+use async_tungstenite::tokio::TokioAdapter;
 use async_tungstenite::{
     tokio::ConnectStream,
     tungstenite::{
+        self,
         protocol::{frame::coding::CloseCode, CloseFrame},
         Message,
     },
@@ -21,17 +24,31 @@ use serde::{Deserialize, Serialize};
 use tokio::time::{Duration, Instant};
 use tracing::{debug, error};
 
-use tendermint::{block::Height, Hash};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+use tendermint::{
+    block::Height,
+    evidence::Evidence,
+    service::{Health, Service},
+    Hash,
+};
 use tendermint_config::net;
+use tokio::{sync::watch, task::JoinHandle};
 
 use super::router::{SubscriptionId, SubscriptionIdRef};
 use crate::dialect::v0_34;
 use crate::{
     client::{
-        subscription::SubscriptionTx,
+        subscription::{SubscriptionBuffer, SubscriptionTx},
         sync::{ChannelRx, ChannelTx},
-        transport::router::{PublishResult, SubscriptionRouter},
-        Client, CompatMode,
+        transport::{
+            auth::{header_map, Authorization},
+            proxy::Proxy,
+            router::{PublishResult, SubscriptionRouter},
+        },
+        Client, CompatMode, Recorder,
     },
     endpoint::{self, subscribe, unsubscribe},
     error::Error,
@@ -39,8 +56,7 @@ use crate::{
     prelude::*,
     query::Query,
     request::Wrapper,
-    response, Id, Order, Request, Response, Scheme, SimpleRequest, Subscription,
-    SubscriptionClient, Url,
+    response, Id, Request, Response, Scheme, SimpleRequest, Subscription, SubscriptionClient, Url,
 };
 
 // WebSocket connection times out if we haven't heard anything at all from the
@@ -79,20 +95,23 @@ pub use async_tungstenite::tungstenite::protocol::WebSocketConfig;
 ///
 /// ### Timeouts
 ///
-/// The WebSocket client connection times out after 30 seconds if it does not
-/// receive anything at all from the server. This will automatically return
-/// errors to all active subscriptions and terminate them.
+/// The WebSocket client connection is considered stale after 30 seconds if it
+/// does not receive anything at all from the server. The driver then returns
+/// a `ConnectionStale` error to all active subscriptions and pending requests,
+/// terminating them, and stops with that error, so that the caller can
+/// reconnect.
 ///
-/// This is not configurable at present.
+/// This can be changed with [`Builder::stale_timeout`].
 ///
 /// ### Keep-Alive
 ///
 /// The WebSocket client implements a keep-alive mechanism whereby it sends a
 /// PING message to the server every 27 seconds, matching the PING cadence of
 /// the Tendermint server (see [this code][tendermint-websocket-ping] for
-/// details).
+/// details). The PONG messages sent back by the server keep the connection
+/// from going stale.
 ///
-/// This is not configurable at present.
+/// This can be changed with [`Builder::ping_interval`].
 ///
 /// ## Examples
 ///
@@ -142,6 +161,10 @@ pub use async_tungstenite::tungstenite::protocol::WebSocketConfig;
 pub struct WebSocketClient {
     inner: sealed::WebSocketClient,
     compat: CompatMode,
+    strict: bool,
+    recorder: Option<Recorder>,
+    subscription_buffer: SubscriptionBuffer,
+    status: DriverStatus,
 }
 
 /// The builder pattern constructor for [`WebSocketClient`].
@@ -149,6 +172,15 @@ pub struct Builder {
     url: WebSocketClientUrl,
     compat: CompatMode,
     transport_config: Option<WebSocketConfig>,
+    proxy: Option<Proxy>,
+    proxy_from_env: bool,
+    strict: bool,
+    recorder: Option<Recorder>,
+    ping_interval: Duration,
+    stale_timeout: Duration,
+    authorization: Option<Authorization>,
+    headers: Vec<(String, String)>,
+    subscription_buffer: SubscriptionBuffer,
 }
 
 impl Builder {
@@ -166,17 +198,160 @@ impl Builder {
         self
     }
 
+    /// Connect through the given HTTP or SOCKS5 proxy, e.g. to reach nodes
+    /// over Tor with `socks5h://127.0.0.1:9050`.
+    ///
+    /// Connections are tunnelled through HTTP proxies with the [HTTP CONNECT]
+    /// method. Proxies reached over TLS (`https://`) are not supported.
+    ///
+    /// [HTTP CONNECT]: https://en.wikipedia.org/wiki/HTTP_tunnel
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Unless a proxy is specified with [`Builder::proxy`], connect through
+    /// the proxy specified by the `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY`
+    /// and `NO_PROXY` environment variables, if any (see
+    /// [`Proxy::from_env`]).
+    ///
+    /// By default, the environment is not consulted.
+    pub fn proxy_from_env(mut self) -> Self {
+        self.proxy_from_env = true;
+        self
+    }
+
+    /// Reject responses to requests containing fields that are not known to
+    /// this crate.
+    ///
+    /// By default, unknown fields are ignored, so that the client can be used
+    /// with nodes running newer versions of Tendermint. Strict mode is meant
+    /// for conformance testing against a particular version.
+    pub fn strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Record every request made by the client, along with the response
+    /// received for it, with the given recorder.
+    ///
+    /// Subscriptions and the events received through them are not recorded.
+    pub fn recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Send a PING message to the server at the given interval.
+    ///
+    /// The interval should be shorter than the stale timeout, so that the
+    /// PONG messages of a responsive server keep the connection alive. The
+    /// default is 27 seconds.
+    ///
+    /// Building the client fails if the interval is zero.
+    pub fn ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = interval;
+        self
+    }
+
+    /// Consider the connection stale once nothing at all has been received
+    /// from the server for the given duration.
+    ///
+    /// The default is 30 seconds.
+    pub fn stale_timeout(mut self, timeout: Duration) -> Self {
+        self.stale_timeout = timeout;
+        self
+    }
+
+    /// Authorize the WebSocket handshake with the given `Authorization`
+    /// header, e.g. to reach nodes behind a reverse proxy requiring a bearer
+    /// token.
+    ///
+    /// This takes precedence over the credentials of the URL of the node,
+    /// which are otherwise sent with HTTP Basic authentication.
+    pub fn authorization(mut self, authorization: Authorization) -> Self {
+        self.authorization = Some(authorization);
+        self
+    }
+
+    /// Send the given HTTP header with the WebSocket handshake, e.g. the
+    /// `X-Api-Key` header required by hosted node providers.
+    ///
+    /// Headers set several times are sent with all of their values. They
+    /// take precedence over the `Authorization` header set by the client
+    /// itself. The names and values of the headers are validated when
+    /// building the client.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Buffer the events of each subscription with the given buffer, whose
+    /// overflow policy decides what happens to the events of subscribers
+    /// which do not keep up.
+    ///
+    /// The default is a buffer of [`DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY`]
+    /// events, dropping the oldest ones once full.
+    ///
+    /// [`DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY`]: crate::client::DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY
+    pub fn subscription_buffer(mut self, buffer: SubscriptionBuffer) -> Self {
+        self.subscription_buffer = buffer;
+        self
+    }
+
     /// Try to create a client with the options specified for this builder.
     pub async fn build(self) -> Result<(WebSocketClient, WebSocketClientDriver), Error> {
+        if self.ping_interval.is_zero() {
+            return Err(Error::invalid_params(
+                "the ping interval must not be zero".to_string(),
+            ));
+        }
         let url = self.url.0;
         let compat = self.compat;
-        let (inner, driver) = if url.is_secure() {
-            sealed::WebSocketClient::new_secure(url, compat, self.transport_config).await?
+        let auth = self.authorization;
+        let headers = header_map(&self.headers)?;
+        let proxy = match self.proxy {
+            Some(proxy) => Some(proxy),
+            None if self.proxy_from_env => Proxy::from_env(&url)?,
+            None => None,
+        };
+        let (inner, driver) = if url.scheme() == Scheme::Unix {
+            if proxy.is_some() {
+                return Err(Error::invalid_params(
+                    "proxies cannot be used with unix:// URLs".to_string(),
+                ));
+            }
+            sealed::WebSocketClient::new_unix(url, auth, headers, compat, self.transport_config)
+                .await?
+        } else if let Some(proxy) = proxy {
+            sealed::WebSocketClient::new_proxied(
+                url,
+                auth,
+                headers,
+                proxy,
+                compat,
+                self.transport_config,
+            )
+            .await?
+        } else if url.is_secure() {
+            sealed::WebSocketClient::new_secure(url, auth, headers, compat, self.transport_config)
+                .await?
         } else {
-            sealed::WebSocketClient::new_unsecure(url, compat, self.transport_config).await?
+            sealed::WebSocketClient::new_unsecure(url, auth, headers, compat, self.transport_config)
+                .await?
         };
-
-        Ok((WebSocketClient { inner, compat }, driver))
+        let driver = driver.with_keepalive(self.ping_interval, self.stale_timeout);
+
+        Ok((
+            WebSocketClient {
+                inner,
+                compat,
+                strict: self.strict,
+                recorder: self.recorder,
+                subscription_buffer: self.subscription_buffer,
+                status: driver.status(),
+            },
+            driver,
+        ))
     }
 }
 
@@ -184,7 +359,9 @@ impl WebSocketClient {
     /// Construct a new WebSocket-based client connecting to the given
     /// Tendermint node's RPC endpoint.
     ///
-    /// Supports both `ws://` and `wss://` protocols.
+    /// Supports both `ws://` and `wss://` protocols. A `unix://` URL connects
+    /// to the `/websocket` endpoint of a node's RPC server listening on the
+    /// Unix domain socket at the URL's path.
     pub async fn new<U>(url: U) -> Result<(Self, WebSocketClientDriver), Error>
     where
         U: TryInto<WebSocketClientUrl, Error = Error>,
@@ -217,6 +394,47 @@ impl WebSocketClient {
             url,
             compat: Default::default(),
             transport_config: Default::default(),
+            proxy: None,
+            proxy_from_env: false,
+            strict: false,
+            recorder: None,
+            ping_interval: PING_INTERVAL,
+            stale_timeout: RECV_TIMEOUT,
+            authorization: None,
+            headers: Vec::new(),
+            subscription_buffer: SubscriptionBuffer::default(),
+        }
+    }
+
+    /// Whether the driver of this client has not terminated yet.
+    ///
+    /// Requests fail once the driver terminated, which
+    /// [`WebSocketClient::driver_exit`] tells the reason of.
+    pub fn is_healthy(&self) -> bool {
+        self.status.exit.borrow().is_none() && self.status.exit.has_changed().is_ok()
+    }
+
+    /// The number of subscriptions of the clients sharing the driver of this
+    /// one, which have not been terminated yet.
+    pub fn active_subscriptions(&self) -> usize {
+        self.status.subscriptions.load(Ordering::Relaxed)
+    }
+
+    /// Wait for the driver of this client to terminate, returning why it did.
+    ///
+    /// Closing the client (see [`SubscriptionClient::close`]) makes the
+    /// driver terminate once it received the responses to the pending
+    /// requests.
+    pub async fn driver_exit(&self) -> DriverExit {
+        let mut exit = self.status.exit.clone();
+        loop {
+            if let Some(exit) = exit.borrow_and_update().clone() {
+                return exit;
+            }
+            if exit.changed().await.is_err() {
+                // The driver may have terminated right before being dropped.
+                return exit.borrow().clone().unwrap_or(DriverExit::Dropped);
+            }
         }
     }
 
@@ -224,7 +442,9 @@ impl WebSocketClient {
     where
         R: SimpleRequest<v0_34::Dialect>,
     {
-        self.inner.perform(request).await
+        self.inner
+            .perform(request, self.strict, self.recorder.as_ref())
+            .await
     }
 }
 
@@ -234,7 +454,9 @@ impl Client for WebSocketClient {
     where
         R: SimpleRequest,
     {
-        self.inner.perform(request).await
+        self.inner
+            .perform(request, self.strict, self.recorder.as_ref())
+            .await
     }
 
     async fn block_results<H>(&self, height: H) -> Result<endpoint::block_results::Response, Error>
@@ -266,6 +488,20 @@ impl Client for WebSocketClient {
         }
     }
 
+    async fn latest_header(&self) -> Result<endpoint::header::Response, Error> {
+        match self.compat {
+            CompatMode::V0_37 => self.perform(endpoint::header::Request::default()).await,
+            CompatMode::V0_34 => {
+                // Back-fill with a request to /block endpoint and
+                // taking just the header from the response.
+                let resp = self
+                    .perform_v0_34(endpoint::block::Request::default())
+                    .await?;
+                Ok(resp.into())
+            },
+        }
+    }
+
     async fn header_by_hash(
         &self,
         hash: Hash,
@@ -290,18 +526,21 @@ impl Client for WebSocketClient {
         perform_with_compat!(self, endpoint::tx::Request::new(hash, prove))
     }
 
-    async fn tx_search(
+    async fn tx_search_with(
         &self,
-        query: Query,
-        prove: bool,
-        page: u32,
-        per_page: u8,
-        order: Order,
+        request: endpoint::tx_search::Request,
     ) -> Result<endpoint::tx_search::Response, Error> {
-        perform_with_compat!(
-            self,
-            endpoint::tx_search::Request::new(query, prove, page, per_page, order)
-        )
+        perform_with_compat!(self, request)
+    }
+
+    async fn broadcast_evidence(&self, e: Evidence) -> Result<endpoint::evidence::Response, Error> {
+        match self.compat {
+            CompatMode::V0_37 => self.perform(endpoint::evidence::Request::new(e)).await,
+            CompatMode::V0_34 => {
+                self.perform_v0_34(endpoint::evidence::Request::new(e))
+                    .await
+            },
+        }
     }
 
     async fn broadcast_tx_commit<T>(
@@ -313,23 +552,57 @@ impl Client for WebSocketClient {
     {
         perform_with_compat!(self, endpoint::broadcast::tx_commit::Request::new(tx))
     }
+
+    async fn check_tx<T>(&self, tx: T) -> Result<endpoint::check_tx::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        perform_with_compat!(self, endpoint::check_tx::Request::new(tx))
+    }
 }
 
 #[async_trait]
 impl SubscriptionClient for WebSocketClient {
     async fn subscribe(&self, query: Query) -> Result<Subscription, Error> {
-        self.inner.subscribe(query).await
+        self.inner.subscribe(query, self.subscription_buffer).await
     }
 
     async fn unsubscribe(&self, query: Query) -> Result<(), Error> {
         self.inner.unsubscribe(query).await
     }
 
+    async fn release(&self, subscription: Subscription) -> Result<(), Error> {
+        self.inner.release(subscription).await
+    }
+
     fn close(self) -> Result<(), Error> {
         self.inner.close()
     }
 }
 
+/// Why a [`WebSocketClientDriver`] terminated.
+#[derive(Debug, Clone)]
+pub enum DriverExit {
+    /// The connection was closed as requested by a client, once the pending
+    /// requests were answered.
+    Closed,
+    /// The driver failed with the given error, e.g. as the connection broke
+    /// or went stale.
+    Failed(Error),
+    /// The driver was dropped before terminating, e.g. as it was never run or
+    /// the task running it was aborted.
+    Dropped,
+}
+
+// The state of a driver shared with its clients.
+#[derive(Debug, Clone)]
+struct DriverStatus {
+    // The number of active subscriptions.
+    subscriptions: Arc<AtomicUsize>,
+    // Set once the driver terminated.
+    exit: watch::Receiver<Option<DriverExit>>,
+}
+
 /// A URL limited to use with WebSocket clients.
 ///
 /// Facilitates useful type conversions and inferences.
@@ -342,7 +615,7 @@ impl TryFrom<Url> for WebSocketClientUrl {
 
     fn try_from(value: Url) -> Result<Self, Error> {
         match value.scheme() {
-            Scheme::WebSocket | Scheme::SecureWebSocket => Ok(Self(value)),
+            Scheme::WebSocket | Scheme::SecureWebSocket | Scheme::Unix => Ok(Self(value)),
             _ => Err(Error::invalid_params(format!(
                 "cannot use URL {value} with WebSocket clients"
             ))),
@@ -383,9 +656,7 @@ impl TryFrom<net::Address> for WebSocketClientUrl {
                 host,
                 port,
             } => format!("ws://{host}:{port}/websocket").parse(),
-            net::Address::Unix { .. } => Err(Error::invalid_params(
-                "only TCP-based node addresses are supported".to_string(),
-            )),
+            net::Address::Unix { path } => format!("unix://{path}").parse(),
         }
     }
 }
@@ -396,21 +667,121 @@ impl From<WebSocketClientUrl> for Url {
     }
 }
 
+/// How often the end of the driver is checked while shutting down a
+/// [`WebSocketService`].
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A [`WebSocketClient`] whose driver runs in the background once started,
+/// managed as a [`Service`].
+///
+/// The service must be started from within a Tokio runtime, and, as shutting
+/// it down blocks until the driver terminated, be shut down from outside of
+/// it, e.g. with `tokio::task::spawn_blocking`. The driver is aborted if it
+/// did not terminate by the deadline.
+pub struct WebSocketService {
+    client: WebSocketClient,
+    driver: Option<WebSocketClientDriver>,
+    driver_hdl: Option<JoinHandle<()>>,
+    // The error the driver terminated with, if any.
+    failure: Arc<Mutex<Option<String>>>,
+}
+
+impl WebSocketService {
+    /// Manage the given client and its driver, as returned by
+    /// [`WebSocketClient::new`], as a service.
+    pub fn new(client: WebSocketClient, driver: WebSocketClientDriver) -> Self {
+        Self {
+            client,
+            driver: Some(driver),
+            driver_hdl: None,
+            failure: Default::default(),
+        }
+    }
+
+    /// The client, to perform requests with while the service is running.
+    pub fn client(&self) -> &WebSocketClient {
+        &self.client
+    }
+}
+
+impl Service for WebSocketService {
+    type Error = Error;
+
+    fn start(&mut self) -> Result<(), Error> {
+        let driver = self
+            .driver
+            .take()
+            .ok_or_else(Error::service_already_started)?;
+        let failure = self.failure.clone();
+        self.driver_hdl = Some(tokio::spawn(async move {
+            if let Err(e) = driver.run().await {
+                *failure.lock().unwrap() = Some(e.to_string());
+            }
+        }));
+        Ok(())
+    }
+
+    fn health(&self) -> Health {
+        if let Some(failure) = self.failure.lock().unwrap().clone() {
+            return Health::Failed(failure);
+        }
+        match (&self.driver, &self.driver_hdl) {
+            (Some(_), _) => Health::NotStarted,
+            (None, Some(hdl)) if !hdl.is_finished() => Health::Running,
+            _ => Health::Stopped,
+        }
+    }
+
+    fn shutdown(&mut self, deadline: Duration) -> Result<(), Error> {
+        let driver_hdl = match &self.driver_hdl {
+            Some(hdl) => hdl,
+            None => return Ok(()),
+        };
+        // The driver may have terminated already, dropping its commands.
+        let _ = self.client.clone().close();
+
+        let started = std::time::Instant::now();
+        while !driver_hdl.is_finished() {
+            let elapsed = started.elapsed();
+            if elapsed >= deadline {
+                driver_hdl.abort();
+                self.driver_hdl = None;
+                return Err(Error::shutdown_timeout(deadline));
+            }
+            std::thread::sleep(SHUTDOWN_POLL_INTERVAL.min(deadline - elapsed));
+        }
+        self.driver_hdl = None;
+        Ok(())
+    }
+}
+
 mod sealed {
+    #[cfg(unix)]
+    use async_tungstenite::tokio::client_async_with_config;
     use async_tungstenite::{
-        tokio::{connect_async_with_config, connect_async_with_tls_connector_and_config},
+        tokio::{
+            client_async_tls_with_config, connect_async_with_config,
+            connect_async_with_tls_connector_and_config,
+        },
         tungstenite::client::IntoClientRequest,
     };
-    use tracing::debug;
+    use http::HeaderMap;
+    use tracing::{debug, Instrument};
 
     use super::{
-        DriverCommand, SimpleRequestCommand, SubscribeCommand, UnsubscribeCommand,
-        WebSocketClientDriver, WebSocketConfig,
+        DriverCommand, DriverStream, ReleaseCommand, SimpleRequestCommand, SubscribeCommand,
+        UnsubscribeCommand, WebSocketClientDriver, WebSocketConfig,
     };
     use crate::{
         client::{
+            subscription::SubscriptionBuffer,
             sync::{unbounded, ChannelTx},
-            transport::auth::authorize,
+            transport::{
+                auth::{authorize, Authorization},
+                proxy::Proxy,
+                recording::Recorder,
+                request_span,
+            },
             CompatMode,
         },
         dialect::Dialect,
@@ -431,6 +802,17 @@ mod sealed {
     #[derive(Debug, Clone)]
     pub struct Secure;
 
+    /// Marker for the [`AsyncTungsteniteClient`] for clients operating over
+    /// connections tunnelled through a proxy.
+    #[derive(Debug, Clone)]
+    pub struct Proxied;
+
+    /// Marker for the [`AsyncTungsteniteClient`] for clients operating over
+    /// Unix domain socket connections.
+    #[cfg(unix)]
+    #[derive(Debug, Clone)]
+    pub struct UnixSocket;
+
     /// An [`async-tungstenite`]-based WebSocket client.
     ///
     /// Different modes of operation (secure and unsecure) are facilitated by
@@ -454,17 +836,21 @@ mod sealed {
         /// doesn't block the client.
         pub async fn new(
             url: Url,
+            auth: Option<Authorization>,
+            headers: HeaderMap,
             compat: CompatMode,
             config: Option<WebSocketConfig>,
         ) -> Result<(Self, WebSocketClientDriver), Error> {
             debug!("Connecting to unsecure WebSocket endpoint: {}", url);
 
-            let (stream, _response) = connect_async_with_config(url, config)
-                .await
-                .map_err(Error::tungstenite)?;
+            let (stream, _response) =
+                connect_async_with_config(handshake(url, auth, headers)?, config)
+                    .await
+                    .map_err(Error::tungstenite)?;
 
             let (cmd_tx, cmd_rx) = unbounded();
-            let driver = WebSocketClientDriver::new(stream, cmd_rx, compat);
+            let driver =
+                WebSocketClientDriver::new(DriverStream::Tcp(Box::new(stream)), cmd_rx, compat);
             let client = Self {
                 cmd_tx,
                 _client_type: Default::default(),
@@ -486,6 +872,8 @@ mod sealed {
         /// doesn't block the client.
         pub async fn new(
             url: Url,
+            auth: Option<Authorization>,
+            headers: HeaderMap,
             compat: CompatMode,
             config: Option<WebSocketConfig>,
         ) -> Result<(Self, WebSocketClientDriver), Error> {
@@ -493,13 +881,106 @@ mod sealed {
 
             // Not supplying a connector means async_tungstenite will create the
             // connector for us.
+            let (stream, _response) = connect_async_with_tls_connector_and_config(
+                handshake(url, auth, headers)?,
+                None,
+                config,
+            )
+            .await
+            .map_err(Error::tungstenite)?;
+
+            let (cmd_tx, cmd_rx) = unbounded();
+            let driver =
+                WebSocketClientDriver::new(DriverStream::Tcp(Box::new(stream)), cmd_rx, compat);
+            let client = Self {
+                cmd_tx,
+                _client_type: Default::default(),
+            };
+
+            Ok((client, driver))
+        }
+    }
+
+    impl AsyncTungsteniteClient<Proxied> {
+        /// Construct a WebSocket client. Immediately attempts to open a WebSocket
+        /// connection to the node with the given address, tunnelled through
+        /// the given proxy, and secured if the address is a `wss://` one.
+        ///
+        /// On success, this returns both a client handle (a `WebSocketClient`
+        /// instance) as well as the WebSocket connection driver. The execution of
+        /// this driver becomes the responsibility of the client owner, and must be
+        /// executed in a separate asynchronous context to the client to ensure it
+        /// doesn't block the client.
+        pub async fn new(
+            url: Url,
+            auth: Option<Authorization>,
+            headers: HeaderMap,
+            proxy: Proxy,
+            compat: CompatMode,
+            config: Option<WebSocketConfig>,
+        ) -> Result<(Self, WebSocketClientDriver), Error> {
+            debug!("Connecting to WebSocket endpoint {} through {}", url, proxy);
+
+            if proxy.is_secure() {
+                return Err(Error::invalid_params(
+                    "the WebSocket client cannot connect through HTTPS proxies".to_string(),
+                ));
+            }
+            let socket = proxy
+                .connect(url.host(), url.port())
+                .await
+                .map_err(Error::io)?;
             let (stream, _response) =
-                connect_async_with_tls_connector_and_config(url, None, config)
+                client_async_tls_with_config(handshake(url, auth, headers)?, socket, config)
                     .await
                     .map_err(Error::tungstenite)?;
 
             let (cmd_tx, cmd_rx) = unbounded();
-            let driver = WebSocketClientDriver::new(stream, cmd_rx, compat);
+            let driver =
+                WebSocketClientDriver::new(DriverStream::Tcp(Box::new(stream)), cmd_rx, compat);
+            let client = Self {
+                cmd_tx,
+                _client_type: Default::default(),
+            };
+
+            Ok((client, driver))
+        }
+    }
+
+    #[cfg(unix)]
+    impl AsyncTungsteniteClient<UnixSocket> {
+        /// Construct a WebSocket client. Immediately attempts to open a WebSocket
+        /// connection to the node's `/websocket` endpoint over the Unix domain
+        /// socket at the path of the given `unix://` URL.
+        ///
+        /// On success, this returns both a client handle (a `WebSocketClient`
+        /// instance) as well as the WebSocket connection driver. The execution of
+        /// this driver becomes the responsibility of the client owner, and must be
+        /// executed in a separate asynchronous context to the client to ensure it
+        /// doesn't block the client.
+        pub async fn new(
+            url: Url,
+            auth: Option<Authorization>,
+            headers: HeaderMap,
+            compat: CompatMode,
+            config: Option<WebSocketConfig>,
+        ) -> Result<(Self, WebSocketClientDriver), Error> {
+            debug!("Connecting to WebSocket endpoint over Unix socket: {}", url);
+
+            let socket = tokio::net::UnixStream::connect(url.path())
+                .await
+                .map_err(Error::io)?;
+            // The host is irrelevant when connecting over a Unix socket, but
+            // the WebSocket handshake request still needs one.
+            let request_url: Url = "ws://localhost/websocket".parse()?;
+            let (stream, _response) =
+                client_async_with_config(handshake(request_url, auth, headers)?, socket, config)
+                    .await
+                    .map_err(Error::tungstenite)?;
+
+            let (cmd_tx, cmd_rx) = unbounded();
+            let driver =
+                WebSocketClientDriver::new(DriverStream::Unix(Box::new(stream)), cmd_rx, compat);
             let client = Self {
                 cmd_tx,
                 _client_type: Default::default(),
@@ -521,36 +1002,54 @@ mod sealed {
     }
 
     impl<C> AsyncTungsteniteClient<C> {
-        pub async fn perform<R, S>(&self, request: R) -> Result<R::Output, Error>
+        pub async fn perform<R, S>(
+            &self,
+            request: R,
+            strict: bool,
+            recorder: Option<&Recorder>,
+        ) -> Result<R::Output, Error>
         where
             R: SimpleRequest<S>,
             S: Dialect,
         {
-            let wrapper = Wrapper::new(request);
-            let id = wrapper.id().to_string();
-            let wrapped_request = wrapper.into_json();
-
-            tracing::debug!("Outgoing request: {}", wrapped_request);
-
-            let (response_tx, mut response_rx) = unbounded();
-
-            self.send_cmd(DriverCommand::SimpleRequest(SimpleRequestCommand {
-                id,
-                wrapped_request,
-                response_tx,
-            }))?;
-
-            let response = response_rx.recv().await.ok_or_else(|| {
-                Error::client_internal("failed to hear back from WebSocket driver".to_string())
-            })??;
-
-            tracing::debug!("Incoming response: {}", response);
+            let span = request_span(&request);
+            async move {
+                let wrapper = Wrapper::new(request);
+                let id = wrapper.id().to_string();
+                let wrapped_request = wrapper.into_json();
+
+                tracing::debug!("Outgoing request: {}", wrapped_request);
+                let recorded_request = recorder.map(|_| wrapped_request.clone());
+
+                let (response_tx, mut response_rx) = unbounded();
+
+                self.send_cmd(DriverCommand::SimpleRequest(SimpleRequestCommand {
+                    id,
+                    wrapped_request,
+                    response_tx,
+                }))?;
+
+                let response = response_rx.recv().await.ok_or_else(|| {
+                    Error::client_internal("failed to hear back from WebSocket driver".to_string())
+                })??;
+
+                tracing::debug!("Incoming response: {}", response);
+                if let (Some(recorder), Some(request)) = (recorder, recorded_request) {
+                    recorder.record(&request, &response)?;
+                }
 
-            R::Response::from_string(response).map(Into::into)
+                R::Response::from_string_with_mode(response, strict).map(Into::into)
+            }
+            .instrument(span)
+            .await
         }
 
-        pub async fn subscribe(&self, query: Query) -> Result<Subscription, Error> {
-            let (subscription_tx, subscription_rx) = unbounded();
+        pub async fn subscribe(
+            &self,
+            query: Query,
+            buffer: SubscriptionBuffer,
+        ) -> Result<Subscription, Error> {
+            let (subscription_tx, subscription_rx) = buffer.channel();
             let (response_tx, mut response_rx) = unbounded();
             // By default we use UUIDs to differentiate subscriptions
             let id = uuid_str();
@@ -578,6 +1077,19 @@ mod sealed {
             })??;
             Ok(())
         }
+
+        pub async fn release(&self, subscription: Subscription) -> Result<(), Error> {
+            let (response_tx, mut response_rx) = unbounded();
+            self.send_cmd(DriverCommand::Release(ReleaseCommand {
+                id: subscription.id().to_string(),
+                query: subscription.query().to_string(),
+                response_tx,
+            }))?;
+            response_rx.recv().await.ok_or_else(|| {
+                Error::client_internal("failed to hear back from WebSocket driver".to_string())
+            })??;
+            Ok(())
+        }
     }
 
     /// Allows us to erase the type signatures associated with the different
@@ -586,53 +1098,117 @@ mod sealed {
     pub enum WebSocketClient {
         Unsecure(AsyncTungsteniteClient<Unsecure>),
         Secure(AsyncTungsteniteClient<Secure>),
+        Proxied(AsyncTungsteniteClient<Proxied>),
+        #[cfg(unix)]
+        Unix(AsyncTungsteniteClient<UnixSocket>),
     }
 
     impl WebSocketClient {
         pub async fn new_unsecure(
             url: Url,
+            auth: Option<Authorization>,
+            headers: HeaderMap,
             compat: CompatMode,
             config: Option<WebSocketConfig>,
         ) -> Result<(Self, WebSocketClientDriver), Error> {
             let (client, driver) =
-                AsyncTungsteniteClient::<Unsecure>::new(url, compat, config).await?;
+                AsyncTungsteniteClient::<Unsecure>::new(url, auth, headers, compat, config).await?;
             Ok((Self::Unsecure(client), driver))
         }
 
         pub async fn new_secure(
             url: Url,
+            auth: Option<Authorization>,
+            headers: HeaderMap,
             compat: CompatMode,
             config: Option<WebSocketConfig>,
         ) -> Result<(Self, WebSocketClientDriver), Error> {
             let (client, driver) =
-                AsyncTungsteniteClient::<Secure>::new(url, compat, config).await?;
+                AsyncTungsteniteClient::<Secure>::new(url, auth, headers, compat, config).await?;
             Ok((Self::Secure(client), driver))
         }
 
+        pub async fn new_proxied(
+            url: Url,
+            auth: Option<Authorization>,
+            headers: HeaderMap,
+            proxy: Proxy,
+            compat: CompatMode,
+            config: Option<WebSocketConfig>,
+        ) -> Result<(Self, WebSocketClientDriver), Error> {
+            let (client, driver) =
+                AsyncTungsteniteClient::<Proxied>::new(url, auth, headers, proxy, compat, config)
+                    .await?;
+            Ok((Self::Proxied(client), driver))
+        }
+
+        #[cfg(unix)]
+        pub async fn new_unix(
+            url: Url,
+            auth: Option<Authorization>,
+            headers: HeaderMap,
+            compat: CompatMode,
+            config: Option<WebSocketConfig>,
+        ) -> Result<(Self, WebSocketClientDriver), Error> {
+            let (client, driver) =
+                AsyncTungsteniteClient::<UnixSocket>::new(url, auth, headers, compat, config)
+                    .await?;
+            Ok((Self::Unix(client), driver))
+        }
+
+        #[cfg(not(unix))]
+        pub async fn new_unix(
+            _url: Url,
+            _auth: Option<Authorization>,
+            _headers: HeaderMap,
+            _compat: CompatMode,
+            _config: Option<WebSocketConfig>,
+        ) -> Result<(Self, WebSocketClientDriver), Error> {
+            Err(Error::unsupported_scheme("unix".to_string()))
+        }
+
         pub fn close(self) -> Result<(), Error> {
             match self {
                 WebSocketClient::Unsecure(c) => c.close(),
                 WebSocketClient::Secure(c) => c.close(),
+                WebSocketClient::Proxied(c) => c.close(),
+                #[cfg(unix)]
+                WebSocketClient::Unix(c) => c.close(),
             }
         }
     }
 
     impl WebSocketClient {
-        pub async fn perform<R, S>(&self, request: R) -> Result<R::Output, Error>
+        pub async fn perform<R, S>(
+            &self,
+            request: R,
+            strict: bool,
+            recorder: Option<&Recorder>,
+        ) -> Result<R::Output, Error>
         where
             R: SimpleRequest<S>,
             S: Dialect,
         {
             match self {
-                WebSocketClient::Unsecure(c) => c.perform(request).await,
-                WebSocketClient::Secure(c) => c.perform(request).await,
+                WebSocketClient::Unsecure(c) => c.perform(request, strict, recorder).await,
+                WebSocketClient::Secure(c) => c.perform(request, strict, recorder).await,
+                WebSocketClient::Proxied(c) => c.perform(request, strict, recorder).await,
+                #[cfg(unix)]
+                WebSocketClient::Unix(c) => c.perform(request, strict, recorder).await,
             }
         }
 
-        pub async fn subscribe(&self, query: Query) -> Result<Subscription, Error> {
+        pub async fn subscribe(
+            &self,
+            query: Query,
+            buffer: SubscriptionBuffer,
+        ) -> Result<Subscription, Error> {
             match self {
-                WebSocketClient::Unsecure(c) => c.subscribe(query).await,
-                WebSocketClient::Secure(c) => c.subscribe(query).await,
+                WebSocketClient::Unsecure(c) => c.subscribe(query, buffer).await,
+                WebSocketClient::Secure(c) => c.subscribe(query, buffer).await,
+                WebSocketClient::Proxied(c) => c.subscribe(query, buffer).await,
+                #[cfg(unix)]
+                WebSocketClient::Unix(c) => c.subscribe(query, buffer).await,
             }
         }
 
@@ -640,12 +1216,43 @@ mod sealed {
             match self {
                 WebSocketClient::Unsecure(c) => c.unsubscribe(query).await,
                 WebSocketClient::Secure(c) => c.unsubscribe(query).await,
+                WebSocketClient::Proxied(c) => c.unsubscribe(query).await,
+                #[cfg(unix)]
+                WebSocketClient::Unix(c) => c.unsubscribe(query).await,
+            }
+        }
+
+        pub async fn release(&self, subscription: Subscription) -> Result<(), Error> {
+            match self {
+                WebSocketClient::Unsecure(c) => c.release(subscription).await,
+                WebSocketClient::Secure(c) => c.release(subscription).await,
+                WebSocketClient::Proxied(c) => c.release(subscription).await,
+                #[cfg(unix)]
+                WebSocketClient::Unix(c) => c.release(subscription).await,
             }
         }
     }
 
     use async_tungstenite::tungstenite;
 
+    /// The request opening a WebSocket connection to the given URL,
+    /// authorized with the given authorization instead of the credentials of
+    /// the URL, if any.
+    pub fn handshake(
+        url: Url,
+        auth: Option<Authorization>,
+        headers: HeaderMap,
+    ) -> Result<tungstenite::handshake::client::Request, Error> {
+        let mut request = url.into_client_request().map_err(Error::tungstenite)?;
+        if let Some(auth) = auth {
+            request
+                .headers_mut()
+                .insert(http::header::AUTHORIZATION, auth.header_value()?);
+        }
+        request.headers_mut().extend(headers);
+        Ok(request)
+    }
+
     impl IntoClientRequest for Url {
         fn into_client_request(
             self,
@@ -685,6 +1292,8 @@ enum DriverCommand {
     Subscribe(SubscribeCommand),
     // Initiate an unsubscribe request.
     Unsubscribe(UnsubscribeCommand),
+    // Terminate a single subscription.
+    Release(ReleaseCommand),
     // For non-subscription-related requests.
     SimpleRequest(SimpleRequestCommand),
     Terminate,
@@ -710,6 +1319,17 @@ struct UnsubscribeCommand {
     response_tx: ChannelTx<Result<(), Error>>,
 }
 
+#[derive(Debug, Clone)]
+struct ReleaseCommand {
+    // The ID of the subscription to terminate.
+    id: String,
+    // The query of the subscription to terminate.
+    query: String,
+    // Where to send the result of the release, once the query is
+    // unsubscribed from if it was its last subscription.
+    response_tx: ChannelTx<Result<(), Error>>,
+}
+
 #[derive(Debug, Clone)]
 struct SimpleRequestCommand {
     // The desired ID for the outgoing JSON-RPC request. Technically we
@@ -725,6 +1345,32 @@ struct SimpleRequestCommand {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct GenericJsonResponse(serde_json::Value);
 
+// The WebSocket connection managed by the driver, established over either a
+// TCP connection or a Unix domain socket.
+enum DriverStream {
+    Tcp(Box<WebSocketStream<ConnectStream>>),
+    #[cfg(unix)]
+    Unix(Box<WebSocketStream<TokioAdapter<tokio::net::UnixStream>>>),
+}
+
+impl DriverStream {
+    async fn send(&mut self, msg: Message) -> Result<(), tungstenite::Error> {
+        match self {
+            DriverStream::Tcp(s) => s.send(msg).await,
+            #[cfg(unix)]
+            DriverStream::Unix(s) => s.send(msg).await,
+        }
+    }
+
+    async fn next(&mut self) -> Option<Result<Message, tungstenite::Error>> {
+        match self {
+            DriverStream::Tcp(s) => s.next().await,
+            #[cfg(unix)]
+            DriverStream::Unix(s) => s.next().await,
+        }
+    }
+}
+
 impl Response for GenericJsonResponse {}
 
 /// Drives the WebSocket connection for a `WebSocketClient` instance.
@@ -733,7 +1379,7 @@ impl Response for GenericJsonResponse {}
 /// with the remote WebSocket endpoint.
 pub struct WebSocketClientDriver {
     // The underlying WebSocket network connection.
-    stream: WebSocketStream<ConnectStream>,
+    stream: DriverStream,
     // Facilitates routing of events to their respective subscriptions.
     router: SubscriptionRouter,
     // How we receive incoming commands from the WebSocketClient.
@@ -741,25 +1387,55 @@ pub struct WebSocketClientDriver {
     // Commands we've received but have not yet completed, indexed by their ID.
     // A Terminate command is executed immediately.
     pending_commands: HashMap<SubscriptionId, DriverCommand>,
+    // Subscribe commands waiting for the confirmation of a pending subscribe
+    // request for the same query, indexed by query.
+    waiting_subscriptions: HashMap<String, Vec<SubscribeCommand>>,
     // The compatibility mode directing how to parse subscription events.
     compat: CompatMode,
+    // How frequently to send ping messages to the remote endpoint.
+    ping_interval: Duration,
+    // How long to wait for any message from the remote endpoint before
+    // considering the connection stale.
+    stale_timeout: Duration,
+    // Whether a client asked to close the connection, which the driver does
+    // once it completed the pending commands.
+    closing: bool,
+    // The number of active subscriptions, shared with the clients.
+    subscriptions: Arc<AtomicUsize>,
+    // Where to tell the clients why the driver terminated.
+    exit_tx: watch::Sender<Option<DriverExit>>,
 }
 
 impl WebSocketClientDriver {
-    fn new(
-        stream: WebSocketStream<ConnectStream>,
-        cmd_rx: ChannelRx<DriverCommand>,
-        compat: CompatMode,
-    ) -> Self {
+    fn new(stream: DriverStream, cmd_rx: ChannelRx<DriverCommand>, compat: CompatMode) -> Self {
         Self {
             stream,
             router: SubscriptionRouter::default(),
             cmd_rx,
             pending_commands: HashMap::new(),
+            waiting_subscriptions: HashMap::new(),
             compat,
+            ping_interval: PING_INTERVAL,
+            stale_timeout: RECV_TIMEOUT,
+            closing: false,
+            subscriptions: Default::default(),
+            exit_tx: watch::channel(None).0,
         }
     }
 
+    fn status(&self) -> DriverStatus {
+        DriverStatus {
+            subscriptions: self.subscriptions.clone(),
+            exit: self.exit_tx.subscribe(),
+        }
+    }
+
+    fn with_keepalive(mut self, ping_interval: Duration, stale_timeout: Duration) -> Self {
+        self.ping_interval = ping_interval;
+        self.stale_timeout = stale_timeout;
+        self
+    }
+
     async fn send_msg(&mut self, msg: Message) -> Result<(), Error> {
         self.stream.send(msg).await.map_err(|e| {
             Error::web_socket("failed to write to WebSocket connection".to_string(), e)
@@ -781,11 +1457,25 @@ impl WebSocketClientDriver {
 
     /// Executes the WebSocket driver, which manages the underlying WebSocket
     /// transport.
+    ///
+    /// When a client is closed, the driver stops accepting new commands, and
+    /// closes the connection once it received the responses to the pending
+    /// ones.
     pub async fn run(mut self) -> Result<(), Error> {
+        let res = self.drive().await;
+        self.subscriptions.store(0, Ordering::Relaxed);
+        self.exit_tx.send_replace(Some(match &res {
+            Ok(()) => DriverExit::Closed,
+            Err(e) => DriverExit::Failed(e.clone()),
+        }));
+        res
+    }
+
+    async fn drive(&mut self) -> Result<(), Error> {
         let mut ping_interval =
-            tokio::time::interval_at(Instant::now().add(PING_INTERVAL), PING_INTERVAL);
+            tokio::time::interval_at(Instant::now().add(self.ping_interval), self.ping_interval);
 
-        let recv_timeout = tokio::time::sleep(RECV_TIMEOUT);
+        let recv_timeout = tokio::time::sleep(self.stale_timeout);
         tokio::pin!(recv_timeout);
 
         loop {
@@ -794,7 +1484,7 @@ impl WebSocketClientDriver {
                     Ok(msg) => {
                         // Reset the receive timeout every time we successfully
                         // receive a message from the remote endpoint.
-                        recv_timeout.as_mut().reset(Instant::now().add(RECV_TIMEOUT));
+                        recv_timeout.as_mut().reset(Instant::now().add(self.stale_timeout));
                         self.handle_incoming_msg(msg).await?
                     },
                     Err(e) => return Err(
@@ -805,16 +1495,34 @@ impl WebSocketClientDriver {
                     ),
                 },
                 Some(cmd) = self.cmd_rx.recv() => match cmd {
+                    DriverCommand::Terminate => self.closing = true,
+                    cmd if self.closing => fail_command(
+                        cmd,
+                        Error::client_internal("WebSocket client is closing".to_string()),
+                    ),
                     DriverCommand::Subscribe(subs_cmd) => self.subscribe(subs_cmd).await?,
                     DriverCommand::Unsubscribe(unsubs_cmd) => self.unsubscribe(unsubs_cmd).await?,
+                    DriverCommand::Release(release_cmd) => self.release(release_cmd).await?,
                     DriverCommand::SimpleRequest(req_cmd) => self.simple_request(req_cmd).await?,
-                    DriverCommand::Terminate => return self.close().await,
                 },
                 _ = ping_interval.tick() => self.ping().await?,
                 _ = &mut recv_timeout => {
-                    return Err(Error::web_socket_timeout(RECV_TIMEOUT));
+                    let e = Error::connection_stale(self.stale_timeout);
+                    error!("{}", e);
+                    self.fail_all(e.clone());
+                    return Err(e);
                 }
             }
+
+            // Subscriptions may also have been terminated by publishing to
+            // them.
+            self.count_subscriptions();
+            if self.closing
+                && self.pending_commands.is_empty()
+                && self.waiting_subscriptions.is_empty()
+            {
+                return self.close().await;
+            }
         }
     }
 
@@ -836,9 +1544,20 @@ impl WebSocketClientDriver {
             let (id, query, subscription_tx, response_tx) =
                 (cmd.id, cmd.query, cmd.subscription_tx, cmd.response_tx);
             self.router.add(id, query, subscription_tx);
+            self.count_subscriptions();
             return response_tx.send(Ok(()));
         }
 
+        // If a subscription request for the given query is already in
+        // flight, wait for its outcome instead of initiating another one.
+        if self.is_subscribing_to(&cmd.query) {
+            self.waiting_subscriptions
+                .entry(cmd.query.clone())
+                .or_default()
+                .push(cmd);
+            return Ok(());
+        }
+
         // Otherwise, we need to initiate a subscription request.
         let wrapper = Wrapper::new_with_id(
             Id::Str(cmd.id.clone()),
@@ -857,13 +1576,18 @@ impl WebSocketClientDriver {
         // Terminate all subscriptions for this query immediately. This
         // prioritizes acknowledgement of the caller's wishes over networking
         // problems.
-        if self.router.remove_by_query(cmd.query.clone()) == 0 {
+        let removed = self.router.remove_by_query(cmd.query.clone());
+        self.count_subscriptions();
+        if removed == 0 {
             // If there were no subscriptions for this query, respond
             // immediately.
             cmd.response_tx.send(Ok(()))?;
             return Ok(());
         }
+        self.send_unsubscribe(cmd).await
+    }
 
+    async fn send_unsubscribe(&mut self, cmd: UnsubscribeCommand) -> Result<(), Error> {
         // Unsubscribe requests can (and probably should) have distinct
         // JSON-RPC IDs as compared to their subscription IDs.
         let wrapper = Wrapper::new(unsubscribe::Request::new(cmd.query.clone()));
@@ -877,6 +1601,38 @@ impl WebSocketClientDriver {
         Ok(())
     }
 
+    // Shares the number of active subscriptions with the clients, which must
+    // be done before responding to the commands changing it.
+    fn count_subscriptions(&self) {
+        self.subscriptions
+            .store(self.router.num_subscriptions(), Ordering::Relaxed);
+    }
+
+    fn is_subscribing_to(&self, query: &str) -> bool {
+        self.pending_commands
+            .values()
+            .any(|cmd| matches!(cmd, DriverCommand::Subscribe(subs_cmd) if subs_cmd.query == query))
+    }
+
+    async fn release(&mut self, cmd: ReleaseCommand) -> Result<(), Error> {
+        // Only unsubscribe from the query once its last subscription is
+        // released. The subscription may also have been terminated already,
+        // e.g. by unsubscribing from its query.
+        let last = matches!(self.router.remove(&cmd.id), Some((_, 0)))
+            && !self.is_subscribing_to(&cmd.query);
+        self.count_subscriptions();
+        if !last {
+            cmd.response_tx.send(Ok(()))?;
+            return Ok(());
+        }
+
+        self.send_unsubscribe(UnsubscribeCommand {
+            query: cmd.query,
+            response_tx: cmd.response_tx,
+        })
+        .await
+    }
+
     async fn handle_incoming_msg(&mut self, msg: Message) -> Result<(), Error> {
         match msg {
             Message::Text(s) => self.handle_text_msg(s).await,
@@ -914,12 +1670,14 @@ impl WebSocketClientDriver {
 
         let id = wrapper.id().to_string();
 
-        if let Some(e) = wrapper.into_error() {
-            self.publish_error(&id, e).await;
+        let error = wrapper.into_error();
+        if let Some(e) = &error {
+            self.publish_error(&id, e.clone()).await;
         }
 
         if let Some(pending_cmd) = self.pending_commands.remove(&id) {
-            self.respond_to_pending_command(pending_cmd, msg).await?;
+            self.respond_to_pending_command(pending_cmd, msg, error)
+                .await?;
         };
 
         // We ignore incoming messages whose ID we don't recognize (could be
@@ -929,7 +1687,7 @@ impl WebSocketClientDriver {
     }
 
     async fn publish_error(&mut self, id: SubscriptionIdRef<'_>, err: Error) {
-        if let PublishResult::AllDisconnected(query) = self.router.publish_error(id, err) {
+        if let PublishResult::AllDisconnected(query) = self.router.publish_error(id, err).await {
             debug!(
                 "All subscribers for query \"{}\" have disconnected. Unsubscribing from query...",
                 query
@@ -948,7 +1706,7 @@ impl WebSocketClientDriver {
     }
 
     async fn publish_event(&mut self, ev: Event) {
-        if let PublishResult::AllDisconnected(query) = self.router.publish_event(ev) {
+        if let PublishResult::AllDisconnected(query) = self.router.publish_event(ev).await {
             debug!(
                 "All subscribers for query \"{}\" have disconnected. Unsubscribing from query...",
                 query
@@ -970,13 +1728,30 @@ impl WebSocketClientDriver {
         &mut self,
         pending_cmd: DriverCommand,
         response: String,
+        error: Option<Error>,
     ) -> Result<(), Error> {
         match pending_cmd {
             DriverCommand::Subscribe(cmd) => {
-                let (id, query, subscription_tx, response_tx) =
-                    (cmd.id, cmd.query, cmd.subscription_tx, cmd.response_tx);
-                self.router.add(id, query, subscription_tx);
-                response_tx.send(Ok(()))
+                // Subscriptions to the same query made in the meantime share
+                // the outcome of this request.
+                let waiting = self
+                    .waiting_subscriptions
+                    .remove(&cmd.query)
+                    .unwrap_or_default();
+                for cmd in core::iter::once(cmd).chain(waiting) {
+                    let (id, query, subscription_tx, response_tx) =
+                        (cmd.id, cmd.query, cmd.subscription_tx, cmd.response_tx);
+                    if let Some(e) = &error {
+                        // The caller may have given up on the subscription in
+                        // the meantime.
+                        let _ = response_tx.send(Err(e.clone()));
+                        continue;
+                    }
+                    self.router.add(id, query, subscription_tx);
+                    self.count_subscriptions();
+                    let _ = response_tx.send(Ok(()));
+                }
+                Ok(())
             },
             DriverCommand::Unsubscribe(cmd) => cmd.response_tx.send(Ok(())),
             DriverCommand::SimpleRequest(cmd) => cmd.response_tx.send(Ok(response)),
@@ -984,6 +1759,21 @@ impl WebSocketClientDriver {
         }
     }
 
+    // Returns the given error to all active subscriptions and pending
+    // commands, terminating them.
+    fn fail_all(&mut self, e: Error) {
+        self.router.publish_error_to_all(e.clone());
+        for (_, cmd) in core::mem::take(&mut self.pending_commands) {
+            fail_command(cmd, e.clone());
+        }
+        for cmd in core::mem::take(&mut self.waiting_subscriptions)
+            .into_values()
+            .flatten()
+        {
+            let _ = cmd.response_tx.send(Err(e.clone()));
+        }
+    }
+
     async fn pong(&mut self, v: Vec<u8>) -> Result<(), Error> {
         self.send_msg(Message::Pong(v)).await
     }
@@ -992,7 +1782,7 @@ impl WebSocketClientDriver {
         self.send_msg(Message::Ping(Vec::new())).await
     }
 
-    async fn close(mut self) -> Result<(), Error> {
+    async fn close(&mut self) -> Result<(), Error> {
         self.send_msg(Message::Close(Some(CloseFrame {
             code: CloseCode::Normal,
             reason: Cow::from("client closed WebSocket connection"),
@@ -1008,6 +1798,18 @@ impl WebSocketClientDriver {
     }
 }
 
+// Returns the given error to the issuer of the given command.
+fn fail_command(cmd: DriverCommand, e: Error) {
+    // The caller may have given up on the command in the meantime.
+    let _ = match cmd {
+        DriverCommand::Subscribe(cmd) => cmd.response_tx.send(Err(e)),
+        DriverCommand::Unsubscribe(cmd) => cmd.response_tx.send(Err(e)),
+        DriverCommand::SimpleRequest(cmd) => cmd.response_tx.send(Err(e)),
+        DriverCommand::Release(cmd) => cmd.response_tx.send(Err(e)),
+        DriverCommand::Terminate => Ok(()),
+    };
+}
+
 #[cfg(test)]
 mod test {
     use alloc::collections::BTreeMap as HashMap;
@@ -1480,6 +2282,87 @@ mod test {
                 );
             }
         }
+        #[tokio::test]
+        async fn subscriptions_to_the_same_query_share_one_node_subscription() {
+            let event = read_event("subscribe_newblock_0").await;
+
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let (event_tx, mut event_rx) = unbounded::<Event>();
+            // Record the methods of the requests received, publishing events
+            // to the subscription made last.
+            let server_hdl = tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut conn = accept_async(stream).await.unwrap();
+                let mut methods = Vec::new();
+                let mut subs_id = None;
+                loop {
+                    tokio::select! {
+                        Some(msg) = conn.next() => {
+                            let msg = match msg.unwrap() {
+                                Message::Text(msg) => msg,
+                                Message::Close(_) => return methods,
+                                _ => continue,
+                            };
+                            let req: serde_json::Value = serde_json::from_str(&msg).unwrap();
+                            let id: Id = serde_json::from_value(req["id"].clone()).unwrap();
+                            let method = req["method"].as_str().unwrap().to_string();
+                            if method == "subscribe" {
+                                subs_id = Some(id.clone());
+                            }
+                            methods.push(method);
+                            let res = response::Wrapper::new_with_id(
+                                id,
+                                Some(serde_json::json!({})),
+                                None,
+                            );
+                            let res = serde_json::to_string(&res).unwrap();
+                            conn.send(Message::Text(res)).await.unwrap();
+                        },
+                        Some(ev) = event_rx.recv() => {
+                            let ev: event::v0_37::SerEvent = ev.into();
+                            let res = response::Wrapper::new_with_id(subs_id.clone().unwrap(), Some(ev), None);
+                            let res = serde_json::to_string(&res).unwrap();
+                            conn.send(Message::Text(res)).await.unwrap();
+                        },
+                    }
+                }
+            });
+
+            let url = format!("ws://127.0.0.1:{port}/websocket").parse().unwrap();
+            let (client, driver) = WebSocketClient::builder(url)
+                .compat_mode(CompatMode::V0_37)
+                .build()
+                .await
+                .unwrap();
+            let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+            let query: Query = EventType::NewBlock.into();
+            let (subs1, subs2) = tokio::join!(
+                client.subscribe(query.clone()),
+                client.subscribe(query.clone())
+            );
+            let (mut subs1, mut subs2) = (subs1.unwrap(), subs2.unwrap());
+            let mut subs3 = client.subscribe(query.clone()).await.unwrap();
+
+            event_tx.send(event.clone()).unwrap();
+            for subs in [&mut subs1, &mut subs2, &mut subs3] {
+                assert_eq!(subs.next().await.unwrap().unwrap(), event);
+            }
+
+            // Releasing some of the subscriptions leaves the others untouched.
+            client.release(subs1).await.unwrap();
+            client.release(subs2).await.unwrap();
+            event_tx.send(event.clone()).unwrap();
+            assert_eq!(subs3.next().await.unwrap().unwrap(), event);
+
+            client.release(subs3).await.unwrap();
+            client.close().unwrap();
+            driver_hdl.await.unwrap().unwrap();
+
+            let methods = server_hdl.await.unwrap();
+            assert_eq!(methods, ["subscribe", "unsubscribe"]);
+        }
     }
 
     mod v0_38 {
@@ -1549,6 +2432,125 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn unresponsive_server_makes_connection_stale() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // Accept the connection, then never read from it, so that neither
+        // requests nor pings are answered.
+        let server_hdl = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let conn = accept_async(stream).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            drop(conn);
+        });
+
+        let url = format!("ws://127.0.0.1:{port}/websocket").parse().unwrap();
+        let (client, driver) = WebSocketClient::builder(url)
+            .ping_interval(Duration::from_millis(50))
+            .stale_timeout(Duration::from_millis(200))
+            .build()
+            .await
+            .unwrap();
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        let err = client
+            .subscribe(EventType::NewBlock.into())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.detail(),
+            crate::error::ErrorDetail::ConnectionStale(_)
+        ));
+
+        let err = driver_hdl.await.unwrap().unwrap_err();
+        assert!(matches!(
+            err.detail(),
+            crate::error::ErrorDetail::ConnectionStale(_)
+        ));
+        server_hdl.abort();
+    }
+
+    #[tokio::test]
+    async fn zero_ping_interval_is_rejected() {
+        let url = "ws://127.0.0.1:26657/websocket".parse().unwrap();
+        let result = WebSocketClient::builder(url)
+            .ping_interval(Duration::ZERO)
+            .build()
+            .await;
+        assert!(matches!(
+            result.map(|_| ()).unwrap_err().detail(),
+            crate::error::ErrorDetail::InvalidParams(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn websocket_service_lifecycle() {
+        let server = TestServer::new("127.0.0.1:0", TestRpcVersion::V0_37).await;
+        let url = server.node_addr.clone().try_into().unwrap();
+        let (client, driver) = WebSocketClient::builder(url).build().await.unwrap();
+
+        let mut service = WebSocketService::new(client, driver);
+        assert_eq!(service.health(), Health::NotStarted);
+        service.start().unwrap();
+        assert!(service.health().is_running());
+        assert!(service.start().is_err());
+
+        let service = tokio::task::spawn_blocking(move || {
+            service.shutdown(Duration::from_secs(5)).unwrap();
+            service
+        })
+        .await
+        .unwrap();
+        assert_eq!(service.health(), Health::Stopped);
+
+        server.terminate().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn driver_status() {
+        let server = TestServer::new("127.0.0.1:0", TestRpcVersion::V0_37).await;
+        let url = server.node_addr.clone().try_into().unwrap();
+        let (client, driver) = WebSocketClient::builder(url).build().await.unwrap();
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+        assert!(client.is_healthy());
+        assert_eq!(client.active_subscriptions(), 0);
+
+        let subs1 = client.subscribe(EventType::NewBlock.into()).await.unwrap();
+        let subs2 = client.subscribe(EventType::NewBlock.into()).await.unwrap();
+        assert_eq!(client.active_subscriptions(), 2);
+        client.release(subs2).await.unwrap();
+        assert_eq!(client.active_subscriptions(), 1);
+
+        // The subscription requested right before closing the client is
+        // confirmed before the driver terminates.
+        let (subs3, _) = futures::join!(client.subscribe(EventType::Tx.into()), async {
+            client.clone().close().unwrap()
+        });
+        subs3.unwrap();
+        assert!(matches!(client.driver_exit().await, DriverExit::Closed));
+        assert!(!client.is_healthy());
+        assert_eq!(client.active_subscriptions(), 0);
+        assert!(client.subscribe(EventType::Tx.into()).await.is_err());
+
+        driver_hdl.await.unwrap().unwrap();
+        drop(subs1);
+        server.terminate().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn driver_dropped() {
+        let server = TestServer::new("127.0.0.1:0", TestRpcVersion::V0_37).await;
+        let url = server.node_addr.clone().try_into().unwrap();
+        let (client, driver) = WebSocketClient::builder(url).build().await.unwrap();
+
+        drop(driver);
+        assert!(!client.is_healthy());
+        assert!(matches!(client.driver_exit().await, DriverExit::Dropped));
+
+        server.terminate().await.unwrap();
+    }
+
     fn authorization(req: &http::Request<()>) -> Option<&str> {
         req.headers()
             .get(AUTHORIZATION)
@@ -1570,4 +2572,33 @@ mod test {
 
         assert_eq!(authorization(&req), None);
     }
+
+    #[test]
+    fn handshake_authorization() {
+        use crate::client::Authorization;
+
+        let url = Url::from_str("ws://toto:tata@example.com/websocket").unwrap();
+        let req = sealed::handshake(url.clone(), None, Default::default()).unwrap();
+        assert_eq!(authorization(&req), Some("Basic dG90bzp0YXRh"));
+
+        let req = sealed::handshake(
+            url,
+            Some(Authorization::bearer("s3cr3t")),
+            Default::default(),
+        )
+        .unwrap();
+        assert_eq!(authorization(&req), Some("Bearer s3cr3t"));
+    }
+
+    #[test]
+    fn handshake_headers() {
+        use crate::client::{transport::auth::header_map, Authorization};
+
+        let url = Url::from_str("ws://example.com/websocket").unwrap();
+        let headers =
+            header_map(&[("X-Api-Key", "s3cr3t"), ("Authorization", "Token 0th3r")]).unwrap();
+        let req = sealed::handshake(url, Some(Authorization::bearer("s3cr3t")), headers).unwrap();
+        assert_eq!(req.headers()["x-api-key"], "s3cr3t");
+        assert_eq!(authorization(&req), Some("Token 0th3r"));
+    }
 }
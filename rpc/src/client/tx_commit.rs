@@ -0,0 +1,332 @@
+//! Waiting for the commit of a broadcast transaction on the client side.
+
+use core::time::Duration;
+
+use futures::StreamExt;
+use tendermint::{block, Hash};
+use tokio::time::{sleep, timeout_at, Instant};
+use tracing::warn;
+
+use crate::{
+    client::{broadcast_guard::tx_hash, Client, SubscriptionClient},
+    endpoint::{broadcast::tx_sync, tx},
+    event::{Event, EventData, TxInfo, TxResult},
+    prelude::*,
+    query::{EventType, Query},
+    Error,
+};
+
+/// How often [`broadcast_tx_and_wait`] polls the `/tx` endpoint once its
+/// subscription failed.
+pub const DEFAULT_TX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The outcome of [`broadcast_tx_and_wait`].
+#[derive(Clone, Debug)]
+pub enum TxCommitOutcome {
+    /// The transaction was rejected by `CheckTx`, and not added to the
+    /// mempool.
+    Rejected(tx_sync::Response),
+    /// The transaction was committed in a block, though its execution may
+    /// have failed.
+    Committed {
+        /// The hash of the transaction
+        hash: Hash,
+        /// The response of `CheckTx`
+        check_tx: tx_sync::Response,
+        /// The height of the block including the transaction
+        height: block::Height,
+        /// The index of the transaction in the block, if known
+        index: Option<u32>,
+        /// The result of the execution of the transaction
+        tx_result: TxResult,
+    },
+    /// The transaction was added to the mempool, but not committed before
+    /// the deadline. It may still be committed afterwards.
+    Pending {
+        /// The hash of the transaction
+        hash: Hash,
+        /// The response of `CheckTx`
+        check_tx: tx_sync::Response,
+    },
+}
+
+/// Broadcast a transaction and wait for it to be committed, for up to the
+/// given deadline.
+///
+/// Unlike `/broadcast_tx_commit`, whose requests are held by the node until
+/// its own timeout and then fail with an error telling nothing about the
+/// transaction, the deadline is enforced on the client side and its expiry
+/// is reported as [`TxCommitOutcome::Pending`].
+///
+/// The transaction is broadcast with `/broadcast_tx_sync` after subscribing
+/// to its commit, with a `tx.hash` query. Should the subscription fail, the
+/// `/tx` endpoint is polled until the deadline instead, which requires the
+/// node to index the transactions.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// use tendermint_rpc::{client::{broadcast_tx_and_wait, TxCommitOutcome}, WebSocketClient};
+///
+/// let (client, driver) = WebSocketClient::new("ws://127.0.0.1:26657/websocket").await?;
+/// tokio::spawn(async move { driver.run().await });
+///
+/// match broadcast_tx_and_wait(&client, tx, Duration::from_secs(30)).await? {
+///     TxCommitOutcome::Committed { height, .. } => println!("committed at height {height}"),
+///     TxCommitOutcome::Rejected(check_tx) => println!("rejected: {}", check_tx.log),
+///     TxCommitOutcome::Pending { hash, .. } => println!("{hash} is still pending"),
+/// }
+/// ```
+pub async fn broadcast_tx_and_wait<C, T>(
+    client: &C,
+    tx: T,
+    deadline: Duration,
+) -> Result<TxCommitOutcome, Error>
+where
+    C: Client + SubscriptionClient + Sync,
+    T: Into<Vec<u8>> + Send,
+{
+    let deadline = Instant::now() + deadline;
+    let tx = tx.into();
+    let hash = tx_hash(&tx);
+
+    // Subscribe first, not to miss a commit right after the broadcast.
+    let query = Query::from(EventType::Tx).and_eq("tx.hash", hash.to_string());
+    let mut subscription = client.subscribe(query).await?;
+
+    let check_tx = match client.broadcast_tx_sync(tx).await {
+        Ok(check_tx) => check_tx,
+        Err(e) => {
+            let _ = client.release(subscription).await;
+            return Err(e);
+        },
+    };
+    if check_tx.code.is_err() {
+        let _ = client.release(subscription).await;
+        return Ok(TxCommitOutcome::Rejected(check_tx));
+    }
+
+    let committed = loop {
+        match timeout_at(deadline, subscription.next()).await {
+            Ok(Some(Ok(Event {
+                data: EventData::Tx { tx_result },
+                ..
+            }))) => break Some(tx_result),
+            Ok(Some(Ok(_))) => continue,
+            Ok(Some(Err(e))) => {
+                warn!("Subscription to the commit of {} failed: {}", hash, e);
+                break poll_tx(client, hash, deadline).await;
+            },
+            Ok(None) => {
+                warn!("Subscription to the commit of {} terminated", hash);
+                break poll_tx(client, hash, deadline).await;
+            },
+            Err(_) => break None,
+        }
+    };
+    let _ = client.release(subscription).await;
+
+    match committed {
+        Some(info) => Ok(TxCommitOutcome::Committed {
+            hash,
+            check_tx,
+            height: block::Height::try_from(info.height).map_err(Error::tendermint)?,
+            index: info.index.and_then(|index| u32::try_from(index).ok()),
+            tx_result: info.result,
+        }),
+        None => Ok(TxCommitOutcome::Pending { hash, check_tx }),
+    }
+}
+
+/// Poll the `/tx` endpoint for the transaction with the given hash until the
+/// deadline.
+async fn poll_tx<C>(client: &C, hash: Hash, deadline: Instant) -> Option<TxInfo>
+where
+    C: Client + Sync,
+{
+    loop {
+        if let Ok(Ok(response)) = timeout_at(deadline, client.tx(hash, false)).await {
+            return Some(tx_info(response));
+        }
+        if Instant::now() + DEFAULT_TX_POLL_INTERVAL > deadline {
+            return None;
+        }
+        sleep(DEFAULT_TX_POLL_INTERVAL).await;
+    }
+}
+
+fn tx_info(response: tx::Response) -> TxInfo {
+    TxInfo {
+        height: response.height.value() as i64,
+        index: Some(response.index.into()),
+        tx: response.tx,
+        result: TxResult {
+            log: Some(response.tx_result.log),
+            gas_wanted: Some(response.tx_result.gas_wanted.to_string()),
+            gas_used: Some(response.tx_result.gas_used.to_string()),
+            events: response.tx_result.events,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint::abci::Code;
+
+    use super::*;
+    use crate::{Method, MockClient, MockRequestMethodMatcher};
+
+    const TX: &[u8] = b"key=value";
+
+    fn broadcast_tx_sync(code: u32) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "",
+            "result": {
+                "code": code,
+                "codespace": "",
+                "data": "",
+                "hash": tx_hash(TX).to_string(),
+                "log": if code == 0 { "" } else { "invalid tx" },
+            }
+        })
+        .to_string()
+    }
+
+    fn tx_response() -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "",
+            "result": {
+                "hash": tx_hash(TX).to_string(),
+                "height": "12",
+                "index": 2,
+                "tx": "a2V5PXZhbHVl",
+                "tx_result": {
+                    "code": 0,
+                    "codespace": "",
+                    "data": null,
+                    "events": [],
+                    "gas_used": "0",
+                    "gas_wanted": "0",
+                    "info": "",
+                    "log": "",
+                },
+            }
+        })
+        .to_string()
+    }
+
+    fn tx_event(height: i64) -> Event {
+        Event {
+            query: Query::from(EventType::Tx)
+                .and_eq("tx.hash", tx_hash(TX).to_string())
+                .to_string(),
+            data: EventData::Tx {
+                tx_result: TxInfo {
+                    height,
+                    index: Some(0),
+                    tx: TX.to_vec(),
+                    result: TxResult {
+                        log: None,
+                        gas_wanted: None,
+                        gas_used: None,
+                        events: vec![],
+                    },
+                },
+            },
+            events: None,
+        }
+    }
+
+    fn client(matcher: MockRequestMethodMatcher) -> MockClient<MockRequestMethodMatcher> {
+        let (client, driver) = MockClient::new(matcher);
+        tokio::spawn(async move { driver.run().await });
+        client
+    }
+
+    #[tokio::test]
+    async fn committed_tx() {
+        let client = client(
+            MockRequestMethodMatcher::default()
+                .map(Method::BroadcastTxSync, Ok(broadcast_tx_sync(0))),
+        );
+
+        let (outcome, _) = tokio::join!(
+            broadcast_tx_and_wait(&client, TX, Duration::from_secs(5)),
+            async {
+                sleep(Duration::from_millis(50)).await;
+                client.publish(&tx_event(7));
+            }
+        );
+        match outcome.unwrap() {
+            TxCommitOutcome::Committed {
+                hash,
+                height,
+                index,
+                check_tx,
+                ..
+            } => {
+                assert_eq!(hash, tx_hash(TX));
+                assert_eq!(height.value(), 7);
+                assert_eq!(index, Some(0));
+                assert_eq!(check_tx.code, Code::Ok);
+            },
+            outcome => panic!("unexpected outcome: {outcome:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejected_tx() {
+        let client = client(
+            MockRequestMethodMatcher::default()
+                .map(Method::BroadcastTxSync, Ok(broadcast_tx_sync(4))),
+        );
+
+        let outcome = broadcast_tx_and_wait(&client, TX, Duration::from_secs(5))
+            .await
+            .unwrap();
+        match outcome {
+            TxCommitOutcome::Rejected(check_tx) => assert_eq!(check_tx.log, "invalid tx"),
+            outcome => panic!("unexpected outcome: {outcome:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn pending_tx() {
+        let client = client(
+            MockRequestMethodMatcher::default()
+                .map(Method::BroadcastTxSync, Ok(broadcast_tx_sync(0))),
+        );
+
+        let outcome = broadcast_tx_and_wait(&client, TX, Duration::from_millis(100))
+            .await
+            .unwrap();
+        assert!(matches!(outcome, TxCommitOutcome::Pending { hash, .. } if hash == tx_hash(TX)));
+    }
+
+    #[tokio::test]
+    async fn failed_subscription_falls_back_to_polling() {
+        let client = client(
+            MockRequestMethodMatcher::default()
+                .map(Method::BroadcastTxSync, Ok(broadcast_tx_sync(0)))
+                .map(Method::Tx, Ok(tx_response())),
+        );
+
+        let (outcome, _) = tokio::join!(
+            broadcast_tx_and_wait(&client, TX, Duration::from_secs(5)),
+            async {
+                sleep(Duration::from_millis(50)).await;
+                let query = Query::from(EventType::Tx).and_eq("tx.hash", tx_hash(TX).to_string());
+                client.unsubscribe(query).await.unwrap();
+            }
+        );
+        match outcome.unwrap() {
+            TxCommitOutcome::Committed { height, index, .. } => {
+                assert_eq!(height.value(), 12);
+                assert_eq!(index, Some(2));
+            },
+            outcome => panic!("unexpected outcome: {outcome:?}"),
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Detection of subscriptions which silently stopped receiving events.
+
+use core::time::Duration;
+
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use tendermint::block::Height;
+use tokio::time::{timeout, Instant};
+use tracing::warn;
+
+use crate::{
+    client::{Client, Subscription, SubscriptionClient},
+    event::Event,
+    prelude::*,
+    query::Query,
+    Error,
+};
+
+/// The default window without events after which the node is checked for
+/// new blocks.
+pub const DEFAULT_STALL_WINDOW: Duration = Duration::from_secs(30);
+
+/// An item of a subscription watched by a [`StallWatchdog`].
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum WatchdogEvent {
+    /// An event received through the subscription
+    Event(Event),
+
+    /// The subscription stalled and was re-established
+    Stalled(Stall),
+}
+
+/// A report of a subscription which stopped receiving events while the node
+/// kept producing blocks.
+///
+/// Events for the blocks after `last_height`, up to and including `height`,
+/// may have been missed, and can be backfilled by searching for them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stall {
+    /// The query of the subscription
+    pub query: Query,
+    /// How long no event was received for
+    pub silent_for: Duration,
+    /// The latest height of the node before it was noticed that no event
+    /// was being received
+    pub last_height: Height,
+    /// The latest height of the node when the stall was detected
+    pub height: Height,
+}
+
+/// A watchdog detecting subscriptions which silently stopped receiving events
+/// and re-establishing them.
+///
+/// At the end of every window without events, the `/status` of the node is
+/// queried. If its latest block height increased since the end of the
+/// previous window, the subscription is considered stalled: it is replaced
+/// with a new subscription to the same query and a [`Stall`] is reported in
+/// its stream. As the query is unsubscribed from, other subscriptions to it
+/// made with the same client are terminated.
+///
+/// Only queries matching events in every block, such as the one for
+/// [`EventType::NewBlock`], are expected to receive events as long as blocks
+/// are produced, so the watchdog is meant for those.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// use futures::StreamExt;
+/// use tendermint_rpc::{
+///     client::{StallWatchdog, WatchdogEvent},
+///     query::EventType,
+///     SubscriptionClient, WebSocketClient,
+/// };
+///
+/// let subs = client.subscribe(EventType::NewBlock.into()).await?;
+/// let mut events = StallWatchdog::default().watch(&client, subs);
+/// while let Some(item) = events.next().await {
+///     match item? {
+///         WatchdogEvent::Event(ev) => println!("{ev:?}"),
+///         WatchdogEvent::Stalled(stall) => backfill(stall.last_height, stall.height).await?,
+///     }
+/// }
+/// ```
+///
+/// [`EventType::NewBlock`]: crate::query::EventType::NewBlock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StallWatchdog {
+    window: Duration,
+}
+
+impl Default for StallWatchdog {
+    fn default() -> Self {
+        Self::new(DEFAULT_STALL_WINDOW)
+    }
+}
+
+struct State<'a, C> {
+    client: &'a C,
+    subscription: Option<Subscription>,
+    window: Duration,
+    last_event_at: Instant,
+    // The latest height of the node at the end of the previous window
+    // without events, if any.
+    last_height: Option<Height>,
+}
+
+impl StallWatchdog {
+    /// Check the node for new blocks after every window of the given
+    /// duration without events.
+    pub fn new(window: Duration) -> Self {
+        Self { window }
+    }
+
+    /// Watch the given subscription, made with the given client.
+    ///
+    /// The returned stream yields the events received through the
+    /// subscription and the stalls detected, and ends with the subscription.
+    /// It also ends after an error re-establishing a stalled subscription.
+    pub fn watch<'a, C>(
+        &self,
+        client: &'a C,
+        subscription: Subscription,
+    ) -> BoxStream<'a, Result<WatchdogEvent, Error>>
+    where
+        C: Client + SubscriptionClient + Sync,
+    {
+        let state = State {
+            client,
+            subscription: Some(subscription),
+            window: self.window,
+            last_event_at: Instant::now(),
+            last_height: None,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                let subscription = state.subscription.as_mut()?;
+                match timeout(state.window, subscription.next()).await {
+                    Ok(Some(Ok(ev))) => {
+                        state.last_event_at = Instant::now();
+                        state.last_height = None;
+                        return Some((Ok(WatchdogEvent::Event(ev)), state));
+                    },
+                    Ok(Some(Err(e))) => return Some((Err(e), state)),
+                    Ok(None) => return None,
+                    Err(_) => {},
+                }
+
+                let height = match state.client.status().await {
+                    Ok(status) => status.sync_info.latest_block_height,
+                    Err(e) => return Some((Err(e), state)),
+                };
+                let last_height = match state.last_height.replace(height) {
+                    Some(last_height) if height > last_height => last_height,
+                    _ => continue,
+                };
+
+                let query = subscription.query().clone();
+                let stall = Stall {
+                    query: query.clone(),
+                    silent_for: state.last_event_at.elapsed(),
+                    last_height,
+                    height,
+                };
+                warn!(
+                    "subscription to \"{}\" received no event for {:?} while the node went from height {} to {}, resubscribing",
+                    stall.query, stall.silent_for, stall.last_height, stall.height
+                );
+
+                state.subscription = None;
+                state.last_height = None;
+                let resubscribed = match state.client.unsubscribe(query.clone()).await {
+                    Ok(()) => state.client.subscribe(query).await,
+                    Err(e) => Err(e),
+                };
+                return match resubscribed {
+                    Ok(subscription) => {
+                        state.subscription = Some(subscription);
+                        state.last_event_at = Instant::now();
+                        Some((Ok(WatchdogEvent::Stalled(stall)), state))
+                    },
+                    Err(e) => Some((Err(e), state)),
+                };
+            }
+        })
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::{MockClient, MockRequestScript},
+        event::v0_37::DeEvent,
+        query::EventType,
+        Method, Response,
+    };
+
+    const STATUS: &str = include_str!("../../tests/kvstore_fixtures/v0_37/incoming/status.json");
+    const NEW_BLOCK: &str =
+        include_str!("../../tests/kvstore_fixtures/v0_37/incoming/subscribe_newblock_0.json");
+
+    fn status_at(height: u64) -> String {
+        STATUS.replace(
+            r#""latest_block_height": "53""#,
+            &format!(r#""latest_block_height": "{height}""#),
+        )
+    }
+
+    fn new_block() -> Event {
+        DeEvent::from_string(NEW_BLOCK).unwrap().into()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn resubscribes_when_blocks_are_produced_without_events() {
+        let script = MockRequestScript::default()
+            .once(Method::Status, Ok(status_at(10)))
+            .on(Method::Status, Ok(status_at(12)));
+        let (client, driver) = MockClient::new(script);
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        let query: Query = EventType::NewBlock.into();
+        let subs = client.subscribe(query.clone()).await.unwrap();
+        let mut events = StallWatchdog::new(Duration::from_secs(1)).watch(&client, subs);
+
+        client.publish(&new_block());
+        assert!(matches!(
+            events.next().await.unwrap().unwrap(),
+            WatchdogEvent::Event(_)
+        ));
+
+        match events.next().await.unwrap().unwrap() {
+            WatchdogEvent::Stalled(stall) => {
+                assert_eq!(stall.query, query);
+                assert_eq!(stall.last_height.value(), 10);
+                assert_eq!(stall.height.value(), 12);
+                assert!(stall.silent_for >= Duration::from_secs(2));
+            },
+            other => panic!("unexpected item: {other:?}"),
+        }
+        assert_eq!(client.matcher().call_count(Method::Status), 2);
+
+        // Events are received through the new subscription.
+        client.publish(&new_block());
+        assert!(matches!(
+            events.next().await.unwrap().unwrap(),
+            WatchdogEvent::Event(_)
+        ));
+
+        drop(events);
+        client.close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn silence_without_new_blocks_is_not_a_stall() {
+        let script = MockRequestScript::default().on(Method::Status, Ok(status_at(10)));
+        let (client, driver) = MockClient::new(script);
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        let subs = client.subscribe(EventType::NewBlock.into()).await.unwrap();
+        let mut events = StallWatchdog::new(Duration::from_secs(1)).watch(&client, subs);
+
+        let next = timeout(Duration::from_secs(10), events.next()).await;
+        assert!(next.is_err());
+        assert!(client.matcher().call_count(Method::Status) >= 9);
+
+        drop(events);
+        client.close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+}
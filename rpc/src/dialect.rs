@@ -1,6 +1,7 @@
 //! Helper types to generalize differences in serialization between
 //! Tendermint RPC protocol versions.
 
+pub mod encoding;
 pub mod v0_34;
 pub mod v0_37;
 
@@ -12,22 +13,32 @@ mod end_block;
 pub use begin_block::BeginBlock;
 pub use check_tx::CheckTx;
 pub use deliver_tx::DeliverTx;
+pub use encoding::Encoding;
 pub use end_block::EndBlock;
 
+use core::fmt;
+
 use serde::{de::DeserializeOwned, Serialize};
 
 use tendermint::{abci, evidence};
 
-pub trait Dialect: sealed::Sealed + Default + Clone + Send + Sync {
-    type Event: Into<abci::Event> + Serialize + DeserializeOwned;
+/// The serialization conventions of a version of the RPC protocol.
+///
+/// Besides the built-in dialects, forks of Tendermint whose RPC deviates from
+/// these conventions can implement their own dialect, supplying the types of
+/// their events and evidence, and the [`Encoding`] of the fields of the
+/// responses of the ABCI methods (see [`CheckTx`] and [`DeliverTx`]). Requests
+/// are performed in a given dialect with
+/// [`HttpClient::perform_with_dialect`](crate::HttpClient::perform_with_dialect).
+///
+/// The endpoints whose responses changed between protocol versions, such as
+/// `/tx`, only implement [`Request`](crate::Request) for the built-in
+/// dialects. Their `v0_34` modules provide response types generic over the
+/// dialect, for forks to implement them for their dialect.
+pub trait Dialect: Default + Clone + Send + Sync {
+    type Event: Into<abci::Event> + Serialize + DeserializeOwned + fmt::Debug;
     type Evidence: From<evidence::Evidence> + Serialize + DeserializeOwned + Send;
+    type Encoding: Encoding;
 }
 
 pub type LatestDialect = v0_37::Dialect;
-
-mod sealed {
-    pub trait Sealed {}
-
-    impl Sealed for super::v0_34::Dialect {}
-    impl Sealed for super::v0_37::Dialect {}
-}
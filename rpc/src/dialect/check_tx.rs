@@ -1,14 +1,20 @@
+use core::marker::PhantomData;
+
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
 use tendermint::abci::{self, Code};
 
+use crate::dialect::encoding::{Base64, Encoding};
 use crate::prelude::*;
 use crate::serializers;
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
-#[serde(default)]
-pub struct CheckTx<Ev> {
+#[serde(
+    default,
+    bound(serialize = "Ev: Serialize", deserialize = "Ev: Deserialize<'de>")
+)]
+pub struct CheckTx<Ev, E: Encoding = Base64> {
     /// The response code.
     ///
     /// Transactions where `code != 0` will be rejected; these transactions will
@@ -27,10 +33,16 @@ pub struct CheckTx<Ev> {
     /// **May be non-deterministic**.
     pub info: String,
     /// Amount of gas requested for the transaction.
-    #[serde(with = "serializers::from_str")]
+    #[serde(
+        serialize_with = "E::serialize_int",
+        deserialize_with = "E::deserialize_int"
+    )]
     pub gas_wanted: i64,
     /// Amount of gas consumed by the transaction.
-    #[serde(with = "serializers::from_str")]
+    #[serde(
+        serialize_with = "E::serialize_int",
+        deserialize_with = "E::deserialize_int"
+    )]
     pub gas_used: i64,
     /// Events that occurred while checking the transaction.
     pub events: Vec<Ev>,
@@ -39,14 +51,19 @@ pub struct CheckTx<Ev> {
     /// The transaction's sender (e.g. the signer).
     pub sender: String,
     /// The transaction's priority (for mempool ordering).
-    #[serde(with = "serializers::from_str")]
+    #[serde(
+        serialize_with = "E::serialize_int",
+        deserialize_with = "E::deserialize_int"
+    )]
     pub priority: i64,
     /// mempool_error is set by Tendermint.
     /// ABCI applictions should not set mempool_error.
     pub mempool_error: String,
+    #[serde(skip)]
+    encoding: PhantomData<E>,
 }
 
-impl<Ev> Default for CheckTx<Ev> {
+impl<Ev, E: Encoding> Default for CheckTx<Ev, E> {
     fn default() -> Self {
         Self {
             code: Default::default(),
@@ -60,15 +77,16 @@ impl<Ev> Default for CheckTx<Ev> {
             sender: Default::default(),
             priority: Default::default(),
             mempool_error: Default::default(),
+            encoding: PhantomData,
         }
     }
 }
 
-impl<Ev> From<CheckTx<Ev>> for abci::response::CheckTx
+impl<Ev, E: Encoding> From<CheckTx<Ev, E>> for abci::response::CheckTx
 where
     Ev: Into<abci::Event>,
 {
-    fn from(msg: CheckTx<Ev>) -> Self {
+    fn from(msg: CheckTx<Ev, E>) -> Self {
         Self {
             code: msg.code,
             data: msg.data,
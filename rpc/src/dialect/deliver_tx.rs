@@ -1,14 +1,20 @@
+use core::marker::PhantomData;
+
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
 use tendermint::abci::{self, Code};
 
+use crate::dialect::encoding::{Base64, Encoding};
 use crate::prelude::*;
 use crate::serializers;
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
-#[serde(default)]
-pub struct DeliverTx<Ev> {
+#[serde(
+    default,
+    bound(serialize = "Ev: Serialize", deserialize = "Ev: Deserialize<'de>")
+)]
+pub struct DeliverTx<Ev, E: Encoding = Base64> {
     /// The response code.
     ///
     /// This code should be `0` only if the transaction is fully valid. However,
@@ -27,18 +33,26 @@ pub struct DeliverTx<Ev> {
     /// **May be non-deterministic**.
     pub info: String,
     /// Amount of gas requested for the transaction.
-    #[serde(with = "serializers::from_str")]
+    #[serde(
+        serialize_with = "E::serialize_int",
+        deserialize_with = "E::deserialize_int"
+    )]
     pub gas_wanted: i64,
     /// Amount of gas consumed by the transaction.
-    #[serde(with = "serializers::from_str")]
+    #[serde(
+        serialize_with = "E::serialize_int",
+        deserialize_with = "E::deserialize_int"
+    )]
     pub gas_used: i64,
     /// Events that occurred while executing the transaction.
     pub events: Vec<Ev>,
     /// The namespace for the `code`.
     pub codespace: String,
+    #[serde(skip)]
+    encoding: PhantomData<E>,
 }
 
-impl<Ev> Default for DeliverTx<Ev> {
+impl<Ev, E: Encoding> Default for DeliverTx<Ev, E> {
     fn default() -> Self {
         Self {
             code: Default::default(),
@@ -49,15 +63,16 @@ impl<Ev> Default for DeliverTx<Ev> {
             gas_used: Default::default(),
             events: Default::default(),
             codespace: Default::default(),
+            encoding: PhantomData,
         }
     }
 }
 
-impl<Ev> From<DeliverTx<Ev>> for abci::response::DeliverTx
+impl<Ev, E: Encoding> From<DeliverTx<Ev, E>> for abci::response::DeliverTx
 where
     Ev: Into<abci::Event>,
 {
-    fn from(msg: DeliverTx<Ev>) -> Self {
+    fn from(msg: DeliverTx<Ev, E>) -> Self {
         Self {
             code: msg.code,
             data: msg.data,
@@ -71,11 +86,11 @@ where
     }
 }
 
-impl<Ev> From<DeliverTx<Ev>> for abci::types::ExecTxResult
+impl<Ev, E: Encoding> From<DeliverTx<Ev, E>> for abci::types::ExecTxResult
 where
     Ev: Into<abci::Event>,
 {
-    fn from(msg: DeliverTx<Ev>) -> Self {
+    fn from(msg: DeliverTx<Ev, E>) -> Self {
         Self {
             code: msg.code,
             data: msg.data,
@@ -0,0 +1,157 @@
+//! Encodings of the fields whose JSON representation varies between dialects.
+
+use core::fmt;
+
+use serde::{de::Error as _, Deserializer, Serializer};
+
+use crate::prelude::*;
+use crate::serializers;
+
+/// The JSON representation of the byte strings and integers of a dialect.
+///
+/// The types of the built-in dialects serialize their fields with the
+/// [`Base64`] encoding. Forks of Tendermint with other conventions can supply
+/// their own encoding through their [`Dialect`](super::Dialect), e.g. to
+/// serialize the integers as JSON numbers rather than strings.
+pub trait Encoding: fmt::Debug + Send + Sync + 'static {
+    /// Serialize the given bytes.
+    fn serialize_bytes<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+
+    /// Deserialize bytes, a missing value being deserialized as no bytes.
+    fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>;
+
+    /// Serialize the given integer, as a string by default.
+    fn serialize_int<S>(value: &i64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializers::from_str::serialize(value, serializer)
+    }
+
+    /// Deserialize an integer, from a string by default.
+    fn deserialize_int<'de, D>(deserializer: D) -> Result<i64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        serializers::from_str::deserialize(deserializer)
+    }
+
+    /// Serialize the given text as the bytes of its UTF-8 encoding.
+    fn serialize_text<S>(text: &str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Self::serialize_bytes(text.as_bytes(), serializer)
+    }
+
+    /// Deserialize text from the bytes of its UTF-8 encoding.
+    fn deserialize_text<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::from_utf8(Self::deserialize_bytes(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// Byte strings encoded in base64 and integers in decimal strings, as in the
+/// Tendermint RPC.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Base64;
+
+impl Encoding for Base64 {
+    fn serialize_bytes<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializers::bytes::base64string::serialize(&bytes, serializer)
+    }
+
+    fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        serializers::bytes::base64string::deserialize(deserializer)
+    }
+}
+
+/// Byte strings encoded in upper case hexadecimal, and integers in decimal
+/// strings.
+///
+/// Deserializes lower case hexadecimal as well.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Hex;
+
+impl Encoding for Hex {
+    fn serialize_bytes<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializers::bytes::hexstring::serialize(&bytes, serializer)
+    }
+
+    fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        serializers::bytes::hexstring::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(bound = "")]
+    struct Fields<E: Encoding> {
+        #[serde(
+            serialize_with = "E::serialize_text",
+            deserialize_with = "E::deserialize_text"
+        )]
+        text: String,
+        #[serde(
+            serialize_with = "E::serialize_int",
+            deserialize_with = "E::deserialize_int"
+        )]
+        int: i64,
+        #[serde(skip)]
+        encoding: core::marker::PhantomData<E>,
+    }
+
+    fn fields<E: Encoding>() -> Fields<E> {
+        Fields {
+            text: "key".to_owned(),
+            int: -42,
+            encoding: Default::default(),
+        }
+    }
+
+    #[test]
+    fn base64() {
+        let json = serde_json::to_value(fields::<Base64>()).unwrap();
+        assert_eq!(json, serde_json::json!({ "text": "a2V5", "int": "-42" }));
+        assert_eq!(
+            serde_json::from_value::<Fields<Base64>>(json).unwrap(),
+            fields()
+        );
+    }
+
+    #[test]
+    fn hex() {
+        let json = serde_json::to_value(fields::<Hex>()).unwrap();
+        assert_eq!(json, serde_json::json!({ "text": "6B6579", "int": "-42" }));
+        assert_eq!(
+            serde_json::from_value::<Fields<Hex>>(
+                serde_json::json!({ "text": "6b6579", "int": "-42" })
+            )
+            .unwrap(),
+            fields()
+        );
+    }
+}
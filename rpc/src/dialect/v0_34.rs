@@ -1,28 +1,33 @@
+use core::marker::PhantomData;
+
 use tendermint::{abci, evidence};
 use tendermint_proto::v0_34::types::Evidence as RawEvidence;
 
+use crate::dialect::encoding::{Base64, Encoding};
 use crate::prelude::*;
-use crate::serializers::bytes::base64string;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug)]
 pub struct Dialect;
 
 impl crate::dialect::Dialect for Dialect {
     type Event = Event;
     type Evidence = Evidence;
+    type Encoding = Base64;
 }
 
+/// An event whose attributes are encoded as bytes, in the given encoding.
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
-pub struct Event {
+#[serde(bound = "")]
+pub struct Event<E: Encoding = Base64> {
     #[serde(rename = "type")]
     pub kind: String,
-    pub attributes: Vec<EventAttribute>,
+    pub attributes: Vec<EventAttribute<E>>,
 }
 
-impl From<Event> for abci::Event {
-    fn from(msg: Event) -> Self {
+impl<E: Encoding> From<Event<E>> for abci::Event {
+    fn from(msg: Event<E>) -> Self {
         Self {
             kind: msg.kind,
             attributes: msg.attributes.into_iter().map(Into::into).collect(),
@@ -30,7 +35,7 @@ impl From<Event> for abci::Event {
     }
 }
 
-impl From<abci::Event> for Event {
+impl<E: Encoding> From<abci::Event> for Event<E> {
     fn from(msg: abci::Event) -> Self {
         Self {
             kind: msg.kind,
@@ -40,37 +45,42 @@ impl From<abci::Event> for Event {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
-pub struct EventAttribute {
+#[serde(bound = "")]
+pub struct EventAttribute<E: Encoding = Base64> {
     /// The event key.
     #[serde(
-        serialize_with = "base64string::serialize",
-        deserialize_with = "base64string::deserialize_to_string"
+        serialize_with = "E::serialize_text",
+        deserialize_with = "E::deserialize_text"
     )]
     pub key: String,
     /// The event value.
     #[serde(
-        serialize_with = "base64string::serialize",
-        deserialize_with = "base64string::deserialize_to_string"
+        serialize_with = "E::serialize_text",
+        deserialize_with = "E::deserialize_text"
     )]
     pub value: String,
     /// Whether Tendermint's indexer should index this event.
     ///
     /// **This field is nondeterministic**.
     pub index: bool,
+    #[serde(skip)]
+    encoding: PhantomData<E>,
 }
 
-impl From<EventAttribute> for abci::EventAttribute {
-    fn from(msg: EventAttribute) -> Self {
+impl<E: Encoding> EventAttribute<E> {
+    /// Create an attribute with the given key and value.
+    pub fn new(key: impl Into<String>, value: impl Into<String>, index: bool) -> Self {
         Self {
-            key: msg.key,
-            value: msg.value,
-            index: msg.index,
+            key: key.into(),
+            value: value.into(),
+            index,
+            encoding: PhantomData,
         }
     }
 }
 
-impl From<abci::EventAttribute> for EventAttribute {
-    fn from(msg: abci::EventAttribute) -> Self {
+impl<E: Encoding> From<EventAttribute<E>> for abci::EventAttribute {
+    fn from(msg: EventAttribute<E>) -> Self {
         Self {
             key: msg.key,
             value: msg.value,
@@ -79,6 +89,12 @@ impl From<abci::EventAttribute> for EventAttribute {
     }
 }
 
+impl<E: Encoding> From<abci::EventAttribute> for EventAttribute<E> {
+    fn from(msg: abci::EventAttribute) -> Self {
+        Self::new(msg.key, msg.value, msg.index)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(into = "RawEvidence", try_from = "RawEvidence")]
 pub struct Evidence(evidence::Evidence);
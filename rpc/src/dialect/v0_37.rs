@@ -1,6 +1,7 @@
 use tendermint::evidence;
 use tendermint_proto::v0_37 as raw;
 
+use crate::dialect::encoding::Base64;
 use crate::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -8,12 +9,13 @@ use serde::{Deserialize, Serialize};
 /// serialization for the ABCI domain type.
 pub use tendermint::abci::Event;
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug)]
 pub struct Dialect;
 
 impl crate::dialect::Dialect for Dialect {
     type Event = Event;
     type Evidence = Evidence;
+    type Encoding = Base64;
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1,10 +1,15 @@
 //! `/abci_query` endpoint JSON-RPC wrapper
 
 use serde::{Deserialize, Serialize};
-use tendermint::{abci::Code, block, merkle::proof::ProofOps, serializers};
+use tendermint::{
+    abci::Code,
+    block,
+    merkle::proof::{ProofOps, ProofRuntime},
+    serializers, AppHash,
+};
 
 use crate::prelude::*;
-use crate::{dialect::Dialect, request::RequestMessage};
+use crate::{dialect::Dialect, request::RequestMessage, Error};
 
 /// Query the ABCI application for information
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -45,6 +50,10 @@ impl RequestMessage for Request {
     fn method(&self) -> crate::Method {
         crate::Method::AbciQuery
     }
+
+    fn height(&self) -> Option<block::Height> {
+        self.height
+    }
 }
 
 impl<S: Dialect> crate::Request<S> for Request {
@@ -101,3 +110,29 @@ pub struct AbciQuery {
     #[serde(default = "String::new")]
     pub codespace: String,
 }
+
+impl AbciQuery {
+    /// Verify the proof of the queried value with the operations decoded by
+    /// the given runtime, such as [`ProofRuntime::with_ics23`] for the stores
+    /// of Cosmos SDK applications.
+    ///
+    /// The keys are ordered from the outermost tree to the innermost one, for
+    /// instance the name of a store followed by the queried key. The state
+    /// queried at a given height is committed to by the app hash in the header
+    /// at the next height.
+    pub fn verify(
+        &self,
+        runtime: &ProofRuntime,
+        app_hash: &AppHash,
+        keys: &[&[u8]],
+    ) -> Result<(), Error> {
+        let proof = self.proof.as_ref().ok_or_else(|| {
+            Error::tendermint(tendermint::Error::invalid_proof(
+                "no proof in the response".to_string(),
+            ))
+        })?;
+        runtime
+            .verify_value(proof, app_hash.as_bytes(), keys, &self.value)
+            .map_err(Error::tendermint)
+    }
+}
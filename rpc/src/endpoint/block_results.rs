@@ -29,6 +29,10 @@ impl RequestMessage for Request {
     fn method(&self) -> crate::Method {
         crate::Method::BlockResults
     }
+
+    fn height(&self) -> Option<block::Height> {
+        self.height
+    }
 }
 
 impl crate::Request<dialect::v0_34::Dialect> for Request {
@@ -95,26 +99,28 @@ impl crate::Response for Response {}
 /// Serialization for /block_results endpoint format in Tendermint 0.34
 pub mod v0_34 {
     use super::Response;
-    use crate::dialect::v0_34::Event;
+    use crate::dialect::{self, Dialect};
     use crate::prelude::*;
-    use crate::{dialect, serializers};
+    use crate::serializers;
     use serde::{Deserialize, Serialize};
     use tendermint::{block, consensus, validator};
 
-    /// RPC dialect helper for serialization of the response.
+    /// RPC dialect helper for serialization of the response, in the
+    /// Tendermint 0.34 dialect by default.
     #[derive(Debug, Serialize, Deserialize)]
-    pub struct DialectResponse {
+    #[serde(bound = "")]
+    pub struct DialectResponse<S: Dialect = dialect::v0_34::Dialect> {
         /// Block height
         pub height: block::Height,
 
         /// Txs results (might be explicit null)
-        pub txs_results: Option<Vec<dialect::DeliverTx<Event>>>,
+        pub txs_results: Option<Vec<dialect::DeliverTx<S::Event, S::Encoding>>>,
 
         /// Begin block events (might be explicit null)
-        pub begin_block_events: Option<Vec<Event>>,
+        pub begin_block_events: Option<Vec<S::Event>>,
 
         /// End block events (might be explicit null)
-        pub end_block_events: Option<Vec<Event>>,
+        pub end_block_events: Option<Vec<S::Event>>,
 
         /// Validator updates (might be explicit null)
         #[serde(deserialize_with = "serializers::nullable::deserialize")]
@@ -124,10 +130,10 @@ pub mod v0_34 {
         pub consensus_param_updates: Option<consensus::Params>,
     }
 
-    impl crate::Response for DialectResponse {}
+    impl<S: Dialect> crate::Response for DialectResponse<S> {}
 
-    impl From<DialectResponse> for Response {
-        fn from(msg: DialectResponse) -> Self {
+    impl<S: Dialect> From<DialectResponse<S>> for Response {
+        fn from(msg: DialectResponse<S>) -> Self {
             Response {
                 height: msg.height,
                 txs_results: msg
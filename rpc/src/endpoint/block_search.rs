@@ -6,13 +6,20 @@ pub use super::{block, block_results};
 use crate::{dialect::Dialect, prelude::*, request::RequestMessage, serializers, Method, Order};
 
 /// Request for searching for blocks by their BeginBlock and EndBlock events.
+///
+/// The query uses the same syntax as event subscriptions, and can also refer
+/// to the `block.height` key, e.g. `block.height > 100 AND transfer.amount >= 5`.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Request {
+    /// The query against which blocks are matched.
     pub query: String,
+    /// The page of results to return, starting at 1.
     #[serde(with = "serializers::from_str")]
     pub page: u32,
+    /// The number of blocks per page of results.
     #[serde(with = "serializers::from_str")]
     pub per_page: u8,
+    /// The order in which to return blocks, by height.
     pub order_by: Order,
 }
 
@@ -42,9 +49,12 @@ impl<S: Dialect> crate::SimpleRequest<S> for Request {
     type Output = Response;
 }
 
+/// Response to a block search request.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Response {
+    /// The blocks on the requested page of results.
     pub blocks: Vec<block::Response>,
+    /// The total number of blocks matching the query, across all pages.
     #[serde(with = "serializers::from_str")]
     pub total_count: u32,
 }
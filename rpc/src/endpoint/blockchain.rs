@@ -1,4 +1,4 @@
-//! `/block` endpoint JSON-RPC wrapper
+//! `/blockchain` endpoint JSON-RPC wrapper
 
 use core::ops::Range;
 
@@ -8,6 +8,9 @@ use tendermint::block;
 use crate::prelude::*;
 use crate::{dialect::Dialect, request::RequestMessage};
 
+/// The maximum number of block metas returned by the node for a request.
+pub const MAX_BLOCK_METAS: u32 = 20;
+
 /// Get information about a specific block
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Request {
@@ -74,19 +74,20 @@ impl crate::Response for Response {}
 /// Serialization for /broadcast_tx_commit endpoint format in Tendermint 0.34
 pub mod v0_34 {
     use super::Response;
-    use crate::dialect;
-    use crate::dialect::v0_34::Event;
+    use crate::dialect::{self, Dialect};
     use serde::{Deserialize, Serialize};
     use tendermint::{block, Hash};
 
-    /// RPC dialect helper for serialization of the response.
+    /// RPC dialect helper for serialization of the response, in the
+    /// Tendermint 0.34 dialect by default.
     #[derive(Debug, Deserialize, Serialize)]
-    pub struct DialectResponse {
+    #[serde(bound = "")]
+    pub struct DialectResponse<S: Dialect = dialect::v0_34::Dialect> {
         /// `CheckTx` result
-        pub check_tx: dialect::CheckTx<Event>,
+        pub check_tx: dialect::CheckTx<S::Event, S::Encoding>,
 
         /// `DeliverTx` result
-        pub deliver_tx: dialect::DeliverTx<Event>,
+        pub deliver_tx: dialect::DeliverTx<S::Event, S::Encoding>,
 
         /// Transaction
         pub hash: Hash,
@@ -95,10 +96,10 @@ pub mod v0_34 {
         pub height: block::Height,
     }
 
-    impl crate::Response for DialectResponse {}
+    impl<S: Dialect> crate::Response for DialectResponse<S> {}
 
-    impl From<DialectResponse> for Response {
-        fn from(msg: DialectResponse) -> Self {
+    impl<S: Dialect> From<DialectResponse<S>> for Response {
+        fn from(msg: DialectResponse<S>) -> Self {
             Self {
                 check_tx: msg.check_tx.into(),
                 tx_result: msg.deliver_tx.into(),
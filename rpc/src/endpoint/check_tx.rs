@@ -0,0 +1,184 @@
+//! `/check_tx` endpoint JSON-RPC wrapper
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tendermint::abci;
+
+use crate::dialect::{
+    self,
+    encoding::{Base64, Encoding},
+    Dialect,
+};
+use crate::{prelude::*, request::RequestMessage, serializers};
+
+/// Check a transaction against the mempool rules of the application, without
+/// adding it to the mempool or broadcasting it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request {
+    /// Transaction to check
+    #[serde(with = "serializers::bytes::base64string")]
+    pub tx: Vec<u8>,
+}
+
+impl Request {
+    /// Create a new request checking the given transaction
+    pub fn new(tx: impl Into<Vec<u8>>) -> Request {
+        Request { tx: tx.into() }
+    }
+}
+
+impl RequestMessage for Request {
+    fn method(&self) -> crate::Method {
+        crate::Method::CheckTx
+    }
+}
+
+impl<S: Dialect> crate::Request<S> for Request {
+    type Response = DialectResponse<S::Event, S::Encoding>;
+}
+
+impl<S: Dialect> crate::SimpleRequest<S> for Request {
+    type Output = Response;
+}
+
+/// Response from `/check_tx`, which is the application's response to
+/// `CheckTx`.
+pub type Response = abci::response::CheckTx;
+
+/// RPC dialect helper for serialization of the response.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(
+    transparent,
+    bound(serialize = "Ev: Serialize", deserialize = "Ev: Deserialize<'de>")
+)]
+pub struct DialectResponse<Ev, E: Encoding = Base64>(pub dialect::CheckTx<Ev, E>);
+
+impl<Ev, E: Encoding> crate::Response for DialectResponse<Ev, E> where
+    Ev: Serialize + DeserializeOwned
+{
+}
+
+impl<Ev, E: Encoding> From<DialectResponse<Ev, E>> for Response
+where
+    Ev: Into<abci::Event>,
+{
+    fn from(msg: DialectResponse<Ev, E>) -> Self {
+        msg.0.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dialect::v0_34, Response as _};
+
+    fn response(attribute: (&str, &str)) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "",
+            "result": {
+                "code": 5,
+                "data": null,
+                "log": "insufficient funds",
+                "info": "",
+                "gas_wanted": "200000",
+                "gas_used": "41234",
+                "events": [{
+                    "type": "tx",
+                    "attributes": [{ "key": attribute.0, "value": attribute.1, "index": true }]
+                }],
+                "codespace": "sdk"
+            }
+        })
+        .to_string()
+    }
+
+    fn check(check_tx: Response) {
+        assert_eq!(check_tx.code.value(), 5);
+        assert_eq!(check_tx.log, "insufficient funds");
+        assert_eq!(check_tx.gas_wanted, 200000);
+        assert_eq!(check_tx.gas_used, 41234);
+        assert_eq!(check_tx.codespace, "sdk");
+        assert_eq!(check_tx.events.len(), 1);
+        assert_eq!(check_tx.events[0].kind, "tx");
+        assert_eq!(check_tx.events[0].attributes[0].key, "fee");
+        assert_eq!(check_tx.events[0].attributes[0].value, "10stake");
+    }
+
+    #[test]
+    fn deserialize_v0_34() {
+        let response = <Request as crate::Request<v0_34::Dialect>>::Response::from_string(
+            response(("ZmVl", "MTBzdGFrZQ==")),
+        )
+        .unwrap();
+        check(response.into());
+    }
+
+    #[test]
+    fn deserialize_v0_37() {
+        let response = <Request as crate::Request<dialect::v0_37::Dialect>>::Response::from_string(
+            response(("fee", "10stake")),
+        )
+        .unwrap();
+        check(response.into());
+    }
+
+    /// The dialect of a fork encoding the event attributes in hexadecimal,
+    /// and the integers as JSON numbers.
+    #[derive(Clone, Debug, Default)]
+    struct ForkDialect;
+
+    #[derive(Clone, Copy, Debug)]
+    struct ForkEncoding;
+
+    impl Encoding for ForkEncoding {
+        fn serialize_bytes<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            dialect::encoding::Hex::serialize_bytes(bytes, serializer)
+        }
+
+        fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            dialect::encoding::Hex::deserialize_bytes(deserializer)
+        }
+
+        fn serialize_int<S>(value: &i64, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.serialize_i64(*value)
+        }
+
+        fn deserialize_int<'de, D>(deserializer: D) -> Result<i64, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            i64::deserialize(deserializer)
+        }
+    }
+
+    impl Dialect for ForkDialect {
+        type Event = v0_34::Event<ForkEncoding>;
+        type Evidence = v0_34::Evidence;
+        type Encoding = ForkEncoding;
+    }
+
+    #[test]
+    fn deserialize_custom_dialect() {
+        let mut response: serde_json::Value =
+            serde_json::from_str(&response(("666565", "31307374616B65"))).unwrap();
+        response["result"]["gas_wanted"] = 200000.into();
+        response["result"]["gas_used"] = 41234.into();
+
+        let response =
+            <Request as crate::Request<ForkDialect>>::Response::from_string(response.to_string())
+                .unwrap();
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["gas_used"], 41234);
+        assert_eq!(json["events"][0]["attributes"][0]["key"], "666565");
+        check(response.into());
+    }
+}
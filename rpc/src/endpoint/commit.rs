@@ -24,6 +24,10 @@ impl RequestMessage for Request {
     fn method(&self) -> crate::Method {
         crate::Method::Commit
     }
+
+    fn height(&self) -> Option<block::Height> {
+        self.height
+    }
 }
 
 impl<S: Dialect> crate::Request<S> for Request {
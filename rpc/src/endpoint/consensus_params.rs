@@ -26,6 +26,10 @@ impl RequestMessage for Request {
     fn method(&self) -> crate::Method {
         crate::Method::ConsensusParams
     }
+
+    fn height(&self) -> Option<Height> {
+        self.height
+    }
 }
 
 impl<S: Dialect> crate::Request<S> for Request {
@@ -135,6 +135,183 @@ pub struct RoundVotes {
     pub precommits_bit_array: String,
 }
 
+impl RoundVotes {
+    /// Parse the summary of the prevotes received in this round.
+    pub fn prevotes_bits(&self) -> Result<VoteSetBits, Error> {
+        self.prevotes_bit_array.parse()
+    }
+
+    /// Parse the summary of the precommits received in this round.
+    pub fn precommits_bits(&self) -> Result<VoteSetBits, Error> {
+        self.precommits_bit_array.parse()
+    }
+}
+
+// From <https://github.com/tendermint/tendermint/blob/e820e68acd69737cfb63bc9ccca5f5450a42b5cf/libs/bits/bit_array.go#L289>
+const NIL_BIT_ARRAY_STR: &str = "nil-BitArray";
+
+/// An array of bits, e.g. marking the validators a vote was received from.
+///
+/// Serialized as a string of `x` (set) and `_` (unset) characters, which is
+/// how Tendermint nodes encode bit arrays in JSON.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BitArray(Vec<bool>);
+
+impl BitArray {
+    pub fn new(bits: Vec<bool>) -> Self {
+        Self(bits)
+    }
+
+    /// The number of bits in the array.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The bit at the given index, if it is within the array.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        self.0.get(index).copied()
+    }
+
+    /// The number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.0.iter().filter(|bit| **bit).count()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl FromStr for BitArray {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(|c| match c {
+                'x' => Ok(true),
+                '_' => Ok(false),
+                _ => Err(Error::client_internal(format!(
+                    "invalid character in bit array: {c:?}"
+                ))),
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for BitArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bit in self.iter() {
+            f.write_str(if bit { "x" } else { "_" })?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for BitArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for BitArray {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The summary of the votes of a particular type received in a round, e.g.
+/// `BA{4:xx_x} 30/40 = 0.75`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VoteSetBits {
+    /// The validators the votes were received from, or `None` if the node
+    /// does not track the votes.
+    pub bits: Option<BitArray>,
+    /// The voting power of the received votes.
+    pub voted_power: i64,
+    /// The total voting power of the validator set.
+    pub total_power: i64,
+}
+
+impl VoteSetBits {
+    /// The fraction of the total voting power the received votes make up.
+    pub fn fraction(&self) -> f64 {
+        if self.total_power == 0 {
+            0.0
+        } else {
+            self.voted_power as f64 / self.total_power as f64
+        }
+    }
+}
+
+impl FromStr for VoteSetBits {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            Error::client_internal(format!(
+                "invalid format for consensus state vote set bit array: {s}"
+            ))
+        };
+
+        // <bits> <voted>/<total> = <fraction>
+        let parts: Vec<&str> = s.split(' ').collect();
+        if parts.len() != 4 || parts[2] != "=" {
+            return Err(invalid());
+        }
+        let bits = if parts[0] == NIL_BIT_ARRAY_STR {
+            None
+        } else {
+            let (len, bits) = parts[0]
+                .strip_prefix("BA{")
+                .and_then(|s| s.strip_suffix('}'))
+                .and_then(|s| s.split_once(':'))
+                .ok_or_else(invalid)?;
+            let bits = BitArray::from_str(bits)?;
+            if usize::from_str(len).ok() != Some(bits.len()) {
+                return Err(invalid());
+            }
+            Some(bits)
+        };
+        let (voted, total) = parts[1].split_once('/').ok_or_else(invalid)?;
+        let voted_power = i64::from_str(voted).map_err(|_| invalid())?;
+        let total_power = i64::from_str(total).map_err(|_| invalid())?;
+
+        Ok(Self {
+            bits,
+            voted_power,
+            total_power,
+        })
+    }
+}
+
+impl fmt::Display for VoteSetBits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.bits {
+            Some(bits) => write!(f, "BA{{{}:{}}}", bits.len(), bits)?,
+            None => f.write_str(NIL_BIT_ARRAY_STR)?,
+        }
+        write!(
+            f,
+            " {}/{} = {:.2}",
+            self.voted_power,
+            self.total_power,
+            self.fraction()
+        )
+    }
+}
+
 /// Details of a single vote from a particular consensus round.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RoundVote {
@@ -397,4 +574,31 @@ mod test {
             assert_eq!(expected.clone(), actual);
         }
     }
+
+    #[test]
+    fn vote_set_bits_round_trip() {
+        let bits: VoteSetBits = "BA{4:xx_x} 30/40 = 0.75".parse().unwrap();
+        assert_eq!(bits.voted_power, 30);
+        assert_eq!(bits.total_power, 40);
+        let array = bits.bits.as_ref().unwrap();
+        assert_eq!(array.len(), 4);
+        assert_eq!(array.count_ones(), 3);
+        assert_eq!(array.get(2), Some(false));
+        assert_eq!(bits.to_string(), "BA{4:xx_x} 30/40 = 0.75");
+
+        let nil: VoteSetBits = "nil-BitArray 0/10 = 0.00".parse().unwrap();
+        assert_eq!(nil.bits, None);
+        assert_eq!(nil.to_string(), "nil-BitArray 0/10 = 0.00");
+    }
+
+    #[test]
+    fn vote_set_bits_rejects_malformed() {
+        for s in [
+            "BA{3:xx_x} 30/40 = 0.75",
+            "BA{4:xxox} 30/40 = 0.75",
+            "BA{4:xx_x}",
+        ] {
+            assert!(VoteSetBits::from_str(s).is_err(), "{}", s);
+        }
+    }
 }
@@ -0,0 +1,322 @@
+//! `/dump_consensus_state` endpoint JSON-RPC wrapper
+
+use alloc::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tendermint::{block, serializers, validator, Block, Time};
+
+pub use super::consensus_state::{BitArray, RoundVote, RoundVotes, VoteSetBits};
+use crate::{dialect::Dialect, prelude::*, request::RequestMessage, Method};
+
+/// Dump the full consensus state of the node, including the state of the
+/// consensus of each of its peers as the node knows it.
+///
+/// Unlike `/consensus_state`, this is expensive to compute and to transfer,
+/// which makes it best suited for debugging.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request;
+
+impl Request {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl RequestMessage for Request {
+    fn method(&self) -> Method {
+        Method::DumpConsensusState
+    }
+}
+
+impl<S: Dialect> crate::Request<S> for Request {
+    type Response = Response;
+}
+
+impl<S: Dialect> crate::SimpleRequest<S> for Request {
+    type Output = Response;
+}
+
+/// The full consensus state (UNSTABLE).
+///
+/// Currently based on <https://github.com/tendermint/tendermint/blob/e820e68acd69737cfb63bc9ccca5f5450a42b5cf/rpc/core/types/responses.go#L167>
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    pub round_state: RoundState,
+    pub peers: Vec<PeerStateInfo>,
+}
+
+impl crate::Response for Response {}
+
+/// The full state of the consensus of the node.
+///
+/// The part sets of the proposed, locked and valid blocks are omitted.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RoundState {
+    pub height: block::Height,
+    pub round: i32,
+    pub step: u8,
+
+    #[serde(with = "serializers::time")]
+    pub start_time: Time,
+
+    /// The time the last block was committed.
+    #[serde(with = "serializers::time")]
+    pub commit_time: Time,
+
+    pub validators: ValidatorSet,
+    pub proposal: Option<Proposal>,
+    pub proposal_block: Option<Block>,
+
+    pub locked_round: i32,
+    pub locked_block: Option<Block>,
+
+    pub valid_round: i32,
+    pub valid_block: Option<Block>,
+
+    /// The votes received at the current height, by round.
+    pub votes: Vec<RoundVotes>,
+
+    pub commit_round: i32,
+    pub last_commit: Option<VoteSet>,
+    pub last_validators: ValidatorSet,
+    pub triggered_timeout_precommit: bool,
+}
+
+/// A validator set along with its current proposer.
+///
+/// Unlike with [`validator::Set`], the total voting power is not part of the
+/// serialized form.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ValidatorSet {
+    #[serde(with = "serializers::nullable")]
+    pub validators: Vec<validator::Info>,
+    pub proposer: Option<validator::Info>,
+}
+
+/// The block proposal of the current round.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Proposal {
+    #[serde(rename = "type")]
+    pub msg_type: i32,
+    pub height: block::Height,
+    pub round: i32,
+    pub pol_round: i32,
+    pub block_id: block::Id,
+    #[serde(with = "serializers::time")]
+    pub timestamp: Time,
+    #[serde(with = "serializers::bytes::base64string")]
+    pub signature: Vec<u8>,
+}
+
+/// The votes of a particular type received for a round.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct VoteSet {
+    pub votes: Vec<RoundVote>,
+    pub votes_bit_array: String,
+    /// The blocks which peers claim to have seen +2/3 of the votes for, by
+    /// peer ID.
+    pub peer_maj_23s: BTreeMap<String, block::Id>,
+}
+
+impl VoteSet {
+    /// Parse the summary of the votes received.
+    pub fn votes_bits(&self) -> Result<VoteSetBits, crate::Error> {
+        self.votes_bit_array.parse()
+    }
+}
+
+/// The state of the consensus of a peer of the node.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerStateInfo {
+    pub node_address: String,
+    pub peer_state: PeerState,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerState {
+    pub round_state: PeerRoundState,
+    pub stats: PeerStateStats,
+}
+
+/// The consensus state of a peer, as known by the node.
+///
+/// The bit arrays mark the block parts and votes the peer is known to have,
+/// and are `None` when the node does not know about the relevant round.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerRoundState {
+    pub height: block::Height,
+    pub round: i32,
+    pub step: u8,
+
+    #[serde(with = "serializers::time")]
+    pub start_time: Time,
+
+    /// Whether the peer has the proposal of the current round.
+    pub proposal: bool,
+    pub proposal_block_part_set_header: block::parts::Header,
+    pub proposal_block_parts: Option<BitArray>,
+    pub proposal_pol_round: i32,
+    pub proposal_pol: Option<BitArray>,
+
+    pub prevotes: Option<BitArray>,
+    pub precommits: Option<BitArray>,
+
+    pub last_commit_round: i32,
+    pub last_commit: Option<BitArray>,
+
+    pub catchup_commit_round: i32,
+    pub catchup_commit: Option<BitArray>,
+}
+
+/// The number of useful messages received from a peer.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerStateStats {
+    #[serde(with = "serializers::from_str")]
+    pub votes: u64,
+    #[serde(with = "serializers::from_str")]
+    pub block_parts: u64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DUMP_CONSENSUS_STATE: &str = r#"{
+        "round_state": {
+            "height": "8",
+            "round": 0,
+            "step": 1,
+            "start_time": "2023-06-20T10:45:03.221958Z",
+            "commit_time": "2023-06-20T10:45:02.221958Z",
+            "validators": {
+                "validators": [
+                    {
+                        "address": "A9D8AB51A336B1D5B4A8C955C2F34020FDA5F413",
+                        "pub_key": {
+                            "type": "tendermint/PubKeyEd25519",
+                            "value": "LIV9sGxEkzcsvr0JcqWd5UbALnYfk79Bjaot1ImseIk="
+                        },
+                        "voting_power": "10",
+                        "proposer_priority": "0"
+                    }
+                ],
+                "proposer": {
+                    "address": "A9D8AB51A336B1D5B4A8C955C2F34020FDA5F413",
+                    "pub_key": {
+                        "type": "tendermint/PubKeyEd25519",
+                        "value": "LIV9sGxEkzcsvr0JcqWd5UbALnYfk79Bjaot1ImseIk="
+                    },
+                    "voting_power": "10",
+                    "proposer_priority": "0"
+                }
+            },
+            "proposal": null,
+            "proposal_block": null,
+            "proposal_block_parts": null,
+            "locked_round": -1,
+            "locked_block": null,
+            "locked_block_parts": null,
+            "valid_round": -1,
+            "valid_block": null,
+            "valid_block_parts": null,
+            "votes": [
+                {
+                    "round": 0,
+                    "prevotes": ["nil-Vote"],
+                    "prevotes_bit_array": "BA{1:_} 0/10 = 0.00",
+                    "precommits": ["nil-Vote"],
+                    "precommits_bit_array": "BA{1:_} 0/10 = 0.00"
+                }
+            ],
+            "commit_round": -1,
+            "last_commit": {
+                "votes": [
+                    "Vote{0:A9D8AB51A336 7/00/SIGNED_MSG_TYPE_PRECOMMIT(Precommit) 3F1607E7A548 59F5C3DE1E07 @ 2023-06-20T10:45:02.010155Z}"
+                ],
+                "votes_bit_array": "BA{1:x} 10/10 = 1.00",
+                "peer_maj_23s": {}
+            },
+            "last_validators": {
+                "validators": [
+                    {
+                        "address": "A9D8AB51A336B1D5B4A8C955C2F34020FDA5F413",
+                        "pub_key": {
+                            "type": "tendermint/PubKeyEd25519",
+                            "value": "LIV9sGxEkzcsvr0JcqWd5UbALnYfk79Bjaot1ImseIk="
+                        },
+                        "voting_power": "10",
+                        "proposer_priority": "0"
+                    }
+                ],
+                "proposer": null
+            },
+            "triggered_timeout_precommit": false
+        },
+        "peers": [
+            {
+                "node_address": "3e16af0cead27979e1fc3dac57d03df3c7a77acc@127.0.0.1:26656",
+                "peer_state": {
+                    "round_state": {
+                        "height": "8",
+                        "round": 0,
+                        "step": 3,
+                        "start_time": "2023-06-20T10:45:03.221958Z",
+                        "proposal": true,
+                        "proposal_block_part_set_header": {
+                            "total": 1,
+                            "hash": "5D4834E4C2E1A1D3B1E50851AE6F4D5CB2B2A59B1F4F4E5E2A0FD3F1A6AAEF3C"
+                        },
+                        "proposal_block_parts": "x",
+                        "proposal_pol_round": -1,
+                        "proposal_pol": "_",
+                        "prevotes": "x",
+                        "precommits": "_",
+                        "last_commit_round": 0,
+                        "last_commit": "x",
+                        "catchup_commit_round": -1,
+                        "catchup_commit": null
+                    },
+                    "stats": {
+                        "votes": "42",
+                        "block_parts": "7"
+                    }
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn deserialize_dump_consensus_state() {
+        let response: Response = serde_json::from_str(DUMP_CONSENSUS_STATE).unwrap();
+
+        let round_state = &response.round_state;
+        assert_eq!(round_state.height.value(), 8);
+        assert_eq!(round_state.locked_round, -1);
+        assert_eq!(round_state.validators.validators.len(), 1);
+        assert!(round_state.validators.proposer.is_some());
+        assert!(round_state.last_validators.proposer.is_none());
+        assert!(round_state.proposal.is_none());
+
+        let prevotes = round_state.votes[0].prevotes_bits().unwrap();
+        assert_eq!(prevotes.bits.unwrap().count_ones(), 0);
+        assert_eq!(prevotes.total_power, 10);
+
+        let last_commit = round_state.last_commit.as_ref().unwrap();
+        assert!(matches!(last_commit.votes[0], RoundVote::Vote(_)));
+        assert_eq!(last_commit.votes_bits().unwrap().fraction(), 1.0);
+
+        let peer = &response.peers[0].peer_state;
+        assert_eq!(peer.round_state.step, 3);
+        assert_eq!(peer.round_state.prevotes, Some(BitArray::new(vec![true])));
+        assert_eq!(peer.round_state.catchup_commit, None);
+        assert_eq!(peer.stats.votes, 42);
+        assert_eq!(peer.stats.block_parts, 7);
+
+        // Bit arrays are serialized back in the node's format.
+        let value = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            value["peers"][0]["peer_state"]["round_state"]["proposal_pol"],
+            "_"
+        );
+    }
+}
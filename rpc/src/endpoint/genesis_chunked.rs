@@ -0,0 +1,56 @@
+//! `/genesis_chunked` endpoint JSON-RPC wrapper
+
+use serde::{Deserialize, Serialize};
+use tendermint::serializers;
+
+use crate::{dialect::Dialect, prelude::*, request::RequestMessage};
+
+/// Get a chunk of the genesis file of the current chain.
+///
+/// Nodes refuse to serve large genesis files through `/genesis`, but serve
+/// them in chunks through this endpoint.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request {
+    /// The index of the chunk, starting at 0.
+    #[serde(with = "serializers::from_str")]
+    pub chunk: u64,
+}
+
+impl Request {
+    /// Request the chunk with the given index.
+    pub fn new(chunk: u64) -> Self {
+        Self { chunk }
+    }
+}
+
+impl RequestMessage for Request {
+    fn method(&self) -> crate::Method {
+        crate::Method::GenesisChunked
+    }
+}
+
+impl<S: Dialect> crate::Request<S> for Request {
+    type Response = Response;
+}
+
+impl<S: Dialect> crate::SimpleRequest<S> for Request {
+    type Output = Response;
+}
+
+/// A chunk of the genesis file
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// The index of the chunk
+    #[serde(with = "serializers::from_str")]
+    pub chunk: u64,
+
+    /// The total number of chunks
+    #[serde(with = "serializers::from_str")]
+    pub total: u64,
+
+    /// The chunk of the JSON-encoded genesis file
+    #[serde(with = "serializers::bytes::base64string")]
+    pub data: Vec<u8>,
+}
+
+impl crate::Response for Response {}
@@ -28,6 +28,10 @@ impl RequestMessage for Request {
     fn method(&self) -> crate::Method {
         crate::Method::Header
     }
+
+    fn height(&self) -> Option<block::Height> {
+        self.height
+    }
 }
 
 impl crate::Request<v0_37::Dialect> for Request {
@@ -0,0 +1,43 @@
+//! `/num_unconfirmed_txs` endpoint JSON-RPC wrapper
+
+use serde::{Deserialize, Serialize};
+use tendermint::serializers;
+
+use crate::{dialect::Dialect, request::RequestMessage};
+
+/// Get the number and total size of the transactions in the mempool of the
+/// node, without the transactions themselves.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request;
+
+impl RequestMessage for Request {
+    fn method(&self) -> crate::Method {
+        crate::Method::NumUnconfirmedTxs
+    }
+}
+
+impl<S: Dialect> crate::Request<S> for Request {
+    type Response = Response;
+}
+
+impl<S: Dialect> crate::SimpleRequest<S> for Request {
+    type Output = Response;
+}
+
+/// The size of the mempool
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// The number of transactions returned, which is always 0
+    #[serde(with = "serializers::from_str")]
+    pub n_txs: u64,
+
+    /// The number of transactions in the mempool
+    #[serde(with = "serializers::from_str")]
+    pub total: u64,
+
+    /// The total size of the transactions in the mempool, in bytes
+    #[serde(with = "serializers::from_str")]
+    pub total_bytes: u64,
+}
+
+impl crate::Response for Response {}
@@ -1,10 +1,10 @@
 //! `/tx` endpoint JSON-RPC wrapper
 
 use serde::{Deserialize, Serialize};
-use tendermint::{abci, block, tx, Hash};
+use tendermint::{abci, block, crypto::Sha256, merkle::MerkleHash, tx, Hash};
 
 use crate::dialect::{self, Dialect};
-use crate::{prelude::*, request::RequestMessage, serializers, Method};
+use crate::{prelude::*, request::RequestMessage, serializers, Error, Method};
 
 /// Request for finding a transaction by its hash.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -66,20 +66,52 @@ pub struct Response {
     pub proof: Option<tx::Proof>,
 }
 
+impl Response {
+    /// Verify the inclusion proof of this transaction against the header of
+    /// the block at the transaction's height, using the given Merkle hasher.
+    ///
+    /// Fails if the response carries no proof, i.e. the transaction was not
+    /// requested with `prove` set.
+    pub fn verify<H>(&self, header: &block::Header) -> Result<(), Error>
+    where
+        H: MerkleHash + Sha256 + Default,
+    {
+        let invalid =
+            |reason: &str| Error::tendermint(tendermint::Error::invalid_proof(reason.to_string()));
+        let proof = self
+            .proof
+            .as_ref()
+            .ok_or_else(|| invalid("response does not include a proof"))?;
+        if header.height != self.height {
+            return Err(invalid("header is not at the height of the transaction"));
+        }
+        if proof.data != self.tx {
+            return Err(invalid("proof is not for this transaction"));
+        }
+        let data_hash = header
+            .data_hash
+            .as_ref()
+            .ok_or_else(|| invalid("header has no data hash"))?;
+        proof.verify_with::<H>(data_hash).map_err(Error::tendermint)
+    }
+}
+
 impl crate::Response for Response {}
 
 /// Serialization for /tx endpoint format in Tendermint 0.34
 pub mod v0_34 {
     use super::Response;
-    use crate::dialect::v0_34::Event;
+    use crate::dialect::{self, Dialect};
     use crate::prelude::*;
-    use crate::{dialect, serializers};
+    use crate::serializers;
     use serde::{Deserialize, Serialize};
     use tendermint::{block, tx, Hash};
 
-    /// RPC dialect helper for serialization of the response.
+    /// RPC dialect helper for serialization of the response, in the
+    /// Tendermint 0.34 dialect by default.
     #[derive(Debug, Deserialize, Serialize)]
-    pub struct DialectResponse {
+    #[serde(bound = "")]
+    pub struct DialectResponse<S: Dialect = dialect::v0_34::Dialect> {
         /// The hash of the transaction.
         ///
         /// Deserialized from a hex-encoded string (there is a discrepancy between
@@ -88,17 +120,17 @@ pub mod v0_34 {
         pub hash: Hash,
         pub height: block::Height,
         pub index: u32,
-        pub tx_result: dialect::DeliverTx<Event>,
+        pub tx_result: dialect::DeliverTx<S::Event, S::Encoding>,
         #[serde(with = "serializers::bytes::base64string")]
         pub tx: Vec<u8>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub proof: Option<tx::Proof>,
     }
 
-    impl crate::Response for DialectResponse {}
+    impl<S: Dialect> crate::Response for DialectResponse<S> {}
 
-    impl From<DialectResponse> for Response {
-        fn from(msg: DialectResponse) -> Self {
+    impl<S: Dialect> From<DialectResponse<S>> for Response {
+        fn from(msg: DialectResponse<S>) -> Self {
             Self {
                 hash: msg.hash,
                 height: msg.height,
@@ -21,6 +21,16 @@ pub struct Request {
     #[serde(with = "serializers::from_str")]
     pub per_page: u8,
     pub order_by: Order,
+    /// Whether the conditions of the query must all be matched by the same
+    /// event, rather than by any events of a transaction.
+    ///
+    /// Only supported by CometBFT 0.34.26, 0.37.1 and later.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub match_events: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
 }
 
 impl Request {
@@ -38,8 +48,16 @@ impl Request {
             page,
             per_page,
             order_by,
+            match_events: false,
         }
     }
+
+    /// Set whether the conditions of the query must all be matched by the
+    /// same event.
+    pub fn with_match_events(mut self, match_events: bool) -> Self {
+        self.match_events = match_events;
+        self
+    }
 }
 
 impl RequestMessage for Request {
@@ -76,22 +94,25 @@ impl crate::Response for Response {}
 /// Serialization for /tx_search endpoint format in Tendermint 0.34
 pub mod v0_34 {
     use super::{tx, Response};
+    use crate::dialect::{self, Dialect};
     use crate::prelude::*;
     use crate::serializers;
     use serde::{Deserialize, Serialize};
 
-    /// RPC dialect helper for serialization of the response.
+    /// RPC dialect helper for serialization of the response, in the
+    /// Tendermint 0.34 dialect by default.
     #[derive(Debug, Deserialize, Serialize)]
-    pub struct DialectResponse {
-        pub txs: Vec<tx::v0_34::DialectResponse>,
+    #[serde(bound = "")]
+    pub struct DialectResponse<S: Dialect = dialect::v0_34::Dialect> {
+        pub txs: Vec<tx::v0_34::DialectResponse<S>>,
         #[serde(with = "serializers::from_str")]
         pub total_count: u32,
     }
 
-    impl crate::Response for DialectResponse {}
+    impl<S: Dialect> crate::Response for DialectResponse<S> {}
 
-    impl From<DialectResponse> for Response {
-        fn from(msg: DialectResponse) -> Self {
+    impl<S: Dialect> From<DialectResponse<S>> for Response {
+        fn from(msg: DialectResponse<S>) -> Self {
             Self {
                 txs: msg.txs.into_iter().map(Into::into).collect(),
                 total_count: msg.total_count,
@@ -0,0 +1,98 @@
+//! `/unconfirmed_txs` endpoint JSON-RPC wrapper
+
+use serde::{Deserialize, Serialize};
+use tendermint::serializers;
+
+use crate::{dialect::Dialect, prelude::*, request::RequestMessage};
+
+/// Get the transactions in the mempool of the node.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request {
+    /// The maximum number of transactions to return.
+    ///
+    /// The node returns up to 30 transactions when unset, and never more
+    /// than 100.
+    #[serde(with = "serializers::optional_from_str")]
+    pub limit: Option<u32>,
+}
+
+impl Request {
+    /// Request up to `limit` of the transactions in the mempool.
+    pub fn new(limit: Option<u32>) -> Self {
+        Self { limit }
+    }
+}
+
+impl RequestMessage for Request {
+    fn method(&self) -> crate::Method {
+        crate::Method::UnconfirmedTxs
+    }
+}
+
+impl<S: Dialect> crate::Request<S> for Request {
+    type Response = Response;
+}
+
+impl<S: Dialect> crate::SimpleRequest<S> for Request {
+    type Output = Response;
+}
+
+/// The transactions in the mempool
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// The number of transactions returned
+    #[serde(with = "serializers::from_str")]
+    pub n_txs: u64,
+
+    /// The number of transactions in the mempool
+    #[serde(with = "serializers::from_str")]
+    pub total: u64,
+
+    /// The total size of the transactions in the mempool, in bytes
+    #[serde(with = "serializers::from_str")]
+    pub total_bytes: u64,
+
+    /// The transactions returned, in the order they are in the mempool
+    #[serde(with = "serializers::txs")]
+    pub txs: Vec<Vec<u8>>,
+}
+
+impl crate::Response for Response {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_request() {
+        let request = serde_json::to_value(Request::new(Some(5))).unwrap();
+        assert_eq!(request, serde_json::json!({ "limit": "5" }));
+        let request = serde_json::to_value(Request::default()).unwrap();
+        assert_eq!(request, serde_json::json!({ "limit": null }));
+    }
+
+    #[test]
+    fn deserialize_response() {
+        let response: Response = serde_json::from_str(
+            r#"{
+                "n_txs": "2",
+                "total": "3",
+                "total_bytes": "19",
+                "txs": ["a2V5MT12YWx1ZTE=", "a2V5Mj12YWx1ZTI="]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(response.n_txs, 2);
+        assert_eq!(response.total, 3);
+        assert_eq!(response.total_bytes, 19);
+        assert_eq!(
+            response.txs,
+            [b"key1=value1".to_vec(), b"key2=value2".to_vec()]
+        );
+
+        let response: Response =
+            serde_json::from_str(r#"{"n_txs":"0","total":"0","total_bytes":"0","txs":null}"#)
+                .unwrap();
+        assert!(response.txs.is_empty());
+    }
+}
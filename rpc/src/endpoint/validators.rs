@@ -49,6 +49,10 @@ impl RequestMessage for Request {
     fn method(&self) -> crate::Method {
         crate::Method::Validators
     }
+
+    fn height(&self) -> Option<block::Height> {
+        self.height
+    }
 }
 
 impl<S: Dialect> crate::Request<S> for Request {
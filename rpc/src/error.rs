@@ -1,6 +1,6 @@
 //! JSON-RPC error types
 
-use core::time::Duration;
+use core::{fmt, time::Duration};
 
 use flex_error::{define_error, DefaultTracer, DisplayError, DisplayOnly, ErrorMessageTracer};
 
@@ -18,22 +18,28 @@ type InvalidUriError = flex_error::DisplayOnly<http::uri::InvalidUri>;
 #[cfg(not(feature = "http"))]
 type InvalidUriError = flex_error::NoSource;
 
-#[cfg(feature = "hyper")]
+#[cfg(all(feature = "hyper", not(target_arch = "wasm32")))]
 type HyperError = flex_error::DisplayOnly<hyper::Error>;
 
-#[cfg(not(feature = "hyper"))]
+#[cfg(any(not(feature = "hyper"), target_arch = "wasm32"))]
 type HyperError = flex_error::NoSource;
 
-#[cfg(feature = "tokio")]
+#[cfg(feature = "tonic")]
+type GrpcError = flex_error::DisplayOnly<tonic::Status>;
+
+#[cfg(not(feature = "tonic"))]
+type GrpcError = flex_error::NoSource;
+
+#[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
 type JoinError = flex_error::DisplayOnly<tokio::task::JoinError>;
 
-#[cfg(not(feature = "tokio"))]
+#[cfg(any(not(feature = "tokio"), target_arch = "wasm32"))]
 type JoinError = flex_error::NoSource;
 
-#[cfg(feature = "async-tungstenite")]
+#[cfg(all(feature = "async-tungstenite", not(target_arch = "wasm32")))]
 type TungsteniteError = flex_error::DisplayOnly<async_tungstenite::tungstenite::Error>;
 
-#[cfg(not(feature = "async-tungstenite"))]
+#[cfg(any(not(feature = "async-tungstenite"), target_arch = "wasm32"))]
 type TungsteniteError = flex_error::NoSource;
 
 define_error! {
@@ -43,6 +49,22 @@ define_error! {
             [ DisplayError<ResponseError> ]
             | _ | { "response error" },
 
+        TxInCache
+            [ DisplayError<ResponseError> ]
+            | _ | { "the transaction is already in the mempool cache" },
+
+        HeightNotAvailable
+            [ DisplayError<ResponseError> ]
+            | _ | { "the node has no data at the requested height" },
+
+        PageOutOfRange
+            [ DisplayError<ResponseError> ]
+            | _ | { "the requested page is out of range" },
+
+        TxCommitTimeout
+            [ DisplayError<ResponseError> ]
+            | _ | { "timed out waiting for the transaction to be committed" },
+
         Io
             [ DisplayOnly<std::io::Error> ]
             | _ | { "I/O error" },
@@ -55,6 +77,18 @@ define_error! {
             [ HyperError ]
             | _ | { "HTTP error" },
 
+        Fetch
+            {
+                reason: String
+            }
+            | e | {
+                format_args!("fetch error: {}", e.reason)
+            },
+
+        Grpc
+            [ GrpcError ]
+            | _ | { "gRPC error" },
+
         InvalidParams
             {
                 message: String
@@ -72,13 +106,13 @@ define_error! {
                 format_args!("web socket error: {}", e.message)
             },
 
-        WebSocketTimeout
+        ConnectionStale
             {
                 timeout: Duration
             }
             | e | {
-                format_args!("reading from WebSocket connection timed out after {} seconds",
-                    e.timeout.as_secs())
+                format_args!("WebSocket connection is stale: nothing received from the node in {}ms",
+                    e.timeout.as_millis())
             },
 
         MethodNotFound
@@ -124,6 +158,17 @@ define_error! {
                 )
             },
 
+        RequestTimeout
+            {
+                duration: Duration
+            }
+            | e | {
+                format_args!(
+                    "request timed out after {}ms",
+                    e.duration.as_millis()
+                )
+            },
+
         ChannelSend
             | _ | { "failed to send message to internal channel" },
 
@@ -170,6 +215,15 @@ define_error! {
             [ DisplayOnly<serde_json::Error> ]
             | _ | { "serde parse error" },
 
+        InvalidResponse
+            {
+                context: ResponseContext,
+            }
+            [ DisplayOnly<Error> ]
+            | e | {
+                format_args!("invalid response: {}", e.context)
+            },
+
         ParseUrl
             [ DisplayOnly<url::ParseError> ]
             | _ | { "parse error" },
@@ -218,6 +272,147 @@ define_error! {
             | e | {
                 format_args!("unsupported Tendermint version reported by the node: {}", e.version)
             },
+
+        DuplicateBroadcast
+            {
+                hash: tendermint::Hash,
+            }
+            | e | {
+                format_args!("transaction {} was already broadcast recently", e.hash)
+            },
+
+        SequenceConflict
+            {
+                hash: tendermint::Hash,
+                log: String,
+            }
+            | e | {
+                format_args!("transaction {} was rejected due to a sequence conflict: {}",
+                    e.hash, e.log)
+            },
+
+        RateLimited
+            {
+                method: String,
+                retry_after: Duration,
+            }
+            | e | {
+                format_args!("rate limit exceeded for {}, retry after {}ms",
+                    e.method, e.retry_after.as_millis())
+            },
+
+        ResponseTooLarge
+            {
+                method: String,
+                limit: u64,
+            }
+            | e | {
+                format_args!("response to {} is larger than the limit of {} bytes",
+                    e.method, e.limit)
+            },
+
+        UnsupportedContentEncoding
+            {
+                encoding: String,
+            }
+            | e | {
+                format_args!("the node sent a response with the unsupported content encoding {}",
+                    e.encoding)
+            },
+
+        ServiceAlreadyStarted
+            | _ | { "service already started" },
+
+        ShutdownTimeout
+            {
+                timeout: Duration,
+            }
+            | e | {
+                format_args!("service did not shut down within {}ms", e.timeout.as_millis())
+            },
+
+        MissingBlockMeta
+            {
+                height: tendermint::block::Height,
+            }
+            | e | {
+                format_args!("the node returned no block meta at height {}", e.height)
+            },
+
+        SubscriptionLagged
+            {
+                dropped: u64,
+            }
+            | e | {
+                format_args!("the subscriber lagged behind, {} events were dropped", e.dropped)
+            },
+
+        SubscriptionOverflow
+            {
+                capacity: usize,
+            }
+            | e | {
+                format_args!("the buffer of the subscription overflowed its capacity of {} events",
+                    e.capacity)
+            },
+    }
+}
+
+/// The maximum length of the response body kept in a [`ResponseContext`].
+pub const RESPONSE_BODY_SNIPPET_LEN: usize = 1024;
+
+/// The wire-level context of a response which could not be decoded, to debug
+/// mismatches with nodes running other versions of Tendermint without
+/// capturing their traffic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResponseContext {
+    /// The name of the method of the request, e.g. `block`.
+    pub method: String,
+    /// The URL of the node, with its credentials redacted.
+    pub url: String,
+    /// The HTTP status code of the response.
+    pub status: u16,
+    /// The start of the response body, up to [`RESPONSE_BODY_SNIPPET_LEN`]
+    /// bytes.
+    pub body: String,
+    /// The length of the whole response body, in bytes.
+    pub body_len: usize,
+}
+
+impl ResponseContext {
+    /// Capture the context of the given response body, keeping only its
+    /// start.
+    pub fn new(method: String, url: String, status: u16, body: &str) -> Self {
+        let mut end = body.len().min(RESPONSE_BODY_SNIPPET_LEN);
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        Self {
+            method,
+            url,
+            status,
+            body: body[..end].to_string(),
+            body_len: body.len(),
+        }
+    }
+
+    /// Whether the body was truncated.
+    pub fn is_truncated(&self) -> bool {
+        self.body.len() < self.body_len
+    }
+}
+
+impl fmt::Display for ResponseContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} from {} (HTTP status {}, {} bytes): {}",
+            self.method, self.url, self.status, self.body_len, self.body
+        )?;
+        if self.is_truncated() {
+            write!(f, "...")?;
+        }
+        Ok(())
     }
 }
 
@@ -230,9 +425,93 @@ impl Clone for Error {
     }
 }
 
+impl Error {
+    /// The error returned by the node in the given response, classified
+    /// according to its data into one of the variants that callers are
+    /// likely to handle, such as [`ErrorDetail::TxInCache`], or else
+    /// [`ErrorDetail::Response`].
+    pub fn from_response(e: ResponseError) -> Error {
+        let reason = e.data().unwrap_or_else(|| e.message());
+        if reason.contains("tx already exists in cache") {
+            Error::tx_in_cache(e)
+        } else if reason.contains("must be less than or equal to the current blockchain height")
+            || reason.contains("is not available, lowest height is")
+        {
+            Error::height_not_available(e)
+        } else if reason.contains("page should be within") {
+            Error::page_out_of_range(e)
+        } else if reason.contains("timed out waiting for tx to be included in a block") {
+            Error::tx_commit_timeout(e)
+        } else {
+            Error::response(e)
+        }
+    }
+
+    /// The error returned by the node, if this error is one, whether it was
+    /// classified or not.
+    pub fn response_error(&self) -> Option<&ResponseError> {
+        match self.detail() {
+            ErrorDetail::Response(e) => Some(&e.source),
+            ErrorDetail::TxInCache(e) => Some(&e.source),
+            ErrorDetail::HeightNotAvailable(e) => Some(&e.source),
+            ErrorDetail::PageOutOfRange(e) => Some(&e.source),
+            ErrorDetail::TxCommitTimeout(e) => Some(&e.source),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(feature = "tokio")]
 impl Error {
     pub fn send<T>(_: tokio::sync::mpsc::error::SendError<T>) -> Error {
         Error::channel_send()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{endpoint::broadcast::tx_sync, response_error::Code, Response};
+
+    fn error_response(data: &str) -> Error {
+        let json = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": {
+                "code": -32603,
+                "message": "Internal error",
+                "data": data,
+            }
+        });
+        tx_sync::Response::from_string(json.to_string()).unwrap_err()
+    }
+
+    #[test]
+    fn response_errors_are_classified() {
+        let e = error_response("tx already exists in cache");
+        assert!(matches!(e.detail(), ErrorDetail::TxInCache(_)));
+
+        let e = error_response(
+            "height 20 must be less than or equal to the current blockchain height 10",
+        );
+        assert!(matches!(e.detail(), ErrorDetail::HeightNotAvailable(_)));
+        let e = error_response("height 1 is not available, lowest height is 5");
+        assert!(matches!(e.detail(), ErrorDetail::HeightNotAvailable(_)));
+
+        let e = error_response("page should be within [1, 2] range, given 3");
+        assert!(matches!(e.detail(), ErrorDetail::PageOutOfRange(_)));
+
+        let e = error_response("timed out waiting for tx to be included in a block");
+        assert!(matches!(e.detail(), ErrorDetail::TxCommitTimeout(_)));
+        assert_eq!(e.response_error().unwrap().code(), Code::InternalError);
+
+        let e = error_response("height must be greater than 0, but got 0");
+        assert!(matches!(e.detail(), ErrorDetail::Response(_)));
+        assert_eq!(
+            e.response_error().unwrap().data(),
+            Some("height must be greater than 0, but got 0")
+        );
+
+        assert!(Error::channel_send().response_error().is_none());
+    }
+}
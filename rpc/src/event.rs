@@ -2,7 +2,7 @@
 
 use alloc::collections::BTreeMap as HashMap;
 
-use tendermint::{abci, block, Block};
+use tendermint::{abci, block, validator, Block, Vote};
 
 use crate::{prelude::*, query::EventType};
 
@@ -25,11 +25,36 @@ impl Event {
     /// Returns `None` if we don't yet support this event type.
     pub fn event_type(&self) -> Option<EventType> {
         match self.data {
-            EventData::NewBlock { .. } => Some(EventType::NewBlock),
+            EventData::NewBlock { .. } | EventData::LegacyNewBlock { .. } => {
+                Some(EventType::NewBlock)
+            },
+            EventData::NewBlockHeader { .. } => Some(EventType::NewBlockHeader),
             EventData::Tx { .. } => Some(EventType::Tx),
-            _ => None,
+            EventData::ValidatorSetUpdates { .. } => Some(EventType::ValidatorSetUpdates),
+            EventData::Vote { .. } => Some(EventType::Vote),
+            EventData::GenericJsonEvent(_) => None,
         }
     }
+
+    /// Returns the values of the indexed attribute with the given composite
+    /// key, of the form `{event type}.{attribute key}` used in queries (e.g.
+    /// `transfer.recipient`).
+    ///
+    /// Returns an empty slice if the event carries no such attribute.
+    pub fn attribute_values(&self, key: &str) -> &[String] {
+        self.events
+            .as_ref()
+            .and_then(|events| events.get(key))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the first value of the indexed attribute with the given
+    /// composite key, if any.
+    ///
+    /// See [`Event::attribute_values`].
+    pub fn attribute_value(&self, key: &str) -> Option<&str> {
+        self.attribute_values(key).first().map(String::as_str)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -50,9 +75,26 @@ pub enum EventData {
         result_begin_block: Option<abci::response::BeginBlock>,
         result_end_block: Option<abci::response::EndBlock>,
     },
+    /// Header of the newly committed block.
+    ///
+    /// Since CometBFT 0.38, only the header is sent.
+    NewBlockHeader {
+        header: Box<block::Header>,
+        num_txs: Option<i64>,
+        result_begin_block: Option<abci::response::BeginBlock>,
+        result_end_block: Option<abci::response::EndBlock>,
+    },
     Tx {
         tx_result: TxInfo,
     },
+    /// Updates to the validator set returned by the application.
+    ValidatorSetUpdates {
+        validator_updates: Vec<validator::Info>,
+    },
+    /// A vote received by the node.
+    Vote {
+        vote: Vote,
+    },
     GenericJsonEvent(serde_json::Value),
 }
 
@@ -74,6 +116,23 @@ pub struct TxResult {
     pub events: Vec<abci::Event>,
 }
 
+impl TxResult {
+    /// Returns the values of the indexed attributes with the given composite
+    /// key, of the form `{event type}.{attribute key}` used in queries (e.g.
+    /// `transfer.recipient`), in the order of the events.
+    ///
+    /// Attributes which are not indexed by the node are skipped.
+    pub fn indexed_attribute_values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        let (kind, attribute_key) = key.split_once('.').unwrap_or((key, ""));
+        self.events
+            .iter()
+            .filter(move |event| event.kind == kind)
+            .flat_map(|event| &event.attributes)
+            .filter(move |attr| attr.index && attr.key == attribute_key)
+            .map(|attr| attr.value.as_str())
+    }
+}
+
 /// Serialization helpers for CometBFT 0.34 RPC
 pub mod v0_34 {
     use super::{Event, EventData, TxInfo, TxResult};
@@ -82,7 +141,7 @@ pub mod v0_34 {
     use crate::{dialect, serializers, Response};
     use alloc::collections::BTreeMap as HashMap;
     use serde::{Deserialize, Serialize};
-    use tendermint::Block;
+    use tendermint::{block, validator, Block, Vote};
 
     #[derive(Serialize, Deserialize, Debug)]
     pub struct DialectEvent {
@@ -129,11 +188,28 @@ pub mod v0_34 {
             result_begin_block: Option<dialect::BeginBlock<RpcEvent>>,
             result_end_block: Option<dialect::EndBlock<RpcEvent>>,
         },
+        #[serde(alias = "tendermint/event/NewBlockHeader")]
+        NewBlockHeader {
+            header: Box<block::Header>,
+            #[serde(with = "serializers::from_str")]
+            num_txs: i64,
+            result_begin_block: dialect::BeginBlock<RpcEvent>,
+            result_end_block: dialect::EndBlock<RpcEvent>,
+        },
         #[serde(alias = "tendermint/event/Tx")]
         Tx {
             #[serde(rename = "TxResult")]
             tx_result: DialectTxInfo,
         },
+        #[serde(alias = "tendermint/event/ValidatorSetUpdates")]
+        ValidatorSetUpdates {
+            validator_updates: Vec<validator::Info>,
+        },
+        #[serde(alias = "tendermint/event/Vote")]
+        Vote {
+            #[serde(rename = "Vote")]
+            vote: Vote,
+        },
         GenericJsonEvent(serde_json::Value),
     }
 
@@ -149,9 +225,24 @@ pub mod v0_34 {
                     result_begin_block: result_begin_block.map(Into::into),
                     result_end_block: result_end_block.map(Into::into),
                 },
+                DialectEventData::NewBlockHeader {
+                    header,
+                    num_txs,
+                    result_begin_block,
+                    result_end_block,
+                } => EventData::NewBlockHeader {
+                    header,
+                    num_txs: Some(num_txs),
+                    result_begin_block: Some(result_begin_block.into()),
+                    result_end_block: Some(result_end_block.into()),
+                },
                 DialectEventData::Tx { tx_result } => EventData::Tx {
                     tx_result: tx_result.into(),
                 },
+                DialectEventData::ValidatorSetUpdates { validator_updates } => {
+                    EventData::ValidatorSetUpdates { validator_updates }
+                },
+                DialectEventData::Vote { vote } => EventData::Vote { vote },
                 DialectEventData::GenericJsonEvent(v) => EventData::GenericJsonEvent(v),
             }
         }
@@ -180,9 +271,24 @@ pub mod v0_34 {
                     result_begin_block: None,
                     result_end_block: None,
                 },
+                EventData::NewBlockHeader {
+                    header,
+                    num_txs,
+                    result_begin_block,
+                    result_end_block,
+                } => DialectEventData::NewBlockHeader {
+                    header,
+                    num_txs: num_txs.unwrap_or_default(),
+                    result_begin_block: result_begin_block.unwrap_or_default().into(),
+                    result_end_block: result_end_block.unwrap_or_default().into(),
+                },
                 EventData::Tx { tx_result } => DialectEventData::Tx {
                     tx_result: tx_result.into(),
                 },
+                EventData::ValidatorSetUpdates { validator_updates } => {
+                    DialectEventData::ValidatorSetUpdates { validator_updates }
+                },
+                EventData::Vote { vote } => DialectEventData::Vote { vote },
                 EventData::GenericJsonEvent(v) => DialectEventData::GenericJsonEvent(v),
             }
         }
@@ -259,7 +365,7 @@ mod latest {
     use alloc::collections::BTreeMap as HashMap;
     use serde::{Deserialize, Serialize};
     use tendermint::abci::Event as RpcEvent;
-    use tendermint::{abci, block, Block};
+    use tendermint::{abci, block, validator, Block, Vote};
 
     #[derive(Deserialize, Debug)]
     pub struct DeEvent {
@@ -300,11 +406,30 @@ mod latest {
             #[serde(default)]
             result_finalize_block: Option<abci::response::FinalizeBlock>,
         },
+        #[serde(alias = "tendermint/event/NewBlockHeader")]
+        NewBlockHeader {
+            header: Box<block::Header>,
+            #[serde(default, with = "serializers::optional_from_str")]
+            num_txs: Option<i64>,
+            #[serde(default)]
+            result_begin_block: Option<abci::response::BeginBlock>,
+            #[serde(default)]
+            result_end_block: Option<abci::response::EndBlock>,
+        },
         #[serde(alias = "tendermint/event/Tx")]
         Tx {
             #[serde(rename = "TxResult")]
             tx_result: DialectTxInfo,
         },
+        #[serde(alias = "tendermint/event/ValidatorSetUpdates")]
+        ValidatorSetUpdates {
+            validator_updates: Vec<validator::Info>,
+        },
+        #[serde(alias = "tendermint/event/Vote")]
+        Vote {
+            #[serde(rename = "Vote")]
+            vote: Vote,
+        },
         GenericJsonEvent(serde_json::Value),
     }
 
@@ -333,9 +458,24 @@ mod latest {
                     result_begin_block: result_begin_block.map(Into::into),
                     result_end_block: result_end_block.map(Into::into),
                 },
+                DeEventData::NewBlockHeader {
+                    header,
+                    num_txs,
+                    result_begin_block,
+                    result_end_block,
+                } => EventData::NewBlockHeader {
+                    header,
+                    num_txs,
+                    result_begin_block,
+                    result_end_block,
+                },
                 DeEventData::Tx { tx_result } => EventData::Tx {
                     tx_result: tx_result.into(),
                 },
+                DeEventData::ValidatorSetUpdates { validator_updates } => {
+                    EventData::ValidatorSetUpdates { validator_updates }
+                },
+                DeEventData::Vote { vote } => EventData::Vote { vote },
                 DeEventData::GenericJsonEvent(v) => EventData::GenericJsonEvent(v),
             }
         }
@@ -410,7 +550,7 @@ pub mod v0_37 {
     use crate::prelude::*;
     use alloc::collections::BTreeMap as HashMap;
     use serde::Serialize;
-    use tendermint::{abci, Block};
+    use tendermint::{abci, block, validator, Block, Vote};
 
     pub use super::latest::*;
 
@@ -443,11 +583,28 @@ pub mod v0_37 {
             result_begin_block: Option<abci::response::BeginBlock>,
             result_end_block: Option<abci::response::EndBlock>,
         },
+        #[serde(alias = "tendermint/event/NewBlockHeader")]
+        NewBlockHeader {
+            header: Box<block::Header>,
+            #[serde(with = "crate::serializers::from_str")]
+            num_txs: i64,
+            result_begin_block: abci::response::BeginBlock,
+            result_end_block: abci::response::EndBlock,
+        },
         #[serde(alias = "tendermint/event/Tx")]
         Tx {
             #[serde(rename = "TxResult")]
             tx_result: DialectTxInfo,
         },
+        #[serde(alias = "tendermint/event/ValidatorSetUpdates")]
+        ValidatorSetUpdates {
+            validator_updates: Vec<validator::Info>,
+        },
+        #[serde(alias = "tendermint/event/Vote")]
+        Vote {
+            #[serde(rename = "Vote")]
+            vote: Vote,
+        },
         GenericJsonEvent(serde_json::Value),
     }
 
@@ -474,9 +631,24 @@ pub mod v0_37 {
                     result_begin_block: result_begin_block.map(Into::into),
                     result_end_block: result_end_block.map(Into::into),
                 },
+                EventData::NewBlockHeader {
+                    header,
+                    num_txs,
+                    result_begin_block,
+                    result_end_block,
+                } => SerEventData::NewBlockHeader {
+                    header,
+                    num_txs: num_txs.unwrap_or_default(),
+                    result_begin_block: result_begin_block.unwrap_or_default(),
+                    result_end_block: result_end_block.unwrap_or_default(),
+                },
                 EventData::Tx { tx_result } => SerEventData::Tx {
                     tx_result: tx_result.into(),
                 },
+                EventData::ValidatorSetUpdates { validator_updates } => {
+                    SerEventData::ValidatorSetUpdates { validator_updates }
+                },
+                EventData::Vote { vote } => SerEventData::Vote { vote },
                 EventData::GenericJsonEvent(v) => SerEventData::GenericJsonEvent(v),
             }
         }
@@ -489,7 +661,7 @@ pub mod v0_38 {
     use crate::prelude::*;
     use alloc::collections::BTreeMap as HashMap;
     use serde::Serialize;
-    use tendermint::{abci, block, Block};
+    use tendermint::{abci, block, validator, Block, Vote};
 
     pub use super::latest::*;
 
@@ -522,11 +694,24 @@ pub mod v0_38 {
             block_id: block::Id,
             result_finalize_block: Option<abci::response::FinalizeBlock>,
         },
+        #[serde(alias = "tendermint/event/NewBlockHeader")]
+        NewBlockHeader {
+            header: Box<block::Header>,
+        },
         #[serde(alias = "tendermint/event/Tx")]
         Tx {
             #[serde(rename = "TxResult")]
             tx_result: DialectTxInfo,
         },
+        #[serde(alias = "tendermint/event/ValidatorSetUpdates")]
+        ValidatorSetUpdates {
+            validator_updates: Vec<validator::Info>,
+        },
+        #[serde(alias = "tendermint/event/Vote")]
+        Vote {
+            #[serde(rename = "Vote")]
+            vote: Vote,
+        },
         GenericJsonEvent(serde_json::Value),
     }
 
@@ -553,11 +738,221 @@ pub mod v0_38 {
                     block_id: Default::default(),
                     result_finalize_block: None,
                 },
+                EventData::NewBlockHeader { header, .. } => SerEventData::NewBlockHeader { header },
                 EventData::Tx { tx_result } => SerEventData::Tx {
                     tx_result: tx_result.into(),
                 },
+                EventData::ValidatorSetUpdates { validator_updates } => {
+                    SerEventData::ValidatorSetUpdates { validator_updates }
+                },
+                EventData::Vote { vote } => SerEventData::Vote { vote },
                 EventData::GenericJsonEvent(v) => SerEventData::GenericJsonEvent(v),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Response;
+    use tendermint::abci::EventAttributeIndexExt;
+
+    fn header_json() -> serde_json::Value {
+        let fixture: serde_json::Value = serde_json::from_str(include_str!(
+            "../tests/kvstore_fixtures/v0_37/incoming/subscribe_newblock_0.json"
+        ))
+        .unwrap();
+        fixture["result"]["data"]["value"]["block"]["header"].clone()
+    }
+
+    fn event_json(data: serde_json::Value) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "b2c7c4b2-53f1-4d0c-8b39-2e0c1a0dbf32",
+            "result": {
+                "query": "transfer.recipient EXISTS",
+                "data": data,
+                "events": {
+                    "tm.event": ["ValidatorSetUpdates"],
+                    "transfer.recipient": ["alice", "bob"],
+                },
+            },
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn legacy_new_block_header() {
+        let json = event_json(serde_json::json!({
+            "type": "tendermint/event/NewBlockHeader",
+            "value": {
+                "header": header_json(),
+                "num_txs": "2",
+                "result_begin_block": {},
+                "result_end_block": { "validator_updates": [] },
+            },
+        }));
+
+        let event: Event = v0_37::DeEvent::from_string(&json).unwrap().into();
+        assert_eq!(event.event_type(), Some(EventType::NewBlockHeader));
+        let EventData::NewBlockHeader {
+            header,
+            num_txs,
+            result_begin_block,
+            result_end_block,
+        } = event.data
+        else {
+            panic!("unexpected event data: {:?}", event.data);
+        };
+        assert_eq!(header.height.value(), 56);
+        assert_eq!(num_txs, Some(2));
+        assert!(result_begin_block.unwrap().events.is_empty());
+        assert!(result_end_block.unwrap().validator_updates.is_empty());
+
+        let event: Event = v0_34::DeEvent::from_string(&json).unwrap().into();
+        assert!(matches!(
+            event.data,
+            EventData::NewBlockHeader {
+                num_txs: Some(2),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn new_block_header() {
+        let json = event_json(serde_json::json!({
+            "type": "tendermint/event/NewBlockHeader",
+            "value": { "header": header_json() },
+        }));
+
+        let event: Event = v0_38::DeEvent::from_string(&json).unwrap().into();
+        let EventData::NewBlockHeader {
+            header,
+            num_txs,
+            result_begin_block,
+            result_end_block,
+        } = event.data.clone()
+        else {
+            panic!("unexpected event data: {:?}", event.data);
+        };
+        assert_eq!(header.height.value(), 56);
+        assert!(num_txs.is_none());
+        assert!(result_begin_block.is_none());
+        assert!(result_end_block.is_none());
+
+        let ser_event: v0_38::SerEvent = event.clone().into();
+        let reparsed: Event =
+            serde_json::from_value::<v0_38::DeEvent>(serde_json::to_value(&ser_event).unwrap())
+                .unwrap()
+                .into();
+        assert_eq!(reparsed, event);
+    }
+
+    #[test]
+    fn validator_set_updates() {
+        let json = event_json(serde_json::json!({
+            "type": "tendermint/event/ValidatorSetUpdates",
+            "value": {
+                "validator_updates": [{
+                    "address": "DD8A65495B6240145764A74E78CF203D51510371",
+                    "pub_key": {
+                        "type": "tendermint/PubKeyEd25519",
+                        "value": "OlNPUmVQ3qZkD/LgCeOd0/1L6bOdfwVsH9r8ynHxtpw=",
+                    },
+                    "voting_power": "10",
+                    "proposer_priority": "0",
+                }],
+            },
+        }));
+
+        let event: Event = v0_37::DeEvent::from_string(&json).unwrap().into();
+        assert_eq!(event.event_type(), Some(EventType::ValidatorSetUpdates));
+        let EventData::ValidatorSetUpdates { validator_updates } = event.data else {
+            panic!("unexpected event data: {:?}", event.data);
+        };
+        assert_eq!(validator_updates.len(), 1);
+        assert_eq!(validator_updates[0].power(), 10);
+    }
+
+    #[test]
+    fn vote() {
+        let json = event_json(serde_json::json!({
+            "type": "tendermint/event/Vote",
+            "value": {
+                "Vote": {
+                    "type": 2,
+                    "height": "56",
+                    "round": 0,
+                    "block_id": {
+                        "hash": "60C0B6596F4F7C591356C5137A6B703C57CF04D9BF9A01845EEE9E1AAF591A4C",
+                        "parts": {
+                            "total": 1,
+                            "hash": "A80BE8FE1D9C786A8C3ED5DA108D86041B139C381A71F88BA2AD0B4976B2ADAE",
+                        },
+                    },
+                    "timestamp": "2023-02-27T07:13:31.980620894Z",
+                    "validator_address": "DD8A65495B6240145764A74E78CF203D51510371",
+                    "validator_index": 0,
+                    "signature": "R2nQOuNfTfKvAZa7S1xnJnMJ6wMT8WaMzN+0c8E2d1gDzAVCzF09mnuF6cPOVz2TeHEFJxNM8GbUF5KZoBkDDA==",
+                },
+            },
+        }));
+
+        let event: Event = v0_38::DeEvent::from_string(&json).unwrap().into();
+        assert_eq!(event.event_type(), Some(EventType::Vote));
+        let EventData::Vote { vote } = event.data else {
+            panic!("unexpected event data: {:?}", event.data);
+        };
+        assert_eq!(vote.height.value(), 56);
+        assert!(vote.is_precommit());
+    }
+
+    #[test]
+    fn indexed_attributes() {
+        let json = event_json(serde_json::json!({
+            "type": "tendermint/event/ValidatorSetUpdates",
+            "value": { "validator_updates": [] },
+        }));
+        let event: Event = v0_38::DeEvent::from_string(&json).unwrap().into();
+        assert_eq!(
+            event.attribute_values("transfer.recipient"),
+            ["alice", "bob"]
+        );
+        assert_eq!(
+            event.attribute_value("tm.event"),
+            Some("ValidatorSetUpdates")
+        );
+        assert!(event.attribute_values("transfer.sender").is_empty());
+        assert_eq!(event.attribute_value("transfer.sender"), None);
+
+        let tx_result = TxResult {
+            log: None,
+            gas_wanted: None,
+            gas_used: None,
+            events: vec![
+                abci::Event::new(
+                    "transfer",
+                    [
+                        ("recipient", "alice").index(),
+                        ("sender", "carol").no_index(),
+                    ],
+                ),
+                abci::Event::new("transfer", [("recipient", "bob").index()]),
+            ],
+        };
+        assert_eq!(
+            tx_result
+                .indexed_attribute_values("transfer.recipient")
+                .collect::<Vec<_>>(),
+            ["alice", "bob"]
+        );
+        assert_eq!(
+            tx_result
+                .indexed_attribute_values("transfer.sender")
+                .count(),
+            0
+        );
+    }
+}
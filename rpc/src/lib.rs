@@ -14,6 +14,24 @@
 //! * `websocket-client` - Provides [`WebSocketClient`], which provides full client functionality,
 //!   including general RPC functionality as well as [`event::Event`] subscription functionality.
 //!   Can be used over secure (`wss://`) and unsecure (`ws://`) connections.
+//! * `blocking` - Provides `client::blocking::HttpClient`, a **synchronous** facade over
+//!   [`HttpClient`] for applications which do not run an async runtime.
+//! * `grpc` - Provides `GrpcClient`, which broadcasts transactions via the **gRPC BroadcastAPI**
+//!   of nodes which serve it.
+//! * `metrics` - Provides `client::MeteredClient`, which records **Prometheus metrics** of the
+//!   requests made through any client.
+//!
+//! ### Web browsers
+//!
+//! On the `wasm32-unknown-unknown` target, the `http-client` and
+//! `websocket-client` features provide clients built on the `fetch` and
+//! `WebSocket` APIs of web browsers instead, e.g. for dashboards and wallets
+//! talking to nodes directly. They support the options that browsers let
+//! scripts control, so that proxies, Unix domain sockets, compression and
+//! recording are not available, and neither are the `blocking` and `grpc`
+//! features nor the client middleware relying on the Tokio timer (retries,
+//! failover, rate limits and the like). The driver of a [`WebSocketClient`]
+//! must be run with `wasm_bindgen_futures::spawn_local`.
 //!
 //! ### Mock Clients
 //!
@@ -22,6 +40,12 @@
 //! [`MockClient`], which implements both [`Client`] and [`SubscriptionClient`]
 //! traits.
 //!
+//! ## Server
+//!
+//! The `server` feature provides `server::Server`, which serves the RPC over
+//! HTTP and WebSocket from user-provided handlers, to test clients against an
+//! in-process fake node.
+//!
 //! [Tendermint RPC]: https://docs.tendermint.com/v0.34/rpc/
 //! [`/subscribe` endpoint]: https://docs.tendermint.com/v0.34/rpc/#/Websocket/subscribe
 
@@ -34,15 +58,19 @@ mod prelude;
 
 pub mod client;
 
+#[cfg(all(feature = "grpc", not(target_arch = "wasm32")))]
+pub use client::GrpcClient;
+#[cfg(all(feature = "websocket-client", not(target_arch = "wasm32")))]
+pub use client::WebSocketConfig;
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
 pub use client::{
-    Client, MockClient, MockRequestMatcher, MockRequestMethodMatcher, Subscription,
-    SubscriptionClient,
+    Client, MockCall, MockClient, MockRequestMatcher, MockRequestMethodMatcher, MockRequestScript,
+    Subscription, SubscriptionClient,
 };
 #[cfg(feature = "http-client")]
 pub use client::{HttpClient, HttpClientUrl};
 #[cfg(feature = "websocket-client")]
-pub use client::{WebSocketClient, WebSocketClientDriver, WebSocketClientUrl, WebSocketConfig};
+pub use client::{WebSocketClient, WebSocketClientDriver, WebSocketClientUrl};
 
 pub mod dialect;
 pub mod endpoint;
@@ -57,7 +85,11 @@ pub mod request;
 pub mod response;
 pub mod response_error;
 mod rpc_url;
+pub mod sample;
 pub mod serializers;
+#[cfg(all(feature = "server", not(target_arch = "wasm32")))]
+pub mod server;
+mod strict;
 mod utils;
 mod version;
 
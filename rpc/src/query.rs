@@ -216,8 +216,14 @@ peg::parser! {
         // At least some whitespace.
         rule __() = quiet!{[' ']+}
 
-        rule string() -> &'input str
-            = "'" s:$([^'\'']*) "'" { s }
+        // Backslashes and single quotes are escaped with a backslash, as when
+        // formatting queries. Other backslashes are taken literally.
+        rule string_char() -> &'input str
+            = "\\" c:$(['\\' | '\'']) { c }
+            / c:$([^'\'']) { c }
+
+        rule string() -> String
+            = "'" s:string_char()* "'" { s.concat() }
 
         rule unsigned() -> u64
             = s:$(['0'..='9']+) {?
@@ -265,7 +271,7 @@ peg::parser! {
             }
 
         rule string_op() -> Operand
-            = s:string() { Operand::String(s.to_owned()) }
+            = s:string() { Operand::String(s) }
 
         rule unsigned_op() -> Operand
             = u:unsigned() { Operand::Unsigned(u) }
@@ -290,8 +296,11 @@ peg::parser! {
         rule float_op() -> Operand
             = f:float() { Operand::Float(f) }
 
+        // As in the node's grammar, tags can contain any character but
+        // whitespace, quotes, parentheses, backslashes and comparison
+        // operators, e.g. `wasm-transfer.amount` or `message.module`.
         rule tag() -> &'input str
-            = $(['a'..='z' | 'A'..='Z'] ['a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '.']*)
+            = $([^' ' | '\t' | '\n' | '\r' | '\\' | '(' | ')' | '"' | '\'' | '=' | '<' | '>']+)
 
         rule operand() -> Operand
             = datetime_op() / date_op() / string_op() / float_op() / signed_op() / unsigned_op()
@@ -312,13 +321,13 @@ peg::parser! {
             = t:tag() _ ">" _ op:operand() { Condition::gt(t.to_owned(), op) }
 
         rule contains() -> Condition
-            = t:tag() __ "CONTAINS" __ op:string() { Condition::contains(t.to_owned(), op.to_owned()) }
+            = t:tag() __ "CONTAINS" __ op:string() { Condition::contains(t.to_owned(), op) }
 
         rule exists() -> Condition
             = t:tag() __ "EXISTS" { Condition::exists(t.to_owned()) }
 
         rule event_type() -> Term
-            = "tm.event" _ "=" _ "'" et:$("NewBlock" / "Tx") "'" {
+            = "tm.event" _ "=" _ "'" et:$("NewBlockHeader" / "NewBlock" / "Tx" / "ValidatorSetUpdates" / "Vote") "'" {
                 Term::EventType(EventType::from_str(et).unwrap())
             }
 
@@ -396,14 +405,20 @@ where
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EventType {
     NewBlock,
+    NewBlockHeader,
     Tx,
+    ValidatorSetUpdates,
+    Vote,
 }
 
 impl fmt::Display for EventType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             EventType::NewBlock => write!(f, "NewBlock"),
+            EventType::NewBlockHeader => write!(f, "NewBlockHeader"),
             EventType::Tx => write!(f, "Tx"),
+            EventType::ValidatorSetUpdates => write!(f, "ValidatorSetUpdates"),
+            EventType::Vote => write!(f, "Vote"),
         }
     }
 }
@@ -414,7 +429,10 @@ impl FromStr for EventType {
     fn from_str(s: &str) -> Result<Self, Error> {
         match s {
             "NewBlock" => Ok(Self::NewBlock),
+            "NewBlockHeader" => Ok(Self::NewBlockHeader),
             "Tx" => Ok(Self::Tx),
+            "ValidatorSetUpdates" => Ok(Self::ValidatorSetUpdates),
+            "Vote" => Ok(Self::Vote),
             invalid => Err(Error::unrecognized_event_type(invalid.to_string())),
         }
     }
@@ -769,6 +787,12 @@ mod test {
         let query = Query::from_str("tm.event='NewBlock'").unwrap();
         assert_eq!(query.event_type, Some(EventType::NewBlock));
         assert!(query.conditions.is_empty());
+        let query = Query::from_str("tm.event='NewBlockHeader'").unwrap();
+        assert_eq!(query.event_type, Some(EventType::NewBlockHeader));
+        let query = Query::from_str("tm.event='ValidatorSetUpdates'").unwrap();
+        assert_eq!(query.event_type, Some(EventType::ValidatorSetUpdates));
+        let query = Query::from_str("tm.event='Vote'").unwrap();
+        assert_eq!(query.event_type, Some(EventType::Vote));
 
         // One event type, with whitespace
         let query = Query::from_str("tm.event =  'NewBlock'").unwrap();
@@ -803,6 +827,35 @@ mod test {
         );
     }
 
+    #[test]
+    fn query_escaped_string_parsing() {
+        let query = Query::from_str(r"memo = '\'quoted\' \\ path\to'").unwrap();
+        assert_eq!(
+            query.conditions,
+            vec![Condition::eq(
+                "memo".to_owned(),
+                Operand::String(r"'quoted' \ path\to".to_owned()),
+            )]
+        );
+
+        // Formatted queries parse back to the same query.
+        let query = Query::from(EventType::Tx)
+            .and_eq("memo", r"it's a \ test")
+            .and_contains("note", "'");
+        assert_eq!(Query::from_str(&query.to_string()).unwrap(), query);
+    }
+
+    #[test]
+    fn query_tag_parsing() {
+        let query = Query::from_str("wasm-transfer.amount >= 5 AND ibc/denom EXISTS").unwrap();
+        assert_eq!(
+            query,
+            Query::gte("wasm-transfer.amount", 5_u64).and_exists("ibc/denom")
+        );
+
+        assert!(Query::from_str("tx'height = 5").is_err());
+    }
+
     #[test]
     fn query_unsigned_term_parsing() {
         let query = Query::from_str("tm.event = 'Tx' AND tx.height = 10").unwrap();
@@ -3,6 +3,7 @@
 use core::fmt::Debug;
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tendermint::block::Height;
 
 use super::{Id, Method, Version};
 use crate::dialect::{Dialect, LatestDialect};
@@ -11,8 +12,22 @@ use crate::{prelude::*, Error};
 /// Serialization for JSON-RPC requests
 pub trait RequestMessage: DeserializeOwned + Serialize + Sized {
     /// Request method
+    ///
+    /// Requests to methods not modelled by this crate, such as the custom
+    /// methods of forked nodes, return [`Method::Custom`] and give the name of
+    /// the method with [`RequestMessage::method_name`].
     fn method(&self) -> Method;
 
+    /// Name of the request method, as sent in the JSON-RPC request
+    fn method_name(&self) -> &str {
+        self.method().as_str()
+    }
+
+    /// Height of the block the request is about, if it is about one
+    fn height(&self) -> Option<Height> {
+        None
+    }
+
     /// Serialize this request as JSON
     fn into_json(self) -> String {
         Wrapper::new(self).into_json()
@@ -54,7 +69,7 @@ pub struct Wrapper<R> {
     id: Id,
 
     /// Request method
-    method: Method,
+    method: String,
 
     /// Request parameters (i.e. request object)
     params: R,
@@ -77,7 +92,7 @@ where
         Self {
             jsonrpc: Version::current(),
             id,
-            method: request.method(),
+            method: request.method_name().to_string(),
             params: request,
         }
     }
@@ -94,3 +109,63 @@ where
         serde_json::to_string_pretty(&self).unwrap()
     }
 }
+
+/// A request to a method which is not modelled by this crate, such as a
+/// custom method of a forked node, with parameters given as JSON.
+///
+/// Its response is the `result` of the JSON-RPC response, as JSON.
+///
+/// ```
+/// use tendermint_rpc::{request::RawRequest, request::RequestMessage, Method};
+///
+/// let request = RawRequest::new("validator_stats", serde_json::json!({ "window": "100" }));
+/// assert_eq!(request.method(), Method::Custom);
+/// assert_eq!(request.method_name(), "validator_stats");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RawRequest {
+    #[serde(skip)]
+    method: String,
+    params: serde_json::Value,
+}
+
+impl RawRequest {
+    /// A request to the given method with the given parameters.
+    pub fn new(method: impl Into<String>, params: serde_json::Value) -> Self {
+        Self {
+            method: method.into(),
+            params,
+        }
+    }
+
+    /// The parameters of the request.
+    pub fn params(&self) -> &serde_json::Value {
+        &self.params
+    }
+}
+
+impl RequestMessage for RawRequest {
+    fn method(&self) -> Method {
+        Method::Custom
+    }
+
+    fn method_name(&self) -> &str {
+        &self.method
+    }
+
+    // The name of the method is only found in the wrapper.
+    fn from_string(s: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let wrapper: Wrapper<serde_json::Value> =
+            serde_json::from_slice(s.as_ref()).map_err(Error::serde)?;
+        Ok(Self::new(wrapper.method, wrapper.params))
+    }
+}
+
+impl<S: Dialect> Request<S> for RawRequest {
+    type Response = serde_json::Value;
+}
+
+impl<S: Dialect> SimpleRequest<S> for RawRequest {
+    type Output = serde_json::Value;
+}
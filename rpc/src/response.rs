@@ -4,7 +4,7 @@ use std::io::Read;
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::{response_error::ResponseError, Error, Id, Version};
+use crate::{response_error::ResponseError, strict, Error, Id, Version};
 
 /// JSON-RPC responses
 pub trait Response: DeserializeOwned + Sized {
@@ -15,6 +15,27 @@ pub trait Response: DeserializeOwned + Sized {
         wrapper.into_result()
     }
 
+    /// Parse a JSON-RPC response from a JSON string, failing on any fields
+    /// in the response that are not known to this crate.
+    ///
+    /// This is useful to test this crate's conformance with a particular
+    /// version of Tendermint, but not in production, where newer nodes may
+    /// legitimately include additional fields in their responses.
+    fn from_string_strict(response: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let wrapper: Wrapper<Self> = strict::from_slice(response.as_ref()).map_err(Error::serde)?;
+        wrapper.into_result()
+    }
+
+    /// Parse a JSON-RPC response from a JSON string, in strict mode
+    /// (see [`Response::from_string_strict`]) if `strict` is set.
+    fn from_string_with_mode(response: impl AsRef<[u8]>, strict: bool) -> Result<Self, Error> {
+        if strict {
+            Self::from_string_strict(response)
+        } else {
+            Self::from_string(response)
+        }
+    }
+
     /// Parse a JSON-RPC response from an `io::Reader`
     fn from_reader(reader: impl Read) -> Result<Self, Error> {
         let wrapper: Wrapper<Self> = serde_json::from_reader(reader).map_err(Error::serde)?;
@@ -22,6 +43,11 @@ pub trait Response: DeserializeOwned + Sized {
     }
 }
 
+/// The raw `result` of a response, e.g. to a [`RawRequest`].
+///
+/// [`RawRequest`]: crate::request::RawRequest
+impl Response for serde_json::Value {}
+
 /// JSON-RPC response wrapper (i.e. message envelope)
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Wrapper<R> {
@@ -52,7 +78,7 @@ impl<R> Wrapper<R> {
 
     /// Convert this wrapper into the underlying error, if any
     pub fn into_error(self) -> Option<Error> {
-        self.error.map(Error::response)
+        self.error.map(Error::from_response)
     }
 
     /// Convert this wrapper into a result type
@@ -61,7 +87,7 @@ impl<R> Wrapper<R> {
         self.version().ensure_supported()?;
 
         if let Some(e) = self.error {
-            Err(Error::response(e))
+            Err(Error::from_response(e))
         } else if let Some(result) = self.result {
             Ok(result)
         } else {
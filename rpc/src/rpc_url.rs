@@ -13,6 +13,7 @@ pub enum Scheme {
     Https,
     WebSocket,
     SecureWebSocket,
+    Unix,
 }
 
 impl fmt::Display for Scheme {
@@ -22,6 +23,7 @@ impl fmt::Display for Scheme {
             Scheme::Https => write!(f, "https"),
             Scheme::WebSocket => write!(f, "ws"),
             Scheme::SecureWebSocket => write!(f, "wss"),
+            Scheme::Unix => write!(f, "unix"),
         }
     }
 }
@@ -35,6 +37,7 @@ impl FromStr for Scheme {
             "https" => Scheme::Https,
             "ws" => Scheme::WebSocket,
             "wss" => Scheme::SecureWebSocket,
+            "unix" => Scheme::Unix,
             _ => return Err(Error::unsupported_scheme(s.to_string())),
         })
     }
@@ -45,6 +48,10 @@ impl FromStr for Scheme {
 ///
 /// Re-implements relevant parts of [`url::Url`]'s interface with convenience
 /// mechanisms for transformation to/from other types.
+///
+/// URLs with the `unix` scheme (e.g. `unix:///tmp/node.sock`) refer to a
+/// Unix domain socket, whose filesystem path is given by [`Url::path`]. Such
+/// URLs have no host and their port is always 0.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Url {
     inner: url::Url,
@@ -61,6 +68,20 @@ impl FromStr for Url {
 
         let scheme: Scheme = inner.scheme().parse()?;
 
+        if scheme == Scheme::Unix {
+            if inner.path().is_empty() {
+                return Err(Error::invalid_params(format!(
+                    "URL is missing its socket path: {s}"
+                )));
+            }
+            return Ok(Self {
+                inner,
+                scheme,
+                host: String::new(),
+                port: 0,
+            });
+        }
+
         let host = inner
             .host_str()
             .ok_or_else(|| Error::invalid_params(format!("URL is missing its host: {s}")))?
@@ -88,6 +109,7 @@ impl Url {
             Scheme::Https => true,
             Scheme::WebSocket => false,
             Scheme::SecureWebSocket => true,
+            Scheme::Unix => false,
         }
     }
 
@@ -127,6 +149,16 @@ impl Url {
     pub fn path(&self) -> &str {
         self.inner.path()
     }
+
+    /// This URL without its username and password, which the APIs of web
+    /// browsers reject URLs with.
+    #[cfg(all(target_arch = "wasm32", feature = "http-client"))]
+    pub(crate) fn without_credentials(&self) -> String {
+        let mut inner = self.inner.clone();
+        let _ = inner.set_username("");
+        let _ = inner.set_password(None);
+        inner.to_string()
+    }
 }
 
 impl fmt::Display for Url {
@@ -343,6 +375,17 @@ mod test {
                     username: Some("foo".to_string()),
                     password: Some("bar".to_string()),
                 }
+            ),
+            (
+                "unix:///tmp/node.sock".to_owned(),
+                ExpectedUrl {
+                    scheme: Scheme::Unix,
+                    host: "".to_string(),
+                    port: 0,
+                    path: "/tmp/node.sock".to_string(),
+                    username: None,
+                    password: None,
+                }
             )
         ];
     }
@@ -0,0 +1,199 @@
+//! Per-block samples for analytics, e.g. of block times and sizes over a
+//! range of heights.
+//!
+//! Samples are taken from blocks or headers obtained by any means, which
+//! this module does not verify. For samples of verified blocks only, use
+//! `verified_block_samples` of the `tendermint-light-client` crate, which
+//! checks each block against a header verified by a light client.
+
+use core::{borrow::Borrow, time::Duration};
+
+use serde::{Serialize, Serializer};
+use tendermint::{
+    account,
+    block::{Block, Header, Height},
+    Time,
+};
+use tendermint_proto::{v0_37::types::Block as RawBlock, Protobuf};
+
+use crate::prelude::*;
+
+/// The columns of the rows produced by [`BlockSample::to_csv_row`], in
+/// order.
+pub const CSV_HEADER: &str = "height,time,interval_secs,proposer_address,tx_count,size";
+
+/// A sample of a block.
+///
+/// Serializes to a flat record of the columns listed in [`CSV_HEADER`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BlockSample {
+    /// Height of the block
+    pub height: Height,
+
+    /// Time of the block
+    pub time: Time,
+
+    /// Time elapsed since the previously sampled block, unless this is the
+    /// first sample or the block is earlier than the previous one.
+    #[serde(rename = "interval_secs", serialize_with = "serialize_secs")]
+    pub interval: Option<Duration>,
+
+    /// Address of the proposer of the block
+    pub proposer_address: account::Id,
+
+    /// Number of transactions in the block, if sampled from a block
+    pub tx_count: Option<usize>,
+
+    /// Size of the protobuf encoding of the block in bytes, as limited by
+    /// the `max_bytes` consensus parameter, if sampled from a block
+    pub size: Option<usize>,
+}
+
+impl BlockSample {
+    /// Render the sample as a row of comma-separated values, of the columns
+    /// listed in [`CSV_HEADER`]. Missing values are left empty.
+    pub fn to_csv_row(&self) -> String {
+        fn column<T: ToString>(value: Option<T>) -> String {
+            value.map(|v| v.to_string()).unwrap_or_default()
+        }
+
+        format!(
+            "{},{},{},{},{},{}",
+            self.height,
+            self.time.to_rfc3339(),
+            column(self.interval.map(|i| i.as_secs_f64())),
+            self.proposer_address,
+            column(self.tx_count),
+            column(self.size),
+        )
+    }
+}
+
+fn serialize_secs<S: Serializer>(interval: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+    interval.map(|i| i.as_secs_f64()).serialize(s)
+}
+
+/// Takes samples of blocks or headers in turn, tracking the time of the
+/// previously sampled one.
+#[derive(Clone, Debug, Default)]
+pub struct Sampler {
+    previous: Option<Time>,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sample the given block.
+    pub fn sample_block(&mut self, block: &Block) -> BlockSample {
+        BlockSample {
+            tx_count: Some(block.data.len()),
+            size: Some(Protobuf::<RawBlock>::encoded_len(block.clone())),
+            ..self.sample_header(&block.header)
+        }
+    }
+
+    /// Sample the given header, for which the transactions and size of the
+    /// block are unknown.
+    pub fn sample_header(&mut self, header: &Header) -> BlockSample {
+        let interval = match self.previous {
+            Some(previous) => header.time.duration_since(previous).ok(),
+            None => None,
+        };
+        self.previous = Some(header.time);
+
+        BlockSample {
+            height: header.height,
+            time: header.time,
+            interval,
+            proposer_address: header.proposer_address,
+            tx_count: None,
+            size: None,
+        }
+    }
+}
+
+/// Sample each of the given blocks, in the given order.
+pub fn block_samples<I>(blocks: I) -> impl Iterator<Item = BlockSample>
+where
+    I: IntoIterator,
+    I::Item: Borrow<Block>,
+{
+    let mut sampler = Sampler::new();
+    blocks
+        .into_iter()
+        .map(move |block| sampler.sample_block(block.borrow()))
+}
+
+/// Sample each of the given headers, in the given order.
+pub fn header_samples<I>(headers: I) -> impl Iterator<Item = BlockSample>
+where
+    I: IntoIterator,
+    I::Item: Borrow<Header>,
+{
+    let mut sampler = Sampler::new();
+    headers
+        .into_iter()
+        .map(move |header| sampler.sample_header(header.borrow()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{endpoint::block, Response};
+
+    fn fixture(height: u64) -> Block {
+        let json = match height {
+            1 => include_str!("../tests/kvstore_fixtures/v0_37/incoming/block_at_height_1.json"),
+            10 => include_str!("../tests/kvstore_fixtures/v0_37/incoming/block_at_height_10.json"),
+            _ => unreachable!(),
+        };
+        block::Response::from_string(json).unwrap().block
+    }
+
+    #[test]
+    fn samples_blocks() {
+        let mut later = fixture(10);
+        later.data.push(b"key=value".to_vec());
+        let blocks = [fixture(1), later];
+
+        let samples: Vec<_> = block_samples(&blocks).collect();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].interval, None);
+        assert_eq!(samples[0].tx_count, Some(0));
+        assert_eq!(samples[1].height.value(), 10);
+        assert_eq!(samples[1].interval, Some(Duration::new(4, 748_232_297)));
+        assert_eq!(samples[1].tx_count, Some(1));
+        assert!(samples[1].size > samples[0].size);
+
+        assert_eq!(
+            samples[1].to_csv_row(),
+            "10,2023-02-27T07:13:08.140032018Z,4.748232297,\
+             DD8A65495B6240145764A74E78CF203D51510371,1,"
+                .to_owned()
+                + &samples[1].size.unwrap().to_string()
+        );
+
+        let mut columns: Vec<_> = CSV_HEADER.split(',').collect();
+        columns.sort_unstable();
+        let record = serde_json::to_value(&samples[1]).unwrap();
+        let fields: Vec<_> = record.as_object().unwrap().keys().collect();
+        assert_eq!(columns, fields);
+    }
+
+    #[test]
+    fn samples_headers() {
+        let headers = [fixture(10).header, fixture(1).header];
+
+        let samples: Vec<_> = header_samples(&headers).collect();
+        // The second block is earlier than the first.
+        assert_eq!(samples[1].interval, None);
+        assert_eq!(samples[1].tx_count, None);
+        assert_eq!(samples[1].size, None);
+        assert_eq!(
+            samples[1].to_csv_row(),
+            "1,2023-02-27T07:13:03.391799721Z,,DD8A65495B6240145764A74E78CF203D51510371,,"
+        );
+    }
+}
@@ -0,0 +1,529 @@
+//! An embeddable JSON-RPC server, serving the Tendermint RPC from
+//! user-provided handlers.
+//!
+//! This lets the tests of clients, and of light clients, run against an
+//! in-process fake node instead of a node running in a container:
+//!
+//! ```no_run
+//! use tendermint::abci::response::Info;
+//! use tendermint_rpc::{endpoint::abci_info, server::Server, Method};
+//!
+//! # async fn example(info: Info) -> Result<(), tendermint_rpc::Error> {
+//! let server = Server::new()
+//!     .on(Method::AbciInfo, move |_: abci_info::Request| {
+//!         Ok(abci_info::Response {
+//!             response: info.clone(),
+//!         })
+//!     })
+//!     .bind(([127, 0, 0, 1], 0).into())?;
+//!
+//! // Point a client to `server.http_url()` or `server.websocket_url()`...
+//!
+//! server.shutdown().await
+//! # }
+//! ```
+//!
+//! JSON-RPC requests are served over HTTP `POST` requests to any path, and
+//! over WebSocket connections to `/websocket`. Requests to methods without a
+//! handler fail with a "method not found" error.
+//!
+//! The `subscribe`, `unsubscribe` and `unsubscribe_all` methods are handled
+//! by the server itself over WebSocket connections, and the events published
+//! with [`ServerHandle::publish`] are sent to the subscriptions whose query
+//! is the `query` of the event.
+
+use alloc::{collections::BTreeMap as HashMap, sync::Arc};
+use std::net::SocketAddr;
+
+use async_tungstenite::{
+    tokio::TokioAdapter,
+    tungstenite::{handshake::derive_accept_key, protocol::Role, Message},
+    WebSocketStream,
+};
+use futures::{SinkExt, StreamExt};
+use hyper::{
+    header,
+    service::{make_service_fn, service_fn},
+    upgrade::Upgraded,
+    Body, StatusCode,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::{
+    sync::{broadcast, oneshot},
+    task::JoinHandle,
+};
+
+use crate::{
+    client::CompatMode,
+    event::{self, Event, EventData},
+    prelude::*,
+    response::Wrapper,
+    response_error::{Code, ResponseError},
+    Error, Id, Method,
+};
+
+/// The number of published events kept for the WebSocket connections which
+/// lag behind. Subscribers lagging further behind miss events.
+const EVENT_BUFFER_LEN: usize = 1024;
+
+type Handler =
+    Arc<dyn Fn(serde_json::Value) -> Result<serde_json::Value, ResponseError> + Send + Sync>;
+
+/// A JSON-RPC server serving the Tendermint RPC from user-provided handlers.
+///
+/// See the [module documentation](self).
+#[derive(Clone, Default)]
+pub struct Server {
+    handlers: HashMap<String, Handler>,
+    compat: CompatMode,
+}
+
+impl Server {
+    /// A server without any handler, speaking the latest supported protocol.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the version of the protocol in which the published events are
+    /// encoded.
+    ///
+    /// The responses of the handlers are sent as they serialize.
+    pub fn with_compat_mode(mut self, compat: CompatMode) -> Self {
+        self.compat = compat;
+        self
+    }
+
+    /// Serve the requests to the given method with the given handler.
+    ///
+    /// The parameters of the requests are decoded as `R`, failing the request
+    /// with an "invalid params" error if they don't decode, and the output of
+    /// the handler is sent as the result of the response.
+    pub fn on<R, O, F>(self, method: Method, handler: F) -> Self
+    where
+        R: DeserializeOwned,
+        O: Serialize,
+        F: Fn(R) -> Result<O, ResponseError> + Send + Sync + 'static,
+    {
+        self.on_raw(method.as_str(), move |params| {
+            // Requests without parameters may be sent with null or empty
+            // parameters, whichever they decode from.
+            let request = R::deserialize(&params)
+                .or_else(|e| match params {
+                    serde_json::Value::Null => R::deserialize(&serde_json::json!({})),
+                    _ => Err(e),
+                })
+                .map_err(|e| ResponseError::invalid_params(&e.to_string()))?;
+            let output = handler(request)?;
+            serde_json::to_value(output).map_err(|e| ResponseError::server_error(e.to_string()))
+        })
+    }
+
+    /// Serve the requests to the given method, which may not be modelled by
+    /// this crate, with the given handler of their parameters as JSON.
+    pub fn on_raw<F>(mut self, method: impl Into<String>, handler: F) -> Self
+    where
+        F: Fn(serde_json::Value) -> Result<serde_json::Value, ResponseError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.handlers.insert(method.into(), Arc::new(handler));
+        self
+    }
+
+    /// Respond to all the requests to the given method with the given
+    /// result.
+    pub fn with_response<O>(self, method: Method, result: O) -> Self
+    where
+        O: Serialize,
+    {
+        let result = serde_json::to_value(result).unwrap();
+        self.on_raw(method.as_str(), move |_| Ok(result.clone()))
+    }
+
+    /// Start serving on the given address, in the background.
+    ///
+    /// Binding to port 0 picks a free port, given by
+    /// [`ServerHandle::local_addr`].
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn bind(self, addr: SocketAddr) -> Result<ServerHandle, Error> {
+        let (event_tx, _) = broadcast::channel(EVENT_BUFFER_LEN);
+        let state = Arc::new(State {
+            handlers: self.handlers,
+            compat: self.compat,
+            event_tx: event_tx.clone(),
+        });
+
+        let make_service = make_service_fn(move |_| {
+            let state = state.clone();
+            async move {
+                Ok::<_, core::convert::Infallible>(service_fn(move |request| {
+                    let state = state.clone();
+                    async move { Ok::<_, core::convert::Infallible>(state.serve(request).await) }
+                }))
+            }
+        });
+        let server = hyper::Server::try_bind(&addr)
+            .map_err(Error::hyper)?
+            .serve(make_service);
+        let local_addr = server.local_addr();
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let server = server.with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        });
+        let server_hdl = tokio::spawn(async move { server.await.map_err(Error::hyper) });
+
+        Ok(ServerHandle {
+            local_addr,
+            event_tx,
+            shutdown_tx,
+            server_hdl,
+        })
+    }
+}
+
+/// A handle to a running [`Server`].
+pub struct ServerHandle {
+    local_addr: SocketAddr,
+    event_tx: broadcast::Sender<Event>,
+    shutdown_tx: oneshot::Sender<()>,
+    server_hdl: JoinHandle<Result<(), Error>>,
+}
+
+impl ServerHandle {
+    /// The address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The URL to serve JSON-RPC requests over HTTP from.
+    pub fn http_url(&self) -> String {
+        format!("http://{}", self.local_addr)
+    }
+
+    /// The URL to serve JSON-RPC requests and subscriptions over WebSocket
+    /// from.
+    pub fn websocket_url(&self) -> String {
+        format!("ws://{}/websocket", self.local_addr)
+    }
+
+    /// Send the given event to the subscriptions to its query.
+    pub fn publish(&self, event: Event) {
+        // Fails if there is no WebSocket connection, in which case there is
+        // no subscription either.
+        let _ = self.event_tx.send(event);
+    }
+
+    /// Stop serving, closing the WebSocket connections.
+    pub async fn shutdown(self) -> Result<(), Error> {
+        // Dropping the sender of the events closes the WebSocket connections.
+        drop(self.event_tx);
+        let _ = self.shutdown_tx.send(());
+        self.server_hdl.await.map_err(Error::join)?
+    }
+}
+
+// The envelope of the incoming requests, whose parameters are decoded by the
+// handlers.
+#[derive(Deserialize)]
+struct IncomingRequest {
+    #[serde(default)]
+    id: Option<Id>,
+    method: String,
+    #[serde(default)]
+    params: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionParams {
+    query: String,
+}
+
+struct State {
+    handlers: HashMap<String, Handler>,
+    compat: CompatMode,
+    event_tx: broadcast::Sender<Event>,
+}
+
+impl State {
+    async fn serve(self: Arc<Self>, request: hyper::Request<Body>) -> hyper::Response<Body> {
+        if is_websocket_upgrade(&request) {
+            if request.uri().path() == "/websocket" {
+                return self.upgrade(request);
+            }
+            return status_response(StatusCode::NOT_FOUND);
+        }
+        if request.method() != hyper::Method::POST {
+            return status_response(StatusCode::METHOD_NOT_ALLOWED);
+        }
+
+        let body = match hyper::body::to_bytes(request.into_body()).await {
+            Ok(body) => body,
+            Err(_) => return status_response(StatusCode::BAD_REQUEST),
+        };
+        let response = match serde_json::from_slice::<IncomingRequest>(&body) {
+            Ok(request) => {
+                let id = request.id.clone().unwrap_or(Id::None);
+                let result = if is_subscription_method(&request.method) {
+                    Err(ResponseError::new(
+                        Code::InvalidRequest,
+                        Some("subscriptions are only served over WebSocket".to_string()),
+                    ))
+                } else {
+                    self.call(request)
+                };
+                response_json(id, result)
+            },
+            Err(e) => response_json(Id::None, Err(ResponseError::parse_error(e))),
+        };
+
+        hyper::Response::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(response))
+            .unwrap()
+    }
+
+    fn call(&self, request: IncomingRequest) -> Result<serde_json::Value, ResponseError> {
+        let handler = self
+            .handlers
+            .get(&request.method)
+            .ok_or_else(|| ResponseError::method_not_found(&request.method))?;
+        handler(request.params)
+    }
+
+    fn upgrade(self: Arc<Self>, mut request: hyper::Request<Body>) -> hyper::Response<Body> {
+        let accept_key = match request.headers().get(header::SEC_WEBSOCKET_KEY) {
+            Some(key) => derive_accept_key(key.as_bytes()),
+            None => return status_response(StatusCode::BAD_REQUEST),
+        };
+        let event_rx = self.event_tx.subscribe();
+        let upgrade = hyper::upgrade::on(&mut request);
+        tokio::spawn(async move {
+            if let Ok(upgraded) = upgrade.await {
+                let conn = WebSocketStream::from_raw_socket(
+                    TokioAdapter::new(upgraded),
+                    Role::Server,
+                    None,
+                )
+                .await;
+                Connection {
+                    state: self,
+                    conn,
+                    event_rx,
+                    subscriptions: HashMap::new(),
+                }
+                .run()
+                .await;
+            }
+        });
+
+        hyper::Response::builder()
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .header(header::CONNECTION, "Upgrade")
+            .header(header::UPGRADE, "websocket")
+            .header(header::SEC_WEBSOCKET_ACCEPT, accept_key)
+            .body(Body::empty())
+            .unwrap()
+    }
+}
+
+// Serves the requests and subscriptions of a WebSocket connection.
+struct Connection {
+    state: Arc<State>,
+    conn: WebSocketStream<TokioAdapter<Upgraded>>,
+    event_rx: broadcast::Receiver<Event>,
+    // The IDs of the subscription requests, by query.
+    subscriptions: HashMap<String, Id>,
+}
+
+impl Connection {
+    async fn run(mut self) {
+        loop {
+            tokio::select! {
+                msg = self.conn.next() => match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        let response = self.handle_request(&text);
+                        if self.conn.send(Message::Text(response)).await.is_err() {
+                            return;
+                        }
+                    },
+                    Some(Ok(Message::Ping(data))) => {
+                        let _ = self.conn.send(Message::Pong(data)).await;
+                    },
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                    Some(Ok(_)) => {},
+                },
+                event = self.event_rx.recv() => match event {
+                    Ok(event) => {
+                        if let Some(msg) = self.event_message(event) {
+                            if self.conn.send(Message::Text(msg)).await.is_err() {
+                                return;
+                            }
+                        }
+                    },
+                    Err(broadcast::error::RecvError::Lagged(_)) => {},
+                    Err(broadcast::error::RecvError::Closed) => {
+                        let _ = self.conn.close(None).await;
+                        return;
+                    },
+                },
+            }
+        }
+    }
+
+    fn handle_request(&mut self, text: &str) -> String {
+        let request = match serde_json::from_str::<IncomingRequest>(text) {
+            Ok(request) => request,
+            Err(e) => return response_json(Id::None, Err(ResponseError::parse_error(e))),
+        };
+        let id = request.id.clone().unwrap_or(Id::None);
+        let result = match request.method.as_str() {
+            "subscribe" => self.subscription_query(request.params).map(|query| {
+                self.subscriptions.insert(query, id.clone());
+                serde_json::json!({})
+            }),
+            "unsubscribe" => self.subscription_query(request.params).map(|query| {
+                self.subscriptions.remove(&query);
+                serde_json::json!({})
+            }),
+            "unsubscribe_all" => {
+                self.subscriptions.clear();
+                Ok(serde_json::json!({}))
+            },
+            _ => self.state.call(request),
+        };
+        response_json(id, result)
+    }
+
+    fn subscription_query(&self, params: serde_json::Value) -> Result<String, ResponseError> {
+        serde_json::from_value::<SubscriptionParams>(params)
+            .map(|params| params.query)
+            .map_err(|e| ResponseError::invalid_params(&e.to_string()))
+    }
+
+    fn event_message(&self, event: Event) -> Option<String> {
+        let id = self.subscriptions.get(&event.query)?.clone();
+        let result = match self.state.compat {
+            CompatMode::V0_34 => serde_json::to_value(event::v0_34::SerEvent::from(event)),
+            // The events of nodes running CometBFT 0.37 differ from the ones of
+            // 0.38, which are decoded with the same protocol.
+            CompatMode::V0_37 => match &event.data {
+                EventData::LegacyNewBlock { .. }
+                | EventData::NewBlockHeader {
+                    num_txs: Some(_), ..
+                } => serde_json::to_value(event::v0_37::SerEvent::from(event)),
+                _ => serde_json::to_value(event::v0_38::SerEvent::from(event)),
+            },
+        };
+        Some(response_json(
+            id,
+            result.map_err(|e| ResponseError::server_error(e.to_string())),
+        ))
+    }
+}
+
+fn response_json(id: Id, result: Result<serde_json::Value, ResponseError>) -> String {
+    let wrapper = match result {
+        Ok(result) => Wrapper::new_with_id(id, Some(result), None),
+        Err(e) => Wrapper::new_with_id(id, None, Some(e)),
+    };
+    serde_json::to_string(&wrapper).unwrap()
+}
+
+fn status_response(status: StatusCode) -> hyper::Response<Body> {
+    hyper::Response::builder()
+        .status(status)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn is_websocket_upgrade(request: &hyper::Request<Body>) -> bool {
+    request
+        .headers()
+        .get(header::UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+}
+
+fn is_subscription_method(method: &str) -> bool {
+    matches!(method, "subscribe" | "unsubscribe" | "unsubscribe_all")
+}
+
+#[cfg(all(test, feature = "http-client", feature = "websocket-client"))]
+mod tests {
+    use futures::StreamExt;
+    use tendermint::abci::response::Info;
+
+    use super::*;
+    use crate::{
+        endpoint::abci_info,
+        query::{EventType, Query},
+        Client, HttpClient, SubscriptionClient, WebSocketClient,
+    };
+
+    fn info() -> Info {
+        Info {
+            data: "fake node".to_string(),
+            last_block_height: 42_u32.into(),
+            ..Default::default()
+        }
+    }
+
+    fn server() -> ServerHandle {
+        Server::new()
+            .on(Method::AbciInfo, |_: abci_info::Request| {
+                Ok(abci_info::Response { response: info() })
+            })
+            .bind(([127, 0, 0, 1], 0).into())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn http_requests() {
+        let server = server();
+        let client = HttpClient::new(server.http_url().as_str()).unwrap();
+
+        assert_eq!(client.abci_info().await.unwrap(), info());
+        let err = client.status().await.unwrap_err();
+        match err.detail() {
+            crate::error::ErrorDetail::Response(e) => {
+                assert_eq!(e.source.code(), Code::MethodNotFound)
+            },
+            _ => panic!("unexpected error: {err:?}"),
+        }
+
+        server.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn websocket_subscriptions() {
+        let server = server();
+        let (client, driver) = WebSocketClient::new(server.websocket_url().as_str())
+            .await
+            .unwrap();
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        assert_eq!(client.abci_info().await.unwrap(), info());
+
+        let query: Query = EventType::NewBlock.into();
+        let mut subs = client.subscribe(query.clone()).await.unwrap();
+        let event = Event {
+            query: query.to_string(),
+            data: EventData::GenericJsonEvent(serde_json::json!({ "height": "42" })),
+            events: None,
+        };
+        // Not delivered, for lack of a subscription to its query.
+        server.publish(Event {
+            query: "tm.event = 'Tx'".to_string(),
+            ..event.clone()
+        });
+        server.publish(event.clone());
+        assert_eq!(subs.next().await.unwrap().unwrap(), event);
+
+        client.close().unwrap();
+        driver_hdl.await.unwrap().unwrap();
+        server.shutdown().await.unwrap();
+    }
+}
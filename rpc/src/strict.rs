@@ -0,0 +1,621 @@
+//! Strict decoding of JSON-RPC responses.
+//!
+//! The response types in this crate silently ignore fields they do not know
+//! about, so that clients keep working against nodes running newer versions
+//! of Tendermint. For conformance testing it is more useful to be told about
+//! such fields, which is what strict decoding does: it wraps the JSON
+//! deserializer so that any value a type would otherwise skip as an unknown
+//! field results in an error naming the field.
+//!
+//! Types decoding through an intermediate buffer (e.g. untagged enums and
+//! flattened structs) are still decoded leniently.
+
+use core::fmt;
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+
+use crate::prelude::*;
+
+/// Deserialize an instance of `T` from JSON bytes, rejecting unknown fields.
+pub fn from_slice<T>(bytes: &[u8]) -> Result<T, serde_json::Error>
+where
+    T: DeserializeOwned,
+{
+    let mut de = serde_json::Deserializer::from_slice(bytes);
+    let value = T::deserialize(Strict::new(&mut de))?;
+    de.end()?;
+    Ok(value)
+}
+
+/// The location of a value within the document being deserialized.
+#[derive(Clone, Copy)]
+enum Path<'p> {
+    Root,
+    Seq { parent: &'p Path<'p>, index: usize },
+    Map { parent: &'p Path<'p>, key: &'p str },
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Path::Root => Ok(()),
+            Path::Seq { parent, index } => write!(f, "{parent}[{index}]"),
+            Path::Map {
+                parent: Path::Root,
+                key,
+            } => write!(f, "{key}"),
+            Path::Map { parent, key } => write!(f, "{parent}.{key}"),
+        }
+    }
+}
+
+/// A deserializer failing on the values that would be ignored.
+struct Strict<'p, D> {
+    de: D,
+    path: Path<'p>,
+}
+
+impl<D> Strict<'static, D> {
+    fn new(de: D) -> Self {
+        Self {
+            de,
+            path: Path::Root,
+        }
+    }
+}
+
+/// Forward the `deserialize_*` methods of a wrapping deserializer to the
+/// inner deserializer, wrapping the visitors with `$wrap`.
+macro_rules! forward_deserialize {
+    ($wrap:expr; $($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                let wrap = $wrap;
+                let (de, visitor) = wrap(self, visitor);
+                de.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+/// The `deserialize_*` methods that are forwarded as they are.
+macro_rules! forward_all_deserialize {
+    ($wrap:expr) => {
+        forward_deserialize! {
+            $wrap;
+            deserialize_any(),
+            deserialize_bool(),
+            deserialize_i8(),
+            deserialize_i16(),
+            deserialize_i32(),
+            deserialize_i64(),
+            deserialize_i128(),
+            deserialize_u8(),
+            deserialize_u16(),
+            deserialize_u32(),
+            deserialize_u64(),
+            deserialize_u128(),
+            deserialize_f32(),
+            deserialize_f64(),
+            deserialize_char(),
+            deserialize_str(),
+            deserialize_string(),
+            deserialize_bytes(),
+            deserialize_byte_buf(),
+            deserialize_option(),
+            deserialize_unit(),
+            deserialize_unit_struct(name: &'static str),
+            deserialize_newtype_struct(name: &'static str),
+            deserialize_seq(),
+            deserialize_tuple(len: usize),
+            deserialize_tuple_struct(name: &'static str, len: usize),
+            deserialize_map(),
+            deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+            deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+            deserialize_identifier(),
+        }
+    };
+}
+
+impl<'de, 'p, D> Deserializer<'de> for Strict<'p, D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_all_deserialize!(|this: Self, visitor| (
+        this.de,
+        Wrap {
+            visitor,
+            path: this.path
+        }
+    ));
+
+    fn deserialize_ignored_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::custom(format_args!(
+            "unknown field `{}`",
+            self.path
+        )))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+/// A visitor making sure that nested values are deserialized strictly.
+struct Wrap<'p, V> {
+    visitor: V,
+    path: Path<'p>,
+}
+
+/// Forward the `visit_*` methods for primitive values to the inner visitor.
+macro_rules! forward_visit {
+    ($($method:ident($($arg:ident: $ty:ty)?)),* $(,)?) => {
+        $(
+            fn $method<E>(self, $($arg: $ty)?) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visitor.$method($($arg)?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'p, V> Visitor<'de> for Wrap<'p, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    forward_visit! {
+        visit_bool(v: bool),
+        visit_i8(v: i8),
+        visit_i16(v: i16),
+        visit_i32(v: i32),
+        visit_i64(v: i64),
+        visit_i128(v: i128),
+        visit_u8(v: u8),
+        visit_u16(v: u16),
+        visit_u32(v: u32),
+        visit_u64(v: u64),
+        visit_u128(v: u128),
+        visit_f32(v: f32),
+        visit_f64(v: f64),
+        visit_char(v: char),
+        visit_str(v: &str),
+        visit_borrowed_str(v: &'de str),
+        visit_string(v: String),
+        visit_bytes(v: &[u8]),
+        visit_borrowed_bytes(v: &'de [u8]),
+        visit_byte_buf(v: Vec<u8>),
+        visit_none(),
+        visit_unit(),
+    }
+
+    fn visit_some<D>(self, de: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.visitor.visit_some(Strict {
+            de,
+            path: self.path,
+        })
+    }
+
+    fn visit_newtype_struct<D>(self, de: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.visitor.visit_newtype_struct(Strict {
+            de,
+            path: self.path,
+        })
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.visitor.visit_seq(StrictSeq {
+            seq,
+            path: self.path,
+            index: 0,
+        })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.visitor.visit_map(StrictMap {
+            map,
+            path: self.path,
+            key: None,
+        })
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        self.visitor.visit_enum(StrictEnum {
+            data,
+            path: self.path,
+        })
+    }
+}
+
+/// A seed deserializing its value strictly.
+struct StrictSeed<'p, T> {
+    seed: T,
+    path: Path<'p>,
+}
+
+impl<'de, 'p, T> DeserializeSeed<'de> for StrictSeed<'p, T>
+where
+    T: DeserializeSeed<'de>,
+{
+    type Value = T::Value;
+
+    fn deserialize<D>(self, de: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.seed.deserialize(Strict {
+            de,
+            path: self.path,
+        })
+    }
+}
+
+struct StrictSeq<'p, A> {
+    seq: A,
+    path: Path<'p>,
+    index: usize,
+}
+
+impl<'de, 'p, A> SeqAccess<'de> for StrictSeq<'p, A>
+where
+    A: SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let path = Path::Seq {
+            parent: &self.path,
+            index: self.index,
+        };
+        self.index += 1;
+        self.seq.next_element_seed(StrictSeed { seed, path })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.seq.size_hint()
+    }
+}
+
+struct StrictMap<'p, A> {
+    map: A,
+    path: Path<'p>,
+    key: Option<String>,
+}
+
+impl<'de, 'p, A> MapAccess<'de> for StrictMap<'p, A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.key = None;
+        self.map.next_key_seed(CaptureKey {
+            seed,
+            key: &mut self.key,
+        })
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let path = Path::Map {
+            parent: &self.path,
+            key: self.key.as_deref().unwrap_or("?"),
+        };
+        self.map.next_value_seed(StrictSeed { seed, path })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.map.size_hint()
+    }
+}
+
+struct StrictEnum<'p, A> {
+    data: A,
+    path: Path<'p>,
+}
+
+impl<'de, 'p, A> EnumAccess<'de> for StrictEnum<'p, A>
+where
+    A: EnumAccess<'de>,
+{
+    type Error = A::Error;
+    type Variant = StrictEnum<'p, A::Variant>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (value, data) = self.data.variant_seed(seed)?;
+        Ok((
+            value,
+            StrictEnum {
+                data,
+                path: self.path,
+            },
+        ))
+    }
+}
+
+impl<'de, 'p, A> VariantAccess<'de> for StrictEnum<'p, A>
+where
+    A: VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.data.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.data.newtype_variant_seed(StrictSeed {
+            seed,
+            path: self.path,
+        })
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.data.tuple_variant(
+            len,
+            Wrap {
+                visitor,
+                path: self.path,
+            },
+        )
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.data.struct_variant(
+            fields,
+            Wrap {
+                visitor,
+                path: self.path,
+            },
+        )
+    }
+}
+
+/// A seed remembering the map key it deserializes, so that it can be named in
+/// errors about its value.
+struct CaptureKey<'k, T> {
+    seed: T,
+    key: &'k mut Option<String>,
+}
+
+impl<'de, 'k, T> DeserializeSeed<'de> for CaptureKey<'k, T>
+where
+    T: DeserializeSeed<'de>,
+{
+    type Value = T::Value;
+
+    fn deserialize<D>(self, de: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.seed.deserialize(CaptureKey {
+            seed: de,
+            key: self.key,
+        })
+    }
+}
+
+impl<'de, 'k, D> Deserializer<'de> for CaptureKey<'k, D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_all_deserialize!(|this: Self, visitor| (
+        this.seed,
+        CaptureKey {
+            seed: visitor,
+            key: this.key
+        }
+    ));
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.seed.deserialize_ignored_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.seed.is_human_readable()
+    }
+}
+
+impl<'de, 'k, V> Visitor<'de> for CaptureKey<'k, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.seed.expecting(f)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        *self.key = Some(v.to_string());
+        self.seed.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        *self.key = Some(v.to_string());
+        self.seed.visit_borrowed_str(v)
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        *self.key = Some(v.clone());
+        self.seed.visit_string(v)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.seed.visit_bool(v)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.seed.visit_i64(v)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.seed.visit_u64(v)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.seed.visit_f64(v)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.seed.visit_bytes(v)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.seed.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.seed.visit_unit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Inner {
+        value: u64,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Outer {
+        name: String,
+        inner: Option<Inner>,
+        items: Vec<Inner>,
+    }
+
+    #[test]
+    fn accepts_known_fields() {
+        let json = r#"{"name":"a","inner":{"value":1},"items":[{"value":2}]}"#;
+        let outer: Outer = from_slice(json.as_bytes()).unwrap();
+        assert_eq!(
+            outer,
+            Outer {
+                name: "a".to_string(),
+                inner: Some(Inner { value: 1 }),
+                items: vec![Inner { value: 2 }],
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let cases = [
+            (r#"{"name":"a","inner":null,"items":[],"extra":1}"#, "extra"),
+            (
+                r#"{"name":"a","inner":{"value":1,"extra":{}},"items":[]}"#,
+                "inner.extra",
+            ),
+            (
+                r#"{"name":"a","inner":null,"items":[{"value":1},{"value":2,"extra":[]}]}"#,
+                "items[1].extra",
+            ),
+        ];
+        for (json, path) in cases {
+            let err = from_slice::<Outer>(json.as_bytes()).unwrap_err();
+            assert!(
+                err.to_string().contains(&format!("unknown field `{path}`")),
+                "{err}"
+            );
+            // Lenient decoding just skips the field.
+            serde_json::from_str::<Outer>(json).unwrap();
+        }
+    }
+}
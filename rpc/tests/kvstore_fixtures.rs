@@ -3,6 +3,7 @@
 use core::str::FromStr;
 use std::{collections::BTreeMap as HashMap, fs, path::PathBuf};
 
+use sha2::Digest;
 use subtle_encoding::{base64, hex};
 use tendermint::{
     abci,
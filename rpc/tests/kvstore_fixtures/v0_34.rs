@@ -237,6 +237,7 @@ fn outgoing_fixtures() {
                 assert_eq!(wrapped.params().page, 1);
                 assert_eq!(wrapped.params().per_page, 10);
                 assert_eq!(wrapped.params().order_by, Order::Ascending);
+                assert!(!wrapped.params().match_events);
             },
             "tx_search_with_prove" => {
                 let wrapped =
@@ -247,6 +248,7 @@ fn outgoing_fixtures() {
                 assert_eq!(wrapped.params().page, 1);
                 assert_eq!(wrapped.params().per_page, 10);
                 assert_eq!(wrapped.params().order_by, Order::Ascending);
+                assert!(!wrapped.params().match_events);
             },
             _ => {
                 panic!("cannot parse file name: {file_name}");
@@ -420,7 +422,7 @@ fn incoming_fixtures() {
             },
             "block_results_at_height_10" => {
                 let result: endpoint::block_results::Response =
-                    endpoint::block_results::v0_34::DialectResponse::from_string(content)
+                    <endpoint::block_results::v0_34::DialectResponse>::from_string(content)
                         .unwrap()
                         .into();
                 assert!(result.begin_block_events.is_none());
@@ -478,6 +480,17 @@ fn incoming_fixtures() {
                     } else {
                         panic!("not a duplicate vote: {evidence:?}");
                     }
+
+                    // The evidence can be broadcast back to a node as it is.
+                    let request = endpoint::evidence::Request::<
+                        tendermint_rpc::dialect::v0_34::Dialect,
+                    >::new(evidence.clone());
+                    let json = serde_json::to_value(&request).unwrap();
+                    assert_eq!(json["evidence"]["type"], "tendermint/DuplicateVoteEvidence");
+                    let decoded: endpoint::evidence::Request<
+                        tendermint_rpc::dialect::v0_34::Dialect,
+                    > = serde_json::from_value(json.clone()).unwrap();
+                    assert_eq!(serde_json::to_value(&decoded).unwrap(), json);
                 }
             },
             "broadcast_tx_async" => {
@@ -492,7 +505,7 @@ fn incoming_fixtures() {
             },
             "broadcast_tx_commit" => {
                 let result: endpoint::broadcast::tx_commit::Response =
-                    endpoint::broadcast::tx_commit::v0_34::DialectResponse::from_string(content)
+                    <endpoint::broadcast::tx_commit::v0_34::DialectResponse>::from_string(content)
                         .unwrap()
                         .into();
                 assert_eq!(result.check_tx.code, abci::Code::Ok);
@@ -1327,7 +1340,7 @@ fn incoming_fixtures() {
             },
             "tx" => {
                 let result: endpoint::tx::Response =
-                    endpoint::tx::v0_34::DialectResponse::from_string(content)
+                    <endpoint::tx::v0_34::DialectResponse>::from_string(content)
                         .unwrap()
                         .into();
                 assert_eq!(
@@ -1346,7 +1359,7 @@ fn incoming_fixtures() {
             },
             "tx_search_no_prove" => {
                 let result: endpoint::tx_search::Response =
-                    endpoint::tx_search::v0_34::DialectResponse::from_string(content)
+                    <endpoint::tx_search::v0_34::DialectResponse>::from_string(content)
                         .unwrap()
                         .into();
                 assert_eq!(result.total_count as usize, result.txs.len());
@@ -1365,7 +1378,7 @@ fn incoming_fixtures() {
             },
             "tx_search_with_prove" => {
                 let result: endpoint::tx_search::Response =
-                    endpoint::tx_search::v0_34::DialectResponse::from_string(content)
+                    <endpoint::tx_search::v0_34::DialectResponse>::from_string(content)
                         .unwrap()
                         .into();
                 assert_eq!(result.total_count as usize, result.txs.len());
@@ -1384,6 +1397,11 @@ fn incoming_fixtures() {
                     assert_eq!(proof.proof.total, 1);
                     assert_eq!(proof.proof.index, 0);
                     assert_ne!(proof.root_hash.as_bytes(), [0; 32]);
+                    let leaf = sha2::Sha256::digest(&tx.tx);
+                    proof
+                        .proof
+                        .verify::<sha2::Sha256>(&proof.root_hash, &leaf)
+                        .unwrap();
                 }
             },
             _ => {
@@ -236,6 +236,7 @@ fn outgoing_fixtures() {
                 assert_eq!(wrapped.params().page, 1);
                 assert_eq!(wrapped.params().per_page, 10);
                 assert_eq!(wrapped.params().order_by, Order::Ascending);
+                assert!(!wrapped.params().match_events);
             },
             "tx_search_with_prove" => {
                 let wrapped =
@@ -246,6 +247,7 @@ fn outgoing_fixtures() {
                 assert_eq!(wrapped.params().page, 1);
                 assert_eq!(wrapped.params().per_page, 10);
                 assert_eq!(wrapped.params().order_by, Order::Ascending);
+                assert!(!wrapped.params().match_events);
             },
             _ => {
                 panic!("cannot parse file name: {file_name}");
@@ -1369,6 +1371,11 @@ fn incoming_fixtures() {
                     assert_eq!(proof.proof.total, 1);
                     assert_eq!(proof.proof.index, 0);
                     assert_ne!(proof.root_hash.as_bytes(), [0; 32]);
+                    let leaf = sha2::Sha256::digest(&tx.tx);
+                    proof
+                        .proof
+                        .verify::<sha2::Sha256>(&proof.root_hash, &leaf)
+                        .unwrap();
                 }
             },
             _ => {
@@ -1413,3 +1420,94 @@ fn check_event_attrs(events: &HashMap<String, Vec<String>>, app_key: &str, heigh
         }
     }
 }
+
+#[test]
+fn incoming_fixtures_strict() {
+    fn strict<R: Response>(content: String) -> Result<(), Error> {
+        R::from_string_strict(content).map(drop)
+    }
+
+    for json_file in find_fixtures("v0_37", "incoming") {
+        let file_name = json_file
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .strip_suffix(".json")
+            .unwrap();
+        let content = fs::read_to_string(&json_file).unwrap();
+        let result = match file_name {
+            "abci_info" => strict::<endpoint::abci_info::Response>(content),
+            "abci_query_with_existing_key" => strict::<endpoint::abci_query::Response>(content),
+            "block_at_height_10" => strict::<endpoint::block::Response>(content),
+            "block_by_hash" => strict::<endpoint::block_by_hash::Response>(content),
+            "block_results_at_height_10" => strict::<endpoint::block_results::Response>(content),
+            "block_search" => strict::<endpoint::block_search::Response>(content),
+            "blockchain_from_1_to_10" => strict::<endpoint::blockchain::Response>(content),
+            "commit_at_height_10" => strict::<endpoint::commit::Response>(content),
+            "consensus_state" => strict::<endpoint::consensus_state::Response>(content),
+            "net_info" => strict::<endpoint::net_info::Response>(content),
+            "status" => strict::<endpoint::status::Response>(content),
+            "tx_search_no_prove" | "tx_search_with_prove" => {
+                strict::<endpoint::tx_search::Response>(content)
+            },
+            // The node includes `codespace`, which is not part of the response
+            // type, so only lenient decoding succeeds.
+            "broadcast_tx_sync" => {
+                let err =
+                    strict::<endpoint::broadcast::tx_sync::Response>(content.clone()).unwrap_err();
+                assert!(err.to_string().contains("serde parse error"));
+                assert!(format!("{err:?}").contains("unknown field `result.codespace`"));
+                endpoint::broadcast::tx_sync::Response::from_string(content).map(drop)
+            },
+            _ => continue,
+        };
+        if let Err(e) = result {
+            panic!("{file_name}: {e:?}");
+        }
+    }
+}
+
+/// The `/header` and `/header_by_hash` responses carry the header of the
+/// corresponding `/block` and `/block_by_hash` responses, which also back-fill
+/// them when talking to nodes which predate these endpoints.
+#[test]
+fn header_fixtures() {
+    fn header_response(block_response: &str) -> String {
+        let mut response: serde_json::Value = serde_json::from_str(block_response).unwrap();
+        response["result"] = serde_json::json!({
+            "header": response["result"]["block"]["header"].take(),
+        });
+        response.to_string()
+    }
+
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/kvstore_fixtures/v0_37");
+
+    let content = fs::read_to_string(dir.join("incoming/block_at_height_10.json")).unwrap();
+    let block = endpoint::block::Response::from_string(&content).unwrap();
+    let header = endpoint::header::Response::from_string(header_response(&content)).unwrap();
+    assert_eq!(header.header.height.value(), 10);
+    assert_eq!(header.header, block.block.header);
+    assert_eq!(
+        endpoint::header::Response::from(block).header,
+        header.header
+    );
+
+    let content = fs::read_to_string(dir.join("incoming/block_by_hash.json")).unwrap();
+    let block = endpoint::block_by_hash::Response::from_string(&content).unwrap();
+    let header =
+        endpoint::header_by_hash::Response::from_string(header_response(&content)).unwrap();
+    assert_eq!(header.header, block.block.clone().map(|b| b.header));
+    assert!(header.header.is_some());
+    assert_eq!(
+        endpoint::header_by_hash::Response::from(block).header,
+        header.header
+    );
+
+    let request = endpoint::header_by_hash::Request::new(
+        Hash::from_str("FCF9C2537FC3534CA71001FE1F14C4F769090948C1A521682F612E7CF73AE639").unwrap(),
+    );
+    let wrapped: RequestWrapper<endpoint::header_by_hash::Request> =
+        serde_json::from_str(&RequestWrapper::new(request.clone()).into_json()).unwrap();
+    assert_eq!(wrapped.params().hash, request.hash);
+}
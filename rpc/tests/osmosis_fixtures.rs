@@ -35,7 +35,7 @@ fn incoming_fixtures() {
         let content = fs::read_to_string(&json_file).unwrap();
         match file_name {
             "block_results_at_height_10499831" => {
-                let r = endpoint::block_results::v0_34::DialectResponse::from_string(content);
+                let r = <endpoint::block_results::v0_34::DialectResponse>::from_string(content);
                 assert!(r.is_ok(), "block_results_at_height_10499831: {r:?}");
             },
             _ => {
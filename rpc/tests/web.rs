@@ -0,0 +1,37 @@
+//! Test suite of the clients for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+use core::time::Duration;
+
+use tendermint_rpc::{error::ErrorDetail, Client, HttpClient, WebSocketClient};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+// Nothing listens on this port.
+const UNREACHABLE_NODE: &str = "127.0.0.1:1";
+
+#[wasm_bindgen_test]
+async fn http_client_fails_to_reach_node() {
+    let client = HttpClient::builder(format!("http://{UNREACHABLE_NODE}").parse().unwrap())
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap();
+
+    let e = client.status().await.unwrap_err();
+    assert!(matches!(e.detail(), ErrorDetail::Fetch(_)), "{e:?}");
+}
+
+#[wasm_bindgen_test]
+async fn websocket_client_fails_to_connect_to_node() {
+    let result = WebSocketClient::new(format!("ws://{UNREACHABLE_NODE}/websocket").as_str()).await;
+    let e = result.err().expect("connected to an unreachable node");
+    assert!(matches!(e.detail(), ErrorDetail::WebSocket(_)), "{e:?}");
+}
+
+#[wasm_bindgen_test]
+fn http_client_rejects_websocket_url() {
+    let e = HttpClient::new(format!("ws://{UNREACHABLE_NODE}").as_str()).unwrap_err();
+    assert!(matches!(e.detail(), ErrorDetail::InvalidUrl(_)), "{e:?}");
+}
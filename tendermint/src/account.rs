@@ -9,7 +9,7 @@ use core::{
 use bytes::Bytes;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use subtle::{self, ConstantTimeEq};
-use subtle_encoding::hex;
+use subtle_encoding::{bech32, hex};
 
 use tendermint_proto::Protobuf;
 
@@ -72,6 +72,38 @@ impl Id {
     pub fn as_bytes(&self) -> &[u8] {
         &self.0[..]
     }
+
+    /// Serialize the account ID as upper case hexadecimal, as in the logs and
+    /// RPC responses of the node
+    pub fn to_hex(self) -> String {
+        self.to_string()
+    }
+
+    /// Serialize the account ID as Bech32 with the given human readable
+    /// prefix, e.g. `cosmosvalcons` for the consensus address of a validator
+    /// on the Cosmos Hub
+    pub fn to_bech32(self, hrp: &str) -> String {
+        bech32::encode(hrp, self.as_bytes())
+    }
+
+    /// Decode an account ID from Bech32, checking that it has the given human
+    /// readable prefix
+    pub fn from_bech32(s: &str, hrp: &str) -> Result<Self, Error> {
+        let (prefix, bytes) = bech32::decode(s).map_err(Error::subtle_encoding)?;
+        if prefix != hrp {
+            return Err(Error::invalid_bech32_prefix(hrp.to_string(), prefix));
+        }
+        bytes.try_into()
+    }
+
+    /// Decode an account ID from either hexadecimal or, if a human readable
+    /// prefix is given, Bech32 with that prefix
+    pub fn parse(s: &str, hrp: Option<&str>) -> Result<Self, Error> {
+        match hrp {
+            Some(hrp) if s.starts_with(hrp) => Self::from_bech32(s, hrp),
+            _ => s.parse(),
+        }
+    }
 }
 
 impl AsRef<[u8]> for Id {
@@ -226,4 +258,23 @@ mod tests {
 
         assert_eq!(id_bytes.ct_eq(&id).unwrap_u8(), 1);
     }
+
+    #[test]
+    fn test_bech32_id() {
+        let id = Id::from_str("0CDA3F47EF3C4906693B170EF650EB968C5F4B2C").unwrap();
+        let encoded = id.to_bech32("cosmosvalcons");
+        assert!(encoded.starts_with("cosmosvalcons1"));
+
+        assert_eq!(Id::from_bech32(&encoded, "cosmosvalcons").unwrap(), id);
+        assert!(Id::from_bech32(&encoded, "osmovalcons").is_err());
+        assert!(Id::from_bech32(&id.to_bech32("cosmosvalcons")[1..], "cosmosvalcons").is_err());
+
+        assert_eq!(Id::parse(&encoded, Some("cosmosvalcons")).unwrap(), id);
+        assert_eq!(Id::parse(&id.to_hex(), Some("cosmosvalcons")).unwrap(), id);
+        assert_eq!(
+            Id::parse("0cda3f47ef3c4906693b170ef650eb968c5f4b2c", None).unwrap(),
+            id
+        );
+        assert!(Id::parse(&encoded, None).is_err());
+    }
 }
@@ -1,19 +1,21 @@
 #[cfg(feature = "rust-crypto")]
 use super::VerificationKey;
 
-use crate::Error;
+use crate::{secret::Secret, Error};
 
+/// The seed of an Ed25519 private key, which is redacted from the `Debug`
+/// output.
 #[derive(Clone, Debug)]
-pub struct SigningKey([u8; 32]);
+pub struct SigningKey(Secret<[u8; 32]>);
 
 impl SigningKey {
     pub fn as_bytes(&self) -> &[u8] {
-        &self.0
+        self.0.expose_secret()
     }
 
     #[cfg(feature = "rust-crypto")]
     pub fn verification_key(&self) -> VerificationKey {
-        let privkey = ed25519_consensus::SigningKey::from(self.0);
+        let privkey = ed25519_consensus::SigningKey::from(*self.0.expose_secret());
         let pubkey = privkey.verification_key();
         let pubkey_bytes = pubkey.to_bytes();
         VerificationKey::new(pubkey_bytes)
@@ -29,7 +31,7 @@ impl TryFrom<&'_ [u8]> for SigningKey {
         }
         let mut bytes = [0u8; 32];
         bytes[..].copy_from_slice(slice);
-        Ok(Self(bytes))
+        Ok(Self(Secret::new(bytes)))
     }
 }
 
@@ -38,7 +40,7 @@ impl TryFrom<SigningKey> for ed25519_consensus::SigningKey {
     type Error = Error;
 
     fn try_from(src: SigningKey) -> Result<Self, Error> {
-        ed25519_consensus::SigningKey::try_from(src.0)
+        ed25519_consensus::SigningKey::try_from(src.0.into_inner())
             .map_err(|_| Error::invalid_key("malformed Ed25519 private key".into()))
     }
 }
@@ -16,7 +16,7 @@ define_error! {
 
         InvalidKey
             { detail: String }
-            |e| { format_args!("invalid key: {e}") },
+            |e| { format_args!("invalid key: {}", e.detail) },
 
         Length
             |_| { format_args!("length error") },
@@ -74,6 +74,10 @@ define_error! {
         InvalidAccountIdLength
             |_| { format_args!("invalid account ID length") },
 
+        InvalidBech32Prefix
+            { expected: String, found: String }
+            |e| { format_args!("invalid Bech32 prefix: expected '{}', got '{}'", e.expected, e.found) },
+
         InvalidSignatureIdLength
             |_| { format_args!("invalid signature ID length") },
 
@@ -202,6 +206,25 @@ define_error! {
             { account: account::Id }
             |e| { format_args!("proposer with address '{0}' no found in validator set", e.account) },
 
+        UnorderedValidators
+            {
+                index: usize,
+                first: account::Id,
+                first_power: u64,
+                second: account::Id,
+                second_power: u64,
+            }
+            |e| {
+                format_args!(
+                    "validators at positions {} and {} are not ordered by power descending, then address ascending: '{}' (power {}) comes before '{}' (power {})",
+                    e.index, e.index + 1, e.first, e.first_power, e.second, e.second_power
+                )
+            },
+
+        DuplicateValidator
+            { address: account::Id }
+            |e| { format_args!("validator with address '{}' appears more than once in validator set", e.address) },
+
         TimeParse
             [ DisplayOnly<time::error::Parse> ]
             |_| { format_args!("time parsing error") },
@@ -229,6 +252,23 @@ define_error! {
         NegativeProofIndex
             [ DisplayOnly<TryFromIntError> ]
             |_| { "negative item index in proof" },
+
+        InvalidProof
+            { reason: String }
+            | e | { format_args!("invalid proof: {}", e.reason) },
+
+        UnknownTypeUrl
+            { type_url: String }
+            | e | { format_args!("no decoder registered for type URL '{}'", e.type_url) },
+
+        DuplicateTypeUrl
+            { type_url: String }
+            | e | { format_args!("type URL '{}' is already registered", e.type_url) },
+
+        DecodeAny
+            { type_url: String }
+            [ DisplayOnly<tendermint_proto::Error> ]
+            | e | { format_args!("error decoding message of type URL '{}'", e.type_url) },
     }
 }
 
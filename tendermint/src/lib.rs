@@ -46,7 +46,10 @@ pub mod private_key;
 pub mod privval;
 pub mod proposal;
 pub mod public_key;
+pub mod registry;
+pub mod secret;
 pub mod serializers;
+pub mod service;
 pub mod signature;
 pub mod time;
 mod timeout;
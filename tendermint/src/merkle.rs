@@ -1,5 +1,7 @@
 //! Merkle tree used in Tendermint networks
 
+#[cfg(feature = "rust-crypto")]
+pub mod ics23;
 pub mod proof;
 
 pub use proof::Proof;
@@ -0,0 +1,273 @@
+//! [ICS 23] commitment proofs, as found in the `ics23:iavl` and
+//! `ics23:simple` operations of the proofs of Cosmos SDK stores.
+//!
+//! The proofs are decoded and verified with the [`ics23`] crate, against the
+//! proof specifications of the IAVL trees of the stores, and of the simple
+//! Merkle trees committing to the roots of the stores. Only existence proofs
+//! can be verified.
+//!
+//! Mirrors `CommitmentOp` in the Cosmos SDK:
+//! <https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/store/types/proof.go>
+//!
+//! [ICS 23]: https://github.com/cosmos/ics23
+
+use ::ics23::{commitment_proof, CommitmentProof, HostFunctionsManager, ProofSpec};
+use prost::Message;
+
+use crate::{
+    merkle::proof::{ProofOp, ProofOperator},
+    prelude::*,
+    Error,
+};
+
+/// The type of the operations proving keys of IAVL trees.
+pub const PROOF_OP_IAVL: &str = "ics23:iavl";
+
+/// The type of the operations proving keys of simple Merkle trees.
+pub const PROOF_OP_SIMPLE: &str = "ics23:simple";
+
+fn invalid(reason: &str) -> Error {
+    Error::invalid_proof(reason.to_string())
+}
+
+/// A proof operation proving the value of a key with an ICS 23 commitment
+/// proof.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommitmentOp {
+    /// The specification of the tree the key is in
+    pub spec: ProofSpec,
+    /// The key proven
+    pub key: Vec<u8>,
+    /// The commitment proof
+    pub proof: CommitmentProof,
+}
+
+impl CommitmentOp {
+    /// Decode the given `ics23:iavl` or `ics23:simple` proof operation.
+    pub fn decode(op: &ProofOp) -> Result<Self, Error> {
+        let spec = match op.field_type.as_str() {
+            PROOF_OP_IAVL => ::ics23::iavl_spec(),
+            PROOF_OP_SIMPLE => ::ics23::tendermint_spec(),
+            _ => return Err(invalid("not an ICS 23 proof operation")),
+        };
+        let proof = CommitmentProof::decode(op.data.as_slice())
+            .map_err(|e| Error::invalid_proof(format!("malformed ICS 23 proof: {e}")))?;
+        Ok(Self {
+            spec,
+            key: op.key.clone(),
+            proof,
+        })
+    }
+}
+
+impl ProofOperator for CommitmentOp {
+    fn run(&self, args: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error> {
+        let proof = match &self.proof.proof {
+            Some(commitment_proof::Proof::Exist(proof)) => proof,
+            _ => return Err(invalid("only existence proofs are supported")),
+        };
+        let value = match args.as_slice() {
+            [value] => value,
+            _ => return Err(invalid("expected a single value to prove")),
+        };
+        let root = ::ics23::calculate_existence_root::<HostFunctionsManager>(proof)
+            .map_err(|e| Error::invalid_proof(format!("invalid ICS 23 proof: {e}")))?;
+        if !::ics23::verify_membership::<HostFunctionsManager>(
+            &self.proof,
+            &self.spec,
+            &root,
+            &self.key,
+            value,
+        ) {
+            return Err(invalid("ICS 23 proof verification failed"));
+        }
+        Ok(vec![root])
+    }
+
+    fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+/// Decode an ICS 23 proof operation, for [`ProofRuntime::with_decoder`].
+///
+/// [`ProofRuntime::with_decoder`]: crate::merkle::proof::ProofRuntime::with_decoder
+pub fn decode_op(op: &ProofOp) -> Result<Box<dyn ProofOperator>, Error> {
+    Ok(Box::new(CommitmentOp::decode(op)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use ::ics23::{ExistenceProof, HashOp, InnerOp, LeafOp};
+
+    use super::*;
+    use crate::{
+        crypto::{default::Sha256, Sha256 as _},
+        merkle::proof::{ProofOps, ProofRuntime},
+    };
+
+    fn existence_proof(
+        key: &[u8],
+        value: &[u8],
+        leaf_prefix: &[u8],
+        path: Vec<InnerOp>,
+    ) -> ExistenceProof {
+        ExistenceProof {
+            key: key.to_vec(),
+            value: value.to_vec(),
+            leaf: Some(LeafOp {
+                prefix: leaf_prefix.to_vec(),
+                ..::ics23::iavl_spec().leaf_spec.unwrap()
+            }),
+            path,
+        }
+    }
+
+    fn inner_op(prefix: &[u8], suffix: &[u8]) -> InnerOp {
+        InnerOp {
+            hash: HashOp::Sha256.into(),
+            prefix: prefix.to_vec(),
+            suffix: suffix.to_vec(),
+        }
+    }
+
+    fn root(proof: &ExistenceProof) -> Vec<u8> {
+        ::ics23::calculate_existence_root::<HostFunctionsManager>(proof).unwrap()
+    }
+
+    fn proof_op(field_type: &str, proof: &ExistenceProof) -> ProofOp {
+        let data = CommitmentProof {
+            proof: Some(commitment_proof::Proof::Exist(proof.clone())),
+        }
+        .encode_to_vec();
+        ProofOp {
+            field_type: field_type.to_string(),
+            key: proof.key.clone(),
+            data,
+        }
+    }
+
+    // The value of a key in an IAVL store, proven in the tree of the store
+    // and in the tree of the roots of the stores.
+    fn store_proof() -> (ProofOps, Vec<u8>) {
+        // A leaf, left child of an inner node of height 1.
+        let iavl = existence_proof(
+            b"key",
+            b"value",
+            &[0, 2, 2],
+            vec![inner_op(
+                &[2, 4, 2, 32],
+                &[[32].as_slice(), &[0xAA; 32]].concat(),
+            )],
+        );
+        let store_root = root(&iavl);
+        // The second of the two stores.
+        let simple = existence_proof(
+            b"bank",
+            &store_root,
+            &[0],
+            vec![inner_op(&[[1].as_slice(), &[0xBB; 32]].concat(), &[])],
+        );
+        let app_hash = root(&simple);
+        let proof = ProofOps {
+            ops: vec![
+                proof_op(PROOF_OP_IAVL, &iavl),
+                proof_op(PROOF_OP_SIMPLE, &simple),
+            ],
+        };
+        (proof, app_hash)
+    }
+
+    fn verify(proof: &ProofOps, root: &[u8], keys: &[&[u8]], value: &[u8]) -> Result<(), Error> {
+        ProofRuntime::with_ics23().verify_value(proof, root, keys, value)
+    }
+
+    #[test]
+    fn leaf_hash_matches_simple_merkle_tree() {
+        let leaf = existence_proof(b"key", b"value", &[0], vec![]);
+        let mut kv = vec![3];
+        kv.extend_from_slice(b"key");
+        kv.push(32);
+        kv.extend(Sha256::digest(b"value"));
+        let expected = crate::merkle::simple_hash_from_byte_vectors::<
+            crate::merkle::NonIncremental<Sha256>,
+        >(&[kv]);
+        assert_eq!(root(&leaf), expected);
+    }
+
+    #[test]
+    fn valid_store_proof() {
+        let (proof, app_hash) = store_proof();
+        verify(&proof, &app_hash, &[b"bank", b"key"], b"value").unwrap();
+    }
+
+    #[test]
+    fn wrong_value_is_rejected() {
+        let (proof, app_hash) = store_proof();
+        verify(&proof, &app_hash, &[b"bank", b"key"], b"other").unwrap_err();
+    }
+
+    #[test]
+    fn wrong_root_is_rejected() {
+        let (proof, _) = store_proof();
+        verify(&proof, &[0; 32], &[b"bank", b"key"], b"value").unwrap_err();
+    }
+
+    #[test]
+    fn wrong_keys_are_rejected() {
+        let (proof, app_hash) = store_proof();
+        verify(&proof, &app_hash, &[b"staking", b"key"], b"value").unwrap_err();
+        verify(&proof, &app_hash, &[b"key"], b"value").unwrap_err();
+        verify(&proof, &app_hash, &[b"ibc", b"bank", b"key"], b"value").unwrap_err();
+    }
+
+    #[test]
+    fn inner_node_as_leaf_is_rejected() {
+        // An inner node that could be mistaken for a leaf.
+        let iavl = existence_proof(
+            b"key",
+            b"value",
+            &[0, 2, 2],
+            vec![inner_op(
+                &[0, 4, 2, 32],
+                &[[32].as_slice(), &[0xAA; 32]].concat(),
+            )],
+        );
+        let proof = ProofOps {
+            ops: vec![proof_op(PROOF_OP_IAVL, &iavl)],
+        };
+        verify(&proof, &root(&iavl), &[b"key"], b"value").unwrap_err();
+    }
+
+    #[test]
+    fn invalid_iavl_leaf_prefix_is_rejected() {
+        // A leaf of height 1.
+        let iavl = existence_proof(b"key", b"value", &[2, 2, 2], vec![]);
+        let proof = ProofOps {
+            ops: vec![proof_op(PROOF_OP_IAVL, &iavl)],
+        };
+        verify(&proof, &root(&iavl), &[b"key"], b"value").unwrap_err();
+    }
+
+    #[test]
+    fn short_inner_prefix_is_rejected() {
+        // An inner node without the height, size and version of IAVL nodes.
+        let iavl = existence_proof(
+            b"key",
+            b"value",
+            &[0, 2, 2],
+            vec![inner_op(&[32], &[[32].as_slice(), &[0xAA; 32]].concat())],
+        );
+        let proof = ProofOps {
+            ops: vec![proof_op(PROOF_OP_IAVL, &iavl)],
+        };
+        verify(&proof, &root(&iavl), &[b"key"], b"value").unwrap_err();
+    }
+
+    #[test]
+    fn unknown_operations_are_rejected() {
+        let (mut proof, app_hash) = store_proof();
+        proof.ops[0].field_type = "iavl:v".to_string();
+        verify(&proof, &app_hash, &[b"bank", b"key"], b"value").unwrap_err();
+    }
+}
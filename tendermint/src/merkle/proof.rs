@@ -1,9 +1,17 @@
 //! Merkle proofs
 
+use alloc::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use tendermint_proto::v0_37::crypto::Proof as RawProof;
 
-use crate::{prelude::*, serializers, Hash};
+#[cfg(feature = "rust-crypto")]
+use crate::merkle::ics23;
+use crate::{
+    merkle::{self, MerkleHash},
+    prelude::*,
+    serializers, Error, Hash,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(try_from = "RawProof", into = "RawProof")]
@@ -18,6 +26,74 @@ pub struct Proof {
     pub aunts: Vec<Hash>,
 }
 
+impl Proof {
+    /// Verify that this proof places the given leaf at its index in the
+    /// Merkle tree with the given root hash.
+    pub fn verify<H>(&self, root_hash: &Hash, leaf: &[u8]) -> Result<(), Error>
+    where
+        H: MerkleHash + Default,
+    {
+        let mut hasher = H::default();
+        if self.leaf_hash.as_bytes() != hasher.leaf_hash(leaf) {
+            return Err(Error::invalid_proof("leaf hash mismatch".to_string()));
+        }
+        match self.compute_root_hash::<H>() {
+            Some(computed) if root_hash.as_bytes() == computed => Ok(()),
+            Some(_) => Err(Error::invalid_proof("root hash mismatch".to_string())),
+            None => Err(Error::invalid_proof("malformed proof".to_string())),
+        }
+    }
+
+    /// Compute the root hash of the Merkle tree from the leaf hash and the
+    /// aunts in this proof, or `None` if the proof is malformed.
+    pub fn compute_root_hash<H>(&self) -> Option<merkle::Hash>
+    where
+        H: MerkleHash + Default,
+    {
+        let leaf_hash = to_merkle_hash(&self.leaf_hash)?;
+        let aunts = self
+            .aunts
+            .iter()
+            .map(to_merkle_hash)
+            .collect::<Option<Vec<_>>>()?;
+        compute_hash_from_aunts(&mut H::default(), self.index, self.total, leaf_hash, &aunts)
+    }
+}
+
+fn to_merkle_hash(hash: &Hash) -> Option<merkle::Hash> {
+    hash.as_bytes().try_into().ok()
+}
+
+// Mirrors `computeHashFromAunts` in the Go implementation:
+// https://github.com/cometbft/cometbft/blob/v0.37.0/crypto/merkle/proof.go#L182
+fn compute_hash_from_aunts<H: MerkleHash>(
+    hasher: &mut H,
+    index: u64,
+    total: u64,
+    leaf_hash: merkle::Hash,
+    aunts: &[merkle::Hash],
+) -> Option<merkle::Hash> {
+    if index >= total || total == 0 {
+        return None;
+    }
+    if total == 1 {
+        return if aunts.is_empty() {
+            Some(leaf_hash)
+        } else {
+            None
+        };
+    }
+    let (last, rest) = aunts.split_last()?;
+    let split = total.next_power_of_two() / 2;
+    if index < split {
+        let left = compute_hash_from_aunts(hasher, index, split, leaf_hash, rest)?;
+        Some(hasher.inner_hash(left, *last))
+    } else {
+        let right = compute_hash_from_aunts(hasher, index - split, total - split, leaf_hash, rest)?;
+        Some(hasher.inner_hash(*last, right))
+    }
+}
+
 /// Merkle proof defined by the list of ProofOps
 /// <https://github.com/tendermint/tendermint/blob/c8483531d8e756f7fbb812db1dd16d841cdf298a/crypto/merkle/merkle.proto#L26>
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
@@ -43,6 +119,108 @@ pub struct ProofOp {
     pub data: Vec<u8>,
 }
 
+/// A decoded [`ProofOp`], computing the root of a tree from the values proven
+/// in it.
+pub trait ProofOperator {
+    /// Compute the outputs of the operation from the given arguments: the
+    /// value proven for the first operation of a chain, and the outputs of
+    /// the previous operation for the others.
+    fn run(&self, args: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error>;
+
+    /// The key proven by the operation, or an empty key if there is none.
+    fn key(&self) -> &[u8];
+}
+
+/// Decodes the proof operations of a given type.
+pub type ProofOpDecoder = fn(&ProofOp) -> Result<Box<dyn ProofOperator>, Error>;
+
+/// Decodes [`ProofOps`] with the decoders registered for the types of their
+/// operations, and verifies them.
+///
+/// Mirrors `ProofRuntime` in the Go implementation:
+/// <https://github.com/cometbft/cometbft/blob/v0.37.0/crypto/merkle/proof_op.go>
+#[derive(Clone, Debug, Default)]
+pub struct ProofRuntime {
+    decoders: BTreeMap<String, ProofOpDecoder>,
+}
+
+impl ProofRuntime {
+    /// A runtime without any registered decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A runtime decoding the ICS 23 operations of the stores of Cosmos SDK
+    /// applications, `ics23:iavl` and `ics23:simple`.
+    #[cfg(feature = "rust-crypto")]
+    pub fn with_ics23() -> Self {
+        Self::new()
+            .with_decoder(ics23::PROOF_OP_IAVL, ics23::decode_op)
+            .with_decoder(ics23::PROOF_OP_SIMPLE, ics23::decode_op)
+    }
+
+    /// Register the decoder of the operations of the given type.
+    pub fn with_decoder(mut self, field_type: impl Into<String>, decoder: ProofOpDecoder) -> Self {
+        self.register(field_type, decoder);
+        self
+    }
+
+    /// Register the decoder of the operations of the given type, replacing
+    /// any previously registered one.
+    pub fn register(&mut self, field_type: impl Into<String>, decoder: ProofOpDecoder) {
+        self.decoders.insert(field_type.into(), decoder);
+    }
+
+    /// Decode the given operations.
+    pub fn decode(&self, proof: &ProofOps) -> Result<Vec<Box<dyn ProofOperator>>, Error> {
+        proof
+            .ops
+            .iter()
+            .map(|op| match self.decoders.get(&op.field_type) {
+                Some(decode) => decode(op),
+                None => Err(Error::invalid_proof(format!(
+                    "unrecognized proof operation type {}",
+                    op.field_type
+                ))),
+            })
+            .collect()
+    }
+
+    /// Verify that the given proof places the value at the given keys of the
+    /// tree with the given root hash.
+    ///
+    /// The keys are ordered from the outermost tree to the innermost one, for
+    /// instance the name of a store followed by a key in that store.
+    pub fn verify_value(
+        &self,
+        proof: &ProofOps,
+        root: &[u8],
+        keys: &[&[u8]],
+        value: &[u8],
+    ) -> Result<(), Error> {
+        let mut keys = keys.to_vec();
+        let mut args = vec![value.to_vec()];
+        for op in self.decode(proof)? {
+            let key = op.key();
+            if !key.is_empty() {
+                match keys.pop() {
+                    Some(expected) if expected == key => {},
+                    Some(_) => return Err(Error::invalid_proof("key mismatch".to_string())),
+                    None => return Err(Error::invalid_proof("too many keys".to_string())),
+                }
+            }
+            args = op.run(args)?;
+        }
+        if args.as_slice() != [root] {
+            return Err(Error::invalid_proof("root hash mismatch".to_string()));
+        }
+        if !keys.is_empty() {
+            return Err(Error::invalid_proof("keys not consumed".to_string()));
+        }
+        Ok(())
+    }
+}
+
 // =============================================================================
 // Protobuf conversions
 // =============================================================================
@@ -16,6 +16,9 @@ use zeroize::Zeroizing;
 pub const ED25519_KEYPAIR_SIZE: usize = 64;
 
 /// Private keys as parsed from configuration files
+///
+/// The key material is redacted from the `Debug` output.
+#[derive(Debug)]
 #[cfg_attr(feature = "rust-crypto", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rust-crypto", serde(tag = "type", content = "value"))] // JSON custom serialization for priv_validator_key.json
 #[non_exhaustive]
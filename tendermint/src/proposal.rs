@@ -135,6 +135,13 @@ mod tests {
         Proposal,
     };
 
+    // The sign bytes of the given golden fixture, see `tests/golden.rs`.
+    fn golden_sign_bytes(fixture: &str) -> Vec<u8> {
+        let fixture: serde_json::Value = serde_json::from_str(fixture).unwrap();
+        let bytes = fixture["sign_bytes"]["bytes"].as_str().unwrap();
+        subtle_encoding::hex::decode_upper(bytes).unwrap()
+    }
+
     #[test]
     fn test_serialization() {
         let dt = datetime!(2018-02-11 07:09:22.765 UTC);
@@ -172,50 +179,8 @@ mod tests {
 
         let _have = request.to_signable_bytes(&mut got);
 
-        // the following vector is generated via:
-        // import (
-        // "encoding/hex"
-        // "fmt"
-        // prototypes "github.com/tendermint/tendermint/proto/tendermint/types"
-        // "github.com/tendermint/tendermint/types"
-        // "strings"
-        // "time"
-        // )
-        //
-        // func proposalSerialize() {
-        // stamp, _ := time.Parse(time.RFC3339Nano, "2018-02-11T07:09:22.765Z")
-        // block_hash, _ :=
-        // hex.DecodeString("DEADBEEFDEADBEEFBAFBAFBAFBAFBAFADEADBEEFDEADBEEFBAFBAFBAFBAFBAFA")
-        // part_hash, _ :=
-        // hex.DecodeString("0022446688AACCEE1133557799BBDDFF0022446688AACCEE1133557799BBDDFF")
-        // proposal := &types.Proposal{
-        // Type:     prototypes.SignedMsgType(prototypes.ProposalType),
-        // Height:   12345,
-        // Round:    23456,
-        // POLRound: -1,
-        // BlockID: types.BlockID{
-        // Hash: block_hash,
-        // PartSetHeader: types.PartSetHeader{
-        // Hash:  part_hash,
-        // Total: 65535,
-        // },
-        // },
-        // Timestamp: stamp,
-        // }
-        // signBytes := types.ProposalSignBytes("test_chain_id", proposal.ToProto())
-        // fmt.Println(strings.Join(strings.Split(fmt.Sprintf("%v", signBytes), " "), ", "))
-        // }
-
-        let want = vec![
-            136, 1, 8, 32, 17, 57, 48, 0, 0, 0, 0, 0, 0, 25, 160, 91, 0, 0, 0, 0, 0, 0, 32, 255,
-            255, 255, 255, 255, 255, 255, 255, 255, 1, 42, 74, 10, 32, 222, 173, 190, 239, 222,
-            173, 190, 239, 186, 251, 175, 186, 251, 175, 186, 250, 222, 173, 190, 239, 222, 173,
-            190, 239, 186, 251, 175, 186, 251, 175, 186, 250, 18, 38, 8, 255, 255, 3, 18, 32, 0,
-            34, 68, 102, 136, 170, 204, 238, 17, 51, 85, 119, 153, 187, 221, 255, 0, 34, 68, 102,
-            136, 170, 204, 238, 17, 51, 85, 119, 153, 187, 221, 255, 50, 12, 8, 162, 216, 255, 211,
-            5, 16, 192, 242, 227, 236, 2, 58, 13, 116, 101, 115, 116, 95, 99, 104, 97, 105, 110,
-            95, 105, 100,
-        ];
+        // The sign bytes generated with the Go implementation.
+        let want = golden_sign_bytes(include_str!("../tests/support/golden/proposal.json"));
 
         assert_eq!(got, want)
     }
@@ -254,47 +219,10 @@ mod tests {
 
         let _have = request.to_signable_bytes(&mut got);
 
-        // the following vector is generated via:
-        // import (
-        // "encoding/hex"
-        // "fmt"
-        // prototypes "github.com/tendermint/tendermint/proto/tendermint/types"
-        // "github.com/tendermint/tendermint/types"
-        // "strings"
-        // "time"
-        // )
-        //
-        // func proposalSerialize() {
-        // stamp, _ := time.Parse(time.RFC3339Nano, "2018-02-11T07:09:22.765Z")
-        // block_hash, _ := hex.DecodeString("")
-        // part_hash, _ :=
-        // hex.DecodeString("0022446688AACCEE1133557799BBDDFF0022446688AACCEE1133557799BBDDFF")
-        // proposal := &types.Proposal{
-        // Type:     prototypes.SignedMsgType(prototypes.ProposalType),
-        // Height:   12345,
-        // Round:    23456,
-        // POLRound: -1,
-        // BlockID: types.BlockID{
-        // Hash: block_hash,
-        // PartSetHeader: types.PartSetHeader{
-        // Hash:  part_hash,
-        // Total: 65535,
-        // },
-        // },
-        // Timestamp: stamp,
-        // }
-        // signBytes := types.ProposalSignBytes("test_chain_id", proposal.ToProto())
-        // fmt.Println(strings.Join(strings.Split(fmt.Sprintf("%v", signBytes), " "), ", "))
-        // }
-
-        let want = vec![
-            102, 8, 32, 17, 57, 48, 0, 0, 0, 0, 0, 0, 25, 160, 91, 0, 0, 0, 0, 0, 0, 32, 255, 255,
-            255, 255, 255, 255, 255, 255, 255, 1, 42, 40, 18, 38, 8, 255, 255, 3, 18, 32, 0, 34,
-            68, 102, 136, 170, 204, 238, 17, 51, 85, 119, 153, 187, 221, 255, 0, 34, 68, 102, 136,
-            170, 204, 238, 17, 51, 85, 119, 153, 187, 221, 255, 50, 12, 8, 162, 216, 255, 211, 5,
-            16, 192, 242, 227, 236, 2, 58, 13, 116, 101, 115, 116, 95, 99, 104, 97, 105, 110, 95,
-            105, 100,
-        ];
+        // The sign bytes generated with the Go implementation.
+        let want = golden_sign_bytes(include_str!(
+            "../tests/support/golden/proposal_empty_block_id.json"
+        ));
 
         assert_eq!(got, want)
     }
@@ -0,0 +1,352 @@
+//! Registry routing protobuf `Any` messages to evidence and public key types.
+//!
+//! Chains built on Tendermint sometimes extend it with evidence kinds or key
+//! types of their own. A [`Registry`] maps the type URLs of such extensions to
+//! decoders producing trait objects, so that they can be decoded next to the
+//! types known to this crate instead of failing. Registries are not global:
+//! each chain gets its own, and the type URLs registered for one chain do not
+//! affect the decoding of another's.
+
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+pub use prost_types::Any;
+use tendermint_proto::{crypto as raw_crypto, types as raw, Protobuf};
+
+use crate::{
+    error::Error,
+    evidence::{DuplicateVoteEvidence, Evidence, LightClientAttackEvidence},
+    prelude::*,
+    PublicKey,
+};
+
+/// Type URL of [`tendermint_proto::types::Evidence`].
+pub const EVIDENCE_TYPE_URL: &str = "/tendermint.types.Evidence";
+
+/// Type URL of [`tendermint_proto::types::DuplicateVoteEvidence`].
+pub const DUPLICATE_VOTE_EVIDENCE_TYPE_URL: &str = "/tendermint.types.DuplicateVoteEvidence";
+
+/// Type URL of [`tendermint_proto::types::LightClientAttackEvidence`].
+pub const LIGHT_CLIENT_ATTACK_EVIDENCE_TYPE_URL: &str =
+    "/tendermint.types.LightClientAttackEvidence";
+
+/// Type URL of [`tendermint_proto::crypto::PublicKey`].
+pub const PUBLIC_KEY_TYPE_URL: &str = "/tendermint.crypto.PublicKey";
+
+const BUILTIN_EVIDENCE_TYPE_URLS: [&str; 3] = [
+    EVIDENCE_TYPE_URL,
+    DUPLICATE_VOTE_EVIDENCE_TYPE_URL,
+    LIGHT_CLIENT_ATTACK_EVIDENCE_TYPE_URL,
+];
+
+/// Evidence of a kind defined outside of Tendermint.
+pub trait CustomEvidence: fmt::Debug + Send + Sync {
+    /// Type URL under which the evidence is encoded.
+    fn type_url(&self) -> &str;
+
+    /// Protobuf encoding of the evidence.
+    fn encode_to_vec(&self) -> Vec<u8>;
+}
+
+/// Public key of a type defined outside of Tendermint.
+pub trait CustomPublicKey: fmt::Debug + Send + Sync {
+    /// Type URL under which the key is encoded.
+    fn type_url(&self) -> &str;
+
+    /// Protobuf encoding of the key.
+    fn encode_to_vec(&self) -> Vec<u8>;
+}
+
+/// Evidence decoded by a [`Registry`].
+#[derive(Debug)]
+pub enum AnyEvidence {
+    /// Evidence of a kind known to this crate
+    Known(Evidence),
+
+    /// Evidence of a kind registered with the registry
+    Custom(Box<dyn CustomEvidence>),
+}
+
+impl AnyEvidence {
+    /// Type URL under which the evidence is encoded.
+    pub fn type_url(&self) -> &str {
+        match self {
+            Self::Known(Evidence::DuplicateVote(_)) => DUPLICATE_VOTE_EVIDENCE_TYPE_URL,
+            Self::Known(Evidence::LightClientAttack(_)) => LIGHT_CLIENT_ATTACK_EVIDENCE_TYPE_URL,
+            Self::Custom(evidence) => evidence.type_url(),
+        }
+    }
+}
+
+impl From<AnyEvidence> for Any {
+    fn from(evidence: AnyEvidence) -> Self {
+        let type_url = evidence.type_url().to_owned();
+        let value = match evidence {
+            AnyEvidence::Known(Evidence::DuplicateVote(ev)) => {
+                Protobuf::<raw::DuplicateVoteEvidence>::encode_vec(*ev)
+            },
+            AnyEvidence::Known(Evidence::LightClientAttack(ev)) => {
+                Protobuf::<raw::LightClientAttackEvidence>::encode_vec(*ev)
+            },
+            AnyEvidence::Custom(ev) => ev.encode_to_vec(),
+        };
+        Any { type_url, value }
+    }
+}
+
+/// Public key decoded by a [`Registry`].
+#[derive(Debug)]
+pub enum AnyPublicKey {
+    /// Key of a type known to this crate
+    Known(PublicKey),
+
+    /// Key of a type registered with the registry
+    Custom(Box<dyn CustomPublicKey>),
+}
+
+impl AnyPublicKey {
+    /// Type URL under which the key is encoded.
+    pub fn type_url(&self) -> &str {
+        match self {
+            Self::Known(_) => PUBLIC_KEY_TYPE_URL,
+            Self::Custom(key) => key.type_url(),
+        }
+    }
+}
+
+impl From<AnyPublicKey> for Any {
+    fn from(key: AnyPublicKey) -> Self {
+        let type_url = key.type_url().to_owned();
+        let value = match key {
+            AnyPublicKey::Known(pk) => Protobuf::<raw_crypto::PublicKey>::encode_vec(pk),
+            AnyPublicKey::Custom(pk) => pk.encode_to_vec(),
+        };
+        Any { type_url, value }
+    }
+}
+
+type EvidenceDecoder = Box<dyn Fn(&[u8]) -> Result<Box<dyn CustomEvidence>, Error> + Send + Sync>;
+
+type PublicKeyDecoder = Box<dyn Fn(&[u8]) -> Result<Box<dyn CustomPublicKey>, Error> + Send + Sync>;
+
+/// Decoders of the evidence kinds and public key types of a chain, by type
+/// URL.
+///
+/// The types known to this crate are always decoded and their type URLs
+/// cannot be registered.
+#[derive(Default)]
+pub struct Registry {
+    evidence: BTreeMap<String, EvidenceDecoder>,
+    public_keys: BTreeMap<String, PublicKeyDecoder>,
+}
+
+impl Registry {
+    /// Create a registry decoding only the types known to this crate.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route the evidence of the given type URL to `decode`.
+    ///
+    /// Fails if the type URL is already registered or is the one of an
+    /// evidence kind known to this crate.
+    pub fn register_evidence<F>(
+        &mut self,
+        type_url: impl Into<String>,
+        decode: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&[u8]) -> Result<Box<dyn CustomEvidence>, Error> + Send + Sync + 'static,
+    {
+        let type_url = type_url.into();
+        if BUILTIN_EVIDENCE_TYPE_URLS.contains(&type_url.as_str())
+            || self.evidence.contains_key(&type_url)
+        {
+            return Err(Error::duplicate_type_url(type_url));
+        }
+        self.evidence.insert(type_url, Box::new(decode));
+        Ok(())
+    }
+
+    /// Route the public keys of the given type URL to `decode`.
+    ///
+    /// Fails if the type URL is already registered or is the one of
+    /// [`PublicKey`].
+    pub fn register_public_key<F>(
+        &mut self,
+        type_url: impl Into<String>,
+        decode: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&[u8]) -> Result<Box<dyn CustomPublicKey>, Error> + Send + Sync + 'static,
+    {
+        let type_url = type_url.into();
+        if type_url == PUBLIC_KEY_TYPE_URL || self.public_keys.contains_key(&type_url) {
+            return Err(Error::duplicate_type_url(type_url));
+        }
+        self.public_keys.insert(type_url, Box::new(decode));
+        Ok(())
+    }
+
+    /// Type URLs of the registered evidence kinds.
+    pub fn evidence_type_urls(&self) -> impl Iterator<Item = &str> {
+        self.evidence.keys().map(String::as_str)
+    }
+
+    /// Type URLs of the registered public key types.
+    pub fn public_key_type_urls(&self) -> impl Iterator<Item = &str> {
+        self.public_keys.keys().map(String::as_str)
+    }
+
+    /// Decode evidence of a known or registered kind.
+    pub fn decode_evidence(&self, any: &Any) -> Result<AnyEvidence, Error> {
+        let decode_error = |e: tendermint_proto::Error| Error::decode_any(any.type_url.clone(), e);
+        match any.type_url.as_str() {
+            EVIDENCE_TYPE_URL => <Evidence as Protobuf<raw::Evidence>>::decode_vec(&any.value)
+                .map(AnyEvidence::Known)
+                .map_err(decode_error),
+            DUPLICATE_VOTE_EVIDENCE_TYPE_URL => <DuplicateVoteEvidence as Protobuf<
+                raw::DuplicateVoteEvidence,
+            >>::decode_vec(&any.value)
+            .map(|ev| AnyEvidence::Known(Evidence::from(ev)))
+            .map_err(decode_error),
+            LIGHT_CLIENT_ATTACK_EVIDENCE_TYPE_URL => <LightClientAttackEvidence as Protobuf<
+                raw::LightClientAttackEvidence,
+            >>::decode_vec(&any.value)
+            .map(|ev| AnyEvidence::Known(Evidence::from(ev)))
+            .map_err(decode_error),
+            type_url => {
+                let decode = self
+                    .evidence
+                    .get(type_url)
+                    .ok_or_else(|| Error::unknown_type_url(type_url.to_owned()))?;
+                decode(&any.value).map(AnyEvidence::Custom)
+            },
+        }
+    }
+
+    /// Decode a public key of a known or registered type.
+    pub fn decode_public_key(&self, any: &Any) -> Result<AnyPublicKey, Error> {
+        match any.type_url.as_str() {
+            PUBLIC_KEY_TYPE_URL => {
+                <PublicKey as Protobuf<raw_crypto::PublicKey>>::decode_vec(&any.value)
+                    .map(AnyPublicKey::Known)
+                    .map_err(|e| Error::decode_any(any.type_url.clone(), e))
+            },
+            type_url => {
+                let decode = self
+                    .public_keys
+                    .get(type_url)
+                    .ok_or_else(|| Error::unknown_type_url(type_url.to_owned()))?;
+                decode(&any.value).map(AnyPublicKey::Custom)
+            },
+        }
+    }
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registry")
+            .field("evidence", &self.evidence.keys().collect::<Vec<_>>())
+            .field("public_keys", &self.public_keys.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorDetail;
+
+    const BLS_TYPE_URL: &str = "/consortium.crypto.bls12_381.PubKey";
+
+    #[derive(Debug, PartialEq)]
+    struct BlsPublicKey(Vec<u8>);
+
+    impl CustomPublicKey for BlsPublicKey {
+        fn type_url(&self) -> &str {
+            BLS_TYPE_URL
+        }
+
+        fn encode_to_vec(&self) -> Vec<u8> {
+            self.0.clone()
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry
+            .register_public_key(BLS_TYPE_URL, |bytes| {
+                if bytes.len() != 48 {
+                    return Err(Error::invalid_key("expected 48 bytes".to_string()));
+                }
+                Ok(Box::new(BlsPublicKey(bytes.to_vec())))
+            })
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn known_public_keys_round_trip() {
+        let key = PublicKey::from_raw_ed25519(&[7; 32]).unwrap();
+        let any = Any::from(AnyPublicKey::Known(key));
+        assert_eq!(any.type_url, PUBLIC_KEY_TYPE_URL);
+
+        match registry().decode_public_key(&any).unwrap() {
+            AnyPublicKey::Known(decoded) => assert_eq!(decoded, key),
+            other => panic!("unexpected key: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn registered_public_keys_are_routed_to_their_decoder() {
+        let any = Any {
+            type_url: BLS_TYPE_URL.to_string(),
+            value: vec![1; 48],
+        };
+        let decoded = registry().decode_public_key(&any).unwrap();
+        assert_eq!(decoded.type_url(), BLS_TYPE_URL);
+        assert_eq!(Any::from(decoded), any);
+
+        let malformed = Any {
+            type_url: BLS_TYPE_URL.to_string(),
+            value: vec![1; 3],
+        };
+        assert!(registry().decode_public_key(&malformed).is_err());
+    }
+
+    #[test]
+    fn unknown_type_urls_are_rejected() {
+        let any = Any {
+            type_url: "/consortium.evidence.DoubleProposal".to_string(),
+            value: vec![],
+        };
+        let err = registry().decode_evidence(&any).unwrap_err();
+        assert!(matches!(err.detail(), ErrorDetail::UnknownTypeUrl(_)));
+    }
+
+    #[test]
+    fn known_type_urls_cannot_be_registered() {
+        let mut registry = registry();
+        let err = registry
+            .register_public_key(PUBLIC_KEY_TYPE_URL, |bytes| {
+                Ok(Box::new(BlsPublicKey(bytes.to_vec())))
+            })
+            .unwrap_err();
+        assert!(matches!(err.detail(), ErrorDetail::DuplicateTypeUrl(_)));
+
+        let err = registry
+            .register_public_key(BLS_TYPE_URL, |bytes| {
+                Ok(Box::new(BlsPublicKey(bytes.to_vec())))
+            })
+            .unwrap_err();
+        assert!(matches!(err.detail(), ErrorDetail::DuplicateTypeUrl(_)));
+
+        let err = registry
+            .register_evidence(DUPLICATE_VOTE_EVIDENCE_TYPE_URL, |_| {
+                Err(Error::invalid_evidence())
+            })
+            .unwrap_err();
+        assert!(matches!(err.detail(), ErrorDetail::DuplicateTypeUrl(_)));
+    }
+}
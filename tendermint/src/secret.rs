@@ -0,0 +1,215 @@
+//! Redaction of sensitive material from `Debug` output and tracing fields.
+//!
+//! Key material, such as private keys and the ephemeral secrets of secret
+//! connections, is always redacted. Other sensitive data, such as the sign
+//! bytes of votes and proposals, is redacted as well by default, but can be
+//! disclosed at runtime to debug signing issues (see [`set_redaction`]).
+
+use core::{
+    fmt,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use serde::{Deserialize, Serialize};
+use subtle_encoding::{Encoding, Hex};
+
+use crate::prelude::*;
+
+/// The placeholder written in place of redacted material.
+pub const REDACTED: &str = "[redacted]";
+
+/// What is redacted from `Debug` output.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Redaction {
+    /// Redact key material and sensitive data
+    #[default]
+    All,
+
+    /// Redact key material only, disclosing sensitive data such as sign
+    /// bytes
+    KeysOnly,
+}
+
+static REDACTION: AtomicU8 = AtomicU8::new(0);
+
+/// Set what is redacted from `Debug` output, for the whole process.
+pub fn set_redaction(redaction: Redaction) {
+    let value = match redaction {
+        Redaction::All => 0,
+        Redaction::KeysOnly => 1,
+    };
+    REDACTION.store(value, Ordering::Relaxed);
+}
+
+/// What is currently redacted from `Debug` output.
+pub fn redaction() -> Redaction {
+    match REDACTION.load(Ordering::Relaxed) {
+        1 => Redaction::KeysOnly,
+        _ => Redaction::All,
+    }
+}
+
+/// Key material, which is always redacted from `Debug` output.
+///
+/// ```
+/// use tendermint::secret::Secret;
+///
+/// let key = Secret::new([0x42u8; 32]);
+/// assert_eq!(format!("{key:?}"), "[redacted]");
+/// assert_eq!(key.expose_secret()[0], 0x42);
+/// ```
+#[derive(Clone, Default)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    /// Wrap the given secret.
+    pub fn new(secret: T) -> Self {
+        Self(secret)
+    }
+
+    /// Borrow the wrapped secret.
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+
+    /// Mutably borrow the wrapped secret.
+    pub fn expose_secret_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+
+    /// Unwrap the secret.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(secret: T) -> Self {
+        Self(secret)
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+/// Sensitive bytes, such as sign bytes, which are redacted from `Debug`
+/// output unless [`Redaction::KeysOnly`] is set, in which case they are
+/// written in hexadecimal. They are serialized as is.
+///
+/// ```
+/// use tendermint::secret::{set_redaction, Redaction, Sensitive};
+///
+/// let sign_bytes = Sensitive::new(vec![0xAB, 0xCD]);
+/// assert_eq!(format!("{sign_bytes:?}"), "[redacted]");
+/// set_redaction(Redaction::KeysOnly);
+/// assert_eq!(format!("{sign_bytes:?}"), "ABCD");
+/// # set_redaction(Redaction::All);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Sensitive<T>(T);
+
+impl<T> Sensitive<T> {
+    /// Wrap the given sensitive data.
+    pub fn new(data: T) -> Self {
+        Self(data)
+    }
+
+    /// Borrow the wrapped data.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Unwrap the data.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Sensitive<T> {
+    fn from(data: T) -> Self {
+        Self(data)
+    }
+}
+
+impl<T: AsRef<[u8]>> fmt::Debug for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match redaction() {
+            Redaction::All => f.write_str(REDACTED),
+            Redaction::KeysOnly => {
+                f.write_str(&Hex::upper_case().encode_to_string(self.0.as_ref()).unwrap())
+            },
+        }
+    }
+}
+
+/// Assert that the given `Debug` output has the given bytes redacted, in
+/// every representation they could be written in. For use in tests.
+#[doc(hidden)]
+pub fn assert_redacted(output: &str, bytes: &[u8]) {
+    let upper = Hex::upper_case().encode_to_string(bytes).unwrap();
+    let lower = Hex::lower_case().encode_to_string(bytes).unwrap();
+    let base64 = subtle_encoding::Base64::default()
+        .encode_to_string(bytes)
+        .unwrap();
+    let decimal = format!("{bytes:?}");
+    let decimal = decimal.trim_matches(|c| c == '[' || c == ']');
+    for encoded in [upper.as_str(), lower.as_str(), base64.as_str(), decimal] {
+        // Look for a prefix, in case the bytes are written out partially.
+        assert!(
+            !output.contains(&encoded[..8]),
+            "{output} contains {encoded}"
+        );
+    }
+    assert!(output.contains(REDACTED), "{output}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{crypto::ed25519::SigningKey, PrivateKey};
+
+    const KEY_BYTES: [u8; 32] = [
+        0x19, 0x12, 0x83, 0x71, 0xFE, 0xA4, 0xAF, 0x15, 0xEA, 0xD9, 0xCB, 0x11, 0x98, 0x80, 0xB4,
+        0x4C, 0xD3, 0xBF, 0x49, 0x9A, 0xA8, 0x0C, 0x8C, 0x4D, 0xEC, 0x96, 0xF1, 0x7A, 0x14, 0x27,
+        0x8E, 0xA1,
+    ];
+
+    #[test]
+    fn key_material_is_redacted() {
+        let signing_key = SigningKey::try_from(&KEY_BYTES[..]).unwrap();
+        let private_key = PrivateKey::Ed25519(signing_key.clone());
+
+        for output in [
+            format!("{signing_key:?}"),
+            format!("{signing_key:#?}"),
+            format!("{private_key:?}"),
+            format!("{private_key:#?}"),
+            format!("{:?}", Secret::new(KEY_BYTES)),
+            format!("{:#?}", Secret::new(KEY_BYTES)),
+        ] {
+            assert_redacted(&output, &KEY_BYTES);
+        }
+    }
+
+    #[test]
+    fn sensitive_data_is_only_disclosed_on_demand() {
+        let sign_bytes = Sensitive::new(KEY_BYTES.to_vec());
+        assert_eq!(redaction(), Redaction::All);
+        assert_redacted(&format!("{sign_bytes:?}"), &KEY_BYTES);
+
+        set_redaction(Redaction::KeysOnly);
+        let disclosed = format!("{sign_bytes:?}");
+        let secret = format!("{:?}", Secret::new(KEY_BYTES));
+        set_redaction(Redaction::All);
+
+        assert_eq!(
+            disclosed,
+            Hex::upper_case().encode_to_string(KEY_BYTES).unwrap()
+        );
+        assert_redacted(&secret, &KEY_BYTES);
+    }
+}
@@ -0,0 +1,53 @@
+//! Lifecycle of the long-running components provided by the crates of this
+//! project, such as the ABCI server or the WebSocket RPC client, which run in
+//! the background once started.
+
+use core::time::Duration;
+
+use crate::prelude::*;
+
+/// The health of a [`Service`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Health {
+    /// The service has not been started yet.
+    NotStarted,
+    /// The service is running.
+    Running,
+    /// The service was shut down, or completed its work.
+    Stopped,
+    /// The service stopped because of the given error.
+    Failed(String),
+}
+
+impl Health {
+    /// Whether the service is running.
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::Running)
+    }
+}
+
+/// A component running background tasks, which embedders start and shut
+/// down through this common interface.
+pub trait Service {
+    /// The error starting or shutting down the service.
+    type Error;
+
+    /// Start the background tasks of the service.
+    ///
+    /// Fails if the service was already started.
+    fn start(&mut self) -> Result<(), Self::Error>;
+
+    /// The current health of the service.
+    fn health(&self) -> Health;
+
+    /// Ask the service to stop, blocking until it did.
+    ///
+    /// The background tasks still running after the given deadline are
+    /// abandoned, or aborted if they can be, and an error is returned.
+    /// Shutting down a service which is not running does nothing.
+    ///
+    /// As this blocks the calling thread, services running on an async
+    /// runtime should be shut down from outside of it, e.g. with
+    /// `tokio::task::spawn_blocking`.
+    fn shutdown(&mut self, deadline: Duration) -> Result<(), Self::Error>;
+}
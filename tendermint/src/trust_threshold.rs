@@ -3,6 +3,7 @@
 use core::{
     convert::TryFrom,
     fmt::{self, Debug, Display},
+    str::FromStr,
 };
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -60,7 +61,7 @@ impl TrustThresholdFraction {
         if denominator == 0 {
             return Err(Error::undefined_trust_threshold());
         }
-        if 3 * numerator < denominator {
+        if 3 * u128::from(numerator) < u128::from(denominator) {
             return Err(Error::trust_threshold_too_small());
         }
         Ok(Self {
@@ -98,8 +99,14 @@ impl From<TrustThresholdFraction> for RawTrustThresholdFraction {
 }
 
 impl TrustThreshold for TrustThresholdFraction {
+    /// Check whether `signed_voting_power / total_voting_power` is greater
+    /// than this fraction.
+    ///
+    /// The fractions are compared exactly, by cross-multiplying them as
+    /// 128-bit integers, which cannot overflow.
     fn is_enough_power(&self, signed_voting_power: u64, total_voting_power: u64) -> bool {
-        signed_voting_power * self.denominator > total_voting_power * self.numerator
+        u128::from(signed_voting_power) * u128::from(self.denominator)
+            > u128::from(total_voting_power) * u128::from(self.numerator)
     }
 }
 
@@ -115,6 +122,27 @@ impl Display for TrustThresholdFraction {
     }
 }
 
+/// Parses a trust threshold formatted as `numerator/denominator`, e.g. `2/3`,
+/// as it is displayed.
+impl FromStr for TrustThresholdFraction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numerator, denominator) = s.split_once('/').ok_or_else(|| {
+            Error::parse(format!(
+                "trust threshold {s} is not of the form numerator/denominator"
+            ))
+        })?;
+        let parse = |n: &str| {
+            n.trim()
+                .parse()
+                .map_err(|e| Error::parse_int(s.to_string(), e))
+        };
+
+        Self::new(parse(numerator)?, parse(denominator)?)
+    }
+}
+
 /// Facilitates validation of [`TrustThresholdFraction`] instances when
 /// deserializing them.
 #[derive(Serialize, Deserialize)]
@@ -188,5 +216,37 @@ mod test {
             assert!(TrustThresholdFraction::new(num, num).is_ok());
             assert!(from_json(num, num).is_ok());
         }
+
+        #[test]
+        fn parses_displayed((num, denom) in arb_correct_frac(1000)) {
+            let frac = TrustThresholdFraction::new(num, denom).unwrap();
+            assert_eq!(frac.to_string().parse::<TrustThresholdFraction>().unwrap(), frac);
+        }
+
+        #[test]
+        fn is_enough_power_is_exact(total in 1..u64::MAX / 3) {
+            // Exactly one third of the total is not more than one third.
+            let third = TrustThresholdFraction::ONE_THIRD;
+            assert!(!third.is_enough_power(total, 3 * total));
+            assert!(third.is_enough_power(total + 1, 3 * total));
+        }
+    }
+
+    #[test]
+    fn no_overflow() {
+        assert!(TrustThresholdFraction::new(u64::MAX / 2, u64::MAX).is_ok());
+        assert!(TrustThresholdFraction::new(u64::MAX / 4, u64::MAX).is_err());
+
+        let frac = TrustThresholdFraction::new(u64::MAX - 1, u64::MAX).unwrap();
+        assert!(frac.is_enough_power(u64::MAX, u64::MAX));
+        assert!(!frac.is_enough_power(u64::MAX - 1, u64::MAX));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!("2/3".parse::<TrustThresholdFraction>().is_ok());
+        assert!("1/4".parse::<TrustThresholdFraction>().is_err());
+        assert!("2:3".parse::<TrustThresholdFraction>().is_err());
+        assert!("two/3".parse::<TrustThresholdFraction>().is_err());
     }
 }
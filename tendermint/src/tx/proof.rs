@@ -2,7 +2,12 @@ use serde::{Deserialize, Serialize};
 use tendermint_proto::v0_37::types::TxProof as RawTxProof;
 use tendermint_proto::Protobuf;
 
-use crate::{merkle, prelude::*, Error, Hash};
+use crate::{
+    crypto::Sha256,
+    merkle::{self, MerkleHash},
+    prelude::*,
+    Error, Hash,
+};
 
 /// Merkle proof of the presence of a transaction in the Merkle tree.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -13,6 +18,31 @@ pub struct Proof {
     pub proof: merkle::Proof,
 }
 
+impl Proof {
+    /// Verify that this proof shows the inclusion of the transaction in a
+    /// block whose header has the given `data_hash`.
+    #[cfg(feature = "rust-crypto")]
+    pub fn verify(&self, data_hash: &Hash) -> Result<(), Error> {
+        self.verify_with::<crate::crypto::default::Sha256>(data_hash)
+    }
+
+    /// Verify this proof against the given `data_hash` with a Merkle hasher
+    /// provided by a crypto provider.
+    pub fn verify_with<H>(&self, data_hash: &Hash) -> Result<(), Error>
+    where
+        H: MerkleHash + Sha256 + Default,
+    {
+        if &self.root_hash != data_hash {
+            return Err(Error::invalid_proof(
+                "root hash does not match the data hash".to_string(),
+            ));
+        }
+        // The leaves of the block data Merkle tree are transaction hashes.
+        let leaf = H::digest(&self.data);
+        self.proof.verify::<H>(&self.root_hash, &leaf)
+    }
+}
+
 impl Protobuf<RawTxProof> for Proof {}
 
 impl TryFrom<RawTxProof> for Proof {
@@ -36,3 +66,79 @@ impl From<Proof> for RawTxProof {
         }
     }
 }
+
+#[cfg(all(test, feature = "rust-crypto"))]
+mod tests {
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::{hash::Algorithm, merkle::simple_hash_from_byte_vectors};
+
+    fn proof_for(txs: &[&[u8]], index: usize) -> Proof {
+        let leaves: Vec<[u8; 32]> = txs.iter().map(|tx| Sha256::digest(tx).into()).collect();
+        let root = simple_hash_from_byte_vectors::<Sha256>(&leaves);
+
+        // Collect the aunts from the leaf's sibling up to the root's child.
+        fn aunts(leaves: &[[u8; 32]], index: usize, out: &mut Vec<Hash>) {
+            if leaves.len() <= 1 {
+                return;
+            }
+            let split = leaves.len().next_power_of_two() / 2;
+            let (left, right) = leaves.split_at(split);
+            let (subtree, sibling, index) = if index < split {
+                (left, right, index)
+            } else {
+                (right, left, index - split)
+            };
+            aunts(subtree, index, out);
+            let sibling = simple_hash_from_byte_vectors::<Sha256>(sibling);
+            out.push(Hash::Sha256(sibling));
+        }
+        let mut proof_aunts = Vec::new();
+        aunts(&leaves, index, &mut proof_aunts);
+
+        Proof {
+            root_hash: Hash::Sha256(root),
+            data: txs[index].to_vec(),
+            proof: merkle::Proof {
+                total: txs.len() as u64,
+                index: index as u64,
+                leaf_hash: Hash::Sha256(Sha256::default().leaf_hash(&leaves[index])),
+                aunts: proof_aunts,
+            },
+        }
+    }
+
+    #[test]
+    fn verifies_inclusion() {
+        let txs: [&[u8]; 5] = [b"tx1", b"tx2", b"tx3", b"tx4", b"tx5"];
+        for index in 0..txs.len() {
+            let proof = proof_for(&txs, index);
+            proof.verify(&proof.root_hash.clone()).unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_data_hash() {
+        let proof = proof_for(&[b"tx1", b"tx2"], 1);
+        let other = Hash::from_bytes(Algorithm::Sha256, &[0; 32]).unwrap();
+        assert!(proof.verify(&other).is_err());
+    }
+
+    #[test]
+    fn rejects_tampered_proof() {
+        let txs: [&[u8]; 3] = [b"tx1", b"tx2", b"tx3"];
+
+        let mut proof = proof_for(&txs, 0);
+        proof.data = b"tx4".to_vec();
+        assert!(proof.verify(&proof.root_hash.clone()).is_err());
+
+        let mut proof = proof_for(&txs, 2);
+        proof.proof.index = 1;
+        assert!(proof.verify(&proof.root_hash.clone()).is_err());
+
+        let mut proof = proof_for(&txs, 1);
+        proof.proof.aunts.pop();
+        assert!(proof.verify(&proof.root_hash.clone()).is_err());
+    }
+}
@@ -1,5 +1,8 @@
 //! Tendermint validators
 
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::fmt;
+
 use serde::{Deserialize, Serialize};
 use tendermint_proto::v0_37::types::SimpleValidator as RawSimpleValidator;
 use tendermint_proto::Protobuf;
@@ -12,7 +15,7 @@ use crate::{
     merkle::{self, MerkleHash},
     prelude::*,
     public_key::deserialize_public_key,
-    vote, Error, PublicKey, Signature,
+    vote, Error, Moniker, PublicKey, Signature,
 };
 
 /// Validator set contains a vector of validators
@@ -43,6 +46,38 @@ impl Set {
         }
     }
 
+    /// Constructor for validators from untrusted sources, which may come in
+    /// any order and contain duplicates.
+    ///
+    /// The validators are sorted in canonical order and identical entries are
+    /// only kept once, but entries sharing an address while differing in
+    /// their public key or voting power are rejected.
+    pub fn try_from_unordered(
+        mut validators: Vec<Info>,
+        proposer: Option<Info>,
+    ) -> Result<Set, Error> {
+        Self::sort_validators(&mut validators);
+
+        let mut deduplicated: Vec<Info> = Vec::with_capacity(validators.len());
+        let mut seen = BTreeMap::new();
+        for validator in validators {
+            match seen.get(&validator.address) {
+                Some(&(pub_key, power))
+                    if (pub_key, power) == (validator.pub_key, validator.power) =>
+                {
+                    continue
+                },
+                Some(_) => return Err(Error::duplicate_validator(validator.address)),
+                None => {
+                    seen.insert(validator.address, (validator.pub_key, validator.power));
+                    deduplicated.push(validator);
+                },
+            }
+        }
+
+        Ok(Self::new(deduplicated, proposer))
+    }
+
     /// Convenience constructor for cases where there is no proposer
     pub fn without_proposer(validators: Vec<Info>) -> Set {
         Self::new(validators, None)
@@ -86,6 +121,37 @@ impl Set {
         vals.sort_by_key(|v| (core::cmp::Reverse(v.power), v.address));
     }
 
+    /// Ensure that the validators are in canonical order, i.e. by voting
+    /// power descending, then by address ascending, without duplicates.
+    ///
+    /// Validator sets built with the constructors are always in canonical
+    /// order, but deserialized ones are kept in the order they came in.
+    pub fn ensure_canonical_order(&self) -> Result<(), Error> {
+        let mut addresses = BTreeSet::new();
+        for validator in &self.validators {
+            if !addresses.insert(validator.address) {
+                return Err(Error::duplicate_validator(validator.address));
+            }
+        }
+
+        for (index, pair) in self.validators.windows(2).enumerate() {
+            let (first, second) = (&pair[0], &pair[1]);
+            if (core::cmp::Reverse(first.power), first.address)
+                > (core::cmp::Reverse(second.power), second.address)
+            {
+                return Err(Error::unordered_validators(
+                    index,
+                    first.address,
+                    first.power(),
+                    second.address,
+                    second.power(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Returns the validator with the given Id if its in the Set.
     pub fn validator(&self, val_id: account::Id) -> Option<Info> {
         self.validators
@@ -100,6 +166,25 @@ impl Set {
         self.hash_with::<crate::crypto::default::Sha256>()
     }
 
+    /// Compute the hash of this validator set, failing if the validators are
+    /// not in canonical order (see [`Set::ensure_canonical_order`]), as the
+    /// hash would then differ from the one computed by other nodes.
+    #[cfg(feature = "rust-crypto")]
+    pub fn try_hash(&self) -> Result<Hash, Error> {
+        self.try_hash_with::<crate::crypto::default::Sha256>()
+    }
+
+    /// Compute the hash of this validator set with a SHA256 hasher provided
+    /// by a crypto provider, failing if the validators are not in canonical
+    /// order.
+    pub fn try_hash_with<H>(&self) -> Result<Hash, Error>
+    where
+        H: MerkleHash + Sha256 + Default,
+    {
+        self.ensure_canonical_order()?;
+        Ok(self.hash_with::<H>())
+    }
+
     /// Hash this header with a SHA256 hasher provided by a crypto provider.
     pub fn hash_with<H>(&self) -> Hash
     where
@@ -222,6 +307,104 @@ impl ProposerPriority {
     }
 }
 
+/// The monikers of validators by address, for human readable output such as
+/// logs.
+///
+/// The registry is supplied by the user, e.g. compiled from the descriptions
+/// of the validators of a chain, as validator sets do not carry monikers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Registry {
+    monikers: BTreeMap<account::Id, Moniker>,
+    bech32_prefix: Option<String>,
+}
+
+impl Registry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Label validators by their Bech32 address with the given human
+    /// readable prefix, e.g. `cosmosvalcons`, rather than by their hex
+    /// address
+    pub fn bech32_prefix(mut self, hrp: impl Into<String>) -> Self {
+        self.bech32_prefix = Some(hrp.into());
+        self
+    }
+
+    /// Register the moniker of a validator, replacing any previous one
+    pub fn insert(&mut self, address: account::Id, moniker: Moniker) -> Option<Moniker> {
+        self.monikers.insert(address, moniker)
+    }
+
+    /// Register the names of the validators in the given set which have one
+    pub fn extend_from_set(&mut self, set: &Set) {
+        for validator in set.validators() {
+            if let Some(moniker) = validator.name.as_ref().and_then(|n| n.parse().ok()) {
+                self.insert(validator.address, moniker);
+            }
+        }
+    }
+
+    /// The moniker of the validator with the given address, if registered
+    pub fn moniker(&self, address: &account::Id) -> Option<&Moniker> {
+        self.monikers.get(address)
+    }
+
+    /// The address of the validator with the given moniker, if registered
+    pub fn address(&self, moniker: &str) -> Option<account::Id> {
+        self.monikers
+            .iter()
+            .find(|(_, m)| m.as_ref() == moniker)
+            .map(|(address, _)| *address)
+    }
+
+    /// Format the address of a validator along with its moniker, if known,
+    /// as e.g. `my-validator (0CDA3F47EF3C4906693B170EF650EB968C5F4B2C)`
+    pub fn label(&self, address: account::Id) -> Label<'_> {
+        Label {
+            registry: self,
+            address,
+        }
+    }
+}
+
+impl FromIterator<(account::Id, Moniker)> for Registry {
+    fn from_iter<I: IntoIterator<Item = (account::Id, Moniker)>>(iter: I) -> Self {
+        Self {
+            monikers: iter.into_iter().collect(),
+            bech32_prefix: None,
+        }
+    }
+}
+
+impl Extend<(account::Id, Moniker)> for Registry {
+    fn extend<I: IntoIterator<Item = (account::Id, Moniker)>>(&mut self, iter: I) {
+        self.monikers.extend(iter)
+    }
+}
+
+/// The address of a validator along with its moniker, as formatted by
+/// [`Registry::label`]
+#[derive(Copy, Clone, Debug)]
+pub struct Label<'a> {
+    registry: &'a Registry,
+    address: account::Id,
+}
+
+impl fmt::Display for Label<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let address = match &self.registry.bech32_prefix {
+            Some(hrp) => self.address.to_bech32(hrp),
+            None => self.address.to_hex(),
+        };
+        match self.registry.moniker(&self.address) {
+            Some(moniker) => write!(f, "{moniker} ({address})"),
+            None => write!(f, "{address}"),
+        }
+    }
+}
+
 /// A change to the validator set.
 ///
 /// Used to inform Tendermint of changes to the validator set.
@@ -374,6 +557,69 @@ mod tests {
             Info::new(pk, vote::Power::try_from(vp).unwrap())
         }
 
+        #[test]
+        fn test_registry() {
+            let mut named = make_validator(vec![1; 32], 10);
+            named.name = Some("alice".to_string());
+            let unnamed = make_validator(vec![2; 32], 10);
+            let set = Set::without_proposer(vec![named.clone(), unnamed.clone()]);
+
+            let mut registry = Registry::new();
+            registry.extend_from_set(&set);
+            assert_eq!(registry.moniker(&named.address).unwrap().as_ref(), "alice");
+            assert_eq!(registry.moniker(&unnamed.address), None);
+            assert_eq!(registry.address("alice"), Some(named.address));
+
+            assert_eq!(
+                registry.label(named.address).to_string(),
+                format!("alice ({})", named.address)
+            );
+            assert_eq!(
+                registry.label(unnamed.address).to_string(),
+                unnamed.address.to_string()
+            );
+
+            let registry = registry.bech32_prefix("cosmosvalcons");
+            assert_eq!(
+                registry.label(named.address).to_string(),
+                format!("alice ({})", named.address.to_bech32("cosmosvalcons"))
+            );
+        }
+
+        #[test]
+        fn test_canonical_order() {
+            let v1 = make_validator(vec![1; 32], 10);
+            let v2 = make_validator(vec![2; 32], 20);
+            let v3 = make_validator(vec![3; 32], 10);
+
+            let ordered = Set::without_proposer(vec![v1.clone(), v2.clone(), v3.clone()]);
+            ordered.ensure_canonical_order().unwrap();
+            assert_eq!(ordered.try_hash().unwrap(), ordered.hash());
+
+            // Deserialized sets are kept as is.
+            let mut json = serde_json::to_value(&ordered).unwrap();
+            json["validators"].as_array_mut().unwrap().reverse();
+            let unordered: Set = serde_json::from_value(json).unwrap();
+            let e = unordered.try_hash().unwrap_err();
+            assert!(e.to_string().contains("positions 0 and 1"), "{e}");
+
+            let mut duplicated = serde_json::to_value(&ordered).unwrap();
+            let first = duplicated["validators"][0].clone();
+            duplicated["validators"].as_array_mut().unwrap().push(first);
+            let duplicated: Set = serde_json::from_value(duplicated).unwrap();
+            assert!(duplicated.ensure_canonical_order().is_err());
+
+            let set =
+                Set::try_from_unordered(vec![v3.clone(), v1.clone(), v2.clone(), v1.clone()], None)
+                    .unwrap();
+            assert_eq!(set, ordered);
+
+            let mut conflicting = v1.clone();
+            conflicting.power = vote::Power::try_from(30_u64).unwrap();
+            let e = Set::try_from_unordered(vec![v1, v2, v3, conflicting], None).unwrap_err();
+            assert!(e.to_string().contains("more than once"), "{e}");
+        }
+
         #[test]
         fn test_validator_set() {
             // test vector generated by Go code
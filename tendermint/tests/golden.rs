@@ -0,0 +1,257 @@
+//! Golden tests for the domain types, driven by the fixtures in
+//! `tests/support/golden`.
+//!
+//! Each fixture is a JSON file of the following form:
+//!
+//! ```json
+//! {
+//!   "description": "What the fixture is about (optional)",
+//!   "type": "block::Header",
+//!   "encoding": "protobuf",
+//!   "payload": "0A04080B1001...",
+//!   "hash": "F30A71F2409F...",
+//!   "sign_bytes": { "chain_id": "test_chain_id", "bytes": "7C0801..." }
+//! }
+//! ```
+//!
+//! where `type` is one of the types registered in [`run`], `encoding` is
+//! either `protobuf` or `json`, and `payload` is the hex encoding of the
+//! encoded value. The value is decoded from the payload and encoded again,
+//! which must give the same payload back (JSON payloads are compared as JSON
+//! values rather than byte by byte). The optional `hash` and `sign_bytes` are
+//! compared with the hash and the sign bytes of the decoded value.
+//!
+//! Adding coverage for a value of a registered type only takes a new fixture.
+//! Fixtures for random values can be generated from the Go implementation with
+//! `tools/golden-gen`.
+#![cfg(feature = "rust-crypto")]
+
+use std::{fs, path::PathBuf, str::FromStr};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use subtle_encoding::hex;
+use tendermint::{block, chain, proposal::Proposal, Hash, Vote};
+use tendermint_proto::{v0_37 as pb, Protobuf};
+
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    #[serde(default)]
+    description: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    encoding: Encoding,
+    #[serde(with = "hex_string")]
+    payload: Vec<u8>,
+    #[serde(default)]
+    hash: Option<Hash>,
+    #[serde(default)]
+    sign_bytes: Option<SignBytes>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Encoding {
+    Protobuf,
+    Json,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignBytes {
+    chain_id: String,
+    #[serde(with = "hex_string")]
+    bytes: Vec<u8>,
+}
+
+mod hex_string {
+    use serde::{de::Error, Deserialize, Deserializer};
+    use subtle_encoding::hex;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        hex::decode_upper(&s)
+            .or_else(|_| hex::decode(&s))
+            .map_err(|e| D::Error::custom(format!("invalid hex payload: {e}")))
+    }
+}
+
+/// The JSON encoding of a domain type.
+struct Json<T> {
+    decode: fn(&[u8]) -> T,
+    encode: fn(&T) -> serde_json::Value,
+}
+
+/// The JSON encoding of a type, through its serde implementations.
+fn serde_json<T: Serialize + DeserializeOwned>() -> Option<Json<T>> {
+    Some(Json {
+        decode: |bytes| serde_json::from_slice(bytes).unwrap(),
+        encode: |value| serde_json::to_value(value).unwrap(),
+    })
+}
+
+/// A domain type that can be checked against golden fixtures.
+trait Golden: Sized {
+    fn decode_protobuf(bytes: &[u8]) -> Self;
+
+    fn encode_protobuf(&self) -> Vec<u8>;
+
+    fn json() -> Option<Json<Self>> {
+        None
+    }
+
+    fn hash(&self) -> Option<Hash> {
+        None
+    }
+
+    fn sign_bytes(&self, _chain_id: chain::Id) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+impl Golden for block::Header {
+    fn decode_protobuf(bytes: &[u8]) -> Self {
+        Protobuf::<pb::types::Header>::decode_vec(bytes).unwrap()
+    }
+
+    fn encode_protobuf(&self) -> Vec<u8> {
+        Protobuf::<pb::types::Header>::encode_vec(self.clone())
+    }
+
+    fn json() -> Option<Json<Self>> {
+        serde_json()
+    }
+
+    fn hash(&self) -> Option<Hash> {
+        Some(block::Header::hash(self))
+    }
+}
+
+impl Golden for block::Id {
+    fn decode_protobuf(bytes: &[u8]) -> Self {
+        Protobuf::<pb::types::BlockId>::decode_vec(bytes).unwrap()
+    }
+
+    fn encode_protobuf(&self) -> Vec<u8> {
+        Protobuf::<pb::types::BlockId>::encode_vec(*self)
+    }
+
+    fn json() -> Option<Json<Self>> {
+        serde_json()
+    }
+}
+
+impl Golden for Vote {
+    fn decode_protobuf(bytes: &[u8]) -> Self {
+        Protobuf::<pb::types::Vote>::decode_vec(bytes).unwrap()
+    }
+
+    fn encode_protobuf(&self) -> Vec<u8> {
+        Protobuf::<pb::types::Vote>::encode_vec(self.clone())
+    }
+
+    fn json() -> Option<Json<Self>> {
+        serde_json()
+    }
+
+    fn sign_bytes(&self, chain_id: chain::Id) -> Option<Vec<u8>> {
+        Some(self.clone().into_signable_vec(chain_id))
+    }
+}
+
+impl Golden for Proposal {
+    fn decode_protobuf(bytes: &[u8]) -> Self {
+        Protobuf::<pb::types::Proposal>::decode_vec(bytes).unwrap()
+    }
+
+    fn encode_protobuf(&self) -> Vec<u8> {
+        Protobuf::<pb::types::Proposal>::encode_vec(self.clone())
+    }
+
+    fn sign_bytes(&self, chain_id: chain::Id) -> Option<Vec<u8>> {
+        Some(self.clone().into_signable_vec(chain_id))
+    }
+}
+
+/// Run the checks of the fixture against the type it is tagged with, naming
+/// the fixture after the given context in the failures.
+fn run(fixture: &Fixture, context: &str) {
+    match fixture.type_name.as_str() {
+        "block::Header" => check::<block::Header>(fixture, context),
+        "block::Id" => check::<block::Id>(fixture, context),
+        "proposal::Proposal" => check::<Proposal>(fixture, context),
+        "vote::Vote" => check::<Vote>(fixture, context),
+        other => panic!("{context}: unregistered fixture type: {other}"),
+    }
+}
+
+fn check<T: Golden>(fixture: &Fixture, context: &str) {
+    let value = match fixture.encoding {
+        Encoding::Protobuf => {
+            let value = T::decode_protobuf(&fixture.payload);
+            assert_eq!(
+                hex::encode_upper(value.encode_protobuf()),
+                hex::encode_upper(&fixture.payload),
+                "{context}: protobuf encoding mismatch"
+            );
+            value
+        },
+        Encoding::Json => {
+            let json = T::json()
+                .unwrap_or_else(|| panic!("{context}: {} has no JSON encoding", fixture.type_name));
+            let value = (json.decode)(&fixture.payload);
+            let expected: serde_json::Value = serde_json::from_slice(&fixture.payload).unwrap();
+            assert_eq!(
+                (json.encode)(&value),
+                expected,
+                "{context}: JSON encoding mismatch"
+            );
+            value
+        },
+    };
+
+    if let Some(expected) = fixture.hash {
+        let hash = value
+            .hash()
+            .unwrap_or_else(|| panic!("{context}: {} has no hash", fixture.type_name));
+        assert_eq!(hash, expected, "{context}: hash mismatch");
+    }
+
+    if let Some(expected) = &fixture.sign_bytes {
+        let chain_id = chain::Id::from_str(&expected.chain_id).unwrap();
+        let sign_bytes = value
+            .sign_bytes(chain_id)
+            .unwrap_or_else(|| panic!("{context}: {} has no sign bytes", fixture.type_name));
+        assert_eq!(
+            hex::encode_upper(sign_bytes),
+            hex::encode_upper(&expected.bytes),
+            "{context}: sign bytes mismatch"
+        );
+    }
+}
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/support/golden");
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some("json".as_ref()))
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn golden_fixtures() {
+    let paths = fixtures();
+    assert!(!paths.is_empty(), "no golden fixtures found");
+
+    for path in paths {
+        let content = fs::read_to_string(&path).unwrap();
+        let fixture: Fixture = serde_json::from_str(&content)
+            .unwrap_or_else(|e| panic!("{}: malformed fixture: {e}", path.display()));
+        let context = format!("{} ({})", path.display(), fixture.description);
+        run(&fixture, &context);
+    }
+}
@@ -1,11 +1,18 @@
 use std::{
-    io::{Read as _, Write as _},
+    io::{self, Read, Write},
     net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     thread,
 };
 
 use rand_core::OsRng;
-use tendermint_p2p::secret_connection::{sort32, Handshake, SecretConnection, Version};
+use tendermint::secret::assert_redacted;
+use tendermint_p2p::secret_connection::{
+    sort32, Handshake, Kdf, SecretConnection, Version, DATA_MAX_SIZE, TAG_SIZE,
+};
 use tendermint_proto::v0_38 as proto;
 use x25519_dalek::PublicKey as EphemeralPublic;
 
@@ -60,6 +67,66 @@ fn test_read_write_single_message() {
     receiver.join().expect("receiver thread has panicked");
 }
 
+#[test]
+fn test_traffic_stats() {
+    // The size of a sealed frame on the wire: the length of the data, the data padded to the
+    // maximum size, and the authentication tag.
+    const SEALED_FRAME_SIZE: usize = 4 + DATA_MAX_SIZE + TAG_SIZE;
+    const MESSAGES: [&[u8]; 3] = [b"The Queen's Gambit", &[7; DATA_MAX_SIZE], b"e4 e5"];
+    let data_size: u64 = MESSAGES.iter().map(|msg| msg.len() as u64).sum();
+
+    let (pipe1, pipe2) = pipe::async_bipipe_buffered();
+    let (wire1, wire2) = (Wire::new(pipe1), Wire::new(pipe2));
+    let (written, read) = (wire2.written.clone(), wire1.read.clone());
+
+    let sender = thread::spawn(move || {
+        let mut conn = new_peer_conn(wire2).expect("handshake to succeed");
+        let (before, written_before) = (conn.stats(), written.load(Ordering::SeqCst));
+
+        for msg in MESSAGES {
+            conn.write_all(msg).expect("expected to write message");
+        }
+
+        let frames = (written.load(Ordering::SeqCst) - written_before) / SEALED_FRAME_SIZE;
+        (before, conn.stats(), frames)
+    });
+
+    let receiver = thread::spawn(move || {
+        let mut conn = new_peer_conn(wire1).expect("handshake to succeed");
+        let (before, read_before) = (conn.stats(), read.load(Ordering::SeqCst));
+
+        for msg in MESSAGES {
+            let mut buf = vec![0; msg.len()];
+            conn.read_exact(&mut buf).expect("expected to read message");
+            assert_eq!(msg, buf);
+        }
+
+        let frames = (read.load(Ordering::SeqCst) - read_before) / SEALED_FRAME_SIZE;
+        (before, conn.stats(), frames)
+    });
+
+    let (sent_before, sent_after, frames_sent) = sender.join().expect("sender thread has panicked");
+    let (received_before, received_after, frames_received) =
+        receiver.join().expect("receiver thread has panicked");
+
+    // Each message fits in a single frame, and only the data is counted.
+    assert_eq!(frames_sent, MESSAGES.len());
+    assert_eq!(frames_received, MESSAGES.len());
+    assert_eq!(sent_after.bytes_sent - sent_before.bytes_sent, data_size);
+    assert_eq!(sent_after.bytes_received, sent_before.bytes_received);
+    assert_eq!(
+        received_after.bytes_received - received_before.bytes_received,
+        data_size
+    );
+    assert_eq!(received_after.bytes_sent, received_before.bytes_sent);
+
+    // The authentication signatures exchanged during the handshake are counted too.
+    assert!(sent_before.bytes_sent > 0);
+    assert_eq!(sent_before.bytes_received, received_before.bytes_sent);
+    assert!(sent_after.streams.is_empty());
+    assert!(received_after.age >= received_before.age);
+}
+
 #[test]
 fn test_evil_peer_shares_invalid_eph_key() {
     let csprng = OsRng {};
@@ -86,6 +153,26 @@ fn test_evil_peer_shares_invalid_auth_sig() {
     assert!(res.is_err());
 }
 
+#[test]
+fn test_debug_output_is_redacted() {
+    let csprng = OsRng {};
+    let local_privkey = ed25519_consensus::SigningKey::new(csprng);
+    let privkey_bytes = local_privkey.to_bytes();
+    let (mut h, _) = Handshake::new(local_privkey, Version::V0_34);
+    assert_redacted(&format!("{h:?}"), &privkey_bytes);
+
+    let h = h
+        .got_key(EphemeralPublic::from(x25519_dalek::X25519_BASEPOINT_BYTES))
+        .unwrap();
+    assert_redacted(&format!("{h:#?}"), &privkey_bytes);
+
+    let kdf = Kdf::derive_secrets_and_challenge(&[0x42; 32], true);
+    let output = format!("{kdf:?}");
+    for secret in [kdf.recv_secret, kdf.send_secret, kdf.challenge] {
+        assert_redacted(&output, &secret);
+    }
+}
+
 #[test]
 fn test_sort() {
     // sanity check
@@ -178,6 +265,43 @@ fn test_split_secret_connection() {
     peer1.join().expect("peer 1's thread to run to completion")
 }
 
+// An I/O handler counting the bytes written to and read from the wire.
+struct Wire<IoHandler> {
+    inner: IoHandler,
+    written: Arc<AtomicUsize>,
+    read: Arc<AtomicUsize>,
+}
+
+impl<IoHandler> Wire<IoHandler> {
+    fn new(inner: IoHandler) -> Self {
+        Self {
+            inner,
+            written: Arc::default(),
+            read: Arc::default(),
+        }
+    }
+}
+
+impl<IoHandler: Read> Read for Wire<IoHandler> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read.fetch_add(n, Ordering::SeqCst);
+        Ok(n)
+    }
+}
+
+impl<IoHandler: Write> Write for Wire<IoHandler> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written.fetch_add(n, Ordering::SeqCst);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 fn new_peer_conn<IoHandler>(
     io_handler: IoHandler,
 ) -> Result<SecretConnection<IoHandler>, tendermint_p2p::error::Error>
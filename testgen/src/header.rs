@@ -36,6 +36,10 @@ pub struct Header {
     pub proposer: Option<usize>,
     #[options(help = "last block id hash (default: Hash::None)")]
     pub last_block_id_hash: Option<Hash>,
+    #[options(help = "hash of the transactions of the block (default: Hash::None)")]
+    pub data_hash: Option<Hash>,
+    #[options(help = "app hash resulting from the previous block (default: empty)")]
+    pub app_hash: Option<Hash>,
 }
 
 // Serialize and deserialize time only up to second precision for integration with MBT.
@@ -74,6 +78,8 @@ impl Header {
             time: None,
             proposer: None,
             last_block_id_hash: None,
+            data_hash: None,
+            app_hash: None,
         }
     }
     set_option!(validators, &[Validator], Some(validators.to_vec()));
@@ -87,6 +93,8 @@ impl Header {
     set_option!(time, Time);
     set_option!(proposer, usize);
     set_option!(last_block_id_hash, Hash);
+    set_option!(data_hash, Hash);
+    set_option!(app_hash, Hash);
 
     pub fn next(&self) -> Self {
         let height = self.height.expect("Missing previous header's height");
@@ -108,6 +116,8 @@ impl Header {
             time: Some((time + Duration::from_secs(1)).unwrap()),
             proposer: self.proposer, // TODO: proposer must be incremented
             last_block_id_hash: Some(last_block_id_hash),
+            data_hash: None,
+            app_hash: None,
         }
     }
 }
@@ -133,6 +143,8 @@ impl Generator<block::Header> for Header {
             time: self.time.or(default.time),
             proposer: self.proposer.or(default.proposer),
             last_block_id_hash: self.last_block_id_hash.or(default.last_block_id_hash),
+            data_hash: self.data_hash.or(default.data_hash),
+            app_hash: self.app_hash.or(default.app_hash),
         }
     }
 
@@ -181,11 +193,15 @@ impl Generator<block::Header> for Header {
             time,
             last_block_id,
             last_commit_hash: None,
-            data_hash: None,
+            data_hash: self.data_hash,
             validators_hash,
             next_validators_hash: next_valset.hash(),
             consensus_hash: validators_hash, // TODO: currently not clear how to produce a valid hash
-            app_hash: AppHash::from_hex_upper("").unwrap(),
+            app_hash: match self.app_hash {
+                Some(hash) => AppHash::try_from(hash.as_bytes().to_vec())
+                    .map_err(|_| SimpleError::new("invalid app hash"))?,
+                None => AppHash::default(),
+            },
             last_results_hash: None,
             evidence_hash: None,
             proposer_address,
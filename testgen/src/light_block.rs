@@ -5,6 +5,7 @@ use tendermint::{
     block::signed_header::SignedHeader, node::Id as PeerId, validator,
     validator::Set as ValidatorSet, Hash, Time,
 };
+use tendermint_light_client_verifier::types::LightBlock as VerifierLightBlock;
 
 use crate::{
     helpers::parse_as, validator::generate_validators, Commit, Generator, Header, Validator,
@@ -17,21 +18,8 @@ use crate::{
 /// Cf. <https://github.com/informalsystems/tendermint-rs/issues/605>
 /// TODO: fix redundant code without introducing cyclic dependency.
 ///
-/// To convert `TmLightBlock` to the Domain type `LightBlock` used in light-client crate
-/// You'll need to implement the `From` trait like below:
-///
-/// ```rust,ignore
-/// impl From<TmLightBlock> for LightBlock {
-///     fn from(tm_lb: TmLightBlock) -> Self {
-///         Self {
-///             signed_header: tm_lb.signed_header,
-///             validators: tm_lb.validators,
-///             next_validators: tm_lb.next_validators,
-///             provider: tm_lb.provider,
-///         }
-///     }
-/// }
-/// ```
+/// A `TmLightBlock` converts into the domain type `LightBlock` used by the
+/// light client crates.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TmLightBlock {
     /// Header and commit of this block
@@ -44,6 +32,17 @@ pub struct TmLightBlock {
     pub provider: PeerId,
 }
 
+impl From<TmLightBlock> for VerifierLightBlock {
+    fn from(tm_lb: TmLightBlock) -> Self {
+        Self {
+            signed_header: tm_lb.signed_header,
+            validators: tm_lb.validators,
+            next_validators: tm_lb.next_validators,
+            provider: tm_lb.provider,
+        }
+    }
+}
+
 /// We use this data structure as a simplistic representation of LightClient's LightBlock
 #[derive(Debug, Options, Serialize, Deserialize, Clone)]
 pub struct LightBlock {
@@ -0,0 +1,7 @@
+module github.com/informalsystems/tendermint-rs/tools/golden-gen
+
+go 1.20
+
+// The implementation the fixtures are generated with, which should match the
+// protocol version the `tendermint` crate is tested against.
+require github.com/cometbft/cometbft v0.37.2
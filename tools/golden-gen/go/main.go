@@ -0,0 +1,229 @@
+// Command golden-gen emits golden fixtures for randomized domain objects, with
+// their encoding, hash and sign bytes as computed by the Go implementation.
+//
+// Fixtures are written to standard output as JSON, one per line, in the format
+// of the fixtures in tendermint/tests/support/golden.
+package main
+
+import (
+	"encoding/hex"
+	"encoding/json"
+	"flag"
+	"fmt"
+	"math/rand"
+	"os"
+	"runtime/debug"
+	"strings"
+	"time"
+
+	cmtproto "github.com/cometbft/cometbft/proto/tendermint/types"
+	cmtversion "github.com/cometbft/cometbft/proto/tendermint/version"
+	"github.com/cometbft/cometbft/types"
+	"github.com/cometbft/cometbft/version"
+)
+
+type fixture struct {
+	Description string     `json:"description"`
+	Type        string     `json:"type"`
+	Encoding    string     `json:"encoding"`
+	Payload     string     `json:"payload"`
+	Hash        string     `json:"hash,omitempty"`
+	SignBytes   *signBytes `json:"sign_bytes,omitempty"`
+}
+
+type signBytes struct {
+	ChainID string `json:"chain_id"`
+	Bytes   string `json:"bytes"`
+}
+
+// generator builds a fixture for a random value of a type.
+type generator func(r *rand.Rand, chainID string) (fixture, error)
+
+var generators = map[string]generator{
+	"block_header": blockHeader,
+	"block_id":     blockID,
+	"proposal":     proposal,
+	"vote":         vote,
+}
+
+func main() {
+	kind := flag.String("type", "", "type of the values to generate ("+kinds()+")")
+	seed := flag.Int64("seed", 0, "seed of the random values")
+	count := flag.Int("count", 1, "number of values to generate")
+	chainID := flag.String("chain-id", "test_chain_id", "chain ID for the sign bytes")
+	flag.Parse()
+
+	generate, ok := generators[*kind]
+	if !ok {
+		fatalf("unknown type %q, expected one of %s", *kind, kinds())
+	}
+
+	r := rand.New(rand.NewSource(*seed))
+	out := json.NewEncoder(os.Stdout)
+	for i := 0; i < *count; i++ {
+		f, err := generate(r, *chainID)
+		if err != nil {
+			fatalf("generating %s: %v", *kind, err)
+		}
+		f.Description = fmt.Sprintf(
+			"Random %s #%d from seed %d, generated with CometBFT %s",
+			*kind, i, *seed, implementation(),
+		)
+		if err := out.Encode(f); err != nil {
+			fatalf("writing fixture: %v", err)
+		}
+	}
+}
+
+func blockHeader(r *rand.Rand, chainID string) (fixture, error) {
+	lastBlockID := randBlockID(r)
+	header := types.Header{
+		Version:            cmtversion.Consensus{Block: version.BlockProtocol, App: r.Uint64()},
+		ChainID:            chainID,
+		Height:             r.Int63n(1<<40) + 2,
+		Time:               randTime(r),
+		LastBlockID:        lastBlockID,
+		LastCommitHash:     randBytes(r, 32),
+		DataHash:           randBytes(r, 32),
+		ValidatorsHash:     randBytes(r, 32),
+		NextValidatorsHash: randBytes(r, 32),
+		ConsensusHash:      randBytes(r, 32),
+		AppHash:            randBytes(r, 32),
+		LastResultsHash:    randBytes(r, 32),
+		EvidenceHash:       randBytes(r, 32),
+		ProposerAddress:    randBytes(r, 20),
+	}
+	payload, err := header.ToProto().Marshal()
+	if err != nil {
+		return fixture{}, err
+	}
+	return fixture{
+		Type:     "block::Header",
+		Encoding: "protobuf",
+		Payload:  encodeHex(payload),
+		Hash:     encodeHex(header.Hash()),
+	}, nil
+}
+
+func blockID(r *rand.Rand, _ string) (fixture, error) {
+	id := randBlockID(r).ToProto()
+	payload, err := id.Marshal()
+	if err != nil {
+		return fixture{}, err
+	}
+	return fixture{
+		Type:     "block::Id",
+		Encoding: "protobuf",
+		Payload:  encodeHex(payload),
+	}, nil
+}
+
+func proposal(r *rand.Rand, chainID string) (fixture, error) {
+	round := r.Int31n(1 << 16)
+	polRound := int32(-1)
+	if round > 0 && r.Intn(2) == 0 {
+		polRound = r.Int31n(round)
+	}
+	p := types.Proposal{
+		Type:      cmtproto.ProposalType,
+		Height:    r.Int63n(1<<40) + 1,
+		Round:     round,
+		POLRound:  polRound,
+		BlockID:   randBlockID(r),
+		Timestamp: randTime(r),
+		Signature: randBytes(r, 64),
+	}
+	pb := p.ToProto()
+	payload, err := pb.Marshal()
+	if err != nil {
+		return fixture{}, err
+	}
+	return fixture{
+		Type:     "proposal::Proposal",
+		Encoding: "protobuf",
+		Payload:  encodeHex(payload),
+		SignBytes: &signBytes{
+			ChainID: chainID,
+			Bytes:   encodeHex(types.ProposalSignBytes(chainID, pb)),
+		},
+	}, nil
+}
+
+func vote(r *rand.Rand, chainID string) (fixture, error) {
+	voteType := cmtproto.PrevoteType
+	if r.Intn(2) == 0 {
+		voteType = cmtproto.PrecommitType
+	}
+	v := types.Vote{
+		Type:             voteType,
+		Height:           r.Int63n(1<<40) + 1,
+		Round:            r.Int31n(1 << 16),
+		BlockID:          randBlockID(r),
+		Timestamp:        randTime(r),
+		ValidatorAddress: randBytes(r, 20),
+		ValidatorIndex:   r.Int31n(1 << 10),
+		Signature:        randBytes(r, 64),
+	}
+	pb := v.ToProto()
+	payload, err := pb.Marshal()
+	if err != nil {
+		return fixture{}, err
+	}
+	return fixture{
+		Type:     "vote::Vote",
+		Encoding: "protobuf",
+		Payload:  encodeHex(payload),
+		SignBytes: &signBytes{
+			ChainID: chainID,
+			Bytes:   encodeHex(types.VoteSignBytes(chainID, pb)),
+		},
+	}, nil
+}
+
+func randBlockID(r *rand.Rand) types.BlockID {
+	return types.BlockID{
+		Hash: randBytes(r, 32),
+		PartSetHeader: types.PartSetHeader{
+			Total: uint32(r.Int31n(1<<16)) + 1,
+			Hash:  randBytes(r, 32),
+		},
+	}
+}
+
+// randTime returns a time within the range of the timestamps supported by
+// both implementations.
+func randTime(r *rand.Rand) time.Time {
+	return time.Unix(r.Int63n(1<<34), r.Int63n(int64(time.Second))).UTC()
+}
+
+func randBytes(r *rand.Rand, n int) []byte {
+	b := make([]byte, n)
+	r.Read(b)
+	return b
+}
+
+func encodeHex(b []byte) string {
+	return strings.ToUpper(hex.EncodeToString(b))
+}
+
+// implementation returns the version of the CometBFT module the fixtures are
+// generated with.
+func implementation() string {
+	if info, ok := debug.ReadBuildInfo(); ok {
+		for _, dep := range info.Deps {
+			if dep.Path == "github.com/cometbft/cometbft" {
+				return dep.Version
+			}
+		}
+	}
+	return "(unknown version)"
+}
+
+func kinds() string {
+	return "block_header, block_id, proposal or vote"
+}
+
+func fatalf(format string, args ...interface{}) {
+	fmt.Fprintf(os.Stderr, "golden-gen: "+format+"\n", args...)
+	os.Exit(1)
+}
@@ -0,0 +1,22 @@
+//! Errors relating to the generation of fixtures.
+
+use std::{io, path::PathBuf, process::ExitStatus};
+
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("failed to run the Go generator (is Go installed?): {0}")]
+    GoUnavailable(io::Error),
+
+    #[error("the Go generator failed with {0}")]
+    GoFailed(ExitStatus),
+
+    #[error("malformed fixture from the Go generator: {0}")]
+    MalformedFixture(String),
+
+    #[error("failed to write {0}: {1}")]
+    Write(PathBuf, io::Error),
+}
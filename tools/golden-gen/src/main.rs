@@ -0,0 +1,141 @@
+mod error;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use structopt::StructOpt;
+
+use crate::error::{Error, Result};
+
+/// The types of the values fixtures can be generated for, as named by the Go
+/// generator.
+const TYPES: &[&str] = &["block_header", "block_id", "proposal", "vote"];
+
+/// Generates the golden fixtures of the `tendermint` crate, which are checked
+/// by its `golden` tests, for randomized domain objects.
+///
+/// The values are generated, encoded, hashed and signed by the Go
+/// implementation, through the Go module in the `go` folder which pins the
+/// version of CometBFT the fixtures are generated with.
+#[derive(Debug, StructOpt)]
+struct Opts {
+    /// The types of the values to generate fixtures for, among `block_header`,
+    /// `block_id`, `proposal` and `vote`. Defaults to all of them.
+    pub types: Vec<String>,
+
+    /// The number of fixtures to generate per type.
+    #[structopt(default_value = "4", short, long)]
+    pub count: u32,
+
+    /// The seed of the random values, which defaults to one derived from the
+    /// current time. Generating with the same seed gives the same fixtures.
+    #[structopt(short, long)]
+    pub seed: Option<i64>,
+
+    /// The chain ID the sign bytes are computed for.
+    #[structopt(default_value = "test_chain_id", long)]
+    pub chain_id: String,
+
+    /// The path of the Go module generating the values.
+    #[structopt(long, parse(from_os_str))]
+    pub go_module: Option<PathBuf>,
+
+    /// The output path in which to store the fixtures. Defaults to the golden
+    /// fixtures folder of the `tendermint` crate.
+    #[structopt(short, long, parse(from_os_str))]
+    pub output: Option<PathBuf>,
+}
+
+fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let opts = Opts::from_args();
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let go_module = opts.go_module.unwrap_or_else(|| manifest_dir.join("go"));
+    let output = opts.output.unwrap_or_else(|| {
+        manifest_dir
+            .join("..")
+            .join("..")
+            .join("tendermint")
+            .join("tests")
+            .join("support")
+            .join("golden")
+    });
+    let seed = opts.seed.unwrap_or_else(time_seed);
+
+    let types = if opts.types.is_empty() {
+        TYPES.iter().map(|t| t.to_string()).collect()
+    } else {
+        opts.types
+    };
+    for type_name in types {
+        if !TYPES.contains(&type_name.as_str()) {
+            return Err(format!("unknown type {type_name}, expected one of {TYPES:?}").into());
+        }
+
+        let fixtures = generate(&go_module, &type_name, seed, opts.count, &opts.chain_id)?;
+        for (index, fixture) in fixtures.iter().enumerate() {
+            let path = output.join(format!("{type_name}_{seed}_{index}.json"));
+            write(&path, fixture)?;
+            println!("{}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the Go generator for the given type, returning the fixtures.
+fn generate(
+    go_module: &Path,
+    type_name: &str,
+    seed: i64,
+    count: u32,
+    chain_id: &str,
+) -> Result<Vec<serde_json::Value>> {
+    let output = Command::new("go")
+        .args(["run", "."])
+        .args(["-type", type_name])
+        .args(["-seed", &seed.to_string()])
+        .args(["-count", &count.to_string()])
+        .args(["-chain-id", chain_id])
+        .current_dir(go_module)
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(Error::GoUnavailable)?;
+    if !output.status.success() {
+        return Err(Error::GoFailed(output.status));
+    }
+
+    let stdout =
+        String::from_utf8(output.stdout).map_err(|e| Error::MalformedFixture(e.to_string()))?;
+    stdout
+        .lines()
+        .map(|line| {
+            let fixture: serde_json::Value =
+                serde_json::from_str(line).map_err(|e| Error::MalformedFixture(e.to_string()))?;
+            match fixture.get("type") {
+                Some(serde_json::Value::String(_)) => Ok(fixture),
+                _ => Err(Error::MalformedFixture(format!("untyped fixture: {line}"))),
+            }
+        })
+        .collect()
+}
+
+fn write(path: &Path, fixture: &serde_json::Value) -> Result<()> {
+    let mut content = serde_json::to_string_pretty(fixture)
+        .map_err(|e| Error::MalformedFixture(e.to_string()))?;
+    content.push('\n');
+    fs::write(path, content).map_err(|e| Error::Write(path.to_path_buf(), e))
+}
+
+/// A seed for when none is given, which is printed so that the fixtures can
+/// be generated again.
+fn time_seed() -> i64 {
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    println!("Generating fixtures with seed {seed}");
+    seed
+}
@@ -44,6 +44,8 @@ const QUOTED_WITH_DEFAULT: &str = r#"#[serde(with = "crate::serializers::from_st
 const DEFAULT: &str = r#"#[serde(default)]"#;
 const HEXSTRING: &str = r#"#[serde(with = "crate::serializers::bytes::hexstring")]"#;
 const BASE64STRING: &str = r#"#[serde(with = "crate::serializers::bytes::base64string")]"#;
+const BASE64STRING_WITH_DEFAULT: &str =
+    r#"#[serde(with = "crate::serializers::bytes::base64string", default)]"#;
 const VEC_BASE64STRING: &str = r#"#[serde(with = "crate::serializers::bytes::vec_base64string")]"#;
 const OPTIONAL: &str = r#"#[serde(with = "crate::serializers::optional")]"#;
 const BYTES_SKIP_IF_EMPTY: &str = r#"#[serde(skip_serializing_if = "bytes::Bytes::is_empty")]"#;
@@ -190,6 +192,11 @@ pub static CUSTOM_FIELD_ATTRIBUTES: &[(&str, &str)] = &[
     (".tendermint.types.Vote.validator_address", HEXSTRING),
     (".tendermint.types.Vote.signature", BASE64STRING),
     (".tendermint.types.Vote.timestamp", OPTIONAL),
+    (".tendermint.types.Vote.extension", BASE64STRING_WITH_DEFAULT),
+    (
+        ".tendermint.types.Vote.extension_signature",
+        BASE64STRING_WITH_DEFAULT,
+    ),
     (".tendermint.types.Validator.address", HEXSTRING),
     (
         ".tendermint.types.Validator.voting_power",